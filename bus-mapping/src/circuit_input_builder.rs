@@ -2,6 +2,7 @@
 //! types from geth / web3 and outputs the circuit inputs.
 
 mod access;
+mod access_list;
 mod block;
 mod builder_client;
 mod call;
@@ -11,45 +12,59 @@ mod execution;
 mod input_state_ref;
 #[cfg(feature = "scroll")]
 mod l2;
+mod partial_state;
+mod progress;
+mod receipt;
+mod self_check;
 #[cfg(all(feature = "tracer-tests", feature = "enable-memory", test))]
 mod tracer_tests;
 mod transaction;
+mod tx_chunk;
 
 pub use self::block::Block;
 use crate::{
-    error::Error,
+    error::{CapacityResource, Error},
     evm::opcodes::{gen_associated_ops, gen_associated_steps},
     operation::{self, CallContextField, Operation, RWCounter, StartOp, StorageOp, RW},
+    precompile::MODEXP_SIZE_LIMIT,
 };
 pub use access::{Access, AccessSet, AccessValue, CodeSource};
+pub use access_list::{AccessListKey, AccessListWrite};
 pub use block::{BlockContext, Blocks};
 pub use builder_client::{build_state_code_db, BuilderClient};
 pub use call::{Call, CallContext, CallKind};
 use core::fmt::Debug;
 use eth_types::{
     self,
-    evm_types::{GasCost, OpcodeId},
+    evm_types::OpcodeId,
     sign_types::get_dummy_tx,
-    state_db::{CodeDB, StateDB},
-    EthBlock, GethExecTrace, Word, H256,
+    state_db::{CodeDB, StateDB, WarmState},
+    trace_file::GethExecTraceReader,
+    EthBlock, GethExecStep, GethExecTrace, ToWord, Word, WordExt, H256,
 };
 use ethers_core::utils::keccak256;
 pub use execution::{
-    BigModExp, CopyAccessList, CopyBytes, CopyDataType, CopyEvent, CopyEventStepsBuilder, CopyStep,
-    EcAddOp, EcMulOp, EcPairingOp, EcPairingPair, ExecState, ExecStep, ExpEvent, ExpStep,
-    NumberOrHash, PrecompileEvent, PrecompileEvents, N_BYTES_PER_PAIR, N_PAIRING_PER_OP, SHA256,
+    BigModExp, Blake2fOp, CopyAccessList, CopyBytes, CopyDataType, CopyEvent, CopyEventOrigin,
+    CopyEventStepsBuilder, CopyStep, EcAddOp, EcMulOp, EcPairingOp, EcPairingPair, ExecState,
+    ExecStep, ExpEvent, ExpStep, NumberOrHash, PrecompileEvent, PrecompileEvents, PrecompileUsage,
+    N_BYTES_PER_PAIR, N_PAIRING_PER_OP, SHA256,
 };
 pub use input_state_ref::CircuitInputStateRef;
 use itertools::Itertools;
 #[cfg(feature = "scroll")]
 use mpt_zktrie::state::ZktrieState;
+pub use progress::{BuildMonitor, BuildProgress, CancellationToken};
+pub use receipt::Receipt;
+use self_check::TxSelfCheck;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 pub use transaction::{
     Transaction, TransactionContext, TxL1Fee, TX_L1_COMMIT_EXTRA_COST, TX_L1_FEE_PRECISION,
 };
+pub use tx_chunk::{ChunkContext, TxChunk};
 
 /// Setup parameters for ECC-related precompile calls.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct PrecompileEcParams {
     /// Maximum number of EcAdd ops supported in one block.
     pub ec_add: usize,
@@ -57,6 +72,14 @@ pub struct PrecompileEcParams {
     pub ec_mul: usize,
     /// Maximum number of EcPairing ops supported in one block.
     pub ec_pairing: usize,
+    /// Maximum number of (G1, G2) pairs of an EcPairing op, i.e. of an
+    /// ecPairing call. At least 2.
+    #[serde(default = "default_ec_pairing_pairs")]
+    pub ec_pairing_pairs: usize,
+}
+
+fn default_ec_pairing_pairs() -> usize {
+    N_PAIRING_PER_OP
 }
 
 impl Default for PrecompileEcParams {
@@ -65,12 +88,13 @@ impl Default for PrecompileEcParams {
             ec_add: 50,
             ec_mul: 50,
             ec_pairing: 2,
+            ec_pairing_pairs: default_ec_pairing_pairs(),
         }
     }
 }
 
 /// Circuit Setup Parameters
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct CircuitsParams {
     /// Maximum number of rw operations in the state circuit (RwTable length /
     /// number of rows). This must be at least the number of rw operations
@@ -110,11 +134,19 @@ pub struct CircuitsParams {
     pub max_poseidon_rows: usize,
     /// Max number of ECC-related ops supported in the ECC circuit.
     pub max_ec_ops: PrecompileEcParams,
+    /// Max byte length of an operand of a MODEXP call proven by the modexp circuit, bounded by
+    /// [`MODEXP_BYTES_BOUND`](crate::precompile::MODEXP_BYTES_BOUND). The rows taken by each call
+    /// grow with it.
+    pub max_modexp_bytes: usize,
     /// This number indicate what 100% usage means, for example if we can support up to 2
     /// ecPairing inside circuit, and max_vertical_circuit_rows is set to 1_000_000,
     /// then if there is 1 ecPairing in the input, we will return 500_000 as the "row usage"
     /// for the ec circuit.
     pub max_vertical_circuit_rows: usize,
+    /// Max number of rounds of the compressions of the blake2f precompile
+    /// calls of a block, unchecked when 0. The compressions are not proven by
+    /// any circuit yet.
+    pub max_blake2f_rounds: usize,
 }
 
 impl Default for CircuitsParams {
@@ -137,6 +169,8 @@ impl Default for CircuitsParams {
             max_vertical_circuit_rows: 0,
             max_rlp_rows: 1000,
             max_ec_ops: PrecompileEcParams::default(),
+            max_modexp_bytes: MODEXP_SIZE_LIMIT,
+            max_blake2f_rounds: 0,
         }
     }
 }
@@ -172,6 +206,15 @@ pub struct CircuitInputBuilder {
     #[cfg(feature = "scroll")]
     /// Initial Zktrie Status for a incremental updating
     pub mpt_init_state: Option<ZktrieState>,
+    /// Maximum depth of the calls accepted in a tx trace, see
+    /// [`eth_types::MAX_CALL_DEPTH`].
+    pub max_call_depth: usize,
+    /// Accounts and slots of a partial state witness, see
+    /// [`CircuitInputBuilder::new_from_partial_state`]. Handling a block fails
+    /// if it accesses state outside of it.
+    pub partial_state: Option<AccessSet>,
+    /// Progress reports and cancellation of the witness generation.
+    pub monitor: BuildMonitor,
 }
 
 impl<'a> CircuitInputBuilder {
@@ -185,6 +228,9 @@ impl<'a> CircuitInputBuilder {
             block_ctx: BlockContext::new(),
             #[cfg(feature = "scroll")]
             mpt_init_state: Default::default(),
+            max_call_depth: eth_types::MAX_CALL_DEPTH,
+            partial_state: None,
+            monitor: BuildMonitor::default(),
         }
     }
     /// Create a new CircuitInputBuilder from the given `eth_block` and
@@ -202,6 +248,13 @@ impl<'a> CircuitInputBuilder {
         Self::new(sdb, code_db, &Blocks::init(chain_id, circuits_params))
     }
 
+    /// Snapshot the current state and codes, to preload them as the pre-state
+    /// of a subsequent witness build (see
+    /// `BlockData::new_from_geth_data_with_warm_state`).
+    pub fn warm_state(&self) -> WarmState {
+        self.sdb.warm_state(&self.code_db)
+    }
+
     /// Obtain a mutable reference to the state that the `CircuitInputBuilder`
     /// maintains, contextualized to a particular transaction and a
     /// particular execution step in that transaction.
@@ -242,6 +295,7 @@ impl<'a> CircuitInputBuilder {
         Transaction::new(
             call_id,
             self.block.chain_id(),
+            &self.block.tx_type_config,
             &self.sdb,
             &mut self.code_db,
             eth_tx,
@@ -288,6 +342,76 @@ impl<'a> CircuitInputBuilder {
         self.finalize_building()?;
         Ok(())
     }
+    /// Handle the consecutive blocks of a chunk by handling each transaction
+    /// to generate all the associated operations, `geth_traces[i]` being the
+    /// traces of the txs of `eth_blocks[i]`.
+    ///
+    /// The blocks which are not in [`Self::block`] yet are added to it, with
+    /// their history hashes extended from the ones of the previous block, so
+    /// that the first block's must be known beforehand (see
+    /// [`Blocks::new_with_l1_queue_index`]) for BLOCKHASH to look past the
+    /// chunk. The rw counter and the cumulative gas used run over the whole
+    /// chunk.
+    pub fn handle_blocks(
+        &mut self,
+        eth_blocks: &[EthBlock],
+        geth_traces: &[Vec<GethExecTrace>],
+    ) -> Result<(), Error> {
+        if eth_blocks.len() != geth_traces.len() {
+            return Err(Error::InvalidGethExecTrace(
+                "number of blocks and of block traces differ",
+            ));
+        }
+        for (eth_block, geth_traces) in eth_blocks.iter().zip(geth_traces) {
+            self.add_block(eth_block)?;
+            self.handle_block_inner(eth_block, geth_traces)?;
+        }
+        self.finalize_building()
+    }
+
+    /// Add `eth_block` to the blocks of the chunk, unless already added, with
+    /// the history hashes of the previous block followed by its hash.
+    fn add_block(&mut self, eth_block: &EthBlock) -> Result<(), Error> {
+        let number = eth_block
+            .number
+            .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?
+            .as_u64();
+        if self.block.blocks.contains_key(&number) {
+            return Ok(());
+        }
+        let max_inner_blocks = self.block.circuits_params.max_inner_blocks;
+        if self.block.blocks.len() >= max_inner_blocks {
+            log::error!("inner block num overflow, MAX_INNER_BLOCKS limit {max_inner_blocks}");
+            return Err(Error::CapacityExceeded {
+                resource: CapacityResource::InnerBlocks,
+                used: self.block.blocks.len() + 1,
+                limit: max_inner_blocks,
+            });
+        }
+        let history_hashes = match self.block.blocks.last_key_value() {
+            Some((prev_number, prev_block)) => {
+                if prev_number + 1 != number {
+                    return Err(Error::InvalidGethExecTrace(
+                        "blocks of a chunk must be consecutive",
+                    ));
+                }
+                let mut history_hashes = prev_block.history_hashes.clone();
+                history_hashes.push(eth_block.parent_hash.to_word());
+                let len = history_hashes.len();
+                history_hashes.split_off(len.saturating_sub(256))
+            }
+            None => vec![eth_block.parent_hash.to_word()],
+        };
+        let block = Block::new_with_l1_queue_index(
+            self.block.chain_id,
+            self.block.start_l1_queue_index,
+            history_hashes,
+            eth_block,
+        )?;
+        self.block.add_block(block);
+        Ok(())
+    }
+
     /// Handle a block by handling each transaction to generate all the
     /// associated operations.
     pub fn handle_block_inner(
@@ -301,6 +425,25 @@ impl<'a> CircuitInputBuilder {
             eth_block.number,
             eth_block.transactions.len()
         );
+        // reject unsupported tx types upfront, before any tx is handled
+        for (index, tx) in eth_block.transactions.iter().enumerate() {
+            let support = self.block.tx_type_config.support_of_tx(tx);
+            if !support.supported {
+                log::error!(
+                    "unsupported tx type {} ({}) of {}th tx {:?}",
+                    support.type_id,
+                    support.name,
+                    index,
+                    tx.hash
+                );
+                return Err(Error::UnsupportedTxType {
+                    index,
+                    tx_type: support.type_id,
+                    required_feature: support.required_feature,
+                });
+            }
+        }
+        self.check_partial_state(eth_block, geth_traces)?;
         for (tx_index, tx) in eth_block.transactions.iter().enumerate() {
             let chunk_tx_idx = self.block.txs.len();
             if self.block.txs.len() >= self.block.circuits_params.max_txs {
@@ -311,7 +454,30 @@ impl<'a> CircuitInputBuilder {
                     tx.transaction_index.unwrap_or_default(),
                     tx.hash
                 );
-                return Err(Error::InternalError("tx num overflow"));
+                return Err(Error::CapacityExceeded {
+                    resource: CapacityResource::Txs,
+                    used: chunk_tx_idx + 1,
+                    limit: self.block.circuits_params.max_txs,
+                });
+            }
+            // EIP-3607: txs sent by a contract are invalid, l1 msgs are exempt as they
+            // may be sent by L1 contracts
+            let sender_code_hash = self.sdb.get_account(&tx.from).1.code_hash_read();
+            if !self.block.tx_type_config.tx_type(tx).is_l1_msg()
+                && !sender_code_hash.is_zero()
+                && sender_code_hash != CodeDB::empty_code_hash()
+            {
+                if cfg!(feature = "scroll") {
+                    // such txs are dropped by the sequencer, skip it like consensus does
+                    log::warn!(
+                        "skip {}th tx {:?}: sender {:?} has code",
+                        tx_index,
+                        tx.hash,
+                        tx.from
+                    );
+                    continue;
+                }
+                return Err(Error::SenderHasCode { index: tx_index });
             }
             let geth_trace = &geth_traces[tx_index];
             log::info!(
@@ -323,10 +489,7 @@ impl<'a> CircuitInputBuilder {
                 tx.to,
                 tx.input.len(),
             );
-            let mut tx = tx.clone();
-            // Chunk can contain multi blocks, so transaction_index needs to be updated
-            tx.transaction_index = Some(self.block.txs.len().into());
-            self.handle_tx(&tx, geth_trace)?;
+            self.handle_tx(tx, geth_trace)?;
             log::debug!(
                 "after handle {}th tx: rwc {:?}, total gas {:?}",
                 chunk_tx_idx,
@@ -414,6 +577,18 @@ impl<'a> CircuitInputBuilder {
             );
         }
         log::debug!("memory_word num: {}", self.block.container.memory.len());
+        log::debug!(
+            "redundant memory_word read num: {}",
+            self.block
+                .txs
+                .iter()
+                .flat_map(|t| t.steps())
+                .map(|step| self
+                    .block
+                    .container
+                    .redundant_memory_reads(&step.bus_mapping_instance))
+                .sum::<usize>()
+        );
         log::debug!("stack num: {}", self.block.container.stack.len());
         log::debug!("storage num: {}", self.block.container.storage.len());
         log::debug!(
@@ -516,7 +691,11 @@ impl<'a> CircuitInputBuilder {
                     max_rws
                 );
                 if cfg!(feature = "strict-ccc") {
-                    return Err(Error::InternalError("rws not enough"));
+                    return Err(Error::CapacityExceeded {
+                        resource: CapacityResource::Rws,
+                        used: total_rws + 1,
+                        limit: max_rws,
+                    });
                 }
             };
         }
@@ -545,7 +724,48 @@ impl<'a> CircuitInputBuilder {
         eth_tx: &eth_types::Transaction,
         geth_trace: &GethExecTrace,
     ) -> Result<(), Error> {
-        let mut tx = self.new_tx(eth_tx, !geth_trace.failed)?;
+        let mut stream = self.begin_tx(eth_tx, geth_trace)?;
+        #[cfg(feature = "enable-memory")]
+        stream.set_memory_captured(
+            !geth_trace
+                .struct_logs
+                .iter()
+                .all(|step| step.memory.is_empty()),
+        );
+        for index in 0..geth_trace.struct_logs.len() {
+            self.handle_geth_step(&mut stream, &geth_trace.struct_logs[index..])?;
+        }
+        self.end_tx(stream)
+    }
+
+    /// Start handling a transaction whose geth steps are fed one by one with
+    /// [`Self::handle_tx_step`], so that the whole trace never has to be held
+    /// in memory, then finished with [`Self::end_tx`].
+    ///
+    /// `geth_trace` is the header of the trace: its `struct_logs` are ignored
+    /// and may be left empty, but its `call_trace`, `gas`, `failed` and
+    /// `l1_fee` are required up front.
+    pub fn begin_tx(
+        &mut self,
+        eth_tx: &eth_types::Transaction,
+        geth_trace: &GethExecTrace,
+    ) -> Result<StreamingTx, Error> {
+        let mut eth_tx = eth_tx.clone();
+        // Chunk can contain multi blocks, so transaction_index needs to be updated
+        eth_tx.transaction_index = Some(self.block.txs.len().into());
+
+        let mut tx = self.new_tx(&eth_tx, !geth_trace.failed)?;
+        let blob_base_fee = self.block.blocks[&tx.block_num].blob_base_fee;
+        tx.check_validity(
+            &self.sdb,
+            &self.block.gas_schedule,
+            blob_base_fee,
+            self.block.check_tx_nonce,
+        )
+        .map_err(|reason| Error::InvalidTx {
+            index: self.block.txs.len(),
+            reason,
+        })?;
 
         // Sanity check for transaction L1 fee.
         let tx_l1_fee = if tx.tx_type.is_l1_msg() {
@@ -561,7 +781,7 @@ impl<'a> CircuitInputBuilder {
             );
         }
 
-        let mut tx_ctx = TransactionContext::new(eth_tx, geth_trace)?;
+        let mut tx_ctx = TransactionContext::new(&eth_tx, geth_trace, self.max_call_depth)?;
         let mut debug_tx = tx.clone();
         debug_tx.input.clear();
         debug_tx.rlp_bytes.clear();
@@ -573,116 +793,53 @@ impl<'a> CircuitInputBuilder {
             &mut self.state_ref(&mut tx, &mut tx_ctx),
             ExecState::BeginTx,
         )?;
+        let begin_tx_gas_cost = begin_tx_steps.iter().map(|st| st.gas_cost.0).sum();
+        tx.steps_mut().extend(begin_tx_steps);
 
-        // check gas cost
-        {
-            let steps_gas_cost: u64 = begin_tx_steps.iter().map(|st| st.gas_cost.0).sum();
-            let real_gas_cost = if geth_trace.struct_logs.is_empty() {
-                GasCost(geth_trace.gas.0)
-            } else {
-                GasCost(tx.gas - geth_trace.struct_logs[0].gas.0)
-            };
-            debug_assert_eq!(
-                steps_gas_cost,
-                real_gas_cost.as_u64(),
-                "begin step cost {:?}, next step cost {:?}",
-                begin_tx_steps[0].gas_cost,
-                begin_tx_steps.get(1).map(|st| st.gas_cost),
-            );
-        }
+        Ok(StreamingTx {
+            tx,
+            tx_ctx,
+            trace_gas: geth_trace.gas.0,
+            begin_tx_gas_cost,
+            steps: 0,
+            pending: Vec::with_capacity(2),
+            self_check: self.monitor.self_check().then(TxSelfCheck::new),
+        })
+    }
 
-        tx.steps_mut().extend(begin_tx_steps);
+    /// Feed the next geth step of a transaction started with
+    /// [`Self::begin_tx`]. A step is handled once the following one is fed
+    /// (or the transaction ended), as its operations may depend on it.
+    pub fn handle_tx_step(
+        &mut self,
+        stream: &mut StreamingTx,
+        geth_step: GethExecStep,
+    ) -> Result<(), Error> {
+        stream.pending.push(geth_step);
+        if stream.pending.len() == 2 {
+            let pending = std::mem::take(&mut stream.pending);
+            self.handle_geth_step(stream, &pending)?;
+            stream.pending = pending;
+            stream.pending.remove(0);
+        }
+        Ok(())
+    }
 
-        for (index, geth_step) in geth_trace.struct_logs.iter().enumerate() {
-            let tx_gas = tx.gas;
-            let mut state_ref = self.state_ref(&mut tx, &mut tx_ctx);
-            log::trace!(
-                "handle {}th tx depth {} {}th/{} opcode {:?} pc: {} gas_left: {} gas_used: {} rwc: {} call_id: {} msize: {} refund: {} args: {}",
-                eth_tx.transaction_index.unwrap_or_default(),
-                geth_step.depth,
-                index,
-                geth_trace.struct_logs.len(),
-                geth_step.op,
-                geth_step.pc.0,
-                geth_step.gas.0,
-                tx_gas - geth_step.gas.0,
-                state_ref.block_ctx.rwc.0,
-                state_ref.call().map(|c| c.call_id).unwrap_or(0),
-                state_ref.call_ctx()?.memory.len(),
-                geth_step.refund.0,
-                {
-                    let stack = &state_ref.call_ctx()?.stack;
-                    if geth_step.op.is_push_with_data() {
-                        #[cfg(feature = "enable-stack")]
-                        {
-                            format!("{:?}", geth_trace.struct_logs.get(index + 1).map(|step| step.stack.last()))
-                        }
-                        #[cfg(not(feature = "enable-stack"))]
-                        {
-                            "N/A".to_string()
-                        }
-                    } else if geth_step.op.is_call_without_value() {
-                        format!(
-                            "{:?} {:40x} {:?} {:?} {:?} {:?}",
-                            stack.last(),
-                            stack.nth_last(1).unwrap_or_default(),
-                            stack.nth_last(2),
-                            stack.nth_last(3),
-                            stack.nth_last(4),
-                            stack.nth_last(5)
-                        )
-                    } else if geth_step.op.is_call_with_value() {
-                        format!(
-                            "{:?} {:40x} {:?} {:?} {:?} {:?} {:?}",
-                            stack.last(),
-                            stack.nth_last(1).unwrap_or_default(),
-                            stack.nth_last(2),
-                            stack.nth_last(3),
-                            stack.nth_last(4),
-                            stack.nth_last(5),
-                            stack.nth_last(6),
-                        )
-                    } else if geth_step.op.is_create() {
-                        format!(
-                            "value {:?} offset {:?} size {:?} {}",
-                            stack.last(),
-                            stack.nth_last(1),
-                            stack.nth_last(2),
-                            if geth_step.op == OpcodeId::CREATE2 {
-                                format!("salt {:?}", stack.nth_last(3))
-                            } else {
-                                "".to_string()
-                            }
-                        )
-                    } else if matches!(geth_step.op, OpcodeId::SSTORE) {
-                        format!(
-                            "{:?} {:?} {:?}",
-                            state_ref.call().map(|c| c.address),
-                            stack.last(),
-                            stack.nth_last(1),
-                        )
-                    } else {
-                        let stack_input_num = 1024 - geth_step.op.valid_stack_ptr_range().1 as usize;
-                        (0..stack_input_num).map(|i|
-                            format!("{:?}",  stack.nth_last(i))
-                        ).collect_vec().join(" ")
-                    }
-                }
-            );
-            debug_assert_eq!(
-                geth_step.depth as usize,
-                state_ref.call().unwrap().depth,
-                "call {:?} calls {:?}",
-                state_ref.call(),
-                state_ref.tx.calls()
-            );
-            let exec_steps = gen_associated_ops(
-                &geth_step.op,
-                &mut state_ref,
-                &geth_trace.struct_logs[index..],
-            )?;
-            tx.steps_mut().extend(exec_steps);
+    /// Finish a transaction started with [`Self::begin_tx`]: handle its last
+    /// geth step and generate its EndTx step.
+    pub fn end_tx(&mut self, mut stream: StreamingTx) -> Result<(), Error> {
+        if let Some(geth_step) = stream.pending.pop() {
+            self.handle_geth_step(&mut stream, &[geth_step])?;
+        }
+        if stream.steps == 0 {
+            stream.check_begin_tx_gas_cost(stream.trace_gas);
         }
+        let StreamingTx {
+            mut tx,
+            mut tx_ctx,
+            self_check,
+            ..
+        } = stream;
 
         // Generate EndTx step
         log::trace!("gen_end_tx_ops");
@@ -690,6 +847,9 @@ impl<'a> CircuitInputBuilder {
             gen_associated_steps(&mut self.state_ref(&mut tx, &mut tx_ctx), ExecState::EndTx)?;
         self.sdb.clear_transient_storage();
         tx.steps_mut().extend(end_tx_steps);
+        if let Some(self_check) = self_check {
+            self_check.finish(tx.calls(), &self.sdb)?;
+        }
 
         debug_assert_eq!(
             tx.calls.len(),
@@ -702,6 +862,138 @@ impl<'a> CircuitInputBuilder {
 
         Ok(())
     }
+
+    /// Handle a transaction whose execution trace is read lazily from a trace
+    /// file, e.g. written by `external_tracer::trace_to_disk`.
+    pub fn handle_tx_from_reader(
+        &mut self,
+        eth_tx: &eth_types::Transaction,
+        reader: GethExecTraceReader,
+    ) -> Result<(), Error> {
+        let mut stream = self.begin_tx(eth_tx, reader.header())?;
+        for geth_step in reader {
+            self.handle_tx_step(&mut stream, geth_step?)?;
+        }
+        self.end_tx(stream)
+    }
+
+    /// Handle the geth step `geth_steps[0]`, the following steps being
+    /// available to look ahead.
+    fn handle_geth_step(
+        &mut self,
+        stream: &mut StreamingTx,
+        geth_steps: &[GethExecStep],
+    ) -> Result<(), Error> {
+        let geth_step = &geth_steps[0];
+        if stream.steps == 0 {
+            stream.check_begin_tx_gas_cost(stream.tx.gas - geth_step.gas.0);
+        }
+        let index = stream.steps;
+        stream.steps += 1;
+
+        self.monitor
+            .step(self.block.txs.len(), self.block_ctx.rwc.0 - 1)?;
+        let tx_index = self.block.txs.len();
+        let tx_gas = stream.tx.gas;
+        let mut state_ref = self.state_ref(&mut stream.tx, &mut stream.tx_ctx);
+        log::trace!(
+            "handle {}th tx depth {} {}th opcode {:?} pc: {} gas_left: {} gas_used: {} rwc: {} call_id: {} msize: {} refund: {} args: {}",
+            tx_index,
+            geth_step.depth,
+            index,
+            geth_step.op,
+            geth_step.pc.0,
+            geth_step.gas.0,
+            tx_gas - geth_step.gas.0,
+            state_ref.block_ctx.rwc.0,
+            state_ref.call().map(|c| c.call_id).unwrap_or(0),
+            state_ref.call_ctx()?.memory.len(),
+            geth_step.refund.0,
+            {
+                let stack = &state_ref.call_ctx()?.stack;
+                if geth_step.op.is_push_with_data() {
+                    #[cfg(feature = "enable-stack")]
+                    {
+                        format!("{:?}", geth_steps.get(1).map(|step| step.stack.last()))
+                    }
+                    #[cfg(not(feature = "enable-stack"))]
+                    {
+                        "N/A".to_string()
+                    }
+                } else if geth_step.op.is_call_without_value() {
+                    format!(
+                        "{:?} {:40x} {:?} {:?} {:?} {:?}",
+                        stack.last(),
+                        stack.nth_last(1).unwrap_or_default(),
+                        stack.nth_last(2),
+                        stack.nth_last(3),
+                        stack.nth_last(4),
+                        stack.nth_last(5)
+                    )
+                } else if geth_step.op.is_call_with_value() {
+                    format!(
+                        "{:?} {:40x} {:?} {:?} {:?} {:?} {:?}",
+                        stack.last(),
+                        stack.nth_last(1).unwrap_or_default(),
+                        stack.nth_last(2),
+                        stack.nth_last(3),
+                        stack.nth_last(4),
+                        stack.nth_last(5),
+                        stack.nth_last(6),
+                    )
+                } else if geth_step.op.is_create() {
+                    format!(
+                        "value {:?} offset {:?} size {:?} {}",
+                        stack.last(),
+                        stack.nth_last(1),
+                        stack.nth_last(2),
+                        if geth_step.op == OpcodeId::CREATE2 {
+                            format!("salt {:?}", stack.nth_last(3))
+                        } else {
+                            "".to_string()
+                        }
+                    )
+                } else if matches!(geth_step.op, OpcodeId::SSTORE) {
+                    format!(
+                        "{:?} {:?} {:?}",
+                        state_ref.call().map(|c| c.address),
+                        stack.last(),
+                        stack.nth_last(1),
+                    )
+                } else {
+                    let stack_input_num = 1024 - geth_step.op.valid_stack_ptr_range().1 as usize;
+                    (0..stack_input_num).map(|i|
+                        format!("{:?}",  stack.nth_last(i))
+                    ).collect_vec().join(" ")
+                }
+            }
+        );
+        debug_assert_eq!(
+            geth_step.depth as usize,
+            state_ref.call().unwrap().depth,
+            "call {:?} calls {:?}",
+            state_ref.call(),
+            state_ref.tx.calls()
+        );
+        let exec_steps = gen_associated_ops(&geth_step.op, &mut state_ref, geth_steps)?;
+        if let Some(self_check) = &mut stream.self_check {
+            self_check.step(
+                geth_step,
+                &exec_steps,
+                state_ref.tx.calls(),
+                &state_ref.block.container,
+                state_ref.code_db,
+            )?;
+        }
+        stream.tx.steps_mut().extend(exec_steps);
+        Ok(())
+    }
+
+    /// Precompile calls handled so far, to be checked against the capacity
+    /// of the precompile circuits.
+    pub fn precompile_usage(&self) -> PrecompileUsage {
+        self.block.precompile_events.usage()
+    }
 }
 
 #[cfg(feature = "test")]
@@ -715,6 +1007,43 @@ impl CircuitInputBuilder {
     }
 }
 
+/// Transaction being handled by a [`CircuitInputBuilder`] from geth steps fed
+/// one by one, see [`CircuitInputBuilder::begin_tx`].
+#[derive(Debug)]
+pub struct StreamingTx {
+    tx: Transaction,
+    tx_ctx: TransactionContext,
+    trace_gas: u64,
+    begin_tx_gas_cost: u64,
+    steps: usize,
+    pending: Vec<GethExecStep>,
+    self_check: Option<TxSelfCheck>,
+}
+
+impl StreamingTx {
+    /// Return the number of geth steps handled so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Declare whether the geth steps fed to the transaction capture the
+    /// memory. Unless declared, the memory of the steps isn't checked against
+    /// the one of the builder with `GETH_TRACE_CHECK_LEVEL`, as the first
+    /// steps, which only look ahead of one step, have an empty memory either
+    /// way.
+    #[cfg(feature = "enable-memory")]
+    pub fn set_memory_captured(&mut self, captured: bool) {
+        self.tx_ctx.memory_captured = Some(captured);
+    }
+
+    fn check_begin_tx_gas_cost(&self, real_gas_cost: u64) {
+        debug_assert_eq!(
+            self.begin_tx_gas_cost, real_gas_cost,
+            "begin step cost mismatch"
+        );
+    }
+}
+
 /// Get the tx hash of the dummy tx (nonce=0, gas=0, gas_price=0, to=0, value=0,
 /// data="")
 pub fn get_dummy_tx_hash() -> H256 {
@@ -755,6 +1084,336 @@ pub fn get_call_memory_offset_length(
     if length.is_zero() {
         Ok((0, 0))
     } else {
-        Ok((offset.low_u64(), length.low_u64()))
+        Ok((offset.fit_u64(), length.fit_u64()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Receipt;
+    use crate::{
+        error::{CapacityResource, Error},
+        mock::BlockData,
+    };
+    use eth_types::{
+        address, bytecode,
+        evm_types::{GasCost, OpcodeId},
+        geth_types::{GethData, TxType},
+        AccessList, AccessListItem, GethExecTrace, ToWord, H256,
+    };
+    use ethers_core::types::BloomInput;
+    use mock::{
+        gwei, test_ctx::helpers::account_0_code_wallet_0_no_code, TestContext, MOCK_WALLETS,
+    };
+
+    #[test]
+    fn handle_blocks_fills_inner_blocks() {
+        let code = bytecode! {
+            PUSH1(0x00)
+            PUSH1(0x01)
+            ADD
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let mut next_block = block.eth_block.clone();
+        let number = block.eth_block.number.unwrap() + 1;
+        next_block.number = Some(number);
+        next_block.parent_hash = block.eth_block.hash.unwrap();
+        for tx in next_block.transactions.iter_mut() {
+            tx.block_number = Some(number);
+        }
+        let eth_blocks = vec![block.eth_block.clone(), next_block];
+        let geth_traces = vec![block.geth_traces.clone(), block.geth_traces.clone()];
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder.handle_blocks(&eth_blocks, &geth_traces).unwrap();
+        assert_eq!(builder.block.blocks.len(), 2);
+        assert_eq!(builder.block.txs.len(), 2);
+        assert_eq!(builder.block.txs[1].block_num, number.as_u64());
+        let history_hashes = &builder.block.blocks[&number.as_u64()].history_hashes;
+        assert_eq!(history_hashes.len(), block.history_hashes.len() + 1);
+        assert_eq!(
+            history_hashes.last(),
+            Some(&block.eth_block.hash.unwrap().to_word())
+        );
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder.block.circuits_params.max_inner_blocks = 1;
+        assert!(matches!(
+            builder.handle_blocks(&eth_blocks, &geth_traces),
+            Err(Error::CapacityExceeded {
+                resource: CapacityResource::InnerBlocks,
+                ..
+            })
+        ));
+    }
+
+    // blake2f fails under scroll, no compression happens then.
+    #[cfg(not(feature = "scroll"))]
+    #[test]
+    fn blake2f_rounds_limit() {
+        // 213 bytes, zero but the rounds: a compression of 2 rounds.
+        let code = bytecode! {
+            PUSH1(0x02)
+            PUSH1(0x03)
+            MSTORE8
+            PUSH1(0x40)
+            PUSH1(0x00)
+            PUSH1(0xd5)
+            PUSH1(0x00)
+            PUSH1(0x09)
+            GAS
+            STATICCALL
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        // unchecked with the default limit of 0
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        assert_eq!(builder.block.precompile_events.usage().blake2f_rounds, 2);
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder.block.circuits_params.max_blake2f_rounds = 1;
+        assert!(matches!(
+            builder.handle_block(&block.eth_block, &block.geth_traces),
+            Err(Error::CapacityExceeded {
+                resource: CapacityResource::Blake2fRounds,
+                used: 2,
+                limit: 1,
+            })
+        ));
+    }
+
+    #[test]
+    fn handle_tx_steps_like_handle_block() {
+        let code = bytecode! {
+            PUSH1(0x20)
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x20)
+            PUSH1(0x00)
+            RETURN
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block_inner(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let mut streaming_builder =
+            BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        for (tx, geth_trace) in block.eth_block.transactions.iter().zip(&block.geth_traces) {
+            let header = GethExecTrace {
+                struct_logs: vec![],
+                ..geth_trace.clone()
+            };
+            let mut stream = streaming_builder.begin_tx(tx, &header).unwrap();
+            #[cfg(feature = "enable-memory")]
+            stream.set_memory_captured(true);
+            for geth_step in geth_trace.struct_logs.iter().cloned() {
+                streaming_builder
+                    .handle_tx_step(&mut stream, geth_step)
+                    .unwrap();
+            }
+            assert_eq!(stream.steps(), geth_trace.struct_logs.len() - 1);
+            streaming_builder.end_tx(stream).unwrap();
+        }
+
+        assert_eq!(
+            format!("{:?}", streaming_builder.block.txs),
+            format!("{:?}", builder.block.txs)
+        );
+        assert_eq!(
+            format!("{:?}", streaming_builder.block.container),
+            format!("{:?}", builder.block.container)
+        );
+        assert_eq!(streaming_builder.block_ctx.rwc, builder.block_ctx.rwc);
+    }
+
+    #[test]
+    fn tx_calls_match_call_trace() {
+        let callee = address!("0x00000000000000000000000000000000000000fe");
+        let code = bytecode! {
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH20(callee.to_word())
+            GAS
+            CALL
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let tx = &builder.block.txs[0];
+        assert_eq!(tx.calls().len(), 2);
+        tx.check_call_tree(&block.geth_traces[0].call_trace)
+            .unwrap();
+
+        let code = bytecode! {
+            STOP
+        };
+        let other_block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        assert!(tx
+            .check_call_tree(&other_block.geth_traces[0].call_trace)
+            .is_err());
+    }
+
+    #[test]
+    fn recursive_calls_at_max_call_depth() {
+        // the contract calls itself with all its gas, until the call nested
+        // more than 1024 times fails
+        let code = bytecode! {
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH1(0x00)
+            ADDRESS
+            GAS
+            CALL
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_wallet_0_no_code(code),
+            |mut txs, accs| {
+                // enough gas for 1024 nested calls keeping 1/64 of their gas
+                txs[0]
+                    .from(MOCK_WALLETS[0].clone())
+                    .to(accs[0].address)
+                    .gas(100_000_000_000u64.into());
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        let geth_trace = &block.geth_traces[0];
+        assert!(!geth_trace.failed);
+        assert!(geth_trace
+            .struct_logs
+            .iter()
+            .any(|step| step.depth as usize == eth_types::MAX_CALL_DEPTH));
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let tx = &builder.block.txs[0];
+        assert_eq!(tx.calls().len(), eth_types::MAX_CALL_DEPTH);
+        tx.check_call_tree(&geth_trace.call_trace).unwrap();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder.max_call_depth = eth_types::MAX_CALL_DEPTH - 1;
+        assert!(matches!(
+            builder.handle_block(&block.eth_block, &block.geth_traces),
+            Err(Error::EthTypeError(eth_types::Error::CallDepthExceeded {
+                depth: eth_types::MAX_CALL_DEPTH,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn typed_tx_from_mock() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            SLOAD
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_wallet_0_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(MOCK_WALLETS[0].clone())
+                    .to(accs[0].address)
+                    .gas_price(gwei(2))
+                    .max_fee_per_gas(gwei(2))
+                    .max_priority_fee_per_gas(gwei(2))
+                    .access_list(AccessList(vec![AccessListItem {
+                        address: accs[0].address,
+                        storage_keys: vec![H256::from_low_u64_be(1)],
+                    }]))
+                    .tx_type(TxType::Eip1559);
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+
+        let eth_tx = &block.eth_block.transactions[0];
+        assert_eq!(eth_tx.recover_from().unwrap(), eth_tx.from);
+        // the slot of the access list is warm
+        let sload = &block.geth_traces[0].struct_logs[1];
+        assert_eq!(sload.op, OpcodeId::SLOAD);
+        assert_eq!(sload.gas_cost, GasCost::WARM_ACCESS);
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        assert_eq!(builder.block.txs[0].tx_type, TxType::Eip1559);
+    }
+
+    #[test]
+    fn receipts_of_logs() {
+        let code = bytecode! {
+            PUSH1(0xab)
+            PUSH1(0x00)
+            MSTORE8
+            PUSH2(0x1234) // topic
+            PUSH1(0x01) // size
+            PUSH1(0x00) // offset
+            LOG1
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let tx = &builder.block.txs[0];
+        let receipts = builder.block.receipts();
+        assert_eq!(receipts.len(), 1);
+        let receipt = &receipts[0];
+        assert_eq!(receipt.status, 1);
+        assert_eq!(receipt.cumulative_gas_used, tx.gas_used);
+        assert_eq!(receipt.logs.len(), 1);
+        let log = &receipt.logs[0];
+        assert_eq!(Some(log.address), tx.to);
+        assert_eq!(log.topics, vec![H256::from_low_u64_be(0x1234)]);
+        assert_eq!(log.data.to_vec(), vec![0xab]);
+        assert!(receipt
+            .bloom
+            .contains_input(BloomInput::Raw(log.address.as_bytes())));
+        assert!(receipt
+            .bloom
+            .contains_input(BloomInput::Raw(log.topics[0].as_bytes())));
+        assert_eq!(
+            builder.block.receipts_root(tx.block_num),
+            Receipt::root(&receipts)
+        );
     }
 }
@@ -0,0 +1,154 @@
+//! Audit of the EIP-2929 access list of each tx, as written to the
+//! `TxAccessListAccount` and `TxAccessListAccountStorage` tags of the rw
+//! table.
+
+use super::{Blocks, ExecState, ExecStep};
+use crate::{error::Error, exec_trace::OperationRef, operation::Target};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Address, Word,
+};
+use std::collections::BTreeMap;
+
+/// Entry of the access list of a tx.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AccessListKey {
+    /// Account
+    Account {
+        /// Transaction ID: Transaction index in the block starting at 1.
+        tx_id: usize,
+        /// Account address
+        address: Address,
+    },
+    /// Storage slot
+    Storage {
+        /// Transaction ID: Transaction index in the block starting at 1.
+        tx_id: usize,
+        /// Account address
+        address: Address,
+        /// Storage key
+        key: Word,
+    },
+}
+
+/// Warmth of an access list entry around a read or write of the rw table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccessListWrite {
+    /// Rw counter of the access
+    pub rwc: usize,
+    /// Whether the entry was warm before the access
+    pub is_warm_prev: bool,
+    /// Whether the entry is warm after the access
+    pub is_warm: bool,
+}
+
+impl Blocks {
+    /// Reads and writes of each entry of the access lists of the block, in
+    /// rw counter order. Writes undone by a revert are included.
+    pub fn access_list_writes(&self) -> BTreeMap<AccessListKey, Vec<AccessListWrite>> {
+        let mut writes: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for op in &self.container.tx_access_list_account {
+            let key = AccessListKey::Account {
+                tx_id: op.op().tx_id,
+                address: op.op().address,
+            };
+            writes.entry(key).or_default().push(AccessListWrite {
+                rwc: op.rwc().0,
+                is_warm_prev: op.op().is_warm_prev,
+                is_warm: op.op().is_warm,
+            });
+        }
+        for op in &self.container.tx_access_list_account_storage {
+            let key = AccessListKey::Storage {
+                tx_id: op.op().tx_id,
+                address: op.op().address,
+                key: op.op().key,
+            };
+            writes.entry(key).or_default().push(AccessListWrite {
+                rwc: op.rwc().0,
+                is_warm_prev: op.op().is_warm_prev,
+                is_warm: op.op().is_warm,
+            });
+        }
+        for entry_writes in writes.values_mut() {
+            entry_writes.sort_by_key(|write| write.rwc);
+        }
+        writes
+    }
+
+    /// Check that every access list entry starts cold in its tx and that each
+    /// write continues from the warmth left by the previous one, as the state
+    /// circuit requires.
+    pub fn check_access_list(&self) -> Result<(), Error> {
+        for (key, writes) in self.access_list_writes() {
+            let mut is_warm = false;
+            for write in writes {
+                check_eq!(
+                    write.is_warm_prev,
+                    is_warm,
+                    "access list of {key:?} is inconsistent at rwc {}",
+                    write.rwc
+                );
+                is_warm = write.is_warm;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the gas cost of the successful steps whose cost only depends on
+    /// the warmth of the account or slot they access: SLOAD, BALANCE,
+    /// EXTCODESIZE and EXTCODEHASH.
+    pub fn check_access_gas(&self) -> Result<(), Error> {
+        for step in self.txs.iter().flat_map(|tx| tx.steps()) {
+            if step.error.is_some() {
+                continue;
+            }
+            let (target, cold_cost) = match step.exec_state {
+                ExecState::Op(OpcodeId::SLOAD) => {
+                    (Target::TxAccessListAccountStorage, GasCost::COLD_SLOAD)
+                }
+                ExecState::Op(
+                    OpcodeId::BALANCE | OpcodeId::EXTCODESIZE | OpcodeId::EXTCODEHASH,
+                ) => (Target::TxAccessListAccount, GasCost::COLD_ACCOUNT_ACCESS),
+                _ => continue,
+            };
+            let is_warm = self.access_list_warmth(step, target)?;
+            let expected = if is_warm {
+                GasCost::WARM_ACCESS
+            } else {
+                cold_cost
+            };
+            check_eq!(
+                step.gas_cost,
+                expected,
+                "{:?} at pc {} costs {:?} but accesses a {} entry",
+                step.exec_state,
+                step.pc.0,
+                step.gas_cost,
+                if is_warm { "warm" } else { "cold" }
+            );
+        }
+        Ok(())
+    }
+
+    /// Warmth before the step of the first access list entry it accesses.
+    fn access_list_warmth(&self, step: &ExecStep, target: Target) -> Result<bool, Error> {
+        let OperationRef(_, idx) = step
+            .bus_mapping_instance
+            .iter()
+            .find(|op_ref| op_ref.target() == target)
+            .ok_or(Error::InternalError("step without access list access"))?;
+        Ok(match target {
+            Target::TxAccessListAccount => {
+                self.container.tx_access_list_account[*idx]
+                    .op()
+                    .is_warm_prev
+            }
+            _ => {
+                self.container.tx_access_list_account_storage[*idx]
+                    .op()
+                    .is_warm_prev
+            }
+        })
+    }
+}
@@ -9,7 +9,12 @@ use crate::{
     operation::{OperationContainer, RWCounter},
     Error,
 };
-use eth_types::{Address, Word, H256};
+use eth_types::{
+    evm_types::{block_utils::block_blob_base_fee, GasSchedule},
+    geth_types::TxTypeConfig,
+    mpt_proof::MptProof,
+    Address, Word, WordExt, H256,
+};
 use std::collections::{BTreeMap, HashMap};
 
 /// Context of a [`Block`] which can mutate in a [`Transaction`].
@@ -88,12 +93,16 @@ pub struct Block {
     pub difficulty: Word,
     /// base fee
     pub base_fee: Word,
+    /// blob base fee
+    pub blob_base_fee: Word,
     /// start l1 queue index
     pub start_l1_queue_index: u64,
     /// Parent block hash
     pub parent_hash: H256,
     /// State root of this block
     pub state_root: H256,
+    /// Receipts root of this block
+    pub receipts_root: H256,
 }
 impl Block {
     /// Create a new block.
@@ -126,11 +135,11 @@ impl Block {
             coinbase: eth_block
                 .author
                 .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?,
-            gas_limit: eth_block.gas_limit.low_u64(),
+            gas_limit: eth_block.gas_limit.fit_u64(),
             number: eth_block
                 .number
                 .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?
-                .low_u64()
+                .as_u64()
                 .into(),
             timestamp: eth_block.timestamp,
             difficulty: if eth_block.difficulty.is_zero() {
@@ -143,8 +152,10 @@ impl Block {
                 eth_block.difficulty
             },
             base_fee: eth_block.base_fee_per_gas.unwrap_or_default(),
+            blob_base_fee: block_blob_base_fee(eth_block)?,
             parent_hash: eth_block.parent_hash,
             state_root: eth_block.state_root,
+            receipts_root: eth_block.receipts_root,
         })
     }
 }
@@ -176,10 +187,27 @@ pub struct Blocks {
     pub circuits_params: CircuitsParams,
     /// chain id
     pub chain_id: u64,
+    /// How txs are mapped to tx types, e.g. which type byte the L1 message
+    /// txs have
+    pub tx_type_config: TxTypeConfig,
+    /// Gas costs of the fork the txs are charged with, e.g. the cost of
+    /// their calldata
+    pub gas_schedule: GasSchedule,
+    /// Reject txs whose nonce is not the nonce of their sender. Off by
+    /// default, as mock traces don't keep tx nonces in sync with accounts.
+    pub check_tx_nonce: bool,
+    /// Check the receipts roots of the block headers against the receipts of
+    /// the txs when converting to a witness. Off by default, as mock blocks
+    /// don't set their receipts roots.
+    pub check_receipts_root: bool,
     /// start_l1_queue_index
     pub start_l1_queue_index: u64,
     /// IO to/from the precompiled contract calls.
     pub precompile_events: PrecompileEvents,
+    /// Hexary trie proofs of the accounts and slots of the partial state the
+    /// blocks are handled from, see
+    /// [`CircuitInputBuilder::new_from_partial_state`](super::CircuitInputBuilder::new_from_partial_state).
+    pub mpt_proofs: Vec<MptProof>,
     /// circuit capacity counter
     copy_counter: usize,
 }
@@ -258,9 +286,9 @@ impl Blocks {
 impl Blocks {
     /// Push a copy event to the block.
     pub fn add_copy_event(&mut self, event: CopyEvent) {
-        self.copy_counter += event.full_length() as usize;
+        // Each step needs 2 rows
+        self.copy_counter += event.copy_rows() / 2;
         self.copy_events.push(event);
-        // Each byte needs 2 rows
         // TODO: magic num
 
         if self.copy_counter > 500_000 && cfg!(feature = "strict-ccc") {
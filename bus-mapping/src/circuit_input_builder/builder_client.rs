@@ -1,4 +1,5 @@
 use eth_types::{
+    code_store::{CodeStore, DiskCodeStore},
     constants::SCROLL_COINBASE,
     geth_types::{self, Account, BlockConstants},
     state_db::{self, CodeDB, StateDB},
@@ -7,9 +8,13 @@ use eth_types::{
 };
 use ethers_providers::JsonRpcClient;
 use hex::decode_to_slice;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 
 use super::{AccessSet, Block, Blocks, CircuitInputBuilder, CircuitsParams};
-use crate::{error::Error, rpc::GethClient};
+use crate::{
+    error::{Error, GasUsedMismatch},
+    rpc::GethClient,
+};
 
 use std::str::FromStr;
 use std::{collections::HashMap, iter};
@@ -21,6 +26,7 @@ pub struct BuilderClient<P: JsonRpcClient> {
     cli: GethClient<P>,
     chain_id: u64,
     circuits_params: CircuitsParams,
+    code_store: Option<DiskCodeStore>,
 }
 
 /// Build a partial StateDB from step 3
@@ -48,8 +54,14 @@ pub fn build_state_code_db(
     }
 
     let mut code_db = CodeDB::new();
-    for (_address, code) in codes {
-        code_db.insert(code.clone());
+    // hashing the codes is independent of the state, hash them in parallel
+    let scheme = code_db.hash_scheme();
+    let hashed_codes: Vec<_> = codes
+        .into_par_iter()
+        .map(|(_address, code)| (scheme.hash_code(&code), code))
+        .collect();
+    for (hash, code) in hashed_codes {
+        code_db.insert_with_hash(hash, code);
     }
     (sdb, code_db)
 }
@@ -66,9 +78,19 @@ impl<P: JsonRpcClient> BuilderClient<P> {
             cli: client,
             chain_id,
             circuits_params,
+            code_store: None,
         })
     }
 
+    /// Look the codes up in `code_store` before fetching them from geth, and
+    /// store the fetched ones, so that they are fetched once over replays.
+    pub fn with_code_store(self, code_store: DiskCodeStore) -> Self {
+        Self {
+            code_store: Some(code_store),
+            ..self
+        }
+    }
+
     /// Step 1. Query geth for Block, Txs, TxExecTraces, history block hashes
     /// and previous state root.
     pub async fn get_block(
@@ -160,13 +182,31 @@ impl<P: JsonRpcClient> BuilderClient<P> {
                 .unwrap();
             proofs.push(proof);
         }
+        let code_hashes: HashMap<Address, H256> = proofs
+            .iter()
+            .map(|proof| (proof.address, proof.code_hash))
+            .collect();
         let mut codes: HashMap<Address, Vec<u8>> = HashMap::new();
         for address in access_set.code {
-            let code = self
-                .cli
-                .get_code(address, (block_num - 1).into())
-                .await
-                .unwrap();
+            let stored_code = self
+                .code_store
+                .as_ref()
+                .zip(code_hashes.get(&address))
+                .and_then(|(code_store, code_hash)| code_store.code(code_hash));
+            let code = match stored_code {
+                Some(code) => code,
+                None => {
+                    let code = self
+                        .cli
+                        .get_code(address, (block_num - 1).into())
+                        .await
+                        .unwrap();
+                    if let Some(code_store) = &self.code_store {
+                        code_store.put(&code)?;
+                    }
+                    code
+                }
+            };
             codes.insert(address, code);
         }
         Ok((proofs, codes))
@@ -321,14 +361,49 @@ impl<P: JsonRpcClient> BuilderClient<P> {
         let mut builder =
             CircuitInputBuilder::new_from_params(self.chain_id, self.circuits_params, sdb, code_db);
         for (eth_block, geth_traces) in blocks_and_traces {
-            let block = Block::new(self.chain_id, Default::default(), eth_block)?;
-            builder.block.blocks.insert(block.number.as_u64(), block);
+            builder.add_block(eth_block)?;
             builder.handle_block_inner(eth_block, geth_traces)?;
         }
         builder.finalize_building()?;
         Ok(builder)
     }
 
+    /// Cross-check the gas used of every tx handled by the builder against
+    /// the `gasUsed` of its receipt, reporting all the mismatching txs.
+    pub async fn check_gas_used(&self, builder: &CircuitInputBuilder) -> Result<(), Error> {
+        let mut mismatches = Vec::new();
+        for (index, tx) in builder.block.txs.iter().enumerate() {
+            let receipt = self.cli.get_tx_receipt(tx.hash).await?;
+            let receipt_gas_used = match receipt.gas_used {
+                Some(gas_used) => gas_used.as_u64(),
+                None => {
+                    log::warn!("receipt of tx {:?} has no gasUsed", tx.hash);
+                    continue;
+                }
+            };
+            if tx.gas_used != receipt_gas_used {
+                log::error!(
+                    "gas used mismatch for {}th tx {:?}: builder {}, receipt {}",
+                    index,
+                    tx.hash,
+                    tx.gas_used,
+                    receipt_gas_used
+                );
+                mismatches.push(GasUsedMismatch {
+                    index,
+                    hash: tx.hash,
+                    builder: tx.gas_used,
+                    receipt: receipt_gas_used,
+                });
+            }
+        }
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::GasUsedMismatch(mismatches))
+        }
+    }
+
     /// Perform all the steps to generate the circuit inputs
     #[allow(unused_mut)]
     #[allow(unused_variables)]
@@ -379,6 +454,7 @@ impl<P: JsonRpcClient> BuilderClient<P> {
                 prev_state_root,
             )?
         };
+        self.check_gas_used(&builder).await?;
         Ok((builder, eth_block))
     }
 
@@ -400,6 +476,7 @@ impl<P: JsonRpcClient> BuilderClient<P> {
         let (state_db, code_db) = Self::build_state_code_db(proofs, codes);
         let builder =
             self.gen_inputs_from_state_multi_blocks(state_db, code_db, &blocks_and_traces)?;
+        self.check_gas_used(&builder).await?;
         Ok(builder)
     }
 
@@ -450,6 +527,7 @@ impl<P: JsonRpcClient> BuilderClient<P> {
                 Default::default(),
             )?
         };
+        self.check_gas_used(&builder).await?;
 
         Ok(builder)
     }
@@ -484,6 +562,7 @@ impl<P: JsonRpcClient> BuilderClient<P> {
                 difficulty,
                 gas_limit: eth_block.gas_limit,
                 base_fee: eth_block.base_fee_per_gas.unwrap(),
+                blob_base_fee: eth_types::evm_types::block_utils::block_blob_base_fee(eth_block)?,
             },
             accounts: proofs
                 .into_iter()
@@ -512,6 +591,8 @@ impl<P: JsonRpcClient> BuilderClient<P> {
                 .map(geth_types::Transaction::from)
                 .collect(),
             logger_config: Default::default(),
+            call_tracer_config: Default::default(),
+            overrides: Default::default(),
             chain_config: None,
             #[cfg(feature = "scroll")]
             l1_queue_index: 0,
@@ -141,7 +141,8 @@ pub struct CallContext {
     /// Call data (copy of tx input or caller's
     /// memory[call_data_offset..call_data_offset + call_data_length])
     pub call_data: Vec<u8>,
-    /// memory context of current call
+    /// memory context of current call, reconstructed by replaying its memory
+    /// writing opcodes, as the geth trace may not capture memory
     pub memory: Memory,
     /// stack context of current call
     pub stack: Stack,
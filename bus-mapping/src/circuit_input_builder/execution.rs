@@ -1,16 +1,13 @@
 //! Execution step related module.
 
-use std::{
-    marker::PhantomData,
-    ops::{Add, Mul, Neg},
-};
+use std::ops::{Add, Mul, Neg};
 
 use crate::{
     circuit_input_builder::CallContext,
     error::{ExecError, OogError},
     exec_trace::OperationRef,
     operation::RWCounter,
-    precompile::{PrecompileAuxData, PrecompileCalls},
+    precompile::{ModExpAuxData, PrecompileAuxData, PrecompileCalls, MODEXP_INPUT_LIMIT},
 };
 use eth_types::{
     evm_types::{memory::MemoryWordRange, Gas, GasCost, MemoryAddress, OpcodeId, ProgramCounter},
@@ -18,7 +15,7 @@ use eth_types::{
     Address, GethExecStep, ToLittleEndian, Word, H256, U256,
 };
 use ethers_core::k256::elliptic_curve::subtle::CtOption;
-use gadgets::impl_expr;
+use gadgets::{impl_binary_number_tag, impl_expr};
 use halo2_proofs::{
     arithmetic::{CurveAffine, Field as Halo2Field},
     halo2curves::{
@@ -27,6 +24,9 @@ use halo2_proofs::{
     },
     plonk::Expression,
 };
+use num::{BigUint, Zero};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
 
 /// An execution step of the EVM.
 #[derive(Clone, Debug)]
@@ -151,6 +151,10 @@ pub enum ExecState {
     BeginTx,
     /// Virtual step End Tx
     EndTx,
+    /// Virtual step Begin Chunk
+    BeginChunk,
+    /// Virtual step End Chunk
+    EndChunk,
     /// Virtual step End Block
     EndBlock,
     /// Virtual step Padding
@@ -206,7 +210,7 @@ impl ExecState {
 }
 
 /// Defines the various source/destination types for a copy event.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
 pub enum CopyDataType {
     /// When we need to pad the Copy rows of the circuit up to a certain maximum
     /// with rows that are not "useful".
@@ -231,84 +235,6 @@ pub enum CopyDataType {
     AccessListStorageKeys,
 }
 
-impl CopyDataType {
-    /// How many bits are necessary to represent a copy data type.
-    pub const N_BITS: usize = 3usize;
-}
-const NUM_COPY_DATA_TYPES: usize = 8usize;
-pub struct CopyDataTypeIter {
-    idx: usize,
-    back_idx: usize,
-    marker: PhantomData<()>,
-}
-impl CopyDataTypeIter {
-    fn get(&self, idx: usize) -> Option<CopyDataType> {
-        match idx {
-            0usize => Some(CopyDataType::Padding),
-            1usize => Some(CopyDataType::Bytecode),
-            2usize => Some(CopyDataType::Memory),
-            3usize => Some(CopyDataType::TxCalldata),
-            4usize => Some(CopyDataType::TxLog),
-            5usize => Some(CopyDataType::RlcAcc),
-            6usize => Some(CopyDataType::AccessListAddresses),
-            7usize => Some(CopyDataType::AccessListStorageKeys),
-            _ => None,
-        }
-    }
-}
-impl strum::IntoEnumIterator for CopyDataType {
-    type Iterator = CopyDataTypeIter;
-    fn iter() -> CopyDataTypeIter {
-        CopyDataTypeIter {
-            idx: 0,
-            back_idx: 0,
-            marker: PhantomData,
-        }
-    }
-}
-impl Iterator for CopyDataTypeIter {
-    type Item = CopyDataType;
-    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
-        #[allow(clippy::iter_nth_zero)]
-        self.nth(0)
-    }
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let t = if self.idx + self.back_idx >= NUM_COPY_DATA_TYPES {
-            0
-        } else {
-            NUM_COPY_DATA_TYPES - self.idx - self.back_idx
-        };
-        (t, Some(t))
-    }
-    fn nth(&mut self, n: usize) -> Option<<Self as Iterator>::Item> {
-        let idx = self.idx + n + 1;
-        if idx + self.back_idx > NUM_COPY_DATA_TYPES {
-            self.idx = NUM_COPY_DATA_TYPES;
-            None
-        } else {
-            self.idx = idx;
-            self.get(idx - 1)
-        }
-    }
-}
-impl ExactSizeIterator for CopyDataTypeIter {
-    fn len(&self) -> usize {
-        self.size_hint().0
-    }
-}
-impl DoubleEndedIterator for CopyDataTypeIter {
-    fn next_back(&mut self) -> Option<<Self as Iterator>::Item> {
-        let back_idx = self.back_idx + 1;
-        if self.idx + back_idx > NUM_COPY_DATA_TYPES {
-            self.back_idx = NUM_COPY_DATA_TYPES;
-            None
-        } else {
-            self.back_idx = back_idx;
-            self.get(NUM_COPY_DATA_TYPES - self.back_idx)
-        }
-    }
-}
-
 impl From<CopyDataType> for usize {
     fn from(t: CopyDataType) -> Self {
         match t {
@@ -324,6 +250,8 @@ impl From<CopyDataType> for usize {
     }
 }
 
+impl_binary_number_tag!(CopyDataType, CopyDataType::AccessListStorageKeys);
+
 impl From<&CopyDataType> for u64 {
     fn from(t: &CopyDataType) -> Self {
         match t {
@@ -360,7 +288,7 @@ pub struct CopyStep {
 }
 
 /// Defines an enum type that can hold either a number or a hash value.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NumberOrHash {
     /// Variant to indicate a number value.
     Number(usize),
@@ -379,7 +307,7 @@ pub enum NumberOrHash {
 ///
 /// Additionally, when the destination is memory, `bytes_write_prev` holds the memory content
 /// *before* the write.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct CopyBytes {
     /// Represents the list of (bytes, is_code, mask) copied during this copy event
     pub bytes: Vec<(u8, bool, bool)>,
@@ -410,7 +338,7 @@ impl CopyBytes {
 /// Save address, storage_key, storage_key_index and is_warm_prev
 /// to column value_word_rlc, value_word_rlc_prev, value and
 /// value_prev in copy circuit.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CopyAccessList {
     /// Access list address
     pub address: Address,
@@ -446,7 +374,7 @@ impl CopyAccessList {
 /// Defines a copy event associated with EVM opcodes such as CALLDATACOPY,
 /// CODECOPY, CREATE, etc. More information:
 /// <https://github.com/privacy-scaling-explorations/zkevm-specs/blob/master/specs/copy-proof.md>.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CopyEvent {
     /// Represents the start address at the source of the copy event.
     pub src_addr: u64,
@@ -472,6 +400,26 @@ pub struct CopyEvent {
     pub copy_bytes: CopyBytes,
     /// Represents transaction access list
     pub access_list: Vec<CopyAccessList>,
+    /// The step which performed this copy event, set when it is pushed to the
+    /// block. It is not serialized.
+    #[serde(skip)]
+    pub origin: Option<CopyEventOrigin>,
+}
+
+/// The step which performed a [`CopyEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CopyEventOrigin {
+    /// Id of the transaction, starting from 1.
+    pub tx_id: usize,
+    /// Index of the step in the steps of the transaction.
+    pub step_index: usize,
+    /// Id of the call of the step.
+    pub call_id: usize,
+    /// Program counter of the step.
+    pub pc: ProgramCounter,
+    /// Opcode of the step, None for steps which are not an opcode (e.g.
+    /// BeginTx or a precompile call).
+    pub opcode: Option<OpcodeId>,
 }
 
 pub type CopyEventSteps = Vec<(u8, bool, bool)>;
@@ -514,6 +462,32 @@ impl CopyEvent {
         usize::from(self.rw_counter_start) as u64
     }
 
+    /// Whether the copy circuit copies the inner words of this event in one
+    /// step each, instead of byte by byte. This applies to copies from memory
+    /// to memory with the same alignment on both sides, without source padding
+    /// and with at least one inner word. The first and last words, with the
+    /// unaligned edges, are still copied byte by byte.
+    pub fn is_word_mode(&self) -> bool {
+        self.src_type == CopyDataType::Memory
+            && self.dst_type == CopyDataType::Memory
+            && self.src_addr % 32 == self.dst_addr % 32
+            && self.src_addr_end == self.src_addr + self.copy_length()
+            && self.full_length() / 32 > 2
+    }
+
+    /// The number of rows of this copy event in the copy circuit, two for each
+    /// byte (one to read it and one to write it), or for each inner word in
+    /// word mode.
+    pub fn copy_rows(&self) -> usize {
+        let steps = if self.is_word_mode() {
+            // The first and last words byte by byte, and a step per inner word.
+            64 + (self.full_length() as usize / 32 - 2)
+        } else {
+            self.full_length() as usize
+        };
+        steps * 2
+    }
+
     /// The number of RW lookups performed by this copy event.
     pub fn rw_counter_delta(&self) -> u64 {
         if self.dst_type == CopyDataType::AccessListAddresses
@@ -848,7 +822,7 @@ where
 }
 
 /// Intermediary multiplication step, representing `a * b == d (mod 2^256)`
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ExpStep {
     /// First multiplicand.
     pub a: Word,
@@ -869,7 +843,7 @@ impl From<(Word, Word, Word)> for ExpStep {
 }
 
 /// Event representing an exponentiation `a ^ b == d (mod 2^256)`.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExpEvent {
     /// Base `a` for the exponentiation.
     pub base: Word,
@@ -897,13 +871,72 @@ impl Default for ExpEvent {
 }
 
 /// I/Os from all precompiled contract calls in a block.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct PrecompileEvents {
     /// All events.
     pub events: Vec<PrecompileEvent>,
 }
 
+/// Calls of the precompiles proven by a dedicated circuit in a block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PrecompileUsage {
+    /// Number of ecAdd calls.
+    pub ec_add: usize,
+    /// Number of ecMul calls.
+    pub ec_mul: usize,
+    /// Number of ecPairing calls.
+    pub ec_pairing: usize,
+    /// Number of modexp calls.
+    pub modexp: usize,
+    /// Number of sha256 calls.
+    pub sha256: usize,
+    /// Number of blake2f calls.
+    pub blake2f: usize,
+    /// Total rounds of the blake2f calls.
+    pub blake2f_rounds: usize,
+    /// Total input bytes of the calls, as padded by the circuits of the
+    /// precompiles with a fixed input size.
+    pub input_bytes: usize,
+}
+
 impl PrecompileEvents {
+    /// Count the calls and input bytes of each precompile.
+    pub fn usage(&self) -> PrecompileUsage {
+        let mut usage = PrecompileUsage::default();
+        for event in &self.events {
+            let input_bytes = match event {
+                PrecompileEvent::Ecrecover(_) => continue,
+                PrecompileEvent::EcAdd(_) => {
+                    usage.ec_add += 1;
+                    128
+                }
+                PrecompileEvent::EcMul(_) => {
+                    usage.ec_mul += 1;
+                    96
+                }
+                PrecompileEvent::EcPairing(op) => {
+                    usage.ec_pairing += 1;
+                    op.pairs.len() * N_BYTES_PER_PAIR
+                }
+                PrecompileEvent::ModExp(_) => {
+                    usage.modexp += 1;
+                    MODEXP_INPUT_LIMIT
+                }
+                PrecompileEvent::SHA256(op) => {
+                    usage.sha256 += 1;
+                    op.input.len()
+                }
+                PrecompileEvent::Blake2F(op) => {
+                    usage.blake2f += 1;
+                    usage.blake2f_rounds += op.rounds as usize;
+                    Blake2fOp::INPUT_LEN
+                }
+            };
+            usage.input_bytes += input_bytes;
+        }
+        usage
+    }
+
     /// Get all ecrecover events.
     pub fn get_ecrecover_events(&self) -> Vec<SignData> {
         self.events
@@ -988,10 +1021,24 @@ impl PrecompileEvents {
             .cloned()
             .collect()
     }
+    /// Get all Blake2F events.
+    pub fn get_blake2f_events(&self) -> Vec<Blake2fOp> {
+        self.events
+            .iter()
+            .filter_map(|e| {
+                if let PrecompileEvent::Blake2F(op) = e {
+                    Some(op)
+                } else {
+                    None
+                }
+            })
+            .cloned()
+            .collect()
+    }
 }
 
 /// I/O from a precompiled contract call.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum PrecompileEvent {
     /// Represents the I/O from Ecrecover call.
     Ecrecover(SignData),
@@ -1005,6 +1052,8 @@ pub enum PrecompileEvent {
     ModExp(BigModExp),
     /// Represents the I/O from SHA256 call.
     SHA256(SHA256),
+    /// Represents the I/O from Blake2F call.
+    Blake2F(Blake2fOp),
 }
 
 impl Default for PrecompileEvent {
@@ -1014,7 +1063,7 @@ impl Default for PrecompileEvent {
 }
 
 /// EcAdd operation: P + Q = R
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EcAddOp {
     /// EVM input for first operand to EcAdd.
     pub p: (U256, U256),
@@ -1116,7 +1165,7 @@ impl EcAddOp {
 }
 
 /// EcMul operation: s.P = R
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EcMulOp {
     /// The EVM inputs to the G1 point.
     pub p: (U256, U256),
@@ -1224,15 +1273,16 @@ impl EcMulOp {
     }
 }
 
-/// The number of pairing inputs per pairing operation. If the inputs provided to the precompile
-/// call are < 4, we append (G1::infinity, G2::generator) until we have the required no. of inputs.
+/// The maximum number of pairs of an ecPairing call under scroll, which fails the calls with more
+/// pairs. It is also the default number of pairs of an EcPairing op in the ECC circuit, see
+/// [`PrecompileEcParams::ec_pairing_pairs`](crate::circuit_input_builder::PrecompileEcParams).
 pub const N_PAIRING_PER_OP: usize = 4;
 
 /// The number of bytes taken to represent a pair (G1, G2).
 pub const N_BYTES_PER_PAIR: usize = 192;
 
 /// Pair of (G1, G2).
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingPair {
     /// EVM inputs for the G1 point.
     pub g1_point: (U256, U256),
@@ -1319,7 +1369,7 @@ impl EcPairingPair {
     }
 
     /// Padding pair for EcPairing operation. The pairing check is done with a constant number
-    /// `N_PAIRING_PER_OP` of (G1, G2) pairs. In case EVM inputs are less in number, we pad them
+    /// `ec_pairing_pairs` of (G1, G2) pairs. In case EVM inputs are less in number, we pad them
     /// with `(G1::Infinity, G2::Infinity)` for simplicity.
     pub fn padding_pair() -> Self {
         Self {
@@ -1334,10 +1384,11 @@ impl EcPairingPair {
 }
 
 /// EcPairing operation
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingOp {
-    /// tuples of G1 and G2 points supplied to the ECC circuit.
-    pub pairs: [EcPairingPair; N_PAIRING_PER_OP],
+    /// tuples of G1 and G2 points of the EVM input, which the ECC circuit pads with
+    /// [`EcPairingPair::padding_pair`].
+    pub pairs: Vec<EcPairingPair>,
     /// Result from the pairing check.
     pub output: Word,
     /// Input bytes to the ecPairing call.
@@ -1359,24 +1410,10 @@ impl Default for EcPairingOp {
         let g2_y0 = U256::from_little_endian(&g2_point.y.c1.to_bytes());
         let g2_y1 = U256::from_little_endian(&g2_point.y.c0.to_bytes());
         Self {
-            pairs: [
-                EcPairingPair {
-                    g1_point: (g1_x, g1_y),
-                    g2_point: (g2_x0, g2_x1, g2_y0, g2_y1),
-                },
-                EcPairingPair {
-                    g1_point: (g1_x, g1_y),
-                    g2_point: (g2_x0, g2_x1, g2_y0, g2_y1),
-                },
-                EcPairingPair {
-                    g1_point: (g1_x, g1_y),
-                    g2_point: (g2_x0, g2_x1, g2_y0, g2_y1),
-                },
-                EcPairingPair {
-                    g1_point: (g1_x, g1_y),
-                    g2_point: (g2_x0, g2_x1, g2_y0, g2_y1),
-                },
-            ],
+            pairs: vec![EcPairingPair {
+                g1_point: (g1_x, g1_y),
+                g2_point: (g2_x0, g2_x1, g2_y0, g2_y1),
+            }],
             output: Word::zero(),
             // It does not matter what the input bytes and return bytes are in this case, as this
             // operation is a filler op. It is not an op constructed from an EVM call to the
@@ -1390,7 +1427,8 @@ impl Default for EcPairingOp {
 }
 
 impl EcPairingOp {
-    /// Returns the uncompressed big-endian byte representation of inputs to the EcPairingOp.
+    /// Returns the uncompressed big-endian byte representation of inputs to the EcPairingOp,
+    /// without the padding of the ECC circuit.
     pub fn to_bytes_be(&self) -> Vec<u8> {
         self.pairs
             .iter()
@@ -1416,11 +1454,9 @@ impl EcPairingOp {
         let other_g1 = G1Affine::from(G1Affine::generator() * Fr::from(6));
         let other_g2 = G2Affine::generator();
         Self {
-            pairs: [
+            pairs: vec![
                 EcPairingPair::new(g1_neg, g2),
                 EcPairingPair::new(other_g1, other_g2),
-                EcPairingPair::new(G1Affine::identity(), G2Affine::generator()),
-                EcPairingPair::new(G1Affine::identity(), G2Affine::generator()),
             ],
             output: 1.into(),
             ..Default::default()
@@ -1428,35 +1464,188 @@ impl EcPairingOp {
     }
 }
 
-/// Event representing an exponentiation `a ^ b == d (mod m)` in precompile modexp.
-#[derive(Clone, Debug)]
+/// Event representing an exponentiation `a ^ b == d (mod m)` in precompile modexp, parsed from
+/// the input of the call against the operand size limit of the modexp circuit.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BigModExp {
-    /// Base `a` for the exponentiation.
-    pub base: Word,
-    /// Exponent `b` for the exponentiation.
-    pub exponent: Word,
-    /// Modulus `m`
-    pub modulus: Word,
-    /// Mod exponentiation result.
-    pub result: Word,
+    /// Input bytes of the call, at most
+    /// [`MODEXP_INPUT_LIMIT`](crate::precompile::MODEXP_INPUT_LIMIT).
+    pub input: Vec<u8>,
+    /// Byte lengths of the base, the exponent and the modulus in the input.
+    pub input_lens: [Word; 3],
+    /// Whether the byte lengths are all within the size limit.
+    pub valid: bool,
+    /// Base `a` for the exponentiation, big-endian, empty if not valid.
+    pub base: Vec<u8>,
+    /// Exponent `b` for the exponentiation, big-endian, empty if not valid.
+    pub exponent: Vec<u8>,
+    /// Modulus `m`, big-endian, empty if not valid.
+    pub modulus: Vec<u8>,
+    /// Mod exponentiation result, big-endian, of the byte length of the modulus.
+    pub result: Vec<u8>,
 }
 
 impl Default for BigModExp {
+    /// The call with an empty input, i.e. all the operands are empty.
     fn default() -> Self {
+        Self::new(vec![], 0)
+    }
+}
+
+impl BigModExp {
+    /// Parse the input bytes of a MODEXP call with operands of at most `size_limit` bytes, and
+    /// compute its result.
+    pub fn new(input: Vec<u8>, size_limit: usize) -> Self {
+        debug_assert!(input.len() <= MODEXP_INPUT_LIMIT);
+        let (valid, input_lens, [base, exponent, modulus]) =
+            ModExpAuxData::parse_input(&input, size_limit);
+
+        let m = BigUint::from_bytes_be(&modulus);
+        let mut result = vec![0u8; modulus.len()];
+        if !m.is_zero() {
+            let d = BigUint::from_bytes_be(&base).modpow(&BigUint::from_bytes_be(&exponent), &m);
+            let d = d.to_bytes_be();
+            result[modulus.len() - d.len()..].copy_from_slice(&d);
+        }
+
         Self {
-            modulus: 1.into(),
-            base: Default::default(),
-            exponent: Default::default(),
-            result: Default::default(),
+            input,
+            input_lens,
+            valid,
+            base,
+            exponent,
+            modulus,
+            result,
         }
     }
+
+    /// Byte lengths of the base, the exponent and the modulus, 0 if not valid.
+    pub fn lens(&self) -> [usize; 3] {
+        [&self.base, &self.exponent, &self.modulus].map(Vec::len)
+    }
+
+    /// Bit length of the exponent.
+    pub fn exp_bit_len(&self) -> usize {
+        BigUint::from_bytes_be(&self.exponent).bits() as usize
+    }
 }
 
 /// Event representing an SHA256 hash in precompile sha256.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct SHA256 {
     /// input bytes
     pub input: Vec<u8>,
     /// digest
     pub digest: [u8; 32],
 }
+
+/// Initialization vector of the BLAKE2b compression function.
+const BLAKE2B_IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// Permutations of the message words of the rounds of the BLAKE2b compression
+/// function, the one of round `i` being `BLAKE2B_SIGMA[i % 10]`.
+const BLAKE2B_SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// Event representing a BLAKE2b compression in precompile blake2f (EIP-152).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Blake2fOp {
+    /// Number of rounds of the compression.
+    pub rounds: u32,
+    /// State vector.
+    pub h: [u64; 8],
+    /// Message block vector.
+    pub m: [u64; 16],
+    /// Offset counters.
+    pub t: [u64; 2],
+    /// Final block indicator flag.
+    pub f: bool,
+}
+
+impl Blake2fOp {
+    /// Length of the input of the blake2f precompile.
+    pub const INPUT_LEN: usize = 213;
+
+    /// Parse the input of the blake2f precompile, None when it is malformed
+    /// and the call fails.
+    pub fn from_input(input: &[u8]) -> Option<Self> {
+        if input.len() != Self::INPUT_LEN || input[212] > 1 {
+            return None;
+        }
+        let word = |i: usize| {
+            let start = 4 + 8 * i;
+            u64::from_le_bytes(input[start..start + 8].try_into().unwrap())
+        };
+        Some(Self {
+            rounds: u32::from_be_bytes(input[..4].try_into().unwrap()),
+            h: std::array::from_fn(word),
+            m: std::array::from_fn(|i| word(8 + i)),
+            t: [word(24), word(25)],
+            f: input[212] == 1,
+        })
+    }
+
+    /// State vector after the compression.
+    pub fn output(&self) -> [u64; 8] {
+        let mut v = [0u64; 16];
+        v[..8].copy_from_slice(&self.h);
+        v[8..].copy_from_slice(&BLAKE2B_IV);
+        v[12] ^= self.t[0];
+        v[13] ^= self.t[1];
+        if self.f {
+            v[14] = !v[14];
+        }
+
+        for round in 0..self.rounds as usize {
+            let s = &BLAKE2B_SIGMA[round % 10];
+            let mut g = |a: usize, b: usize, c: usize, d: usize, x: u64, y: u64| {
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+                v[d] = (v[d] ^ v[a]).rotate_right(32);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right(24);
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+                v[d] = (v[d] ^ v[a]).rotate_right(16);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right(63);
+            };
+            g(0, 4, 8, 12, self.m[s[0]], self.m[s[1]]);
+            g(1, 5, 9, 13, self.m[s[2]], self.m[s[3]]);
+            g(2, 6, 10, 14, self.m[s[4]], self.m[s[5]]);
+            g(3, 7, 11, 15, self.m[s[6]], self.m[s[7]]);
+            g(0, 5, 10, 15, self.m[s[8]], self.m[s[9]]);
+            g(1, 6, 11, 12, self.m[s[10]], self.m[s[11]]);
+            g(2, 7, 8, 13, self.m[s[12]], self.m[s[13]]);
+            g(3, 4, 9, 14, self.m[s[14]], self.m[s[15]]);
+        }
+
+        std::array::from_fn(|i| self.h[i] ^ v[i] ^ v[i + 8])
+    }
+
+    /// Output bytes of the blake2f precompile, the state vector after the
+    /// compression.
+    pub fn output_bytes(&self) -> Vec<u8> {
+        self.output()
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect()
+    }
+}
@@ -2,16 +2,16 @@
 
 use super::{
     get_call_memory_offset_length, get_create_init_code, BlockContext, Blocks, Call, CallContext,
-    CallKind, CodeSource, CopyEvent, ExecState, ExecStep, ExpEvent, PrecompileEvent, Transaction,
-    TransactionContext,
+    CallKind, CodeSource, CopyEvent, CopyEventOrigin, ExecState, ExecStep, ExpEvent,
+    PrecompileEvent, Transaction, TransactionContext,
 };
 #[cfg(feature = "scroll")]
 use crate::util::KECCAK_CODE_HASH_EMPTY;
 use crate::{
     circuit_input_builder::execution::{CopyEventPrevBytes, CopyEventSteps, CopyEventStepsBuilder},
     error::{
-        get_step_reported_error, ContractAddressCollisionError, DepthError, ExecError,
-        InsufficientBalanceError, NonceUintOverflowError,
+        get_step_reported_error, CapacityResource, ContractAddressCollisionError, DepthError,
+        ExecError, InsufficientBalanceError, NonceUintOverflowError,
     },
     exec_trace::OperationRef,
     operation::{
@@ -19,7 +19,7 @@ use crate::{
         StackOp, Target, TxAccessListAccountOp, TxAccessListAccountStorageOp, TxLogField, TxLogOp,
         TxReceiptField, TxReceiptOp, RW,
     },
-    precompile::PrecompileCalls,
+    precompile::{PrecompileCalls, LAST_PRECOMPILE_ADDRESS},
     Error,
 };
 use eth_types::{
@@ -192,11 +192,60 @@ impl<'a> CircuitInputStateRef<'a> {
 
         if rwc > effective_limit && cfg!(feature = "strict-ccc") {
             log::error!("rwc > max_rws, rwc={}, max_rws={}", rwc, max_rws);
-            return Err(Error::InternalError("rws not enough"));
+            return Err(Error::CapacityExceeded {
+                resource: CapacityResource::Rws,
+                used: rwc,
+                limit: effective_limit,
+            });
         };
         Ok(())
     }
 
+    /// Check whether the ECC precompile calls will overflow the ECC circuit
+    /// limits, which the circuit could not be assigned with.
+    pub fn check_ec_ops_limit(&self) -> Result<(), Error> {
+        let max_ec_ops = self.block.circuits_params.max_ec_ops;
+        let usage = self.block.precompile_events.usage();
+        for (resource, used, limit) in [
+            (CapacityResource::EcAdd, usage.ec_add, max_ec_ops.ec_add),
+            (CapacityResource::EcMul, usage.ec_mul, max_ec_ops.ec_mul),
+            (
+                CapacityResource::EcPairing,
+                usage.ec_pairing,
+                max_ec_ops.ec_pairing,
+            ),
+        ] {
+            if used > limit {
+                log::error!("{resource:?} > max, used={used}, max={limit}");
+                return Err(Error::CapacityExceeded {
+                    resource,
+                    used,
+                    limit,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check whether the blake2f precompile calls fit in `max_blake2f_rounds`,
+    /// if not 0.
+    pub fn check_blake2f_rounds_limit(&self) -> Result<(), Error> {
+        let limit = self.block.circuits_params.max_blake2f_rounds;
+        let usage = self.block.precompile_events.usage();
+        if limit != 0 && usage.blake2f_rounds > limit {
+            log::error!(
+                "blake2f rounds > max, used={}, max={limit}",
+                usage.blake2f_rounds
+            );
+            return Err(Error::CapacityExceeded {
+                resource: CapacityResource::Blake2fRounds,
+                used: usage.blake2f_rounds,
+                limit,
+            });
+        }
+        Ok(())
+    }
+
     /// Push a read type [`CallContextOp`] into the
     /// [`OperationContainer`](crate::operation::OperationContainer) with
     /// the next [`RWCounter`](crate::operation::RWCounter)  and then adds a
@@ -328,7 +377,7 @@ impl<'a> CircuitInputStateRef<'a> {
         address: MemoryAddress, //Caution: make sure this address = slot passing
         value: Word,
     ) -> Result<Vec<u8>, Error> {
-        assert_eq!(address.0 % 32, 0);
+        check_eq!(address.0 % 32, 0);
         let mem = &mut self.call_ctx_mut()?.memory;
         let value_prev = mem.read_word(address);
         let value_prev_bytes = value_prev.to_be_bytes();
@@ -1000,7 +1049,7 @@ impl<'a> CircuitInputStateRef<'a> {
     pub(crate) fn create2_address(&self, _step: &GethExecStep) -> Result<Address, Error> {
         let call_ctx = self.call_ctx()?;
         #[cfg(feature = "enable-stack")]
-        assert_eq!(call_ctx.stack, _step.stack);
+        check_eq!(call_ctx.stack, _step.stack);
         let salt = call_ctx.stack.nth_last(3)?;
         let init_code = get_create_init_code(call_ctx)?.to_vec();
         let address = get_create2_address(self.call()?.address, salt.to_be_bytes(), init_code);
@@ -1051,7 +1100,7 @@ impl<'a> CircuitInputStateRef<'a> {
 
     /// Check if address is a precompiled or not.
     pub fn is_precompiled(&self, address: &Address) -> bool {
-        address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19])
+        address.0[0..19] == [0u8; 19] && (1..=LAST_PRECOMPILE_ADDRESS).contains(&address.0[19])
     }
 
     /// Parse [`Call`] from a *CALL*/CREATE* step without information about success and persistent.
@@ -1061,7 +1110,7 @@ impl<'a> CircuitInputStateRef<'a> {
         let caller_ctx = self.call_ctx()?.clone();
         let stack = &caller_ctx.stack;
         #[cfg(feature = "enable-stack")]
-        assert_eq!(stack, &step.stack);
+        check_eq!(stack, &step.stack);
 
         let (caller_address, address, value) = match kind {
             CallKind::Call => (
@@ -1355,8 +1404,8 @@ impl<'a> CircuitInputStateRef<'a> {
             let length = length.expect("length not set");
             #[cfg(feature = "enable-stack")]
             {
-                assert_eq!(offset, step.stack.nth_last(0)?);
-                assert_eq!(length, step.stack.nth_last(1)?);
+                check_eq!(offset, step.stack.nth_last(0)?);
+                check_eq!(length, step.stack.nth_last(1)?);
             }
             let code = callee_memory.read_chunk(MemoryRange::new_with_length(
                 offset.low_u64(),
@@ -1400,7 +1449,7 @@ impl<'a> CircuitInputStateRef<'a> {
             {
                 let offset = offset.expect("offset not set");
                 #[cfg(feature = "enable-stack")]
-                assert_eq!(offset, step.stack.nth_last(0)?);
+                check_eq!(offset, step.stack.nth_last(0)?);
                 offset.low_u64()
             } else {
                 // common err, call empty, call precompile
@@ -1452,8 +1501,8 @@ impl<'a> CircuitInputStateRef<'a> {
                     let length = length.expect("length not set");
                     #[cfg(feature = "enable-stack")]
                     {
-                        assert_eq!(offset, geth_step.stack.nth_last(0)?);
-                        assert_eq!(length, geth_step.stack.nth_last(1)?);
+                        check_eq!(offset, geth_step.stack.nth_last(0)?);
+                        check_eq!(length, geth_step.stack.nth_last(1)?);
                     }
                     // This is the convention we are using for memory addresses so that there is no
                     // memory expansion cost when the length is 0.
@@ -1524,7 +1573,7 @@ impl<'a> CircuitInputStateRef<'a> {
             .get(1)
             .ok_or(Error::InternalError("invalid index 1"))?;
         #[cfg(feature = "enable-stack")]
-        assert_eq!(self.caller_ctx()?.stack, geth_step_next.stack);
+        check_eq!(self.caller_ctx()?.stack, geth_step_next.stack);
         self.call_context_read(
             exec_step,
             call.call_id,
@@ -1628,9 +1677,19 @@ impl<'a> CircuitInputStateRef<'a> {
         Ok(())
     }
 
-    /// Push a copy event to the state.
-    pub fn push_copy(&mut self, step: &mut ExecStep, event: CopyEvent) {
+    /// Push a copy event to the state, recording `step` as its origin.
+    pub fn push_copy(&mut self, step: &mut ExecStep, mut event: CopyEvent) {
         step.copy_rw_counter_delta += event.rw_counter_delta();
+        event.origin = Some(CopyEventOrigin {
+            tx_id: self.tx_ctx.id(),
+            step_index: self.tx.steps().len(),
+            call_id: self.tx.calls()[step.call_index].call_id,
+            pc: step.pc,
+            opcode: match step.exec_state {
+                ExecState::Op(opcode) => Some(opcode),
+                _ => None,
+            },
+        });
         self.block.add_copy_event(event);
     }
 
@@ -1690,7 +1749,7 @@ impl<'a> CircuitInputStateRef<'a> {
 
         let call_ctx = self.call_ctx()?;
         #[cfg(feature = "enable-stack")]
-        assert_eq!(call_ctx.stack, step.stack);
+        check_eq!(call_ctx.stack, step.stack);
         // get value first if call/create
         let value = match step.op {
             OpcodeId::CALL | OpcodeId::CALLCODE => call_ctx.stack.nth_last(2)?,
@@ -2021,18 +2080,29 @@ impl<'a> CircuitInputStateRef<'a> {
         Ok((copy_steps, prev_bytes))
     }
 
+    /// Generate the copy steps of the return data of a precompile call, from
+    /// its result to the caller's memory.
+    ///
+    /// The result was written to the callee's memory in the same step by
+    /// [`Self::gen_copy_steps_for_precompile_callee_memory`], so reading those
+    /// words back is redundant when the whole result is returned: the copy
+    /// then has the result's RLC as source, which the circuit checks equal to
+    /// the RLC of the written output, and only writes the caller's memory.
+    /// That is the case when write steps are returned alone, with no read
+    /// steps.  A truncated result is read back from the callee's memory.
     pub(crate) fn gen_copy_steps_for_precompile_returndata(
         &mut self,
         exec_step: &mut ExecStep,
         dst_addr: impl Into<MemoryAddress>,
         copy_length: impl Into<MemoryAddress>,
         result: &[u8],
-    ) -> Result<(CopyEventSteps, CopyEventSteps, CopyEventPrevBytes), Error> {
+    ) -> Result<(Option<CopyEventSteps>, CopyEventSteps, CopyEventPrevBytes), Error> {
         let copy_length = copy_length.into().0;
         if copy_length == 0 {
-            return Ok((vec![], vec![], vec![]));
+            return Ok((None, vec![], vec![]));
         }
-        assert!(copy_length <= result.len());
+        check!(copy_length <= result.len());
+        let elide_reads = copy_length == result.len();
 
         let (src_range, dst_range, write_slot_bytes) = combine_copy_slot_bytes(
             0,
@@ -2046,9 +2116,11 @@ impl<'a> CircuitInputStateRef<'a> {
         let read_slot_bytes = MemoryRef(result).read_chunk(src_range);
         debug_assert_eq!(read_slot_bytes.len(), write_slot_bytes.len());
 
-        let read_steps = CopyEventStepsBuilder::memory_range(src_range)
-            .source(read_slot_bytes.as_slice())
-            .build();
+        let read_steps = (!elide_reads).then(|| {
+            CopyEventStepsBuilder::memory_range(src_range)
+                .source(read_slot_bytes.as_slice())
+                .build()
+        });
 
         let write_steps = CopyEventStepsBuilder::memory_range(dst_range)
             .source(write_slot_bytes.as_slice())
@@ -2060,8 +2132,10 @@ impl<'a> CircuitInputStateRef<'a> {
         let mut prev_bytes = vec![];
         for (read_chunk, write_chunk) in read_slot_bytes.chunks(32).zip(write_slot_bytes.chunks(32))
         {
-            let value = self.memory_read_word(exec_step, src_chunk_index.into())?;
-            debug_assert_eq!(Word::from_big_endian(read_chunk), value);
+            if !elide_reads {
+                let value = self.memory_read_word(exec_step, src_chunk_index.into())?;
+                debug_assert_eq!(Word::from_big_endian(read_chunk), value);
+            }
             src_chunk_index += 32;
 
             let mut prev_bytes_write = self.memory_write_caller(
@@ -2084,7 +2158,7 @@ impl<'a> CircuitInputStateRef<'a> {
         dst_addr: impl Into<MemoryAddress>,
         copy_length: impl Into<MemoryAddress>,
     ) -> Result<(CopyEventSteps, CopyEventPrevBytes), Error> {
-        assert!(self.call()?.is_root);
+        check!(self.call()?.is_root);
 
         let copy_length = copy_length.into().0;
         if copy_length == 0 {
@@ -2124,7 +2198,7 @@ impl<'a> CircuitInputStateRef<'a> {
         dst_addr: impl Into<MemoryAddress>,
         copy_length: impl Into<MemoryAddress>,
     ) -> Result<(CopyEventSteps, CopyEventSteps, Vec<u8>), Error> {
-        assert!(!self.call()?.is_root);
+        check!(!self.call()?.is_root);
 
         let copy_length = copy_length.into().0;
         if copy_length == 0 {
@@ -2385,7 +2459,7 @@ impl<'a> CircuitInputStateRef<'a> {
         dst_chunk_index: usize,
         prev_bytes: &mut Vec<u8>,
     ) -> Result<(), Error> {
-        assert_eq!(write_chunk.len(), 32);
+        check_eq!(write_chunk.len(), 32);
         let write_word = Word::from_big_endian(write_chunk);
         let mut prev_bytes_write =
             self.memory_write_word(exec_step, dst_chunk_index.into(), write_word)?;
@@ -2404,7 +2478,7 @@ impl<'a> CircuitInputStateRef<'a> {
         full_length: usize,
         result: &mut Vec<u8>,
     ) -> Result<(), Error> {
-        assert!(full_length >= chunks.len() && full_length % 32 == 0);
+        check!(full_length >= chunks.len() && full_length % 32 == 0);
         let padding: Vec<_> = repeat(0).take(full_length - chunks.len()).collect();
 
         let mut chunk_index = chunk_index;
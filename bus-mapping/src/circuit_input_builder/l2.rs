@@ -1,13 +1,15 @@
 pub use super::block::{BlockContext, Blocks};
 use crate::{
-    circuit_input_builder::{self, Block, CircuitInputBuilder, CircuitsParams},
+    circuit_input_builder::{
+        self, build_state_code_db, Block, BuildMonitor, CircuitInputBuilder, CircuitsParams,
+    },
     error::Error,
 };
 use eth_types::{
     self,
     l2_types::{trace::collect_codes, BlockTrace, StorageTrace},
     state_db::{self, CodeDB, StateDB},
-    Address, EthBlock, ToWord, Word, H256,
+    Address, EIP1186ProofResponse, EthBlock, ToBigEndian, ToWord, Word, H256,
 };
 use ethers_core::types::Bytes;
 use mpt_zktrie::state::ZktrieState;
@@ -36,8 +38,9 @@ impl CircuitInputBuilder {
             .into_iter()
             .map(From::from)
             .collect();
-        assert_eq!(
-            self.block.chain_id, block_trace.chain_id,
+        check_eq!(
+            self.block.chain_id,
+            block_trace.chain_id,
             "unexpected chain id in new block_trace"
         );
         // Scroll EVM disables BLOCKHASH opcode, so here we don't need any hashes.
@@ -106,14 +109,93 @@ impl CircuitInputBuilder {
             block: block.clone(),
             block_ctx: BlockContext::new(),
             mpt_init_state: Some(mpt_init_state),
+            max_call_depth: eth_types::MAX_CALL_DEPTH,
+            partial_state: None,
+            monitor: Default::default(),
         }
     }
 
+    /// Build the zktrie state of `state_root` from the `eth_getProof` responses
+    /// of the touched accounts and slots, instead of the storage trace of a
+    /// l2 block trace.
+    pub fn mpt_init_state_from_proofs(
+        state_root: H256,
+        proofs: &[EIP1186ProofResponse],
+    ) -> Result<ZktrieState, Error> {
+        let storage_proofs: Vec<(Address, H256, &[Bytes])> = proofs
+            .iter()
+            .flat_map(|proof| {
+                proof.storage_proof.iter().map(|storage_proof| {
+                    (
+                        proof.address,
+                        H256(storage_proof.key.to_be_bytes()),
+                        storage_proof.proof.as_slice(),
+                    )
+                })
+            })
+            .collect();
+        ZktrieState::from_trace_with_additional(
+            state_root,
+            proofs.iter().map(|proof| {
+                (
+                    &proof.address,
+                    proof.account_proof.iter().map(Bytes::as_ref),
+                )
+            }),
+            storage_proofs
+                .iter()
+                .map(|(address, key, proof)| (address, key, proof.iter().map(Bytes::as_ref))),
+            std::iter::empty(),
+        )
+        .map_err(Error::IoError)
+    }
+
+    /// Create a new CircuitInputBuilder on top of the state of `state_root`,
+    /// from the `eth_getProof` responses of all the accounts and slots touched
+    /// by the txs to replay and the codes of the touched contracts. Unlike
+    /// [`Self::new_from_l2_trace`], the txs can then be replayed with
+    /// [`Self::handle_block`] from the traces of any RPC node serving proofs.
+    pub fn new_from_proofs(
+        chain_id: u64,
+        circuits_params: CircuitsParams,
+        state_root: H256,
+        proofs: Vec<EIP1186ProofResponse>,
+        codes: HashMap<Address, Vec<u8>>,
+    ) -> Result<Self, Error> {
+        let mpt_init_state = Self::mpt_init_state_from_proofs(state_root, &proofs)?;
+        let (sdb, code_db) = build_state_code_db(proofs, codes);
+        let mut builder_block = circuit_input_builder::Blocks::init(chain_id, circuits_params);
+        builder_block.prev_state_root = state_root;
+        Ok(Self::new_with_trie_state(
+            sdb,
+            code_db,
+            mpt_init_state,
+            &builder_block,
+        ))
+    }
+
     /// Create a new CircuitInputBuilder from the given `l2_trace` and `circuits_params`
     pub fn new_from_l2_trace(
         circuits_params: CircuitsParams,
         l2_trace: BlockTrace,
         light_mode: bool,
+    ) -> Result<Self, Error> {
+        Self::new_from_l2_trace_with_monitor(
+            circuits_params,
+            l2_trace,
+            light_mode,
+            BuildMonitor::default(),
+        )
+    }
+
+    /// Create a new CircuitInputBuilder from the given `l2_trace` and
+    /// `circuits_params`, with `monitor` reporting the progress of the
+    /// witness generation of the trace.
+    pub fn new_from_l2_trace_with_monitor(
+        circuits_params: CircuitsParams,
+        l2_trace: BlockTrace,
+        light_mode: bool,
+        monitor: BuildMonitor,
     ) -> Result<Self, Error> {
         let chain_id = l2_trace.chain_id;
 
@@ -182,6 +264,9 @@ impl CircuitInputBuilder {
             block: builder_block,
             block_ctx: BlockContext::new(),
             mpt_init_state,
+            max_call_depth: eth_types::MAX_CALL_DEPTH,
+            partial_state: None,
+            monitor,
         };
 
         builder.apply_l2_trace(l2_trace)?;
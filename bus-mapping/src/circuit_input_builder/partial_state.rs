@@ -0,0 +1,166 @@
+//! Witness building from a partial state: only the accounts and slots touched
+//! by the block, with their proofs, as in a stateless execution witness.
+
+use super::{build_state_code_db, AccessSet, Blocks, CircuitInputBuilder, CircuitsParams};
+use crate::error::Error;
+use eth_types::{state_db::CodeDB, Address, EIP1186ProofResponse, EthBlock, GethExecTrace, H256};
+use std::collections::HashMap;
+
+impl CircuitInputBuilder {
+    /// Create a new CircuitInputBuilder from a partial state: the
+    /// `eth_getProof` responses against `state_root` of all the accounts and
+    /// slots touched by the blocks to handle, and the codes of the touched
+    /// contracts. The proofs are verified, and handling a block fails if it
+    /// accesses an account, slot or code missing from the partial state.
+    pub fn new_from_partial_state(
+        chain_id: u64,
+        circuits_params: CircuitsParams,
+        state_root: H256,
+        proofs: Vec<EIP1186ProofResponse>,
+        codes: HashMap<Address, Vec<u8>>,
+    ) -> Result<Self, Error> {
+        #[cfg(not(feature = "scroll"))]
+        verify_proofs(state_root, &proofs)?;
+
+        let mut partial_state = AccessSet::default();
+        for proof in &proofs {
+            partial_state.add_account(proof.address);
+            for storage_proof in &proof.storage_proof {
+                partial_state.add_storage(proof.address, storage_proof.key);
+            }
+        }
+
+        #[cfg(feature = "scroll")]
+        let mpt_init_state = Self::mpt_init_state_from_proofs(state_root, &proofs)?;
+        let mut blocks = Blocks::init(chain_id, circuits_params);
+        blocks.prev_state_root = state_root;
+        #[cfg(not(feature = "scroll"))]
+        {
+            blocks.mpt_proofs = proofs
+                .iter()
+                .flat_map(|proof| eth_types::mpt_proof::MptProof::from_eip1186(state_root, proof))
+                .collect();
+        }
+        let (sdb, code_db) = build_state_code_db(proofs, codes);
+
+        let mut builder = Self::new(sdb, code_db, &blocks);
+        #[cfg(feature = "scroll")]
+        {
+            builder.mpt_init_state = Some(mpt_init_state);
+        }
+        builder.partial_state = Some(partial_state);
+        Ok(builder)
+    }
+
+    /// Check that the state accessed by `geth_traces`, and the coinbase of
+    /// `eth_block`, is in the partial state of the builder, if any.
+    pub(crate) fn check_partial_state(
+        &self,
+        eth_block: &EthBlock,
+        geth_traces: &[GethExecTrace],
+    ) -> Result<(), Error> {
+        let Some(partial_state) = &self.partial_state else {
+            return Ok(());
+        };
+
+        let mut accessed = AccessSet::default();
+        if let Some(coinbase) = eth_block.author {
+            accessed.add_account(coinbase);
+        }
+        for geth_trace in geth_traces {
+            accessed.extend_from_traces(&geth_trace.prestate);
+        }
+
+        for (address, keys) in &accessed.state {
+            let provided = partial_state
+                .state
+                .get(address)
+                .ok_or(Error::AccountNotFound(*address))?;
+            if let Some(key) = keys.iter().find(|key| !provided.contains(key)) {
+                return Err(Error::StorageKeyNotFound(*address, *key));
+            }
+        }
+        for address in &accessed.code {
+            let (_, account) = self.sdb.get_account(address);
+            if account.code_hash != CodeDB::empty_code_hash()
+                && !account.code_hash.is_zero()
+                && !self.code_db.0.contains_key(&account.code_hash)
+            {
+                return Err(Error::CodeNotFound(account.code_hash));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Verify the hexary trie proofs of the partial state against `state_root`.
+#[cfg(not(feature = "scroll"))]
+fn verify_proofs(state_root: H256, proofs: &[EIP1186ProofResponse]) -> Result<(), Error> {
+    use eth_types::mpt_proof::{verify_account_proof, verify_storage_proof};
+
+    for proof in proofs {
+        let invalid = |key| Error::InvalidStateProof {
+            address: proof.address,
+            key,
+        };
+        let account = verify_account_proof(state_root, proof.address, &proof.account_proof)
+            .map_err(|_| invalid(None))?;
+        let matches = match account {
+            Some(account) => {
+                account.nonce == proof.nonce
+                    && account.balance == proof.balance
+                    && account.storage_root == proof.storage_hash
+                    && account.code_hash == proof.code_hash
+            }
+            None => proof.nonce.is_zero() && proof.balance.is_zero(),
+        };
+        if !matches {
+            return Err(invalid(None));
+        }
+        for storage_proof in &proof.storage_proof {
+            let value =
+                verify_storage_proof(proof.storage_hash, storage_proof.key, &storage_proof.proof)
+                    .map_err(|_| invalid(Some(storage_proof.key)))?;
+            if value != storage_proof.value {
+                return Err(invalid(Some(storage_proof.key)));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{circuit_input_builder::AccessSet, mock::BlockData, Error};
+    use eth_types::{bytecode, geth_types::GethData};
+    use mock::TestContext;
+
+    #[test]
+    fn handle_block_checks_partial_state() {
+        let code = bytecode! {
+            PUSH1(0x00)
+            SLOAD
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder.partial_state = Some(AccessSet::from_geth_data(&block));
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let mut partial_state = AccessSet::from_geth_data(&block);
+        for keys in partial_state.state.values_mut() {
+            keys.clear();
+        }
+        builder.partial_state = Some(partial_state);
+        assert!(matches!(
+            builder.handle_block(&block.eth_block, &block.geth_traces),
+            Err(Error::StorageKeyNotFound(..))
+        ));
+    }
+}
@@ -0,0 +1,210 @@
+//! Progress reports, cooperative cancellation and self-check of the witness
+//! generation of
+//! [`CircuitInputBuilder::handle_block`](super::CircuitInputBuilder::handle_block).
+
+use crate::error::Error;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+/// Progress of the witness generation, reported after each geth step.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildProgress {
+    /// Index in the chunk of the tx being handled.
+    pub tx_index: usize,
+    /// Number of geth steps handled, over all txs.
+    pub steps: usize,
+    /// Number of rws generated.
+    pub rws: usize,
+}
+
+/// Token cancelling a witness generation, e.g. from another thread. The
+/// generation stops at the next geth step with [`Error::Cancelled`].
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token which is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the witness generations using this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+
+    /// Return whether the token is cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+type ProgressCallback = Arc<dyn Fn(&BuildProgress) + Send + Sync>;
+
+/// Monitor of the witness generation of a
+/// [`CircuitInputBuilder`](super::CircuitInputBuilder): reports its progress,
+/// stops it once cancelled or past its deadline, and optionally self-checks
+/// each step.
+#[derive(Clone, Default)]
+pub struct BuildMonitor {
+    on_progress: Option<ProgressCallback>,
+    cancellation: Option<CancellationToken>,
+    deadline: Option<Instant>,
+    self_check: bool,
+    progress: BuildProgress,
+}
+
+impl fmt::Debug for BuildMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BuildMonitor")
+            .field("on_progress", &self.on_progress.is_some())
+            .field("cancellation", &self.cancellation)
+            .field("deadline", &self.deadline)
+            .field("self_check", &self.self_check)
+            .field("progress", &self.progress)
+            .finish()
+    }
+}
+
+impl BuildMonitor {
+    /// Call `on_progress` after each geth step.
+    pub fn with_progress(
+        self,
+        on_progress: impl Fn(&BuildProgress) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            on_progress: Some(Arc::new(on_progress)),
+            ..self
+        }
+    }
+
+    /// Stop the witness generation once `cancellation` is cancelled.
+    pub fn with_cancellation(self, cancellation: CancellationToken) -> Self {
+        Self {
+            cancellation: Some(cancellation),
+            ..self
+        }
+    }
+
+    /// Stop the witness generation once past `deadline`.
+    pub fn with_deadline(self, deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    /// Re-execute each geth step with a lightweight interpreter, compare its
+    /// stack, memory and storage against the operations of the builder and
+    /// the state captured in the trace, and stop at the first difference with
+    /// [`Error::SelfCheckFailed`]. It is slow, and the trace captures the
+    /// state only with the `enable-stack`, `enable-memory` and
+    /// `enable-storage` features.
+    pub fn with_self_check(self) -> Self {
+        Self {
+            self_check: true,
+            ..self
+        }
+    }
+
+    /// Return whether each geth step is self-checked.
+    pub(crate) fn self_check(&self) -> bool {
+        self.self_check
+    }
+
+    /// Return the progress of the witness generation.
+    pub fn progress(&self) -> BuildProgress {
+        self.progress
+    }
+
+    /// Record a geth step of the tx `tx_index`, with `rws` rws generated so
+    /// far, and return [`Error::Cancelled`] if the generation must stop.
+    pub(crate) fn step(&mut self, tx_index: usize, rws: usize) -> Result<(), Error> {
+        self.progress = BuildProgress {
+            tx_index,
+            steps: self.progress.steps + 1,
+            rws,
+        };
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(&self.progress);
+        }
+        let cancelled = self
+            .cancellation
+            .as_ref()
+            .map_or(false, CancellationToken::is_cancelled)
+            || self
+                .deadline
+                .map_or(false, |deadline| Instant::now() >= deadline);
+        if cancelled {
+            log::warn!("witness generation cancelled at {:?}", self.progress);
+            return Err(Error::Cancelled(self.progress));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::BlockData;
+    use eth_types::{bytecode, geth_types::GethData};
+    use mock::TestContext;
+    use std::sync::atomic::AtomicUsize;
+
+    fn block() -> GethData {
+        let code = bytecode! {
+            PUSH1(0x00)
+            PUSH1(0x01)
+            ADD
+            STOP
+        };
+        TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn handle_block_reports_progress() {
+        let block = block();
+        let steps = Arc::new(AtomicUsize::new(0));
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder.monitor = BuildMonitor::default().with_progress({
+            let steps = steps.clone();
+            move |progress| steps.store(progress.steps, Ordering::Relaxed)
+        });
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        assert_eq!(steps.load(Ordering::Relaxed), 4);
+        assert_eq!(builder.monitor.progress().steps, 4);
+    }
+
+    #[test]
+    fn handle_block_stops_once_cancelled() {
+        let block = block();
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder.monitor = BuildMonitor::default().with_cancellation(cancellation);
+        assert!(matches!(
+            builder.handle_block(&block.eth_block, &block.geth_traces),
+            Err(Error::Cancelled(BuildProgress { steps: 1, .. }))
+        ));
+    }
+
+    #[test]
+    fn handle_block_passes_self_check() {
+        let block = block();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder.monitor = BuildMonitor::default().with_self_check();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+    }
+}
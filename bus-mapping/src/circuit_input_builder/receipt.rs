@@ -0,0 +1,131 @@
+//! Receipts of the transactions of the blocks of a chunk.
+
+use super::Blocks;
+use crate::Error;
+use eth_types::{mpt_proof::ordered_trie_root, H256};
+use ethers_core::{
+    types::{Bloom, BloomInput, Log},
+    utils::rlp::{Encodable, RlpStream},
+};
+use serde::{Deserialize, Serialize};
+
+/// Receipt of a transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Receipt {
+    /// Denotes the ID of the tx.
+    pub id: usize,
+    /// EIP-2718 type byte of the tx, 0 for legacy txs.
+    pub tx_type_id: u64,
+    /// Denotes whether or not the tx was executed successfully.
+    pub status: u8,
+    /// Denotes the cumulative gas used by the txs of the block up to this
+    /// one.
+    pub cumulative_gas_used: u64,
+    /// Represents the 256-bytes bloom filter.
+    pub bloom: Bloom,
+    /// List of logs generated by the tx.
+    pub logs: Vec<Log>,
+}
+
+impl Receipt {
+    /// Bloom filter of the addresses and topics of `logs`.
+    pub fn logs_bloom(logs: &[Log]) -> Bloom {
+        let mut bloom = Bloom::default();
+        for log in logs {
+            bloom.accrue(BloomInput::Raw(log.address.as_bytes()));
+            for topic in &log.topics {
+                bloom.accrue(BloomInput::Raw(topic.as_bytes()));
+            }
+        }
+        bloom
+    }
+
+    /// Encoding of the receipt in the receipts trie, the RLP of the receipt
+    /// prefixed by the type byte of typed txs.
+    pub fn encode_envelope(&self) -> Vec<u8> {
+        let rlp = self.rlp_bytes();
+        if self.tx_type_id == 0 {
+            rlp.to_vec()
+        } else {
+            [&[self.tx_type_id as u8], rlp.as_ref()].concat()
+        }
+    }
+
+    /// Root of the receipts trie of a block with the txs of `receipts`.
+    pub fn root<'a>(receipts: impl IntoIterator<Item = &'a Receipt>) -> H256 {
+        let receipts: Vec<_> = receipts.into_iter().map(Receipt::encode_envelope).collect();
+        ordered_trie_root(&receipts)
+    }
+}
+
+impl Encodable for Receipt {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(4);
+        s.append(&self.status);
+        s.append(&self.cumulative_gas_used);
+        s.append(&self.bloom);
+        s.begin_list(self.logs.len());
+        for log in self.logs.iter() {
+            s.begin_list(3);
+            s.append(&log.address);
+            s.append_list(&log.topics);
+            s.append(&log.data.0);
+        }
+    }
+}
+
+impl Blocks {
+    /// Receipts of the txs of the chunk, in order. The cumulative gas used
+    /// starts over at each block.
+    pub fn receipts(&self) -> Vec<Receipt> {
+        let mut cumulative_gas_used = 0;
+        let mut block_num = None;
+        self.txs
+            .iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                if block_num != Some(tx.block_num) {
+                    block_num = Some(tx.block_num);
+                    cumulative_gas_used = 0;
+                }
+                cumulative_gas_used += tx.gas_used;
+                Receipt {
+                    id: index + 1,
+                    tx_type_id: self.tx_type_config.type_id(tx.tx_type),
+                    status: tx.is_success() as u8,
+                    cumulative_gas_used,
+                    bloom: Receipt::logs_bloom(&tx.logs),
+                    logs: tx.logs.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Root of the receipts of the txs of block `block_num`.
+    pub fn receipts_root(&self, block_num: u64) -> H256 {
+        let receipts = self.receipts();
+        Receipt::root(
+            self.txs
+                .iter()
+                .zip(&receipts)
+                .filter(|(tx, _)| tx.block_num == block_num)
+                .map(|(_, receipt)| receipt),
+        )
+    }
+
+    /// Return [`Error::InvalidReceiptsRoot`] if the receipts root of a block
+    /// header is not the root of the receipts of the txs of the block.
+    pub fn check_receipts_roots(&self) -> Result<(), Error> {
+        for (&block_num, block) in &self.blocks {
+            let computed_root = self.receipts_root(block_num);
+            if computed_root != block.receipts_root {
+                return Err(Error::InvalidReceiptsRoot {
+                    block_num,
+                    header_root: block.receipts_root,
+                    computed_root,
+                });
+            }
+        }
+        Ok(())
+    }
+}
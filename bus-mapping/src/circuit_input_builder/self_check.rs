@@ -0,0 +1,1048 @@
+//! Self-check of the witness generation: each geth step is re-executed by a
+//! lightweight interpreter, whose stack, memory and storage are compared
+//! against the operations generated by the builder for the step and against
+//! the state captured in the trace, so that a bus-mapping bug is reported at
+//! the step which introduced it rather than as a constraint failure of the
+//! circuits.
+//!
+//! The interpreter executes the stack, arithmetic, bitwise, memory and
+//! storage opcodes. The effects of the other opcodes, e.g. the calls, the
+//! copies and the environment reads, are taken from the operations of the
+//! builder, whose reads are still checked against the state of the
+//! interpreter. The storage of a tx starts from the committed values read by
+//! the builder, and must end as the one of the builder.
+//!
+//! The trace captures the state only with the `enable-stack`, `enable-memory`
+//! and `enable-storage` features, for the stack, the memory and the storage
+//! read by SLOAD. Without them, the builder is only compared against the
+//! interpreter.
+
+use super::{Call, ExecState, ExecStep};
+use crate::{
+    operation::{
+        MemoryOp, Operation, OperationContainer, StackOp, StorageOp, Target, TransientStorageOp, RW,
+    },
+    Error,
+};
+use eth_types::{
+    evm_types::{Memory, OpcodeId, Stack},
+    state_db::{CodeDB, StateDB},
+    Address, GethExecStep, ToBigEndian, Word, U512,
+};
+use ethers_core::utils::keccak256;
+use std::{collections::HashMap, fmt, sync::Once};
+
+/// Largest memory offset of a step which doesn't run out of gas.
+const MAX_MEMORY_OFFSET: u64 = u32::MAX as u64;
+
+#[cfg(not(all(
+    feature = "enable-stack",
+    feature = "enable-memory",
+    feature = "enable-storage"
+)))]
+static UNCAPTURED_TRACE_WARNING: Once = Once::new();
+
+/// Storage slot, persistent or transient.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Slot {
+    Storage(Address, Word),
+    Transient(Address, Word),
+}
+
+/// State of a call being executed.
+#[derive(Debug, Default)]
+struct Frame {
+    stack: Stack,
+    memory: Memory,
+    /// Whether the memory expansion of the last step of the call wasn't
+    /// executed, so that the size of the memory is the one of the builder.
+    memory_size_unknown: bool,
+    /// Previous values of the slots written by the call and its successful
+    /// callees, restored if the call reverts.
+    journal: Vec<(Slot, Word)>,
+}
+
+/// Operations generated by the builder for a step.
+struct StepOps<'a> {
+    stack: Vec<&'a Operation<StackOp>>,
+    memory: Vec<&'a Operation<MemoryOp>>,
+    storage: Vec<&'a Operation<StorageOp>>,
+    transient_storage: Vec<&'a Operation<TransientStorageOp>>,
+}
+
+impl<'a> StepOps<'a> {
+    fn new(exec_step: &ExecStep, container: &'a OperationContainer) -> Self {
+        let mut ops = Self {
+            stack: Vec::new(),
+            memory: Vec::new(),
+            storage: Vec::new(),
+            transient_storage: Vec::new(),
+        };
+        for op_ref in &exec_step.bus_mapping_instance {
+            let index = op_ref.as_usize();
+            match op_ref.target() {
+                Target::Stack => ops.stack.push(&container.stack[index]),
+                Target::Memory => ops.memory.push(&container.memory[index]),
+                Target::Storage => ops.storage.push(&container.storage[index]),
+                Target::TransientStorage => {
+                    ops.transient_storage
+                        .push(&container.transient_storage[index]);
+                }
+                _ => {}
+            }
+        }
+        ops
+    }
+}
+
+/// Interpreter re-executing the geth steps of a tx, see the [module
+/// documentation](self).
+#[derive(Debug)]
+pub(crate) struct TxSelfCheck {
+    /// Calls being executed, by call id, the current one last.
+    frames: Vec<(usize, Frame)>,
+    storage: HashMap<Slot, Word>,
+    /// Opcode and program counter of the step being checked.
+    step: (OpcodeId, usize),
+    /// Whether the trace captures the stack, i.e. some step had a stack.
+    #[cfg(feature = "enable-stack")]
+    stack_captured: bool,
+    /// Whether the trace captures the memory, i.e. some step had a memory.
+    #[cfg(feature = "enable-memory")]
+    memory_captured: bool,
+}
+
+impl TxSelfCheck {
+    pub(crate) fn new() -> Self {
+        #[cfg(not(all(
+            feature = "enable-stack",
+            feature = "enable-memory",
+            feature = "enable-storage"
+        )))]
+        UNCAPTURED_TRACE_WARNING.call_once(|| {
+            log::warn!(
+                "self-check: without the enable-stack, enable-memory and enable-storage \
+                 features, the trace doesn't capture the whole state, and the builder is \
+                 only compared against the interpreter"
+            )
+        });
+        Self {
+            frames: Vec::new(),
+            storage: HashMap::new(),
+            step: (OpcodeId::STOP, 0),
+            #[cfg(feature = "enable-stack")]
+            stack_captured: false,
+            #[cfg(feature = "enable-memory")]
+            memory_captured: false,
+        }
+    }
+
+    /// Re-execute `geth_step`, and check the `exec_steps` generated for it by
+    /// the builder, whose operations are in `container`.
+    pub(crate) fn step(
+        &mut self,
+        geth_step: &GethExecStep,
+        exec_steps: &[ExecStep],
+        calls: &[Call],
+        container: &OperationContainer,
+        code_db: &CodeDB,
+    ) -> Result<(), Error> {
+        self.step = (geth_step.op, geth_step.pc.0);
+        for (index, exec_step) in exec_steps.iter().enumerate() {
+            let call = &calls[exec_step.call_index];
+            self.enter(call.call_id, calls);
+            let ops = StepOps::new(exec_step, container);
+            // the following steps are the internal ones of precompile calls
+            if index > 0 {
+                self.apply(call.call_id, &ops)?;
+                continue;
+            }
+            if exec_step.exec_state != ExecState::Op(geth_step.op) || exec_step.pc != geth_step.pc {
+                return Err(self.mismatch(
+                    "step",
+                    (geth_step.op, geth_step.pc.0),
+                    (&exec_step.exec_state, exec_step.pc.0),
+                ));
+            }
+            self.check_sizes(exec_step)?;
+            self.check_trace(geth_step)?;
+            if exec_step.error.is_some() || !self.execute(geth_step, call, &ops, code_db)? {
+                self.apply(call.call_id, &ops)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Finish the tx, whose storage must then be the one of the builder.
+    pub(crate) fn finish(mut self, calls: &[Call], sdb: &StateDB) -> Result<(), Error> {
+        while let Some((call_id, frame)) = self.frames.pop() {
+            self.exit(call_id, frame, calls);
+        }
+        for (slot, value) in &self.storage {
+            if let Slot::Storage(address, key) = slot {
+                let found = *sdb.get_storage(address, key).1;
+                if found != *value {
+                    return Err(self.mismatch(
+                        "storage at the end of the tx",
+                        (address, key, value),
+                        (address, key, found),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Switch to the frame of the call `call_id`, new or one of the calls
+    /// being executed, in which case the calls above it have returned.
+    fn enter(&mut self, call_id: usize, calls: &[Call]) {
+        match self.frames.iter().position(|(id, _)| *id == call_id) {
+            Some(depth) => {
+                while self.frames.len() > depth + 1 {
+                    let (call_id, frame) = self.frames.pop().expect("frames above the depth");
+                    self.exit(call_id, frame, calls);
+                }
+            }
+            None => self.frames.push((call_id, Frame::default())),
+        }
+    }
+
+    /// Return from the call `call_id`, reverting its writes if it failed.
+    fn exit(&mut self, call_id: usize, frame: Frame, calls: &[Call]) {
+        let is_success = calls
+            .iter()
+            .find(|call| call.call_id == call_id)
+            .map_or(true, |call| call.is_success);
+        if is_success {
+            if let Some((_, caller)) = self.frames.last_mut() {
+                caller.journal.extend(frame.journal);
+            }
+        } else {
+            for (slot, value) in frame.journal.into_iter().rev() {
+                self.storage.insert(slot, value);
+            }
+        }
+    }
+
+    fn mismatch(
+        &self,
+        kind: &'static str,
+        expected: impl fmt::Debug,
+        found: impl fmt::Debug,
+    ) -> Error {
+        mismatch(self.step, kind, expected, found)
+    }
+
+    /// Check the stack and memory sizes of the builder before the step.
+    fn check_sizes(&mut self, exec_step: &ExecStep) -> Result<(), Error> {
+        let step = self.step;
+        let frame = &mut self.frames.last_mut().expect("a step is in a call").1;
+        if frame.stack.len() != exec_step.stack_size {
+            return Err(mismatch(
+                step,
+                "stack size",
+                frame.stack.len(),
+                exec_step.stack_size,
+            ));
+        }
+        if frame.memory_size_unknown {
+            // memory doesn't shrink
+            if exec_step.memory_size < frame.memory.len() {
+                return Err(mismatch(
+                    step,
+                    "memory size",
+                    frame.memory.len(),
+                    exec_step.memory_size,
+                ));
+            }
+            frame.memory.0.resize(exec_step.memory_size, 0);
+            frame.memory_size_unknown = false;
+        } else if frame.memory.len() != exec_step.memory_size {
+            return Err(mismatch(
+                step,
+                "memory size",
+                frame.memory.len(),
+                exec_step.memory_size,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check the state before the step against the one captured in the
+    /// trace.
+    fn check_trace(&mut self, geth_step: &GethExecStep) -> Result<(), Error> {
+        #[cfg(feature = "enable-stack")]
+        {
+            self.stack_captured |= !geth_step.stack.is_empty();
+            let stack = &self.current().stack;
+            if self.stack_captured && *stack != geth_step.stack {
+                return Err(self.mismatch("stack of the trace", &geth_step.stack, stack));
+            }
+        }
+        #[cfg(feature = "enable-memory")]
+        {
+            // the trace may not capture the memory, even with the feature
+            self.memory_captured |= !geth_step.memory.is_empty();
+            let memory = &self.current().memory;
+            if self.memory_captured && *memory != geth_step.memory {
+                return Err(self.mismatch("memory of the trace", &geth_step.memory, memory));
+            }
+        }
+        let _ = geth_step;
+        Ok(())
+    }
+
+    fn current(&self) -> &Frame {
+        &self.frames.last().expect("a step is in a call").1
+    }
+
+    fn frame_mut(&mut self, call_id: usize) -> Result<&mut Frame, Error> {
+        let step = self.step;
+        match self.frames.iter_mut().find(|(id, _)| *id == call_id) {
+            Some((_, frame)) => Ok(frame),
+            None => Err(mismatch(step, "call", "a call being executed", call_id)),
+        }
+    }
+
+    /// Apply the stack and memory operations of a step which isn't executed,
+    /// checking its reads against the state before it.
+    fn apply(&mut self, call_id: usize, ops: &StepOps) -> Result<(), Error> {
+        let step = self.step;
+        // stack size before the step, popped elements and top written, by call
+        let mut stack_effects: Vec<(usize, usize, usize, Option<usize>)> = Vec::new();
+        for op in &ops.stack {
+            let &StackOp {
+                call_id,
+                address,
+                value,
+            } = op.op();
+            let frame = self.frame_mut(call_id)?;
+            let position = match stack_effects.iter().position(|(id, ..)| *id == call_id) {
+                Some(position) => position,
+                None => {
+                    stack_effects.push((call_id, frame.stack.len(), 0, None));
+                    stack_effects.len() - 1
+                }
+            };
+            let effect = &mut stack_effects[position];
+            let index = stack_index(address.0);
+            match op.rw() {
+                RW::READ => {
+                    let found = frame.stack.0.get(index).copied();
+                    if found != Some(value) {
+                        return Err(mismatch(step, "stack", (address, found), (address, value)));
+                    }
+                    effect.2 += 1;
+                }
+                RW::WRITE => {
+                    if index >= frame.stack.len() {
+                        frame.stack.0.resize(index + 1, Word::zero());
+                    }
+                    frame.stack.0[index] = value;
+                    effect.3 = effect.3.max(Some(index));
+                }
+            }
+        }
+        for (call_id, len, pops, top) in stack_effects {
+            let len = top.map_or(len.saturating_sub(pops), |top| top + 1);
+            self.frame_mut(call_id)?.stack.0.truncate(len);
+        }
+
+        for op in &ops.memory {
+            let &MemoryOp {
+                call_id,
+                address,
+                value,
+                ..
+            } = op.op();
+            let frame = self.frame_mut(call_id)?;
+            match op.rw() {
+                RW::READ => {
+                    let found = frame.memory.read_word(address);
+                    if found != value {
+                        return Err(mismatch(step, "memory", (address, found), (address, value)));
+                    }
+                }
+                RW::WRITE => {
+                    frame.memory.extend_at_least(address.0 + 32);
+                    frame.memory.0[address.0..address.0 + 32].copy_from_slice(&value.to_be_bytes());
+                    frame.memory_size_unknown = true;
+                }
+            }
+        }
+        // the memory expansion of the step is taken from the next step of the call
+        self.frame_mut(call_id)?.memory_size_unknown = true;
+        Ok(())
+    }
+
+    /// Execute the step in the current call, if the interpreter supports its
+    /// opcode, and check the operations of the builder against it.
+    fn execute(
+        &mut self,
+        geth_step: &GethExecStep,
+        call: &Call,
+        ops: &StepOps,
+        code_db: &CodeDB,
+    ) -> Result<bool, Error> {
+        let op = geth_step.op;
+        let Some(pops) = stack_inputs(op) else {
+            return Ok(false);
+        };
+        let step = self.step;
+        let frame = &mut self.frames.last_mut().expect("a step is in a call").1;
+        let storage = &mut self.storage;
+        if frame.stack.len() < pops {
+            return Err(mismatch(step, "stack size", pops, frame.stack.len()));
+        }
+        check_ops(step, call.call_id, frame, ops, RW::READ, None)?;
+
+        let stack = &mut frame.stack.0;
+        let mut args = stack.split_off(stack.len() - pops);
+        // the `i`th input from the top of the stack
+        let arg = |i: usize| args[pops - 1 - i];
+        let offset = |word: Word| {
+            if word > MAX_MEMORY_OFFSET.into() {
+                return Err(mismatch(
+                    step,
+                    "memory",
+                    "an offset within the memory",
+                    word,
+                ));
+            }
+            Ok(word.as_usize())
+        };
+        // value written by the builder for the top of the stack, if not the pushed one
+        let mut top_written = None;
+        let result = match op {
+            OpcodeId::STOP | OpcodeId::JUMPDEST | OpcodeId::JUMP | OpcodeId::JUMPI => None,
+            OpcodeId::POP => None,
+            OpcodeId::PC => Some(geth_step.pc.0.into()),
+            OpcodeId::MSIZE => Some(frame.memory.len().into()),
+            op if op.is_push() => {
+                let code = code_db
+                    .0
+                    .get(&call.code_hash)
+                    .ok_or(Error::CodeNotFound(call.code_hash))?;
+                let data_len = op.data_len();
+                let data_start = (geth_step.pc.0 + 1).min(code.len());
+                let data = &code[data_start..(data_start + data_len).min(code.len())];
+                let mut bytes = [0u8; 32];
+                bytes[32 - data_len..32 - data_len + data.len()].copy_from_slice(data);
+                let value = Word::from_big_endian(&bytes);
+                // the bytecode circuit reads the data of a truncated push without padding
+                top_written = Some(value >> ((data_len - data.len()) * 8));
+                Some(value)
+            }
+            op if op.is_dup() => {
+                let value = args[0];
+                stack.append(&mut args);
+                Some(value)
+            }
+            op if op.is_swap() => {
+                args.swap(0, pops - 1);
+                stack.append(&mut args);
+                None
+            }
+            OpcodeId::ADD => Some(arg(0).overflowing_add(arg(1)).0),
+            OpcodeId::MUL => Some(arg(0).overflowing_mul(arg(1)).0),
+            OpcodeId::SUB => Some(arg(0).overflowing_sub(arg(1)).0),
+            OpcodeId::DIV => Some(arg(0).checked_div(arg(1)).unwrap_or_default()),
+            OpcodeId::SDIV => Some(sdiv(arg(0), arg(1))),
+            OpcodeId::MOD => Some(arg(0).checked_rem(arg(1)).unwrap_or_default()),
+            OpcodeId::SMOD => Some(smod(arg(0), arg(1))),
+            OpcodeId::ADDMOD => Some(mulmod_or_addmod(
+                U512::from(arg(0)) + U512::from(arg(1)),
+                arg(2),
+            )),
+            OpcodeId::MULMOD => Some(mulmod_or_addmod(arg(0).full_mul(arg(1)), arg(2))),
+            OpcodeId::EXP => Some(arg(0).overflowing_pow(arg(1)).0),
+            OpcodeId::SIGNEXTEND => Some(signextend(arg(0), arg(1))),
+            OpcodeId::LT => Some(word(arg(0) < arg(1))),
+            OpcodeId::GT => Some(word(arg(0) > arg(1))),
+            OpcodeId::SLT => Some(word(signed_order(arg(0)) < signed_order(arg(1)))),
+            OpcodeId::SGT => Some(word(signed_order(arg(0)) > signed_order(arg(1)))),
+            OpcodeId::EQ => Some(word(arg(0) == arg(1))),
+            OpcodeId::ISZERO => Some(word(arg(0).is_zero())),
+            OpcodeId::AND => Some(arg(0) & arg(1)),
+            OpcodeId::OR => Some(arg(0) | arg(1)),
+            OpcodeId::XOR => Some(arg(0) ^ arg(1)),
+            OpcodeId::NOT => Some(!arg(0)),
+            OpcodeId::BYTE => Some(if arg(0) < 32.into() {
+                arg(1).byte(31 - arg(0).as_usize()).into()
+            } else {
+                Word::zero()
+            }),
+            OpcodeId::SHL => Some(if arg(0) < 256.into() {
+                arg(1) << arg(0).as_usize()
+            } else {
+                Word::zero()
+            }),
+            OpcodeId::SHR => Some(if arg(0) < 256.into() {
+                arg(1) >> arg(0).as_usize()
+            } else {
+                Word::zero()
+            }),
+            OpcodeId::SAR => Some(sar(arg(0), arg(1))),
+            OpcodeId::SHA3 => {
+                let size = arg(1);
+                let data = if size.is_zero() {
+                    &[][..]
+                } else {
+                    let (offset, size) = (offset(arg(0))?, offset(size)?);
+                    frame.memory.extend_at_least(offset + size);
+                    &frame.memory.0[offset..offset + size]
+                };
+                Some(Word::from_big_endian(&keccak256(data)))
+            }
+            OpcodeId::MLOAD => {
+                let offset = offset(arg(0))?;
+                frame.memory.extend_at_least(offset + 32);
+                Some(frame.memory.read_word(offset.into()))
+            }
+            OpcodeId::MSTORE => {
+                let offset = offset(arg(0))?;
+                frame.memory.extend_at_least(offset + 32);
+                frame.memory.0[offset..offset + 32].copy_from_slice(&arg(1).to_be_bytes());
+                None
+            }
+            OpcodeId::MSTORE8 => {
+                let offset = offset(arg(0))?;
+                frame.memory.extend_at_least(offset + 1);
+                frame.memory.0[offset] = arg(1).byte(0);
+                None
+            }
+            OpcodeId::SLOAD | OpcodeId::SSTORE => {
+                let key = arg(0);
+                let storage_op = ops.storage.first().map(|op| op.op());
+                let Some(storage_op) =
+                    storage_op.filter(|op| op.address == call.address && op.key == key)
+                else {
+                    return Err(mismatch(step, "storage", (call.address, key), storage_op));
+                };
+                let slot = Slot::Storage(call.address, key);
+                let value = *storage.entry(slot).or_insert(storage_op.committed_value);
+                if op == OpcodeId::SLOAD {
+                    if storage_op.value != value {
+                        return Err(mismatch(step, "storage", value, storage_op.value));
+                    }
+                    #[cfg(feature = "enable-storage")]
+                    if let Some(expected) = geth_step.storage.0.get(&key) {
+                        if *expected != value {
+                            return Err(mismatch(step, "storage of the trace", expected, value));
+                        }
+                    }
+                    Some(value)
+                } else {
+                    if (storage_op.value_prev, storage_op.value) != (value, arg(1)) {
+                        return Err(mismatch(
+                            step,
+                            "storage",
+                            (value, arg(1)),
+                            (storage_op.value_prev, storage_op.value),
+                        ));
+                    }
+                    frame.journal.push((slot, value));
+                    storage.insert(slot, arg(1));
+                    None
+                }
+            }
+            OpcodeId::TLOAD | OpcodeId::TSTORE => {
+                let key = arg(0);
+                let storage_op = ops.transient_storage.first().map(|op| op.op());
+                let Some(storage_op) =
+                    storage_op.filter(|op| op.address == call.address && op.key == key)
+                else {
+                    return Err(mismatch(
+                        step,
+                        "transient storage",
+                        (call.address, key),
+                        storage_op,
+                    ));
+                };
+                let slot = Slot::Transient(call.address, key);
+                let value = storage.get(&slot).copied().unwrap_or_default();
+                if op == OpcodeId::TLOAD {
+                    if storage_op.value != value {
+                        return Err(mismatch(step, "transient storage", value, storage_op.value));
+                    }
+                    Some(value)
+                } else {
+                    if (storage_op.value_prev, storage_op.value) != (value, arg(1)) {
+                        return Err(mismatch(
+                            step,
+                            "transient storage",
+                            (value, arg(1)),
+                            (storage_op.value_prev, storage_op.value),
+                        ));
+                    }
+                    frame.journal.push((slot, value));
+                    storage.insert(slot, arg(1));
+                    None
+                }
+            }
+            _ => unreachable!("{op:?} has no stack inputs"),
+        };
+        if let Some(result) = result {
+            frame.stack.0.push(result);
+        }
+
+        check_ops(step, call.call_id, frame, ops, RW::WRITE, top_written)?;
+        Ok(true)
+    }
+}
+
+/// Check the stack and memory operations `rw` of the builder in the call
+/// `call_id` against its `frame`, the reads before the step and the writes
+/// after it. `top_written` is the value written for the top of the stack if
+/// not the one on the stack.
+fn check_ops(
+    step: (OpcodeId, usize),
+    call_id: usize,
+    frame: &Frame,
+    ops: &StepOps,
+    rw: RW,
+    top_written: Option<Word>,
+) -> Result<(), Error> {
+    for op in ops.stack.iter().filter(|op| op.rw() == rw) {
+        let &StackOp {
+            call_id: op_call_id,
+            address,
+            value,
+        } = op.op();
+        let index = stack_index(address.0);
+        let expected = match top_written {
+            Some(value) if index + 1 == frame.stack.len() => Some(value),
+            _ => frame.stack.0.get(index).copied(),
+        };
+        if op_call_id != call_id || expected != Some(value) {
+            return Err(mismatch(
+                step,
+                "stack",
+                (call_id, address, expected),
+                (op_call_id, address, value),
+            ));
+        }
+    }
+    for op in ops.memory.iter().filter(|op| op.rw() == rw) {
+        let &MemoryOp {
+            call_id: op_call_id,
+            address,
+            value,
+            ..
+        } = op.op();
+        let expected = frame.memory.read_word(address);
+        if op_call_id != call_id || expected != value {
+            return Err(mismatch(
+                step,
+                "memory",
+                (call_id, address, expected),
+                (op_call_id, address, value),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Index from the bottom of the stack of the element at `address`.
+fn stack_index(address: usize) -> usize {
+    1023usize.wrapping_sub(address)
+}
+
+/// Number of stack inputs of the opcodes executed by the interpreter.
+fn stack_inputs(op: OpcodeId) -> Option<usize> {
+    let inputs = match op {
+        op if op.is_push() => 0,
+        op if op.is_dup() => op.postfix().expect("DUPn") as usize,
+        op if op.is_swap() => op.postfix().expect("SWAPn") as usize + 1,
+        OpcodeId::STOP | OpcodeId::JUMPDEST | OpcodeId::PC | OpcodeId::MSIZE => 0,
+        OpcodeId::POP
+        | OpcodeId::JUMP
+        | OpcodeId::ISZERO
+        | OpcodeId::NOT
+        | OpcodeId::MLOAD
+        | OpcodeId::SLOAD
+        | OpcodeId::TLOAD => 1,
+        OpcodeId::JUMPI
+        | OpcodeId::ADD
+        | OpcodeId::MUL
+        | OpcodeId::SUB
+        | OpcodeId::DIV
+        | OpcodeId::SDIV
+        | OpcodeId::MOD
+        | OpcodeId::SMOD
+        | OpcodeId::EXP
+        | OpcodeId::SIGNEXTEND
+        | OpcodeId::LT
+        | OpcodeId::GT
+        | OpcodeId::SLT
+        | OpcodeId::SGT
+        | OpcodeId::EQ
+        | OpcodeId::AND
+        | OpcodeId::OR
+        | OpcodeId::XOR
+        | OpcodeId::BYTE
+        | OpcodeId::SHL
+        | OpcodeId::SHR
+        | OpcodeId::SAR
+        | OpcodeId::SHA3
+        | OpcodeId::MSTORE
+        | OpcodeId::MSTORE8
+        | OpcodeId::SSTORE
+        | OpcodeId::TSTORE => 2,
+        OpcodeId::ADDMOD | OpcodeId::MULMOD => 3,
+        _ => return None,
+    };
+    Some(inputs)
+}
+
+fn word(value: bool) -> Word {
+    Word::from(value as u64)
+}
+
+fn is_neg(x: Word) -> bool {
+    x.bit(255)
+}
+
+fn neg(x: Word) -> Word {
+    (!x).overflowing_add(Word::one()).0
+}
+
+fn abs(x: Word) -> Word {
+    if is_neg(x) {
+        neg(x)
+    } else {
+        x
+    }
+}
+
+/// Map a two's complement word to one of the same unsigned order.
+fn signed_order(x: Word) -> Word {
+    x ^ (Word::one() << 255)
+}
+
+fn sdiv(a: Word, b: Word) -> Word {
+    let quotient = abs(a).checked_div(abs(b)).unwrap_or_default();
+    if is_neg(a) != is_neg(b) {
+        neg(quotient)
+    } else {
+        quotient
+    }
+}
+
+fn smod(a: Word, b: Word) -> Word {
+    let remainder = abs(a).checked_rem(abs(b)).unwrap_or_default();
+    if is_neg(a) {
+        neg(remainder)
+    } else {
+        remainder
+    }
+}
+
+fn mulmod_or_addmod(value: U512, modulus: Word) -> Word {
+    if modulus.is_zero() {
+        return Word::zero();
+    }
+    Word::try_from(value % U512::from(modulus)).expect("remainder of a word modulus")
+}
+
+fn signextend(size: Word, value: Word) -> Word {
+    if size >= 31.into() {
+        return value;
+    }
+    let sign_bit = size.as_usize() * 8 + 7;
+    let mask = (Word::one() << (sign_bit + 1)) - 1;
+    if value.bit(sign_bit) {
+        value | !mask
+    } else {
+        value & mask
+    }
+}
+
+fn sar(shift: Word, value: Word) -> Word {
+    match (shift < 256.into(), is_neg(value)) {
+        (true, false) => value >> shift.as_usize(),
+        (true, true) => !(!value >> shift.as_usize()),
+        (false, false) => Word::zero(),
+        (false, true) => Word::MAX,
+    }
+}
+
+fn mismatch(
+    (op, pc): (OpcodeId, usize),
+    kind: &'static str,
+    expected: impl fmt::Debug,
+    found: impl fmt::Debug,
+) -> Error {
+    let (expected, found) = (format!("{expected:?}"), format!("{found:?}"));
+    log::error!(
+        "self-check: {kind} mismatch at {op:?} at pc {pc}: expected {expected}, found {found}"
+    );
+    Error::SelfCheckFailed {
+        op,
+        pc,
+        kind,
+        expected,
+        found,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        circuit_input_builder::{BuildMonitor, CircuitInputBuilder},
+        mock::BlockData,
+    };
+    use eth_types::{bytecode, geth_types::GethData, GethExecTrace};
+    use mock::{TestContext, MOCK_ACCOUNTS};
+
+    /// Block of a tx exercising the opcodes executed by the interpreter, and
+    /// calling a contract which writes the storage and memory before
+    /// reverting.
+    fn block() -> GethData {
+        let callee = bytecode! {
+            .op_sstore(1, 0xff)
+            .op_mstore(0, 0xab)
+            .op_revert(0, 0x20)
+        };
+        let caller = bytecode! {
+            PUSH32(Word::MAX)
+            PUSH1(0x07)
+            SDIV
+            PUSH1(0x03)
+            DUP2
+            SMOD
+            DUP2
+            DUP2
+            SLT
+            POP
+            SGT
+            PUSH1(0x05)
+            PUSH1(0x03)
+            PUSH1(0x08)
+            ADDMOD
+            PUSH1(0x07)
+            PUSH1(0x06)
+            PUSH32(Word::MAX)
+            MULMOD
+            PUSH2(0x80f0)
+            PUSH1(0x01)
+            SIGNEXTEND
+            PUSH1(0x04)
+            SAR
+            SWAP2
+            PUSH1(0x1f)
+            BYTE
+            PUSH1(0x03)
+            PUSH1(0x02)
+            EXP
+            SHL
+            PUSH1(0x02)
+            SHR
+            ADD
+            MUL
+            SUB
+            NOT
+            PUSH1(0x0f)
+            AND
+            PUSH1(0xf0)
+            OR
+            PUSH1(0x03)
+            XOR
+            PUSH1(0x05)
+            SWAP1
+            DIV
+            PUSH1(0x03)
+            SWAP1
+            MOD
+            ISZERO
+            PUSH1(0x01)
+            EQ
+            PUSH1(0x02)
+            LT
+            PUSH1(0x00)
+            GT
+            POP
+            .op_mstore(0x03, 0x42)
+            .op_mstore8(0x40, 0xcd)
+            MSIZE
+            POP
+            .op_sha3(0x02, 0x23)
+            POP
+            .op_sstore(0x00, 0x1234)
+            .op_sload(0x00)
+            POP
+            .op_mload(0x21)
+            POP
+            .op_call(0xffff, MOCK_ACCOUNTS[1], 0, 0, 0x20, 0x20, 0x20)
+            POP
+            .op_sload(0x00)
+            POP
+            STOP
+        };
+        TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(caller);
+                accs[1].address(MOCK_ACCOUNTS[1]).code(callee);
+                accs[2]
+                    .address(MOCK_ACCOUNTS[2])
+                    .balance(Word::from(1u64 << 30));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[2].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into()
+    }
+
+    fn build(block: &GethData, monitor: BuildMonitor) -> Result<CircuitInputBuilder, Error> {
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder.monitor = monitor;
+        builder.handle_block(&block.eth_block, &block.geth_traces)?;
+        Ok(builder)
+    }
+
+    /// Self-check the first tx of a built block against `geth_trace`.
+    fn check_tx(builder: &CircuitInputBuilder, geth_trace: &GethExecTrace) -> Result<(), Error> {
+        let tx = &builder.block.txs()[0];
+        let mut exec_steps = tx
+            .steps()
+            .iter()
+            .filter(|step| !matches!(step.exec_state, ExecState::BeginTx | ExecState::EndTx))
+            .peekable();
+        let mut self_check = TxSelfCheck::new();
+        for geth_step in &geth_trace.struct_logs {
+            // the step of the geth step, then the ones of the precompile it calls
+            let mut steps = vec![exec_steps.next().unwrap().clone()];
+            while let Some(step) =
+                exec_steps.next_if(|step| matches!(step.exec_state, ExecState::Precompile(_)))
+            {
+                steps.push(step.clone());
+            }
+            self_check.step(
+                geth_step,
+                &steps,
+                tx.calls(),
+                &builder.block.container,
+                &builder.code_db,
+            )?;
+        }
+        self_check.finish(tx.calls(), &builder.sdb)
+    }
+
+    /// Index of the operation of `target` of the first step of `op`.
+    fn op_index(builder: &CircuitInputBuilder, op: OpcodeId, target: Target, rw: RW) -> usize {
+        let container = &builder.block.container;
+        builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(op))
+            .unwrap()
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == target)
+            .map(|op_ref| op_ref.as_usize())
+            .find(|index| match target {
+                Target::Stack => container.stack[*index].rw() == rw,
+                Target::Memory => container.memory[*index].rw() == rw,
+                Target::Storage => container.storage[*index].rw() == rw,
+                _ => unreachable!(),
+            })
+            .unwrap()
+    }
+
+    fn assert_mismatch(result: Result<(), Error>, op: OpcodeId, kind: &str) {
+        match result {
+            Err(Error::SelfCheckFailed {
+                op: found_op,
+                kind: found_kind,
+                ..
+            }) => assert_eq!((found_op, found_kind), (op, kind)),
+            result => panic!("expected a {kind} mismatch at {op:?}, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn self_check_passes() {
+        let block = block();
+        build(&block, BuildMonitor::default().with_self_check()).unwrap();
+        let builder = build(&block, BuildMonitor::default()).unwrap();
+        check_tx(&builder, &block.geth_traces[0]).unwrap();
+    }
+
+    #[test]
+    fn self_check_finds_wrong_stack() {
+        let block = block();
+        let mut builder = build(&block, BuildMonitor::default()).unwrap();
+        let index = op_index(&builder, OpcodeId::ADDMOD, Target::Stack, RW::WRITE);
+        builder.block.container.stack[index].op_mut().value += Word::one();
+        assert_mismatch(
+            check_tx(&builder, &block.geth_traces[0]),
+            OpcodeId::ADDMOD,
+            "stack",
+        );
+    }
+
+    #[test]
+    fn self_check_finds_wrong_memory() {
+        let block = block();
+        let mut builder = build(&block, BuildMonitor::default()).unwrap();
+        let index = op_index(&builder, OpcodeId::MSTORE8, Target::Memory, RW::WRITE);
+        builder.block.container.memory[index].op_mut().value += Word::one();
+        assert_mismatch(
+            check_tx(&builder, &block.geth_traces[0]),
+            OpcodeId::MSTORE8,
+            "memory",
+        );
+    }
+
+    #[test]
+    fn self_check_finds_wrong_storage() {
+        let block = block();
+        let mut builder = build(&block, BuildMonitor::default()).unwrap();
+        let index = op_index(&builder, OpcodeId::SLOAD, Target::Storage, RW::READ);
+        builder.block.container.storage[index].op_mut().value += Word::one();
+        assert_mismatch(
+            check_tx(&builder, &block.geth_traces[0]),
+            OpcodeId::SLOAD,
+            "storage",
+        );
+    }
+
+    #[test]
+    fn self_check_finds_unreverted_storage() {
+        let block = block();
+        let mut builder = build(&block, BuildMonitor::default()).unwrap();
+        // the storage written by the reverted call is left in the builder
+        *builder
+            .sdb
+            .get_storage_mut(&MOCK_ACCOUNTS[1], &Word::one())
+            .1 = 0xff.into();
+        assert_mismatch(
+            check_tx(&builder, &block.geth_traces[0]),
+            OpcodeId::STOP,
+            "storage at the end of the tx",
+        );
+    }
+
+    #[cfg(feature = "enable-stack")]
+    #[test]
+    fn self_check_finds_wrong_trace() {
+        let mut block = block();
+        let geth_step = block.geth_traces[0]
+            .struct_logs
+            .iter_mut()
+            .find(|step| step.op == OpcodeId::SMOD)
+            .unwrap();
+        geth_step.stack.0[0] += Word::one();
+        let builder = build(&block, BuildMonitor::default()).unwrap();
+        assert_mismatch(
+            check_tx(&builder, &block.geth_traces[0]),
+            OpcodeId::SMOD,
+            "stack of the trace",
+        );
+    }
+}
@@ -46,6 +46,7 @@ impl CircuitInputBuilderTx {
                 prestate: block.geth_traces[0].prestate.clone(),
                 call_trace: block.geth_traces[0].call_trace.clone(),
             },
+            eth_types::MAX_CALL_DEPTH,
         )
         .unwrap();
 
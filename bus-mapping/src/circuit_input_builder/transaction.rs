@@ -4,16 +4,19 @@ use super::{
     call::ReversionGroup, curie::is_curie_enabled, Call, CallContext, CallKind, CodeSource,
     ExecStep,
 };
-use crate::{l2_predeployed::l1_gas_price_oracle, Error};
+use crate::{error::InvalidTxReason, l2_predeployed::l1_gas_price_oracle, Error};
 use eth_types::evm_types::gas_utils::tx_data_gas_cost;
 use eth_types::{
-    evm_types::OpcodeId,
+    evm_types::{GasSchedule, OpcodeId},
     geth_types,
-    geth_types::{get_rlp_unsigned, TxType},
+    geth_types::{BlobTxFields, TxType, TxTypeConfig},
+    sign_types::is_low_s,
     state_db::{CodeDB, StateDB},
-    AccessList, Address, GethExecTrace, Signature, Word, H256,
+    AccessList, Address, GethCallTrace, GethExecTrace, Signature, Word, WordExt, H256,
 };
-use ethers_core::utils::get_contract_address;
+use ethers_core::{types::Log, utils::get_contract_address};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 /// Precision of transaction L1 fee
 pub const TX_L1_FEE_PRECISION: u64 = 1_000_000_000;
@@ -42,12 +45,24 @@ pub struct TransactionContext {
     /// in the inner most revert (which we track with the last element in
     /// the reversion groups stack), and skip it in the outer revert.
     pub(crate) reversion_groups: Vec<ReversionGroup>,
+    /// Whether the geth steps capture the memory, which is then checked
+    /// against the one of the builder with `GETH_TRACE_CHECK_LEVEL`. `None`
+    /// when unknown, for a trace fed step by step.
+    #[cfg(feature = "enable-memory")]
+    pub(crate) memory_captured: Option<bool>,
 }
 
 impl TransactionContext {
-    /// Create a new Self.
-    pub fn new(eth_tx: &eth_types::Transaction, geth_trace: &GethExecTrace) -> Result<Self, Error> {
-        let call_is_success = geth_trace.call_trace.gen_call_is_success(vec![]);
+    /// Create a new Self. Fails if a call of `geth_trace` is nested deeper
+    /// than `max_call_depth`.
+    pub fn new(
+        eth_tx: &eth_types::Transaction,
+        geth_trace: &GethExecTrace,
+        max_call_depth: usize,
+    ) -> Result<Self, Error> {
+        let call_is_success = geth_trace
+            .call_trace
+            .gen_call_is_success(vec![], max_call_depth)?;
 
         let mut tx_ctx = Self {
             id: eth_tx
@@ -61,6 +76,8 @@ impl TransactionContext {
             calls: Vec::new(),
             reversion_groups: Vec::new(),
             l1_fee: geth_trace.l1_fee,
+            #[cfg(feature = "enable-memory")]
+            memory_captured: None,
         };
         tx_ctx.push_call_ctx(
             0,
@@ -205,6 +222,12 @@ pub struct Transaction {
     pub l1_fee_committed: TxL1Fee,
     /// EIP2930
     pub access_list: Option<AccessList>,
+    /// EIP4844
+    pub blob: BlobTxFields,
+    /// Gas charged to the sender after refunds, set when the tx ends
+    pub gas_used: u64,
+    /// Logs emitted by the persistent calls of the tx, in order
+    pub logs: Vec<Log>,
     /// Calls made in the transaction
     pub(crate) calls: Vec<Call>,
     /// Execution steps
@@ -231,6 +254,7 @@ impl From<&Transaction> for geth_types::Transaction {
             //rlp_signed_bytes: tx.rlp_signed_bytes.clone(),
             rlp_bytes: tx.rlp_bytes.clone(),
             tx_type: tx.tx_type,
+            blob: tx.blob.clone(),
             ..Default::default()
         }
     }
@@ -266,6 +290,9 @@ impl Transaction {
             l1_fee: Default::default(),
             l1_fee_committed: Default::default(),
             access_list: None,
+            blob: Default::default(),
+            gas_used: 0,
+            logs: Vec::new(),
         }
     }
 
@@ -273,6 +300,7 @@ impl Transaction {
     pub fn new(
         call_id: usize,
         chain_id: u64,
+        tx_type_config: &TxTypeConfig,
         sdb: &StateDB,
         code_db: &mut CodeDB,
         eth_tx: &eth_types::Transaction,
@@ -342,7 +370,12 @@ impl Transaction {
             }
         );
 
-        let tx_type = TxType::get_tx_type(eth_tx);
+        let tx_type = tx_type_config.tx_type(eth_tx);
+        // the tx pool rejects malleable signatures (EIP-2), and so does the tx circuit
+        if !tx_type.is_l1_msg() && !is_low_s(&eth_tx.s) {
+            return Err(Error::EthTypeError(eth_types::Error::SignatureHighS));
+        }
+        let blob = BlobTxFields::of_tx(eth_tx)?;
         let (l1_fee, l1_fee_committed) = if tx_type.is_l1_msg() {
             Default::default()
         } else {
@@ -360,7 +393,7 @@ impl Transaction {
             l1_fee,
             l1_fee_committed
         );
-        let rlp_signed_bytes = eth_tx.rlp().to_vec();
+        let rlp_signed_bytes = tx_type_config.rlp_signed(eth_tx);
         //debug_assert_eq!(H256(ethers_core::utils::keccak256(&bytes)), eth_tx.hash);
 
         Ok(Self {
@@ -368,8 +401,8 @@ impl Transaction {
             hash: eth_tx.hash,
             chain_id: tx_chain_id,
             tx_type,
-            rlp_bytes: eth_tx.rlp().to_vec(),
-            rlp_unsigned_bytes: get_rlp_unsigned(eth_tx),
+            rlp_bytes: rlp_signed_bytes.clone(),
+            rlp_unsigned_bytes: tx_type_config.rlp_unsigned(eth_tx),
             rlp_signed_bytes,
             nonce: eth_tx.nonce.as_u64(),
             gas: eth_tx.gas.as_u64(),
@@ -390,14 +423,145 @@ impl Transaction {
             l1_fee,
             l1_fee_committed,
             access_list: eth_tx.access_list.clone(),
+            blob,
+            gas_used: 0,
+            logs: Vec::new(),
         })
     }
 
+    /// Check the tx against the state of its sender before it is applied, as
+    /// consensus does. L1 msgs don't buy gas and their nonce is their queue
+    /// index, so only their intrinsic gas is checked.
+    pub fn check_validity(
+        &self,
+        sdb: &StateDB,
+        gas_schedule: &GasSchedule,
+        blob_base_fee: Word,
+        check_nonce: bool,
+    ) -> Result<(), InvalidTxReason> {
+        let sender = sdb.get_account(&self.from).1;
+        if !self.tx_type.is_l1_msg() && self.nonce == u64::MAX {
+            return Err(InvalidTxReason::NonceMax);
+        }
+        if check_nonce && !self.tx_type.is_l1_msg() && sender.nonce != self.nonce.into() {
+            return Err(InvalidTxReason::NonceMismatch {
+                expected: sender.nonce.fit_u64(),
+                found: self.nonce,
+            });
+        }
+        let intrinsic_gas =
+            gas_schedule.tx_intrinsic_gas(self.is_create(), &self.input, &self.access_list);
+        if self.gas < intrinsic_gas {
+            return Err(InvalidTxReason::IntrinsicGasTooLow {
+                gas: self.gas,
+                intrinsic_gas,
+            });
+        }
+        if self.tx_type.is_l1_msg() {
+            return Ok(());
+        }
+        let cost = self
+            .gas_price
+            .saturating_mul(self.gas.into())
+            .saturating_add(self.l1_fee().into())
+            .saturating_add(blob_base_fee.saturating_mul(self.blob.blob_gas().into()))
+            .saturating_add(self.value);
+        if sender.balance < cost {
+            return Err(InvalidTxReason::InsufficientBalance {
+                balance: sender.balance,
+                cost,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check the calls of the tx, as reconstructed from its geth steps,
+    /// against `call_trace`, the reference call graph of geth's call tracer:
+    /// the calls must be entered in the same order, at the same depth, with
+    /// the same type, addresses, value and success. Returns the first
+    /// mismatch.
+    ///
+    /// The calls failing their precheck, e.g. on insufficient balance, never
+    /// execute and are left out of the call graph, so a failed call which
+    /// doesn't match the next call of the graph is skipped.
+    pub fn check_call_tree(&self, call_trace: &GethCallTrace) -> Result<(), String> {
+        let mut frames = call_trace.executed_calls().into_iter().peekable();
+        for (index, call) in self.calls.iter().enumerate() {
+            let call_type = match call.kind {
+                CallKind::Call => OpcodeId::CALL,
+                CallKind::CallCode => OpcodeId::CALLCODE,
+                CallKind::DelegateCall => OpcodeId::DELEGATECALL,
+                CallKind::StaticCall => OpcodeId::STATICCALL,
+                CallKind::Create => OpcodeId::CREATE,
+                CallKind::Create2 => OpcodeId::CREATE2,
+            };
+            // geth logs the current contract as the caller of a DELEGATECALL
+            let from = if call.is_delegatecall() {
+                call.address
+            } else {
+                call.caller_address
+            };
+            let to = if call.is_create() {
+                Some(call.address)
+            } else {
+                call.code_address()
+            };
+            let is_frame = |frame: &GethCallTrace, depth: usize| {
+                depth == call.depth
+                    && OpcodeId::from_str(frame.call_type()).ok() == Some(call_type)
+                    && frame.from() == from
+                    && frame.to() == to
+            };
+            let (frame, _) = match frames.peek() {
+                Some((frame, depth)) if is_frame(*frame, *depth) => frames.next().unwrap(),
+                _ if !call.is_root && !call.is_success => continue,
+                Some((frame, depth)) => {
+                    return Err(format!(
+                        "call {index} is a {call_type} from {from:?} to {to:?} at depth {}, \
+                        but geth entered a {} from {:?} to {:?} at depth {depth}",
+                        call.depth,
+                        frame.call_type(),
+                        frame.from(),
+                        frame.to(),
+                    ))
+                }
+                None => return Err(format!("call {index} is not entered by geth")),
+            };
+            if call.is_success != frame.error().is_none() {
+                return Err(format!(
+                    "call {index} has is_success = {}, but geth reports error {:?}",
+                    call.is_success,
+                    frame.error(),
+                ));
+            }
+            if let Some(value) = frame.value().filter(|value| *value != call.value) {
+                return Err(format!(
+                    "call {index} has value {}, but geth reports {value}",
+                    call.value
+                ));
+            }
+        }
+        match frames.next() {
+            Some((frame, depth)) => Err(format!(
+                "geth entered a {} from {:?} to {:?} at depth {depth} which is missing",
+                frame.call_type(),
+                frame.from(),
+                frame.to(),
+            )),
+            None => Ok(()),
+        }
+    }
+
     /// Whether this [`Transaction`] is a create one
     pub fn is_create(&self) -> bool {
         self.calls[0].is_create()
     }
 
+    /// Whether the tx succeeded, which is the status of its receipt.
+    pub fn is_success(&self) -> bool {
+        self.calls[0].is_success
+    }
+
     /// Return the list of execution steps of this transaction.
     pub fn steps(&self) -> &[ExecStep] {
         &self.steps
@@ -479,7 +643,7 @@ impl Transaction {
 }
 
 /// Transaction L1 fee for L1GasPriceOracle contract
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TxL1Fee {
     /// chain id
     pub chain_id: u64,
@@ -0,0 +1,278 @@
+//! Split of the txs of a [`Blocks`] whose rws exceed `max_rws` into
+//! consecutive chunks of txs, each of which fits a state circuit of
+//! `max_rws` rows.
+//!
+//! Chunks are split at tx boundaries, so no call context spans two of them,
+//! and the rw counters of consecutive chunks are contiguous. Each chunk is
+//! proven by its own circuit instance, which starts with a BeginChunk step
+//! (but the first) and ends with an EndChunk step (but the last), and exposes
+//! its [`ChunkContext`] as public inputs.
+
+use super::{Blocks, ExecState, ExecStep};
+use crate::{
+    error::{CapacityResource, Error},
+    operation::{RWCounter, Target},
+};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Consecutive txs of a block proven together.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxChunk {
+    /// Indices of the txs in the block
+    pub txs: Range<usize>,
+    /// Rw counters of the rws of the txs
+    pub rwc: Range<usize>,
+}
+
+impl TxChunk {
+    /// Number of rws of the txs of the chunk.
+    pub fn num_rws(&self) -> usize {
+        self.rwc.len()
+    }
+}
+
+/// Context of a chunk exposed as public inputs, through which a chunk
+/// continues where the previous one ends: the rw counter and the tx id carry
+/// over, and the state root after a chunk is the state root before the next.
+///
+/// The end of the last chunk is left open, i.e. 0, as the block ends with it.
+/// The default context is the one of a block proven in a single chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkContext {
+    /// Index of the chunk
+    pub idx: usize,
+    /// Number of chunks of the block
+    pub total_chunks: usize,
+    /// Rw counter of the first rw of the chunk
+    pub initial_rwc: usize,
+    /// Rw counter of the first rw of the next chunk
+    pub end_rwc: usize,
+    /// Id in the block of the first tx of the chunk
+    pub initial_tx_id: usize,
+    /// Id in the block of the last tx of the chunk
+    pub end_tx_id: usize,
+}
+
+impl Default for ChunkContext {
+    fn default() -> Self {
+        Self {
+            idx: 0,
+            total_chunks: 1,
+            initial_rwc: 1,
+            end_rwc: 0,
+            initial_tx_id: 1,
+            end_tx_id: 0,
+        }
+    }
+}
+
+impl ChunkContext {
+    /// Whether the chunk is the first of its block.
+    pub fn is_first(&self) -> bool {
+        self.idx == 0
+    }
+
+    /// Whether the chunk is the last of its block.
+    pub fn is_last(&self) -> bool {
+        self.idx + 1 == self.total_chunks
+    }
+
+    /// Whether the chunk continues where `prev` ends.
+    pub fn follows(&self, prev: &ChunkContext) -> bool {
+        self.idx == prev.idx + 1
+            && self.total_chunks == prev.total_chunks
+            && self.initial_rwc == prev.end_rwc
+            && self.initial_tx_id == prev.end_tx_id + 1
+    }
+}
+
+impl Blocks {
+    /// Rw counters of the rws of each tx, in tx order.
+    pub fn tx_rwc_ranges(&self) -> Vec<Range<usize>> {
+        let starts = self
+            .txs
+            .iter()
+            .map(|tx| tx.steps()[0].rwc.0)
+            .chain(std::iter::once(self.block_steps.end_block_step.rwc.0))
+            .collect::<Vec<_>>();
+        starts.windows(2).map(|w| w[0]..w[1]).collect()
+    }
+
+    /// Rws of the EndBlock step, which every chunk repeats.
+    fn end_block_rws(&self) -> usize {
+        self.block_steps
+            .end_block_step
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() != Target::Start)
+            .count()
+    }
+
+    /// Split the txs into the fewest consecutive chunks whose rws, together
+    /// with the EndBlock rws and the leading Start row, fit `max_rws`.
+    /// Fails if a single tx does not fit.
+    pub fn split_txs(&self, max_rws: usize) -> Result<Vec<TxChunk>, Error> {
+        let overhead = self.end_block_rws() + 1;
+        let capacity = max_rws.saturating_sub(overhead);
+
+        let mut chunks: Vec<TxChunk> = vec![];
+        for (idx, rwc) in self.tx_rwc_ranges().into_iter().enumerate() {
+            if rwc.len() > capacity {
+                return Err(Error::CapacityExceeded {
+                    resource: CapacityResource::Rws,
+                    used: rwc.len() + overhead,
+                    limit: max_rws,
+                });
+            }
+            match chunks.last_mut() {
+                Some(chunk) if chunk.num_rws() + rwc.len() <= capacity => {
+                    chunk.txs.end = idx + 1;
+                    chunk.rwc.end = rwc.end;
+                }
+                _ => chunks.push(TxChunk {
+                    txs: idx..idx + 1,
+                    rwc,
+                }),
+            }
+        }
+        Ok(chunks)
+    }
+
+    /// Contexts of the chunks of [`Blocks::split_txs`].
+    pub fn chunk_contexts(&self, max_rws: usize) -> Result<Vec<ChunkContext>, Error> {
+        let chunks = self.split_txs(max_rws)?;
+        let total_chunks = chunks.len();
+        Ok(chunks
+            .into_iter()
+            .enumerate()
+            .map(|(idx, chunk)| {
+                let is_last = idx + 1 == total_chunks;
+                ChunkContext {
+                    idx,
+                    total_chunks,
+                    initial_rwc: chunk.rwc.start,
+                    end_rwc: if is_last { 0 } else { chunk.rwc.end },
+                    initial_tx_id: chunk.txs.start + 1,
+                    end_tx_id: if is_last { 0 } else { chunk.txs.end },
+                }
+            })
+            .collect())
+    }
+
+    /// BeginChunk step of a chunk but the first. It shares the TxId write of
+    /// the BeginTx step of the first tx of the chunk.
+    pub fn begin_chunk_step(&self, ctx: &ChunkContext) -> Option<ExecStep> {
+        if ctx.is_first() {
+            return None;
+        }
+        let begin_tx_step = &self.txs[ctx.initial_tx_id - 1].steps()[0];
+        Some(ExecStep {
+            exec_state: ExecState::BeginChunk,
+            rwc: RWCounter(ctx.initial_rwc),
+            gas_left: begin_tx_step.gas_left,
+            bus_mapping_instance: begin_tx_step.bus_mapping_instance[..1].to_vec(),
+            ..ExecStep::default()
+        })
+    }
+
+    /// EndChunk step of a chunk but the last, after the last step of its last
+    /// tx.
+    pub fn end_chunk_step(&self, ctx: &ChunkContext) -> Option<ExecStep> {
+        if ctx.is_last() {
+            return None;
+        }
+        let last_step = self.txs[ctx.end_tx_id - 1].steps().last()?;
+        Some(ExecStep {
+            exec_state: ExecState::EndChunk,
+            rwc: RWCounter(ctx.end_rwc),
+            call_index: last_step.call_index,
+            ..ExecStep::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::BlockData;
+    use eth_types::{address, bytecode, geth_types::GethData, Word};
+    use mock::TestContext;
+
+    #[test]
+    fn split_txs_at_rws_capacity() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        };
+        let block: GethData = TestContext::<2, 3>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000cafe001"))
+                    .code(code);
+                accs[1]
+                    .address(address!("0x000000000000000000000000000000000cafe002"))
+                    .balance(Word::from(1u64 << 30));
+            },
+            |txs, accs| {
+                for (nonce, tx) in txs.into_iter().enumerate() {
+                    tx.to(accs[0].address)
+                        .from(accs[1].address)
+                        .nonce(Word::from(nonce));
+                }
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let blocks = &builder.block;
+
+        let ranges = blocks.tx_rwc_ranges();
+        assert_eq!(ranges.len(), 3);
+        for w in ranges.windows(2) {
+            assert_eq!(w[0].end, w[1].start);
+        }
+
+        let overhead = blocks.end_block_rws() + 1;
+        let total = ranges[2].end - ranges[0].start;
+        let chunks = blocks.split_txs(total + overhead).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].txs, 0..3);
+
+        let largest = ranges.iter().map(|rwc| rwc.len()).max().unwrap();
+        let chunks = blocks.split_txs(largest + overhead).unwrap();
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks[0].txs.start, 0);
+        assert_eq!(chunks.last().unwrap().txs.end, 3);
+        for w in chunks.windows(2) {
+            assert_eq!(w[0].txs.end, w[1].txs.start);
+            assert_eq!(w[0].rwc.end, w[1].rwc.start);
+        }
+
+        assert!(blocks.split_txs(largest + overhead - 1).is_err());
+
+        let ctxs = blocks.chunk_contexts(largest + overhead).unwrap();
+        assert_eq!(ctxs.len(), chunks.len());
+        assert_eq!(ctxs[0].initial_rwc, 1);
+        assert_eq!(ctxs[0].initial_tx_id, 1);
+        assert!(blocks.begin_chunk_step(&ctxs[0]).is_none());
+        for w in ctxs.windows(2) {
+            assert!(w[1].follows(&w[0]));
+            let begin_chunk = blocks.begin_chunk_step(&w[1]).unwrap();
+            assert_eq!(begin_chunk.rwc.0, w[1].initial_rwc);
+            let end_chunk = blocks.end_chunk_step(&w[0]).unwrap();
+            assert_eq!(end_chunk.rwc.0, w[0].end_rwc);
+        }
+        let last = ctxs.last().unwrap();
+        assert!(last.is_last());
+        assert_eq!((last.end_rwc, last.end_tx_id), (0, 0));
+        assert!(blocks.end_chunk_step(last).is_none());
+    }
+}
@@ -26,6 +26,14 @@ pub enum Error {
     AddressNotFound(Address),
     /// Code not found in the CodeDB
     CodeNotFound(H256),
+    /// A proof of a partial state doesn't verify against the state root, or
+    /// proves other values than the ones of the partial state.
+    InvalidStateProof {
+        /// Address of the account
+        address: Address,
+        /// Storage key, for a storage proof
+        key: Option<Word>,
+    },
     /// Unable to figure out error at a [`GethExecStep`]
     UnexpectedExecStepError(&'static str, Box<GethExecStep>),
     /// Invalid [`eth_types::GethExecTrace`] due to an invalid/unexpected value
@@ -39,6 +47,211 @@ pub enum Error {
     ExecutionError(ExecError),
     /// Internal Code error
     InternalError(&'static str),
+    /// The block contains a tx whose type is not supported by this build.
+    UnsupportedTxType {
+        /// Index of the tx in the block.
+        index: usize,
+        /// EIP-2718 type byte of the tx.
+        tx_type: u64,
+        /// Feature the build needs to support this tx type, if any.
+        required_feature: Option<&'static str>,
+    },
+    /// The sender of a non L1 msg tx has code deployed (EIP-3607).
+    SenderHasCode {
+        /// Index of the tx in the block.
+        index: usize,
+    },
+    /// A tx of the block is invalid, consensus would have rejected it.
+    InvalidTx {
+        /// Index of the tx in the chunk.
+        index: usize,
+        /// Why the tx is invalid.
+        reason: InvalidTxReason,
+    },
+    /// The traces execute an opcode whose circuit support is not compiled in.
+    UnsupportedOpcode {
+        /// Opcode executed by the traces.
+        opcode: OpcodeId,
+        /// Feature the build needs to support this opcode.
+        required_feature: &'static str,
+    },
+    /// The traces call a precompile the circuits don't support, see
+    /// [`check_precompile_supported`](crate::precompile::check_precompile_supported).
+    UnsupportedPrecompile {
+        /// Address of the precompile.
+        address: Address,
+    },
+    /// A successful MODEXP call has an operand longer than the modexp circuit
+    /// supports.
+    UnsupportedModExpSize {
+        /// Byte lengths of the base, the exponent and the modulus.
+        input_lens: [Word; 3],
+        /// Maximum byte length of an operand, see
+        /// [`modexp_size_limit`](crate::precompile::modexp_size_limit).
+        limit: usize,
+    },
+    /// A successful ecPairing call has more pairs than the ECC circuit
+    /// supports per call, see
+    /// [`PrecompileEcParams::ec_pairing_pairs`](crate::circuit_input_builder::PrecompileEcParams).
+    UnsupportedEcPairingSize {
+        /// Number of pairs of the call.
+        n_pairs: usize,
+        /// Maximum number of pairs of a call.
+        limit: usize,
+    },
+    /// The receipts root of a block header is not the root of the receipts of
+    /// the txs of the block.
+    InvalidReceiptsRoot {
+        /// Number of the block.
+        block_num: u64,
+        /// Receipts root of the block header.
+        header_root: H256,
+        /// Root of the receipts of the txs of the block.
+        computed_root: H256,
+    },
+    /// The block uses more of a resource than the circuits are configured
+    /// for in [`CircuitsParams`](crate::circuit_input_builder::CircuitsParams).
+    /// The block can be proven once split.
+    CapacityExceeded {
+        /// Exceeded resource.
+        resource: CapacityResource,
+        /// Amount used by the block.
+        used: usize,
+        /// Capacity of the circuits.
+        limit: usize,
+    },
+    /// The gas used computed by the builder disagrees with the tx receipts.
+    GasUsedMismatch(Vec<GasUsedMismatch>),
+    /// A check on the traces failed while the `strict-asserts` feature is
+    /// disabled.
+    AssertionFailed(String),
+    /// The witness generation was cancelled by its
+    /// [`BuildMonitor`](crate::circuit_input_builder::BuildMonitor), at the
+    /// given progress.
+    Cancelled(crate::circuit_input_builder::BuildProgress),
+    /// The state re-executed by the self-check of
+    /// [`BuildMonitor::with_self_check`](crate::circuit_input_builder::BuildMonitor::with_self_check)
+    /// differs from the operations of the builder or from the trace at a geth
+    /// step.
+    SelfCheckFailed {
+        /// Opcode of the step, the last one of the tx for its final storage.
+        op: OpcodeId,
+        /// Program counter of the step.
+        pc: usize,
+        /// Part of the state which differs, e.g. the stack, the memory or the
+        /// storage, or the ones "of the trace".
+        kind: &'static str,
+        /// State of the trace, or of the interpreter when compared against
+        /// the builder.
+        expected: String,
+        /// State of the interpreter, or of the builder when compared against
+        /// the interpreter.
+        found: String,
+    },
+}
+
+/// `assert!` on a condition derived from the traces. Without the
+/// `strict-asserts` feature and outside of tests, a failure returns an
+/// [`Error::AssertionFailed`] from the enclosing function instead of
+/// panicking, so that a prover handling a queue of blocks can skip a
+/// malformed one.
+macro_rules! check {
+    ($cond:expr $(,)?) => {
+        check!($cond, "assertion failed: {}", stringify!($cond))
+    };
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            if cfg!(any(test, feature = "strict-asserts")) {
+                panic!($($arg)+);
+            }
+            return Err($crate::Error::AssertionFailed(format!($($arg)+)));
+        }
+    };
+}
+
+/// `assert_eq!` counterpart of `check!`.
+macro_rules! check_eq {
+    ($left:expr, $right:expr $(,)?) => {{
+        let (left, right) = (&$left, &$right);
+        check!(
+            *left == *right,
+            "assertion `left == right` failed\n  left: {:?}\n right: {:?}",
+            left,
+            right
+        )
+    }};
+    ($left:expr, $right:expr, $($arg:tt)+) => {{
+        let (left, right) = (&$left, &$right);
+        check!(*left == *right, $($arg)+)
+    }};
+}
+
+/// Resource of the circuits bounded by
+/// [`CircuitsParams`](crate::circuit_input_builder::CircuitsParams), see
+/// [`Error::CapacityExceeded`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CapacityResource {
+    /// Txs, bounded by `max_txs`.
+    Txs,
+    /// Read-write operations, bounded by `max_rws`.
+    Rws,
+    /// ecAdd calls, bounded by `max_ec_ops.ec_add`.
+    EcAdd,
+    /// ecMul calls, bounded by `max_ec_ops.ec_mul`.
+    EcMul,
+    /// ecPairing calls, bounded by `max_ec_ops.ec_pairing`.
+    EcPairing,
+    /// Rows of the copy circuit, bounded by `max_copy_rows`.
+    CopyRows,
+    /// Keccak permutations, bounded by the capacity of `max_keccak_rows`.
+    Keccak,
+    /// Blocks of a chunk, bounded by `max_inner_blocks`.
+    InnerBlocks,
+    /// Rounds of the blake2f compressions, bounded by `max_blake2f_rounds`.
+    Blake2fRounds,
+}
+
+/// Cause of an [`Error::InvalidTx`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvalidTxReason {
+    /// The tx nonce is not the nonce of the sender.
+    NonceMismatch {
+        /// Nonce of the sender.
+        expected: u64,
+        /// Nonce of the tx.
+        found: u64,
+    },
+    /// The tx nonce is the maximum u64, so the sender nonce would overflow
+    /// (EIP-2681).
+    NonceMax,
+    /// The sender can't pay for the value and the fees of the tx.
+    InsufficientBalance {
+        /// Balance of the sender.
+        balance: Word,
+        /// Value plus the gas limit, L1 fee and blob gas charged upfront.
+        cost: Word,
+    },
+    /// The gas limit doesn't cover the intrinsic gas of the tx.
+    IntrinsicGasTooLow {
+        /// Gas limit of the tx.
+        gas: u64,
+        /// Intrinsic gas of the tx.
+        intrinsic_gas: u64,
+    },
+}
+
+/// Gas used by a tx as computed by the builder, next to the `gasUsed` of its
+/// receipt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GasUsedMismatch {
+    /// Index of the tx in the chunk.
+    pub index: usize,
+    /// Hash of the tx.
+    pub hash: H256,
+    /// Gas used computed by the builder.
+    pub builder: u64,
+    /// Gas used reported by the receipt.
+    pub receipt: u64,
 }
 
 impl From<eth_types::Error> for Error {
@@ -22,6 +22,7 @@ mod address;
 mod arithmetic;
 mod balance;
 mod begin_end_tx;
+mod blobhash;
 mod blockhash;
 mod calldatacopy;
 mod calldataload;
@@ -60,7 +61,9 @@ mod sstore;
 mod stackonlyop;
 mod stop;
 mod swap;
+#[cfg(feature = "cancun")]
 mod tload;
+#[cfg(feature = "cancun")]
 mod tstore;
 
 mod error_codestore;
@@ -88,6 +91,7 @@ use address::Address;
 use arithmetic::ArithmeticOpcode;
 use balance::Balance;
 use begin_end_tx::{gen_begin_tx_steps, gen_end_tx_steps};
+use blobhash::Blobhash;
 use blockhash::Blockhash;
 use calldatacopy::Calldatacopy;
 use calldataload::Calldataload;
@@ -131,7 +135,9 @@ use sstore::Sstore;
 use stackonlyop::StackPopOnlyOpcode;
 use stop::Stop;
 use swap::Swap;
+#[cfg(feature = "cancun")]
 use tload::Tload;
+#[cfg(feature = "cancun")]
 use tstore::Tstore;
 
 pub use sstore::calc_expected_tx_refund;
@@ -236,6 +242,10 @@ fn fn_gen_associated_ops(opcode_id: &OpcodeId) -> FnGenAssociatedOps {
         OpcodeId::CHAINID => GetBlockHeaderField::<{ OpcodeId::CHAINID }>::gen_associated_ops,
         OpcodeId::SELFBALANCE => Selfbalance::gen_associated_ops,
         OpcodeId::BASEFEE => GetBlockHeaderField::<{ OpcodeId::BASEFEE }>::gen_associated_ops,
+        OpcodeId::BLOBHASH => Blobhash::gen_associated_ops,
+        OpcodeId::BLOBBASEFEE => {
+            GetBlockHeaderField::<{ OpcodeId::BLOBBASEFEE }>::gen_associated_ops
+        }
         OpcodeId::POP => StackPopOnlyOpcode::<1>::gen_associated_ops,
         OpcodeId::MCOPY => MCopy::gen_associated_ops,
         OpcodeId::MLOAD => Mload::gen_associated_ops,
@@ -249,8 +259,12 @@ fn fn_gen_associated_ops(opcode_id: &OpcodeId) -> FnGenAssociatedOps {
         OpcodeId::MSIZE => Msize::gen_associated_ops,
         OpcodeId::GAS => Gas::gen_associated_ops,
         OpcodeId::JUMPDEST => Dummy::gen_associated_ops,
+        #[cfg(feature = "cancun")]
         OpcodeId::TLOAD => Tload::gen_associated_ops,
+        #[cfg(feature = "cancun")]
         OpcodeId::TSTORE => Tstore::gen_associated_ops,
+        #[cfg(not(feature = "cancun"))]
+        OpcodeId::TLOAD | OpcodeId::TSTORE => unsupported_cancun_opcode,
         OpcodeId::DUP1 => Dup::<1>::gen_associated_ops,
         OpcodeId::DUP2 => Dup::<2>::gen_associated_ops,
         OpcodeId::DUP3 => Dup::<3>::gen_associated_ops,
@@ -402,39 +416,41 @@ pub fn gen_associated_ops(
 ) -> Result<Vec<ExecStep>, Error> {
     #[cfg(feature = "enable-memory")]
     if GETH_TRACE_CHECK_LEVEL.should_check() {
-        let memory_enabled = !geth_steps.iter().all(|s| s.memory.is_empty());
-        assert!(memory_enabled);
-        if memory_enabled {
-            #[allow(clippy::collapsible_else_if)]
-            if state.call_ctx()?.memory != geth_steps[0].memory {
-                log::error!(
-                    "wrong mem before {:?}. len in state {}, len in step {}",
-                    opcode_id,
-                    &state.call_ctx()?.memory.len(),
-                    &geth_steps[0].memory.len(),
-                );
-                log::error!("state mem {:?}", &state.call_ctx()?.memory);
-                log::error!("step  mem {:?}", &geth_steps[0].memory);
-
-                for i in 0..std::cmp::min(
-                    state.call_ctx()?.memory.0.len(),
-                    geth_steps[0].memory.0.len(),
-                ) {
-                    let state_mem = state.call_ctx()?.memory.0[i];
-                    let step_mem = geth_steps[0].memory.0[i];
-                    if state_mem != step_mem {
-                        log::error!(
-                            "diff at {}: state {:?} != step {:?}",
-                            i,
-                            state_mem,
-                            step_mem
-                        );
+        // unknown for a trace fed step by step, see `StreamingTx::set_memory_captured`
+        if let Some(memory_enabled) = state.tx_ctx.memory_captured {
+            check!(memory_enabled);
+            if memory_enabled {
+                #[allow(clippy::collapsible_else_if)]
+                if state.call_ctx()?.memory != geth_steps[0].memory {
+                    log::error!(
+                        "wrong mem before {:?}. len in state {}, len in step {}",
+                        opcode_id,
+                        &state.call_ctx()?.memory.len(),
+                        &geth_steps[0].memory.len(),
+                    );
+                    log::error!("state mem {:?}", &state.call_ctx()?.memory);
+                    log::error!("step  mem {:?}", &geth_steps[0].memory);
+
+                    for i in 0..std::cmp::min(
+                        state.call_ctx()?.memory.0.len(),
+                        geth_steps[0].memory.0.len(),
+                    ) {
+                        let state_mem = state.call_ctx()?.memory.0[i];
+                        let step_mem = geth_steps[0].memory.0[i];
+                        if state_mem != step_mem {
+                            log::error!(
+                                "diff at {}: state {:?} != step {:?}",
+                                i,
+                                state_mem,
+                                step_mem
+                            );
+                        }
                     }
+                    if GETH_TRACE_CHECK_LEVEL.should_panic() {
+                        panic!("mem wrong");
+                    }
+                    state.call_ctx_mut()?.memory = geth_steps[0].memory.clone();
                 }
-                if GETH_TRACE_CHECK_LEVEL.should_panic() {
-                    panic!("mem wrong");
-                }
-                state.call_ctx_mut()?.memory = geth_steps[0].memory.clone();
             }
         }
     }
@@ -569,7 +585,7 @@ fn dummy_gen_selfdestruct_ops(
     let sender = state.call()?.address;
     let receiver = state.call_ctx_mut()?.stack.pop()?.to_address();
     #[cfg(feature = "enable-stack")]
-    assert_eq!(receiver, geth_step.stack.last()?.to_address());
+    check_eq!(receiver, geth_step.stack.last()?.to_address());
 
     let is_warm = state.sdb.check_account_in_access_list(&receiver);
     state.push_op_reversible(
@@ -600,7 +616,9 @@ fn dummy_gen_selfdestruct_ops(
         value
     );
     // NOTE: In this dummy implementation we assume that the receiver already
-    // exists.
+    // exists. The storage of the sender is not cleared: the state circuit and
+    // the MPT updates have no storage deletion, so an account re-created at
+    // the same address in the block is not proven to start with empty storage.
 
     state.push_op_reversible(
         &mut exec_step,
@@ -655,3 +673,16 @@ fn dummy_gen_selfdestruct_ops(
     )?;
     Ok(vec![exec_step])
 }
+
+/// TLOAD and TSTORE (EIP-1153) without the `cancun` feature: the circuits
+/// have no gadget for them, so the block cannot be proven.
+#[cfg(not(feature = "cancun"))]
+fn unsupported_cancun_opcode(
+    _state: &mut CircuitInputStateRef,
+    geth_steps: &[GethExecStep],
+) -> Result<Vec<ExecStep>, Error> {
+    Err(Error::UnsupportedOpcode {
+        opcode: geth_steps[0].op,
+        required_feature: "cancun",
+    })
+}
@@ -343,13 +343,13 @@ where
 
         #[cfg(feature = "enable-stack")]
         for (i, input) in stack_inputs.iter().enumerate() {
-            assert_eq!(*input, geth_step.stack.nth_last(i)?);
+            check_eq!(*input, geth_step.stack.nth_last(i)?);
         }
         let output = Self::handle(stack_inputs);
         state.stack_push(&mut exec_step, output)?;
 
         #[cfg(feature = "enable-stack")]
-        assert_eq!(
+        check_eq!(
             output,
             geth_steps[1].stack.nth_last(0)?,
             "stack mismatch, opcode: {}, inputs: {}, actual: {:x}, expected: {:x}",
@@ -21,7 +21,7 @@ impl Opcode for Balance {
         let address_word = state.stack_pop(&mut exec_step)?;
         let address = address_word.to_address();
         #[cfg(feature = "enable-stack")]
-        assert_eq!(address_word, geth_step.stack.last()?);
+        check_eq!(address_word, geth_step.stack.last()?);
 
         // Read transaction ID, rw_counter_end_of_reversion, and is_persistent
         // from call context.
@@ -77,7 +77,7 @@ impl Opcode for Balance {
 
         // Write the BALANCE result to stack.
         #[cfg(feature = "enable-stack")]
-        assert_eq!(geth_steps[1].stack.last()?, balance);
+        check_eq!(geth_steps[1].stack.last()?, balance);
         state.stack_push(&mut exec_step, balance)?;
 
         Ok(vec![exec_step])
@@ -11,14 +11,13 @@ use crate::{
     operation::{
         AccountField, AccountOp, CallContextField, StorageOp, TxReceiptField, TxRefundOp, RW,
     },
-    precompile::{execute_precompiled, PrecompileCalls},
+    precompile::{
+        check_precompile_supported, execute_precompiled, PrecompileCalls, LAST_PRECOMPILE_ADDRESS,
+    },
     Error,
 };
 use eth_types::{
-    evm_types::{
-        gas_utils::{tx_access_list_gas_cost, tx_data_gas_cost},
-        GasCost, MAX_REFUND_QUOTIENT_OF_GAS_USED,
-    },
+    evm_types::{GasCost, MAX_INIT_CODE_SIZE, MAX_REFUND_QUOTIENT_OF_GAS_USED},
     state_db::CodeDB,
     utils::is_precompiled,
     Bytecode, ToWord, Word,
@@ -95,7 +94,16 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
             }
         }
     } else {
-        // else, add 3 ( or 6 after curie) RW read operations for transaction L1 fee.
+        // the caller of a non l1 msg tx must not have code (EIP-3607)
+        let caller_acc = state.sdb.get_account(&caller_address).1.clone();
+        state.account_read(
+            &mut exec_step,
+            caller_address,
+            AccountField::CodeHash,
+            caller_acc.code_hash_read().to_word(),
+        )?;
+
+        // add 3 ( or 6 after curie) RW read operations for transaction L1 fee.
         gen_tx_l1_fee_ops(state, &mut exec_step)?;
     }
 
@@ -108,7 +116,7 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
     )?;
 
     // the rw delta before is:
-    // + for non-l1 msg tx: 3 or 6 (rw for fee oracle contrace)
+    // + for non-l1 msg tx: 1 (read codehash) + 3 or 6 (rw for fee oracle contrace)
     // + for scroll l1-msg tx:
     //   * caller existed: 1 (read codehash)
     //   * caller not existed: 3 (read codehash and create account)
@@ -148,7 +156,7 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
     )?;
 
     // Add precompile contract address to access list
-    for address in 1..=9 {
+    for address in 1..=LAST_PRECOMPILE_ADDRESS as u64 {
         let address = eth_types::Address::from_low_u64_be(address);
         let is_warm_prev = !state.sdb.add_account_to_access_list(address);
         state.tx_access_list_account_write(
@@ -182,28 +190,29 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
         )?;
     }
 
-    // Calculate gas cost of init code only for EIP-3860 of Shanghai.
-    let init_code_gas_cost = if state.tx.is_create() {
-        (state.tx.input.len() as u64 + 31) / 32 * eth_types::evm_types::INIT_CODE_WORD_GAS
-    } else {
-        0
-    };
+    // Init code size is limited by EIP-3860 of Shanghai.
+    if state.tx.is_create() {
+        check!(
+            state.tx.input.len() as u64 <= MAX_INIT_CODE_SIZE,
+            "init code size {} of creation tx exceeds {MAX_INIT_CODE_SIZE}",
+            state.tx.input.len()
+        );
+    }
 
     // Calculate intrinsic gas cost
-    let call_data_gas_cost = tx_data_gas_cost(&state.tx.input);
-    let access_list_gas_cost = tx_access_list_gas_cost(&state.tx.access_list);
-    let intrinsic_gas_cost = if state.tx.is_create() {
-        GasCost::CREATION_TX.as_u64()
-    } else {
-        GasCost::TX.as_u64()
-    } + call_data_gas_cost
-        + access_list_gas_cost
-        + init_code_gas_cost;
-    log::trace!("intrinsic_gas_cost {intrinsic_gas_cost}, call_data_gas_cost {call_data_gas_cost}, access_list_gas_cost {access_list_gas_cost}, init_code_gas_cost {init_code_gas_cost}, &mut exec_step.gas_cost {:?}", &mut exec_step.gas_cost);
+    let intrinsic_gas_cost = state.block.gas_schedule.tx_intrinsic_gas(
+        state.tx.is_create(),
+        &state.tx.input,
+        &state.tx.access_list,
+    );
+    log::trace!("intrinsic_gas_cost {intrinsic_gas_cost}");
     exec_step.gas_cost = GasCost(intrinsic_gas_cost);
 
     // Get code_hash of callee account
     let callee_account = &state.sdb.get_account(&call.address).1.clone();
+    if !call.is_create() {
+        check_precompile_supported(&call.address)?;
+    }
     let is_precompile = is_precompiled(&call.address);
     let callee_exists = !callee_account.is_empty();
     //if !callee_exists && call.value.is_zero() {
@@ -257,11 +266,20 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
         }
     }
 
-    // Transfer with fee
+    // Transfer with fee. The blob gas of a blob tx is paid upfront at the
+    // blob base fee and never refunded (EIP-4844).
     let fee = if state.tx.tx_type.is_l1_msg() {
         0.into()
     } else {
-        state.tx.gas_price * state.tx.gas + state.tx_ctx.l1_fee
+        let blob_base_fee = state
+            .block
+            .blocks
+            .get(&state.tx.block_num)
+            .unwrap()
+            .blob_base_fee;
+        state.tx.gas_price * state.tx.gas
+            + state.tx_ctx.l1_fee
+            + blob_base_fee * state.tx.blob.blob_gas()
     };
     state.transfer_with_fee(
         &mut exec_step,
@@ -315,6 +333,7 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
                 dst_type: CopyDataType::Bytecode,
                 dst_id: NumberOrHash::Hash(code_hash),
                 log_id: None,
+                origin: None,
                 rw_counter_start,
                 copy_bytes: CopyBytes::new(bytes, None, None),
                 access_list: vec![],
@@ -418,12 +437,18 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
                 state.call_context_write(&mut exec_step, call.call_id, field, value)?;
             }
 
-            let precompile_call: PrecompileCalls = call.address.0[19].into();
-            let (result, precompile_call_gas_cost, has_oog_err) = execute_precompiled(
-                &precompile_call.into(),
-                &state.tx.input,
-                exec_step.gas_left.0 - exec_step.gas_cost.as_u64(),
-            );
+            let precompile_call = PrecompileCalls::try_from(&call.address)?;
+            // the tx gas limit has been checked against the intrinsic gas by the tracer, but a
+            // malformed trace must not underflow here.
+            let callee_gas_left = exec_step
+                .gas_left
+                .0
+                .checked_sub(exec_step.gas_cost.as_u64())
+                .ok_or(Error::InvalidGethExecTrace(
+                    "intrinsic gas exceeds gas limit of tx to precompile",
+                ))?;
+            let (result, precompile_call_gas_cost, has_oog_err) =
+                execute_precompiled(&precompile_call.into(), &state.tx.input, callee_gas_left);
 
             // insert a copy event (input) generate word memory read for input.
             // we do not handle output / return since it is not part of the mined tx
@@ -434,7 +459,10 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
             };
             // we copy the truncated part or whole call data
             let src_addr = call.call_data_offset;
-            let src_addr_end = call.call_data_offset.checked_add(n_input_bytes).unwrap();
+            let src_addr_end = call
+                .call_data_offset
+                .checked_add(n_input_bytes)
+                .ok_or(Error::InternalError("precompile call data range overflow"))?;
 
             let copy_steps = state
                 .tx
@@ -462,6 +490,7 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
                     dst_type: CopyDataType::RlcAcc,
                     dst_addr: 0,
                     log_id: None,
+                    origin: None,
                     rw_counter_start,
                     copy_bytes: CopyBytes::new(copy_steps, None, None),
                     access_list: vec![],
@@ -609,6 +638,7 @@ pub fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Er
         state.tx.gas_price - block_info.base_fee
     };
     let gas_cost = state.tx.gas - exec_step.gas_left.0 - effective_refund;
+    state.tx.gas_used = gas_cost;
     let coinbase_reward = if state.tx.tx_type.is_l1_msg() {
         Word::zero()
     } else {
@@ -832,12 +862,12 @@ fn gen_tx_l1_fee_ops(
 }
 
 // Add two copy-events for tx access-list addresses and storage keys for
-// EIP-1559 and EIP-2930.
+// EIP-1559, EIP-2930 and EIP-4844.
 fn gen_tx_access_list_ops(
     state: &mut CircuitInputStateRef,
     exec_step: &mut ExecStep,
 ) -> Result<(), Error> {
-    if !(state.tx.tx_type.is_eip1559() || state.tx.tx_type.is_eip2930()) {
+    if !(state.tx.tx_type.is_dynamic_fee() || state.tx.tx_type.is_eip2930()) {
         return Ok(());
     }
 
@@ -906,6 +936,7 @@ fn add_access_list_address_copy_event(
         copy_bytes,
         access_list,
         log_id: None,
+        origin: None,
     };
 
     state.push_copy(exec_step, copy_event);
@@ -991,9 +1022,338 @@ fn add_access_list_storage_key_copy_event(
         copy_bytes,
         access_list,
         log_id: None,
+        origin: None,
     };
 
     state.push_copy(exec_step, copy_event);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod begin_end_tx_tests {
+    use crate::{
+        error::{Error, InvalidTxReason},
+        mock::BlockData,
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{GasCost, MAX_REFUND_QUOTIENT_OF_GAS_USED},
+        geth_types::{GethData, TxType},
+        sign_types::SECP256K1_Q,
+        Word,
+    };
+    use mock::{
+        eth,
+        test_ctx::{helpers::*, TestContext},
+        MOCK_ACCOUNTS,
+    };
+
+    #[test]
+    fn unsupported_tx_type_is_rejected() {
+        let code = bytecode! {
+            STOP
+        };
+        let mut block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        // pretend the tx is an EIP-7702 set code tx
+        block.eth_block.transactions[0].transaction_type = Some(4.into());
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let err = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::UnsupportedTxType {
+                index: 0,
+                tx_type: 4,
+                required_feature: None,
+            }
+        ));
+        assert!(builder.block.txs().is_empty());
+    }
+
+    #[cfg(feature = "cancun")]
+    #[test]
+    fn blob_tx_is_eip4844() {
+        let code = bytecode! {
+            STOP
+        };
+        let mut block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .transaction_type(2)
+                    .max_priority_fee_per_gas(Word::from(1))
+                    .max_fee_per_gas(Word::from(0x10_0000_0000u64));
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        // a blob tx traces as the EIP-1559 tx with the same fields
+        let tx = &mut block.eth_block.transactions[0];
+        tx.transaction_type = Some(3.into());
+        tx.other = serde_json::from_value(serde_json::json!({
+            "maxFeePerBlobGas": "0x3",
+            "blobVersionedHashes": [
+                "0x01a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8",
+            ],
+        }))
+        .unwrap();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let tx = &builder.block.txs()[0];
+        assert_eq!(tx.tx_type, TxType::Eip4844);
+        assert_eq!(tx.blob.blob_versioned_hashes.len(), 1);
+    }
+
+    #[cfg(not(feature = "scroll"))]
+    #[test]
+    fn point_evaluation_call_is_rejected() {
+        let point_evaluation = eth_types::Address::from_low_u64_be(0x0a);
+        let code = bytecode! {
+            .op_staticcall(0xffff, point_evaluation, 0, 0, 0, 0)
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let err = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::UnsupportedPrecompile { address } if address == point_evaluation
+        ));
+    }
+
+    #[test]
+    fn high_s_tx_is_rejected() {
+        let code = bytecode! {
+            STOP
+        };
+        let mut block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        // (r, n - s) is as valid as (r, s), but EIP-2 only accepts the low one
+        let tx = &mut block.eth_block.transactions[0];
+        tx.s = Word::from_big_endian(&SECP256K1_Q.to_bytes_be()) - tx.s.max(Word::one());
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let err = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::EthTypeError(eth_types::Error::SignatureHighS)
+        ));
+    }
+
+    #[test]
+    fn sender_with_code_is_rejected() {
+        let code = bytecode! {
+            STOP
+        };
+        let mut block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code.clone()),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        // deploy code at the sender after tracing, geth refuses to trace such a tx
+        let sender = block.eth_block.transactions[0].from;
+        block
+            .accounts
+            .iter_mut()
+            .find(|acc| acc.address == sender)
+            .unwrap()
+            .code = code.code().into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let result = builder.handle_block(&block.eth_block, &block.geth_traces);
+
+        if cfg!(feature = "scroll") {
+            assert!(result.is_ok());
+        } else {
+            assert!(matches!(result, Err(Error::SenderHasCode { index: 0 })));
+        }
+        assert!(builder.block.txs().is_empty());
+    }
+
+    #[test]
+    fn intrinsic_gas_too_low_tx_is_rejected() {
+        let code = bytecode! {
+            STOP
+        };
+        let mut block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        let tx = &mut block.eth_block.transactions[0];
+        tx.gas = (GasCost::TX.as_u64() - 1).into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let err = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::InvalidTx {
+                index: 0,
+                reason: InvalidTxReason::IntrinsicGasTooLow {
+                    intrinsic_gas: 21000,
+                    ..
+                },
+            }
+        ));
+    }
+
+    #[test]
+    fn max_nonce_tx_is_rejected() {
+        let code = bytecode! {
+            STOP
+        };
+        let mut block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        // EIP-2681: the sender nonce can't be increased past 2^64 - 1
+        block.eth_block.transactions[0].nonce = u64::MAX.into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let err = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::InvalidTx {
+                index: 0,
+                reason: InvalidTxReason::NonceMax,
+            }
+        ));
+    }
+
+    #[test]
+    fn gas_used_matches_trace() {
+        // The second SSTORE resets the slot, so the tx gets a refund.
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            PUSH1(0x00)
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        assert_eq!(builder.block.txs[0].gas_used, block.geth_traces[0].gas.0);
+    }
+
+    #[test]
+    fn refund_capped_by_gas_used() {
+        // Each SSTORE clears a slot for a refund of SSTORE_CLEARS_SCHEDULE,
+        // more than a fifth of the gas used.
+        let code = bytecode! {
+            PUSH1(0x00)
+            PUSH1(0x00)
+            SSTORE
+            PUSH1(0x00)
+            PUSH1(0x01)
+            SSTORE
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code)
+                    .storage([(0.into(), 1.into()), (1.into(), 1.into())].into_iter());
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let refund = builder
+            .block
+            .container
+            .tx_refund
+            .iter()
+            .map(|op| op.op().value)
+            .last()
+            .unwrap();
+        assert_eq!(refund, 2 * GasCost::SSTORE_CLEARS_SCHEDULE.as_u64());
+
+        let tx = &builder.block.txs[0];
+        let end_tx = tx.steps().last().unwrap();
+        let gas_used_before_refund = tx.gas - end_tx.gas_left.0;
+        let max_refund = gas_used_before_refund / MAX_REFUND_QUOTIENT_OF_GAS_USED as u64;
+        assert!(refund > max_refund);
+        assert_eq!(tx.gas_used, gas_used_before_refund - max_refund);
+        assert_eq!(tx.gas_used, block.geth_traces[0].gas.0);
+    }
+}
@@ -0,0 +1,103 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    Error,
+};
+use eth_types::{GethExecStep, ToWord, Word};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::BLOBHASH`](crate::evm::OpcodeId::BLOBHASH)
+/// `OpcodeId`.
+///
+/// The result is the versioned hash at `index` of the blob tx, or zero if
+/// `index` is out of range, as for txs of any other type.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Blobhash;
+
+impl Opcode for Blobhash {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let index = state.stack_pop(&mut exec_step)?;
+        #[cfg(feature = "enable-stack")]
+        check_eq!(index, geth_step.stack.last()?);
+
+        let versioned_hash = usize::try_from(index)
+            .ok()
+            .and_then(|index| state.tx.blob.blob_versioned_hashes.get(index))
+            .map_or(Word::zero(), |hash| hash.to_word());
+        #[cfg(feature = "enable-stack")]
+        check_eq!(versioned_hash, geth_steps[1].stack.last()?);
+        state.stack_push(&mut exec_step, versioned_hash)?;
+
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod blobhash_tests {
+    use crate::{
+        circuit_input_builder::ExecState,
+        mock::BlockData,
+        operation::{StackOp, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{OpcodeId, StackAddress},
+        geth_types::GethData,
+        Word,
+    };
+    use mock::test_ctx::{helpers::*, TestContext};
+    use pretty_assertions::assert_eq;
+
+    #[cfg(not(feature = "scroll"))]
+    #[test]
+    fn blobhash_opcode_impl() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            BLOBHASH
+            STOP
+        };
+
+        // Get the execution steps from the external tracer
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::BLOBHASH))
+            .unwrap();
+
+        assert_eq!(
+            [0, 1]
+                .map(|idx| &builder.block.container.stack[step.bus_mapping_instance[idx].as_usize()])
+                .map(|operation| (operation.rw(), operation.op())),
+            [
+                (
+                    RW::READ,
+                    &StackOp::new(1, StackAddress::from(1023), Word::one())
+                ),
+                (
+                    RW::WRITE,
+                    &StackOp::new(1, StackAddress::from(1023), Word::zero())
+                ),
+            ]
+        );
+    }
+}
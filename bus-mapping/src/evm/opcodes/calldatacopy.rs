@@ -24,9 +24,9 @@ impl Opcode for Calldatacopy {
         let length = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(memory_offset, geth_step.stack.nth_last(0)?);
-            assert_eq!(data_offset, geth_step.stack.nth_last(1)?);
-            assert_eq!(length, geth_step.stack.nth_last(2)?);
+            check_eq!(memory_offset, geth_step.stack.nth_last(0)?);
+            check_eq!(data_offset, geth_step.stack.nth_last(1)?);
+            check_eq!(length, geth_step.stack.nth_last(2)?);
         }
         if state.call()?.is_root {
             state.call_context_read(
@@ -106,6 +106,7 @@ fn gen_copy_event(
             dst_id: NumberOrHash::Number(state.call()?.call_id),
             dst_addr,
             log_id: None,
+            origin: None,
             rw_counter_start,
             copy_bytes,
             access_list: vec![],
@@ -123,6 +124,7 @@ fn gen_copy_event(
             dst_id: NumberOrHash::Number(state.call()?.call_id),
             dst_addr,
             log_id: None,
+            origin: None,
             rw_counter_start,
             //fetch pre read and write bytes of CopyBytes
             copy_bytes: CopyBytes::new(read_steps, Some(write_steps), Some(prev_bytes)),
@@ -9,7 +9,7 @@ use crate::{
         precompiles::gen_associated_ops as precompile_associated_ops,
     },
     operation::{AccountField, CallContextField, TxAccessListAccountOp},
-    precompile::{execute_precompiled, PrecompileCalls},
+    precompile::{check_precompile_supported, execute_precompiled, PrecompileCalls},
     Error,
 };
 use eth_types::{
@@ -133,7 +133,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
         let stack_inputs: Vec<Word> = state.stack_pops(&mut exec_step, N_ARGS)?;
         #[cfg(feature = "enable-stack")]
         for (i, input) in stack_inputs.iter().enumerate() {
-            assert_eq!(*input, geth_step.stack.nth_last(i)?);
+            check_eq!(*input, geth_step.stack.nth_last(i)?);
         }
         state.stack_push(&mut exec_step, (callee_call.is_success as u64).into())?;
 
@@ -194,6 +194,9 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
         )?;
 
         let code_address = callee_call.code_address();
+        if let Some(code_address) = code_address {
+            check_precompile_supported(&code_address)?;
+        }
         let is_precompile = code_address
             .map(|ref addr| is_precompiled(addr))
             .unwrap_or(false);
@@ -348,7 +351,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                 }
 
                 #[cfg(feature = "enable-stack")]
-                assert_eq!(
+                check_eq!(
                     state.caller_ctx()?.stack.stack_pointer().0,
                     geth_step.stack.stack_pointer().0 + N_ARGS - 1
                 );
@@ -411,6 +414,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                             dst_type: CopyDataType::RlcAcc,
                             dst_addr: 0,
                             log_id: None,
+                            origin: None,
                             rw_counter_start,
                             copy_bytes: CopyBytes::new(copy_steps, None, None),
                             access_list: vec![],
@@ -442,6 +446,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                             dst_type: CopyDataType::Memory,
                             dst_addr: 0,
                             log_id: None,
+                            origin: None,
                             rw_counter_start,
                             copy_bytes: CopyBytes::new(copy_steps, None, Some(prev_bytes)),
                             access_list: vec![],
@@ -462,6 +467,16 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                             length,
                             &result,
                         )?;
+                    // Without read steps, the whole result is copied from its RLC.
+                    let src_type = if read_steps.is_some() {
+                        CopyDataType::Memory
+                    } else {
+                        CopyDataType::RlcAcc
+                    };
+                    let (read_steps, write_steps) = match read_steps {
+                        Some(read_steps) => (read_steps, Some(write_steps)),
+                        None => (write_steps, None),
+                    };
                     let returned_bytes = read_steps
                         .iter()
                         .filter(|(_, _, is_mask)| !*is_mask)
@@ -471,19 +486,16 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                         &mut exec_step,
                         CopyEvent {
                             src_id: NumberOrHash::Number(callee_call.call_id),
-                            src_type: CopyDataType::Memory,
+                            src_type,
                             src_addr: 0,
                             src_addr_end: length as u64,
                             dst_id: NumberOrHash::Number(callee_call.caller_id),
                             dst_type: CopyDataType::Memory,
                             dst_addr: callee_call.return_data_offset,
                             log_id: None,
+                            origin: None,
                             rw_counter_start,
-                            copy_bytes: CopyBytes::new(
-                                read_steps,
-                                Some(write_steps),
-                                Some(prev_bytes),
-                            ),
+                            copy_bytes: CopyBytes::new(read_steps, write_steps, Some(prev_bytes)),
                             access_list: vec![],
                         },
                     );
@@ -564,7 +576,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
             // 3. Call to account with non-empty code.
             (false, _, false) => {
                 #[cfg(feature = "enable-stack")]
-                assert_eq!(
+                check_eq!(
                     state.caller_ctx()?.stack.stack_pointer().0,
                     geth_step.stack.stack_pointer().0 + N_ARGS - 1
                 );
@@ -1076,4 +1088,90 @@ pub mod tests {
             }
         }
     }
+
+    #[test]
+    fn precompile_return_data_reads_elided() {
+        use crate::{
+            circuit_input_builder::{CircuitsParams, CopyDataType, ExecState},
+            mock::BlockData,
+            operation::Target,
+        };
+        use eth_types::{bytecode, geth_types::GethData, word};
+        use mock::{
+            test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+            TestContext,
+        };
+
+        // identity of a word at 0x10, returning all or the first half of it.
+        for (ret_size, src_type, return_data_reads) in [
+            (0x20, CopyDataType::RlcAcc, 0),
+            (0x10, CopyDataType::Memory, 1),
+        ] {
+            let code = PrecompileCallArgs {
+                setup_code: bytecode! {
+                    PUSH16(word!("0123456789ABCDEF0123456789ABCDEF"))
+                    PUSH1(0x00)
+                    MSTORE
+                },
+                ret_size: Word::from(ret_size),
+                ret_offset: Word::from(0x20),
+                call_data_offset: Word::from(0x10),
+                call_data_length: Word::from(0x20),
+                address: Word::from(0x4),
+                ..Default::default()
+            }
+            .with_call_op(OpcodeId::STATICCALL);
+            let block: GethData = TestContext::<2, 1>::new(
+                None,
+                account_0_code_account_1_no_code(code),
+                tx_from_1_to_0,
+                |block, _tx| block.number(0xcafeu64),
+            )
+            .unwrap()
+            .into();
+
+            let mut builder =
+                BlockData::new_from_geth_data_with_params(block.clone(), CircuitsParams::default())
+                    .new_circuit_input_builder();
+            builder
+                .handle_block(&block.eth_block, &block.geth_traces)
+                .unwrap();
+
+            let step = builder.block.txs[0]
+                .steps()
+                .iter()
+                .find(|step| step.exec_state == ExecState::Op(OpcodeId::STATICCALL))
+                .unwrap();
+            let memory_rws = step
+                .bus_mapping_instance
+                .iter()
+                .filter(|op_ref| op_ref.0 == Target::Memory)
+                .map(|op_ref| builder.block.container.memory[op_ref.1].rw())
+                .collect::<Vec<_>>();
+            // 2 words of input, 1 word of output and 1 word of return data.
+            assert_eq!(
+                memory_rws.iter().filter(|rw| rw.is_read()).count(),
+                2 + return_data_reads
+            );
+            assert_eq!(memory_rws.iter().filter(|rw| rw.is_write()).count(), 2);
+            let redundant_reads = builder
+                .block
+                .container
+                .redundant_memory_reads(&step.bus_mapping_instance);
+            // only a truncated output is read back.
+            assert_eq!(redundant_reads, return_data_reads);
+
+            let return_data_copy = builder.block.copy_events.last().unwrap();
+            assert_eq!(return_data_copy.src_type, src_type);
+            assert_eq!(return_data_copy.dst_type, CopyDataType::Memory);
+            assert_eq!(
+                return_data_copy.rw_counter_delta(),
+                if src_type == CopyDataType::Memory {
+                    2
+                } else {
+                    1
+                }
+            );
+        }
+    }
 }
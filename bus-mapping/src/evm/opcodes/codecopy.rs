@@ -25,9 +25,9 @@ impl Opcode for Codecopy {
 
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(dest_offset, geth_step.stack.nth_last(0)?);
-            assert_eq!(code_offset, geth_step.stack.nth_last(1)?);
-            assert_eq!(length, geth_step.stack.nth_last(2)?);
+            check_eq!(dest_offset, geth_step.stack.nth_last(0)?);
+            check_eq!(code_offset, geth_step.stack.nth_last(1)?);
+            check_eq!(length, geth_step.stack.nth_last(2)?);
         }
 
         let copy_event = gen_copy_event(
@@ -77,6 +77,7 @@ fn gen_copy_event(
         dst_id: NumberOrHash::Number(state.call()?.call_id),
         dst_addr,
         log_id: None,
+        origin: None,
         rw_counter_start,
         //fetch pre write bytes of CopyBytes
         copy_bytes: CopyBytes::new(copy_steps, None, Some(prev_bytes)),
@@ -228,5 +229,9 @@ mod codecopy_tests {
                 assert_eq!(*is_code, bytecode_element.is_code);
             }
         }
+
+        // bytecode is read byte by byte, so there is no word mode.
+        assert!(!copy_events[0].is_word_mode());
+        assert_eq!(copy_events[0].copy_rows(), word_ops * 32 * 2);
     }
 }
@@ -23,7 +23,7 @@ impl Opcode for Codesize {
         let codesize = code.len();
 
         #[cfg(feature = "enable-stack")]
-        assert_eq!(codesize, geth_steps[1].stack.last()?.as_usize());
+        check_eq!(codesize, geth_steps[1].stack.last()?.as_usize());
         state.stack_push(&mut exec_step, codesize.into())?;
 
         Ok(vec![exec_step])
@@ -108,7 +108,7 @@ impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
         let stack_inputs = state.stack_pops(&mut exec_step, n_pop)?;
         #[cfg(feature = "enable-stack")]
         for (i, value) in stack_inputs.iter().enumerate() {
-            assert_eq!(*value, geth_step.stack.nth_last(i)?);
+            check_eq!(*value, geth_step.stack.nth_last(i)?);
         }
 
         state.stack_push(
@@ -241,7 +241,7 @@ impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
                 let salt = stack_inputs[3];
                 log::trace!("create2 initcode {}", hex::encode(&initcode));
                 log::trace!("create2 caller {:?}", caller.address);
-                assert_eq!(
+                check_eq!(
                     address,
                     get_create2_address(caller.address, salt.to_be_bytes(), initcode.clone())
                 );
@@ -257,7 +257,7 @@ impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
                 stream.append(&Word::from(caller_nonce));
                 stream.out().to_vec()
             };
-            assert_eq!(
+            check_eq!(
                 address,
                 H160(keccak256(&address_preimage)[12..].try_into().unwrap())
             );
@@ -395,6 +395,7 @@ fn handle_copy(
             dst_id: NumberOrHash::Hash(code_hash),
             dst_addr: 0,
             log_id: None,
+            origin: None,
             copy_bytes: CopyBytes::new(copy_steps, None, None),
             access_list: vec![],
         },
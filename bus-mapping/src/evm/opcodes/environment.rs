@@ -54,6 +54,12 @@ impl BlockHeaderToField for GetBlockHeaderField<{ OpcodeId::BASEFEE }> {
     }
 }
 
+impl BlockHeaderToField for GetBlockHeaderField<{ OpcodeId::BLOBBASEFEE }> {
+    fn handle(block_head: &Block) -> Word {
+        block_head.blob_base_fee
+    }
+}
+
 impl<const OP: OpcodeId> Opcode for GetBlockHeaderField<OP>
 where
     Self: BlockHeaderToField,
@@ -68,7 +74,7 @@ where
         let output = Self::handle(block_head);
 
         #[cfg(feature = "enable-stack")]
-        assert_eq!(output, geth_steps[1].stack.last()?);
+        check_eq!(output, geth_steps[1].stack.last()?);
         state.stack_push(&mut exec_step, output)?;
 
         Ok(vec![exec_step])
@@ -87,7 +93,7 @@ impl Opcode for Pc {
         let output: U256 = geth_steps[0].pc.0.into();
 
         #[cfg(feature = "enable-stack")]
-        assert_eq!(output, geth_steps[1].stack.last()?);
+        check_eq!(output, geth_steps[1].stack.last()?);
         state.stack_push(&mut exec_step, output)?;
 
         Ok(vec![exec_step])
@@ -106,7 +112,7 @@ impl Opcode for Msize {
         let output: U256 = state.call_ctx()?.memory.len().into();
 
         #[cfg(feature = "enable-stack")]
-        assert_eq!(output, geth_steps[1].stack.last()?);
+        check_eq!(output, geth_steps[1].stack.last()?);
         state.stack_push(&mut exec_step, output)?;
 
         Ok(vec![exec_step])
@@ -125,7 +131,7 @@ impl Opcode for Gas {
         let output: U256 = geth_steps[1].gas.0.into();
 
         #[cfg(feature = "enable-stack")]
-        assert_eq!(output, geth_steps[1].stack.last()?);
+        check_eq!(output, geth_steps[1].stack.last()?);
         state.stack_push(&mut exec_step, output)?;
 
         Ok(vec![exec_step])
@@ -173,4 +179,13 @@ mod tests {
             STOP
         });
     }
+
+    #[cfg(not(feature = "scroll"))]
+    #[test]
+    fn blobbasefee_opcode_impl() {
+        test_trace(bytecode! {
+            BLOBBASEFEE
+            STOP
+        });
+    }
 }
@@ -20,7 +20,7 @@ impl Opcode for ErrorCodeStore {
 
         exec_step.error = state.get_step_err(geth_step, next_step)?;
 
-        assert!(
+        check!(
             exec_step.error == Some(ExecError::CodeStoreOutOfGas)
                 || exec_step.error == Some(ExecError::MaxCodeSizeExceeded)
         );
@@ -29,15 +29,15 @@ impl Opcode for ErrorCodeStore {
         let length = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(offset, geth_step.stack.nth_last(0)?);
-            assert_eq!(length, geth_step.stack.nth_last(1)?);
+            check_eq!(offset, geth_step.stack.nth_last(0)?);
+            check_eq!(length, geth_step.stack.nth_last(1)?);
         }
 
         // in internal call context
         let call = state.call()?;
 
         // create context check
-        assert!(call.is_create());
+        check!(call.is_create());
 
         state.handle_return(
             (Some(offset), Some(length)),
@@ -36,7 +36,7 @@ impl<const IS_CREATE2: bool> Opcode for ContractAddressCollision<IS_CREATE2> {
         let _stack_inputs = state.stack_pops(&mut exec_step, n_pop)?;
         #[cfg(feature = "enable-stack")]
         for (i, value) in _stack_inputs.iter().enumerate() {
-            assert_eq!(*value, geth_step.stack.nth_last(i)?);
+            check_eq!(*value, geth_step.stack.nth_last(i)?);
         }
 
         let _address = if IS_CREATE2 {
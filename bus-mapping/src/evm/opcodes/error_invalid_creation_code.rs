@@ -23,21 +23,21 @@ impl Opcode for ErrorCreationCode {
         let length = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(offset, geth_step.stack.nth_last(0)?);
-            assert_eq!(length, geth_step.stack.nth_last(1)?);
+            check_eq!(offset, geth_step.stack.nth_last(0)?);
+            check_eq!(length, geth_step.stack.nth_last(1)?);
         }
 
         // in create context
         let call = state.call()?;
 
         // create context check
-        assert!(call.is_create());
+        check!(call.is_create());
 
-        assert!(length > U256::zero());
+        check!(length > U256::zero());
 
         // read first byte and assert it is 0xef
         let byte = state.call_ctx()?.memory.0[offset.as_usize()];
-        assert!(byte == 0xef);
+        check!(byte == 0xef);
 
         let shift = offset.as_u64() % 32;
         let slot = offset.as_u64() - shift;
@@ -22,7 +22,7 @@ impl Opcode for InvalidJump {
         };
         exec_step.error = state.get_step_err(geth_step, next_step).unwrap();
         // assert op code can only be JUMP or JUMPI
-        assert!(geth_step.op == OpcodeId::JUMP || geth_step.op == OpcodeId::JUMPI);
+        check!(geth_step.op == OpcodeId::JUMP || geth_step.op == OpcodeId::JUMPI);
         let _counter = state.stack_pop(&mut exec_step)?;
         let is_jumpi = geth_step.op == OpcodeId::JUMPI;
         let _condition: Word = if is_jumpi {
@@ -32,9 +32,9 @@ impl Opcode for InvalidJump {
         };
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(_counter, geth_step.stack.last()?);
+            check_eq!(_counter, geth_step.stack.last()?);
             if is_jumpi {
-                assert_eq!(_condition, geth_step.stack.nth_last(1)?);
+                check_eq!(_condition, geth_step.stack.nth_last(1)?);
             }
         }
 
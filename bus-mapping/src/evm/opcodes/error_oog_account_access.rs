@@ -20,7 +20,7 @@ impl Opcode for ErrorOOGAccountAccess {
         exec_step.error = Some(ExecError::OutOfGas(OogError::AccountAccess));
 
         // assert op code is BALANCE | EXTCODESIZE | EXTCODEHASH
-        assert!([
+        check!([
             OpcodeId::BALANCE,
             OpcodeId::EXTCODESIZE,
             OpcodeId::EXTCODEHASH
@@ -30,7 +30,7 @@ impl Opcode for ErrorOOGAccountAccess {
         let address_word = state.stack_pop(&mut exec_step)?;
         let address = address_word.to_address();
         #[cfg(feature = "enable-stack")]
-        assert_eq!(address_word, geth_step.stack.last()?);
+        check_eq!(address_word, geth_step.stack.last()?);
 
         // Read transaction ID from call context.
         state.call_context_read(
@@ -51,7 +51,7 @@ impl Opcode for OOGCall {
         #[cfg(feature = "enable-stack")]
         {
             for (i, v) in stack_inputs.iter().enumerate() {
-                assert_eq!(*v, geth_step.stack.nth_last(i)?);
+                check_eq!(*v, geth_step.stack.nth_last(i)?);
             }
         }
         // Must fail.
@@ -23,7 +23,7 @@ impl Opcode for ErrorOOGLog {
         };
         exec_step.error = state.get_step_err(geth_step, next_step)?;
         // assert op code can only be Log*
-        assert!([
+        check!([
             OpcodeId::LOG0,
             OpcodeId::LOG1,
             OpcodeId::LOG2,
@@ -35,8 +35,8 @@ impl Opcode for ErrorOOGLog {
         let _msize = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(_mstart, geth_step.stack.nth_last(0)?);
-            assert_eq!(_msize, geth_step.stack.nth_last(1)?);
+            check_eq!(_mstart, geth_step.stack.nth_last(0)?);
+            check_eq!(_msize, geth_step.stack.nth_last(1)?);
         }
 
         // read static call property
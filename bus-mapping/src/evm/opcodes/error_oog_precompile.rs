@@ -24,13 +24,16 @@ impl ErrorOOGPrecompile {
         call: Call,
     ) -> Result<ExecStep, Error> {
         exec_step.error = Some(ExecError::OutOfGas(OogError::Precompile));
+        let code_address = call
+            .code_address()
+            .ok_or(Error::InternalError("precompile call without code address"))?;
 
         // callee_address
         state.call_context_read(
             &mut exec_step,
             call.call_id,
             CallContextField::CalleeAddress,
-            call.code_address().unwrap().to_word(),
+            code_address.to_word(),
         )?;
         state.call_context_read(
             &mut exec_step,
@@ -51,7 +51,7 @@ impl Opcode for OOGSloadSstore {
 
         let key = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
-        assert_eq!(key, geth_step.stack.last()?);
+        check_eq!(key, geth_step.stack.last()?);
 
         let is_warm = state
             .sdb
@@ -72,7 +72,7 @@ impl Opcode for OOGSloadSstore {
         if geth_step.op == OpcodeId::SSTORE {
             let _value = state.stack_pop(&mut exec_step)?;
             #[cfg(feature = "enable-stack")]
-            assert_eq!(_value, geth_step.stack.nth_last(1)?);
+            check_eq!(_value, geth_step.stack.nth_last(1)?);
 
             let (_, value_prev) = state.sdb.get_storage(&callee_address, &key);
             let (_, original_value) = state.sdb.get_committed_storage(&callee_address, &key);
@@ -45,7 +45,7 @@ impl Opcode for PrecompileFailed {
         let _stack_inputs = state.stack_pops(&mut exec_step, stack_input_num)?;
         #[cfg(feature = "enable-stack")]
         for (i, v) in _stack_inputs.into_iter().enumerate() {
-            assert_eq!(v, geth_step.stack.nth_last(i)?);
+            check_eq!(v, geth_step.stack.nth_last(i)?);
         }
 
         // Must fail.
@@ -20,7 +20,7 @@ impl Opcode for ErrorReturnDataOutOfBound {
         let next_step = geth_steps.get(1);
 
         exec_step.error = Some(ExecError::ReturnDataOutOfBounds);
-        assert_eq!(
+        check_eq!(
             state.get_step_err(geth_step, next_step).unwrap(),
             Some(ExecError::ReturnDataOutOfBounds)
         );
@@ -30,16 +30,16 @@ impl Opcode for ErrorReturnDataOutOfBound {
         let length = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(_memory_offset, geth_step.stack.nth_last(0)?);
-            assert_eq!(data_offset, geth_step.stack.nth_last(1)?);
-            assert_eq!(length, geth_step.stack.nth_last(2)?);
+            check_eq!(_memory_offset, geth_step.stack.nth_last(0)?);
+            check_eq!(data_offset, geth_step.stack.nth_last(1)?);
+            check_eq!(length, geth_step.stack.nth_last(2)?);
         }
 
         let call_id = state.call()?.call_id;
         let call_ctx = state.call_ctx()?;
         let return_data = &call_ctx.return_data;
         let last_callee_return_data_length = state.call()?.last_callee_return_data_length;
-        assert_eq!(
+        check_eq!(
             last_callee_return_data_length as usize,
             return_data.len(),
             "callee return data size should be correct"
@@ -53,7 +53,7 @@ impl Opcode for ErrorReturnDataOutOfBound {
         let remainder_end_exceed_length =
             Word::from(last_callee_return_data_length) < remainder_end;
         // one of three must hold at least one.
-        assert!(data_offset_overflow | remainder_end_overflow | remainder_end_exceed_length);
+        check!(data_offset_overflow | remainder_end_overflow | remainder_end_exceed_length);
         // read last callee info
         state.call_context_read(
             &mut exec_step,
@@ -25,11 +25,11 @@ impl Opcode for ErrorWriteProtection {
         };
         exec_step.error = state.get_step_err(geth_step, next_step).unwrap();
         // assert error is targeting ExecError::WriteProtection.
-        assert_eq!(exec_step.clone().error.unwrap(), ExecError::WriteProtection);
+        check_eq!(exec_step.clone().error.unwrap(), ExecError::WriteProtection);
 
         let current_call = state.call()?.clone();
         // assert op code can only be following codes
-        assert!([
+        check!([
             OpcodeId::SSTORE,
             OpcodeId::TSTORE,
             OpcodeId::CREATE,
@@ -50,7 +50,7 @@ impl Opcode for ErrorWriteProtection {
             for _i in 0..3 {
                 let _v = state.stack_pop(&mut exec_step)?;
                 #[cfg(feature = "enable-stack")]
-                assert_eq!(_v, geth_step.stack.nth_last(_i)?);
+                check_eq!(_v, geth_step.stack.nth_last(_i)?);
             }
         }
 
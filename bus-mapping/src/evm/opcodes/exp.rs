@@ -45,8 +45,8 @@ impl Opcode for Exponentiation {
         let exponent = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(base, geth_step.stack.nth_last(0)?);
-            assert_eq!(exponent, geth_step.stack.nth_last(1)?);
+            check_eq!(base, geth_step.stack.nth_last(0)?);
+            check_eq!(exponent, geth_step.stack.nth_last(1)?);
         }
 
         let (exponentiation, _) = base.overflowing_pow(exponent);
@@ -26,10 +26,10 @@ impl Opcode for Extcodecopy {
         let length = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(external_address_word, geth_step.stack.nth_last(0)?);
-            assert_eq!(dest_offset, geth_step.stack.nth_last(1)?);
-            assert_eq!(offset, geth_step.stack.nth_last(2)?);
-            assert_eq!(length, geth_step.stack.nth_last(3)?);
+            check_eq!(external_address_word, geth_step.stack.nth_last(0)?);
+            check_eq!(dest_offset, geth_step.stack.nth_last(1)?);
+            check_eq!(offset, geth_step.stack.nth_last(2)?);
+            check_eq!(length, geth_step.stack.nth_last(3)?);
         }
 
         for (field, value) in [
@@ -132,6 +132,7 @@ fn gen_copy_event(
         dst_type: CopyDataType::Memory,
         dst_id: NumberOrHash::Number(state.call()?.call_id),
         log_id: None,
+        origin: None,
         rw_counter_start,
         copy_bytes: CopyBytes::new(copy_steps, None, Some(prev_bytes)),
         access_list: vec![],
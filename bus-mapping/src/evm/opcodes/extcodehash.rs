@@ -22,7 +22,7 @@ impl Opcode for Extcodehash {
         let external_address_word = state.stack_pop(&mut exec_step)?;
         let external_address = external_address_word.to_address();
         #[cfg(feature = "enable-stack")]
-        assert_eq!(external_address_word, step.stack.last()?);
+        check_eq!(external_address_word, step.stack.last()?);
 
         // Read transaction id, rw_counter_end_of_reversion, and is_persistent from call
         // context
@@ -77,7 +77,7 @@ impl Opcode for Extcodehash {
             code_hash.to_word(),
         )?;
         #[cfg(feature = "enable-stack")]
-        assert_eq!(steps[1].stack.last()?, code_hash.to_word());
+        check_eq!(steps[1].stack.last()?, code_hash.to_word());
         // Stack write of the result of EXTCODEHASH.
         state.stack_push(&mut exec_step, code_hash.to_word())?;
 
@@ -123,6 +123,59 @@ mod extcodehash_tests {
         test_ok(true, true)
     }
 
+    #[test]
+    fn precompile_account() {
+        let identity = address!("0x0000000000000000000000000000000000000004");
+        let code = bytecode! {
+            PUSH20(identity.to_word())
+            EXTCODEHASH
+            STOP
+        };
+        // An empty precompile does not exist and hashes to 0, while one that
+        // received ETH exists and has the empty code hash.
+        for (balance, expected) in [
+            (Word::zero(), U256::zero()),
+            (Word::one(), Word::from(keccak256([]))),
+        ] {
+            let block: GethData = TestContext::<3, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(address!("0x0000000000000000000000000000000000000010"))
+                        .balance(Word::from(1u64 << 20))
+                        .code(code.clone());
+                    accs[1].address(identity).balance(balance);
+                    accs[2]
+                        .address(address!("0x0000000000000000000000000000000000cafe01"))
+                        .balance(Word::from(1u64 << 20));
+                },
+                |mut txs, accs| {
+                    txs[0].to(accs[0].address).from(accs[2].address);
+                },
+                |block, _tx| block.number(0xcafeu64),
+            )
+            .unwrap()
+            .into();
+
+            let mut builder =
+                BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+            builder
+                .handle_block(&block.eth_block, &block.geth_traces)
+                .unwrap();
+
+            let step = builder.block.txs()[0]
+                .steps()
+                .iter()
+                .find(|step| step.exec_state == ExecState::Op(OpcodeId::EXTCODEHASH))
+                .unwrap();
+            let account_read =
+                &builder.block.container.account[step.bus_mapping_instance[5].as_usize()];
+            assert_eq!(account_read.op().value, expected);
+            let hash_push = &builder.block.container.stack[step.bus_mapping_instance[6].as_usize()];
+            assert_eq!(hash_push.op().value, expected);
+        }
+    }
+
     fn test_ok(exists: bool, is_warm: bool) -> Result<(), Error> {
         // In each test case, this is the external address we will call EXTCODEHASH on.
         let external_address = address!("0xaabbccddee000000000000000000000000000000");
@@ -21,7 +21,7 @@ impl Opcode for Extcodesize {
         let address_word = state.stack_pop(&mut exec_step)?;
         let address = address_word.to_address();
         #[cfg(feature = "enable-stack")]
-        assert_eq!(address_word, geth_step.stack.last()?);
+        check_eq!(address_word, geth_step.stack.last()?);
 
         // Read transaction ID, rw_counter_end_of_reversion, and is_persistent from call
         // context.
@@ -87,7 +87,7 @@ impl Opcode for Extcodesize {
 
         // Write the EXTCODESIZE result to stack.
         #[cfg(feature = "enable-stack")]
-        assert_eq!(code_size, geth_steps[1].stack.last()?);
+        check_eq!(code_size, geth_steps[1].stack.last()?);
         state.stack_push(&mut exec_step, code_size)?;
 
         Ok(vec![exec_step])
@@ -3,7 +3,7 @@ use crate::{
     circuit_input_builder::{CircuitInputStateRef, ExecStep},
     Error,
 };
-use eth_types::GethExecStep;
+use eth_types::{GethExecStep, WordExt};
 
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct Jumpi;
@@ -21,7 +21,7 @@ impl Opcode for Jumpi {
 
         if let Some(next_step) = geth_steps.get(1) {
             if condition == 0.into() {
-                assert_eq!(
+                check_eq!(
                     next_step.pc.0,
                     geth_step.pc.0 + 1,
                     "jumpi should not jump: current step {:?} next step {:?}",
@@ -29,9 +29,9 @@ impl Opcode for Jumpi {
                     next_step
                 );
             } else {
-                assert_eq!(
+                check_eq!(
                     next_step.pc.0 as u64,
-                    pc.low_u64(),
+                    pc.fit_u64(),
                     "jumpi should jump: current step {:?} next step {:?}",
                     geth_step,
                     next_step
@@ -41,7 +41,7 @@ impl Opcode for Jumpi {
 
         #[cfg(feature = "enable-stack")]
         for (i, v) in [pc, condition].into_iter().enumerate() {
-            assert_eq!(v, geth_step.stack.nth_last(i)?);
+            check_eq!(v, geth_step.stack.nth_last(i)?);
         }
 
         Ok(vec![exec_step])
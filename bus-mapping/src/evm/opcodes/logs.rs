@@ -6,7 +6,8 @@ use crate::{
     operation::{CallContextField, TxLogField},
     Error,
 };
-use eth_types::{GethExecStep, ToWord, Word};
+use eth_types::{evm_types::memory::MemoryRange, GethExecStep, ToBigEndian, ToWord, Word, H256};
+use ethers_core::types::Log as EthLog;
 
 #[derive(Clone, Copy, Debug)]
 pub(crate) struct Log;
@@ -24,8 +25,8 @@ impl Opcode for Log {
         let msize = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(mstart, geth_step.stack.nth_last(0)?);
-            assert_eq!(msize, geth_step.stack.nth_last(1)?);
+            check_eq!(mstart, geth_step.stack.nth_last(0)?);
+            check_eq!(msize, geth_step.stack.nth_last(1)?);
         }
 
         let call_id = state.call()?.call_id;
@@ -72,10 +73,12 @@ impl Opcode for Log {
             _ => panic!("currently only handle successful log state"),
         };
 
+        let mut topics = Vec::with_capacity(topic_count);
         for i in 0..topic_count {
             let topic = state.stack_pop(&mut exec_step)?;
+            topics.push(H256::from(topic.to_be_bytes()));
             #[cfg(feature = "enable-stack")]
-            assert_eq!(topic, geth_step.stack.nth_last(2 + i)?);
+            check_eq!(topic, geth_step.stack.nth_last(2 + i)?);
 
             if state.call()?.is_persistent {
                 state.tx_log_write(
@@ -97,6 +100,21 @@ impl Opcode for Log {
                 gen_copy_event(state, mstart.low_u64(), msize.low_u64(), &mut exec_step)?;
             state.push_copy(&mut exec_step, copy_event);
             state.tx_ctx.log_id += 1;
+
+            let data = state
+                .call_ctx()?
+                .memory
+                .read_chunk(MemoryRange::new_with_length(
+                    mstart.low_u64(),
+                    msize.low_u64(),
+                ));
+            let address = state.call()?.address;
+            state.tx.logs.push(EthLog {
+                address,
+                topics,
+                data: data.into(),
+                ..Default::default()
+            });
         }
 
         // reconstruction
@@ -123,7 +141,7 @@ fn gen_copy_event(
 ) -> Result<CopyEvent, Error> {
     let rw_counter_start = state.block_ctx.rwc;
 
-    assert!(state.call()?.is_persistent, "Error: Call is not persistent");
+    check!(state.call()?.is_persistent, "Error: Call is not persistent");
 
     let (src_addr, src_addr_end) = (memory_start, memory_start.checked_add(msize).unwrap());
     let (read_steps, write_steps) = state.gen_copy_steps_for_log(exec_step, src_addr, msize)?;
@@ -137,6 +155,7 @@ fn gen_copy_event(
         dst_id: NumberOrHash::Number(state.tx_ctx.id()),
         dst_addr: 0,
         log_id: Some(state.tx_ctx.log_id as u64 + 1),
+        origin: None,
         rw_counter_start,
         copy_bytes: CopyBytes::new(read_steps, Some(write_steps), None),
         access_list: vec![],
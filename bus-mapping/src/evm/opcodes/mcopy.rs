@@ -25,9 +25,9 @@ impl Opcode for MCopy {
 
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(dest_offset, geth_step.stack.nth_last(0)?);
-            assert_eq!(src_offset, geth_step.stack.nth_last(1)?);
-            assert_eq!(length, geth_step.stack.nth_last(2)?);
+            check_eq!(dest_offset, geth_step.stack.nth_last(0)?);
+            check_eq!(src_offset, geth_step.stack.nth_last(1)?);
+            check_eq!(length, geth_step.stack.nth_last(2)?);
         }
 
         let copy_event = gen_copy_event(
@@ -77,6 +77,7 @@ fn gen_copy_event(
         dst_id: NumberOrHash::Number(call_id),
         dst_addr,
         log_id: None,
+        origin: None,
         rw_counter_start,
         // needs both read/write and prev bytes in CopyBytes
         copy_bytes: CopyBytes::new(read_steps, Some(write_steps), Some(prev_bytes)),
@@ -1,4 +1,5 @@
-use eth_types::{bytecode, geth_types::GethData, word, Bytecode, GethExecStep};
+use crate::mock::BlockData;
+use eth_types::{bytecode, evm_types::Memory, geth_types::GethData, word, Bytecode, GethExecStep};
 use mock::{
     test_ctx::{
         helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
@@ -130,3 +131,61 @@ fn call_test() {
     };
     trace_and_assert(code, -2, -1, assert_expanded);
 }
+
+#[test]
+fn memory_reconstructed_without_memory_trace() {
+    let code = bytecode! {
+        PUSH32(word!("0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20"))
+        PUSH1(0x05u64)
+        MSTORE
+        PUSH1(0xffu64)
+        PUSH1(0x42u64)
+        MSTORE8
+        PUSH1(0x20u64)
+        PUSH1(0x00u64)
+        PUSH1(0x50u64)
+        CODECOPY
+        PUSH1(0x40u64)
+        PUSH1(0x03u64)
+        SHA3
+        PUSH1(0x00u64)
+        MSTORE
+        PUSH1(0x60u64)
+        PUSH1(0x00u64)
+        RETURN
+    };
+    let block: GethData = TestContext::<2, 1>::new_with_logger_config(
+        None,
+        account_0_code_account_1_no_code(code),
+        tx_from_1_to_0,
+        |block, _tx| block.number(0xcafeu64),
+        LoggerConfig::enable_memory(),
+    )
+    .unwrap()
+    .into();
+    let mut stripped_block = block.clone();
+    for geth_trace in stripped_block.geth_traces.iter_mut() {
+        for step in geth_trace.struct_logs.iter_mut() {
+            step.memory = Memory::default();
+        }
+    }
+
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    let mut stripped_builder =
+        BlockData::new_from_geth_data(stripped_block.clone()).new_circuit_input_builder();
+    stripped_builder
+        .handle_block(&stripped_block.eth_block, &stripped_block.geth_traces)
+        .unwrap();
+
+    assert_eq!(
+        format!("{:?}", stripped_builder.block.container.memory),
+        format!("{:?}", builder.block.container.memory)
+    );
+    assert_eq!(
+        format!("{:?}", stripped_builder.block.copy_events),
+        format!("{:?}", builder.block.copy_events)
+    );
+}
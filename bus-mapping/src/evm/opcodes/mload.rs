@@ -22,7 +22,7 @@ impl Opcode for Mload {
         let mut exec_step = state.new_step(geth_step)?;
         let stack_value_read = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
-        assert_eq!(stack_value_read, geth_step.stack.last()?);
+        check_eq!(stack_value_read, geth_step.stack.last()?);
 
         // Read the memory value from the next step of the trace.
         let mem_read_value = state
@@ -36,7 +36,7 @@ impl Opcode for Mload {
 
         // First stack write
         #[cfg(feature = "enable-stack")]
-        assert_eq!(mem_read_value, geth_steps[1].stack.last()?);
+        check_eq!(mem_read_value, geth_steps[1].stack.last()?);
         state.stack_push(&mut exec_step, mem_read_value)?;
 
         state.memory_read_word(&mut exec_step, slot.into())?;
@@ -22,8 +22,8 @@ impl<const IS_MSTORE8: bool> Opcode for Mstore<IS_MSTORE8> {
         let value = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(offset, geth_step.stack.nth_last(0)?);
-            assert_eq!(value, geth_step.stack.nth_last(1)?);
+            check_eq!(offset, geth_step.stack.nth_last(0)?);
+            check_eq!(value, geth_step.stack.nth_last(1)?);
         }
 
         let offset_u64 = offset.as_u64() as usize;
@@ -0,0 +1,54 @@
+use crate::{
+    circuit_input_builder::{Blake2fOp, PrecompileEvent},
+    precompile::PrecompileAuxData,
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    return_bytes: &[u8],
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    // a malformed input fails the call, no compression happens then.
+    let opt_event = if output_bytes.is_empty() {
+        None
+    } else {
+        Blake2fOp::from_input(input_bytes).map(PrecompileEvent::Blake2F)
+    };
+    let aux_data = PrecompileAuxData::Base {
+        input_bytes: input_bytes.to_vec(),
+        output_bytes: output_bytes.to_vec(),
+        return_bytes: return_bytes.to_vec(),
+    };
+
+    (opt_event, Some(aux_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // vector 5 of EIP-152, the compression of BLAKE2b-512("abc").
+    const INPUT: &str = "0000000c48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b61626300000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000300000000000000000000000000000001";
+    const OUTPUT: &str = "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923";
+
+    #[test]
+    fn blake2f_events() {
+        let input = hex::decode(INPUT).unwrap();
+        let output = hex::decode(OUTPUT).unwrap();
+
+        let (event, _) = opt_data(&input, &output, &output);
+        let Some(PrecompileEvent::Blake2F(op)) = event else {
+            panic!("no blake2f event for {event:?}");
+        };
+        assert_eq!(op.rounds, 12);
+        assert_eq!(op.t, [3, 0]);
+        assert!(op.f);
+        assert_eq!(op.output_bytes(), output);
+
+        // wrong length, then a final block flag which is not a boolean
+        assert!(opt_data(&input[1..], &output, &output).0.is_none());
+        let mut input = input;
+        input[212] = 2;
+        assert!(opt_data(&input, &output, &output).0.is_none());
+    }
+}
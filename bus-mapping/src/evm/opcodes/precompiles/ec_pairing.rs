@@ -38,7 +38,8 @@ pub(crate) fn opt_data(
     }
 
     let op = if !input_bytes.is_empty() {
-        if (input_bytes.len() > N_PAIRING_PER_OP * N_BYTES_PER_PAIR)
+        // scroll fails the calls with more than N_PAIRING_PER_OP pairs.
+        if (cfg!(feature = "scroll") && input_bytes.len() > N_PAIRING_PER_OP * N_BYTES_PER_PAIR)
             || (input_bytes.len() % N_BYTES_PER_PAIR != 0)
         {
             return (
@@ -48,12 +49,9 @@ pub(crate) fn opt_data(
                 )))),
             );
         }
-        debug_assert!(
-            input_bytes.len() % N_BYTES_PER_PAIR == 0
-                && input_bytes.len() <= N_PAIRING_PER_OP * N_BYTES_PER_PAIR
-        );
+        debug_assert!(input_bytes.len() % N_BYTES_PER_PAIR == 0);
         // process input bytes.
-        let mut pairs = input_bytes
+        let pairs = input_bytes
             .chunks_exact(N_BYTES_PER_PAIR)
             .map(|chunk| {
                 // process <= 192 bytes chunk at a time.
@@ -71,19 +69,16 @@ pub(crate) fn opt_data(
                 }
             })
             .collect_vec();
-        // pad the pairs to make them of fixed size: N_PAIRING_PER_OP.
-        pairs.resize(N_PAIRING_PER_OP, EcPairingPair::padding_pair());
         EcPairingOp {
-            pairs: <[_; N_PAIRING_PER_OP]>::try_from(pairs).unwrap(),
+            pairs,
             output: pairing_check,
             input_bytes: input_bytes.to_vec(),
             output_bytes: output_bytes.to_vec(),
             return_bytes: return_bytes.to_vec(),
         }
     } else {
-        let pairs = [EcPairingPair::padding_pair(); N_PAIRING_PER_OP];
         EcPairingOp {
-            pairs,
+            pairs: vec![],
             output: pairing_check,
             input_bytes: vec![],
             output_bytes: output_bytes.to_vec(),
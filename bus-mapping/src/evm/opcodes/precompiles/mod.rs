@@ -2,19 +2,21 @@ use eth_types::{GethExecStep, ToWord, Word};
 
 use crate::{
     circuit_input_builder::{
-        Call, CircuitInputStateRef, ExecState, ExecStep, PrecompileEvent, SHA256,
+        Call, CircuitInputStateRef, ExecState, ExecStep, PrecompileEvent, N_BYTES_PER_PAIR, SHA256,
     },
     operation::CallContextField,
-    precompile::{PrecompileAuxData, PrecompileCalls},
+    precompile::{modexp_size_limit, ModExpAuxData, PrecompileAuxData, PrecompileCalls},
     Error,
 };
 
+mod blake2f;
 mod ec_add;
 mod ec_mul;
 mod ec_pairing;
 mod ecrecover;
 mod modexp;
 
+use blake2f::opt_data as opt_data_blake2f;
 use ec_add::opt_data as opt_data_ec_add;
 use ec_mul::opt_data as opt_data_ec_mul;
 use ec_pairing::opt_data as opt_data_ec_pairing;
@@ -53,11 +55,40 @@ pub fn gen_ops(
     output_bytes: &[u8],
     return_bytes: &[u8],
 ) -> Result<ExecStep, Error> {
-    assert_eq!(call.code_address(), Some(precompile.into()));
+    if call.code_address() != Some(precompile.into()) {
+        log::error!(
+            "precompile {:?} executed by call with code address {:?}",
+            precompile,
+            call.code_address()
+        );
+        return Err(Error::InternalError(
+            "precompile step does not match call code address",
+        ));
+    }
     exec_step.exec_state = ExecState::Precompile(precompile);
 
     common_call_ctx_reads(state, &mut exec_step, &call)?;
 
+    // Chains without the scroll size limit accept operands longer than the modexp circuit is
+    // configured for.
+    let modexp_size_limit = modexp_size_limit(state.block.circuits_params.max_modexp_bytes);
+    if precompile == PrecompileCalls::Modexp && call.is_success {
+        let (valid, input_lens) = ModExpAuxData::check_input(input_bytes, modexp_size_limit);
+        if !valid {
+            return Err(Error::UnsupportedModExpSize {
+                input_lens,
+                limit: modexp_size_limit,
+            });
+        }
+    }
+    if precompile == PrecompileCalls::Bn128Pairing && call.is_success {
+        let n_pairs = input_bytes.len() / N_BYTES_PER_PAIR;
+        let limit = state.block.circuits_params.max_ec_ops.ec_pairing_pairs;
+        if n_pairs > limit {
+            return Err(Error::UnsupportedEcPairingSize { n_pairs, limit });
+        }
+    }
+
     let (opt_event, aux_data) = match precompile {
         PrecompileCalls::Ecrecover => opt_data_ecrecover(input_bytes, output_bytes, return_bytes),
         PrecompileCalls::Bn128Add => opt_data_ec_add(input_bytes, output_bytes, return_bytes),
@@ -65,7 +96,10 @@ pub fn gen_ops(
         PrecompileCalls::Bn128Pairing => {
             opt_data_ec_pairing(input_bytes, output_bytes, return_bytes)
         }
-        PrecompileCalls::Modexp => opt_data_modexp(input_bytes, output_bytes, return_bytes),
+        PrecompileCalls::Modexp => {
+            opt_data_modexp(input_bytes, output_bytes, return_bytes, modexp_size_limit)
+        }
+        PrecompileCalls::Blake2F => opt_data_blake2f(input_bytes, output_bytes, return_bytes),
         PrecompileCalls::Identity => (
             None,
             Some(PrecompileAuxData::Identity {
@@ -106,6 +140,8 @@ pub fn gen_ops(
     log::trace!("precompile event {opt_event:?}, aux data {aux_data:?}");
     if let Some(event) = opt_event {
         state.push_precompile_event(event);
+        state.check_ec_ops_limit()?;
+        state.check_blake2f_rounds_limit()?;
     }
     exec_step.aux_data = aux_data;
 
@@ -117,15 +153,15 @@ fn common_call_ctx_reads(
     exec_step: &mut ExecStep,
     call: &Call,
 ) -> Result<(), Error> {
+    let code_address = call
+        .code_address()
+        .ok_or(Error::InternalError("precompile call without code address"))?;
     for (field, value) in [
         (
             CallContextField::IsSuccess,
             Word::from(call.is_success as u64),
         ),
-        (
-            CallContextField::CalleeAddress,
-            call.code_address().unwrap().to_word(),
-        ),
+        (CallContextField::CalleeAddress, code_address.to_word()),
         (CallContextField::IsRoot, Word::from(call.is_root as u64)),
         (
             CallContextField::CallDataOffset,
@@ -3,26 +3,24 @@ use crate::{
     precompile::{ModExpAuxData, PrecompileAuxData},
 };
 
-use eth_types::Word;
-
 pub(crate) fn opt_data(
     input_bytes: &[u8],
     output_bytes: &[u8],
     return_bytes: &[u8],
+    size_limit: usize,
 ) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
-    let aux_data = ModExpAuxData::new(input_bytes, output_bytes, return_bytes);
-    if aux_data.valid {
-        let event = BigModExp {
-            base: Word::from_big_endian(&aux_data.inputs[0]),
-            exponent: Word::from_big_endian(&aux_data.inputs[1]),
-            modulus: Word::from_big_endian(&aux_data.inputs[2]),
-            result: Word::from_big_endian(&aux_data.output),
-        };
-        (
-            Some(PrecompileEvent::ModExp(event)),
-            Some(PrecompileAuxData::Modexp(aux_data)),
-        )
-    } else {
-        (None, Some(PrecompileAuxData::Modexp(aux_data)))
-    }
+    let aux_data = ModExpAuxData::new(input_bytes, output_bytes, return_bytes, size_limit);
+    // the modexp circuit proves the validity of the input, so the invalid calls have their
+    // event too.
+    let event = BigModExp::new(input_bytes.to_vec(), size_limit);
+    debug_assert!(
+        !aux_data.valid || output_bytes.is_empty() || output_bytes == event.result,
+        "modexp result {:?} differs from the output {:?}",
+        event.result,
+        output_bytes,
+    );
+    (
+        Some(PrecompileEvent::ModExp(event)),
+        Some(PrecompileAuxData::Modexp(aux_data)),
+    )
 }
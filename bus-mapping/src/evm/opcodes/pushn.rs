@@ -32,7 +32,7 @@ impl Opcode for PushN {
         };
         let real_value = Word::from_big_endian(&value_bytes);
         #[cfg(feature = "enable-stack")]
-        assert_eq!(real_value, geth_steps[1].stack.last()?);
+        check_eq!(real_value, geth_steps[1].stack.last()?);
         let missing_bits = data_len.saturating_sub(max_len) * 8;
 
         state.call_ctx_mut()?.stack.push(real_value)?;
@@ -12,7 +12,7 @@ use eth_types::{
     bytecode::BytecodeElement,
     evm_types::{memory::MemoryWordRange, OpcodeId},
     state_db::CodeDB,
-    Bytecode, GethExecStep, ToWord, Word, H256,
+    Bytecode, GethExecStep, ToWord, Word, WordExt, H256,
 };
 use ethers_core::utils::keccak256;
 
@@ -31,15 +31,15 @@ impl Opcode for ReturnRevert {
         let length = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(offset, step.stack.nth_last(0)?);
-            assert_eq!(length, step.stack.nth_last(1)?);
+            check_eq!(offset, step.stack.nth_last(0)?);
+            check_eq!(length, step.stack.nth_last(1)?);
         }
 
         if !length.is_zero() {
             state
                 .call_ctx_mut()?
                 .memory
-                .extend_at_least((offset.low_u64() + length.low_u64()).try_into().unwrap());
+                .extend_at_least((offset + length).fit_usize());
         }
 
         let call = state.call()?.clone();
@@ -288,11 +288,13 @@ fn handle_copy(
             src_type: CopyDataType::Memory,
             src_id: NumberOrHash::Number(source.id),
             src_addr: source.offset.try_into().unwrap(),
-            src_addr_end: (source.offset + source.length).try_into().unwrap(),
+            // The source ends with the copy, as it is never padded.
+            src_addr_end: (source.offset + copy_length).try_into().unwrap(),
             dst_type: CopyDataType::Memory,
             dst_id: NumberOrHash::Number(destination.id),
             dst_addr: destination.offset.try_into().unwrap(),
             log_id: None,
+            origin: None,
             copy_bytes: CopyBytes::new(read_steps, Some(write_steps), Some(dst_data_prev)),
             access_list: vec![],
         },
@@ -360,6 +362,7 @@ fn handle_create(
             dst_id,
             dst_addr: 0,
             log_id: None,
+            origin: None,
             copy_bytes: CopyBytes::new(copy_steps, None, None),
             access_list: vec![],
         },
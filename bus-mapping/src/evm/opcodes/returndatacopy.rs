@@ -24,9 +24,9 @@ impl Opcode for Returndatacopy {
 
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(memory_offset, geth_step.stack.nth_last(0)?);
-            assert_eq!(data_offset, geth_step.stack.nth_last(1)?);
-            assert_eq!(length, geth_step.stack.nth_last(2)?);
+            check_eq!(memory_offset, geth_step.stack.nth_last(0)?);
+            check_eq!(data_offset, geth_step.stack.nth_last(1)?);
+            check_eq!(length, geth_step.stack.nth_last(2)?);
         }
 
         let call_id = state.call()?.call_id;
@@ -36,8 +36,9 @@ impl Opcode for Returndatacopy {
         let last_callee_return_data_offset = state.call()?.last_callee_return_data_offset;
         let last_callee_return_data_length = state.call()?.last_callee_return_data_length;
 
-        assert_eq!(
-            last_callee_return_data_length as usize, return_data_len,
+        check_eq!(
+            last_callee_return_data_length as usize,
+            return_data_len,
             "callee return data size should be correct"
         );
 
@@ -82,11 +83,12 @@ fn gen_copy_event(
         length.as_u64(),
     );
 
+    // The copy is within the return data, otherwise it is an out of bound
+    // error, so the source ends with the copy, without padding.
     let last_callee_return_data_offset = state.call()?.last_callee_return_data_offset;
-    let last_callee_return_data_length = state.call()?.last_callee_return_data_length;
     let (src_addr, src_addr_end) = (
         last_callee_return_data_offset + data_offset,
-        last_callee_return_data_offset + last_callee_return_data_length,
+        last_callee_return_data_offset + data_offset + length,
     );
 
     let (read_steps, write_steps, prev_bytes) =
@@ -101,6 +103,7 @@ fn gen_copy_event(
         dst_id: NumberOrHash::Number(state.call()?.call_id),
         dst_addr,
         log_id: None,
+        origin: None,
         rw_counter_start,
         copy_bytes: CopyBytes::new(read_steps, Some(write_steps), Some(prev_bytes)),
         access_list: vec![],
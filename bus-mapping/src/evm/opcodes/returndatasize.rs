@@ -37,7 +37,7 @@ impl Opcode for Returndatasize {
                     real_size,
                     geth_step
                 );
-                assert_eq!(real_size, size);
+                check_eq!(real_size, size);
             }
         }
         state.stack_push(&mut exec_step, size.into())?;
@@ -19,7 +19,7 @@ impl Opcode for Selfbalance {
         let callee_address = state.call()?.address;
         let self_balance = state.sdb.get_balance(&callee_address);
         #[cfg(feature = "enable-stack")]
-        assert_eq!(self_balance, geth_steps[1].stack.last()?);
+        check_eq!(self_balance, geth_steps[1].stack.last()?);
 
         // CallContext read of the callee_address
         state.call_context_read(
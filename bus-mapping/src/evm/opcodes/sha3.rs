@@ -28,8 +28,8 @@ impl Opcode for Sha3 {
         let size = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(offset, geth_step.stack.nth_last(0)?);
-            assert_eq!(size, geth_step.stack.nth_last(1)?);
+            check_eq!(offset, geth_step.stack.nth_last(0)?);
+            check_eq!(size, geth_step.stack.nth_last(1)?);
         }
 
         if size.gt(&U256::zero()) {
@@ -51,7 +51,7 @@ impl Opcode for Sha3 {
         let sha3 = keccak256(&sha3_input);
         let _output = Word::from_big_endian(&sha3);
         #[cfg(feature = "enable-stack")]
-        assert_eq!(_output, geth_steps[1].stack.last()?);
+        check_eq!(_output, geth_steps[1].stack.last()?);
         state.stack_push(&mut exec_step, sha3.into())?;
 
         // Memory read operations
@@ -90,6 +90,7 @@ impl Opcode for Sha3 {
                 dst_type: CopyDataType::RlcAcc,
                 dst_id: NumberOrHash::Number(call_id),
                 log_id: None,
+                origin: None,
                 rw_counter_start,
                 copy_bytes: CopyBytes::new(copy_steps, None, None),
                 access_list: vec![],
@@ -54,13 +54,13 @@ impl Opcode for Sload {
         // First stack read
         let key = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
-        assert_eq!(key, geth_step.stack.last()?);
+        check_eq!(key, geth_step.stack.last()?);
 
         // Storage read
         let value_from_statedb = *state.sdb.get_storage(&contract_addr, &key).1;
 
         #[cfg(feature = "enable-stack")]
-        assert_eq!(
+        check_eq!(
             value_from_statedb,
             geth_steps[1].stack.last()?,
             "inconsistent sload: step proof {value_from_statedb:?}, result {:?} in contract {contract_addr:?}, key {key:?}", geth_steps[1].stack.last()?,
@@ -247,4 +247,61 @@ mod sload_tests {
     fn sload_opcode_impl_cold() {
         test_ok(false)
     }
+
+    #[test]
+    fn sload_cold_again_after_revert() {
+        use eth_types::{evm_types::GasCost, ToWord};
+        use mock::eth;
+
+        // Warms slot 0 of the caller, then reverts.
+        let callee = bytecode! {
+            PUSH1(0x00u64)
+            SLOAD
+            PUSH1(0x00u64)
+            PUSH1(0x00u64)
+            REVERT
+        };
+        let caller = bytecode! {
+            PUSH1(0x00u64) // retLength
+            PUSH1(0x00u64) // retOffset
+            PUSH1(0x00u64) // argsLength
+            PUSH1(0x00u64) // argsOffset
+            PUSH32(MOCK_ACCOUNTS[1].to_word()) // addr
+            GAS
+            DELEGATECALL
+            POP
+            PUSH1(0x00u64)
+            SLOAD
+            STOP
+        };
+        let block: GethData = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(caller);
+                accs[1].address(MOCK_ACCOUNTS[1]).code(callee);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(1));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        builder.block.check_access_list().unwrap();
+        builder.block.check_access_gas().unwrap();
+
+        let sload_costs: Vec<_> = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .filter(|step| step.exec_state == ExecState::Op(OpcodeId::SLOAD))
+            .map(|step| step.gas_cost)
+            .collect();
+        assert_eq!(sload_costs, vec![GasCost::COLD_SLOAD; 2]);
+    }
 }
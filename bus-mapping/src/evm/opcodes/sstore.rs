@@ -118,8 +118,8 @@ impl Opcode for Sstore {
         let value = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(key, geth_step.stack.nth_last(0)?);
-            assert_eq!(value, geth_step.stack.nth_last(1)?);
+            check_eq!(key, geth_step.stack.nth_last(0)?);
+            check_eq!(value, geth_step.stack.nth_last(1)?);
         }
 
         let is_warm = state
@@ -170,8 +170,9 @@ impl Opcode for Sstore {
             calc_expected_tx_refund(state.sdb.refund(), value, value_prev, committed_value);
 
         #[cfg(not(feature = "fix-refund"))]
-        assert_eq!(
-            refund, refund_expected,
+        check_eq!(
+            refund,
+            refund_expected,
             "expected refund {refund_expected} is not equal to current {refund}"
         );
         #[cfg(feature = "fix-refund")]
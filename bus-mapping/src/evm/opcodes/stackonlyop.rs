@@ -25,7 +25,7 @@ impl<const N_POP: usize, const IS_ERR: bool> Opcode for StackPopOnlyOpcode<N_POP
         let _stack_inputs = state.stack_pops(&mut exec_step, N_POP)?;
         #[cfg(feature = "enable-stack")]
         for (i, v) in _stack_inputs.into_iter().enumerate() {
-            assert_eq!(v, geth_step.stack.nth_last(i)?);
+            check_eq!(v, geth_step.stack.nth_last(i)?);
         }
 
         if IS_ERR {
@@ -29,11 +29,11 @@ impl<const N: usize> Opcode for Swap<N> {
 
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(stack_b_value_read, geth_step.stack.nth_last(N)?);
-            assert_eq!(stack_b_position, geth_step.stack.nth_last_filled(N));
+            check_eq!(stack_b_value_read, geth_step.stack.nth_last(N)?);
+            check_eq!(stack_b_position, geth_step.stack.nth_last_filled(N));
 
-            assert_eq!(stack_a_value_read, geth_step.stack.last()?);
-            assert_eq!(stack_a_position, geth_step.stack.last_filled());
+            check_eq!(stack_a_value_read, geth_step.stack.last()?);
+            check_eq!(stack_a_position, geth_step.stack.last_filled());
         }
 
         // Write a into b_position, write b into a_position
@@ -60,8 +60,8 @@ impl Opcode for Tstore {
         let value = state.stack_pop(&mut exec_step)?;
         #[cfg(feature = "enable-stack")]
         {
-            assert_eq!(key, geth_step.stack.nth_last(0)?);
-            assert_eq!(value, geth_step.stack.nth_last(1)?);
+            check_eq!(key, geth_step.stack.nth_last(0)?);
+            check_eq!(value, geth_step.stack.nth_last(1)?);
         }
 
         let (_, value_prev) = state.sdb.get_transient_storage(&contract_addr, &key);
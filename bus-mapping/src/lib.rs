@@ -224,8 +224,9 @@
 extern crate alloc;
 extern crate core;
 
-pub mod circuit_input_builder;
+#[macro_use]
 pub mod error;
+pub mod circuit_input_builder;
 pub mod evm;
 pub mod exec_trace;
 pub use eth_types::l2_predeployed;
@@ -1,9 +1,12 @@
 //! Mock types and functions to generate mock data useful for tests
 
-use crate::circuit_input_builder::{AccessSet, Block, Blocks, CircuitInputBuilder, CircuitsParams};
+use crate::{
+    circuit_input_builder::{AccessSet, Block, Blocks, CircuitInputBuilder, CircuitsParams},
+    Error,
+};
 use eth_types::{
     geth_types::GethData,
-    state_db::{self, CodeDB, StateDB},
+    state_db::{self, CodeDB, StateDB, WarmState},
     ToWord, Word, H256,
 };
 use ethers_core::utils::keccak256;
@@ -49,13 +52,33 @@ impl BlockData {
         geth_data: GethData,
         circuits_params: CircuitsParams,
     ) -> Self {
-        let mut sdb = StateDB::new();
-        let mut code_db = CodeDB::new();
+        Self::new_from_pre_state(geth_data, circuits_params, StateDB::new(), CodeDB::new())
+    }
+
+    /// Create a new block from the given Geth data, on top of a [`WarmState`]
+    /// snapshot of a previous block. Accounts in the Geth data take
+    /// precedence over the ones in the snapshot.
+    pub fn new_from_geth_data_with_warm_state(
+        geth_data: GethData,
+        circuits_params: CircuitsParams,
+        warm_state: WarmState,
+    ) -> Self {
+        let (sdb, code_db) = warm_state.into_dbs();
+        Self::new_from_pre_state(geth_data, circuits_params, sdb, code_db)
+    }
 
+    fn new_from_pre_state(
+        geth_data: GethData,
+        circuits_params: CircuitsParams,
+        mut sdb: StateDB,
+        mut code_db: CodeDB,
+    ) -> Self {
         let access_set = AccessSet::from_geth_data(&geth_data);
-        // Initialize all accesses accounts to zero
+        // Initialize all accesses accounts missing from the pre-state to zero
         for addr in access_set.state.keys() {
-            sdb.set_account(addr, state_db::Account::zero());
+            if !sdb.get_account(addr).0 {
+                sdb.set_account(addr, state_db::Account::zero());
+            }
         }
 
         for account in geth_data.accounts {
@@ -96,6 +119,28 @@ impl BlockData {
     }
 }
 
+/// Prepare a `builder` which handled the previous blocks to handle the block
+/// of `geth_data`, traced on top of the state they left (see
+/// [`WarmState::geth_accounts`]): add the block header and initialize to zero
+/// the accounts accessed by the block which don't exist yet.
+pub fn add_next_block(
+    builder: &mut CircuitInputBuilder,
+    geth_data: &GethData,
+) -> Result<(), Error> {
+    let access_set = AccessSet::from_geth_data(geth_data);
+    for addr in access_set.state.keys() {
+        if !builder.sdb.get_account(addr).0 {
+            builder.sdb.set_account(addr, state_db::Account::zero());
+        }
+    }
+    builder.block.add_block(Block::new(
+        geth_data.chain_id,
+        geth_data.history_hashes.clone(),
+        &geth_data.eth_block,
+    )?);
+    Ok(())
+}
+
 #[cfg(test)]
 #[ctor::ctor]
 fn init_env_logger() {
@@ -5,6 +5,7 @@ use super::{
 };
 use crate::exec_trace::OperationRef;
 use itertools::Itertools;
+use std::collections::HashSet;
 
 /// The `OperationContainer` is meant to store all of the [`Operation`]s that an
 /// [`ExecStep`](crate::circuit_input_builder::ExecStep) performs during its
@@ -196,6 +197,24 @@ impl OperationContainer {
     pub fn sorted_storage(&self) -> Vec<Operation<StorageOp>> {
         self.storage.iter().sorted().cloned().collect()
     }
+
+    /// Returns the number of [`MemoryOp`] reads among `op_refs`, the operations
+    /// of one [`ExecStep`](crate::circuit_input_builder::ExecStep), which read
+    /// a word already read or written before in the same step.  Their value is
+    /// implied by the previous access of the word, so they are the entries a
+    /// compaction of the rw table could elide, as the return data of a
+    /// precompile call is not read back from the output written in the step.
+    pub fn redundant_memory_reads(&self, op_refs: &[OperationRef]) -> usize {
+        let mut accessed = HashSet::new();
+        op_refs
+            .iter()
+            .filter(|op_ref| op_ref.0 == Target::Memory)
+            .map(|op_ref| &self.memory[op_ref.1])
+            .filter(|op| {
+                !accessed.insert((op.op().call_id, op.op().address.0)) && op.rw().is_read()
+            })
+            .count()
+    }
 }
 
 #[cfg(test)]
@@ -248,4 +267,31 @@ mod container_test {
         assert_eq!(memory_ref, OperationRef::from((Target::Memory, 0)));
         assert_eq!(storage_ref, OperationRef::from((Target::Storage, 0)));
     }
+
+    #[test]
+    fn redundant_memory_reads_test() {
+        let mut global_counter = RWCounter::default();
+        let mut operation_container = OperationContainer::default();
+        let mut memory_op = |rw, call_id, address: u64| {
+            operation_container.insert(Operation::new(
+                global_counter.inc_pre(),
+                rw,
+                MemoryOp::new(call_id, MemoryAddress::from(address), 1.into()),
+            ))
+        };
+        let op_refs = vec![
+            memory_op(RW::WRITE, 1, 0),
+            // read of a word written before in the step
+            memory_op(RW::READ, 1, 0),
+            memory_op(RW::READ, 1, 32),
+            // read of the same word of another call
+            memory_op(RW::READ, 2, 32),
+            // read of a word read before in the step
+            memory_op(RW::READ, 1, 32),
+            memory_op(RW::WRITE, 1, 32),
+        ];
+
+        assert_eq!(operation_container.redundant_memory_reads(&op_refs), 2);
+        assert_eq!(operation_container.redundant_memory_reads(&op_refs[2..]), 1);
+    }
 }
@@ -2,9 +2,13 @@
 
 use eth_types::{evm_types::GasCost, Address, ToBigEndian, Word};
 use revm_precompile::{Precompile, PrecompileError, PrecompileErrors, Precompiles};
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
-use crate::circuit_input_builder::{EcMulOp, EcPairingOp};
+use crate::{
+    circuit_input_builder::{EcMulOp, EcPairingOp},
+    Error,
+};
 
 pub(crate) fn execute_precompiled(
     address: &Address,
@@ -64,6 +68,28 @@ pub enum PrecompileCalls {
     Blake2F = 0x09,
 }
 
+/// Address of the last precompiled contract supported by the circuits.
+///
+/// The point evaluation precompile (EIP-4844) at 0x0a is out of scope: no
+/// circuit verifies its KZG proof, so the builder rejects the calls to it,
+/// see [`check_precompile_supported`].
+pub const LAST_PRECOMPILE_ADDRESS: u8 = 0x09;
+
+/// Address of the point evaluation precompile (EIP-4844).
+pub const POINT_EVALUATION_ADDRESS: u8 = 0x0a;
+
+/// Reject a call to `address` if the traces execute it as a precompile the
+/// circuits don't support: the point evaluation precompile, active in the l1
+/// traces of geth since cancun.
+pub fn check_precompile_supported(address: &Address) -> Result<(), Error> {
+    if cfg!(not(feature = "scroll"))
+        && *address == Address::from_low_u64_be(POINT_EVALUATION_ADDRESS.into())
+    {
+        return Err(Error::UnsupportedPrecompile { address: *address });
+    }
+    Ok(())
+}
+
 impl Default for PrecompileCalls {
     fn default() -> Self {
         Self::Ecrecover
@@ -102,8 +128,24 @@ impl From<u8> for PrecompileCalls {
             0x07 => Self::Bn128Mul,
             0x08 => Self::Bn128Pairing,
             0x09 => Self::Blake2F,
-            _ => unreachable!("precompile contracts only from 0x01 to 0x09"),
+            _ => unreachable!(
+                "precompile contracts only from 0x01 to {LAST_PRECOMPILE_ADDRESS:#04x}"
+            ),
+        }
+    }
+}
+
+impl TryFrom<&Address> for PrecompileCalls {
+    type Error = Error;
+
+    fn try_from(address: &Address) -> Result<Self, Self::Error> {
+        let bytes = address.as_fixed_bytes();
+        if bytes[..19].iter().any(|&b| b != 0)
+            || !(0x01..=LAST_PRECOMPILE_ADDRESS).contains(&bytes[19])
+        {
+            return Err(Error::InternalError("address is not a precompile contract"));
         }
+        Ok(bytes[19].into())
     }
 }
 
@@ -140,7 +182,7 @@ impl PrecompileCalls {
 }
 
 /// Auxiliary data for Ecrecover
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcrecoverAuxData {
     /// Keccak hash of the message being signed.
     pub msg_hash: Word,
@@ -196,24 +238,38 @@ impl EcrecoverAuxData {
     }
 }
 
-/// size limit of modexp
+/// size limit of modexp on the scroll chain, which fails the calls with longer operands
 pub const MODEXP_SIZE_LIMIT: usize = 32;
-/// size of input limit
-pub const MODEXP_INPUT_LIMIT: usize = 192;
+/// Upper bound of the operand size limit of the modexp circuit, see
+/// [`CircuitsParams::max_modexp_bytes`](crate::circuit_input_builder::CircuitsParams).
+#[cfg(feature = "scroll")]
+pub const MODEXP_BYTES_BOUND: usize = MODEXP_SIZE_LIMIT;
+/// Upper bound of the operand size limit of the modexp circuit, see
+/// [`CircuitsParams::max_modexp_bytes`](crate::circuit_input_builder::CircuitsParams).
+#[cfg(not(feature = "scroll"))]
+pub const MODEXP_BYTES_BOUND: usize = 1024;
+/// size of input limit: the header of the 3 lengths and the 3 operands
+pub const MODEXP_INPUT_LIMIT: usize = 96 + 3 * MODEXP_BYTES_BOUND;
+
+/// Operand size limit of the modexp circuit configured with `max_modexp_bytes`. The scroll chain
+/// fails the calls with operands longer than [`MODEXP_SIZE_LIMIT`], so it is the limit there.
+pub fn modexp_size_limit(max_modexp_bytes: usize) -> usize {
+    if cfg!(feature = "scroll") {
+        MODEXP_SIZE_LIMIT
+    } else {
+        max_modexp_bytes.min(MODEXP_BYTES_BOUND)
+    }
+}
 
 /// Auxiliary data for Modexp
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ModExpAuxData {
     /// The specified len of inputs: [base, exp, modulus]
     pub input_lens: [Word; 3],
-    /// Input value [base, exp, modulus], limited to SIZE_LIMIT
-    pub inputs: [[u8; MODEXP_SIZE_LIMIT]; 3],
-    /// Input valid.
+    /// Input value [base, exp, modulus] of their specified len, empty if the input is not valid
+    pub inputs: [Vec<u8>; 3],
+    /// Input valid, i.e. all the lens are within the size limit.
     pub valid: bool,
-    /// len of output, limited to lens of moduls, but can be 0
-    pub output_len: usize,
-    /// output of modexp.
-    pub output: [u8; MODEXP_SIZE_LIMIT],
     /// Input to the modexp call.
     pub input_bytes: Vec<u8>,
     /// Output bytes from the modexp call.
@@ -223,78 +279,59 @@ pub struct ModExpAuxData {
 }
 
 impl ModExpAuxData {
-    /// If mem is smaller than U256, left pad zero
-    /// Or else, keep the least 32bytes.
-    fn parse_memory_to_value(mem: &[u8]) -> [u8; MODEXP_SIZE_LIMIT] {
-        let mut value_bytes = [0u8; MODEXP_SIZE_LIMIT];
-        if !mem.is_empty() {
-            let copy_len = mem.len().min(MODEXP_SIZE_LIMIT);
-            let src_offset = mem.len() - copy_len;
-            let dst_offset = MODEXP_SIZE_LIMIT - copy_len;
-            value_bytes.as_mut_slice()[dst_offset..].copy_from_slice(&mem[src_offset..]);
-        }
-        value_bytes
-    }
-
-    /// check input
-    pub fn check_input(input: &[u8]) -> (bool, [Word; 3]) {
-        let mut i = input.chunks(32);
-        let base_len = Word::from_big_endian(i.next().unwrap_or(&[]));
-        let exp_len = Word::from_big_endian(i.next().unwrap_or(&[]));
-        let modulus_len = Word::from_big_endian(i.next().unwrap_or(&[]));
-
-        let limit = Word::from(MODEXP_SIZE_LIMIT);
-
-        let input_valid = base_len <= limit && exp_len <= limit && modulus_len <= limit;
-        log::debug!("modexp base_len {base_len} exp_len {exp_len} modulus_len {modulus_len}");
+    /// check input: the lens of [base, exp, modulus] in the header, and whether they are all
+    /// within `size_limit`. A header shorter than 96 bytes is right padded with zeros.
+    pub fn check_input(input: &[u8], size_limit: usize) -> (bool, [Word; 3]) {
+        let mut header = [0u8; 96];
+        let header_len = input.len().min(96);
+        header[..header_len].copy_from_slice(&input[..header_len]);
+        let input_lens = [0, 1, 2].map(|i| Word::from_big_endian(&header[i * 32..(i + 1) * 32]));
+
+        let limit = Word::from(size_limit);
+
+        let input_valid = input_lens.iter().all(|len| *len <= limit);
+        log::debug!(
+            "modexp base_len {} exp_len {} modulus_len {}",
+            input_lens[0],
+            input_lens[1],
+            input_lens[2]
+        );
         if !input_valid {
             log::warn!("modexp input input_valid {input_valid}");
         }
-        (input_valid, [base_len, exp_len, modulus_len])
+        (input_valid, input_lens)
     }
 
-    /// Create a new instance of modexp auxiliary data.
-    pub fn new(input: &[u8], output: &[u8], return_bytes: &[u8]) -> Self {
-        let mut resized_input = input.to_vec();
-        if resized_input.len() < 96 {
-            resized_input.resize(96, 0);
+    /// Parse the input into [base, exp, modulus], right padding it with zeros. The values are
+    /// empty if the input is not valid.
+    pub fn parse_input(input: &[u8], size_limit: usize) -> (bool, [Word; 3], [Vec<u8>; 3]) {
+        let (input_valid, input_lens) = Self::check_input(input, size_limit);
+        if !input_valid {
+            return (input_valid, input_lens, Default::default());
         }
 
-        let (input_valid, [base_len, exp_len, modulus_len]) = Self::check_input(&resized_input);
-
-        let base_mem_len = if input_valid { base_len.as_usize() } else { 0 };
-        let exp_mem_len = if input_valid { exp_len.as_usize() } else { 0 };
-        let modulus_mem_len = if input_valid {
-            modulus_len.as_usize()
-        } else {
-            0
-        };
+        let mut offset = 96;
+        let values = input_lens.map(|len| {
+            let len = len.as_usize();
+            let mut value = vec![0u8; len];
+            if offset < input.len() {
+                let copy_len = len.min(input.len() - offset);
+                value[..copy_len].copy_from_slice(&input[offset..offset + copy_len]);
+            }
+            offset += len;
+            value
+        });
+        (input_valid, input_lens, values)
+    }
 
-        let (base, exp, modulus) = if base_mem_len == 0 && modulus_mem_len == 0 {
-            // special case
-            ([0u8; 32], [0u8; 32], [0u8; 32])
-        } else {
-            // In non scroll mode, this can be dangerous.
-            // If base and mod are all 0, exp can be very huge.
-            resized_input.resize(96 + base_mem_len + exp_mem_len + modulus_mem_len, 0);
-            let mut cur_input_begin = &resized_input[96..];
-
-            let base = Self::parse_memory_to_value(&cur_input_begin[..base_mem_len]);
-            cur_input_begin = &cur_input_begin[base_mem_len..];
-            let exp = Self::parse_memory_to_value(&cur_input_begin[..exp_mem_len]);
-            cur_input_begin = &cur_input_begin[exp_mem_len..];
-            let modulus = Self::parse_memory_to_value(&cur_input_begin[..modulus_mem_len]);
-            (base, exp, modulus)
-        };
-        let output_len = output.len();
-        let output = Self::parse_memory_to_value(output);
+    /// Create a new instance of modexp auxiliary data.
+    pub fn new(input: &[u8], output: &[u8], return_bytes: &[u8], size_limit: usize) -> Self {
+        let (valid, input_lens, inputs) = Self::parse_input(input, size_limit);
 
         Self {
-            valid: input_valid,
-            input_lens: [base_len, exp_len, modulus_len],
-            inputs: [base, exp, modulus],
-            output,
-            output_len,
+            valid,
+            input_lens,
+            inputs,
             input_bytes: input.to_vec(),
             output_bytes: output.to_vec(),
             return_bytes: return_bytes.to_vec(),
@@ -303,7 +340,7 @@ impl ModExpAuxData {
 }
 
 /// Auxiliary data for EcAdd, i.e. P + Q = R
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcAddAuxData {
     /// x co-ordinate of the first point.
     pub p_x: Word,
@@ -348,7 +385,7 @@ impl EcAddAuxData {
 }
 
 /// Auxiliary data for EcMul, i.e. s * P = R
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcMulAuxData {
     /// x co-ordinate of the point.
     pub p_x: Word,
@@ -395,11 +432,11 @@ impl EcMulAuxData {
 }
 
 /// Auxiliary data for EcPairing.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingAuxData(pub EcPairingOp);
 
 /// Erroneous bytes passed to the EcPairing precompile call.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EcPairingError {
     /// the calldatalength passed to EcPairing precompile call is expected to be:
     /// 1. len(input) <= 768
@@ -408,7 +445,7 @@ pub enum EcPairingError {
 }
 
 /// Auxiliary data attached to an internal state for precompile verification.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PrecompileAuxData {
     /// Base precompile (used for SHA256, RIPEMD-160 and BLAKE2F).
     Base {
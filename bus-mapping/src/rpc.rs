@@ -7,6 +7,7 @@ use eth_types::{
     ResultGethExecTraces, ResultGethPrestateTraces, Transaction, Word, H256, U64,
 };
 pub use ethers_core::types::BlockNumber;
+use ethers_core::types::TransactionReceipt;
 use ethers_providers::JsonRpcClient;
 use serde::Serialize;
 use serde_json::json;
@@ -140,6 +141,16 @@ impl<P: JsonRpcClient> GethClient<P> {
         tx
     }
 
+    /// Calls `eth_getTransactionReceipt` via JSON-RPC returning the
+    /// [`TransactionReceipt`] of a mined transaction.
+    pub async fn get_tx_receipt(&self, hash: H256) -> Result<TransactionReceipt, Error> {
+        let hash = serialize(&hash);
+        self.0
+            .request("eth_getTransactionReceipt", [hash])
+            .await
+            .map_err(|e| Error::JSONRpcError(e.into()))
+    }
+
     /// Calls `debug_traceBlockByHash` via JSON-RPC returning a
     /// [`Vec<GethExecTrace>`] with each GethTrace corresponding to 1
     /// transaction of the block.
@@ -90,12 +90,7 @@ mod tests {
             max_rlp_rows: 256,
             ..Default::default()
         };
-        let (_, circuit, instance) =
-            SuperCircuit::<_, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, 0x100>::build(
-                block,
-                circuits_params,
-            )
-            .unwrap();
+        let (_, circuit, instance) = SuperCircuit::<_>::build(block, circuits_params).unwrap();
         let instance_refs: Vec<&[Fr]> = instance.iter().map(|v| &v[..]).collect();
 
         // Bench setup generation
@@ -120,7 +115,7 @@ mod tests {
             Challenge255<G1Affine>,
             ChaChaRng,
             Blake2bWrite<Vec<u8>, G1Affine, Challenge255<G1Affine>>,
-            SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, 0x100>,
+            SuperCircuit<Fr>,
         >(
             &general_params,
             &pk,
@@ -0,0 +1,118 @@
+//! Stores of contract codes addressed by their code hash, to share the codes
+//! between witness builds, e.g. an on-disk store so that repeated RPC replays
+//! don't fetch the same codes again.
+
+use crate::{
+    state_db::CodeDB,
+    utils::{hash_code_keccak, hash_code_poseidon},
+    Error, Hash,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Store of contract codes addressed by their code hash.
+pub trait CodeStore {
+    /// Return the code of `hash`, if stored.
+    fn code(&self, hash: &Hash) -> Option<Vec<u8>>;
+
+    /// Store `code`.
+    fn store(&mut self, code: &[u8]) -> Result<(), Error>;
+}
+
+impl CodeStore for CodeDB {
+    fn code(&self, hash: &Hash) -> Option<Vec<u8>> {
+        self.0.get(hash).cloned()
+    }
+
+    fn store(&mut self, code: &[u8]) -> Result<(), Error> {
+        self.insert(code.to_vec());
+        Ok(())
+    }
+}
+
+/// Content-addressed store of codes in a directory, where each code is
+/// written once, in `code/{keccak code hash}`, and indexed by its Poseidon
+/// code hash in `poseidon/{Poseidon code hash}`. Codes can be looked up by
+/// either hash.
+#[derive(Debug, Clone)]
+pub struct DiskCodeStore {
+    dir: PathBuf,
+}
+
+impl DiskCodeStore {
+    /// Open the store in `dir`, creating it if missing.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, Error> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(dir.join("code")).map_err(Error::IoError)?;
+        fs::create_dir_all(dir.join("poseidon")).map_err(Error::IoError)?;
+        Ok(Self { dir })
+    }
+
+    fn code_path(&self, keccak_hash: &Hash) -> PathBuf {
+        self.dir.join("code").join(hex::encode(keccak_hash))
+    }
+
+    fn poseidon_path(&self, poseidon_hash: &Hash) -> PathBuf {
+        self.dir.join("poseidon").join(hex::encode(poseidon_hash))
+    }
+
+    /// Store `code`, unless already stored.
+    pub fn put(&self, code: &[u8]) -> Result<(), Error> {
+        let keccak_hash = hash_code_keccak(code);
+        Self::write_once(&self.code_path(&keccak_hash), code)?;
+        Self::write_once(
+            &self.poseidon_path(&hash_code_poseidon(code)),
+            keccak_hash.as_bytes(),
+        )
+    }
+
+    /// Write `contents` to `path` through a temporary file, so that a
+    /// concurrent reader never sees a partial file.
+    fn write_once(path: &Path, contents: &[u8]) -> Result<(), Error> {
+        if path.exists() {
+            return Ok(());
+        }
+        let tmp_path = path.with_extension(format!("tmp{}", std::process::id()));
+        fs::write(&tmp_path, contents).map_err(Error::IoError)?;
+        fs::rename(&tmp_path, path).map_err(Error::IoError)
+    }
+}
+
+impl CodeStore for DiskCodeStore {
+    fn code(&self, hash: &Hash) -> Option<Vec<u8>> {
+        if let Ok(code) = fs::read(self.code_path(hash)) {
+            return Some(code);
+        }
+        let keccak_hash = fs::read(self.poseidon_path(hash)).ok()?;
+        fs::read(self.code_path(&Hash::from_slice(&keccak_hash))).ok()
+    }
+
+    fn store(&mut self, code: &[u8]) -> Result<(), Error> {
+        self.put(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disk_code_store_by_keccak_and_poseidon_hash() {
+        let dir = std::env::temp_dir().join(format!("disk_code_store_{}", std::process::id()));
+        let code = vec![0x60, 0x01, 0x60, 0x02, 0x01, 0x00];
+
+        let mut store = DiskCodeStore::open(&dir).unwrap();
+        assert_eq!(store.code(&hash_code_keccak(&code)), None);
+        store.store(&code).unwrap();
+        // storing twice keeps a single copy
+        store.store(&code).unwrap();
+
+        let store = DiskCodeStore::open(&dir).unwrap();
+        assert_eq!(store.code(&hash_code_keccak(&code)), Some(code.clone()));
+        assert_eq!(store.code(&hash_code_poseidon(&code)), Some(code.clone()));
+        assert_eq!(fs::read_dir(dir.join("code")).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
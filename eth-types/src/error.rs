@@ -35,8 +35,36 @@ pub enum Error {
     /// Error when an EvmWord is too big to be converted into a
     /// `MemoryAddress`.
     WordToMemAddr,
+    /// Error when a word does not fit in the integer or field element it is
+    /// converted into.
+    WordOverflow {
+        /// The word converted
+        value: crate::Word,
+        /// Number of bits of the target
+        bits: usize,
+    },
     /// Signature parsing error.
     Signature,
+    /// Tx signature whose `s` is in the upper half of the curve order, which
+    /// EIP-2 disallows.
+    SignatureHighS,
+    /// Error while reading or writing a warm state file.
+    IoError(std::io::Error),
+    /// Error while de/serializing a warm state file.
+    BincodeError(bincode::Error),
+    /// Warm state file was written with an unsupported format version.
+    WarmStateVersion(u32),
+    /// A call in the trace is nested deeper than the configured limit.
+    CallDepthExceeded {
+        /// Depth of the call, the root call being at depth 1.
+        depth: usize,
+        /// Configured limit
+        max_depth: usize,
+    },
+    /// EIP-4844 blob tx without recipient. Blob txs cannot create contracts.
+    BlobTxCreate,
+    /// Malformed or unsupported EIP-2718 tx envelope.
+    TxEnvelope(String),
 }
 
 impl Display for Error {
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::{fmt, marker::ConstParamTy};
 
 pub mod block_utils;
+pub mod gas_schedule;
 pub mod gas_utils;
 pub mod memory;
 pub mod opcode_ids;
@@ -11,6 +12,7 @@ pub mod stack;
 pub mod storage;
 pub mod transient_storage;
 
+pub use gas_schedule::{EthFork, GasSchedule};
 pub use memory::{Memory, MemoryAddress, MemoryRef};
 pub use opcode_ids::OpcodeId;
 pub use stack::{Stack, StackAddress};
@@ -1,10 +1,16 @@
 //! Helper constants and utility functions for block
 
-use crate::{U256, U64};
+use crate::{Block, Error, U256, U64};
 use ethers_core::utils::keccak256;
 
 /// Maximum range of previous blocks allowed inside BLOCKHASH opcode
 pub const NUM_PREV_BLOCK_ALLOWED: u64 = 256;
+/// Minimum blob base fee (EIP-4844)
+pub const MIN_BLOB_BASE_FEE: u64 = 1;
+/// Update rate of the blob base fee (EIP-4844, Cancun)
+pub const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3338477;
+/// Blob gas used by each blob of a tx (EIP-4844)
+pub const GAS_PER_BLOB: u64 = 1 << 17;
 
 /// Calculate block hash by chain ID and block number (only for scroll).
 /// Return a pair of input and output.
@@ -27,3 +33,77 @@ pub fn is_valid_block_number(block_number: U256, current_block_number: U256) ->
                 .checked_sub(NUM_PREV_BLOCK_ALLOWED.into())
                 .unwrap_or_default()
 }
+
+/// Blob base fee returned by BLOBBASEFEE for a block with `excess_blob_gas`
+/// (EIP-4844).
+pub fn calc_blob_base_fee(excess_blob_gas: u64) -> U256 {
+    fake_exponential(
+        MIN_BLOB_BASE_FEE.into(),
+        excess_blob_gas.into(),
+        BLOB_BASE_FEE_UPDATE_FRACTION.into(),
+    )
+}
+
+/// Blob base fee of `block`, computed from the `excessBlobGas` of its header.
+/// Headers from before Cancun have none, which is treated as zero.
+pub fn block_blob_base_fee<TX>(block: &Block<TX>) -> Result<U256, Error> {
+    let excess_blob_gas = block
+        .other
+        .get_deserialized::<U64>("excessBlobGas")
+        .transpose()
+        .map_err(Error::SerdeError)?
+        .unwrap_or_default();
+    Ok(calc_blob_base_fee(excess_blob_gas.as_u64()))
+}
+
+/// Approximation of `factor * e ** (numerator / denominator)` using the
+/// Taylor expansion, as specified by EIP-4844.
+fn fake_exponential(factor: U256, numerator: U256, denominator: U256) -> U256 {
+    let mut i = U256::one();
+    let mut output = U256::zero();
+    let mut numerator_accum = factor * denominator;
+    while !numerator_accum.is_zero() {
+        output += numerator_accum;
+        numerator_accum = numerator_accum * numerator / (denominator * i);
+        i += U256::one();
+    }
+    output / denominator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fake_exponential_vectors() {
+        // test vectors of EIP-4844
+        for (factor, numerator, denominator, expected) in [
+            (1u64, 0u64, 1u64, 1u64),
+            (38493, 0, 1000, 38493),
+            (0, 1234, 2345, 0),
+            (1, 2, 1, 6),
+            (1, 4, 2, 6),
+            (1, 3, 1, 16),
+            (1, 6, 2, 18),
+            (1, 4, 1, 49),
+            (1, 8, 2, 50),
+            (10, 8, 2, 542),
+            (11, 8, 2, 596),
+            (1, 5, 1, 136),
+            (1, 5, 2, 11),
+            (2, 5, 2, 23),
+        ] {
+            assert_eq!(
+                fake_exponential(factor.into(), numerator.into(), denominator.into()),
+                expected.into(),
+                "fake_exponential({factor}, {numerator}, {denominator})"
+            );
+        }
+    }
+
+    #[test]
+    fn blob_base_fee() {
+        assert_eq!(calc_blob_base_fee(0), MIN_BLOB_BASE_FEE.into());
+        assert_eq!(calc_blob_base_fee(BLOB_BASE_FEE_UPDATE_FRACTION), 2.into());
+    }
+}
@@ -0,0 +1,186 @@
+//! Gas costs which changed across Ethereum hardforks.
+
+use super::{GasCost, INIT_CODE_WORD_GAS};
+use crate::AccessList;
+use serde::{Deserialize, Serialize};
+
+/// Ethereum hardforks which changed a cost of [`GasSchedule`].
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum EthFork {
+    /// Last fork before EIP-2028 lowered the cost of non-zero calldata bytes.
+    Petersburg,
+    /// EIP-2028 calldata and EIP-2200 SSTORE costs.
+    Istanbul,
+    /// EIP-2929 cold and warm accesses, EIP-2930 access lists.
+    Berlin,
+    /// EIP-3529 lowered SSTORE clears refund.
+    London,
+    /// EIP-3860 init code costs.
+    #[default]
+    Shanghai,
+}
+
+/// Gas costs of the configured [`EthFork`]. The default is the schedule of
+/// [`EthFork::Shanghai`], whose costs are the ones of [`GasCost`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasSchedule {
+    /// Fork of the schedule.
+    pub fork: EthFork,
+    /// Cost of a zero calldata byte of a tx.
+    pub tx_data_zero: u64,
+    /// Cost of a non-zero calldata byte of a tx.
+    pub tx_data_non_zero: u64,
+    /// Cost of an address in the access list of a tx, 0 before access lists.
+    pub access_list_per_address: u64,
+    /// Cost of a storage key in the access list of a tx, 0 before access
+    /// lists.
+    pub access_list_per_storage_key: u64,
+    /// Cost of a word of init code of a creation tx.
+    pub init_code_word: u64,
+    /// Cost of accessing a cold account. Before Berlin, it is the cost of
+    /// CALL and EXTCODE* accessing any account.
+    pub cold_account_access: u64,
+    /// Cost of reading a cold slot. Before Berlin, it is the cost of SLOAD.
+    pub cold_sload: u64,
+    /// Cost of accessing a warm account or slot. Before Berlin, it is the cost
+    /// of SLOAD.
+    pub warm_access: u64,
+    /// Cost of SSTORE setting a clean slot from zero.
+    pub sstore_set: u64,
+    /// Cost of SSTORE updating a clean non-zero slot.
+    pub sstore_reset: u64,
+    /// Refund of SSTORE clearing a slot.
+    pub sstore_clears_schedule: u64,
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        Self::of(EthFork::default())
+    }
+}
+
+impl GasSchedule {
+    /// Return the gas schedule of `fork`.
+    pub fn of(fork: EthFork) -> Self {
+        let shanghai = Self {
+            fork,
+            tx_data_zero: 4,
+            tx_data_non_zero: 16,
+            access_list_per_address: GasCost::ACCESS_LIST_PER_ADDRESS.as_u64(),
+            access_list_per_storage_key: GasCost::ACCESS_LIST_PER_STORAGE_KEY.as_u64(),
+            init_code_word: INIT_CODE_WORD_GAS,
+            cold_account_access: GasCost::COLD_ACCOUNT_ACCESS.as_u64(),
+            cold_sload: GasCost::COLD_SLOAD.as_u64(),
+            warm_access: GasCost::WARM_ACCESS.as_u64(),
+            sstore_set: GasCost::SSTORE_SET.as_u64(),
+            sstore_reset: GasCost::SSTORE_RESET.as_u64(),
+            sstore_clears_schedule: GasCost::SSTORE_CLEARS_SCHEDULE.as_u64(),
+        };
+        let london = Self {
+            init_code_word: 0,
+            ..shanghai
+        };
+        let berlin = Self {
+            sstore_clears_schedule: 15000,
+            ..london
+        };
+        let istanbul = Self {
+            access_list_per_address: 0,
+            access_list_per_storage_key: 0,
+            cold_account_access: 700,
+            cold_sload: 800,
+            warm_access: 800,
+            sstore_reset: 5000,
+            ..berlin
+        };
+        let petersburg = Self {
+            tx_data_non_zero: 68,
+            cold_sload: 200,
+            warm_access: 200,
+            ..istanbul
+        };
+        match fork {
+            EthFork::Petersburg => petersburg,
+            EthFork::Istanbul => istanbul,
+            EthFork::Berlin => berlin,
+            EthFork::London => london,
+            EthFork::Shanghai => shanghai,
+        }
+    }
+
+    /// Calculate gas cost for transaction data.
+    pub fn tx_data_gas_cost(&self, data: &[u8]) -> u64 {
+        data.iter().fold(0, |acc, byte| {
+            acc + if *byte == 0 {
+                self.tx_data_zero
+            } else {
+                self.tx_data_non_zero
+            }
+        })
+    }
+
+    /// Calculate gas cost for access list (EIP 2930).
+    pub fn tx_access_list_gas_cost(&self, access_list: &Option<AccessList>) -> u64 {
+        access_list.as_ref().map_or(0, |access_list| {
+            access_list.0.len() as u64 * self.access_list_per_address
+                + access_list
+                    .0
+                    .iter()
+                    .fold(0, |acc, item| acc + item.storage_keys.len() as u64)
+                    * self.access_list_per_storage_key
+        })
+    }
+
+    /// Calculate intrinsic gas of a transaction, charged before its execution.
+    pub fn tx_intrinsic_gas(
+        &self,
+        is_create: bool,
+        data: &[u8],
+        access_list: &Option<AccessList>,
+    ) -> u64 {
+        let (tx_gas, init_code_gas) = if is_create {
+            (
+                GasCost::CREATION_TX.as_u64(),
+                (data.len() as u64 + 31) / 32 * self.init_code_word,
+            )
+        } else {
+            (GasCost::TX.as_u64(), 0)
+        };
+        tx_gas
+            + self.tx_data_gas_cost(data)
+            + self.tx_access_list_gas_cost(access_list)
+            + init_code_gas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calldata_cost_per_fork() {
+        let data = [0, 1, 2];
+        assert_eq!(
+            GasSchedule::of(EthFork::Petersburg).tx_data_gas_cost(&data),
+            140
+        );
+        assert_eq!(
+            GasSchedule::of(EthFork::Istanbul).tx_data_gas_cost(&data),
+            36
+        );
+        assert_eq!(GasSchedule::default().tx_data_gas_cost(&data), 36);
+    }
+
+    #[test]
+    fn init_code_is_charged_since_shanghai() {
+        let init_code = [1; 33];
+        let london = GasSchedule::of(EthFork::London);
+        let shanghai = GasSchedule::of(EthFork::Shanghai);
+        assert_eq!(
+            shanghai.tx_intrinsic_gas(true, &init_code, &None),
+            london.tx_intrinsic_gas(true, &init_code, &None) + 2 * INIT_CODE_WORD_GAS
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! Utility functions to help calculate gas
 
-use super::GasCost;
+use super::{GasCost, GasSchedule};
 use crate::{AccessList, Word};
 
 /// Calculate memory expansion gas cost by current and next memory word size.
@@ -49,20 +49,20 @@ pub fn eip150_gas(gas_left: u64, gas_specified: Word) -> u64 {
     capped_gas
 }
 
-/// Calculate gas cost for access list (EIP 2930).
+/// Calculate gas cost for access list (EIP 2930), see
+/// [`GasSchedule::tx_access_list_gas_cost`].
 pub fn tx_access_list_gas_cost(access_list: &Option<AccessList>) -> u64 {
-    access_list.as_ref().map_or(0, |access_list| {
-        access_list.0.len() as u64 * GasCost::ACCESS_LIST_PER_ADDRESS.as_u64()
-            + access_list
-                .0
-                .iter()
-                .fold(0, |acc, item| acc + item.storage_keys.len() as u64)
-                * GasCost::ACCESS_LIST_PER_STORAGE_KEY.as_u64()
-    })
+    GasSchedule::default().tx_access_list_gas_cost(access_list)
 }
 
-/// Calculate gas cost for transaction data.
+/// Calculate gas cost for transaction data (EIP 2028), see
+/// [`GasSchedule::tx_data_gas_cost`].
 pub fn tx_data_gas_cost(data: &[u8]) -> u64 {
-    data.iter()
-        .fold(0, |acc, byte| acc + if *byte == 0 { 4 } else { 16 })
+    GasSchedule::default().tx_data_gas_cost(data)
+}
+
+/// Calculate intrinsic gas of a transaction, see
+/// [`GasSchedule::tx_intrinsic_gas`].
+pub fn tx_intrinsic_gas(is_create: bool, data: &[u8], access_list: &Option<AccessList>) -> u64 {
+    GasSchedule::default().tx_intrinsic_gas(is_create, data, access_list)
 }
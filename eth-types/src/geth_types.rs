@@ -3,25 +3,33 @@
 #[cfg(feature = "scroll")]
 use crate::l2_types::BlockTrace;
 use crate::{
+    evm_types::block_utils::{block_blob_base_fee, calc_blob_base_fee, GAS_PER_BLOB},
     sign_types::{biguint_to_32bytes_le, ct_option_ok_or, recover_pk2, SignData, SECP256K1_Q},
+    trace_file::TraceRecord,
     AccessList, Address, Block, Bytes, Error, GethExecTrace, Hash, ToBigEndian, ToLittleEndian,
-    Word, U64,
+    ToWord, Word, U64,
 };
 use ethers_core::types::{
     transaction::eip2718::TypedTransaction, Eip1559TransactionRequest, Eip2930TransactionRequest,
     NameOrAddress, TransactionRequest, H256,
 };
+use ethers_core::utils::rlp::RlpStream;
 use halo2curves::{group::ff::PrimeField, secp256k1::Fq};
 use num::Integer;
 use num_bigint::BigUint;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_with::serde_as;
 use sha3::{Digest, Keccak256};
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
 use strum_macros::EnumIter;
 
 /// Tx type
-#[derive(Default, Debug, Copy, Clone, EnumIter, Serialize, PartialEq, Eq)]
+#[derive(Default, Debug, Copy, Clone, EnumIter, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TxType {
     /// EIP 155 tx
     #[default]
@@ -32,8 +40,11 @@ pub enum TxType {
     Eip1559,
     /// EIP 2930 tx
     Eip2930,
-    /// L1 Message tx
+    /// L1 Message tx, or any other unsigned system tx recognized by the
+    /// [`TxTypeConfig`] in use
     L1Msg,
+    /// EIP 4844 blob tx
+    Eip4844,
 }
 
 impl From<TxType> for usize {
@@ -74,30 +85,20 @@ impl TxType {
         matches!(*self, TxType::Eip2930)
     }
 
-    /// Get the type of transaction
+    /// If this type is Eip4844 or not
+    pub fn is_eip4844(&self) -> bool {
+        matches!(*self, TxType::Eip4844)
+    }
+
+    /// If this type prices gas with a fee cap and a tip cap, as EIP-1559 and
+    /// EIP-4844 txs do.
+    pub fn is_dynamic_fee(&self) -> bool {
+        self.is_eip1559() || self.is_eip4844()
+    }
+
+    /// Get the type of transaction under the default [`TxTypeConfig`]
     pub fn get_tx_type(tx: &crate::Transaction) -> Self {
-        match tx.transaction_type {
-            Some(x) if x == U64::from(1) => Self::Eip2930,
-            Some(x) if x == U64::from(2) => Self::Eip1559,
-            Some(x) if x == U64::from(0x7e) => Self::L1Msg,
-            _ => {
-                if cfg!(feature = "scroll") {
-                    if tx.v.is_zero() && tx.r.is_zero() && tx.s.is_zero() {
-                        Self::L1Msg
-                    } else {
-                        match tx.v.as_u64() {
-                            0 | 1 | 27 | 28 => Self::PreEip155,
-                            _ => Self::Eip155,
-                        }
-                    }
-                } else {
-                    match tx.v.as_u64() {
-                        0 | 1 | 27 | 28 => Self::PreEip155,
-                        _ => Self::Eip155,
-                    }
-                }
-            }
-        }
+        TxTypeConfig::default().tx_type(tx)
     }
 
     /// Return the recovery id of signature for recovering the signing pk
@@ -116,6 +117,10 @@ impl TxType {
                 assert!(v <= 1);
                 v
             }
+            TxType::Eip4844 => {
+                assert!(v <= 1);
+                v
+            }
             TxType::L1Msg => {
                 unreachable!("L1 msg does not have signature")
             }
@@ -125,42 +130,314 @@ impl TxType {
     }
 }
 
-/// Get the RLP bytes for signing
-pub fn get_rlp_unsigned(tx: &crate::Transaction) -> Vec<u8> {
-    let sig_v = tx.v;
-    match TxType::get_tx_type(tx) {
-        TxType::Eip155 => {
-            let mut tx: TransactionRequest = tx.into();
-            tx.chain_id = Some(tx.chain_id.unwrap_or_else(|| {
-                let recv_v = TxType::Eip155.get_recovery_id(sig_v.as_u64()) as u64;
-                (sig_v - recv_v - 35) / 2
-            }));
-            tx.rlp().to_vec()
+/// How txs are mapped to [`TxType`]s. Besides the standard Ethereum tx types,
+/// an L2 may have unsigned system txs (L1 messages on Scroll, deposit txs on
+/// other rollups), which all map to [`TxType::L1Msg`]. Their signed RLP is
+/// `system_tx_type_id || rlp([nonce, gas, to, value, data, from])`, the
+/// layout the tx and RLP circuits decode for [`TxType::L1Msg`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxTypeConfig {
+    /// EIP-2718 type byte of the system txs.
+    pub system_tx_type_id: u64,
+    /// Whether a typeless tx with an all-zero signature is a system tx.
+    pub zero_sig_is_system_tx: bool,
+}
+
+impl Default for TxTypeConfig {
+    /// Scroll L1 messages, which have the type byte 0x7e.
+    fn default() -> Self {
+        Self {
+            system_tx_type_id: 0x7e,
+            zero_sig_is_system_tx: cfg!(feature = "scroll"),
         }
-        TxType::PreEip155 => {
-            let tx: TransactionRequest = tx.into();
-            tx.rlp_unsigned().to_vec()
+    }
+}
+
+impl TxTypeConfig {
+    /// Get the type of `tx`
+    pub fn tx_type(&self, tx: &crate::Transaction) -> TxType {
+        match tx.transaction_type {
+            Some(x) if x == U64::from(1) => TxType::Eip2930,
+            Some(x) if x == U64::from(2) => TxType::Eip1559,
+            Some(x) if cfg!(feature = "cancun") && x == U64::from(3) => TxType::Eip4844,
+            Some(x) if x == U64::from(self.system_tx_type_id) => TxType::L1Msg,
+            _ => {
+                if self.zero_sig_is_system_tx && tx.v.is_zero() && tx.r.is_zero() && tx.s.is_zero()
+                {
+                    TxType::L1Msg
+                } else {
+                    match tx.v.as_u64() {
+                        0 | 1 | 27 | 28 => TxType::PreEip155,
+                        _ => TxType::Eip155,
+                    }
+                }
+            }
+        }
+    }
+
+    /// EIP-2718 type byte of `tx_type`, 0 for legacy txs.
+    pub fn type_id(&self, tx_type: TxType) -> u64 {
+        match tx_type {
+            TxType::Eip155 | TxType::PreEip155 => 0,
+            TxType::Eip2930 => 1,
+            TxType::Eip1559 => 2,
+            TxType::Eip4844 => 3,
+            TxType::L1Msg => self.system_tx_type_id,
+        }
+    }
+
+    /// All tx types known under this config, and whether they are supported.
+    pub fn support_matrix(&self) -> Vec<TxTypeSupport> {
+        let mut matrix = vec![
+            TxTypeSupport {
+                type_id: 0,
+                name: "legacy",
+                required_feature: None,
+                supported: true,
+            },
+            TxTypeSupport {
+                type_id: 1,
+                name: "eip2930",
+                required_feature: None,
+                supported: true,
+            },
+            TxTypeSupport {
+                type_id: 2,
+                name: "eip1559",
+                required_feature: None,
+                supported: true,
+            },
+            TxTypeSupport {
+                type_id: 3,
+                name: "eip4844",
+                required_feature: Some("cancun"),
+                supported: cfg!(feature = "cancun"),
+            },
+            // set code txs are not supported by any build yet
+            TxTypeSupport {
+                type_id: 4,
+                name: "eip7702",
+                required_feature: None,
+                supported: false,
+            },
+        ];
+        // a system tx type byte shadows the standard tx type it collides with
+        matrix.retain(|support| support.type_id != self.system_tx_type_id);
+        matrix.push(TxTypeSupport {
+            type_id: self.system_tx_type_id,
+            name: "l1msg",
+            required_feature: None,
+            supported: true,
+        });
+        matrix
+    }
+
+    /// Support of the tx type with the given type byte. Type bytes unknown to
+    /// this config are reported as unsupported.
+    pub fn support_of(&self, type_id: u64) -> TxTypeSupport {
+        self.support_matrix()
+            .into_iter()
+            .find(|support| support.type_id == type_id)
+            .unwrap_or(TxTypeSupport {
+                type_id,
+                name: "unknown",
+                required_feature: None,
+                supported: false,
+            })
+    }
+
+    /// Support of the type of `tx`.
+    pub fn support_of_tx(&self, tx: &crate::Transaction) -> TxTypeSupport {
+        self.support_of(tx.transaction_type.map_or(0, |tx_type| tx_type.as_u64()))
+    }
+
+    /// Get the RLP bytes for signing. System txs are not signed.
+    pub fn rlp_unsigned(&self, tx: &crate::Transaction) -> Vec<u8> {
+        let sig_v = tx.v;
+        match self.tx_type(tx) {
+            TxType::Eip155 => {
+                let mut tx: TransactionRequest = tx.into();
+                tx.chain_id = Some(tx.chain_id.unwrap_or_else(|| {
+                    let recv_v = TxType::Eip155.get_recovery_id(sig_v.as_u64()) as u64;
+                    (sig_v - recv_v - 35) / 2
+                }));
+                tx.rlp().to_vec()
+            }
+            TxType::PreEip155 => {
+                let tx: TransactionRequest = tx.into();
+                tx.rlp_unsigned().to_vec()
+            }
+            TxType::Eip1559 => {
+                let tx: Eip1559TransactionRequest = tx.into();
+                let typed_tx: TypedTransaction = tx.into();
+                typed_tx.rlp().to_vec()
+            }
+            TxType::Eip2930 => {
+                let tx: Eip2930TransactionRequest = tx.into();
+                let typed_tx: TypedTransaction = tx.into();
+                typed_tx.rlp().to_vec()
+            }
+            TxType::Eip4844 => blob_tx_rlp(tx, false),
+            TxType::L1Msg => {
+                // L1 msg does not have signature
+                vec![]
+            }
         }
-        TxType::Eip1559 => {
-            let tx: Eip1559TransactionRequest = tx.into();
-            let typed_tx: TypedTransaction = tx.into();
-            typed_tx.rlp().to_vec()
+    }
+
+    /// Get the RLP bytes of the signed tx
+    pub fn rlp_signed(&self, tx: &crate::Transaction) -> Vec<u8> {
+        match self.tx_type(tx) {
+            TxType::Eip4844 => blob_tx_rlp(tx, true),
+            TxType::L1Msg => self.system_tx_rlp(tx),
+            _ => tx.rlp().to_vec(),
         }
-        TxType::Eip2930 => {
-            let tx: Eip2930TransactionRequest = tx.into();
-            let typed_tx: TypedTransaction = tx.into();
-            typed_tx.rlp().to_vec()
+    }
+
+    /// RLP bytes of a system tx:
+    /// `system_tx_type_id || rlp([nonce, gas, to, value, data, from])`.
+    fn system_tx_rlp(&self, tx: &crate::Transaction) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(6);
+        stream.append(&tx.nonce);
+        stream.append(&tx.gas);
+        match tx.to {
+            Some(to) => stream.append(&to),
+            None => stream.append_empty_data(),
+        };
+        stream.append(&tx.value);
+        stream.append(&tx.input.to_vec());
+        stream.append(&tx.from);
+
+        [&[self.system_tx_type_id as u8], stream.out().as_ref()].concat()
+    }
+}
+
+/// Support of a transaction type, identified by its EIP-2718 type byte, in
+/// this build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxTypeSupport {
+    /// EIP-2718 type byte, 0 for legacy txs.
+    pub type_id: u64,
+    /// Name of the tx type.
+    pub name: &'static str,
+    /// Feature the build needs to support this tx type, if any.
+    pub required_feature: Option<&'static str>,
+    /// Whether this build supports the tx type.
+    pub supported: bool,
+}
+
+impl TxTypeSupport {
+    /// Support of the tx type with the given type byte under the default
+    /// [`TxTypeConfig`]. Type bytes unknown to this build are reported as
+    /// unsupported.
+    pub fn of(type_id: u64) -> Self {
+        TxTypeConfig::default().support_of(type_id)
+    }
+
+    /// Support of the type of `tx` under the default [`TxTypeConfig`].
+    pub fn of_tx(tx: &crate::Transaction) -> Self {
+        TxTypeConfig::default().support_of_tx(tx)
+    }
+}
+
+/// All tx types known to this build, and whether they are supported.
+pub fn tx_type_support_matrix() -> Vec<TxTypeSupport> {
+    TxTypeConfig::default().support_matrix()
+}
+
+/// Type bytes of the tx types supported by this build.
+pub fn supported_tx_type_ids() -> Vec<u64> {
+    tx_type_support_matrix()
+        .into_iter()
+        .filter(|support| support.supported)
+        .map(|support| support.type_id)
+        .collect()
+}
+
+/// Get the RLP bytes for signing
+pub fn get_rlp_unsigned(tx: &crate::Transaction) -> Vec<u8> {
+    TxTypeConfig::default().rlp_unsigned(tx)
+}
+
+/// Get the RLP bytes of the signed tx
+pub fn get_rlp_signed(tx: &crate::Transaction) -> Vec<u8> {
+    TxTypeConfig::default().rlp_signed(tx)
+}
+
+/// RLP bytes of an EIP-4844 tx, which ethers cannot encode:
+/// `0x03 || rlp([chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas,
+/// gas_limit, to, value, data, access_list, max_fee_per_blob_gas,
+/// blob_versioned_hashes])`, followed by the signature when `signed`.
+fn blob_tx_rlp(tx: &crate::Transaction, signed: bool) -> Vec<u8> {
+    let blob = BlobTxFields::of_tx(tx).expect("invalid blob tx fields");
+    let mut stream = RlpStream::new();
+    stream.begin_unbounded_list();
+    stream.append(&tx.chain_id.unwrap_or_default());
+    stream.append(&tx.nonce);
+    stream.append(&tx.max_priority_fee_per_gas.unwrap_or_default());
+    stream.append(&tx.max_fee_per_gas.unwrap_or_default());
+    stream.append(&tx.gas);
+    stream.append(&tx.to.unwrap_or_default());
+    stream.append(&tx.value);
+    stream.append(&tx.input.to_vec());
+    stream.append(&tx.access_list.clone().unwrap_or_default());
+    stream.append(&blob.max_fee_per_blob_gas);
+    stream.append_list::<H256, _>(&blob.blob_versioned_hashes);
+    if signed {
+        stream.append(&tx.v);
+        stream.append(&tx.r);
+        stream.append(&tx.s);
+    }
+    stream.finalize_unbounded_list();
+
+    [&[3u8], stream.out().as_ref()].concat()
+}
+
+/// Blob fields of an EIP-4844 tx. Ethers does not know them, so they are
+/// read from the unknown fields of a [`crate::Transaction`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobTxFields {
+    /// Max fee per blob gas
+    pub max_fee_per_blob_gas: Word,
+    /// Versioned hashes of the blobs
+    pub blob_versioned_hashes: Vec<H256>,
+}
+
+impl BlobTxFields {
+    /// Blob fields of `tx`, empty if it is not a blob tx. Fails if a blob tx
+    /// has malformed blob fields or creates a contract.
+    pub fn of_tx(tx: &crate::Transaction) -> Result<Self, Error> {
+        if !TxType::get_tx_type(tx).is_eip4844() {
+            return Ok(Self::default());
         }
-        TxType::L1Msg => {
-            // L1 msg does not have signature
-            vec![]
+        if tx.to.is_none() {
+            return Err(Error::BlobTxCreate);
         }
+        Ok(Self {
+            max_fee_per_blob_gas: tx
+                .other
+                .get_deserialized::<Word>("maxFeePerBlobGas")
+                .transpose()
+                .map_err(Error::SerdeError)?
+                .unwrap_or_default(),
+            blob_versioned_hashes: tx
+                .other
+                .get_deserialized::<Vec<H256>>("blobVersionedHashes")
+                .transpose()
+                .map_err(Error::SerdeError)?
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Blob gas used by the tx
+    pub fn blob_gas(&self) -> u64 {
+        GAS_PER_BLOB * self.blob_versioned_hashes.len() as u64
     }
 }
 
 /// Definition of all of the data related to an account.
 #[serde_as]
-#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize)]
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Account {
     /// Address
     pub address: Address,
@@ -171,7 +448,10 @@ pub struct Account {
     /// EVM Code
     pub code: Bytes,
     /// Storage
-    #[serde(serialize_with = "serde_account_storage")]
+    #[serde(
+        serialize_with = "serde_account_storage",
+        deserialize_with = "de_account_storage"
+    )]
     pub storage: HashMap<Word, Word>,
 }
 
@@ -196,6 +476,15 @@ fn serde_account_storage<S: Serializer>(
         .serialize(serializer)
 }
 
+fn de_account_storage<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<HashMap<Word, Word>, D::Error> {
+    Ok(HashMap::<Hash, Hash>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(k, v)| (k.to_word(), v.to_word()))
+        .collect())
+}
+
 /// Definition of all of the constants related to an Ethereum block and
 /// chain to be used as setup for the external tracer.
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
@@ -212,6 +501,8 @@ pub struct BlockConstants {
     pub gas_limit: Word,
     /// base fee
     pub base_fee: Word,
+    /// blob base fee
+    pub blob_base_fee: Word,
 }
 
 impl<TX> TryFrom<&Block<TX>> for BlockConstants {
@@ -225,6 +516,7 @@ impl<TX> TryFrom<&Block<TX>> for BlockConstants {
             difficulty: block.difficulty,
             gas_limit: block.gas_limit,
             base_fee: block.base_fee_per_gas.ok_or(Error::IncompleteBlock)?,
+            blob_base_fee: block_blob_base_fee(block)?,
         })
     }
 }
@@ -246,6 +538,7 @@ impl BlockConstants {
             difficulty,
             gas_limit,
             base_fee,
+            blob_base_fee: calc_blob_base_fee(0),
         }
     }
 }
@@ -277,6 +570,8 @@ pub struct Transaction {
     pub call_data: Bytes,
     /// Access list
     pub access_list: Option<AccessList>,
+    /// Blob fields (EIP-4844)
+    pub blob: BlobTxFields,
 
     /// "v" value of the transaction signature
     pub v: u64,
@@ -331,10 +626,11 @@ impl From<&crate::Transaction> for Transaction {
             gas_fee_cap: tx.max_fee_per_gas,
             call_data: tx.input.clone(),
             access_list: tx.access_list.clone(),
+            blob: BlobTxFields::of_tx(tx).unwrap_or_default(),
             v: tx.v.as_u64(),
             r: tx.r,
             s: tx.s,
-            rlp_bytes: tx.rlp().to_vec(),
+            rlp_bytes: get_rlp_signed(tx),
             rlp_unsigned_bytes: get_rlp_unsigned(tx),
             hash: tx.hash,
         }
@@ -424,6 +720,56 @@ impl GethData {
 }
 */
 
+/// Encoding of a [`GethData`] in a JSON file.
+#[derive(Serialize, Deserialize)]
+struct GethDataRecord {
+    chain_id: u64,
+    history_hashes: Vec<Word>,
+    eth_block: Block<crate::Transaction>,
+    geth_traces: Vec<TraceRecord>,
+    accounts: Vec<Account>,
+    #[cfg(feature = "scroll")]
+    block_trace: BlockTrace,
+}
+
+impl GethData {
+    /// Write the data to the JSON file at `path`, so that it can be checked
+    /// in as a fixture instead of being traced again.
+    pub fn to_json_file(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let record = GethDataRecord {
+            chain_id: self.chain_id,
+            history_hashes: self.history_hashes.clone(),
+            eth_block: self.eth_block.clone(),
+            geth_traces: self.geth_traces.iter().map(TraceRecord::from).collect(),
+            accounts: self.accounts.clone(),
+            #[cfg(feature = "scroll")]
+            block_trace: self.block_trace.clone(),
+        };
+        let file = BufWriter::new(File::create(path).map_err(Error::IoError)?);
+        serde_json::to_writer(file, &record).map_err(Error::SerdeError)
+    }
+
+    /// Read the data from the JSON file at `path`, written by
+    /// [`Self::to_json_file`].
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file = BufReader::new(File::open(path).map_err(Error::IoError)?);
+        let record: GethDataRecord = serde_json::from_reader(file).map_err(Error::SerdeError)?;
+        Ok(Self {
+            chain_id: record.chain_id,
+            history_hashes: record.history_hashes,
+            eth_block: record.eth_block,
+            geth_traces: record
+                .geth_traces
+                .into_iter()
+                .map(GethExecTrace::try_from)
+                .collect::<Result<_, _>>()?,
+            accounts: record.accounts,
+            #[cfg(feature = "scroll")]
+            block_trace: record.block_trace,
+        })
+    }
+}
+
 /// Returns the number of addresses and the cumulative number of storage keys in
 /// the entire access list.
 pub fn access_list_size(access_list: &Option<AccessList>) -> (u64, u64) {
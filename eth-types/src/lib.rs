@@ -24,6 +24,7 @@ pub mod macros;
 pub mod error;
 #[macro_use]
 pub mod bytecode;
+pub mod code_store;
 pub mod constants;
 pub mod evm_types;
 pub mod forks;
@@ -31,9 +32,13 @@ pub mod geth_types;
 /// L2 system contracts
 pub mod l2_predeployed;
 pub mod l2_types;
+pub mod mpt_proof;
 pub mod sign_types;
 pub mod state_db;
+pub mod trace_file;
+pub mod tx_envelope;
 pub mod utils;
+pub mod word;
 
 use crate::evm_types::{Gas, GasCost, OpcodeId, ProgramCounter};
 pub use bytecode::Bytecode;
@@ -51,12 +56,13 @@ pub use ethers_core::{
 };
 use serde::{de, Deserialize, Deserializer, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::HashMap,
     fmt,
     fmt::{Display, Formatter},
     str::FromStr,
     sync::LazyLock,
 };
+pub use word::{FieldWord, StackWord, WordExt};
 
 #[cfg(feature = "enable-memory")]
 use crate::evm_types::Memory;
@@ -718,6 +724,10 @@ pub struct GethPrestateTrace {
     pub storage: Option<HashMap<U256, U256>>,
 }
 
+/// Maximum depth of a call in a [`GethCallTrace`], the root call of a tx
+/// being at depth 1. The EVM rejects calls nested more than 1024 times.
+pub const MAX_CALL_DEPTH: usize = 1025;
+
 /// The call trace returned by geth RPC debug_trace* methods.
 /// using callTracer
 #[derive(Deserialize, Serialize, Clone, Debug, Eq, PartialEq)]
@@ -725,38 +735,128 @@ pub struct GethCallTrace {
     #[serde(default)]
     calls: Vec<GethCallTrace>,
     error: Option<String>,
+    #[serde(
+        rename = "revertReason",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    revert_reason: Option<String>,
     from: Address,
-    // gas: U256,
+    #[serde(default)]
+    gas: U256,
     #[serde(rename = "gasUsed")]
     gas_used: U256,
-    // input: Bytes,
+    #[serde(default)]
+    input: Bytes,
     output: Option<Bytes>,
     to: Option<Address>,
     #[serde(rename = "type")]
     call_type: String,
-    // value: U256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<U256>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    logs: Vec<GethCallLog>,
 }
 
-/// Flattened Call Trace
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct FlatGethCallTrace {
-    // error: Option<String>,
-    /// from
-    pub from: Address,
-    // gas: U256,
-    /// gas used
-    pub gas_used: U256,
-    // input: Bytes,
-    /// is callee code empty
-    pub is_callee_code_empty: bool,
-    /// to
-    pub to: Option<Address>,
-    /// call type
-    pub call_type: OpcodeId,
-    // value: U256,
+/// Log emitted by a call of a [`GethCallTrace`], only traced with the
+/// `withLog` option of the callTracer.
+#[derive(Deserialize, Serialize, Clone, Debug, Eq, PartialEq)]
+pub struct GethCallLog {
+    /// address of the contract emitting the log
+    pub address: Address,
+    /// topics
+    #[serde(default)]
+    pub topics: Vec<H256>,
+    /// data
+    #[serde(default)]
+    pub data: Bytes,
 }
 
 impl GethCallTrace {
+    /// Sub calls, in the order they are entered.
+    pub fn calls(&self) -> &[GethCallTrace] {
+        &self.calls
+    }
+
+    /// Error of the call, `None` if it succeeded.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Reason of the revert, decoded from the output, if any.
+    pub fn revert_reason(&self) -> Option<&str> {
+        self.revert_reason.as_deref()
+    }
+
+    /// Caller of the call, which is the current contract for CALLCODE and
+    /// DELEGATECALL.
+    pub fn from(&self) -> Address {
+        self.from
+    }
+
+    /// Gas provided to the call.
+    pub fn gas(&self) -> U256 {
+        self.gas
+    }
+
+    /// Gas used by the call.
+    pub fn gas_used(&self) -> U256 {
+        self.gas_used
+    }
+
+    /// Call data, or init code for CREATE and CREATE2.
+    pub fn input(&self) -> &Bytes {
+        &self.input
+    }
+
+    /// Return data, or deployed code for CREATE and CREATE2.
+    pub fn output(&self) -> Option<&Bytes> {
+        self.output.as_ref()
+    }
+
+    /// Callee of the call, which is the created address for CREATE and
+    /// CREATE2.
+    pub fn to(&self) -> Option<Address> {
+        self.to
+    }
+
+    /// Type of the call, as the name of its opcode.
+    pub fn call_type(&self) -> &str {
+        &self.call_type
+    }
+
+    /// Value transferred by the call, `None` for DELEGATECALL.
+    pub fn value(&self) -> Option<U256> {
+        self.value
+    }
+
+    /// Logs emitted by the call, if traced.
+    pub fn logs(&self) -> &[GethCallLog] {
+        &self.logs
+    }
+
+    /// Return the calls executed by geth with their depth, the root call
+    /// being at depth 1, in the order they are entered. The calls skipped by
+    /// geth, e.g. on insufficient balance, are left out as they get no call
+    /// index.
+    pub fn executed_calls(&self) -> Vec<(&GethCallTrace, usize)> {
+        let mut calls = vec![];
+        let mut stack = vec![(self, 1)];
+        while let Some((call, depth)) = stack.pop() {
+            if call.is_skipped() {
+                continue;
+            }
+            calls.push((call, depth));
+            stack.extend(
+                call.calls
+                    .iter()
+                    .rev()
+                    .map(|sub_call| (sub_call, depth + 1)),
+            );
+        }
+        calls
+    }
+
     fn is_precheck_failed(&self) -> bool {
         self.error
             .as_ref()
@@ -765,84 +865,46 @@ impl GethCallTrace {
             .unwrap_or(false)
     }
 
-    /// generate the call_is_success vec
-    pub fn gen_call_is_success(&self, mut call_is_success: Vec<bool>) -> Vec<bool> {
+    /// Errors that make geth skip a call without executing it, so that it
+    /// gets no call index.
+    fn is_skipped(&self) -> bool {
         // ignore the call if precheck failed
         // https://github.com/ethereum/go-ethereum/issues/21438
-        if self.is_precheck_failed() {
-            return call_is_success;
-        }
-        if self.error.as_deref() == Some(GethExecError::InsufficientBalance.error()) {
-            return call_is_success;
-        }
-        if self.error.as_deref() == Some(GethExecError::Depth.error()) {
-            return call_is_success;
-        }
-        if self.error.as_deref() == Some(GethExecError::NonceUintOverflow.error()) {
-            return call_is_success;
-        }
-        call_is_success.push(self.error.is_none());
-        for call in &self.calls {
-            call_is_success = call.gen_call_is_success(call_is_success);
-        }
-        call_is_success
-    }
-
-    /// flatten the call trace as it is.
-    pub fn flatten_trace(
-        &self,
-        prestate: &HashMap<Address, GethPrestateTrace>,
-    ) -> Vec<FlatGethCallTrace> {
-        let mut trace = vec![];
-        // store the code that created in this tx, which is not include in prestate
-        let mut created = HashSet::new();
-        self.flatten_trace_inner(prestate, &mut trace, &mut created);
-        trace
+        self.is_precheck_failed()
+            || [
+                GethExecError::InsufficientBalance,
+                GethExecError::Depth,
+                GethExecError::NonceUintOverflow,
+            ]
+            .into_iter()
+            .any(|err| self.error.as_deref() == Some(err.error()))
     }
 
-    fn flatten_trace_inner(
+    /// generate the call_is_success vec, in the order the calls are entered.
+    /// The trace is walked with an explicit stack, and an error is returned
+    /// for calls deeper than `max_depth` (the root call being at depth 1).
+    pub fn gen_call_is_success(
         &self,
-        prestate: &HashMap<Address, GethPrestateTrace>,
-        trace: &mut Vec<FlatGethCallTrace>,
-        created: &mut HashSet<Address>,
-    ) {
-        // ignore the call if precheck failed
-        // https://github.com/ethereum/go-ethereum/issues/21438
-        if self.is_precheck_failed() {
-            return;
-        }
-        let call_type = OpcodeId::from_str(&self.call_type).unwrap();
-        let is_callee_code_empty = self
-            .to
-            .as_ref()
-            .map(|addr| {
-                !created.contains(addr)
-                    && prestate
-                        .get(addr)
-                        .unwrap()
-                        .code
-                        .as_ref()
-                        .unwrap()
-                        .is_empty()
-            })
-            .unwrap_or(false);
-
-        trace.push(FlatGethCallTrace {
-            from: self.from,
-            to: self.to,
-            is_callee_code_empty,
-            gas_used: self.gas_used,
-            call_type,
-        });
-
-        for call in &self.calls {
-            call.flatten_trace_inner(prestate, trace, created);
-        }
-
-        let has_output = self.output.as_ref().map(|x| !x.is_empty()).unwrap_or(false);
-        if call_type.is_create() && has_output {
-            created.insert(self.to.unwrap());
+        mut call_is_success: Vec<bool>,
+        max_depth: usize,
+    ) -> Result<Vec<bool>, Error> {
+        let mut stack = vec![(self, 1)];
+        while let Some((call, depth)) = stack.pop() {
+            if call.is_skipped() {
+                continue;
+            }
+            if depth > max_depth {
+                return Err(Error::CallDepthExceeded { depth, max_depth });
+            }
+            call_is_success.push(call.error.is_none());
+            stack.extend(
+                call.calls
+                    .iter()
+                    .rev()
+                    .map(|sub_call| (sub_call, depth + 1)),
+            );
         }
+        Ok(call_is_success)
     }
 }
 
@@ -1058,15 +1120,60 @@ mod tests {
                 call_trace: GethCallTrace {
                     calls: Vec::new(),
                     error: None,
+                    revert_reason: None,
                     from: address!("0x000000000000000000000000000000000cafe001"),
                     to: None,
+                    gas: U256::zero(),
                     gas_used: U256::zero(),
+                    input: Bytes::default(),
                     call_type: "CALL".to_string(),
-                    output: Some(Bytes::from([0x00]))
+                    output: Some(Bytes::from([0x00])),
+                    value: None,
+                    logs: Vec::new(),
                 }
             }
         );
     }
+    /// Call trace of a contract calling itself recursively until the EVM
+    /// rejects the call nested more than 1024 times.
+    fn recursive_call_trace() -> GethCallTrace {
+        let callee = address!("0x000000000000000000000000000000000cafe001");
+        let call = |calls, error: Option<&str>| GethCallTrace {
+            calls,
+            error: error.map(str::to_string),
+            revert_reason: None,
+            from: callee,
+            to: Some(callee),
+            gas: U256::zero(),
+            gas_used: U256::zero(),
+            input: Bytes::default(),
+            call_type: "CALL".to_string(),
+            output: None,
+            value: None,
+            logs: Vec::new(),
+        };
+        (0..MAX_CALL_DEPTH).fold(
+            call(vec![], Some(GethExecError::Depth.error())),
+            |sub_call, _| call(vec![sub_call], None),
+        )
+    }
+
+    #[test]
+    fn recursive_call_trace_at_max_depth() {
+        let call_trace = recursive_call_trace();
+        let call_is_success = call_trace
+            .gen_call_is_success(vec![], MAX_CALL_DEPTH)
+            .unwrap();
+        assert_eq!(call_is_success, vec![true; MAX_CALL_DEPTH]);
+
+        assert!(matches!(
+            call_trace.gen_call_is_success(vec![], 1024),
+            Err(Error::CallDepthExceeded {
+                depth: 1025,
+                max_depth: 1024
+            })
+        ));
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,614 @@
+//! Verification of proofs of the standard Ethereum hexary Merkle Patricia
+//! Trie, as returned by `eth_getProof` (EIP-1186), against keccak state and
+//! storage roots, and roots of the tries of the txs and receipts of a block.
+
+use crate::{Address, Bytes, EIP1186ProofResponse, Hash, Word, H256};
+use ethers_core::utils::{
+    keccak256,
+    rlp::{self, DecoderError, Rlp, RlpStream},
+};
+use serde::{Deserialize, Serialize};
+
+/// Node of the empty hexary trie, the RLP of the empty string.
+pub const EMPTY_TRIE_NODE: [u8; 1] = [0x80];
+
+/// Root of the empty hexary trie, keccak256 of [`EMPTY_TRIE_NODE`].
+pub const EMPTY_TRIE_ROOT: H256 = H256([
+    0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+    0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+]);
+
+/// Error of a hexary trie proof.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MptProofError {
+    /// The proof ends before the node of hash.
+    MissingNode(Hash),
+    /// The node at index of the proof doesn't have the hash referenced by its
+    /// parent.
+    HashMismatch(usize),
+    /// A node is neither a branch, an extension nor a leaf.
+    InvalidNode,
+    /// The proof has nodes after the end of the path.
+    UnusedNodes,
+    /// The node at index is valid, but inlines a child or is a branch with a
+    /// value, which the keccak MPT circuit doesn't support.
+    UnsupportedNode(usize),
+    /// RLP decoding error.
+    Rlp(DecoderError),
+}
+
+impl std::fmt::Display for MptProofError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl std::error::Error for MptProofError {}
+
+impl From<DecoderError> for MptProofError {
+    fn from(err: DecoderError) -> Self {
+        Self::Rlp(err)
+    }
+}
+
+/// Account as stored in the leaves of the Ethereum state trie.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TrieAccount {
+    /// Nonce
+    pub nonce: Word,
+    /// Balance
+    pub balance: Word,
+    /// Root of the storage trie
+    pub storage_root: H256,
+    /// Keccak hash of code
+    pub code_hash: H256,
+}
+
+impl TrieAccount {
+    /// RLP of the account, the value of its leaf in the state trie.
+    pub fn rlp(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream
+            .append(&self.nonce)
+            .append(&self.balance)
+            .append(&self.storage_root)
+            .append(&self.code_hash);
+        stream.out().to_vec()
+    }
+}
+
+/// Reference of a child node, by hash or inlined in its parent when its RLP is
+/// shorter than 32 bytes.
+enum NodeRef {
+    Hash(Hash),
+    Inline(Vec<u8>),
+}
+
+impl NodeRef {
+    fn from_rlp(rlp: &Rlp) -> Result<Option<Self>, MptProofError> {
+        Ok(if rlp.is_list() {
+            Some(Self::Inline(rlp.as_raw().to_vec()))
+        } else if rlp.is_empty() {
+            None
+        } else {
+            let hash = rlp.data()?;
+            if hash.len() != 32 {
+                return Err(MptProofError::InvalidNode);
+            }
+            Some(Self::Hash(H256::from_slice(hash)))
+        })
+    }
+}
+
+/// Decode a hex-prefix encoded path into its nibbles and leaf flag.
+fn decode_path(encoded: &[u8]) -> Result<(Vec<u8>, bool), MptProofError> {
+    let (first, rest) = encoded.split_first().ok_or(MptProofError::InvalidNode)?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(MptProofError::InvalidNode);
+    }
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(rest.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+    Ok((nibbles, flag >= 2))
+}
+
+/// Hex-prefix encode the nibbles of a path, flagged as the path of a leaf or
+/// of an extension.
+fn encode_path(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let mut encoded = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let nibbles = if nibbles.len() % 2 == 1 {
+        encoded.push(((flag + 1) << 4) | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        encoded.push(flag << 4);
+        nibbles
+    };
+    encoded.extend(nibbles.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    encoded
+}
+
+/// Append the reference of the child node of RLP `node` to its parent.
+fn append_child(stream: &mut RlpStream, node: &[u8]) {
+    if node.len() < 32 {
+        stream.append_raw(node, 1);
+    } else {
+        stream.append(&keccak256(node).to_vec());
+    }
+}
+
+/// RLP of the node of the sorted `entries`, all sharing the first `depth`
+/// nibbles of their path. The nodes on `proof_path`, if any, are pushed to
+/// `proof`, from the deepest one.
+fn encode_node(
+    entries: &[(Vec<u8>, &[u8])],
+    depth: usize,
+    proof_path: Option<&[u8]>,
+    proof: &mut Vec<Bytes>,
+) -> Vec<u8> {
+    let node = encode_node_children(entries, depth, proof_path, proof);
+    if proof_path.is_some() && (depth == 0 || node.len() >= 32) {
+        proof.push(node.clone().into());
+    }
+    node
+}
+
+fn encode_node_children(
+    entries: &[(Vec<u8>, &[u8])],
+    depth: usize,
+    proof_path: Option<&[u8]>,
+    proof: &mut Vec<Bytes>,
+) -> Vec<u8> {
+    if let [(path, value)] = entries {
+        let mut stream = RlpStream::new_list(2);
+        stream
+            .append(&encode_path(&path[depth..], true))
+            .append(&value.to_vec());
+        return stream.out().to_vec();
+    }
+
+    // the common prefix of sorted paths is the one of the first and the last
+    let (first, last) = (&entries[0].0, &entries[entries.len() - 1].0);
+    let shared = first[depth..]
+        .iter()
+        .zip(&last[depth..])
+        .take_while(|(a, b)| a == b)
+        .count();
+    if shared > 0 {
+        let prefix = &first[depth..depth + shared];
+        let child_path = proof_path.filter(|path| path.get(depth..depth + shared) == Some(prefix));
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&encode_path(prefix, false));
+        append_child(
+            &mut stream,
+            &encode_node(entries, depth + shared, child_path, proof),
+        );
+        return stream.out().to_vec();
+    }
+
+    let mut stream = RlpStream::new_list(17);
+    // a path ending at the branch sorts first
+    let (value, entries) = match entries.split_first() {
+        Some(((path, value), rest)) if path.len() == depth => (Some(*value), rest),
+        _ => (None, entries),
+    };
+    for nibble in 0..16 {
+        let start = entries.partition_point(|(path, _)| path[depth] < nibble);
+        let end = entries.partition_point(|(path, _)| path[depth] <= nibble);
+        if start == end {
+            stream.append_empty_data();
+        } else {
+            let child_path = proof_path.filter(|path| path.get(depth) == Some(&nibble));
+            append_child(
+                &mut stream,
+                &encode_node(&entries[start..end], depth + 1, child_path, proof),
+            );
+        }
+    }
+    match value {
+        Some(value) => stream.append(&value.to_vec()),
+        None => stream.append_empty_data(),
+    };
+    stream.out().to_vec()
+}
+
+/// Root of the trie of `values`, keyed by the RLP of their index, as the
+/// transactions and receipts roots of a block.
+pub fn ordered_trie_root<T: AsRef<[u8]>>(values: &[T]) -> H256 {
+    let mut entries: Vec<(Vec<u8>, &[u8])> = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let key = rlp::encode(&(index as u64));
+            let path = key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]);
+            (path.collect(), value.as_ref())
+        })
+        .collect();
+    entries.sort();
+    if entries.is_empty() {
+        return EMPTY_TRIE_ROOT;
+    }
+    H256(keccak256(encode_node(&entries, 0, None, &mut Vec::new())))
+}
+
+/// Root of the secure trie of `entries`, keyed by the keccak hash of their
+/// keys as the state and storage tries, and the proof of `key` in it.
+pub fn secure_trie_proof<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+    entries: &[(K, V)],
+    key: &[u8],
+) -> (H256, Vec<Bytes>) {
+    let nibbles = |key: &[u8]| -> Vec<u8> {
+        keccak256(key)
+            .iter()
+            .flat_map(|byte| [byte >> 4, byte & 0x0f])
+            .collect()
+    };
+    let mut entries: Vec<(Vec<u8>, &[u8])> = entries
+        .iter()
+        .map(|(key, value)| (nibbles(key.as_ref()), value.as_ref()))
+        .collect();
+    entries.sort();
+    entries.dedup_by(|a, b| a.0 == b.0);
+    if entries.is_empty() {
+        return (EMPTY_TRIE_ROOT, Vec::new());
+    }
+    let mut proof = Vec::new();
+    let root = encode_node(&entries, 0, Some(&nibbles(key)), &mut proof);
+    proof.reverse();
+    (H256(keccak256(root)), proof)
+}
+
+/// Root of the secure trie of `entries`, as [`secure_trie_proof`] without
+/// the proof.
+pub fn secure_trie_root<K: AsRef<[u8]>, V: AsRef<[u8]>>(entries: &[(K, V)]) -> H256 {
+    secure_trie_proof(entries, &[]).0
+}
+
+/// Root of the storage trie of the `slots`, the zero ones being left out.
+pub fn storage_root(slots: &[(Word, Word)]) -> H256 {
+    let entries: Vec<_> = slots
+        .iter()
+        .filter(|(_, value)| !value.is_zero())
+        .map(|(key, value)| {
+            let mut slot = [0u8; 32];
+            key.to_big_endian(&mut slot);
+            (slot, rlp::encode(value).to_vec())
+        })
+        .collect();
+    secure_trie_root(&entries)
+}
+
+/// Root of the state trie of the `accounts`, by address.
+pub fn state_root(accounts: &[(Address, TrieAccount)]) -> H256 {
+    let entries: Vec<_> = accounts
+        .iter()
+        .map(|(address, account)| (*address, account.rlp()))
+        .collect();
+    secure_trie_root(&entries)
+}
+
+/// Verify the proof of `key` in the trie of `root`. Return the value of the
+/// leaf, or `None` if the proof shows that the trie has no such key. The proof
+/// of a key of the empty trie may be empty, as returned by `eth_getProof` for
+/// the empty storage tries.
+pub fn verify_proof(
+    root: Hash,
+    key: &[u8],
+    proof: &[Bytes],
+) -> Result<Option<Vec<u8>>, MptProofError> {
+    if proof.is_empty() && root == EMPTY_TRIE_ROOT {
+        return Ok(None);
+    }
+    let path: Vec<u8> = key
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .collect();
+    let mut path = path.as_slice();
+    let mut proof = proof.iter().enumerate();
+    let mut next = NodeRef::Hash(root);
+
+    let value = loop {
+        let node = match next {
+            NodeRef::Hash(hash) => {
+                let (index, node) = proof.next().ok_or(MptProofError::MissingNode(hash))?;
+                if H256(keccak256(node)) != hash {
+                    return Err(MptProofError::HashMismatch(index));
+                }
+                node.to_vec()
+            }
+            NodeRef::Inline(node) => node,
+        };
+        let node = Rlp::new(&node);
+        if !node.is_list() && node.is_empty() {
+            // empty trie
+            break None;
+        }
+        let child = match node.item_count()? {
+            17 => match path.split_first() {
+                None => {
+                    let value = node.at(16)?;
+                    break (!value.is_empty())
+                        .then(|| value.data().map(<[u8]>::to_vec))
+                        .transpose()?;
+                }
+                Some((nibble, rest)) => {
+                    path = rest;
+                    NodeRef::from_rlp(&node.at(*nibble as usize)?)?
+                }
+            },
+            2 => {
+                let (node_path, is_leaf) = decode_path(node.at(0)?.data()?)?;
+                if is_leaf {
+                    break (path == node_path.as_slice())
+                        .then(|| node.at(1)?.data().map(<[u8]>::to_vec))
+                        .transpose()?;
+                }
+                match path.strip_prefix(node_path.as_slice()) {
+                    Some(rest) => {
+                        path = rest;
+                        NodeRef::from_rlp(&node.at(1)?)?
+                    }
+                    None => None,
+                }
+            }
+            _ => return Err(MptProofError::InvalidNode),
+        };
+        match child {
+            Some(child) => next = child,
+            None => break None,
+        }
+    };
+
+    if proof.next().is_some() {
+        return Err(MptProofError::UnusedNodes);
+    }
+    Ok(value)
+}
+
+/// Verify the proof of the account of `address` in the state trie of
+/// `state_root`. Return `None` for a proof of a non existing account.
+pub fn verify_account_proof(
+    state_root: Hash,
+    address: Address,
+    proof: &[Bytes],
+) -> Result<Option<TrieAccount>, MptProofError> {
+    verify_proof(state_root, &keccak256(address), proof)?
+        .map(|value| {
+            let account = Rlp::new(&value);
+            Ok(TrieAccount {
+                nonce: account.val_at(0)?,
+                balance: account.val_at(1)?,
+                storage_root: account.val_at(2)?,
+                code_hash: account.val_at(3)?,
+            })
+        })
+        .transpose()
+}
+
+/// Verify the proof of the slot `key` in the storage trie of `storage_root`.
+/// Return the value of the slot, zero if the slot is not in the trie.
+pub fn verify_storage_proof(
+    storage_root: Hash,
+    key: Word,
+    proof: &[Bytes],
+) -> Result<Word, MptProofError> {
+    let mut slot = [0u8; 32];
+    key.to_big_endian(&mut slot);
+    Ok(match verify_proof(storage_root, &keccak256(slot), proof)? {
+        Some(value) => Rlp::new(&value).as_val()?,
+        None => Word::zero(),
+    })
+}
+
+/// Proof of a key of a secure trie, i.e. a trie keyed by the keccak hash of
+/// the keys, as the state and storage tries.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MptProof {
+    /// Root of the trie
+    pub root: H256,
+    /// Key before hashing: the address of an account, or the big-endian slot
+    /// of a storage value
+    pub key: Vec<u8>,
+    /// RLP of the nodes on the path of the key, from the root
+    pub nodes: Vec<Bytes>,
+}
+
+impl MptProof {
+    /// The proof of the account and the proofs of the storage slots of an
+    /// `eth_getProof` response against `state_root`.
+    pub fn from_eip1186(state_root: H256, proof: &EIP1186ProofResponse) -> Vec<Self> {
+        let account = Self {
+            root: state_root,
+            key: proof.address.as_bytes().to_vec(),
+            nodes: proof.account_proof.clone(),
+        };
+        let storage = proof.storage_proof.iter().map(|storage_proof| {
+            let mut slot = [0u8; 32];
+            storage_proof.key.to_big_endian(&mut slot);
+            Self {
+                root: proof.storage_hash,
+                key: slot.to_vec(),
+                nodes: storage_proof.proof.clone(),
+            }
+        });
+        std::iter::once(account).chain(storage).collect()
+    }
+
+    /// Verify the proof, see [`verify_proof`].
+    pub fn verify(&self) -> Result<Option<Vec<u8>>, MptProofError> {
+        verify_proof(self.root, &keccak256(&self.key), &self.nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Single leaf trie of `value` at `key`.
+    fn leaf_node(key: &[u8; 32], value: &[u8]) -> Bytes {
+        let mut path = vec![0x20];
+        path.extend_from_slice(key);
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&path).append(&value.to_vec());
+        stream.out().to_vec().into()
+    }
+
+    #[test]
+    fn empty_trie_proof() {
+        assert_eq!(EMPTY_TRIE_ROOT, H256(keccak256(EMPTY_TRIE_NODE)));
+        let proof = [Bytes::from(vec![0x80])];
+        assert_eq!(verify_proof(EMPTY_TRIE_ROOT, &[1; 32], &proof), Ok(None));
+        assert_eq!(verify_proof(EMPTY_TRIE_ROOT, &[1; 32], &[]), Ok(None));
+        assert_eq!(
+            verify_storage_proof(EMPTY_TRIE_ROOT, Word::one(), &[]),
+            Ok(Word::zero())
+        );
+        assert_eq!(
+            verify_proof(H256::zero(), &[1; 32], &[]),
+            Err(MptProofError::MissingNode(H256::zero()))
+        );
+        assert_eq!(
+            verify_storage_proof(EMPTY_TRIE_ROOT, Word::one(), &proof),
+            Ok(Word::zero())
+        );
+    }
+
+    #[test]
+    fn single_leaf_storage_proof() {
+        let key = Word::from(7);
+        let value = Word::from(0x1234);
+        let mut slot = [0u8; 32];
+        key.to_big_endian(&mut slot);
+        let node = leaf_node(&keccak256(slot), &ethers_core::utils::rlp::encode(&value));
+        let root = H256(keccak256(&node));
+        let proof = [node];
+
+        assert_eq!(verify_storage_proof(root, key, &proof), Ok(value));
+        assert_eq!(
+            verify_storage_proof(root, Word::from(8), &proof),
+            Ok(Word::zero())
+        );
+        assert_eq!(
+            verify_storage_proof(H256::zero(), key, &proof),
+            Err(MptProofError::HashMismatch(0))
+        );
+    }
+
+    #[test]
+    fn single_leaf_account_proof() {
+        let address = Address::repeat_byte(0xaa);
+        let account = TrieAccount {
+            nonce: Word::from(1),
+            balance: Word::from(100),
+            storage_root: EMPTY_TRIE_ROOT,
+            code_hash: *crate::KECCAK_CODE_HASH_EMPTY,
+        };
+        let mut stream = RlpStream::new_list(4);
+        stream
+            .append(&account.nonce)
+            .append(&account.balance)
+            .append(&account.storage_root)
+            .append(&account.code_hash);
+        assert_eq!(account.rlp(), stream.out().to_vec());
+        let node = leaf_node(&keccak256(address), &stream.out());
+        let root = H256(keccak256(&node));
+        assert_eq!(state_root(&[(address, account.clone())]), root);
+
+        assert_eq!(
+            verify_account_proof(root, address, &[node]),
+            Ok(Some(account))
+        );
+    }
+
+    #[test]
+    fn invalid_child_reference() {
+        // a branch referencing its child by a 5 bytes string
+        let mut stream = RlpStream::new_list(17);
+        stream.append(&vec![0x11; 5]);
+        for _ in 1..17 {
+            stream.append_empty_data();
+        }
+        let branch = Bytes::from(stream.out().to_vec());
+        let root = H256(keccak256(&branch));
+        assert_eq!(
+            verify_proof(root, &[0x01; 32], &[branch]),
+            Err(MptProofError::InvalidNode)
+        );
+    }
+
+    #[test]
+    fn secure_trie_proofs() {
+        let entries: Vec<_> = (1..40u64)
+            .map(|slot| {
+                let mut key = [0u8; 32];
+                Word::from(slot).to_big_endian(&mut key);
+                (key, rlp::encode(&Word::from(slot * 1000)).to_vec())
+            })
+            .collect();
+        for (key, value) in &entries {
+            let (root, proof) = secure_trie_proof(&entries, key);
+            assert_eq!(
+                verify_storage_proof(root, Word::from_big_endian(key), &proof),
+                Ok(Word::from_big_endian(key) * 1000)
+            );
+            assert_eq!(
+                verify_proof(root, &keccak256(key), &proof),
+                Ok(Some(value.clone()))
+            );
+        }
+        let (root, proof) = secure_trie_proof(&entries, &[0xff; 32]);
+        assert_eq!(verify_proof(root, &keccak256([0xff; 32]), &proof), Ok(None));
+        assert_eq!(
+            secure_trie_proof::<[u8; 32], Vec<u8>>(&[], &[0xff; 32]),
+            (EMPTY_TRIE_ROOT, Vec::new())
+        );
+    }
+
+    #[test]
+    fn ordered_trie_root_proofs() {
+        assert_eq!(ordered_trie_root::<Vec<u8>>(&[]), EMPTY_TRIE_ROOT);
+
+        // a single leaf at the key rlp(0) = 0x80
+        let value = vec![0xaa; 40];
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&vec![0x20, 0x80]).append(&value);
+        let leaf = Bytes::from(stream.out().to_vec());
+        assert_eq!(ordered_trie_root(&[&value]), H256(keccak256(&leaf)));
+        assert_eq!(
+            verify_proof(H256(keccak256(&leaf)), &[0x80], &[leaf]),
+            Ok(Some(value.clone()))
+        );
+
+        // keys 0x80 and 0x01 branch on their first nibble
+        let values = [vec![0xaa; 40], vec![0xbb; 40]];
+        let leaf = |nibble: u8, value: &Vec<u8>| {
+            let mut stream = RlpStream::new_list(2);
+            stream.append(&vec![0x30 | nibble]).append(value);
+            Bytes::from(stream.out().to_vec())
+        };
+        let leaves = [leaf(0, &values[0]), leaf(1, &values[1])];
+        let mut stream = RlpStream::new_list(17);
+        for nibble in 0..16 {
+            match nibble {
+                0 => stream.append(&keccak256(&leaves[1]).to_vec()),
+                8 => stream.append(&keccak256(&leaves[0]).to_vec()),
+                _ => stream.append_empty_data(),
+            };
+        }
+        stream.append_empty_data();
+        let branch = Bytes::from(stream.out().to_vec());
+        let root = ordered_trie_root(&values);
+        assert_eq!(root, H256(keccak256(&branch)));
+        assert_eq!(
+            verify_proof(root, &[0x01], &[branch.clone(), leaves[1].clone()]),
+            Ok(Some(values[1].clone()))
+        );
+        assert_eq!(
+            verify_proof(root, &[0x80], &[branch, leaves[0].clone()]),
+            Ok(Some(values[0].clone()))
+        );
+    }
+}
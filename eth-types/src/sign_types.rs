@@ -25,6 +25,7 @@ use halo2curves::{
     Coordinates, CurveAffine,
 };
 use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
 use sha3::digest::generic_array::GenericArray;
 use std::sync::LazyLock;
 use subtle::CtOption;
@@ -51,7 +52,7 @@ pub fn sign(randomness: Fq, sk: Fq, msg_hash: Fq) -> (Fq, Fq, u8) {
 
 /// Signature data required by the SignVerify Chip as input to verify a
 /// signature.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SignData {
     /// Secp256k1 signature point (r, s, v)
     /// v must be 0 or 1
@@ -108,6 +109,12 @@ impl SignData {
         let pk_hash = keccak256(pk_bytes_swap_endianness(&pk_bytes_le(&self.pk)));
         Address::from_slice(&pk_hash[12..])
     }
+
+    /// Whether the `s` of the signature is in the lower half of the curve
+    /// order
+    pub fn is_low_s(&self) -> bool {
+        BigUint::from_bytes_le(&self.signature.1.to_repr()) <= *SECP256K1_HALF_Q
+    }
 }
 
 static SIGN_DATA_DEFAULT: LazyLock<SignData> = LazyLock::new(|| {
@@ -198,6 +205,17 @@ pub fn recover_pk2(
 pub static SECP256K1_Q: LazyLock<BigUint> =
     LazyLock::new(|| BigUint::from_bytes_le(&(Fq::zero() - Fq::one()).to_repr()) + 1u64);
 
+/// Half of [`SECP256K1_Q`], the largest `s` a tx signature may have (EIP-2).
+pub static SECP256K1_HALF_Q: LazyLock<BigUint> = LazyLock::new(|| &*SECP256K1_Q >> 1);
+
+/// Whether `s` is in the lower half of the curve order. EIP-2 rejects tx
+/// signatures with a high `s`, as `(r, n - s)` is a second valid signature.
+pub fn is_low_s(s: &Word) -> bool {
+    let mut s_be = [0u8; 32];
+    s.to_big_endian(&mut s_be);
+    BigUint::from_bytes_be(&s_be) <= *SECP256K1_HALF_Q
+}
+
 /// Helper function to convert a `CtOption` into an `Result`.  Similar to
 /// `Option::ok_or`.
 pub fn ct_option_ok_or<T, E>(v: CtOption<T>, err: E) -> Result<T, E> {
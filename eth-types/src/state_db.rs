@@ -2,11 +2,16 @@
 //! Ethereum State Trie.
 
 use crate::{
-    utils::{hash_code, is_precompiled},
-    Address, Hash, Word, H256, KECCAK_CODE_HASH_EMPTY, U256,
+    geth_types,
+    utils::{hash_code, is_precompiled, HashScheme},
+    Address, Error, Hash, Word, H256, KECCAK_CODE_HASH_EMPTY, U256,
 };
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeSet, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
     sync::LazyLock,
 };
 
@@ -22,13 +27,14 @@ pub static EMPTY_CODE_HASH_LE: LazyLock<[u8; 32]> = LazyLock::new(|| {
 
 const VALUE_ZERO: Word = Word::zero();
 
-/// Memory storage for contract code by code hash.
+/// Memory storage for contract code by code hash, where the code hash is
+/// computed with the [`HashScheme`] of the database.
 #[derive(Debug)]
-pub struct CodeDB(pub HashMap<Hash, Vec<u8>>);
+pub struct CodeDB(pub HashMap<Hash, Vec<u8>>, HashScheme);
 
 impl Clone for CodeDB {
     fn clone(&self) -> Self {
-        CodeDB(self.0.clone())
+        CodeDB(self.0.clone(), self.1)
     }
 }
 
@@ -41,17 +47,25 @@ impl Default for CodeDB {
 impl CodeDB {
     /// Create a new empty Self.
     pub fn new() -> Self {
-        let mut codedb = Self(HashMap::new());
+        Self::new_with_scheme(HashScheme::default())
+    }
+    /// Create a new empty Self hashing code with `scheme`.
+    pub fn new_with_scheme(scheme: HashScheme) -> Self {
+        let mut codedb = Self(HashMap::new(), scheme);
         codedb.insert(Vec::new());
         codedb
     }
+    /// Return the hash scheme of the code hashes.
+    pub fn hash_scheme(&self) -> HashScheme {
+        self.1
+    }
     /// Insert code along with code hash
     pub fn insert_with_hash(&mut self, hash: H256, code: Vec<u8>) {
         self.0.insert(hash, code);
     }
     /// Insert code to CodeDB, and return the code hash.
     pub fn insert(&mut self, code: Vec<u8>) -> Hash {
-        let hash = Self::hash(&code);
+        let hash = self.1.hash_code(&code);
         self.insert_with_hash(hash, code);
         hash
     }
@@ -68,7 +82,7 @@ impl CodeDB {
 
 /// Account of the Ethereum State Trie, which contains an in-memory key-value
 /// database that represents the Account Storage Trie.
-#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
 pub struct Account {
     /// Nonce
     pub nonce: Word,
@@ -123,6 +137,40 @@ impl Account {
     }
 }
 
+/// Change of a [`StateDB`], recorded with the previous value so that it can be
+/// undone by [`StateDB::revert_to_snapshot`].
+#[derive(Debug, Clone)]
+enum JournalEntry {
+    Account(Address, Option<Account>),
+    Storage(Address, Word, Option<Word>),
+    DirtyStorage((Address, Word), Option<Word>),
+    TransientStorage((Address, Word), Option<Word>),
+    TransientStorageCleared(HashMap<(Address, Word), Word>),
+    AccessListAccount(Address, bool),
+    AccessListAccountStorage((Address, Word), bool),
+    Destructed(Address),
+    Touched(Address),
+    Refund(u64),
+    TxCommitted(Box<TxState>),
+}
+
+/// Fields of a [`StateDB`] with transaction lifespan, as cleared by
+/// [`StateDB::commit_tx`].
+#[derive(Debug, Clone)]
+struct TxState {
+    access_list_account: HashSet<Address>,
+    access_list_account_storage: HashSet<(Address, U256)>,
+    dirty_storage: HashMap<(Address, Word), Word>,
+    destructed_account: HashSet<Address>,
+    touched_account: HashSet<Address>,
+    refund: u64,
+}
+
+/// Identifier of a snapshot of a [`StateDB`], taken by
+/// [`StateDB::snapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot(usize);
+
 /// In-memory key-value database that represents the Ethereum State Trie.
 #[derive(Debug, Clone, Default)]
 pub struct StateDB {
@@ -147,6 +195,10 @@ pub struct StateDB {
     // TODO: a better name?
     touched_account: HashSet<Address>,
     refund: u64,
+    // Changes since the oldest open snapshot, only recorded while a snapshot is
+    // open, and the journal length at each open snapshot.
+    journal: Vec<JournalEntry>,
+    snapshots: Vec<usize>,
 }
 
 impl StateDB {
@@ -155,8 +207,109 @@ impl StateDB {
         Self::default()
     }
 
+    /// Take a snapshot of the state, which can be restored with
+    /// [`StateDB::revert_to_snapshot`]. Only the changes made while a snapshot
+    /// is open are recorded, so taking one doesn't copy the state. Snapshots
+    /// can be nested, and must be reverted or discarded in reverse order.
+    pub fn snapshot(&mut self) -> Snapshot {
+        self.snapshots.push(self.journal.len());
+        Snapshot(self.snapshots.len() - 1)
+    }
+
+    /// Restore the state at `snapshot`, undoing all the changes made since,
+    /// and close it together with the snapshots taken after it.
+    pub fn revert_to_snapshot(&mut self, snapshot: Snapshot) {
+        let len = self.snapshots[snapshot.0];
+        self.snapshots.truncate(snapshot.0);
+        while self.journal.len() > len {
+            let entry = self.journal.pop().expect("journal not empty");
+            self.undo(entry);
+        }
+    }
+
+    /// Close `snapshot` together with the snapshots taken after it, keeping
+    /// the changes made since.
+    pub fn discard_snapshot(&mut self, snapshot: Snapshot) {
+        self.snapshots.truncate(snapshot.0);
+        if self.snapshots.is_empty() {
+            self.journal.clear();
+        }
+    }
+
+    fn journal(&mut self, entry: impl FnOnce(&Self) -> JournalEntry) {
+        if !self.snapshots.is_empty() {
+            let entry = entry(self);
+            self.journal.push(entry);
+        }
+    }
+
+    fn journal_account(&mut self, addr: &Address) {
+        self.journal(|sdb| JournalEntry::Account(*addr, sdb.state.get(addr).cloned()));
+    }
+
+    fn undo(&mut self, entry: JournalEntry) {
+        fn restore<K: std::hash::Hash + Eq, V>(map: &mut HashMap<K, V>, key: K, prev: Option<V>) {
+            match prev {
+                Some(value) => map.insert(key, value),
+                None => map.remove(&key),
+            };
+        }
+        fn restore_set<K: std::hash::Hash + Eq>(set: &mut HashSet<K>, key: K, present: bool) {
+            if present {
+                set.insert(key);
+            } else {
+                set.remove(&key);
+            }
+        }
+        match entry {
+            JournalEntry::Account(addr, prev) => restore(&mut self.state, addr, prev),
+            JournalEntry::Storage(addr, key, prev) => {
+                if let Some(acc) = self.state.get_mut(&addr) {
+                    restore(&mut acc.storage, key, prev);
+                }
+            }
+            JournalEntry::DirtyStorage(key, prev) => restore(&mut self.dirty_storage, key, prev),
+            JournalEntry::TransientStorage(key, prev) => {
+                restore(&mut self.transient_storage, key, prev)
+            }
+            JournalEntry::TransientStorageCleared(transient_storage) => {
+                self.transient_storage = transient_storage
+            }
+            JournalEntry::AccessListAccount(addr, present) => {
+                restore_set(&mut self.access_list_account, addr, present)
+            }
+            JournalEntry::AccessListAccountStorage(pair, present) => {
+                restore_set(&mut self.access_list_account_storage, pair, present)
+            }
+            JournalEntry::Destructed(addr) => {
+                self.destructed_account.remove(&addr);
+            }
+            JournalEntry::Touched(addr) => {
+                self.touched_account.remove(&addr);
+            }
+            JournalEntry::Refund(refund) => self.refund = refund,
+            JournalEntry::TxCommitted(tx_state) => {
+                let TxState {
+                    access_list_account,
+                    access_list_account_storage,
+                    dirty_storage,
+                    destructed_account,
+                    touched_account,
+                    refund,
+                } = *tx_state;
+                self.access_list_account = access_list_account;
+                self.access_list_account_storage = access_list_account_storage;
+                self.dirty_storage = dirty_storage;
+                self.destructed_account = destructed_account;
+                self.touched_account = touched_account;
+                self.refund = refund;
+            }
+        }
+    }
+
     /// Set an [`Account`] at `addr` in the StateDB.
     pub fn set_account(&mut self, addr: &Address, acc: Account) {
+        self.journal_account(addr);
         self.state.insert(*addr, acc);
     }
 
@@ -189,13 +342,18 @@ impl StateDB {
     /// empty_code_hash}
     // has already been applied. So further Account Write Rw is allowed.
     pub fn set_touched(&mut self, addr: &Address) -> bool {
-        self.touched_account.insert(*addr)
+        let inserted = self.touched_account.insert(*addr);
+        if inserted {
+            self.journal(|_| JournalEntry::Touched(*addr));
+        }
+        inserted
     }
 
     /// Get a mutable reference to the [`Account`] at `addr`.  If the
     /// [`Account`] is not found in the state, a zero one will be inserted
     /// and returned along with false.
     pub fn get_account_mut(&mut self, addr: &Address) -> (bool, &mut Account) {
+        self.journal_account(addr);
         let found = if self.state.contains_key(addr) {
             true
         } else {
@@ -247,7 +405,15 @@ impl StateDB {
     /// be inserted at `key` in its storage, and the value will be returned
     /// along with false.
     pub fn get_storage_mut(&mut self, addr: &Address, key: &Word) -> (bool, &mut Word) {
-        let (_, acc) = self.get_account_mut(addr);
+        // only the slot can be changed, so journal the account only if created
+        if !self.state.contains_key(addr) {
+            self.journal_account(addr);
+            self.state.insert(*addr, Account::zero());
+        }
+        self.journal(|sdb| {
+            JournalEntry::Storage(*addr, *key, sdb.state[addr].storage.get(key).copied())
+        });
+        let acc = self.state.get_mut(addr).expect("addr not inserted");
         let found = if acc.storage.contains_key(key) {
             true
         } else {
@@ -262,7 +428,8 @@ impl StateDB {
     /// After transaction execution, `dirty_storage` is committed into `storage`
     /// in `commit_tx` method.
     pub fn set_storage(&mut self, addr: &Address, key: &Word, value: &Word) {
-        self.dirty_storage.insert((*addr, *key), *value);
+        let prev = self.dirty_storage.insert((*addr, *key), *value);
+        self.journal(|_| JournalEntry::DirtyStorage((*addr, *key), prev));
     }
 
     /// Get balance of account with the given address.
@@ -274,7 +441,8 @@ impl StateDB {
     /// Set transient storage value at `addr` and `key`.
     /// Transient storage is cleared after transaction execution.
     pub fn set_transient_storage(&mut self, addr: &Address, key: &Word, value: &Word) {
-        self.transient_storage.insert((*addr, *key), *value);
+        let prev = self.transient_storage.insert((*addr, *key), *value);
+        self.journal(|_| JournalEntry::TransientStorage((*addr, *key), prev));
     }
 
     /// Get nonce of account with `addr`.
@@ -302,13 +470,20 @@ impl StateDB {
     /// Add `addr` into account access list. Returns `true` if it's not in the
     /// access list before.
     pub fn add_account_to_access_list(&mut self, addr: Address) -> bool {
-        self.access_list_account.insert(addr)
+        let inserted = self.access_list_account.insert(addr);
+        if inserted {
+            self.journal(|_| JournalEntry::AccessListAccount(addr, false));
+        }
+        inserted
     }
 
     /// Remove `addr` from account access list.
     pub fn remove_account_from_access_list(&mut self, addr: &Address) {
         let exist = self.access_list_account.remove(addr);
         debug_assert!(exist);
+        if exist {
+            self.journal(|_| JournalEntry::AccessListAccount(*addr, true));
+        }
     }
 
     /// Check whether `(addr, key)` exists in account storage access list.
@@ -319,19 +494,30 @@ impl StateDB {
     /// Add `(addr, key)` into account storage access list. Returns `true` if
     /// it's not in the access list before.
     pub fn add_account_storage_to_access_list(&mut self, (addr, key): (Address, Word)) -> bool {
-        self.access_list_account_storage.insert((addr, key))
+        let inserted = self.access_list_account_storage.insert((addr, key));
+        if inserted {
+            self.journal(|_| JournalEntry::AccessListAccountStorage((addr, key), false));
+        }
+        inserted
     }
 
     /// Remove `(addr, key)` from account storage access list.
     pub fn remove_account_storage_from_access_list(&mut self, pair: &(Address, Word)) {
         let exist = self.access_list_account_storage.remove(pair);
         debug_assert!(exist);
+        if exist {
+            self.journal(|_| JournalEntry::AccessListAccountStorage(*pair, true));
+        }
     }
 
-    /// Set account as self destructed.
+    /// Set account as self destructed.  The account is kept zeroed until the
+    /// end of the current tx, so that it can be re-created at the same address
+    /// in a later tx of the block.
     pub fn destruct_account(&mut self, addr: Address) {
-        self.state.insert(addr, Account::zero());
-        self.destructed_account.insert(addr);
+        self.set_account(&addr, Account::zero());
+        if self.destructed_account.insert(addr) {
+            self.journal(|_| JournalEntry::Destructed(addr));
+        }
     }
 
     /// Retrieve refund.
@@ -341,6 +527,7 @@ impl StateDB {
 
     /// Set refund
     pub fn set_refund(&mut self, value: u64) {
+        self.journal(|sdb| JournalEntry::Refund(sdb.refund));
         self.refund = value;
     }
 
@@ -348,6 +535,16 @@ impl StateDB {
     /// It should be invoked before processing
     /// with new transaction with the same [`StateDB`].
     pub fn commit_tx(&mut self) {
+        self.journal(|sdb| {
+            JournalEntry::TxCommitted(Box::new(TxState {
+                access_list_account: sdb.access_list_account.clone(),
+                access_list_account_storage: sdb.access_list_account_storage.clone(),
+                dirty_storage: sdb.dirty_storage.clone(),
+                destructed_account: sdb.destructed_account.clone(),
+                touched_account: sdb.touched_account.clone(),
+                refund: sdb.refund,
+            }))
+        });
         self.access_list_account = HashSet::new();
         self.access_list_account_storage = HashSet::new();
         for ((addr, key), value) in self.dirty_storage.clone() {
@@ -356,7 +553,7 @@ impl StateDB {
         }
         self.dirty_storage = HashMap::new();
         self.touched_account = HashSet::new();
-        for addr in self.destructed_account.clone() {
+        for addr in std::mem::take(&mut self.destructed_account) {
             let (_, account) = self.get_account_mut(&addr);
             *account = ACCOUNT_ZERO.clone();
         }
@@ -365,7 +562,94 @@ impl StateDB {
 
     /// Clear transient storage.
     pub fn clear_transient_storage(&mut self) {
-        self.transient_storage = HashMap::new();
+        let transient_storage = std::mem::take(&mut self.transient_storage);
+        self.journal(|_| JournalEntry::TransientStorageCleared(transient_storage));
+    }
+
+    /// Snapshot the committed state together with the codes in `code_db`, so
+    /// that it can be preloaded as the pre-state of a later witness build.
+    /// Writes of a transaction that has not been committed yet are included,
+    /// as if [`StateDB::commit_tx`] had been invoked.
+    pub fn warm_state(&self, code_db: &CodeDB) -> WarmState {
+        let mut sdb = self.clone();
+        sdb.commit_tx();
+        WarmState {
+            version: WarmState::VERSION,
+            accounts: sdb.state.into_iter().collect(),
+            codes: code_db.0.clone().into_iter().collect(),
+        }
+    }
+}
+
+/// Snapshot of a [`StateDB`] and its [`CodeDB`] after a block, used to chain
+/// witness builds (e.g. in benchmarks or soak runs over synthetic blocks)
+/// without reconstructing the full state for every block.
+///
+/// It is stored as a compact bincode file with [`WarmState::save`] and read
+/// back with [`WarmState::load`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WarmState {
+    /// Format version of the snapshot.
+    pub version: u32,
+    /// Committed accounts, ordered by address.
+    pub accounts: BTreeMap<Address, Account>,
+    /// Contract codes by code hash.
+    pub codes: BTreeMap<Hash, Vec<u8>>,
+}
+
+impl WarmState {
+    /// Current format version of the warm state file.
+    pub const VERSION: u32 = 1;
+
+    /// Write the snapshot to the file at `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let writer = BufWriter::new(File::create(path).map_err(Error::IoError)?);
+        bincode::serialize_into(writer, self).map_err(Error::BincodeError)
+    }
+
+    /// Read a snapshot from the file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let reader = BufReader::new(File::open(path).map_err(Error::IoError)?);
+        let warm_state: Self = bincode::deserialize_from(reader).map_err(Error::BincodeError)?;
+        if warm_state.version != Self::VERSION {
+            return Err(Error::WarmStateVersion(warm_state.version));
+        }
+        Ok(warm_state)
+    }
+
+    /// Non empty accounts of the snapshot with their code, to trace a
+    /// following block on top of this state.
+    pub fn geth_accounts(&self) -> Vec<geth_types::Account> {
+        self.accounts
+            .iter()
+            .map(|(address, account)| geth_types::Account {
+                address: *address,
+                nonce: account.nonce,
+                balance: account.balance,
+                code: self
+                    .codes
+                    .get(&account.code_hash)
+                    .cloned()
+                    .unwrap_or_default()
+                    .into(),
+                storage: account.storage.clone(),
+            })
+            .filter(|account| !account.is_empty())
+            .collect()
+    }
+
+    /// Turn the snapshot into the [`StateDB`] and [`CodeDB`] to start a
+    /// witness build from.
+    pub fn into_dbs(self) -> (StateDB, CodeDB) {
+        let mut sdb = StateDB::new();
+        for (addr, account) in self.accounts {
+            sdb.set_account(&addr, account);
+        }
+        let mut code_db = CodeDB::new();
+        for (hash, code) in self.codes {
+            code_db.insert_with_hash(hash, code);
+        }
+        (sdb, code_db)
     }
 }
 
@@ -428,4 +712,139 @@ mod statedb_tests {
         assert!(found);
         assert_eq!(value, &Word::from(102));
     }
+
+    #[test]
+    fn destructed_account_can_be_recreated() {
+        let addr = address!("0x0000000000000000000000000000000000000001");
+        let mut statedb = StateDB::new();
+
+        let (_, acc) = statedb.get_account_mut(&addr);
+        acc.nonce = Word::from(1);
+        statedb.set_storage(&addr, &Word::from(1), &Word::from(101));
+        statedb.set_storage(&addr, &Word::from(2), &Word::zero());
+        statedb.commit_tx();
+        statedb.set_storage(&addr, &Word::from(3), &Word::from(103));
+        assert_eq!(
+            statedb.get_storage(&addr, &Word::from(1)).1,
+            &Word::from(101)
+        );
+        assert_eq!(
+            statedb.get_storage(&addr, &Word::from(3)).1,
+            &Word::from(103)
+        );
+
+        statedb.destruct_account(addr);
+        statedb.commit_tx();
+        assert_eq!(statedb.get_account(&addr).1, &Account::zero());
+
+        // re-create the account in a later tx
+        let (_, acc) = statedb.get_account_mut(&addr);
+        acc.nonce = Word::from(1);
+        statedb.commit_tx();
+        assert_eq!(statedb.get_account(&addr).1.nonce, Word::from(1));
+        for key in [1, 3] {
+            assert!(statedb.get_storage(&addr, &Word::from(key)).1.is_zero());
+        }
+    }
+
+    #[test]
+    fn revert_to_snapshot() {
+        let addr_a = address!("0x0000000000000000000000000000000000000001");
+        let addr_b = address!("0x0000000000000000000000000000000000000002");
+        let mut statedb = StateDB::new();
+
+        let (_, acc) = statedb.get_account_mut(&addr_a);
+        acc.nonce = Word::from(1);
+        statedb.set_storage(&addr_a, &Word::from(1), &Word::from(101));
+        statedb.add_account_to_access_list(addr_a);
+
+        let snapshot = statedb.snapshot();
+        statedb.increase_nonce(&addr_a);
+        statedb.set_storage(&addr_a, &Word::from(1), &Word::from(102));
+        statedb.add_account_to_access_list(addr_b);
+        statedb.set_refund(100);
+
+        // nested snapshot across the end of the tx
+        let nested = statedb.snapshot();
+        statedb.commit_tx();
+        statedb.destruct_account(addr_a);
+        let (_, value) = statedb.get_storage_mut(&addr_b, &Word::from(2));
+        *value = Word::from(103);
+        statedb.revert_to_snapshot(nested);
+        assert_eq!(statedb.get_nonce(&addr_a), 2);
+        assert_eq!(
+            statedb.get_storage(&addr_a, &Word::from(1)).1,
+            &Word::from(102)
+        );
+        assert_eq!(
+            statedb.get_committed_storage(&addr_a, &Word::from(1)),
+            (false, &Word::zero())
+        );
+        assert!(statedb.check_account_in_access_list(&addr_b));
+        assert_eq!(statedb.refund(), 100);
+        assert!(!statedb.get_account(&addr_b).0);
+
+        statedb.revert_to_snapshot(snapshot);
+        assert_eq!(statedb.get_nonce(&addr_a), 1);
+        assert_eq!(
+            statedb.get_storage(&addr_a, &Word::from(1)).1,
+            &Word::from(101)
+        );
+        assert!(statedb.check_account_in_access_list(&addr_a));
+        assert!(!statedb.check_account_in_access_list(&addr_b));
+        assert_eq!(statedb.refund(), 0);
+
+        // changes are kept once the snapshot is discarded
+        let snapshot = statedb.snapshot();
+        statedb.increase_nonce(&addr_a);
+        statedb.discard_snapshot(snapshot);
+        assert_eq!(statedb.get_nonce(&addr_a), 2);
+    }
+
+    #[test]
+    fn codedb_hash_scheme() {
+        let code = vec![0x60, 0x00];
+        for scheme in [HashScheme::Keccak, HashScheme::Poseidon] {
+            let mut codedb = CodeDB::new_with_scheme(scheme);
+            assert_eq!(codedb.hash_scheme(), scheme);
+            assert_eq!(codedb.insert(code.clone()), scheme.hash_code(&code));
+            assert!(codedb.0.contains_key(&scheme.empty_code_hash()));
+        }
+    }
+
+    #[test]
+    fn warm_state_roundtrip() {
+        let addr_a = address!("0x0000000000000000000000000000000000000001");
+        let addr_b = address!("0x0000000000000000000000000000000000000002");
+        let mut statedb = StateDB::new();
+        let mut code_db = CodeDB::new();
+
+        let code_hash = code_db.insert(vec![0x60, 0x00, 0x00]);
+        let (_, acc) = statedb.get_account_mut(&addr_a);
+        acc.nonce = Word::from(1);
+        acc.code_hash = code_hash;
+        acc.code_size = Word::from(3);
+        statedb.set_storage(&addr_a, &Word::from(2), &Word::from(101));
+        statedb.commit_tx();
+        // not committed yet
+        statedb.set_storage(&addr_b, &Word::from(3), &Word::from(102));
+
+        let path = std::env::temp_dir().join("eth_types_warm_state_roundtrip.bin");
+        statedb.warm_state(&code_db).save(&path).unwrap();
+        let (statedb, code_db) = WarmState::load(&path).unwrap().into_dbs();
+        std::fs::remove_file(&path).unwrap();
+
+        let (found, acc) = statedb.get_account(&addr_a);
+        assert!(found);
+        assert_eq!(acc.nonce, Word::from(1));
+        assert_eq!(code_db.0.get(&acc.code_hash), Some(&vec![0x60, 0x00, 0x00]));
+        assert_eq!(
+            statedb.get_committed_storage(&addr_a, &Word::from(2)),
+            (true, &Word::from(101))
+        );
+        assert_eq!(
+            statedb.get_committed_storage(&addr_b, &Word::from(3)),
+            (true, &Word::from(102))
+        );
+    }
 }
@@ -0,0 +1,308 @@
+//! Compact binary file of a [`GethExecTrace`], whose struct logs are written
+//! one by one and read lazily, so that traces larger than memory can be
+//! handled step by step.
+//!
+//! The file holds the steps, encoded with bincode, followed by the rest of
+//! the trace in JSON and its length as a little endian `u64`.
+
+use crate::{
+    evm_types::{Gas, GasCost, OpcodeId, ProgramCounter},
+    Error, GethExecError, GethExecStep, GethExecTrace,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Seek, SeekFrom, Take, Write},
+    path::Path,
+    str::FromStr,
+};
+
+/// Encoding of a [`GethExecStep`] in a trace file.
+#[derive(Serialize, Deserialize)]
+struct StepRecord {
+    pc: u64,
+    op: u8,
+    gas: u64,
+    gas_cost: u64,
+    refund: u64,
+    depth: u16,
+    error: Option<String>,
+    #[cfg(feature = "enable-stack")]
+    stack: Vec<[u64; 4]>,
+    #[cfg(feature = "enable-memory")]
+    memory: Vec<u8>,
+    #[cfg(feature = "enable-storage")]
+    storage: Vec<([u64; 4], [u64; 4])>,
+}
+
+impl From<&GethExecStep> for StepRecord {
+    fn from(step: &GethExecStep) -> Self {
+        Self {
+            pc: step.pc.0 as u64,
+            op: step.op.as_u8(),
+            gas: step.gas.0,
+            gas_cost: step.gas_cost.0,
+            refund: step.refund.0,
+            depth: step.depth,
+            // as geth reports it, so that it can be parsed back
+            error: step.error.map(|error| match error {
+                GethExecError::InvalidOpcode(OpcodeId::INVALID(byte)) => {
+                    format!("invalid opcode: opcode {byte:#x} not defined")
+                }
+                error => error.to_string(),
+            }),
+            #[cfg(feature = "enable-stack")]
+            stack: step.stack.0.iter().map(|word| word.0).collect(),
+            #[cfg(feature = "enable-memory")]
+            memory: step.memory.0.clone(),
+            #[cfg(feature = "enable-storage")]
+            storage: step
+                .storage
+                .0
+                .iter()
+                .map(|(key, value)| (key.0, value.0))
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<StepRecord> for GethExecStep {
+    type Error = Error;
+
+    fn try_from(record: StepRecord) -> Result<Self, Error> {
+        let error = match record.error {
+            Some(error) => Some(
+                GethExecError::from_str(&error)
+                    .map_err(|_| Error::TracingError(format!("unknown step error {error}")))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            pc: ProgramCounter(record.pc as usize),
+            op: OpcodeId::from(record.op),
+            gas: Gas(record.gas),
+            gas_cost: GasCost(record.gas_cost),
+            refund: Gas(record.refund),
+            depth: record.depth,
+            error,
+            #[cfg(feature = "enable-stack")]
+            stack: crate::evm_types::Stack(record.stack.into_iter().map(crate::U256).collect()),
+            #[cfg(feature = "enable-memory")]
+            memory: crate::evm_types::Memory(record.memory),
+            #[cfg(feature = "enable-storage")]
+            storage: crate::evm_types::Storage(
+                record
+                    .storage
+                    .into_iter()
+                    .map(|(key, value)| (crate::U256(key), crate::U256(value)))
+                    .collect(),
+            ),
+        })
+    }
+}
+
+/// Encoding of a [`GethExecTrace`] in other files, e.g. the JSON files of
+/// [`GethData`](crate::geth_types::GethData), as its struct logs don't
+/// serialize the way geth reports them.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct TraceRecord {
+    header: GethExecTrace,
+    struct_logs: Vec<StepRecord>,
+}
+
+impl From<&GethExecTrace> for TraceRecord {
+    fn from(trace: &GethExecTrace) -> Self {
+        Self {
+            header: GethExecTrace {
+                struct_logs: Vec::new(),
+                ..trace.clone()
+            },
+            struct_logs: trace.struct_logs.iter().map(StepRecord::from).collect(),
+        }
+    }
+}
+
+impl TryFrom<TraceRecord> for GethExecTrace {
+    type Error = Error;
+
+    fn try_from(record: TraceRecord) -> Result<Self, Error> {
+        Ok(Self {
+            struct_logs: record
+                .struct_logs
+                .into_iter()
+                .map(GethExecStep::try_from)
+                .collect::<Result<_, _>>()?,
+            ..record.header
+        })
+    }
+}
+
+/// Writer of a trace file.
+#[derive(Debug)]
+pub struct GethExecTraceWriter {
+    file: BufWriter<File>,
+}
+
+impl GethExecTraceWriter {
+    /// Create the trace file at `path`.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(Self {
+            file: BufWriter::new(File::create(path).map_err(Error::IoError)?),
+        })
+    }
+
+    /// Append `step` to the struct logs of the trace.
+    pub fn write_step(&mut self, step: &GethExecStep) -> Result<(), Error> {
+        bincode::serialize_into(&mut self.file, &StepRecord::from(step))
+            .map_err(Error::BincodeError)
+    }
+
+    /// Write the rest of the trace, `header`, whose struct logs are ignored,
+    /// and close the file.
+    pub fn finish(mut self, header: &GethExecTrace) -> Result<(), Error> {
+        let header = GethExecTrace {
+            struct_logs: Vec::new(),
+            ..header.clone()
+        };
+        let header = serde_json::to_vec(&header).map_err(Error::SerdeError)?;
+        self.file.write_all(&header).map_err(Error::IoError)?;
+        self.file
+            .write_all(&(header.len() as u64).to_le_bytes())
+            .map_err(Error::IoError)?;
+        self.file.flush().map_err(Error::IoError)
+    }
+}
+
+/// Reader of a trace file, iterating over its struct logs.
+#[derive(Debug)]
+pub struct GethExecTraceReader {
+    header: GethExecTrace,
+    steps: Take<BufReader<File>>,
+}
+
+impl GethExecTraceReader {
+    /// Open the trace file at `path` and read its header.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let mut file = File::open(path).map_err(Error::IoError)?;
+        let end = file.seek(SeekFrom::End(-8)).map_err(Error::IoError)?;
+        let mut len = [0u8; 8];
+        file.read_exact(&mut len).map_err(Error::IoError)?;
+        let len = u64::from_le_bytes(len);
+        let steps_len = end.checked_sub(len).ok_or_else(|| {
+            Error::TracingError("trace file header longer than the file".to_string())
+        })?;
+        file.seek(SeekFrom::Start(steps_len))
+            .map_err(Error::IoError)?;
+        let mut header = Vec::with_capacity(len as usize);
+        (&mut file)
+            .take(len)
+            .read_to_end(&mut header)
+            .map_err(Error::IoError)?;
+        let header = serde_json::from_slice(&header).map_err(Error::SerdeError)?;
+        file.seek(SeekFrom::Start(0)).map_err(Error::IoError)?;
+        Ok(Self {
+            header,
+            steps: BufReader::new(file).take(steps_len),
+        })
+    }
+
+    /// Return the trace without its struct logs, which are read by iterating
+    /// over the reader.
+    pub fn header(&self) -> &GethExecTrace {
+        &self.header
+    }
+}
+
+impl Iterator for GethExecTraceReader {
+    type Item = Result<GethExecStep, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.steps.limit() == 0 {
+            return None;
+        }
+        let record: StepRecord = match bincode::deserialize_from(&mut self.steps) {
+            Ok(record) => record,
+            Err(err) => return Some(Err(Error::BincodeError(err))),
+        };
+        Some(GethExecStep::try_from(record))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geth_types::{Account, GethData},
+        Address, Bytes, Word,
+    };
+    use std::collections::HashMap;
+
+    fn trace() -> GethExecTrace {
+        serde_json::from_str(
+            r#"{
+                "gas": 26809,
+                "failed": false,
+                "returnValue": "",
+                "structLogs": [
+                    {"pc": 0, "op": "PUSH1", "gas": 22705, "gasCost": 3, "depth": 1},
+                    {"pc": 2, "op": "opcode 0xfe not defined", "gas": 22702, "gasCost": 0,
+                     "depth": 1, "error": "invalid opcode: opcode 0xfe not defined"}
+                ],
+                "prestate": {},
+                "callTrace": {
+                    "calls": [],
+                    "error": null,
+                    "from": "0x000000000000000000000000000000000cafe111",
+                    "gasUsed": "0x0",
+                    "output": null,
+                    "to": "0x000000000000000000000000000000000cafe222",
+                    "type": "CALL"
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn trace_file_roundtrip() {
+        let trace = trace();
+        let path = std::env::temp_dir().join("eth_types_trace_file_roundtrip.bin");
+        let mut writer = GethExecTraceWriter::create(&path).unwrap();
+        for step in &trace.struct_logs {
+            writer.write_step(step).unwrap();
+        }
+        writer.finish(&trace).unwrap();
+
+        let reader = GethExecTraceReader::open(&path).unwrap();
+        assert_eq!(reader.header().gas, trace.gas);
+        assert!(reader.header().struct_logs.is_empty());
+        let steps = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(steps, trace.struct_logs);
+    }
+
+    #[test]
+    fn geth_data_json_roundtrip() {
+        let data = GethData {
+            chain_id: 1338,
+            geth_traces: vec![trace()],
+            accounts: vec![Account {
+                address: Address::repeat_byte(0xfe),
+                balance: Word::from(100),
+                code: Bytes::from(vec![0x60, 0x01]),
+                storage: HashMap::from([(Word::from(1), Word::from(2))]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let path = std::env::temp_dir().join("eth_types_geth_data_json_roundtrip.json");
+        data.to_json_file(&path).unwrap();
+        let read = GethData::from_json_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read.chain_id, data.chain_id);
+        assert_eq!(read.eth_block, data.eth_block);
+        assert_eq!(read.geth_traces, data.geth_traces);
+        assert_eq!(read.accounts, data.accounts);
+    }
+}
@@ -0,0 +1,210 @@
+//! Decoding of the EIP-2718 envelopes of txs, the inverse of
+//! [`TxTypeConfig::rlp_signed`]: a legacy tx is a bare RLP list, while a typed
+//! tx is its type byte followed by the RLP list of its fields:
+//! - `0x01 || rlp([chain_id, nonce, gas_price, gas_limit, to, value, data,
+//!   access_list, y_parity, r, s])` for EIP-2930 txs,
+//! - `0x02 || rlp([chain_id, nonce, max_priority_fee_per_gas,
+//!   max_fee_per_gas, gas_limit, to, value, data, access_list, y_parity, r,
+//!   s])` for EIP-1559 txs,
+//! - `0x03 || rlp([..., access_list, max_fee_per_blob_gas,
+//!   blob_versioned_hashes, y_parity, r, s])` for EIP-4844 txs,
+//! - `system_tx_type_id || rlp([nonce, gas, to, value, data, from])` for
+//!   system txs.
+
+use crate::{
+    geth_types::{Transaction as GethTransaction, TxType, TxTypeConfig},
+    AccessList, Address, Error, Hash, Word, U64,
+};
+use ethers_core::utils::{
+    keccak256,
+    rlp::{Decodable, DecoderError, Rlp},
+};
+
+impl TxTypeConfig {
+    /// Decode the signed envelope `bytes` of a tx, recovering its sender
+    /// from its signature unless it is a system tx.
+    pub fn decode_signed(&self, bytes: &[u8]) -> Result<crate::Transaction, Error> {
+        let (&first, payload) = bytes
+            .split_first()
+            .ok_or_else(|| Error::TxEnvelope("empty tx envelope".to_string()))?;
+        let support = if first >= 0xc0 {
+            self.support_of(0)
+        } else {
+            self.support_of(first as u64)
+        };
+        if !support.supported {
+            return Err(Error::TxEnvelope(format!(
+                "unsupported tx type {:#x} ({})",
+                support.type_id, support.name
+            )));
+        }
+
+        let mut tx = if first >= 0xc0 {
+            decode_legacy(&Rlp::new(bytes))
+        } else if first as u64 == self.system_tx_type_id {
+            decode_system(&Rlp::new(payload), first)
+        } else {
+            decode_typed(&Rlp::new(payload), first)
+        }
+        .map_err(|err| Error::TxEnvelope(err.to_string()))?;
+        tx.hash = Hash::from(keccak256(bytes));
+        if self.tx_type(&tx) != TxType::L1Msg {
+            tx.from = GethTransaction::from(&tx).sign_data()?.get_addr();
+        }
+        Ok(tx)
+    }
+}
+
+/// Decode the signed envelope `bytes` of a tx under the default
+/// [`TxTypeConfig`].
+pub fn decode_signed(bytes: &[u8]) -> Result<crate::Transaction, Error> {
+    TxTypeConfig::default().decode_signed(bytes)
+}
+
+fn decode_legacy(rlp: &Rlp) -> Result<crate::Transaction, DecoderError> {
+    expect_fields(rlp, 9)?;
+    let v: U64 = rlp.val_at(6)?;
+    Ok(crate::Transaction {
+        nonce: rlp.val_at(0)?,
+        gas_price: Some(rlp.val_at(1)?),
+        gas: rlp.val_at(2)?,
+        to: decode_to(&rlp.at(3)?)?,
+        value: rlp.val_at(4)?,
+        input: rlp.val_at::<Vec<u8>>(5)?.into(),
+        chain_id: (v.as_u64() >= 35).then(|| ((v.as_u64() - 35) / 2).into()),
+        v,
+        r: rlp.val_at(7)?,
+        s: rlp.val_at(8)?,
+        ..Default::default()
+    })
+}
+
+fn decode_typed(rlp: &Rlp, type_id: u8) -> Result<crate::Transaction, DecoderError> {
+    let dynamic_fee = type_id != 1;
+    let fields = match type_id {
+        1 => 11,
+        2 => 12,
+        3 => 14,
+        _ => return Err(DecoderError::Custom("unknown tx type")),
+    };
+    expect_fields(rlp, fields)?;
+
+    let mut tx = crate::Transaction {
+        transaction_type: Some(type_id.into()),
+        chain_id: Some(rlp.val_at(0)?),
+        nonce: rlp.val_at(1)?,
+        ..Default::default()
+    };
+    // the fields after the fees are shifted by the additional fee of
+    // dynamic fee txs
+    let index = if dynamic_fee {
+        tx.max_priority_fee_per_gas = Some(rlp.val_at(2)?);
+        tx.max_fee_per_gas = Some(rlp.val_at(3)?);
+        4
+    } else {
+        tx.gas_price = Some(rlp.val_at(2)?);
+        3
+    };
+    tx.gas = rlp.val_at(index)?;
+    tx.to = decode_to(&rlp.at(index + 1)?)?;
+    tx.value = rlp.val_at(index + 2)?;
+    tx.input = rlp.val_at::<Vec<u8>>(index + 3)?.into();
+    tx.access_list = Some(AccessList::decode(&rlp.at(index + 4)?)?);
+    if type_id == 3 {
+        let max_fee_per_blob_gas: Word = rlp.val_at(9)?;
+        let blob_versioned_hashes: Vec<Hash> = rlp.list_at(10)?;
+        tx.other.insert(
+            "maxFeePerBlobGas".to_string(),
+            serde_json::to_value(max_fee_per_blob_gas).expect("serialize word"),
+        );
+        tx.other.insert(
+            "blobVersionedHashes".to_string(),
+            serde_json::to_value(blob_versioned_hashes).expect("serialize hashes"),
+        );
+    }
+    tx.v = rlp.val_at(fields - 3)?;
+    tx.r = rlp.val_at(fields - 2)?;
+    tx.s = rlp.val_at(fields - 1)?;
+    Ok(tx)
+}
+
+fn decode_system(rlp: &Rlp, type_id: u8) -> Result<crate::Transaction, DecoderError> {
+    expect_fields(rlp, 6)?;
+    Ok(crate::Transaction {
+        transaction_type: Some(type_id.into()),
+        nonce: rlp.val_at(0)?,
+        gas: rlp.val_at(1)?,
+        to: decode_to(&rlp.at(2)?)?,
+        value: rlp.val_at(3)?,
+        input: rlp.val_at::<Vec<u8>>(4)?.into(),
+        from: rlp.val_at(5)?,
+        ..Default::default()
+    })
+}
+
+/// Decode the recipient of a tx, empty for a contract creation.
+fn decode_to(rlp: &Rlp) -> Result<Option<Address>, DecoderError> {
+    if rlp.is_empty() {
+        Ok(None)
+    } else {
+        rlp.as_val().map(Some)
+    }
+}
+
+fn expect_fields(rlp: &Rlp, fields: usize) -> Result<(), DecoderError> {
+    if rlp.item_count()? != fields {
+        return Err(DecoderError::RlpIncorrectListLen);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{geth_types::get_rlp_signed, AccessListItem};
+    use ethers_core::types::{
+        transaction::eip2718::TypedTransaction, Eip1559TransactionRequest, TransactionRequest,
+    };
+    use ethers_signers::{LocalWallet, Signer};
+
+    fn sign(wallet: &LocalWallet, request: TypedTransaction) -> Vec<u8> {
+        let sig = wallet.sign_transaction_sync(&request).unwrap();
+        request.rlp_signed(&sig).to_vec()
+    }
+
+    #[test]
+    fn tx_envelope_roundtrip() {
+        let wallet = LocalWallet::from_bytes(&[0x11; 32])
+            .unwrap()
+            .with_chain_id(1338u64);
+        let legacy = TransactionRequest::new()
+            .nonce(3)
+            .gas(21_000)
+            .gas_price(7)
+            .to(Address::repeat_byte(0xfe))
+            .value(100)
+            .data(vec![1, 2, 3])
+            .chain_id(1338);
+        let dynamic_fee = Eip1559TransactionRequest::new()
+            .nonce(4)
+            .gas(50_000)
+            .max_fee_per_gas(9)
+            .max_priority_fee_per_gas(2)
+            .access_list(AccessList(vec![AccessListItem {
+                address: Address::repeat_byte(0xfe),
+                storage_keys: vec![Hash::from_low_u64_be(1)],
+            }]))
+            .data(vec![0x60, 0x00])
+            .chain_id(1338);
+
+        for request in [legacy.into(), dynamic_fee.into()] {
+            let bytes = sign(&wallet, request);
+            let tx = decode_signed(&bytes).unwrap();
+            assert_eq!(tx.from, wallet.address());
+            assert_eq!(get_rlp_signed(&tx), bytes);
+        }
+
+        assert!(decode_signed(&[]).is_err());
+        assert!(decode_signed(&[0x05, 0xc0]).is_err());
+    }
+}
@@ -15,12 +15,48 @@ pub fn is_precompiled(address: &Address) -> bool {
 /// Default number of bytes to pack into a field element.
 pub const POSEIDON_HASH_BYTES_IN_FIELD: usize = 31;
 
+/// Hash function used to compute code hashes, selectable at runtime so that a
+/// single binary can produce witnesses for both kinds of code hashes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HashScheme {
+    /// Keccak256 of the code, as in Ethereum.
+    Keccak,
+    /// Poseidon hash of the code packed into field elements, as in Scroll.
+    Poseidon,
+}
+
+impl Default for HashScheme {
+    /// Poseidon with the `scroll` feature, Keccak otherwise.
+    fn default() -> Self {
+        if cfg!(feature = "scroll") {
+            Self::Poseidon
+        } else {
+            Self::Keccak
+        }
+    }
+}
+
+impl HashScheme {
+    /// Compute the code hash of `code` under this scheme.
+    pub fn hash_code(self, code: &[u8]) -> Hash {
+        match self {
+            Self::Keccak => hash_code_keccak(code),
+            Self::Poseidon => hash_code_poseidon(code),
+        }
+    }
+
+    /// Code hash of the empty code under this scheme.
+    pub fn empty_code_hash(self) -> Hash {
+        match self {
+            Self::Keccak => *crate::KECCAK_CODE_HASH_EMPTY,
+            Self::Poseidon => *crate::POSEIDON_CODE_HASH_EMPTY,
+        }
+    }
+}
+
 /// Default code hash
 pub fn hash_code(code: &[u8]) -> Hash {
-    #[cfg(feature = "scroll")]
-    return hash_code_poseidon(code);
-    #[cfg(not(feature = "scroll"))]
-    return hash_code_keccak(code);
+    HashScheme::default().hash_code(code)
 }
 
 /// Keccak code hash
@@ -75,6 +111,19 @@ fn test_empty_code_hash() {
     assert_eq!(*crate::KECCAK_CODE_HASH_EMPTY, hash_code_keccak(&[]));
 }
 
+#[test]
+fn test_hash_scheme() {
+    for scheme in [HashScheme::Keccak, HashScheme::Poseidon] {
+        assert_eq!(scheme.empty_code_hash(), scheme.hash_code(&[]));
+    }
+    assert_eq!(HashScheme::Keccak.hash_code(&[1]), hash_code_keccak(&[1]));
+    assert_eq!(
+        HashScheme::Poseidon.hash_code(&[1]),
+        hash_code_poseidon(&[1])
+    );
+    assert_eq!(HashScheme::default().hash_code(&[1]), hash_code(&[1]));
+}
+
 #[cfg(feature = "scroll")]
 #[test]
 fn code_hashing() {
@@ -0,0 +1,175 @@
+//! Newtypes telling 256-bit EVM words apart from values small enough to be a
+//! field element of the circuits, with explicit conversions between them and
+//! into integers.
+//!
+//! Converting a [`Word`] with `low_u64` silently truncates it, while `as_u64`
+//! panics. The conversions of [`WordExt`] make the intent explicit instead:
+//! the checked ones return [`Error::WordOverflow`], and the `fit_*` ones
+//! truncate values which are known to fit, asserting that they do when the
+//! `strict-word-conversion` feature is enabled.
+
+use crate::{Address, Error, ToLittleEndian, ToWord, Word};
+
+/// Number of bits of the values held by a [`FieldWord`], which are below the
+/// modulus of the fields of the circuits.
+pub const FIELD_WORD_BITS: usize = 253;
+
+/// A 256-bit EVM word, as found on the stack, in memory or in storage. It
+/// does not fit in a field element, and is held by the circuits as its low
+/// and high 128 bits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StackWord(pub Word);
+
+impl From<Word> for StackWord {
+    fn from(word: Word) -> Self {
+        Self(word)
+    }
+}
+
+impl From<StackWord> for Word {
+    fn from(word: StackWord) -> Self {
+        word.0
+    }
+}
+
+impl StackWord {
+    /// Return the low and high 128 bits of the word.
+    pub fn to_lo_hi(self) -> (u128, u128) {
+        (self.0.low_u128(), (self.0 >> 128).low_u128())
+    }
+
+    /// Convert the word into a [`FieldWord`], if it has at most
+    /// [`FIELD_WORD_BITS`] bits.
+    pub fn to_field_word(self) -> Result<FieldWord, Error> {
+        FieldWord::new(self.0)
+    }
+}
+
+/// A value of at most [`FIELD_WORD_BITS`] bits, which is a field element of
+/// the circuits as is, e.g. an address, a gas amount or a counter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FieldWord(Word);
+
+impl FieldWord {
+    /// Create the value from `word`, failing if it has more than
+    /// [`FIELD_WORD_BITS`] bits.
+    pub fn new(word: Word) -> Result<Self, Error> {
+        if word.bits() > FIELD_WORD_BITS {
+            return Err(Error::WordOverflow {
+                value: word,
+                bits: FIELD_WORD_BITS,
+            });
+        }
+        Ok(Self(word))
+    }
+
+    /// Return the value as a [`Word`].
+    pub fn word(self) -> Word {
+        self.0
+    }
+
+    /// Return the little endian bytes of the value, which are the
+    /// representation of its field element.
+    pub fn to_le_bytes(self) -> [u8; 32] {
+        self.0.to_le_bytes()
+    }
+}
+
+impl TryFrom<Word> for FieldWord {
+    type Error = Error;
+
+    fn try_from(word: Word) -> Result<Self, Error> {
+        Self::new(word)
+    }
+}
+
+impl From<u64> for FieldWord {
+    fn from(value: u64) -> Self {
+        Self(value.into())
+    }
+}
+
+impl From<u128> for FieldWord {
+    fn from(value: u128) -> Self {
+        Self(value.into())
+    }
+}
+
+impl From<Address> for FieldWord {
+    fn from(address: Address) -> Self {
+        Self(address.to_word())
+    }
+}
+
+/// Explicit conversions of a [`Word`] into integers.
+pub trait WordExt {
+    /// Convert into a `u64`, failing if the word does not fit.
+    fn checked_u64(&self) -> Result<u64, Error>;
+
+    /// Convert into a `usize`, failing if the word does not fit.
+    fn checked_usize(&self) -> Result<usize, Error>;
+
+    /// Convert into a `u64` a word known to fit, e.g. the offset of a non
+    /// empty memory range, for which the execution would have run out of gas
+    /// otherwise. It is truncated if it does not, which panics with the
+    /// `strict-word-conversion` feature.
+    fn fit_u64(&self) -> u64;
+
+    /// Convert into a `usize` a word known to fit, as [`WordExt::fit_u64`].
+    fn fit_usize(&self) -> usize {
+        self.fit_u64() as usize
+    }
+}
+
+impl WordExt for Word {
+    fn checked_u64(&self) -> Result<u64, Error> {
+        if self.bits() > 64 {
+            return Err(Error::WordOverflow {
+                value: *self,
+                bits: 64,
+            });
+        }
+        Ok(self.low_u64())
+    }
+
+    fn checked_usize(&self) -> Result<usize, Error> {
+        let value = self.checked_u64()?;
+        usize::try_from(value).map_err(|_| Error::WordOverflow {
+            value: *self,
+            bits: usize::BITS as usize,
+        })
+    }
+
+    fn fit_u64(&self) -> u64 {
+        #[cfg(feature = "strict-word-conversion")]
+        assert!(self.bits() <= 64, "word {self:#x} truncated to u64");
+        self.low_u64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_conversions() {
+        let big = Word::one() << 64;
+        assert_eq!(Word::from(7).checked_u64().unwrap(), 7);
+        assert!(matches!(
+            big.checked_u64(),
+            Err(Error::WordOverflow { bits: 64, .. })
+        ));
+        assert_eq!(StackWord(big + 1).to_lo_hi(), ((1u128 << 64) | 1, 0));
+        assert_eq!(StackWord(Word::MAX).to_lo_hi(), (u128::MAX, u128::MAX));
+
+        assert!(FieldWord::new(Word::MAX).is_err());
+        assert!(FieldWord::new((Word::one() << FIELD_WORD_BITS) - 1).is_ok());
+        assert!(StackWord(Word::one() << FIELD_WORD_BITS)
+            .to_field_word()
+            .is_err());
+        assert_eq!(
+            FieldWord::from(Address::repeat_byte(0xff)).word(),
+            Address::repeat_byte(0xff).to_word()
+        );
+    }
+}
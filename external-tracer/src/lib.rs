@@ -1,13 +1,31 @@
-//! This module generates traces by connecting to an external tracer
+//! This module generates traces by connecting to an external tracer: geth
+//! through FFI, or revm with the `revm` feature.
+
+#[cfg(not(any(feature = "geth-utils", feature = "revm")))]
+compile_error!("external-tracer requires the geth-utils or the revm feature");
+#[cfg(all(feature = "scroll", not(feature = "geth-utils")))]
+compile_error!("external-tracer requires the geth-utils feature to trace l2 blocks");
+
+#[cfg(all(feature = "revm", not(feature = "scroll")))]
+mod revm_tracer;
+#[cfg(not(feature = "scroll"))]
+mod trace_file;
 
 #[cfg(feature = "scroll")]
 use eth_types::l2_types::BlockTrace;
+#[cfg(not(feature = "scroll"))]
+use eth_types::trace_file::GethExecTraceReader;
 use eth_types::{
     geth_types::{Account, BlockConstants, Transaction},
-    Address, Error, GethExecTrace, Word,
+    Address, Bytes, Error, GethExecTrace, Word,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+#[cfg(not(feature = "scroll"))]
+use std::path::Path;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
 };
-use serde::Serialize;
-use std::collections::BTreeMap;
 
 /// Configuration structure for `geth_utlis::trace`
 #[derive(Debug, Default, Clone, Serialize)]
@@ -21,10 +39,16 @@ pub struct TraceConfig {
     pub block_constants: BlockConstants,
     /// accounts
     pub accounts: BTreeMap<Address, Account>,
+    /// state overrides applied on top of `accounts`, e.g. to inject code at a
+    /// precompile or system contract address
+    #[serde(skip)]
+    pub overrides: BTreeMap<Address, AccountOverride>,
     /// transaction
     pub transactions: Vec<Transaction>,
     /// logger config
     pub logger_config: LoggerConfig,
+    /// call tracer config
+    pub call_tracer_config: CallTracerConfig,
     /// chain config
     pub chain_config: Option<ChainConfig>,
     /// beginning index of l1 queue
@@ -32,6 +56,74 @@ pub struct TraceConfig {
     pub l1_queue_index: u64,
 }
 
+impl TraceConfig {
+    /// Return `accounts` with `overrides` applied, which is the state the txs
+    /// are traced on, and the one the builder must start from.
+    pub fn accounts_with_overrides(&self) -> BTreeMap<Address, Account> {
+        let mut accounts = self.accounts.clone();
+        for (address, account_override) in &self.overrides {
+            let account = accounts.entry(*address).or_insert_with(|| Account {
+                address: *address,
+                ..Default::default()
+            });
+            account_override.apply(account);
+        }
+        accounts
+    }
+
+    /// Return the config with `overrides` applied to its `accounts`, as it is
+    /// passed to the tracer.
+    fn with_overrides_applied(&self) -> Cow<'_, Self> {
+        if self.overrides.is_empty() {
+            return Cow::Borrowed(self);
+        }
+        Cow::Owned(Self {
+            accounts: self.accounts_with_overrides(),
+            overrides: BTreeMap::new(),
+            ..self.clone()
+        })
+    }
+}
+
+/// Override of an account of a [`TraceConfig`], akin to the state overrides
+/// of `eth_call`: the set fields replace the ones of the account, which is
+/// created if missing.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverride {
+    /// balance
+    pub balance: Option<Word>,
+    /// nonce
+    pub nonce: Option<Word>,
+    /// code
+    pub code: Option<Bytes>,
+    /// storage replacing the whole storage of the account
+    pub state: Option<HashMap<Word, Word>>,
+    /// storage slots replacing the ones of the account, the others being
+    /// kept
+    pub state_diff: Option<HashMap<Word, Word>>,
+}
+
+impl AccountOverride {
+    fn apply(&self, account: &mut Account) {
+        if let Some(balance) = self.balance {
+            account.balance = balance;
+        }
+        if let Some(nonce) = self.nonce {
+            account.nonce = nonce;
+        }
+        if let Some(code) = &self.code {
+            account.code = code.clone();
+        }
+        if let Some(state) = &self.state {
+            account.storage = state.clone();
+        }
+        if let Some(state_diff) = &self.state_diff {
+            account.storage.extend(state_diff);
+        }
+    }
+}
+
 /// Configuration structure for `logger.Config`
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "PascalCase")]
@@ -74,47 +166,182 @@ impl LoggerConfig {
     }
 }
 
-/// Configuration structure for `params.ChainConfig`
-#[derive(Clone, Debug, Default, Serialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct ChainConfig {}
+/// Configuration structure for the config of geth's `callTracer`, whose call
+/// frames are returned in [`GethExecTrace::call_trace`]. Only taken by the l1
+/// tracer.
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallTracerConfig {
+    /// capture the logs emitted by each call
+    pub with_log: bool,
+}
+
+/// Configuration structure for `params.ChainConfig`: the fork schedule, as
+/// the activation block or timestamp of each fork, `None` if the fork is not
+/// activated. The default activates all the forks at genesis, as the tracer
+/// does without a chain config.
+///
+/// The l1 tracer takes the schedule as is, while the l2 tracer can only
+/// delay the forks it activates by default.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainConfig {
+    /// Homestead block
+    pub homestead_block: Option<u64>,
+    /// DAO fork block
+    pub dao_fork_block: Option<u64>,
+    /// Whether the DAO hard fork is supported
+    pub dao_fork_support: bool,
+    /// EIP-150 (Tangerine Whistle) block
+    pub eip150_block: Option<u64>,
+    /// EIP-155 (Spurious Dragon) block
+    pub eip155_block: Option<u64>,
+    /// EIP-158 (Spurious Dragon) block
+    pub eip158_block: Option<u64>,
+    /// Byzantium block
+    pub byzantium_block: Option<u64>,
+    /// Constantinople block
+    pub constantinople_block: Option<u64>,
+    /// Petersburg block
+    pub petersburg_block: Option<u64>,
+    /// Istanbul block
+    pub istanbul_block: Option<u64>,
+    /// Muir Glacier block
+    pub muir_glacier_block: Option<u64>,
+    /// Berlin block
+    pub berlin_block: Option<u64>,
+    /// London block
+    pub london_block: Option<u64>,
+    /// Total difficulty of the Merge, `None` before Paris
+    pub terminal_total_difficulty: Option<u64>,
+    /// Whether the Merge has happened
+    pub terminal_total_difficulty_passed: bool,
+    /// Shanghai timestamp
+    pub shanghai_time: Option<u64>,
+    /// Cancun timestamp
+    pub cancun_time: Option<u64>,
+}
+
+impl Default for ChainConfig {
+    fn default() -> Self {
+        Self {
+            homestead_block: Some(0),
+            dao_fork_block: Some(0),
+            dao_fork_support: true,
+            eip150_block: Some(0),
+            eip155_block: Some(0),
+            eip158_block: Some(0),
+            byzantium_block: Some(0),
+            constantinople_block: Some(0),
+            petersburg_block: Some(0),
+            istanbul_block: Some(0),
+            muir_glacier_block: Some(0),
+            berlin_block: Some(0),
+            london_block: Some(0),
+            terminal_total_difficulty: Some(0),
+            terminal_total_difficulty_passed: true,
+            shanghai_time: Some(0),
+            cancun_time: Some(0),
+        }
+    }
+}
 
+/// Backend generating the traces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TracerBackend {
+    /// geth, through FFI
+    #[cfg(feature = "geth-utils")]
+    Geth,
+    /// revm, in pure Rust. Only traces l1 blocks.
+    #[cfg(feature = "revm")]
+    Revm,
+}
+
+impl TracerBackend {
+    /// Backend selected by the `TRACER_BACKEND` env var, `geth` or `revm`,
+    /// geth if unset and built.
+    pub fn from_env() -> Self {
+        match std::env::var("TRACER_BACKEND").as_deref() {
+            #[cfg(feature = "geth-utils")]
+            Ok("geth") => return Self::Geth,
+            #[cfg(feature = "revm")]
+            Ok("revm") => return Self::Revm,
+            Ok(backend) => log::warn!("tracer backend {backend} unknown or not built"),
+            Err(_) => {}
+        }
+        #[cfg(feature = "geth-utils")]
+        return Self::Geth;
+        #[cfg(not(feature = "geth-utils"))]
+        Self::Revm
+    }
+}
+
+/// Creates the traces of the txs of the specified config, with the backend
+/// of [`TracerBackend::from_env`]
 #[cfg(not(feature = "scroll"))]
 pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
-    let trace_config = &serde_json::to_string_pretty(&config).unwrap();
-    log::trace!("trace config: {}", trace_config);
-    // Get the trace
-    let trace_string = geth_utils::trace(trace_config).map_err(|error| match error {
-        geth_utils::Error::TracingError(error) => Error::TracingError(error),
-    })?;
+    trace_with(config, TracerBackend::from_env())
+}
 
-    log::trace!("trace: {}", trace_string);
+/// Creates the traces of the txs of the specified config with `backend`
+#[cfg(not(feature = "scroll"))]
+pub fn trace_with(
+    config: &TraceConfig,
+    backend: TracerBackend,
+) -> Result<Vec<GethExecTrace>, Error> {
+    deserialize_trace(&backend_trace(config, backend)?)
+}
+
+/// Creates the traces of the txs of the specified config with the backend of
+/// [`TracerBackend::from_env`], writing the struct logs of the tx `i` to the
+/// trace file `{dir}/trace_{i}.bin` as they are parsed, and returns the
+/// readers of the trace files. Apart from the raw output of the tracer, only
+/// one parsed step at a time is held in memory.
+#[cfg(not(feature = "scroll"))]
+pub fn trace_to_disk(config: &TraceConfig, dir: &Path) -> Result<Vec<GethExecTraceReader>, Error> {
+    let trace_string = backend_trace(config, TracerBackend::from_env())?;
 
     let mut deserializer = serde_json::Deserializer::from_str(&trace_string);
     deserializer.disable_recursion_limit();
     let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
-    serde::Deserialize::deserialize(deserializer).map_err(Error::SerdeError)
+    serde::de::DeserializeSeed::deserialize(trace_file::TracesSeed { dir }, deserializer)
+        .map_err(Error::SerdeError)
 }
 
-/// Creates a l2-trace for the specified config
-#[cfg(feature = "scroll")]
-pub fn l2trace(config: &TraceConfig) -> Result<BlockTrace, Error> {
-    let l2_config = config.clone();
-    let trace_config = &serde_json::to_string_pretty(&l2_config).unwrap();
+#[cfg(not(feature = "scroll"))]
+fn backend_trace(config: &TraceConfig, backend: TracerBackend) -> Result<String, Error> {
+    match backend {
+        #[cfg(feature = "geth-utils")]
+        TracerBackend::Geth => geth_trace(config),
+        #[cfg(feature = "revm")]
+        TracerBackend::Revm => revm_tracer::trace(&config.with_overrides_applied()),
+    }
+}
+
+#[cfg(feature = "geth-utils")]
+fn geth_trace(config: &TraceConfig) -> Result<String, Error> {
+    let trace_config = &serde_json::to_string_pretty(&config.with_overrides_applied()).unwrap();
     log::trace!("trace config: {}", trace_config);
-    // Get the trace
-    let trace_string = geth_utils::trace(trace_config).map_err(|error| match error {
+    geth_utils::trace(trace_config).map_err(|error| match error {
         geth_utils::Error::TracingError(error) => Error::TracingError(error),
-    })?;
+    })
+}
 
+fn deserialize_trace<T: DeserializeOwned>(trace_string: &str) -> Result<T, Error> {
     log::trace!("trace: {}", trace_string);
 
-    let mut deserializer = serde_json::Deserializer::from_str(&trace_string);
+    let mut deserializer = serde_json::Deserializer::from_str(trace_string);
     deserializer.disable_recursion_limit();
     let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
     serde::Deserialize::deserialize(deserializer).map_err(Error::SerdeError)
 }
 
+/// Creates a l2-trace for the specified config
+#[cfg(feature = "scroll")]
+pub fn l2trace(config: &TraceConfig) -> Result<BlockTrace, Error> {
+    deserialize_trace(&geth_trace(config)?)
+}
+
 #[cfg(feature = "scroll")]
 pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
     let block_trace = l2trace(config)?;
@@ -125,3 +352,50 @@ pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
         .map(From::from)
         .collect::<Vec<_>>())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_types::address;
+
+    #[test]
+    fn accounts_with_overrides() {
+        let contract = address!("0x000000000000000000000000000000000cafe001");
+        let beacon_roots = address!("0x000000000000000000000000000000000000000b");
+        let config = TraceConfig {
+            accounts: BTreeMap::from([(
+                contract,
+                Account {
+                    address: contract,
+                    balance: Word::from(1),
+                    storage: HashMap::from([(Word::from(1), Word::from(1))]),
+                    ..Default::default()
+                },
+            )]),
+            overrides: BTreeMap::from([
+                (
+                    contract,
+                    AccountOverride {
+                        state_diff: Some(HashMap::from([(Word::from(2), Word::from(2))])),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    beacon_roots,
+                    AccountOverride {
+                        code: Some(Bytes::from([0x00])),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            ..Default::default()
+        };
+
+        let accounts = config.accounts_with_overrides();
+        assert_eq!(accounts[&contract].balance, Word::from(1));
+        assert_eq!(accounts[&contract].storage.len(), 2);
+        assert_eq!(accounts[&beacon_roots].address, beacon_roots);
+        assert_eq!(accounts[&beacon_roots].code, Bytes::from([0x00]));
+        assert!(config.with_overrides_applied().overrides.is_empty());
+    }
+}
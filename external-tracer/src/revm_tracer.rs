@@ -0,0 +1,901 @@
+//! Tracer backend executing the txs with revm, in pure Rust, instead of geth
+//! through FFI.
+//!
+//! The traces are serialized as geth's tracer outputs them: struct logs, call
+//! trace and prestate. Like geth's struct logger, a step only carries the
+//! errors raised before the execution of its opcode (out of gas, stack
+//! underflow and overflow, invalid opcode).
+
+use crate::{CallTracerConfig, ChainConfig, LoggerConfig, TraceConfig};
+use eth_types::{
+    evm_types::OpcodeId, Address, Bytes, Error, GethCallLog, GethExecError, GethPrestateTrace,
+    Word, H256,
+};
+use revm::{
+    db::{CacheDB, EmptyDB},
+    inspector_handle_register,
+    interpreter::{
+        CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, InstructionResult,
+        Interpreter, InterpreterResult,
+    },
+    primitives::{
+        self, AccountInfo, BlobExcessGasAndPrice, BlockEnv, Bytecode, CreateScheme,
+        ExecutionResult, Log, ResultAndState, SpecId, TransactTo, TxEnv, B256, U256,
+    },
+    DatabaseCommit, DatabaseRef, Evm, EvmContext, Inspector,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+type Db = CacheDB<EmptyDB>;
+
+/// Trace of a tx, as output by geth's tracer.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TxTrace {
+    gas: u64,
+    failed: bool,
+    return_value: String,
+    struct_logs: Vec<StructLog>,
+    prestate: HashMap<Address, GethPrestateTrace>,
+    call_trace: CallFrame,
+}
+
+/// Step of geth's struct logger.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StructLog {
+    pc: usize,
+    op: String,
+    gas: u64,
+    gas_cost: u64,
+    refund: u64,
+    depth: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stack: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage: Option<BTreeMap<String, String>>,
+}
+
+/// Call of geth's call tracer.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CallFrame {
+    #[serde(rename = "type")]
+    call_type: &'static str,
+    from: Address,
+    to: Option<Address>,
+    gas: Word,
+    gas_used: Word,
+    input: Bytes,
+    output: Option<Bytes>,
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<Word>,
+    calls: Vec<CallFrame>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    logs: Vec<GethCallLog>,
+}
+
+impl CallFrame {
+    fn new(
+        call_type: &'static str,
+        from: Address,
+        to: Option<Address>,
+        gas: u64,
+        input: Bytes,
+        value: Option<Word>,
+    ) -> Self {
+        Self {
+            call_type,
+            from,
+            to,
+            gas: gas.into(),
+            gas_used: Word::zero(),
+            input,
+            output: None,
+            error: None,
+            value,
+            calls: Vec::new(),
+            logs: Vec::new(),
+        }
+    }
+
+    /// Drop the logs of the frame and its sub calls, reverted with it, as
+    /// geth's call tracer does.
+    fn clear_logs(&mut self) {
+        self.logs.clear();
+        self.calls.iter_mut().for_each(CallFrame::clear_logs);
+    }
+}
+
+/// Inspector recording the struct logs and the call trace of a tx.
+struct TraceInspector {
+    logger_config: LoggerConfig,
+    call_tracer_config: CallTracerConfig,
+    struct_logs: Vec<StructLog>,
+    // opcode and gas left of the step being executed
+    op: OpcodeId,
+    gas_before: u64,
+    // storage accessed so far by each contract, as logged by geth
+    storage: HashMap<primitives::Address, BTreeMap<String, String>>,
+    calls: Vec<CallFrame>,
+    call_trace: Option<CallFrame>,
+}
+
+impl TraceInspector {
+    fn new(logger_config: LoggerConfig, call_tracer_config: CallTracerConfig) -> Self {
+        Self {
+            logger_config,
+            call_tracer_config,
+            struct_logs: Vec::new(),
+            op: OpcodeId::STOP,
+            gas_before: 0,
+            storage: HashMap::new(),
+            calls: Vec::new(),
+            call_trace: None,
+        }
+    }
+
+    fn storage_log(
+        &mut self,
+        op: OpcodeId,
+        interp: &Interpreter,
+        context: &EvmContext<&mut Db>,
+    ) -> Option<BTreeMap<String, String>> {
+        let address = interp.contract.target_address;
+        let stack = interp.stack.data();
+        let (key, value) = match (op, stack.len()) {
+            (OpcodeId::SLOAD, len) if len >= 1 => {
+                let key = stack[len - 1];
+                let value = context
+                    .journaled_state
+                    .state
+                    .get(&address)
+                    .and_then(|account| account.storage.get(&key))
+                    .map(|slot| slot.present_value)
+                    .unwrap_or_else(|| context.db.storage_ref(address, key).unwrap_or_default());
+                (key, value)
+            }
+            (OpcodeId::SSTORE, len) if len >= 2 => (stack[len - 1], stack[len - 2]),
+            _ => return None,
+        };
+        let storage = self.storage.entry(address).or_default();
+        storage.insert(format!("{key:064x}"), format!("{value:064x}"));
+        Some(storage.clone())
+    }
+
+    fn end_frame(&mut self, result: &InterpreterResult, address: Option<primitives::Address>) {
+        let mut frame = self.calls.pop().expect("call frame not started");
+        frame.gas_used = frame
+            .gas
+            .as_u64()
+            .saturating_sub(result.gas.remaining())
+            .into();
+        if let Some(address) = address {
+            frame.to = Some(from_address(address));
+        }
+        frame.output = Some(result.output.to_vec().into());
+        if !result.is_ok() {
+            frame.error = Some(error_message(result.result));
+            frame.clear_logs();
+        }
+        match self.calls.last_mut() {
+            Some(parent) => parent.calls.push(frame),
+            None => self.call_trace = Some(frame),
+        }
+    }
+}
+
+impl<'a> Inspector<&'a mut Db> for TraceInspector {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<&'a mut Db>) {
+        let op = OpcodeId::from(interp.current_opcode());
+        self.op = op;
+        self.gas_before = interp.gas.remaining();
+        let stack = (!self.logger_config.disable_stack).then(|| {
+            interp
+                .stack
+                .data()
+                .iter()
+                .map(|value| format!("{value:#x}"))
+                .collect()
+        });
+        let memory = self.logger_config.enable_memory.then(|| {
+            interp
+                .shared_memory
+                .context_memory()
+                .chunks(32)
+                .map(hex_string)
+                .collect()
+        });
+        let storage = if self.logger_config.disable_storage {
+            None
+        } else {
+            self.storage_log(op, interp, context)
+        };
+        self.struct_logs.push(StructLog {
+            pc: interp.program_counter(),
+            op: op_name(op),
+            gas: interp.gas.remaining(),
+            gas_cost: 0,
+            refund: interp.gas.refunded() as u64,
+            depth: context.journaled_state.depth() as usize,
+            error: None,
+            stack,
+            memory,
+            storage,
+        });
+    }
+
+    fn log(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<&'a mut Db>, log: &Log) {
+        if !self.call_tracer_config.with_log {
+            return;
+        }
+        if let Some(frame) = self.calls.last_mut() {
+            frame.logs.push(GethCallLog {
+                address: from_address(log.address),
+                topics: log
+                    .data
+                    .topics()
+                    .iter()
+                    .map(|topic| H256(topic.0))
+                    .collect(),
+                data: log.data.data.to_vec().into(),
+            });
+        }
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<&'a mut Db>) {
+        let step = self.struct_logs.last_mut().expect("step not started");
+        step.gas_cost = self.gas_before.saturating_sub(interp.gas.remaining());
+        step.error = step_error(interp.instruction_result, self.op, interp.stack.len());
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<&'a mut Db>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        let (call_type, from) = match inputs.scheme {
+            CallScheme::CallCode => ("CALLCODE", inputs.caller),
+            // revm keeps the caller of the current call, geth logs the current contract
+            CallScheme::DelegateCall => ("DELEGATECALL", inputs.target_address),
+            CallScheme::StaticCall => ("STATICCALL", inputs.caller),
+            _ => ("CALL", inputs.caller),
+        };
+        // geth logs no value for DELEGATECALL, which inherits the one of its caller
+        let value =
+            (inputs.scheme != CallScheme::DelegateCall).then(|| from_u256(inputs.value.get()));
+        self.calls.push(CallFrame::new(
+            call_type,
+            from_address(from),
+            Some(from_address(inputs.bytecode_address)),
+            inputs.gas_limit,
+            inputs.input.to_vec().into(),
+            value,
+        ));
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<&'a mut Db>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.end_frame(&outcome.result, None);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<&'a mut Db>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        let call_type = match inputs.scheme {
+            CreateScheme::Create => "CREATE",
+            CreateScheme::Create2 { .. } => "CREATE2",
+        };
+        self.calls.push(CallFrame::new(
+            call_type,
+            from_address(inputs.caller),
+            None,
+            inputs.gas_limit,
+            inputs.init_code.to_vec().into(),
+            Some(from_u256(inputs.value)),
+        ));
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<&'a mut Db>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.end_frame(&outcome.result, outcome.address);
+        outcome
+    }
+}
+
+/// Trace the txs of `config` with revm, and return the traces serialized as
+/// geth's tracer does.
+pub(crate) fn trace(config: &TraceConfig) -> Result<String, Error> {
+    let block = &config.block_constants;
+    let chain_config = config.chain_config.clone().unwrap_or_default();
+    let spec_id = spec_id(
+        &chain_config,
+        block.number.as_u64(),
+        block.timestamp.as_u64(),
+    );
+
+    let mut db = Db::new(EmptyDB::default());
+    for (address, account) in &config.accounts {
+        let code = Bytecode::new_raw(account.code.to_vec().into());
+        let info = AccountInfo::new(
+            to_u256(account.balance),
+            account.nonce.as_u64(),
+            code.hash_slow(),
+            code,
+        );
+        db.insert_account_info(to_address(*address), info);
+        for (key, value) in &account.storage {
+            db.insert_account_storage(to_address(*address), to_u256(*key), to_u256(*value))
+                .expect("infallible empty db");
+        }
+    }
+    let history_len = config.history_hashes.len() as u64;
+    for (index, hash) in config.history_hashes.iter().enumerate() {
+        let number = block.number.as_u64() - history_len + index as u64;
+        db.block_hashes
+            .insert(U256::from(number), B256::from(to_u256(*hash)));
+    }
+
+    let block_env = BlockEnv {
+        number: U256::from(block.number.as_u64()),
+        coinbase: to_address(block.coinbase),
+        timestamp: to_u256(block.timestamp),
+        gas_limit: to_u256(block.gas_limit),
+        basefee: to_u256(block.base_fee),
+        difficulty: to_u256(block.difficulty),
+        prevrandao: Some(B256::from(to_u256(block.difficulty))),
+        blob_excess_gas_and_price: Some(BlobExcessGasAndPrice {
+            excess_blob_gas: 0,
+            blob_gasprice: block.blob_base_fee.as_u128(),
+        }),
+    };
+
+    let mut traces = Vec::with_capacity(config.transactions.len());
+    for (index, tx) in config.transactions.iter().enumerate() {
+        let tx_env = TxEnv {
+            caller: to_address(tx.from),
+            gas_limit: tx.gas_limit.as_u64(),
+            gas_price: to_u256(tx.gas_fee_cap.or(tx.gas_price).unwrap_or_default()),
+            transact_to: match tx.to {
+                Some(to) => TransactTo::Call(to_address(to)),
+                None => TransactTo::Create,
+            },
+            value: to_u256(tx.value),
+            data: tx.call_data.to_vec().into(),
+            nonce: Some(tx.nonce.as_u64()),
+            chain_id: Some(config.chain_id),
+            access_list: tx
+                .access_list
+                .iter()
+                .flat_map(|access_list| &access_list.0)
+                .map(|item| primitives::AccessListItem {
+                    address: to_address(item.address),
+                    storage_keys: item
+                        .storage_keys
+                        .iter()
+                        .map(|key| key.to_fixed_bytes().into())
+                        .collect(),
+                })
+                .collect(),
+            gas_priority_fee: tx.gas_tip_cap.or(tx.gas_price).map(to_u256),
+            blob_hashes: tx
+                .blob
+                .blob_versioned_hashes
+                .iter()
+                .map(|hash| hash.to_fixed_bytes().into())
+                .collect(),
+            max_fee_per_blob_gas: (!tx.blob.blob_versioned_hashes.is_empty())
+                .then(|| to_u256(tx.blob.max_fee_per_blob_gas)),
+            ..Default::default()
+        };
+
+        let mut inspector = TraceInspector::new(
+            config.logger_config.clone(),
+            config.call_tracer_config.clone(),
+        );
+        let ResultAndState { result, state } = {
+            let mut evm = Evm::builder()
+                .with_db(&mut db)
+                .with_external_context(&mut inspector)
+                .with_spec_id(spec_id)
+                .modify_cfg_env(|cfg| cfg.chain_id = config.chain_id)
+                .modify_block_env(|env| *env = block_env.clone())
+                .modify_tx_env(|env| *env = tx_env)
+                .append_handler_register(inspector_handle_register)
+                .build();
+            evm.transact().map_err(|err| {
+                Error::TracingError(format!(
+                    "Failed to apply config.Transactions[{index}]: {err:?}"
+                ))
+            })?
+        };
+
+        let prestate = state
+            .iter()
+            .map(|(address, account)| {
+                let info = db.basic_ref(*address).ok().flatten().unwrap_or_default();
+                let code = info
+                    .code
+                    .map(|code| code.original_bytes())
+                    .filter(|code| !code.is_empty());
+                let prestate = GethPrestateTrace {
+                    balance: Some(from_u256(info.balance)),
+                    nonce: Some(info.nonce),
+                    code: code.map(|code| code.to_vec().into()),
+                    storage: Some(
+                        account
+                            .storage
+                            .iter()
+                            .map(|(key, slot)| (from_u256(*key), from_u256(slot.original_value)))
+                            .collect(),
+                    ),
+                };
+                (from_address(*address), prestate)
+            })
+            .collect();
+        db.commit(state);
+
+        let (gas, failed, output) = match result {
+            ExecutionResult::Success {
+                gas_used, output, ..
+            } => (gas_used, false, output.into_data()),
+            ExecutionResult::Revert { gas_used, output } => (gas_used, true, output),
+            ExecutionResult::Halt { gas_used, .. } => (gas_used, true, Default::default()),
+        };
+        let call_trace = inspector.call_trace.take().unwrap_or_else(|| {
+            CallFrame::new(
+                if tx.to.is_some() { "CALL" } else { "CREATE" },
+                tx.from,
+                tx.to,
+                tx.gas_limit.as_u64(),
+                tx.call_data.clone(),
+                Some(tx.value),
+            )
+        });
+        traces.push(TxTrace {
+            gas,
+            failed,
+            return_value: hex_string(&output),
+            struct_logs: inspector.struct_logs,
+            prestate,
+            call_trace,
+        });
+    }
+
+    serde_json::to_string(&traces).map_err(Error::SerdeError)
+}
+
+/// Spec of the latest fork of `chain_config` activated at the block `number`
+/// with `timestamp`.
+fn spec_id(chain_config: &ChainConfig, number: u64, timestamp: u64) -> SpecId {
+    let by_block = |block: Option<u64>| block.map_or(false, |block| block <= number);
+    let by_time = |time: Option<u64>| time.map_or(false, |time| time <= timestamp);
+    if by_time(chain_config.cancun_time) {
+        SpecId::CANCUN
+    } else if by_time(chain_config.shanghai_time) {
+        SpecId::SHANGHAI
+    } else if chain_config.terminal_total_difficulty_passed {
+        SpecId::MERGE
+    } else if by_block(chain_config.london_block) {
+        SpecId::LONDON
+    } else if by_block(chain_config.berlin_block) {
+        SpecId::BERLIN
+    } else if by_block(chain_config.muir_glacier_block) {
+        SpecId::MUIR_GLACIER
+    } else if by_block(chain_config.istanbul_block) {
+        SpecId::ISTANBUL
+    } else if by_block(chain_config.petersburg_block) {
+        SpecId::PETERSBURG
+    } else if by_block(chain_config.constantinople_block) {
+        SpecId::CONSTANTINOPLE
+    } else if by_block(chain_config.byzantium_block) {
+        SpecId::BYZANTIUM
+    } else if by_block(chain_config.eip158_block) {
+        SpecId::SPURIOUS_DRAGON
+    } else if by_block(chain_config.eip150_block) {
+        SpecId::TANGERINE
+    } else if by_block(chain_config.homestead_block) {
+        SpecId::HOMESTEAD
+    } else {
+        SpecId::FRONTIER
+    }
+}
+
+/// Error of a step, if raised before the execution of its opcode `op` with
+/// `stack_len` items on the stack.
+fn step_error(result: InstructionResult, op: OpcodeId, stack_len: usize) -> Option<String> {
+    let error = match result {
+        InstructionResult::OutOfGas
+        | InstructionResult::MemoryOOG
+        | InstructionResult::MemoryLimitOOG
+        | InstructionResult::InvalidOperandOOG => GethExecError::OutOfGas,
+        InstructionResult::StackUnderflow => GethExecError::StackUnderflow {
+            stack_len: stack_len as u64,
+            required: 1024 - op.valid_stack_ptr_range().1 as u64,
+        },
+        InstructionResult::StackOverflow => GethExecError::StackOverflow {
+            stack_len: stack_len as u64,
+            limit: 1024,
+        },
+        InstructionResult::OpcodeNotFound | InstructionResult::InvalidFEOpcode => {
+            return Some(format!("invalid opcode: {}", op_name(op)))
+        }
+        _ => return None,
+    };
+    Some(error.to_string())
+}
+
+/// Error message of a call ending with `result`, as geth's call tracer.
+fn error_message(result: InstructionResult) -> String {
+    match result {
+        InstructionResult::Revert => GethExecError::ExecutionReverted.to_string(),
+        InstructionResult::CallTooDeep => GethExecError::Depth.to_string(),
+        InstructionResult::OutOfFunds => GethExecError::InsufficientBalance.to_string(),
+        InstructionResult::CreateCollision => GethExecError::ContractAddressCollision.to_string(),
+        InstructionResult::InvalidJump => GethExecError::InvalidJump.to_string(),
+        InstructionResult::StateChangeDuringStaticCall => {
+            GethExecError::WriteProtection.to_string()
+        }
+        InstructionResult::OutOfOffset => GethExecError::ReturnDataOutOfBounds.to_string(),
+        InstructionResult::CreateContractSizeLimit => {
+            GethExecError::MaxCodeSizeExceeded.to_string()
+        }
+        InstructionResult::CreateInitCodeSizeLimit => {
+            GethExecError::MaxInitCodeSizeExceeded.to_string()
+        }
+        InstructionResult::CreateContractStartingWithEF => GethExecError::InvalidCode.to_string(),
+        InstructionResult::NonceOverflow => GethExecError::NonceUintOverflow.to_string(),
+        InstructionResult::OutOfGas
+        | InstructionResult::MemoryOOG
+        | InstructionResult::MemoryLimitOOG
+        | InstructionResult::InvalidOperandOOG
+        | InstructionResult::PrecompileOOG => GethExecError::OutOfGas.to_string(),
+        result => format!("{result:?}"),
+    }
+}
+
+/// Name of `op` as logged by geth.
+fn op_name(op: OpcodeId) -> String {
+    match op {
+        OpcodeId::INVALID(byte) => format!("opcode {byte:#x} not defined"),
+        op => op.to_string(),
+    }
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn to_address(address: Address) -> primitives::Address {
+    address.0.into()
+}
+
+fn from_address(address: primitives::Address) -> Address {
+    Address::from_slice(address.as_slice())
+}
+
+fn to_u256(word: Word) -> U256 {
+    U256::from_limbs(word.0)
+}
+
+fn from_u256(value: U256) -> Word {
+    Word(value.into_limbs())
+}
+
+/// Differential tests of the revm backend against geth's tracer, run with
+/// `cargo test -p external-tracer --features revm`.
+#[cfg(all(test, feature = "geth-utils"))]
+mod tests {
+    use super::*;
+    use crate::{trace_with, TracerBackend};
+    use eth_types::{
+        address, bytecode,
+        geth_types::{Account, BlockConstants, Transaction},
+        Bytecode, GethExecTrace,
+    };
+
+    fn sender() -> Address {
+        address!("0x00000000000000000000000000000000000cafe1")
+    }
+
+    fn contract(address: Address, code: Bytes) -> Account {
+        Account {
+            address,
+            balance: Word::from(10u64.pow(18)),
+            code,
+            ..Default::default()
+        }
+    }
+
+    fn tx(nonce: u64, to: Option<Address>, call_data: Bytes) -> Transaction {
+        Transaction {
+            from: sender(),
+            to,
+            nonce: nonce.into(),
+            gas_limit: Word::from(1_000_000),
+            gas_price: Some(Word::from(2)),
+            call_data,
+            ..Default::default()
+        }
+    }
+
+    /// Code writing `init_code`, at most 32 bytes long, to the memory at 0.
+    fn mstore_init_code(init_code: &Bytecode) -> Bytecode {
+        let init_code = init_code.code();
+        let mut word = [0u8; 32];
+        word[..init_code.len()].copy_from_slice(&init_code);
+        bytecode! {
+            PUSH32(Word::from_big_endian(&word))
+            PUSH1(0x00)
+            MSTORE
+        }
+    }
+
+    /// Trace `transactions` on `accounts` with both backends, check that the
+    /// traces are the same and return them.
+    fn assert_traced_as_geth(
+        mut accounts: Vec<Account>,
+        transactions: Vec<Transaction>,
+    ) -> Vec<GethExecTrace> {
+        accounts.push(Account {
+            address: sender(),
+            balance: Word::from(10u64.pow(18)),
+            ..Default::default()
+        });
+        let config = TraceConfig {
+            chain_id: 1337,
+            block_constants: BlockConstants {
+                coinbase: address!("0x00000000000000000000000000000000c014ba5e"),
+                timestamp: Word::from(0x10),
+                number: 0x10u64.into(),
+                difficulty: Word::from(0x20000),
+                gas_limit: Word::from(10_000_000),
+                base_fee: Word::one(),
+                blob_base_fee: Word::one(),
+            },
+            accounts: accounts
+                .into_iter()
+                .map(|account| (account.address, account))
+                .collect(),
+            transactions,
+            logger_config: LoggerConfig {
+                enable_memory: true,
+                disable_stack: false,
+                disable_storage: false,
+                enable_return_data: true,
+            },
+            call_tracer_config: CallTracerConfig { with_log: true },
+            ..Default::default()
+        };
+
+        let geth_traces = trace_with(&config, TracerBackend::Geth).unwrap();
+        let revm_traces = trace_with(&config, TracerBackend::Revm).unwrap();
+        assert_eq!(revm_traces.len(), geth_traces.len());
+        for (index, (revm, geth)) in revm_traces.iter().zip(&geth_traces).enumerate() {
+            for (step, (revm, geth)) in revm.struct_logs.iter().zip(&geth.struct_logs).enumerate() {
+                assert_eq!(revm, geth, "tx {index}, step {step}");
+            }
+            assert_eq!(revm.struct_logs.len(), geth.struct_logs.len(), "tx {index}");
+            assert_eq!(revm.gas, geth.gas, "tx {index}");
+            assert_eq!(revm.failed, geth.failed, "tx {index}");
+            assert_eq!(revm.return_value, geth.return_value, "tx {index}");
+            assert_eq!(revm.call_trace, geth.call_trace, "tx {index}");
+            assert_eq!(revm.prestate, geth.prestate, "tx {index}");
+            assert_eq!(revm, geth, "tx {index}");
+        }
+        geth_traces
+    }
+
+    #[test]
+    fn opcodes_traced_as_geth() {
+        let callee = address!("0x000000000000000000000000000000000cafe002");
+        let mut code = bytecode! {
+            // fresh, then committed and preset slots
+            PUSH1(0x2a)
+            PUSH1(0x01)
+            SSTORE
+            PUSH1(0x01)
+            SLOAD
+            PUSH1(0x02)
+            SLOAD
+            ADD
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x03)
+            PUSH1(0x02)
+            EXP
+            PUSH1(0x20)
+            MSTORE
+            PUSH1(0x40)
+            PUSH1(0x00)
+            SHA3
+            PUSH1(0x03)
+            SSTORE
+            // environment
+            CALLER
+            BALANCE
+            CALLVALUE
+            CALLDATASIZE
+            NUMBER
+            TIMESTAMP
+            CHAINID
+            GASPRICE
+            GAS
+            MSIZE
+            PUSH1(0x00)
+            CALLDATALOAD
+        };
+        // jump over an invalid opcode: PUSH1 dest, JUMP, INVALID, JUMPDEST
+        let dest = code.code().len() + 4;
+        code.append(&bytecode! {
+            PUSH1(dest)
+            JUMP
+            .write_op(OpcodeId::INVALID(0xfe))
+            JUMPDEST
+            PUSH1(0x20)
+            PUSH1(0x00)
+            LOG0
+            PUSH1(0x40)
+            PUSH1(0x00)
+            RETURN
+        });
+        let mut account = contract(callee, code.into());
+        account.storage.insert(Word::from(2), Word::from(7));
+
+        // the second tx finds the slots written by the first
+        let traces = assert_traced_as_geth(
+            vec![account],
+            vec![
+                tx(0, Some(callee), Bytes::from([0x01; 36])),
+                tx(1, Some(callee), Bytes::default()),
+            ],
+        );
+        assert!(traces.iter().all(|trace| !trace.failed));
+    }
+
+    #[test]
+    fn calls_and_creates_traced_as_geth() {
+        let caller = address!("0x000000000000000000000000000000000cafe003");
+        let callee = address!("0x000000000000000000000000000000000cafe004");
+        let callee_code = bytecode! {
+            PUSH1(0x01)
+            SLOAD
+            PUSH1(0x01)
+            ADD
+            PUSH1(0x01)
+            SSTORE
+            CALLER
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x20)
+            PUSH1(0x00)
+            RETURN
+        };
+        // deploys a STOP
+        let init_code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            RETURN
+        };
+        let init_len = init_code.code().len();
+        let mut caller_code = bytecode! {
+            .op_call(0xffff, callee, 0, 0, 0, 0, 0x20)
+            POP
+            .op_call(0xffff, callee, 1, 0, 0x04, 0, 0x20)
+            POP
+            .op_callcode(0xffff, callee, 0, 0, 0, 0, 0x20)
+            POP
+            .op_delegatecall(0xffff, callee, 0, 0, 0, 0x20)
+            POP
+            // the SSTORE of the callee fails in a static call
+            .op_staticcall(0xffff, callee, 0, 0, 0, 0x20)
+            POP
+        };
+        caller_code.append(&mstore_init_code(&init_code));
+        caller_code.append(&bytecode! {
+            .op_create(0, 0, init_len)
+            POP
+            .op_create2(1, 0, init_len, 0x1234)
+            POP
+            STOP
+        });
+
+        // the create tx calls the callee from the constructor
+        let mut constructor = bytecode! {
+            .op_call(0xffff, callee, 0, 0, 0, 0, 0x20)
+            POP
+        };
+        constructor.append(&init_code);
+
+        assert_traced_as_geth(
+            vec![
+                contract(caller, caller_code.into()),
+                contract(callee, callee_code.into()),
+            ],
+            vec![
+                tx(0, Some(caller), Bytes::default()),
+                tx(1, None, constructor.into()),
+            ],
+        );
+    }
+
+    #[test]
+    fn reverts_traced_as_geth() {
+        let reverter = address!("0x000000000000000000000000000000000cafe005");
+        let caller = address!("0x000000000000000000000000000000000cafe006");
+        let looper = address!("0x000000000000000000000000000000000cafe007");
+        let underflow = address!("0x000000000000000000000000000000000cafe008");
+        let invalid = address!("0x000000000000000000000000000000000cafe009");
+        // writes a slot and logs before reverting with data
+        let reverter_code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x01)
+            SSTORE
+            PUSH1(0x2a)
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x20)
+            PUSH1(0x00)
+            LOG0
+            PUSH1(0x20)
+            PUSH1(0x00)
+            REVERT
+        };
+        let caller_code = bytecode! {
+            .op_call(0xffff, reverter, 0, 0, 0, 0, 0x20)
+            POP
+            RETURNDATASIZE
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x20)
+            PUSH1(0x00)
+            RETURN
+        };
+        let looper_code = bytecode! {
+            JUMPDEST
+            PUSH1(0x00)
+            JUMP
+        };
+        let mut out_of_gas = tx(3, Some(looper), Bytes::default());
+        out_of_gas.gas_limit = Word::from(50_000);
+
+        let traces = assert_traced_as_geth(
+            vec![
+                contract(reverter, reverter_code.into()),
+                contract(caller, caller_code.into()),
+                contract(looper, looper_code.into()),
+                contract(underflow, bytecode! { ADD }.into()),
+                contract(invalid, Bytes::from([0xfe])),
+            ],
+            vec![
+                tx(0, Some(caller), Bytes::default()),
+                tx(1, Some(reverter), Bytes::default()),
+                tx(2, None, bytecode! { PUSH1(0x00) PUSH1(0x00) REVERT }.into()),
+                out_of_gas,
+                tx(4, Some(underflow), Bytes::default()),
+                tx(5, Some(invalid), Bytes::default()),
+            ],
+        );
+        let failed: Vec<_> = traces.iter().map(|trace| trace.failed).collect();
+        assert_eq!(failed, [false, true, true, true, true, true]);
+    }
+}
@@ -0,0 +1,108 @@
+//! Streaming deserialization of the traces into trace files, writing each
+//! struct log to disk as soon as it is parsed instead of collecting them.
+
+use eth_types::{
+    trace_file::{GethExecTraceReader, GethExecTraceWriter},
+    GethExecStep, GethExecTrace,
+};
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::{fmt, path::Path};
+
+/// Seed of a list of traces, written to `{dir}/trace_{index}.bin`.
+pub(crate) struct TracesSeed<'a> {
+    pub(crate) dir: &'a Path,
+}
+
+impl<'de> DeserializeSeed<'de> for TracesSeed<'_> {
+    type Value = Vec<GethExecTraceReader>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for TracesSeed<'_> {
+    type Value = Vec<GethExecTraceReader>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of geth traces")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut readers = Vec::new();
+        loop {
+            let path = self.dir.join(format!("trace_{}.bin", readers.len()));
+            if seq.next_element_seed(TraceSeed { path: &path })?.is_none() {
+                return Ok(readers);
+            }
+            readers.push(GethExecTraceReader::open(&path).map_err(de::Error::custom)?);
+        }
+    }
+}
+
+/// Seed of a trace, written to `path`.
+struct TraceSeed<'a> {
+    path: &'a Path,
+}
+
+impl<'de> DeserializeSeed<'de> for TraceSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for TraceSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a geth trace")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut writer = GethExecTraceWriter::create(self.path).map_err(de::Error::custom)?;
+        let mut header = serde_json::Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "structLogs" {
+                map.next_value_seed(StructLogsSeed {
+                    writer: &mut writer,
+                })?;
+            } else {
+                header.insert(key, map.next_value()?);
+            }
+        }
+        header.insert("structLogs".to_string(), serde_json::Value::Array(vec![]));
+        let header: GethExecTrace =
+            serde_json::from_value(header.into()).map_err(de::Error::custom)?;
+        writer.finish(&header).map_err(de::Error::custom)
+    }
+}
+
+/// Seed of the struct logs of a trace, written to `writer`.
+struct StructLogsSeed<'a> {
+    writer: &'a mut GethExecTraceWriter,
+}
+
+impl<'de> DeserializeSeed<'de> for StructLogsSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for StructLogsSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of geth steps")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(step) = seq.next_element::<GethExecStep>()? {
+            self.writer.write_step(&step).map_err(de::Error::custom)?;
+        }
+        Ok(())
+    }
+}
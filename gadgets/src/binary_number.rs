@@ -1,15 +1,20 @@
 //! The binary number chip implements functionality to represent any given value
 //! in binary bits, which can be compared against a value or expression for
 //! equality.
+//!
+//! The enums of tags of the circuits implement [`BinaryNumberTag`] with
+//! [`impl_binary_number_tag`](crate::impl_binary_number_tag), which derives the
+//! number of bits of their encoding from their largest tag.
 
 use crate::util::{and, not, Expr};
 use crate::Field;
+use eth_types::geth_types::TxType;
 use halo2_proofs::{
     circuit::{Region, Value},
     plonk::{Advice, Any, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
     poly::Rotation,
 };
-use std::{collections::BTreeSet, marker::PhantomData};
+use std::{collections::BTreeSet, fmt::Debug, marker::PhantomData};
 use strum::IntoEnumIterator;
 
 /// Helper trait that implements functionality to represent a generic type as
@@ -34,6 +39,55 @@ where
     }
 }
 
+/// Enum of tags of a column, encoded in binary by a [`BinaryNumberChip`] of
+/// [`Self::N_BITS`] bits, e.g. a `BinaryNumberConfig<T, { T::N_BITS }>`.
+pub trait BinaryNumberTag: Copy + Debug + Into<usize> + IntoEnumIterator {
+    /// Number of bits of the binary encoding of the tags.
+    const N_BITS: usize;
+
+    /// Assign the tags to `column` of a fixed table, each one at the row of
+    /// its value.
+    fn assign_fixed_table<F: Field>(
+        region: &mut Region<'_, F>,
+        column: Column<Fixed>,
+        name: &str,
+    ) -> Result<(), Error> {
+        for tag in Self::iter() {
+            let value: usize = tag.into();
+            region.assign_fixed(
+                || format!("assign {tag:?} in {name} fixed column"),
+                column,
+                value,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the number of bits of the binary encoding of values up to `max`.
+pub const fn num_bits(max: usize) -> usize {
+    if max == 0 {
+        1
+    } else {
+        (usize::BITS - max.leading_zeros()) as usize
+    }
+}
+
+/// Implements [`BinaryNumberTag`](crate::binary_number::BinaryNumberTag) for
+/// an enum of tags converted to `usize` by their discriminant, given its
+/// largest tag.
+#[macro_export]
+macro_rules! impl_binary_number_tag {
+    ($type:ty, $max:expr) => {
+        impl $crate::binary_number::BinaryNumberTag for $type {
+            const N_BITS: usize = $crate::binary_number::num_bits($max as usize);
+        }
+    };
+}
+
+impl_binary_number_tag!(TxType, TxType::Eip4844);
+
 /// Config for the binary number chip.
 #[derive(Clone, Copy, Debug)]
 pub struct BinaryNumberConfig<T, const N: usize> {
@@ -169,6 +223,12 @@ where
 
         // Disallow bit patterns (if any) that don't correspond to a variant of T.
         let valid_values: BTreeSet<usize> = T::iter().map(|t| from_bits(&t.as_bits())).collect();
+        assert_eq!(
+            valid_values.len(),
+            T::iter().count(),
+            "variants of {} don't fit in {N} bits",
+            std::any::type_name::<T>()
+        );
         let mut invalid_values = (0..1 << N).filter(|i| !valid_values.contains(i)).peekable();
         if invalid_values.peek().is_some() {
             meta.create_gate("binary number value in range", |meta| {
@@ -210,3 +270,31 @@ pub fn from_bits(bits: &[bool]) -> usize {
     bits.iter()
         .fold(0, |result, &bit| bit as usize + 2 * result)
 }
+
+#[cfg(test)]
+mod test {
+    use super::{num_bits, BinaryNumberChip, BinaryNumberTag};
+    use eth_types::geth_types::TxType;
+    use halo2_proofs::{halo2curves::bn256::Fr, plonk::ConstraintSystem};
+
+    #[test]
+    fn bits_of_tags() {
+        assert_eq!(num_bits(0), 1);
+        assert_eq!(num_bits(1), 1);
+        assert_eq!(num_bits(7), 3);
+        assert_eq!(num_bits(8), 4);
+        assert_eq!(TxType::N_BITS, 3);
+
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let selector = meta.fixed_column();
+        BinaryNumberChip::<Fr, TxType, { TxType::N_BITS }>::configure(&mut meta, selector, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "don't fit in 2 bits")]
+    fn tags_wider_than_bits() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let selector = meta.fixed_column();
+        BinaryNumberChip::<Fr, TxType, 2>::configure(&mut meta, selector, None);
+    }
+}
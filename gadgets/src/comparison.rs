@@ -0,0 +1,529 @@
+//! Comparison chip can be used to compare LT, EQ and GT for two unsigned
+//! integers of a width set at construction, from 8 to 256 bits.
+//!
+//! Two integers of more than [`MAX_LIMB_BYTES`] bytes don't fit together in a
+//! field element, so the operands are given as limbs, least significant
+//! first, whose sizes are [`limb_sizes`]. Each limb is compared with the `lt`
+//! and `diff` of the [`lt`] module, and the limbs are combined with
+//! [`lt_of_limbs`] and [`eq_of_limbs`]. These functions are shared with the
+//! comparison gadgets of the EVM circuit.
+
+use crate::{
+    bool_check,
+    is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
+    util::{sum, Expr},
+    Field,
+};
+use eth_types::{ToLittleEndian, U256};
+use halo2_proofs::{
+    circuit::{Chip, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, TableColumn, VirtualCells},
+    poly::Rotation,
+};
+use std::cmp::Ordering;
+
+/// Maximum number of bytes of a limb of the operands, so that the difference
+/// of two limbs plus its range fits in a field element.
+pub const MAX_LIMB_BYTES: usize = 16;
+
+/// Sizes in bytes of the limbs of operands of `num_bits` bits, least
+/// significant first. Panics unless `num_bits` is a multiple of 8 in
+/// `8..=256`.
+pub fn limb_sizes(num_bits: usize) -> Vec<usize> {
+    assert!(
+        num_bits % 8 == 0 && (8..=256).contains(&num_bits),
+        "comparison of {num_bits} bits operands, expected a multiple of 8 in 8..=256"
+    );
+    let num_bytes = num_bits / 8;
+    (0..num_bytes)
+        .step_by(MAX_LIMB_BYTES)
+        .map(|start| (num_bytes - start).min(MAX_LIMB_BYTES))
+        .collect()
+}
+
+/// `lhs < rhs` of operands of `N` bytes, witnessed by `lt`, `1` when
+/// `lhs < rhs` and `0` otherwise, and the bytes of `diff`, `lhs - rhs` when
+/// `lhs >= rhs` and `lhs - rhs + 256^N` otherwise. As `lt` is boolean and the
+/// operands are below `256^N`, `lhs - rhs == diff - lt * 256^N` only holds
+/// for the right `lt`.
+pub mod lt {
+    use crate::{
+        util::{expr_from_bytes, pow_of_two, Expr},
+        Field,
+    };
+    use halo2_proofs::plonk::Expression;
+
+    /// Returns the expression of `diff - lt * 256^N` which `lhs - rhs` is
+    /// constrained to equal, for the `N` bytes of `diff`.
+    pub fn expr<F: Field, E: Expr<F>>(lt: Expression<F>, diff_bytes: &[E]) -> Expression<F> {
+        expr_from_bytes(diff_bytes) - (lt * pow_of_two::<F>(diff_bytes.len() * 8))
+    }
+
+    /// Returns `lt` and the `num_bytes` bytes of `diff` of `lhs` and `rhs`.
+    pub fn value<F: Field>(lhs: F, rhs: F, num_bytes: usize) -> (bool, Vec<u8>) {
+        let lt = lhs < rhs;
+        let range = pow_of_two::<F>(num_bytes * 8);
+        let diff = (lhs - rhs) + (if lt { range } else { F::ZERO });
+        (lt, diff.to_repr()[..num_bytes].to_vec())
+    }
+}
+
+/// Returns `lhs < rhs` of operands split in limbs, from the `(lt, eq)` of the
+/// comparisons of their limbs, least significant first. The `eq` of the least
+/// significant limb is not used.
+pub fn lt_of_limbs<F: Field>(limbs: &[(Expression<F>, Expression<F>)]) -> Expression<F> {
+    let (first, rest) = limbs.split_first().expect("at least one limb");
+    rest.iter().fold(first.0.clone(), |lt, (limb_lt, limb_eq)| {
+        limb_lt.clone() + limb_eq.clone() * lt
+    })
+}
+
+/// Returns `lhs == rhs` of operands split in limbs, from the `eq` of the
+/// comparisons of their limbs.
+pub fn eq_of_limbs<F: Field>(limbs: &[(Expression<F>, Expression<F>)]) -> Expression<F> {
+    let (first, rest) = limbs.split_first().expect("at least one limb");
+    rest.iter()
+        .fold(first.1.clone(), |eq, (_, limb_eq)| limb_eq.clone() * eq)
+}
+
+/// Instruction that the Comparison chip needs to implement.
+pub trait ComparisonInstruction<F: Field> {
+    /// Assign the lhs and rhs witnesses to the Comparison chip's region,
+    /// returning how lhs compares to rhs.
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: U256,
+        rhs: U256,
+    ) -> Result<Ordering, Error>;
+}
+
+/// Config of the comparison of a limb of the operands.
+#[derive(Clone, Debug)]
+pub struct LimbConfig<F> {
+    /// Denotes the lt outcome of the limbs.
+    pub lt: Column<Advice>,
+    /// Denotes the bytes representation of the difference of the limbs.
+    pub diff: Vec<Column<Advice>>,
+    /// Config of the IsZero of the sum of the bytes of `diff`, which is zero
+    /// when the limbs are equal.
+    pub eq: IsZeroConfig<F>,
+}
+
+impl<F: Field> LimbConfig<F> {
+    fn expr(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Rotation,
+    ) -> (Expression<F>, Expression<F>) {
+        let diff = sum::expr(self.diff.iter().map(|c| meta.query_advice(*c, rotation)));
+        (
+            meta.query_advice(self.lt, rotation),
+            self.eq.expr_at(meta, rotation, diff),
+        )
+    }
+}
+
+/// Config for the Comparison chip.
+#[derive(Clone, Debug)]
+pub struct ComparisonConfig<F> {
+    /// Width of the operands in bits.
+    pub num_bits: usize,
+    /// Configs of the limbs of the operands, least significant first.
+    pub limbs: Vec<LimbConfig<F>>,
+    /// Denotes the range within which each byte of the differences should
+    /// lie.
+    pub u8_table: TableColumn,
+}
+
+impl<F: Field> ComparisonConfig<F> {
+    /// Returns an expression that denotes whether lhs < rhs, or not.
+    pub fn is_lt(&self, meta: &mut VirtualCells<F>, rotation: Rotation) -> Expression<F> {
+        lt_of_limbs(&self.limbs_expr(meta, rotation))
+    }
+
+    /// Returns an expression that denotes whether lhs == rhs, or not.
+    pub fn is_eq(&self, meta: &mut VirtualCells<F>, rotation: Rotation) -> Expression<F> {
+        eq_of_limbs(&self.limbs_expr(meta, rotation))
+    }
+
+    /// Returns an expression that denotes whether lhs > rhs, or not.
+    pub fn is_gt(&self, meta: &mut VirtualCells<F>, rotation: Rotation) -> Expression<F> {
+        let limbs = self.limbs_expr(meta, rotation);
+        1.expr() - lt_of_limbs(&limbs) - eq_of_limbs(&limbs)
+    }
+
+    fn limbs_expr(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Rotation,
+    ) -> Vec<(Expression<F>, Expression<F>)> {
+        self.limbs
+            .iter()
+            .map(|limb| limb.expr(meta, rotation))
+            .collect()
+    }
+}
+
+/// Chip that compares lhs and rhs of a width set at construction.
+#[derive(Clone, Debug)]
+pub struct ComparisonChip<F> {
+    config: ComparisonConfig<F>,
+}
+
+impl<F: Field> ComparisonChip<F> {
+    /// Configures the Comparison chip for operands of `num_bits` bits, whose
+    /// limbs of [`limb_sizes`] are returned by `lhs` and `rhs`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F> + Clone,
+        lhs: impl FnOnce(&mut VirtualCells<F>) -> Vec<Expression<F>>,
+        rhs: impl FnOnce(&mut VirtualCells<F>) -> Vec<Expression<F>>,
+        num_bits: usize,
+        u8_table: TableColumn,
+    ) -> ComparisonConfig<F> {
+        let sizes = limb_sizes(num_bits);
+        let columns: Vec<_> = sizes
+            .iter()
+            .map(|size| {
+                (
+                    meta.advice_column(),
+                    (0..*size).map(|_| meta.advice_column()).collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+
+        meta.create_gate("comparison gate", |meta| {
+            let q_enable = q_enable.clone()(meta);
+            let (lhs, rhs) = (lhs(meta), rhs(meta));
+            assert_eq!(lhs.len(), columns.len(), "lhs limbs of the comparison");
+            assert_eq!(rhs.len(), columns.len(), "rhs limbs of the comparison");
+
+            let mut constraints = Vec::with_capacity(2 * columns.len());
+            for ((lhs, rhs), (lt, diff)) in lhs.into_iter().zip(rhs).zip(&columns) {
+                let lt = meta.query_advice(*lt, Rotation::cur());
+                let diff_bytes: Vec<_> = diff
+                    .iter()
+                    .map(|c| meta.query_advice(*c, Rotation::cur()))
+                    .collect();
+                constraints.push(lhs - rhs - lt::expr(lt.clone(), &diff_bytes));
+                constraints.push(bool_check(lt));
+            }
+            constraints
+                .into_iter()
+                .map(move |poly| q_enable.clone() * poly)
+        });
+
+        let limbs = columns
+            .into_iter()
+            .map(|(lt, diff)| {
+                for cell_column in &diff {
+                    meta.lookup("range check for u8", |meta| {
+                        let q_enable = q_enable.clone()(meta);
+                        vec![(
+                            q_enable * meta.query_advice(*cell_column, Rotation::cur()),
+                            u8_table,
+                        )]
+                    });
+                }
+                let value_inv = meta.advice_column();
+                let eq = IsZeroChip::configure(
+                    meta,
+                    q_enable.clone(),
+                    |meta| sum::expr(diff.iter().map(|c| meta.query_advice(*c, Rotation::cur()))),
+                    value_inv,
+                );
+                LimbConfig { lt, diff, eq }
+            })
+            .collect();
+
+        ComparisonConfig {
+            num_bits,
+            limbs,
+            u8_table,
+        }
+    }
+
+    /// Constructs a Comparison chip given a config.
+    pub fn construct(config: ComparisonConfig<F>) -> ComparisonChip<F> {
+        ComparisonChip { config }
+    }
+}
+
+impl<F: Field> ComparisonInstruction<F> for ComparisonChip<F> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: U256,
+        rhs: U256,
+    ) -> Result<Ordering, Error> {
+        let config = self.config();
+        if lhs.bits().max(rhs.bits()) > config.num_bits {
+            return Err(Error::Synthesis);
+        }
+
+        let (lhs_bytes, rhs_bytes) = (lhs.to_le_bytes(), rhs.to_le_bytes());
+        let mut start = 0;
+        for (limb_idx, limb) in config.limbs.iter().enumerate() {
+            let end = start + limb.diff.len();
+            let limb_value = |bytes: &[u8]| {
+                let mut repr = [0u8; 32];
+                repr[..end - start].copy_from_slice(&bytes[start..end]);
+                F::from_repr(repr).unwrap()
+            };
+            let (lt, diff_bytes) = lt::value(
+                limb_value(&lhs_bytes),
+                limb_value(&rhs_bytes),
+                limb.diff.len(),
+            );
+
+            region.assign_advice(
+                || format!("comparison chip: lt of limb {limb_idx}"),
+                limb.lt,
+                offset,
+                || Value::known(F::from(lt as u64)),
+            )?;
+            for (idx, (diff_column, diff_byte)) in limb.diff.iter().zip(&diff_bytes).enumerate() {
+                region.assign_advice(
+                    || format!("comparison chip: diff byte {idx} of limb {limb_idx}"),
+                    *diff_column,
+                    offset,
+                    || Value::known(F::from(*diff_byte as u64)),
+                )?;
+            }
+            IsZeroChip::construct(limb.eq.clone()).assign(
+                region,
+                offset,
+                Value::known(sum::value(&diff_bytes)),
+            )?;
+            start = end;
+        }
+
+        Ok(lhs.cmp(&rhs))
+    }
+}
+
+impl<F: Field> Chip<F> for ComparisonChip<F> {
+    type Config = ComparisonConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{limb_sizes, ComparisonChip, ComparisonConfig, ComparisonInstruction};
+    use crate::{util::Expr, Field};
+    use eth_types::U256;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::bn256::Fr as Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+        poly::Rotation,
+    };
+    use std::{cmp::Ordering, marker::PhantomData};
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F> {
+        q_enable: Selector,
+        lhs: Vec<Column<Advice>>,
+        rhs: Vec<Column<Advice>>,
+        // 0 when lhs < rhs, 1 when lhs == rhs and 2 when lhs > rhs
+        check: Column<Advice>,
+        comparison: ComparisonConfig<F>,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F: Field, const NUM_BITS: usize> {
+        values: Vec<(U256, U256)>,
+        checks: Vec<Ordering>,
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: Field, const NUM_BITS: usize> Circuit<F> for TestCircuit<F, NUM_BITS> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let num_limbs = limb_sizes(NUM_BITS).len();
+            let lhs: Vec<_> = (0..num_limbs).map(|_| meta.advice_column()).collect();
+            let rhs: Vec<_> = (0..num_limbs).map(|_| meta.advice_column()).collect();
+            let check = meta.advice_column();
+            let u8_table = meta.lookup_table_column();
+
+            let comparison = ComparisonChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                |meta| {
+                    lhs.iter()
+                        .map(|c| meta.query_advice(*c, Rotation::cur()))
+                        .collect()
+                },
+                |meta| {
+                    rhs.iter()
+                        .map(|c| meta.query_advice(*c, Rotation::cur()))
+                        .collect()
+                },
+                NUM_BITS,
+                u8_table,
+            );
+
+            meta.create_gate("check comparison", |meta| {
+                let q_enable = meta.query_selector(q_enable);
+                let check = meta.query_advice(check, Rotation::cur());
+                let lt = comparison.is_lt(meta, Rotation::cur());
+                let gt = comparison.is_gt(meta, Rotation::cur());
+                // check == 1 - lt + gt
+                vec![q_enable * (check + lt - gt - 1.expr())]
+            });
+
+            TestCircuitConfig {
+                q_enable,
+                lhs,
+                rhs,
+                check,
+                comparison,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = ComparisonChip::construct(config.comparison.clone());
+
+            layouter.assign_table(
+                || "load u8 range check table",
+                |mut table| {
+                    for i in 0..=u8::MAX as usize {
+                        table.assign_cell(
+                            || "assign cell in fixed column",
+                            config.comparison.u8_table,
+                            i,
+                            || Value::known(F::from(i as u64)),
+                        )?;
+                    }
+                    Ok(())
+                },
+            )?;
+
+            layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    for (offset, ((lhs, rhs), check)) in
+                        self.values.iter().zip(&self.checks).enumerate()
+                    {
+                        config.q_enable.enable(&mut region, offset)?;
+                        let limbs = |value: &U256| {
+                            let bytes = eth_types::ToLittleEndian::to_le_bytes(value);
+                            let mut start = 0;
+                            limb_sizes(NUM_BITS)
+                                .into_iter()
+                                .map(|size| {
+                                    let mut repr = [0u8; 32];
+                                    repr[..size].copy_from_slice(&bytes[start..start + size]);
+                                    start += size;
+                                    F::from_repr(repr).unwrap()
+                                })
+                                .collect::<Vec<_>>()
+                        };
+                        for (columns, value) in [(&config.lhs, lhs), (&config.rhs, rhs)] {
+                            for (column, limb) in columns.iter().zip(limbs(value)) {
+                                region.assign_advice(
+                                    || "operand limb",
+                                    *column,
+                                    offset,
+                                    || Value::known(limb),
+                                )?;
+                            }
+                        }
+                        region.assign_advice(
+                            || "check",
+                            config.check,
+                            offset,
+                            || Value::known(F::from((*check as i8 + 1) as u64)),
+                        )?;
+                        chip.assign(&mut region, offset, *lhs, *rhs)?;
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn verify<const NUM_BITS: usize>(values: Vec<(U256, U256)>, checks: Vec<Ordering>) -> bool {
+        let circuit = TestCircuit::<Fp, NUM_BITS> {
+            values,
+            checks,
+            _marker: PhantomData,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        prover.verify().is_ok()
+    }
+
+    fn cases(max: U256) -> (Vec<(U256, U256)>, Vec<Ordering>) {
+        let values = vec![
+            (U256::zero(), U256::zero()),
+            (U256::zero(), U256::one()),
+            (U256::one(), U256::zero()),
+            (max - 1, max),
+            (max, max - 1),
+            (max, max),
+            (max >> 1, max),
+            (U256::one(), max),
+        ];
+        let checks = values.iter().map(|(lhs, rhs)| lhs.cmp(rhs)).collect();
+        (values, checks)
+    }
+
+    #[test]
+    fn compare_any_width() {
+        fn check<const NUM_BITS: usize>() {
+            let max = if NUM_BITS == 256 {
+                U256::MAX
+            } else {
+                (U256::one() << NUM_BITS) - 1
+            };
+            let (values, checks) = cases(max);
+            assert!(
+                verify::<NUM_BITS>(values.clone(), checks.clone()),
+                "{NUM_BITS} bits"
+            );
+
+            let wrong = checks.iter().map(|check| check.reverse()).collect();
+            assert!(!verify::<NUM_BITS>(values, wrong), "{NUM_BITS} bits");
+        }
+        check::<8>();
+        check::<64>();
+        check::<128>();
+        check::<136>();
+        check::<256>();
+    }
+
+    #[test]
+    fn limbs_of_widths() {
+        assert_eq!(limb_sizes(8), vec![1]);
+        assert_eq!(limb_sizes(128), vec![16]);
+        assert_eq!(limb_sizes(136), vec![16, 1]);
+        assert_eq!(limb_sizes(256), vec![16, 16]);
+        assert!(std::panic::catch_unwind(|| limb_sizes(12)).is_err());
+        assert!(std::panic::catch_unwind(|| limb_sizes(264)).is_err());
+    }
+}
@@ -9,6 +9,7 @@ use halo2_proofs::{
 
 use crate::{
     bool_check,
+    comparison::lt,
     util::{expr_from_bytes, pow_of_two, sum},
 };
 
@@ -126,7 +127,7 @@ impl<F: Field, const N_BYTES: usize> LtInstruction<F> for LtChip<F, N_BYTES> {
     ) -> Result<(), Error> {
         let config = self.config();
 
-        let lt = lhs < rhs;
+        let (lt, diff_bytes) = lt::value(lhs, rhs, N_BYTES);
         region.assign_advice(
             || "lt chip: lt",
             config.lt,
@@ -134,8 +135,6 @@ impl<F: Field, const N_BYTES: usize> LtInstruction<F> for LtChip<F, N_BYTES> {
             || Value::known(F::from(lt as u64)),
         )?;
 
-        let diff = (lhs - rhs) + (if lt { config.range } else { F::ZERO });
-        let diff_bytes = diff.to_repr();
         for (idx, diff_column) in config.diff.iter().enumerate() {
             region.assign_advice(
                 || format!("lt chip: diff byte {idx}"),
@@ -14,6 +14,7 @@
 pub mod batched_is_zero;
 pub mod binary_number;
 pub mod comparator;
+pub mod comparison;
 pub mod evm_word;
 pub mod is_equal;
 pub mod is_zero;
@@ -21,10 +22,12 @@ pub mod less_than;
 pub mod monotone;
 pub mod mul_add;
 pub mod range;
+pub mod u256;
 pub mod util;
 
 use eth_types::Address;
 use eth_types::DebugU256;
+use eth_types::FieldWord;
 use eth_types::ToWord;
 use eth_types::U256;
 use halo2_proofs::{
@@ -89,6 +92,12 @@ impl<F: Field> ToScalar<F> for Address {
     }
 }
 
+impl<F: Field> ToScalar<F> for FieldWord {
+    fn to_scalar(&self) -> Option<F> {
+        F::from_repr(self.to_le_bytes()).into()
+    }
+}
+
 impl<F: Field> ToScalar<F> for bool {
     fn to_scalar(&self) -> Option<F> {
         self.to_word().to_scalar()
@@ -0,0 +1,737 @@
+//! Chips of the 256-bit arithmetic of the EVM, modulo 2^256:
+//! - [`AddWordsChip`] checks `a + b == sum + carry * 2^256`, for ADD and for
+//!   SUB as `b + (a - b) == a + borrow * 2^256`.
+//! - [`MulAddChip`] checks `a * b + c == d (mod 2^256)`, for MUL with `c == 0`.
+//! - [`DivModChip`] checks `quotient * divisor + remainder == dividend` without
+//!   overflow and with `remainder < divisor`, for DIV and MOD.
+//!
+//! The words are given to the chips as 128-bit lo/hi halves, or 64-bit limbs
+//! for the operands of a product, each range checked with the u16 table.
+//!
+//! The circuit layout of the [`AddWordsChip`] is as follows:
+#[rustfmt::skip]
+// | q_enable | col0      | col1      | col2      | col3      |
+// |----------|-----------|-----------|-----------|-----------|
+// | 1        | a_lo      | a_hi      | b_lo      | b_hi      |
+// | 0        | sum_lo    | sum_hi    | carry_lo  | carry_hi  |
+// | 0        | -         | -         | -         | -         |
+// | 0        | -         | -         | -         | -         |
+// | 0        | -         | -         | -         | -         |
+// | 0        | -         | -         | -         | -         |
+// |----------|-----------|-----------|-----------|-----------|
+// last rows are padding to fit in 6 rows range_check_128 chip
+//
+// The circuit layout of the [`DivModChip`] is the one of the `MulAddChip` for
+// `quotient * divisor + remainder == dividend`, next to the columns of
+// `divisor_is_zero` and of `remainder < divisor` on the first row:
+//
+// | q_enable | mul_add (col0..col3)       | divisor_is_zero | remainder_lt_divisor |
+// |----------|----------------------------|-----------------|----------------------|
+// | 1        | quotient limbs             | divisor_inv     | lt, diff of lo/hi    |
+// | 0        | divisor limbs              | -               | -                    |
+// | 0        | remainder and dividend     | -               | -                    |
+// | 0..      | carries and padding        | -               | -                    |
+// |----------|----------------------------|-----------------|----------------------|
+
+use eth_types::Word;
+use halo2_proofs::{
+    circuit::{Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, TableColumn, VirtualCells},
+    poly::Rotation,
+};
+
+pub use crate::mul_add::{MulAddChip, MulAddConfig};
+use crate::{
+    bool_check,
+    comparison::{ComparisonChip, ComparisonConfig, ComparisonInstruction},
+    is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
+    range::{UIntRangeCheckChip, UIntRangeCheckInstruction},
+    util::{pow_of_two, split_u256, split_u256_limb64, sum, Expr},
+    Field,
+};
+
+/// Config for the AddWordsChip.
+#[derive(Clone, Debug)]
+pub struct AddWordsConfig<F> {
+    /// First of the columns which we use over multiple rows to represent the
+    /// schema described above.
+    pub col0: Column<Advice>,
+    /// Second of the columns which we use over multiple rows to represent the
+    /// schema described above.
+    pub col1: Column<Advice>,
+    /// Third of the columns which we use over multiple rows to represent the
+    /// schema described above.
+    pub col2: Column<Advice>,
+    /// Fourth of the columns which we use over multiple rows to represent the
+    /// schema described above.
+    pub col3: Column<Advice>,
+    /// Lookup table for the range checks.
+    pub u16_table: TableColumn,
+    /// Range check of a, b and sum which need to be in [0, 2^128)
+    pub range_check_128: UIntRangeCheckChip<F, { UIntRangeCheckChip::SIZE_U128 }, 6>,
+}
+
+impl<F: Field> AddWordsConfig<F> {
+    /// 128-bit lo-hi parts of `a` from the equation `a + b == sum`.
+    pub fn a_lo_hi_cur(&self, meta: &mut VirtualCells<'_, F>) -> (Expression<F>, Expression<F>) {
+        (
+            meta.query_advice(self.col0, Rotation::cur()),
+            meta.query_advice(self.col1, Rotation::cur()),
+        )
+    }
+
+    /// 128-bit lo-hi parts of `b` from the equation `a + b == sum`.
+    pub fn b_lo_hi_cur(&self, meta: &mut VirtualCells<'_, F>) -> (Expression<F>, Expression<F>) {
+        (
+            meta.query_advice(self.col2, Rotation::cur()),
+            meta.query_advice(self.col3, Rotation::cur()),
+        )
+    }
+
+    /// 128-bit lo-hi parts of `sum` from the equation `a + b == sum`.
+    pub fn sum_lo_hi_cur(&self, meta: &mut VirtualCells<'_, F>) -> (Expression<F>, Expression<F>) {
+        (
+            meta.query_advice(self.col0, Rotation::next()),
+            meta.query_advice(self.col1, Rotation::next()),
+        )
+    }
+
+    /// Carry of `a + b` over 2^256, the borrow of the subtraction.
+    pub fn carry_cur(&self, meta: &mut VirtualCells<'_, F>) -> Expression<F> {
+        meta.query_advice(self.col3, Rotation::next())
+    }
+}
+
+/// Chip to constrain a + b == sum + carry * 2^256.
+#[derive(Clone, Debug)]
+pub struct AddWordsChip<F> {
+    /// Config for the chip.
+    pub config: AddWordsConfig<F>,
+}
+
+impl<F: Field> AddWordsChip<F> {
+    /// Number of rows of an assignment of the chip.
+    pub const HEIGHT: usize = 6;
+
+    /// Configure the AddWords chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F> + Clone,
+        u16_table: TableColumn,
+    ) -> AddWordsConfig<F> {
+        let cols = [(); 4].map(|_| meta.advice_column());
+
+        meta.create_gate("add words gate", |meta| {
+            let q_enable = q_enable.clone()(meta);
+
+            let [a_lo, a_hi, b_lo, b_hi] = cols.map(|col| meta.query_advice(col, Rotation::cur()));
+            let [sum_lo, sum_hi, carry_lo, carry_hi] =
+                cols.map(|col| meta.query_advice(col, Rotation::next()));
+
+            // a_lo + b_lo, fits in 129 bits, decomposed in sum_lo (128 bits) and carry_lo.
+            let check_lo = a_lo + b_lo - (sum_lo + carry_lo.clone() * pow_of_two::<F>(128));
+            // a_hi + b_hi + carry_lo, fits in 129 bits, decomposed in sum_hi (128 bits) and
+            // carry_hi.
+            let check_hi =
+                a_hi + b_hi + carry_lo.clone() - (sum_hi + carry_hi.clone() * pow_of_two::<F>(128));
+
+            [
+                check_lo,
+                check_hi,
+                bool_check(carry_lo),
+                bool_check(carry_hi),
+            ]
+            .into_iter()
+            .map(move |poly| q_enable.clone() * poly)
+        });
+
+        // range check for a, b on first row and sum on second row
+        let range_check_128_config = UIntRangeCheckChip::configure(
+            meta,
+            q_enable,
+            |meta| {
+                cols.map(|col| meta.query_advice(col, Rotation::cur()))
+                    .into_iter()
+                    .chain([cols[0], cols[1]].map(|col| meta.query_advice(col, Rotation::next())))
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap()
+            },
+            u16_table,
+        );
+
+        AddWordsConfig {
+            col0: cols[0],
+            col1: cols[1],
+            col2: cols[2],
+            col3: cols[3],
+            u16_table,
+            range_check_128: UIntRangeCheckChip::construct(range_check_128_config),
+        }
+    }
+
+    /// Construct the AddWords chip given a configuration.
+    pub fn construct(config: AddWordsConfig<F>) -> Self {
+        Self { config }
+    }
+
+    /// Assign `a + b`, returning the sum modulo 2^256 and the carry.
+    pub fn add(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: Word,
+        b: Word,
+    ) -> Result<(Word, bool), Error> {
+        let (sum, carry) = a.overflowing_add(b);
+        self.assign(region, offset, [a, b, sum])?;
+        Ok((sum, carry))
+    }
+
+    /// Assign `a - b` as `b + (a - b) == a + borrow * 2^256`, returning the
+    /// difference modulo 2^256 and the borrow, so the difference is at the `b`
+    /// cells and `a` at the `sum` cells.
+    pub fn sub(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: Word,
+        b: Word,
+    ) -> Result<(Word, bool), Error> {
+        let (difference, borrow) = a.overflowing_sub(b);
+        self.assign(region, offset, [b, difference, a])?;
+        Ok((difference, borrow))
+    }
+
+    /// Assign witness data of `a + b == sum (mod 2^256)` to the AddWords chip.
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        words: [Word; 3],
+    ) -> Result<(), Error> {
+        let [a, b, sum] = words;
+
+        let (a_lo, a_hi) = split_u256(&a);
+        let (b_lo, b_hi) = split_u256(&b);
+        let (sum_lo, sum_hi) = split_u256(&sum);
+        // the halves are below 2^128, so their sums don't overflow
+        let carry_lo = (a_lo + b_lo) >> 128;
+        let carry_hi = (a_hi + b_hi + carry_lo) >> 128;
+
+        let cols = [
+            self.config.col0,
+            self.config.col1,
+            self.config.col2,
+            self.config.col3,
+        ];
+        let rows = [
+            [
+                ("a_lo", a_lo),
+                ("a_hi", a_hi),
+                ("b_lo", b_lo),
+                ("b_hi", b_hi),
+            ],
+            [
+                ("sum_lo", sum_lo),
+                ("sum_hi", sum_hi),
+                ("carry_lo", carry_lo),
+                ("carry_hi", carry_hi),
+            ],
+        ];
+        for (row, values) in rows.into_iter().enumerate() {
+            for (col, (name, value)) in cols.into_iter().zip(values) {
+                region.assign_advice(
+                    || name,
+                    col,
+                    offset + row,
+                    || Value::known(F::from_u128(value.as_u128())),
+                )?;
+            }
+        }
+        self.config.range_check_128.assign(
+            region,
+            offset,
+            [a_lo, a_hi, b_lo, b_hi, sum_lo, sum_hi].map(|x| F::from_u128(x.as_u128())),
+        )?;
+
+        // unused padding rows
+        for row in rows.len()..Self::HEIGHT {
+            for col in cols {
+                region.assign_advice(
+                    || "unused padding row",
+                    col,
+                    offset + row,
+                    || Value::known(F::ZERO),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Annotates columns of this gadget embedded within a circuit region.
+    pub fn annotate_columns_in_region(&self, region: &mut Region<F>, prefix: &str) {
+        [
+            (self.config.col0, "GADGET_ADD_WORDS_col0"),
+            (self.config.col1, "GADGET_ADD_WORDS_col1"),
+            (self.config.col2, "GADGET_ADD_WORDS_col2"),
+            (self.config.col3, "GADGET_ADD_WORDS_col3"),
+        ]
+        .iter()
+        .for_each(|(col, ann)| region.name_column(|| format!("{prefix}_{ann}"), *col));
+    }
+}
+
+/// Config for the DivModChip.
+#[derive(Clone, Debug)]
+pub struct DivModConfig<F> {
+    /// Config of `quotient * divisor + remainder == dividend`.
+    pub mul_add: MulAddConfig<F>,
+    /// Config of the IsZero of the sum of the limbs of the divisor.
+    pub divisor_is_zero: IsZeroConfig<F>,
+    /// Config of `remainder < divisor`.
+    pub remainder_lt_divisor: ComparisonConfig<F>,
+}
+
+impl<F: Field> DivModConfig<F> {
+    /// 128-bit lo-hi parts of the dividend.
+    pub fn dividend_lo_hi_cur(
+        &self,
+        meta: &mut VirtualCells<'_, F>,
+    ) -> (Expression<F>, Expression<F>) {
+        self.mul_add.d_lo_hi_cur(meta)
+    }
+
+    /// 128-bit lo-hi parts of the divisor.
+    pub fn divisor_lo_hi_cur(
+        &self,
+        meta: &mut VirtualCells<'_, F>,
+    ) -> (Expression<F>, Expression<F>) {
+        let (limb0, limb1, limb2, limb3) = self.mul_add.b_limbs_cur(meta);
+        (
+            limb0 + limb1 * pow_of_two::<F>(64),
+            limb2 + limb3 * pow_of_two::<F>(64),
+        )
+    }
+
+    /// 128-bit lo-hi parts of the result of DIV, the quotient or 0 when the
+    /// divisor is 0.
+    pub fn quotient_lo_hi_cur(
+        &self,
+        meta: &mut VirtualCells<'_, F>,
+    ) -> (Expression<F>, Expression<F>) {
+        let (limb0, limb1, limb2, limb3) = self.mul_add.a_limbs_cur(meta);
+        let divisor_is_not_zero = 1.expr() - self.divisor_is_zero.expr();
+        (
+            (limb0 + limb1 * pow_of_two::<F>(64)) * divisor_is_not_zero.clone(),
+            (limb2 + limb3 * pow_of_two::<F>(64)) * divisor_is_not_zero,
+        )
+    }
+
+    /// 128-bit lo-hi parts of the result of MOD, the remainder or 0 when the
+    /// divisor is 0.
+    pub fn remainder_lo_hi_cur(
+        &self,
+        meta: &mut VirtualCells<'_, F>,
+    ) -> (Expression<F>, Expression<F>) {
+        let (lo, hi) = self.mul_add.c_lo_hi_cur(meta);
+        let divisor_is_not_zero = 1.expr() - self.divisor_is_zero.expr();
+        (lo * divisor_is_not_zero.clone(), hi * divisor_is_not_zero)
+    }
+}
+
+/// Chip to constrain quotient * divisor + remainder == dividend, with
+/// remainder < divisor when divisor != 0.
+#[derive(Clone, Debug)]
+pub struct DivModChip<F> {
+    /// Config for the chip.
+    pub config: DivModConfig<F>,
+}
+
+impl<F: Field> DivModChip<F> {
+    /// Number of rows of an assignment of the chip.
+    pub const HEIGHT: usize = 8;
+
+    /// Configure the DivMod chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F> + Clone,
+        u8_table: TableColumn,
+        u16_table: TableColumn,
+    ) -> DivModConfig<F> {
+        let mul_add = MulAddChip::configure(meta, q_enable.clone(), u16_table);
+
+        let divisor_inv = meta.advice_column();
+        let divisor_is_zero = IsZeroChip::configure(
+            meta,
+            q_enable.clone(),
+            |meta| {
+                let (limb0, limb1, limb2, limb3) = mul_add.b_limbs_cur(meta);
+                sum::expr([limb0, limb1, limb2, limb3])
+            },
+            divisor_inv,
+        );
+
+        let divisor_lo_hi = |meta: &mut VirtualCells<'_, F>| {
+            let (limb0, limb1, limb2, limb3) = mul_add.b_limbs_cur(meta);
+            vec![
+                limb0 + limb1 * pow_of_two::<F>(64),
+                limb2 + limb3 * pow_of_two::<F>(64),
+            ]
+        };
+        let remainder_lt_divisor = ComparisonChip::configure(
+            meta,
+            q_enable.clone(),
+            |meta| {
+                let (lo, hi) = mul_add.c_lo_hi_cur(meta);
+                vec![lo, hi]
+            },
+            divisor_lo_hi,
+            256,
+            u8_table,
+        );
+
+        meta.create_gate("div mod gate", |meta| {
+            let q_enable = q_enable(meta);
+            let remainder_lt_divisor = remainder_lt_divisor.is_lt(meta, Rotation::cur());
+
+            // the product doesn't wrap around 2^256, and the remainder is
+            // reduced unless the divisor is 0.
+            [
+                mul_add.overflow.clone(),
+                (1.expr() - divisor_is_zero.expr()) * (1.expr() - remainder_lt_divisor),
+            ]
+            .into_iter()
+            .map(move |poly| q_enable.clone() * poly)
+        });
+
+        DivModConfig {
+            mul_add,
+            divisor_is_zero,
+            remainder_lt_divisor,
+        }
+    }
+
+    /// Construct the DivMod chip given a configuration.
+    pub fn construct(config: DivModConfig<F>) -> Self {
+        Self { config }
+    }
+
+    /// Assign `dividend / divisor` and `dividend % divisor`, returning the
+    /// results of DIV and MOD, both 0 when the divisor is 0.
+    pub fn div_mod(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        dividend: Word,
+        divisor: Word,
+    ) -> Result<(Word, Word), Error> {
+        if divisor.is_zero() {
+            self.assign(region, offset, [Word::zero(), divisor, dividend, dividend])?;
+            return Ok((Word::zero(), Word::zero()));
+        }
+        let (quotient, remainder) = dividend.div_mod(divisor);
+        self.assign(region, offset, [quotient, divisor, remainder, dividend])?;
+        Ok((quotient, remainder))
+    }
+
+    /// Assign witness data of `quotient * divisor + remainder == dividend` to
+    /// the DivMod chip.
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        words: [Word; 4],
+    ) -> Result<(), Error> {
+        let [_, divisor, remainder, _] = words;
+
+        MulAddChip::construct(self.config.mul_add.clone()).assign(region, offset, words)?;
+        IsZeroChip::construct(self.config.divisor_is_zero.clone()).assign(
+            region,
+            offset,
+            Value::known(
+                split_u256_limb64(&divisor)
+                    .iter()
+                    .fold(F::ZERO, |acc, limb| acc + F::from(limb.as_u64())),
+            ),
+        )?;
+        ComparisonChip::construct(self.config.remainder_lt_divisor.clone())
+            .assign(region, offset, remainder, divisor)?;
+
+        Ok(())
+    }
+
+    /// Annotates columns of this gadget embedded within a circuit region.
+    pub fn annotate_columns_in_region(&self, region: &mut Region<F>, prefix: &str) {
+        MulAddChip::construct(self.config.mul_add.clone())
+            .annotate_columns_in_region(region, &format!("{prefix}_GADGET_DIV_MOD"));
+        self.config
+            .divisor_is_zero
+            .annotate_columns_in_region(region, &format!("{prefix}_GADGET_DIV_MOD_divisor"));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AddWordsChip, AddWordsConfig, DivModChip, DivModConfig};
+    use crate::Field;
+    use eth_types::Word;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::bn256::Fr as Fp,
+        plonk::{Circuit, ConstraintSystem, Error, Selector, TableColumn},
+    };
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+    use std::marker::PhantomData;
+
+    #[derive(Clone, Copy, Debug)]
+    enum Op {
+        /// `a + b`, checked against `overflowing_add`.
+        Add(Word, Word),
+        /// `a - b`, checked against `overflowing_sub`.
+        Sub(Word, Word),
+        /// `dividend / divisor` and `dividend % divisor`, checked against the
+        /// EVM DIV and MOD.
+        DivMod(Word, Word),
+        /// `a + b == sum` assigned as is.
+        AddWords([Word; 3]),
+        /// `quotient * divisor + remainder == dividend` assigned as is.
+        DivModWords([Word; 4]),
+    }
+
+    #[derive(Clone)]
+    struct TestCircuitConfig<F> {
+        q_add: Selector,
+        q_div_mod: Selector,
+        add_words: AddWordsConfig<F>,
+        div_mod: DivModConfig<F>,
+        u8_table: TableColumn,
+        u16_table: TableColumn,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        ops: Vec<Op>,
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: Field> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_add = meta.complex_selector();
+            let q_div_mod = meta.complex_selector();
+            let u8_table = meta.lookup_table_column();
+            let u16_table = meta.lookup_table_column();
+            let add_words =
+                AddWordsChip::configure(meta, |meta| meta.query_selector(q_add), u16_table);
+            let div_mod = DivModChip::configure(
+                meta,
+                |meta| meta.query_selector(q_div_mod),
+                u8_table,
+                u16_table,
+            );
+            Self::Config {
+                q_add,
+                q_div_mod,
+                add_words,
+                div_mod,
+                u8_table,
+                u16_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let add_chip = AddWordsChip::construct(config.add_words);
+            let div_mod_chip = DivModChip::construct(config.div_mod);
+
+            for (name, column, size) in [
+                ("u8 table", config.u8_table, 1 << 8),
+                ("u16 table", config.u16_table, 1 << 16),
+            ] {
+                layouter.assign_table(
+                    || name,
+                    |mut table| {
+                        for i in 0..size {
+                            table.assign_cell(
+                                || format!("{name} row {i}"),
+                                column,
+                                i,
+                                || Value::known(F::from(i as u64)),
+                            )?;
+                        }
+                        Ok(())
+                    },
+                )?;
+            }
+
+            layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    let mut offset = 0;
+                    for op in &self.ops {
+                        match *op {
+                            Op::Add(a, b) => {
+                                config.q_add.enable(&mut region, offset)?;
+                                let result = add_chip.add(&mut region, offset, a, b)?;
+                                assert_eq!(result, a.overflowing_add(b));
+                                offset += AddWordsChip::<F>::HEIGHT;
+                            }
+                            Op::Sub(a, b) => {
+                                config.q_add.enable(&mut region, offset)?;
+                                let result = add_chip.sub(&mut region, offset, a, b)?;
+                                assert_eq!(result, a.overflowing_sub(b));
+                                offset += AddWordsChip::<F>::HEIGHT;
+                            }
+                            Op::AddWords(words) => {
+                                config.q_add.enable(&mut region, offset)?;
+                                add_chip.assign(&mut region, offset, words)?;
+                                offset += AddWordsChip::<F>::HEIGHT;
+                            }
+                            Op::DivMod(dividend, divisor) => {
+                                config.q_div_mod.enable(&mut region, offset)?;
+                                let result =
+                                    div_mod_chip.div_mod(&mut region, offset, dividend, divisor)?;
+                                let expected = if divisor.is_zero() {
+                                    (Word::zero(), Word::zero())
+                                } else {
+                                    (dividend / divisor, dividend % divisor)
+                                };
+                                assert_eq!(result, expected);
+                                offset += DivModChip::<F>::HEIGHT;
+                            }
+                            Op::DivModWords(words) => {
+                                config.q_div_mod.enable(&mut region, offset)?;
+                                div_mod_chip.assign(&mut region, offset, words)?;
+                                offset += DivModChip::<F>::HEIGHT;
+                            }
+                        }
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn verify(ops: Vec<Op>) -> Result<(), ()> {
+        let circuit = TestCircuit::<Fp> {
+            ops,
+            _marker: PhantomData,
+        };
+        let prover = MockProver::<Fp>::run(17, &circuit, vec![]).unwrap();
+        prover.verify_par().map_err(|_| ())
+    }
+
+    /// Edge words of the arithmetic, then random words of random widths.
+    fn sample_words(rng: &mut XorShiftRng, n: usize) -> Vec<Word> {
+        let edges = [
+            Word::zero(),
+            Word::one(),
+            Word::from(u64::MAX),
+            Word::from(u128::MAX),
+            Word::one() << 128,
+            Word::MAX,
+        ];
+        let random = (0..n).map(|_| {
+            let word = Word::from_little_endian(&rng.gen::<[u8; 32]>());
+            word >> rng.gen_range(0..256usize)
+        });
+        edges.into_iter().chain(random).collect()
+    }
+
+    fn rng() -> XorShiftRng {
+        XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ])
+    }
+
+    #[test]
+    fn add_sub_words() {
+        let mut rng = rng();
+        let words = sample_words(&mut rng, 10);
+        let ops = words
+            .iter()
+            .flat_map(|a| words.iter().map(move |b| (*a, *b)))
+            .flat_map(|(a, b)| [Op::Add(a, b), Op::Sub(a, b)])
+            .collect();
+        assert_eq!(verify(ops), Ok(()));
+
+        for (a, b) in words.iter().zip(words.iter().rev()) {
+            let (sum, _) = a.overflowing_add(*b);
+            for wrong_sum in [sum.overflowing_add(1.into()).0, sum ^ (Word::one() << 128)] {
+                assert_eq!(verify(vec![Op::AddWords([*a, *b, wrong_sum])]), Err(()));
+            }
+        }
+    }
+
+    #[test]
+    fn div_mod_words() {
+        let mut rng = rng();
+        let words = sample_words(&mut rng, 10);
+        let ops = words
+            .iter()
+            .flat_map(|a| words.iter().map(move |b| Op::DivMod(*a, *b)))
+            .collect();
+        assert_eq!(verify(ops), Ok(()));
+
+        for (dividend, divisor) in words.iter().zip(words.iter().rev()) {
+            if divisor.is_zero() {
+                continue;
+            }
+            let (quotient, remainder) = dividend.div_mod(*divisor);
+            // wrong quotient
+            let quotient_plus_one = quotient.overflowing_add(1.into()).0;
+            assert_eq!(
+                verify(vec![Op::DivModWords([
+                    quotient_plus_one,
+                    *divisor,
+                    remainder,
+                    *dividend
+                ])]),
+                Err(())
+            );
+            // remainder not reduced
+            if !quotient.is_zero() {
+                if let (remainder, false) = remainder.overflowing_add(*divisor) {
+                    assert_eq!(
+                        verify(vec![Op::DivModWords([
+                            quotient - 1,
+                            *divisor,
+                            remainder,
+                            *dividend
+                        ])]),
+                        Err(())
+                    );
+                }
+            }
+        }
+
+        // the product wraps around 2^256
+        let (quotient, divisor) = (Word::MAX, Word::from(2));
+        let dividend = quotient.overflowing_mul(divisor).0;
+        assert_eq!(
+            verify(vec![Op::DivModWords([
+                quotient,
+                divisor,
+                Word::zero(),
+                dividend
+            ])]),
+            Err(())
+        );
+    }
+}
@@ -18,7 +18,6 @@ import (
 	_ "github.com/ethereum/go-ethereum/eth/tracers/native"
 	"github.com/ethereum/go-ethereum/params"
 	"github.com/holiman/uint256"
-	"github.com/imdario/mergo"
 )
 
 func init() {
@@ -94,12 +93,13 @@ func FormatLogs(logs []logger.StructLog) []StructLogRes {
 }
 
 type Block struct {
-	Coinbase   common.Address `json:"coinbase"`
-	Timestamp  *hexutil.Big   `json:"timestamp"`
-	Number     *hexutil.Big   `json:"number"`
-	Difficulty *hexutil.Big   `json:"difficulty"`
-	GasLimit   *hexutil.Big   `json:"gas_limit"`
-	BaseFee    *hexutil.Big   `json:"base_fee"`
+	Coinbase    common.Address `json:"coinbase"`
+	Timestamp   *hexutil.Big   `json:"timestamp"`
+	Number      *hexutil.Big   `json:"number"`
+	Difficulty  *hexutil.Big   `json:"difficulty"`
+	GasLimit    *hexutil.Big   `json:"gas_limit"`
+	BaseFee     *hexutil.Big   `json:"base_fee"`
+	BlobBaseFee *hexutil.Big   `json:"blob_base_fee"`
 }
 
 type Account struct {
@@ -136,7 +136,9 @@ type TraceConfig struct {
 	Accounts      map[common.Address]Account `json:"accounts"`
 	Transactions  []Transaction              `json:"transactions"`
 	LoggerConfig  *logger.Config             `json:"logger_config"`
-	ChainConfig   *params.ChainConfig        `json:"chain_config"`
+	// CallTracerConfig is the config of the callTracer, e.g. {"withLog": true}
+	CallTracerConfig json.RawMessage     `json:"call_tracer_config"`
+	ChainConfig      *params.ChainConfig `json:"chain_config"`
 }
 
 func newUint64(val uint64) *uint64 { return &val }
@@ -171,7 +173,9 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 	}
 
 	if config.ChainConfig != nil {
-		mergo.Merge(&chainConfig, config.ChainConfig, mergo.WithOverride)
+		// The fork schedule is given in full, forks left unset are not activated
+		chainConfig = *config.ChainConfig
+		chainConfig.ChainID = new(big.Int).SetUint64(config.ChainID)
 	}
 
 	// Debug for Shanghai
@@ -236,6 +240,7 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		Difficulty:  toBigInt(config.Block.Difficulty),
 		Random:      &randao,
 		BaseFee:     toBigInt(config.Block.BaseFee),
+		BlobBaseFee: toBigInt(config.Block.BlobBaseFee),
 		GasLimit:    blockGasLimit,
 	}
 
@@ -268,13 +273,17 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 	executionResults := make([]*ExecutionResult, len(config.Transactions))
 	for i, message := range messages {
 		txContext := core.NewEVMTxContext(&message)
+		callTracerConfig := config.CallTracerConfig
+		if len(callTracerConfig) == 0 {
+			callTracerConfig = json.RawMessage("null")
+		}
 		muxTracerConfig := []byte(fmt.Sprintf(`
 		{
 			"structLogTracer": %s,
-			"callTracer": null,
+			"callTracer": %s,
 			"prestateTracer": null
 		}
-		`, loggerConfig))
+		`, loggerConfig, callTracerConfig))
 		muxTracer, err := tracers.DefaultDirectory.New("muxTracer", new(tracers.Context), muxTracerConfig)
 		if err != nil {
 			return nil, err
@@ -1,9 +1,10 @@
 use crate::{get_client, GenDataOutput};
 use bus_mapping::{
     circuit_input_builder::{
-        BuilderClient, CircuitInputBuilder, CircuitsParams, PrecompileEcParams,
+        BuilderClient, CircuitInputBuilder, CircuitsParams, PrecompileEcParams, N_PAIRING_PER_OP,
     },
     mock::BlockData,
+    precompile::MODEXP_SIZE_LIMIT,
 };
 use eth_types::geth_types::GethData;
 use halo2_proofs::{
@@ -45,9 +46,6 @@ use zkevm_circuits::{
     witness::{block_convert, Block},
 };
 
-/// TEST_MOCK_RANDOMNESS
-const TEST_MOCK_RANDOMNESS: u64 = 0x100;
-
 /// MAX_TXS
 const MAX_TXS: usize = 4;
 /// MAX_CALLDATA
@@ -92,11 +90,14 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
     max_keccak_rows: MAX_KECCAK_ROWS,
     max_poseidon_rows: MAX_POSEIDON_ROWS,
     max_vertical_circuit_rows: MAX_VERTICAL_CIRCUIT_ROWS,
+    max_blake2f_rounds: 0,
+    max_modexp_bytes: MODEXP_SIZE_LIMIT,
     max_rlp_rows: MAX_RLP_ROWS,
     max_ec_ops: PrecompileEcParams {
         ec_add: MAX_EC_ADD,
         ec_mul: MAX_EC_MUL,
         ec_pairing: MAX_EC_PAIRING,
+        ec_pairing_pairs: N_PAIRING_PER_OP,
     },
 };
 
@@ -146,13 +147,8 @@ pub static KECCAK_CIRCUIT_TEST: LazyLock<TokioMutex<IntegrationTest<TestKeccakCi
     LazyLock::new(|| TokioMutex::new(IntegrationTest::new("Keccak", KECCAK_CIRCUIT_DEGREE)));
 
 /// Integration test for Copy circuit
-pub static SUPER_CIRCUIT_TEST: LazyLock<
-    TokioMutex<
-        IntegrationTest<
-            SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, TEST_MOCK_RANDOMNESS>,
-        >,
-    >,
-> = LazyLock::new(|| TokioMutex::new(IntegrationTest::new("Super", SUPER_CIRCUIT_DEGREE)));
+pub static SUPER_CIRCUIT_TEST: LazyLock<TokioMutex<IntegrationTest<SuperCircuit<Fr>>>> =
+    LazyLock::new(|| TokioMutex::new(IntegrationTest::new("Super", SUPER_CIRCUIT_DEGREE)));
 
 /// Integration test for Exp circuit
 pub static EXP_CIRCUIT_TEST: LazyLock<TokioMutex<IntegrationTest<TestExpCircuit<Fr>>>> =
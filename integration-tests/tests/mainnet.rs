@@ -1,6 +1,7 @@
 #![allow(unused_mut)]
 use bus_mapping::{
-    circuit_input_builder::{BuilderClient, CircuitsParams, PrecompileEcParams},
+    circuit_input_builder::{BuilderClient, CircuitsParams, PrecompileEcParams, N_PAIRING_PER_OP},
+    precompile::MODEXP_SIZE_LIMIT,
     util::read_env_var,
     Error::JSONRpcError,
 };
@@ -37,6 +38,8 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
     max_keccak_rows: 0,
     max_poseidon_rows: 0,
     max_vertical_circuit_rows: 0,
+    max_blake2f_rounds: 0,
+    max_modexp_bytes: MODEXP_SIZE_LIMIT,
     max_exp_steps: 1000,
     max_evm_rows: 0,
     max_rlp_rows: 33000,
@@ -44,6 +47,7 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
         ec_add: 10,
         ec_mul: 10,
         ec_pairing: 4,
+        ec_pairing_pairs: N_PAIRING_PER_OP,
     },
 };
 
@@ -118,7 +122,7 @@ fn test_witness_block(block: &witness::Block) -> Vec<VerifyFailure> {
     } else if *CIRCUIT == "bytecode" {
         test_with::<BytecodeCircuit<Fr>>(block)
     } else if *CIRCUIT == "super" {
-        test_with::<SuperCircuit<Fr, 350, 2_000_000, 64, 0x1000>>(block)
+        test_with::<SuperCircuit<Fr>>(block)
     } else {
         unimplemented!()
     };
@@ -0,0 +1,111 @@
+//! Builder of contract calls with ABI-encoded calldata.
+
+use crate::{AddrOrWallet, MockTransaction};
+use eth_types::{Bytes, Word};
+use ethers_core::abi::{AbiParser, Function, Token, Tokenizable, Tokenize};
+
+/// Builder of a call to a contract function, given by its Solidity-style
+/// signature, e.g. `transfer(address,uint256)` or
+/// `function balanceOf(address owner) returns (uint256)`, whose calldata is
+/// ABI-encoded from the arguments. The call is wired into a
+/// [`MockTransaction`] with [`MockTransaction::call`]:
+///
+/// ```ignore
+/// let transfer = CallBuilder::new("transfer(address,uint256)")
+///     .arg(accs[1].address)
+///     .arg(Word::from(100));
+/// txs[0].from(accs[0].address).call(&transfer.to(accs[2].address));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CallBuilder {
+    function: Function,
+    args: Vec<Token>,
+    to: Option<AddrOrWallet>,
+    value: Word,
+}
+
+impl CallBuilder {
+    /// Create a call of the function of `signature`, without arguments.
+    /// Panics on an invalid signature.
+    pub fn new(signature: &str) -> Self {
+        let signature = signature.trim();
+        let signature = if signature.starts_with("function ") {
+            signature.to_string()
+        } else {
+            format!("function {signature}")
+        };
+        let function = AbiParser::default()
+            .parse_function(&signature)
+            .unwrap_or_else(|err| panic!("invalid function signature {signature}: {err}"));
+        Self {
+            function,
+            args: Vec::new(),
+            to: None,
+            value: Word::zero(),
+        }
+    }
+
+    /// Append an argument of the call.
+    pub fn arg<T: Tokenizable>(mut self, arg: T) -> Self {
+        self.args.push(arg.into_token());
+        self
+    }
+
+    /// Set all the arguments of the call, e.g. as a tuple.
+    pub fn args<T: Tokenize>(mut self, args: T) -> Self {
+        self.args = args.into_tokens();
+        self
+    }
+
+    /// Set the contract called.
+    pub fn to<T: Into<AddrOrWallet>>(mut self, to: T) -> Self {
+        self.to = Some(to.into());
+        self
+    }
+
+    /// Set the value transferred by the call.
+    pub fn value(mut self, value: Word) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Return the 4 bytes selector of the function.
+    pub fn selector(&self) -> [u8; 4] {
+        self.function.short_signature()
+    }
+
+    /// Return the ABI-encoded calldata of the call. Panics if the arguments
+    /// don't match the signature.
+    pub fn calldata(&self) -> Bytes {
+        self.function
+            .encode_input(&self.args)
+            .unwrap_or_else(|err| {
+                panic!(
+                    "invalid arguments {:?} of {}: {err}",
+                    self.args,
+                    self.function.signature()
+                )
+            })
+            .into()
+    }
+
+    /// Decode the return data of the call, following the outputs of the
+    /// signature.
+    pub fn decode_output(&self, output: &[u8]) -> Vec<Token> {
+        self.function
+            .decode_output(output)
+            .unwrap_or_else(|err| panic!("invalid output of {}: {err}", self.function.signature()))
+    }
+}
+
+impl MockTransaction {
+    /// Set the to, value and input fields of the MockTransaction from `call`.
+    pub fn call(&mut self, call: &CallBuilder) -> &mut Self {
+        if let Some(to) = &call.to {
+            self.to = Some(to.clone());
+        }
+        self.value = call.value;
+        self.input = call.calldata();
+        self
+    }
+}
@@ -0,0 +1,314 @@
+//! Prebuilt contract accounts, standing for common mainnet workloads in
+//! tests. They are set up from the [`TestContext`](crate::TestContext)
+//! closures with the [`MockAccount`] methods of this module, e.g.
+//! `accs[0].address(token).erc20([(holder, Word::from(100))])`.
+//!
+//! The functions of the contracts are called with calldata ABI-encoded by a
+//! [`CallBuilder`], and revert without data on an unknown selector or when
+//! their checks fail.
+
+use crate::{CallBuilder, MockAccount};
+use eth_types::{bytecode, Address, Bytecode, ToWord, Word};
+use ethers_core::{
+    abi::{self, Token},
+    utils::keccak256,
+};
+use std::sync::LazyLock;
+
+/// Code of an ERC-20 token subset, whose balance of an account is stored at
+/// the slot of its address:
+/// - `balanceOf(address) returns (uint256)`
+/// - `transfer(address,uint256) returns (bool)`, logging a `Transfer` event
+///   and reverting on insufficient balance.
+pub static ERC20_CODE: LazyLock<Bytecode> = LazyLock::new(|| {
+    let transfer_topic = Word::from_big_endian(&keccak256("Transfer(address,address,uint256)"));
+    dispatcher(&[
+        ("balanceOf(address)", &balance_of),
+        ("transfer(address,uint256)", &|revert| {
+            bytecode! {
+                JUMPDEST
+                PUSH1(0x24)
+                CALLDATALOAD // amount
+                CALLER
+                SLOAD // balance of the sender
+                DUP2
+                DUP2
+                LT
+                PUSH2(revert)
+                JUMPI
+                DUP2
+                SWAP1
+                SUB
+                CALLER
+                SSTORE
+                PUSH1(0x04)
+                CALLDATALOAD // recipient
+                DUP1
+                SLOAD
+                DUP3
+                ADD
+                SWAP1
+                SSTORE
+                // Transfer(sender, recipient, amount)
+                PUSH1(0x00)
+                MSTORE
+                PUSH1(0x04)
+                CALLDATALOAD
+                CALLER
+                PUSH32(transfer_topic)
+                PUSH1(0x20)
+                PUSH1(0x00)
+                LOG3
+                PUSH1(0x01)
+                PUSH1(0x00)
+                MSTORE
+                PUSH1(0x20)
+                PUSH1(0x00)
+                RETURN
+            }
+        }),
+    ])
+});
+
+/// Code of a WETH-like wrapper of ether, whose balance of an account is
+/// stored at the slot of its address:
+/// - `balanceOf(address) returns (uint256)`
+/// - `deposit()`, payable, crediting the value to the sender
+/// - `withdraw(uint256)`, sending the amount back to the sender and
+///   reverting on insufficient balance or failed transfer.
+pub static WETH_CODE: LazyLock<Bytecode> = LazyLock::new(|| {
+    dispatcher(&[
+        ("balanceOf(address)", &balance_of),
+        ("deposit()", &|_| {
+            bytecode! {
+                JUMPDEST
+                CALLER
+                SLOAD
+                CALLVALUE
+                ADD
+                CALLER
+                SSTORE
+                STOP
+            }
+        }),
+        ("withdraw(uint256)", &|revert| {
+            bytecode! {
+                JUMPDEST
+                PUSH1(0x04)
+                CALLDATALOAD // amount
+                CALLER
+                SLOAD // balance of the sender
+                DUP2
+                DUP2
+                LT
+                PUSH2(revert)
+                JUMPI
+                DUP2
+                SWAP1
+                SUB
+                CALLER
+                SSTORE
+                // send the amount to the sender
+                PUSH1(0x00)
+                PUSH1(0x00)
+                PUSH1(0x00)
+                PUSH1(0x00)
+                DUP5
+                CALLER
+                GAS
+                CALL
+                ISZERO
+                PUSH2(revert)
+                JUMPI
+                STOP
+            }
+        }),
+    ])
+});
+
+/// EIP-1967 slot of the implementation of a proxy.
+pub static PROXY_IMPLEMENTATION_SLOT: LazyLock<Word> = LazyLock::new(|| {
+    Word::from_big_endian(&keccak256("eip1967.proxy.implementation")) - Word::one()
+});
+
+/// Code of a proxy, forwarding all its calls with DELEGATECALL to the
+/// implementation stored at [`PROXY_IMPLEMENTATION_SLOT`], and returning or
+/// reverting with the return data of the implementation.
+pub static PROXY_CODE: LazyLock<Bytecode> = LazyLock::new(|| {
+    let forward = |success: usize| {
+        bytecode! {
+            CALLDATASIZE
+            PUSH1(0x00)
+            PUSH1(0x00)
+            CALLDATACOPY
+            PUSH1(0x00)
+            PUSH1(0x00)
+            CALLDATASIZE
+            PUSH1(0x00)
+            PUSH32(*PROXY_IMPLEMENTATION_SLOT)
+            SLOAD
+            GAS
+            DELEGATECALL
+            RETURNDATASIZE
+            PUSH1(0x00)
+            PUSH1(0x00)
+            RETURNDATACOPY
+            PUSH2(success)
+            JUMPI
+            RETURNDATASIZE
+            PUSH1(0x00)
+            REVERT
+        }
+    };
+    let success = forward(0).code().len();
+    let mut code = forward(success);
+    code.append(&bytecode! {
+        JUMPDEST
+        RETURNDATASIZE
+        PUSH1(0x00)
+        RETURN
+    });
+    code
+});
+
+/// Code of a contract reverting any call with `Error(reason)`, as a Solidity
+/// `revert(reason)`.
+pub fn reverting_code(reason: &str) -> Bytecode {
+    let data: Vec<u8> = keccak256("Error(string)")[..4]
+        .iter()
+        .copied()
+        .chain(abi::encode(&[Token::String(reason.to_string())]))
+        .collect();
+    let mut code = Bytecode::default();
+    for (index, chunk) in data.chunks(32).enumerate() {
+        let mut word = [0u8; 32];
+        word[..chunk.len()].copy_from_slice(chunk);
+        code.op_mstore(index * 32, Word::from_big_endian(&word));
+    }
+    code.append(&bytecode! {
+        PUSH2(data.len())
+        PUSH1(0x00)
+        REVERT
+    });
+    code
+}
+
+/// Code of a gas guzzler, storing to a new slot in a loop until it runs out
+/// of gas.
+pub static GAS_GUZZLER_CODE: LazyLock<Bytecode> = LazyLock::new(|| {
+    bytecode! {
+        JUMPDEST
+        GAS
+        DUP1
+        SSTORE
+        PUSH1(0x00)
+        JUMP
+    }
+});
+
+/// Body of `balanceOf(address) returns (uint256)`, for balances stored at the
+/// slot of their address.
+fn balance_of(_revert: usize) -> Bytecode {
+    bytecode! {
+        JUMPDEST
+        PUSH1(0x04)
+        CALLDATALOAD
+        SLOAD
+        PUSH1(0x00)
+        MSTORE
+        PUSH1(0x20)
+        PUSH1(0x00)
+        RETURN
+    }
+}
+
+/// Build a contract dispatching its calls on their selector to the body of
+/// the function of the signature. A body starts with a `JUMPDEST`, and is
+/// built from the position of a `JUMPDEST` reverting without data, to jump to
+/// when its checks fail.
+fn dispatcher(functions: &[(&str, &dyn Fn(usize) -> Bytecode)]) -> Bytecode {
+    let dispatch = |dests: &[usize]| {
+        let mut code = bytecode! {
+            PUSH1(0x00)
+            CALLDATALOAD
+            PUSH1(0xe0)
+            SHR
+        };
+        for ((signature, _), dest) in functions.iter().zip(dests) {
+            let selector = u32::from_be_bytes(CallBuilder::new(signature).selector());
+            code.append(&bytecode! {
+                DUP1
+                PUSH4(selector as u64)
+                EQ
+                PUSH2(*dest)
+                JUMPI
+            });
+        }
+        code
+    };
+    let revert_block = bytecode! {
+        JUMPDEST
+        PUSH1(0x00)
+        DUP1
+        REVERT
+    };
+
+    let revert = dispatch(&vec![0; functions.len()]).code().len();
+    let bodies: Vec<_> = functions.iter().map(|(_, body)| body(revert)).collect();
+    let mut dests = Vec::with_capacity(bodies.len());
+    let mut dest = revert + revert_block.code().len();
+    for body in &bodies {
+        dests.push(dest);
+        dest += body.code().len();
+    }
+
+    let mut code = dispatch(&dests);
+    code.append(&revert_block);
+    for body in &bodies {
+        code.append(body);
+    }
+    code
+}
+
+impl MockAccount {
+    /// Set the account up as an ERC-20 token, see [`ERC20_CODE`], with the
+    /// `balances` of its holders.
+    pub fn erc20<I: IntoIterator<Item = (Address, Word)>>(&mut self, balances: I) -> &mut Self {
+        self.code(ERC20_CODE.code()).storage(
+            balances
+                .into_iter()
+                .map(|(holder, balance)| (holder.to_word(), balance)),
+        )
+    }
+
+    /// Set the account up as a WETH-like wrapper of ether, see
+    /// [`WETH_CODE`], with the `balances` of its holders. The ether wrapped
+    /// is expected to be set as the balance of the account.
+    pub fn weth<I: IntoIterator<Item = (Address, Word)>>(&mut self, balances: I) -> &mut Self {
+        self.code(WETH_CODE.code()).storage(
+            balances
+                .into_iter()
+                .map(|(holder, balance)| (holder.to_word(), balance)),
+        )
+    }
+
+    /// Set the account up as a proxy of `implementation`, see
+    /// [`PROXY_CODE`].
+    pub fn proxy(&mut self, implementation: Address) -> &mut Self {
+        self.code(PROXY_CODE.code()).storage(std::iter::once((
+            *PROXY_IMPLEMENTATION_SLOT,
+            implementation.to_word(),
+        )))
+    }
+
+    /// Set the account up as a contract reverting with `reason`, see
+    /// [`reverting_code`].
+    pub fn reverting(&mut self, reason: &str) -> &mut Self {
+        self.code(reverting_code(reason).code())
+    }
+
+    /// Set the account up as a gas guzzler, see [`GAS_GUZZLER_CODE`].
+    pub fn gas_guzzler(&mut self) -> &mut Self {
+        self.code(GAS_GUZZLER_CODE.code())
+    }
+}
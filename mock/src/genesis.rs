@@ -0,0 +1,209 @@
+//! Tiny custom chains described by a geth style genesis plus a block of txs,
+//! so that end to end fixtures can be checked into the repo as plain JSON.
+
+use crate::{
+    test_ctx::{deployed_system_contract_for_test_env, gen_trace_config, verify_created_addresses},
+    MockBlock, MockTransaction,
+};
+use eth_types::{
+    geth_types::{Account, GethData},
+    Address, Block, Bytes, Error, Transaction, Word, H256,
+};
+use ethers_signers::{LocalWallet, Signer};
+#[cfg(feature = "scroll")]
+use external_tracer::l2trace;
+#[cfg(not(feature = "scroll"))]
+use external_tracer::trace;
+use external_tracer::LoggerConfig;
+use itertools::Itertools;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Subset of a geth genesis file: the chain id, the header fields of the
+/// genesis block and the allocated accounts.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Genesis {
+    /// Chain config
+    pub config: GenesisConfig,
+    /// Coinbase of the genesis block, inherited by the following block.
+    #[serde(default)]
+    pub coinbase: Address,
+    /// Timestamp of the genesis block
+    #[serde(default)]
+    pub timestamp: Word,
+    /// Gas limit of the genesis block, inherited by the following block.
+    pub gas_limit: Word,
+    /// Difficulty of the genesis block
+    #[serde(default)]
+    pub difficulty: Word,
+    /// Base fee of the genesis block, inherited by the following block.
+    #[serde(default)]
+    pub base_fee_per_gas: Word,
+    /// State root claimed by the genesis header. Not part of geth's format,
+    /// since geth computes it from `alloc`.
+    #[serde(default)]
+    pub state_root: H256,
+    /// Accounts in the genesis state
+    pub alloc: BTreeMap<Address, GenesisAccount>,
+}
+
+/// Chain config of a [`Genesis`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenesisConfig {
+    /// Chain id
+    pub chain_id: u64,
+}
+
+/// Account allocated in a [`Genesis`].
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GenesisAccount {
+    /// Nonce
+    #[serde(default)]
+    pub nonce: Word,
+    /// Balance
+    pub balance: Word,
+    /// EVM code
+    #[serde(default)]
+    pub code: Bytes,
+    /// Storage
+    #[serde(default)]
+    pub storage: HashMap<Word, Word>,
+}
+
+/// Block following a [`Genesis`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenesisChildBlock {
+    /// Timestamp
+    pub timestamp: Word,
+    /// State root claimed by the header
+    pub state_root: H256,
+    /// Transactions, all legacy ones.
+    pub transactions: Vec<GenesisChildTx>,
+}
+
+/// Legacy tx of a [`GenesisChildBlock`], signed at load time. The nonce is
+/// derived from the genesis state and the previous txs of the sender.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenesisChildTx {
+    /// Private key of the sender
+    pub secret_key: H256,
+    /// Callee, `None` for a contract creation.
+    pub to: Option<Address>,
+    /// Gas limit
+    pub gas_limit: Word,
+    /// Gas price
+    pub gas_price: Word,
+    /// Value
+    #[serde(default)]
+    pub value: Word,
+    /// Call data or init code
+    #[serde(default)]
+    pub data: Bytes,
+}
+
+impl Genesis {
+    /// Accounts of the genesis state.
+    pub fn accounts(&self) -> Vec<Account> {
+        self.alloc
+            .iter()
+            .map(|(address, account)| Account {
+                address: *address,
+                nonce: account.nonce,
+                balance: account.balance,
+                code: account.code.clone(),
+                storage: account.storage.clone(),
+            })
+            .collect()
+    }
+
+    /// Sign the txs of `block`, block number 1, and trace them on top of the
+    /// genesis state.
+    pub fn trace_child_block(&self, block: &GenesisChildBlock) -> Result<GethData, Error> {
+        let chain_id = self.config.chain_id;
+        let mut nonces: HashMap<Address, Word> = self
+            .alloc
+            .iter()
+            .map(|(address, account)| (*address, account.nonce))
+            .collect();
+        let transactions = block
+            .transactions
+            .iter()
+            .enumerate()
+            .map(|(idx, tx)| {
+                let wallet = LocalWallet::from_bytes(tx.secret_key.as_bytes())
+                    .map_err(|_| Error::Signature)?
+                    .with_chain_id(chain_id);
+                let nonce = nonces.entry(wallet.address()).or_default();
+                let mut mock_tx = MockTransaction::default();
+                mock_tx
+                    .transaction_idx(idx as u64)
+                    .nonce(*nonce)
+                    .from(wallet)
+                    .gas(tx.gas_limit)
+                    .gas_price(tx.gas_price)
+                    .value(tx.value)
+                    .input(tx.data.clone())
+                    .chain_id(chain_id);
+                if let Some(to) = tx.to {
+                    mock_tx.to(to);
+                }
+                *nonce += Word::one();
+                Ok(mock_tx.build())
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut mock_block = MockBlock::default();
+        mock_block
+            .number(1)
+            .author(self.coinbase)
+            .gas_limit(self.gas_limit)
+            .base_fee_per_gas(self.base_fee_per_gas)
+            .difficulty(self.difficulty)
+            .timestamp(block.timestamp)
+            .state_root(block.state_root)
+            .chain_id(chain_id);
+        mock_block.transactions.extend(transactions);
+        let eth_block = Block::<Transaction>::from(mock_block);
+
+        let accounts = self
+            .accounts()
+            .into_iter()
+            .chain(deployed_system_contract_for_test_env())
+            .collect_vec();
+        let trace_config = gen_trace_config(
+            chain_id,
+            eth_block.clone(),
+            accounts.clone(),
+            None,
+            LoggerConfig::default(),
+        )?;
+
+        #[cfg(feature = "scroll")]
+        let block_trace = l2trace(&trace_config)?;
+        #[cfg(feature = "scroll")]
+        let geth_traces = block_trace
+            .execution_results
+            .clone()
+            .into_iter()
+            .map(From::from)
+            .collect::<Vec<_>>();
+        #[cfg(not(feature = "scroll"))]
+        let geth_traces = trace(&trace_config)?;
+
+        verify_created_addresses(&eth_block, &geth_traces)?;
+
+        Ok(GethData {
+            chain_id,
+            history_hashes: vec![],
+            eth_block,
+            geth_traces,
+            accounts,
+            #[cfg(feature = "scroll")]
+            block_trace,
+        })
+    }
+}
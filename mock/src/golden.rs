@@ -0,0 +1,87 @@
+//! Registry of golden [`GethData`] fixtures: traces too expensive to generate
+//! in every test, which are checked in under `mock/golden` once and read back
+//! by the tests of all the circuits. A fixture missing there is generated and
+//! written on its first load, as all of them are when `UPDATE_GOLDEN` is set.
+
+use crate::{
+    test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+    TestContext,
+};
+use eth_types::{bytecode, geth_types::GethData, Error};
+use std::path::{Path, PathBuf};
+
+/// A golden fixture, generated by tracing a test scenario.
+#[derive(Debug, Clone, Copy)]
+pub struct GoldenFixture {
+    /// Name of the fixture, and of its file.
+    pub name: &'static str,
+    generate: fn() -> Result<GethData, Error>,
+}
+
+/// All the golden fixtures.
+pub static GOLDEN_FIXTURES: &[GoldenFixture] = &[GoldenFixture {
+    name: "deep_calls",
+    generate: deep_calls,
+}];
+
+impl GoldenFixture {
+    /// Path of the file of the fixture. The traces of l2geth differ from the
+    /// ones of geth, so the fixtures of the `scroll` feature are kept apart.
+    pub fn path(&self) -> PathBuf {
+        let suffix = if cfg!(feature = "scroll") {
+            ".scroll"
+        } else {
+            ""
+        };
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("golden")
+            .join(format!("{}{suffix}.json", self.name))
+    }
+
+    /// Load the fixture from its file, generating it if needed.
+    pub fn load(&self) -> Result<GethData, Error> {
+        let path = self.path();
+        if std::env::var_os("UPDATE_GOLDEN").is_none() && path.exists() {
+            return GethData::from_json_file(&path);
+        }
+        log::info!("generating golden fixture {}", path.display());
+        let data = (self.generate)()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(Error::IoError)?;
+        }
+        data.to_json_file(&path)?;
+        Ok(data)
+    }
+}
+
+/// Load the golden fixture `name`. Panics if no fixture has this name.
+pub fn golden(name: &str) -> Result<GethData, Error> {
+    GOLDEN_FIXTURES
+        .iter()
+        .find(|fixture| fixture.name == name)
+        .unwrap_or_else(|| panic!("unknown golden fixture {name}"))
+        .load()
+}
+
+/// A contract calling itself with all its gas, until the innermost call runs
+/// out of gas a few hundred calls deep.
+fn deep_calls() -> Result<GethData, Error> {
+    let code = bytecode! {
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        ADDRESS
+        GAS
+        CALL
+        STOP
+    };
+    TestContext::<2, 1>::new(
+        None,
+        account_0_code_account_1_no_code(code),
+        tx_from_1_to_0,
+        |block, _tx| block,
+    )
+    .map(GethData::from)
+}
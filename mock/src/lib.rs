@@ -1,19 +1,28 @@
 #![feature(lazy_cell)]
 //! Mock types and functions to generate GethData used for tests
 
-use eth_types::{address, bytecode, bytecode::Bytecode, word, Address, Bytes, Word};
+use eth_types::{
+    address, bytecode, bytecode::Bytecode, evm_types::OpcodeId, word, Address, Bytes, Word,
+};
 use ethers_signers::LocalWallet;
 use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 use std::sync::LazyLock;
 mod account;
 mod block;
+mod call;
+pub mod fixtures;
+pub mod genesis;
+pub mod golden;
+pub mod random_block;
 pub mod test_ctx;
 mod transaction;
 
 pub(crate) use account::MockAccount;
 pub(crate) use block::MockBlock;
-pub use test_ctx::TestContext;
+pub use call::CallBuilder;
+pub use random_block::{random_block, RandomBlockProfile};
+pub use test_ctx::{MultiBlockTestContext, TestContext};
 pub use transaction::{AddrOrWallet, MockTransaction, CORRECT_MOCK_TXS};
 
 /// Mock block gas limit
@@ -155,3 +164,22 @@ pub fn generate_mock_call_bytecode(params: MockCallBytecodeParams) -> Bytecode {
         STOP
     }
 }
+
+/// Generate init code which runs `constructor` and then deploys `runtime`.
+/// The runtime code is appended to the init code and copied to memory with
+/// CODECOPY before being returned, so `constructor` must not halt.
+pub fn generate_init_code(constructor: Bytecode, runtime: &Bytecode) -> Bytecode {
+    // PUSH2 len, DUP1, PUSH2 offset, PUSH1 0, CODECOPY, PUSH1 0, RETURN
+    const COPY_AND_RETURN_LEN: usize = 13;
+    let mut code = constructor;
+    let offset = code.code().len() + COPY_AND_RETURN_LEN;
+    code.push(2, runtime.code().len())
+        .write_op(OpcodeId::DUP1)
+        .push(2, offset)
+        .push(1, 0u64)
+        .write_op(OpcodeId::CODECOPY)
+        .push(1, 0u64)
+        .write_op(OpcodeId::RETURN)
+        .append(runtime);
+    code
+}
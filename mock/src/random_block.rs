@@ -0,0 +1,224 @@
+//! Deterministic pseudo-random blocks used for property testing the circuits
+//! beyond hand written test cases.
+
+use crate::{eth, TestContext, MOCK_ACCOUNTS, MOCK_WALLETS};
+use eth_types::{bytecode::Bytecode, Address, Bytes, Error};
+use ethers_signers::Signer;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+/// Number of contracts deployed in a [`random_block`].
+pub const RANDOM_BLOCK_CONTRACTS: usize = 3;
+/// Number of accounts in a [`random_block`]: the tx sender followed by the
+/// contracts.
+pub const RANDOM_BLOCK_ACCOUNTS: usize = RANDOM_BLOCK_CONTRACTS + 1;
+
+/// Number of snippets each contract is made of.
+const SNIPPETS_PER_CONTRACT: usize = 16;
+/// Memory offsets stay below this bound to keep memory expansion cheap.
+const MAX_MEMORY_OFFSET: u64 = 0x400;
+/// Maximum size of a memory range touched by a snippet.
+const MAX_MEMORY_SIZE: u64 = 0x80;
+/// Storage keys are drawn from a small space so slots get written repeatedly.
+const MAX_STORAGE_KEY: u64 = 8;
+/// Maximum calldata length of a tx.
+const MAX_CALLDATA_LEN: usize = 64;
+/// Gas passed to each nested call, capped by the 63/64 rule.
+const CALL_GAS: u64 = 0x10_0000;
+
+/// Opcode mix of the contracts of a [`random_block`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RandomBlockProfile {
+    /// MSTORE, MLOAD, CALLDATACOPY, CODECOPY and SHA3 on random offsets.
+    MemoryHeavy,
+    /// SSTORE and SLOAD on a small key space, including resets to zero which
+    /// trigger refunds.
+    StorageHeavy,
+    /// Each contract calls the next one with random call kinds, so txs to the
+    /// first contract go as deep as there are contracts.
+    CallDeep,
+    /// Snippets of all the other profiles.
+    Mixed,
+}
+
+impl RandomBlockProfile {
+    /// All the profiles.
+    pub const ALL: [Self; 4] = [
+        Self::MemoryHeavy,
+        Self::StorageHeavy,
+        Self::CallDeep,
+        Self::Mixed,
+    ];
+}
+
+/// Generate a block of `NTX` txs sent by the first of
+/// [`static@MOCK_WALLETS`] to contracts deployed at the first
+/// [`RANDOM_BLOCK_CONTRACTS`] of [`static@MOCK_ACCOUNTS`]. Contract codes, tx
+/// targets and calldata are derived from `seed` only, so a failing seed can
+/// be replayed.
+pub fn random_block<const NTX: usize>(
+    seed: u64,
+    profile: RandomBlockProfile,
+) -> Result<TestContext<RANDOM_BLOCK_ACCOUNTS, NTX>, Error> {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    let codes: Vec<Bytecode> = (0..RANDOM_BLOCK_CONTRACTS)
+        .map(|idx| random_contract(&mut rng, profile, idx))
+        .collect();
+    let tx_specs: Vec<(usize, Bytes)> = (0..NTX)
+        .map(|_| {
+            let target = rng.gen_range(0..RANDOM_BLOCK_CONTRACTS);
+            let input_len = rng.gen_range(0..=MAX_CALLDATA_LEN);
+            let input: Vec<u8> = (0..input_len).map(|_| rng.gen()).collect();
+            (target, input.into())
+        })
+        .collect();
+
+    TestContext::new(
+        None,
+        |mut accs| {
+            accs[0].address(MOCK_WALLETS[0].address()).balance(eth(10));
+            for (acc, (address, code)) in accs[1..].iter_mut().zip(MOCK_ACCOUNTS.iter().zip(codes))
+            {
+                acc.address(*address).balance(eth(1)).code(code);
+            }
+        },
+        |txs, accs| {
+            for (tx, (target, input)) in txs.into_iter().zip(tx_specs) {
+                tx.from(MOCK_WALLETS[0].clone())
+                    .to(accs[target + 1].address)
+                    .input(input);
+            }
+        },
+        |block, _txs| block.number(0xcafeu64),
+    )
+}
+
+fn random_contract(rng: &mut ChaCha20Rng, profile: RandomBlockProfile, idx: usize) -> Bytecode {
+    let next = MOCK_ACCOUNTS[..RANDOM_BLOCK_CONTRACTS]
+        .get(idx + 1)
+        .copied();
+    let mut code = Bytecode::default();
+    for _ in 0..SNIPPETS_PER_CONTRACT {
+        match profile {
+            RandomBlockProfile::MemoryHeavy => memory_snippet(rng, &mut code),
+            RandomBlockProfile::StorageHeavy => storage_snippet(rng, &mut code),
+            RandomBlockProfile::CallDeep => {
+                if rng.gen_bool(0.75) {
+                    arithmetic_snippet(rng, &mut code)
+                } else {
+                    memory_snippet(rng, &mut code)
+                }
+            }
+            RandomBlockProfile::Mixed => match rng.gen_range(0..4) {
+                0 => memory_snippet(rng, &mut code),
+                1 => storage_snippet(rng, &mut code),
+                2 => arithmetic_snippet(rng, &mut code),
+                _ => {
+                    if let Some(next) = next {
+                        call_snippet(rng, &mut code, next)
+                    }
+                }
+            },
+        }
+    }
+    if profile == RandomBlockProfile::CallDeep {
+        if let Some(next) = next {
+            for _ in 0..rng.gen_range(1..=2) {
+                call_snippet(rng, &mut code, next);
+            }
+        }
+    }
+    code.op_return(random_offset(rng), 0x20);
+    code
+}
+
+fn random_offset(rng: &mut ChaCha20Rng) -> u64 {
+    rng.gen_range(0..MAX_MEMORY_OFFSET)
+}
+
+fn random_size(rng: &mut ChaCha20Rng) -> u64 {
+    rng.gen_range(0..=MAX_MEMORY_SIZE)
+}
+
+fn memory_snippet(rng: &mut ChaCha20Rng, code: &mut Bytecode) {
+    let offset = random_offset(rng);
+    match rng.gen_range(0..6) {
+        0 => code.op_mstore(offset, rng.gen::<u64>()),
+        1 => code.op_mstore8(offset, rng.gen_range(0..0x100u64)),
+        2 => code.op_mload(offset).op_pop(),
+        3 => code.op_calldatacopy(
+            offset,
+            rng.gen_range(0..MAX_CALLDATA_LEN as u64),
+            random_size(rng),
+        ),
+        4 => code.op_codecopy(offset, 0u64, random_size(rng)),
+        _ => code.op_sha3(offset, random_size(rng)).op_pop(),
+    };
+}
+
+fn storage_snippet(rng: &mut ChaCha20Rng, code: &mut Bytecode) {
+    let key = rng.gen_range(0..MAX_STORAGE_KEY);
+    match rng.gen_range(0..3) {
+        0 => code.op_sstore(key, rng.gen::<u64>()),
+        // resetting a slot to zero triggers a refund
+        1 => code.op_sstore(key, 0u64),
+        _ => code.op_sload(key).op_pop(),
+    };
+}
+
+fn arithmetic_snippet(rng: &mut ChaCha20Rng, code: &mut Bytecode) {
+    let (a, b) = (rng.gen::<u64>(), rng.gen::<u64>());
+    match rng.gen_range(0..6) {
+        0 => code.op_add(a, b),
+        1 => code.op_mul(a, b),
+        2 => code.op_div(a, b),
+        3 => code.op_exp(a, rng.gen_range(0..0x100u64)),
+        4 => code.op_shl(rng.gen_range(0..0x100u64), a),
+        _ => code.op_byte(rng.gen_range(0..32u64), a),
+    }
+    .op_pop();
+}
+
+fn call_snippet(rng: &mut ChaCha20Rng, code: &mut Bytecode, callee: Address) {
+    let (args_offset, args_size) = (random_offset(rng), random_size(rng));
+    let (ret_offset, ret_size) = (random_offset(rng), random_size(rng));
+    match rng.gen_range(0..4) {
+        0 => code.op_call(
+            CALL_GAS,
+            callee,
+            0u64,
+            args_offset,
+            args_size,
+            ret_offset,
+            ret_size,
+        ),
+        1 => code.op_callcode(
+            CALL_GAS,
+            callee,
+            0u64,
+            args_offset,
+            args_size,
+            ret_offset,
+            ret_size,
+        ),
+        2 => code.op_delegatecall(
+            CALL_GAS,
+            callee,
+            args_offset,
+            args_size,
+            ret_offset,
+            ret_size,
+        ),
+        _ => code.op_staticcall(
+            CALL_GAS,
+            callee,
+            args_offset,
+            args_size,
+            ret_offset,
+            ret_size,
+        ),
+    }
+    .op_pop()
+    .op_returndatasize()
+    .op_pop();
+}
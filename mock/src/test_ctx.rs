@@ -1,13 +1,15 @@
 //! Mock types and functions to generate Test environments for ZKEVM tests
 
-use crate::{eth, MockAccount, MockBlock, MockTransaction, MOCK_WALLETS};
+use crate::{eth, CallBuilder, MockAccount, MockBlock, MockTransaction, MOCK_WALLETS};
 #[cfg(feature = "scroll")]
 use eth_types::l2_types::BlockTrace;
 use eth_types::{
+    evm_types::block_utils::{calculate_block_hash, NUM_PREV_BLOCK_ALLOWED},
     geth_types::{Account, BlockConstants, GethData},
     l2_predeployed::l1_gas_price_oracle,
-    BigEndianHash, Block, Bytecode, Error, Transaction, Word, H256,
+    Address, BigEndianHash, Block, Bytecode, Error, Transaction, Word, H256, U64,
 };
+use ethers_core::utils::get_contract_address;
 #[cfg(feature = "scroll")]
 use external_tracer::l2trace;
 #[cfg(not(feature = "scroll"))]
@@ -99,7 +101,7 @@ pub struct TestContext<const NACC: usize, const NTX: usize> {
     block_trace: BlockTrace,
 }
 
-fn deployed_system_contract_for_test_env() -> Vec<Account> {
+pub(crate) fn deployed_system_contract_for_test_env() -> Vec<Account> {
     if cfg!(feature = "scroll") {
         vec![l1_gas_price_oracle::default_contract_account()]
     } else {
@@ -224,6 +226,8 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
         #[cfg(not(feature = "scroll"))]
         let geth_traces = trace(&trace_config)?;
 
+        verify_created_addresses(&block, &geth_traces)?;
+
         Ok(Self {
             chain_id,
             accounts,
@@ -267,6 +271,63 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
         &self.block_trace
     }
 
+    /// Address of the contract deployed by the creation tx at `tx_idx`, or
+    /// `None` if that tx is not a creation.
+    pub fn created_address(&self, tx_idx: usize) -> Option<Address> {
+        let tx = &self.eth_block.transactions[tx_idx];
+        tx.to
+            .is_none()
+            .then(|| get_contract_address(tx.from, tx.nonce))
+    }
+
+    /// Split the txs of the context over `n` consecutive blocks, the first
+    /// one having the header of the context. Following blocks increment its
+    /// number and timestamp, and have the previous block as parent and as
+    /// last history hash. Block hashes are computed like scroll does, see
+    /// [`calculate_block_hash`].
+    pub fn with_blocks(self, n: usize) -> MultiBlockTestContext {
+        assert!(n > 0, "a multi block context needs at least one block");
+        let chain_id = self.chain_id;
+        let first_number = self.eth_block.number.unwrap_or_default();
+        let txs = &self.eth_block.transactions;
+        let mut parent_hash = self.eth_block.parent_hash;
+        let eth_blocks = (0..n)
+            .map(|idx| {
+                let number = first_number + U64::from(idx);
+                let hash =
+                    H256::from_uint(&calculate_block_hash(chain_id, number.as_u64().into()).1);
+                let mut block = self.eth_block.clone();
+                block.number = Some(number);
+                block.timestamp = self.eth_block.timestamp + Word::from(idx);
+                block.parent_hash = parent_hash;
+                block.hash = Some(hash);
+                block.transactions = txs[idx * txs.len() / n..(idx + 1) * txs.len() / n]
+                    .iter()
+                    .enumerate()
+                    .map(|(tx_idx, tx)| Transaction {
+                        block_number: Some(number),
+                        transaction_index: Some(tx_idx.into()),
+                        ..tx.clone()
+                    })
+                    .collect();
+                parent_hash = hash;
+                block
+            })
+            .collect();
+
+        MultiBlockTestContext {
+            chain_id,
+            accounts: self
+                .accounts
+                .iter()
+                .cloned()
+                .chain(deployed_system_contract_for_test_env())
+                .collect_vec(),
+            history_hashes: self.history_hashes,
+            eth_blocks,
+        }
+    }
+
     /// Returns a simple TestContext setup with a single tx executing the
     /// bytecode passed as parameters. The balances of the 2 accounts and
     /// addresses are the ones used in [`TestContext::
@@ -282,6 +343,133 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
             |block, _txs| block.number(0xcafeu64),
         )
     }
+
+    /// Returns a simple TestContext setup like
+    /// [`TestContext::simple_ctx_with_bytecode`], whose single tx makes
+    /// `call` to the contract of `bytecode`.
+    pub fn simple_ctx_with_call(
+        bytecode: Bytecode,
+        call: CallBuilder,
+    ) -> Result<TestContext<2, 1>, Error> {
+        TestContext::new(
+            None,
+            account_0_code_wallet_0_no_code(bytecode),
+            |mut txs, accs| {
+                txs[0]
+                    .from(MOCK_WALLETS[0].clone())
+                    .call(&call.to(accs[0].address));
+            },
+            |block, _txs| block.number(0xcafeu64),
+        )
+    }
+}
+
+/// Consecutive blocks sharing the accounts of a [`TestContext`], see
+/// [`TestContext::with_blocks`].
+///
+/// Blocks can't be traced upfront since each one runs on top of the state
+/// left by the previous ones, which is only known once they are executed. The
+/// caller is expected to trace them in order with [`Self::trace_block`].
+#[derive(Debug, Clone)]
+pub struct MultiBlockTestContext {
+    /// chain id
+    pub chain_id: u64,
+    /// Accounts before the first block, including the system contracts.
+    pub accounts: Vec<Account>,
+    /// history hashes of the first block
+    pub history_hashes: Vec<Word>,
+    /// Blocks, without traces.
+    pub eth_blocks: Vec<Block<Transaction>>,
+}
+
+impl MultiBlockTestContext {
+    /// Total number of txs over all the blocks.
+    pub fn num_txs(&self) -> usize {
+        self.eth_blocks
+            .iter()
+            .map(|block| block.transactions.len())
+            .sum()
+    }
+
+    /// History hashes of the block at `idx`: the ones of the first block
+    /// followed by the hashes of the previous blocks, keeping the most recent
+    /// 256 ones.
+    pub fn history_hashes(&self, idx: usize) -> Vec<Word> {
+        let history_hashes = self
+            .history_hashes
+            .iter()
+            .copied()
+            .chain(
+                self.eth_blocks[..idx]
+                    .iter()
+                    .map(|block| block.hash.unwrap_or_default().into_uint()),
+            )
+            .collect_vec();
+        let skip = history_hashes
+            .len()
+            .saturating_sub(NUM_PREV_BLOCK_ALLOWED as usize);
+        history_hashes[skip..].to_vec()
+    }
+
+    /// Trace the block at `idx` on top of `accounts`, the state left by the
+    /// previous blocks.
+    pub fn trace_block(&self, idx: usize, accounts: Vec<Account>) -> Result<GethData, Error> {
+        let eth_block = self.eth_blocks[idx].clone();
+        let history_hashes = self.history_hashes(idx);
+        let trace_config = gen_trace_config(
+            self.chain_id,
+            eth_block.clone(),
+            accounts.clone(),
+            Some(history_hashes.clone()),
+            LoggerConfig::default(),
+        )?;
+
+        #[cfg(feature = "scroll")]
+        let block_trace = l2trace(&trace_config)?;
+        #[cfg(feature = "scroll")]
+        let geth_traces = block_trace
+            .execution_results
+            .clone()
+            .into_iter()
+            .map(From::from)
+            .collect::<Vec<_>>();
+        #[cfg(not(feature = "scroll"))]
+        let geth_traces = trace(&trace_config)?;
+
+        verify_created_addresses(&eth_block, &geth_traces)?;
+
+        Ok(GethData {
+            chain_id: self.chain_id,
+            history_hashes,
+            eth_block,
+            geth_traces,
+            accounts,
+            #[cfg(feature = "scroll")]
+            block_trace,
+        })
+    }
+}
+
+/// Check that the constructors of creation txs ran at the address derived
+/// from the sender and its nonce.
+pub(crate) fn verify_created_addresses(
+    block: &Block<Transaction>,
+    geth_traces: &[eth_types::GethExecTrace],
+) -> Result<(), Error> {
+    for (idx, (tx, trace)) in block.transactions.iter().zip(geth_traces).enumerate() {
+        if tx.to.is_some() {
+            continue;
+        }
+        let expected = get_contract_address(tx.from, tx.nonce);
+        if let Some(created) = trace.call_trace.to() {
+            if created != expected {
+                return Err(Error::TracingError(format!(
+                    "tx {idx} created {created:?} instead of {expected:?}"
+                )));
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Generates config to generating execution traces for the transactions included in the provided
@@ -307,6 +495,8 @@ pub fn gen_trace_config(
             .map(eth_types::geth_types::Transaction::from)
             .collect(),
         logger_config,
+        call_tracer_config: Default::default(),
+        overrides: Default::default(),
         chain_config: None,
         #[cfg(feature = "scroll")]
         l1_queue_index: 0,
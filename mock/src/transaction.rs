@@ -2,8 +2,8 @@
 
 use super::{MOCK_ACCOUNTS, MOCK_CHAIN_ID};
 use eth_types::{
-    geth_types::Transaction as GethTransaction, word, AccessList, Address, Bytes, Hash,
-    Transaction, Word, U64,
+    geth_types::{Transaction as GethTransaction, TxType},
+    word, AccessList, Address, Bytes, Hash, Transaction, Word, U64,
 };
 use ethers_core::{
     rand::{CryptoRng, RngCore},
@@ -268,6 +268,14 @@ impl MockTransaction {
         self
     }
 
+    /// Turn the MockTransaction into a contract creation whose constructor is
+    /// `init_code`, e.g. built with [`generate_init_code`](crate::generate_init_code).
+    pub fn deploy<T: Into<Bytes>>(&mut self, init_code: T) -> &mut Self {
+        self.to = None;
+        self.input = init_code.into();
+        self
+    }
+
     /// Set value field for the MockTransaction.
     pub fn value(&mut self, value: Word) -> &mut Self {
         self.value = value;
@@ -306,6 +314,19 @@ impl MockTransaction {
         self
     }
 
+    /// Set the transaction_type field of the MockTransaction from `tx_type`,
+    /// so that [`Self::build`] signs it as a tx of this type. Only the
+    /// signed standard tx types can be built.
+    pub fn tx_type(&mut self, tx_type: TxType) -> &mut Self {
+        let transaction_type = match tx_type {
+            TxType::Eip155 => 0,
+            TxType::Eip2930 => 1,
+            TxType::Eip1559 => 2,
+            _ => panic!("mock tx of type {tx_type:?} can not be built"),
+        };
+        self.transaction_type(transaction_type)
+    }
+
     /// Set access_list field for the MockTransaction.
     pub fn access_list(&mut self, access_list: AccessList) -> &mut Self {
         self.access_list = access_list;
@@ -0,0 +1,71 @@
+//! Generate the EVM verifier of a circuit, or the calldata of a proof to it.
+//!
+//! Usage:
+//!   gen-verifier verifier <super|compression> <params_dir> <vk_file> <num_instance> <output_dir>
+//!   gen-verifier calldata <evm_proof_json_file> <output_file>
+//!
+//! `num_instance` is the comma separated number of instances of each instance
+//! column, e.g. `4` for a circuit with a single column of 4 instances. The
+//! verifier is dumped as `evm_verifier.yul` and `evm_verifier.bin` (`solc`
+//! must be in PATH) to `output_dir`.
+
+use anyhow::{bail, Context, Result};
+use halo2_proofs::halo2curves::bn256::Fr;
+use prover::{
+    common::Verifier,
+    config::LAYER4_CONFIG_PATH,
+    io::{deserialize_vk, from_json_file, read_all},
+    params::ParamsManager,
+    zkevm_circuits::super_circuit::params::ScrollSuperCircuit,
+    CircuitExt, CompressionCircuit, EvmProof,
+};
+use std::env;
+
+fn gen_verifier<C: CircuitExt<Fr>>(
+    params_dir: &str,
+    vk_file: &str,
+    num_instance: &str,
+    output_dir: &str,
+) -> Result<()> {
+    let vk = deserialize_vk::<C>(&read_all(vk_file));
+    let params = ParamsManager::from_env(params_dir).into_params(vk.get_domain().k())?;
+    let num_instance = num_instance
+        .split(',')
+        .map(str::parse)
+        .collect::<Result<Vec<usize>, _>>()
+        .context("invalid num_instance")?;
+
+    std::fs::create_dir_all(output_dir)?;
+    let deployment_code =
+        Verifier::<C>::new(params, vk).gen_evm_verifier_code(num_instance, Some(output_dir));
+    log::info!(
+        "generated EVM verifier of {} bytes in {output_dir}",
+        deployment_code.len()
+    );
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    prover::utils::init_env_and_log("gen_verifier");
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["verifier", "super", params_dir, vk_file, num_instance, output_dir] => {
+            gen_verifier::<ScrollSuperCircuit>(params_dir, vk_file, num_instance, output_dir)
+        }
+        ["verifier", "compression", params_dir, vk_file, num_instance, output_dir] => {
+            env::set_var("COMPRESSION_CONFIG", &*LAYER4_CONFIG_PATH);
+            gen_verifier::<CompressionCircuit>(params_dir, vk_file, num_instance, output_dir)
+        }
+        ["calldata", proof_file, output_file] => {
+            let evm_proof: EvmProof = from_json_file(proof_file)?;
+            std::fs::write(output_file, evm_proof.proof.calldata())?;
+            Ok(())
+        }
+        _ => bail!(
+            "usage:\n  gen-verifier verifier <super|compression> <params_dir> <vk_file> \
+             <num_instance> <output_dir>\n  gen-verifier calldata <evm_proof_json_file> \
+             <output_file>"
+        ),
+    }
+}
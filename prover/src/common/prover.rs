@@ -1,10 +1,10 @@
-use crate::utils::{load_params, param_path_for_degree};
+use crate::params::ParamsManager;
 use halo2_proofs::{
     halo2curves::bn256::{Bn256, G1Affine},
     plonk::ProvingKey,
-    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+    poly::kzg::commitment::ParamsKZG,
 };
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::{BTreeMap, HashMap};
 
 mod aggregation;
 mod chunk;
@@ -32,36 +32,11 @@ impl Prover {
     }
 
     pub fn from_params_dir(params_dir: &str, degrees: &[u32]) -> Self {
-        let degrees = BTreeSet::from_iter(degrees);
-        let max_degree = **degrees.last().unwrap();
-
-        // Downsize params if any params of degree doesn't exist.
-        let mut params_map = BTreeMap::new();
-        for d in BTreeSet::from_iter(degrees).into_iter().rev() {
-            let params = match load_params(params_dir, *d, None) {
-                Ok(params) => params,
-                Err(_) => {
-                    let params: &ParamsKZG<_> = params_map
-                        .first_key_value()
-                        .unwrap_or_else(|| {
-                            panic!(
-                                "File `{}` must exist",
-                                param_path_for_degree(params_dir, max_degree)
-                            )
-                        })
-                        .1;
-
-                    let mut params: ParamsKZG<_> = params.clone();
-                    params.downsize(*d);
-
-                    log::warn!("Optimization: download params{d} to params dir",);
-
-                    params
-                }
-            };
-
-            params_map.insert(*d, params);
-        }
+        // Params of missing degrees are downloaded, or downsized from the
+        // largest degree.
+        let params_map = ParamsManager::from_env(params_dir)
+            .load_all(degrees)
+            .unwrap_or_else(|err| panic!("Failed to load params from {params_dir}: {err}"));
 
         Self {
             params_map,
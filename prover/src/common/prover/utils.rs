@@ -28,6 +28,8 @@ impl Prover {
             desc,
             pk.get_vk().transcript_repr()
         );
+        // NOTE: the MSMs and FFTs of the proof run on CPU inside halo2_proofs,
+        // which exposes no hook to offload them, so there is no GPU backend.
         let snark = gen_snark_shplonk(params, pk, circuit, rng, None::<String>)?;
         Ok(snark)
     }
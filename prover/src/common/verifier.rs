@@ -1,4 +1,4 @@
-use crate::{io::deserialize_vk, utils::load_params};
+use crate::{io::deserialize_vk, params::ParamsManager};
 use halo2_proofs::{
     halo2curves::bn256::{Bn256, Fr, G1Affine},
     plonk::VerifyingKey,
@@ -33,7 +33,9 @@ impl<C: CircuitExt<Fr>> Verifier<C> {
     }
 
     pub fn from_params_dir(params_dir: &str, degree: u32, vk: &[u8]) -> Self {
-        let params = load_params(params_dir, degree, None).unwrap();
+        let params = ParamsManager::from_env(params_dir)
+            .into_params(degree)
+            .unwrap();
 
         Self::from_params(params, vk)
     }
@@ -7,4 +7,12 @@ impl<C: CircuitExt<Fr>> Verifier<C> {
     pub fn gen_evm_verifier(&self, evm_proof: &EvmProof, output_dir: Option<&str>) {
         crate::evm::gen_evm_verifier::<C>(&self.params, &self.vk, evm_proof, output_dir)
     }
+
+    pub fn gen_evm_verifier_code(
+        &self,
+        num_instance: Vec<usize>,
+        output_dir: Option<&str>,
+    ) -> Vec<u8> {
+        crate::evm::gen_evm_verifier_code::<C>(&self.params, &self.vk, num_instance, output_dir)
+    }
 }
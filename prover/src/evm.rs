@@ -16,6 +16,22 @@ pub fn gen_evm_verifier<C: CircuitExt<Fr>>(
     evm_proof: &EvmProof,
     output_dir: Option<&str>,
 ) {
+    let deployment_code =
+        gen_evm_verifier_code::<C>(params, vk, evm_proof.num_instance.clone(), output_dir);
+
+    let success = evm_proof.proof.evm_verify(deployment_code);
+    assert!(success);
+}
+
+/// Return the deployment code of the EVM verifier of the circuit of `vk` with
+/// `num_instance` instances, and dump its YUL and binary bytecode(use `solc`
+/// in PATH) to output_dir.
+pub fn gen_evm_verifier_code<C: CircuitExt<Fr>>(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    num_instance: Vec<usize>,
+    output_dir: Option<&str>,
+) -> Vec<u8> {
     let yul_file_path = output_dir.map(|dir| {
         let mut path = PathBuf::from_str(dir).unwrap();
         path.push("evm_verifier.yul");
@@ -26,7 +42,7 @@ pub fn gen_evm_verifier<C: CircuitExt<Fr>>(
     let deployment_code = snark_verifier_sdk::gen_evm_verifier::<C, Kzg<Bn256, Bdfg21>>(
         params,
         vk,
-        evm_proof.num_instance.clone(),
+        num_instance,
         yul_file_path.as_deref(),
     );
 
@@ -36,6 +52,5 @@ pub fn gen_evm_verifier<C: CircuitExt<Fr>>(
         write_file(&mut dir, "evm_verifier.bin", &deployment_code);
     }
 
-    let success = evm_proof.proof.evm_verify(deployment_code);
-    assert!(success);
+    deployment_code
 }
@@ -1,5 +1,5 @@
 use crate::{
-    common, config::INNER_DEGREE, io::deserialize_vk, utils::load_params,
+    common, config::INNER_DEGREE, io::deserialize_vk, params::ParamsManager,
     zkevm::circuit::TargetCircuit,
 };
 use halo2_proofs::plonk::keygen_vk;
@@ -19,7 +19,9 @@ impl<C: TargetCircuit> From<common::Verifier<C::Inner>> for Verifier<C> {
 
 impl<C: TargetCircuit> Verifier<C> {
     pub fn from_params_dir(params_dir: &str, raw_vk: Option<&[u8]>) -> Self {
-        let params = load_params(params_dir, *INNER_DEGREE, None).unwrap();
+        let params = ParamsManager::from_env(params_dir)
+            .into_params(*INNER_DEGREE)
+            .unwrap();
 
         let vk = raw_vk.map_or_else(
             || {
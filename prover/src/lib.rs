@@ -16,6 +16,7 @@ pub mod consts;
 mod evm;
 pub mod inner;
 pub mod io;
+pub mod params;
 pub mod proof;
 pub mod recursion;
 pub mod test;
@@ -0,0 +1,423 @@
+//! Management of the KZG setup params files: download of the missing degrees,
+//! verification, memory-mapped loading, and cache of the loaded params.
+//!
+//! A params file is verified against the SHA-256 hash pinned for its degree,
+//! or, when none is, against the setup pinned by [`PARAMS_G2_SECRET_POWER`]:
+//! its points must be the powers of the secret of the setup, which is checked
+//! with random linear combinations of them. The hash of a file verified
+//! against the setup is then pinned in the `params.sha256` file of its
+//! directory, for the next loads to only check it.
+
+use crate::utils::{
+    param_path_for_degree, params_file_len, DEFAULT_SERDE_FORMAT, PARAMS_G2_SECRET_POWER,
+};
+use anyhow::{bail, Context, Result};
+use halo2_proofs::{
+    arithmetic::best_multiexp,
+    halo2curves::{
+        bn256::{Bn256, Fr, G1Affine, G2Affine},
+        ff::Field,
+        group::{prime::PrimeCurveAffine, Curve},
+        pairing::Engine,
+    },
+    poly::{
+        commitment::{Params, ParamsProver},
+        kzg::commitment::ParamsKZG,
+        EvaluationDomain,
+    },
+};
+use memmap2::Mmap;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::Path,
+};
+
+/// Env var of the URL to download the missing params files from.
+pub const PARAMS_URL_ENV: &str = "PARAMS_URL";
+
+/// File of the SHA-256 hashes pinned for the params files of a directory, in
+/// the format of `sha256sum`.
+pub const PINNED_SHA256_FILE: &str = "params.sha256";
+
+/// Params files of a directory: `params{degree}` files downloaded from
+/// `{url}/params{degree}` when missing, and verified before being loaded.
+#[derive(Debug)]
+pub struct ParamsManager {
+    dir: String,
+    url: Option<String>,
+    sha256: HashMap<u32, String>,
+    /// `s_g2` of the setup the params are verified against
+    s_g2: String,
+    cache: BTreeMap<u32, ParamsKZG<Bn256>>,
+}
+
+impl ParamsManager {
+    /// Manage the params files of `dir`, without download.
+    pub fn new(dir: &str) -> Self {
+        Self {
+            dir: dir.to_string(),
+            url: None,
+            sha256: HashMap::new(),
+            s_g2: PARAMS_G2_SECRET_POWER.to_string(),
+            cache: BTreeMap::new(),
+        }
+    }
+
+    /// Manage the params files of `dir`, downloading the missing ones from the
+    /// URL of the `PARAMS_URL` env var, if set.
+    pub fn from_env(dir: &str) -> Self {
+        match std::env::var(PARAMS_URL_ENV) {
+            Ok(url) => Self::new(dir).with_url(&url),
+            Err(_) => Self::new(dir),
+        }
+    }
+
+    /// Download the missing params files from `url`.
+    pub fn with_url(self, url: &str) -> Self {
+        Self {
+            url: Some(url.trim_end_matches('/').to_string()),
+            ..self
+        }
+    }
+
+    /// Pin the SHA-256 hash, in hex, of the params file of `degree`, over the
+    /// one of the `params.sha256` file.
+    pub fn with_sha256(mut self, degree: u32, sha256: &str) -> Self {
+        self.sha256.insert(degree, sha256.to_lowercase());
+        self
+    }
+
+    /// Return the path of the params file of `degree`.
+    pub fn path(&self, degree: u32) -> String {
+        param_path_for_degree(&self.dir, degree)
+    }
+
+    /// Return the SHA-256 hash pinned for the params file of `degree`, if any.
+    pub fn pinned_sha256(&self, degree: u32) -> Result<Option<String>> {
+        if let Some(sha256) = self.sha256.get(&degree) {
+            return Ok(Some(sha256.clone()));
+        }
+        let path = Path::new(&self.dir).join(PINNED_SHA256_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let file_name = format!("params{degree}");
+        let pinned = fs::read_to_string(&path)?;
+        for line in pinned.lines() {
+            let Some((sha256, name)) = line.split_once(char::is_whitespace) else {
+                continue;
+            };
+            // `sha256sum` marks the names of the files hashed in binary mode
+            // with a `*`
+            if name.trim_start().trim_start_matches('*') == file_name {
+                return Ok(Some(sha256.to_lowercase()));
+            }
+        }
+        Ok(None)
+    }
+
+    fn pin_sha256(&self, degree: u32, sha256: &str) -> Result<()> {
+        let path = Path::new(&self.dir).join(PINNED_SHA256_FILE);
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(file, "{sha256}  params{degree}")
+            .with_context(|| format!("Failed to pin the params hash in {}", path.display()))
+    }
+
+    /// Return the path of the params file of `degree`, downloading and
+    /// verifying it if missing.
+    pub fn fetch(&self, degree: u32) -> Result<String> {
+        let path = self.path(degree);
+        if Path::new(&path).exists() {
+            return Ok(path);
+        }
+        let Some(url) = &self.url else {
+            bail!("Missing params file {path}, and no {PARAMS_URL_ENV} to download it from");
+        };
+
+        let url = format!("{url}/params{degree}");
+        let download_path = format!("{path}.download");
+        log::info!("Downloading params of degree {degree} from {url}");
+        fs::create_dir_all(&self.dir)?;
+        if let Err(err) = download(&url, &download_path)
+            .and_then(|()| self.read_verified(degree, &download_path).map(|_| ()))
+        {
+            let _ = fs::remove_file(&download_path);
+            return Err(err);
+        }
+        fs::rename(&download_path, &path)?;
+        Ok(path)
+    }
+
+    /// Verify the params file of `degree`.
+    pub fn verify(&self, degree: u32) -> Result<()> {
+        self.read_verified(degree, &self.path(degree)).map(|_| ())
+    }
+
+    /// Read the params file of `degree` at `path`, memory-mapped, and verify
+    /// it against its pinned SHA-256 hash, or against the setup when no hash
+    /// is pinned for its degree, pinning its hash once verified.
+    fn read_verified(&self, degree: u32, path: &str) -> Result<ParamsKZG<Bn256>> {
+        let file = File::open(path).with_context(|| format!("Failed to open params {path}"))?;
+        // SAFETY: the params files aren't modified while they're loaded.
+        let mmap = unsafe { Mmap::map(&file)? };
+        let expected_len = params_file_len(degree, DEFAULT_SERDE_FORMAT);
+        if mmap.len() as u64 != expected_len {
+            bail!(
+                "Params file {path} has {} bytes instead of {expected_len} for degree {degree}",
+                mmap.len()
+            );
+        }
+
+        let sha256 = hex::encode(Sha256::digest(&mmap[..]));
+        let pinned = self.pinned_sha256(degree)?;
+        if let Some(expected) = &pinned {
+            if &sha256 != expected {
+                bail!("Params file {path} has SHA-256 {sha256} instead of {expected}");
+            }
+        }
+        let params = ParamsKZG::<Bn256>::read_custom(&mut &mmap[..], DEFAULT_SERDE_FORMAT)?;
+        if params.k() != degree {
+            bail!(
+                "Params file {path} is of degree {} instead of {degree}",
+                params.k()
+            );
+        }
+        if pinned.is_none() {
+            log::info!("Verifying params of degree {degree} against the setup");
+            verify_setup(&params, &self.s_g2)
+                .with_context(|| format!("Params file {path} isn't of the setup"))?;
+            self.pin_sha256(degree, &sha256)?;
+        }
+        Ok(params)
+    }
+
+    /// Return the params of `degree`, loaded from its file, fetched if missing,
+    /// or downsized from the params of a larger degree when the file can't be
+    /// fetched. The loaded params are verified and cached.
+    pub fn load(&mut self, degree: u32) -> Result<&ParamsKZG<Bn256>> {
+        if self.cache.contains_key(&degree) {
+            return Ok(&self.cache[&degree]);
+        }
+        let params = match self.fetch(degree) {
+            Ok(path) => self.read_verified(degree, &path)?,
+            Err(err) => {
+                let Some((_, params)) = self.cache.range(degree..).next() else {
+                    return Err(err);
+                };
+                log::warn!("Optimization: download params{degree} to params dir");
+                let mut params = params.clone();
+                params.downsize(degree);
+                params
+            }
+        };
+        Ok(self.cache.entry(degree).or_insert(params))
+    }
+
+    /// Load the params of `degree` and return them.
+    pub fn into_params(mut self, degree: u32) -> Result<ParamsKZG<Bn256>> {
+        self.load(degree)?;
+        Ok(self.cache.remove(&degree).unwrap())
+    }
+
+    /// Load the params of all `degrees`, the largest first so that the others
+    /// can be downsized from it, and return them.
+    pub fn load_all(mut self, degrees: &[u32]) -> Result<BTreeMap<u32, ParamsKZG<Bn256>>> {
+        let mut degrees = degrees.to_vec();
+        degrees.sort_unstable_by(|a, b| b.cmp(a));
+        for degree in &degrees {
+            self.load(*degree)?;
+        }
+        self.cache.retain(|degree, _| degrees.contains(degree));
+        Ok(self.cache)
+    }
+}
+
+fn download(url: &str, path: &str) -> Result<()> {
+    // no timeout, as the params files of large degrees take long to download
+    let mut response = reqwest::blocking::Client::builder()
+        .timeout(None::<std::time::Duration>)
+        .build()?
+        .get(url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to download params from {url}"))?;
+    response.copy_to(&mut File::create(path)?)?;
+    Ok(())
+}
+
+/// Verify that `params` are of the setup of `s_g2`, i.e. that for its secret
+/// `s`, `g[i] = [s^i] G1` and `g_lagrange[i] = [L_i(s)] G1`, where `L_i` are
+/// the Lagrange polynomials of the domain of the params.
+///
+/// With random `r` and `c`, the powers are checked by the pairing
+/// `e(sum r_i g[i + 1], G2) = e(sum r_i g[i], [s] G2)`, and the Lagrange
+/// points by `sum c_i g_lagrange[i] = sum a_i g[i]`, where `a` are the
+/// coefficients of the polynomial of evaluations `c` on the domain.
+pub fn verify_setup(params: &ParamsKZG<Bn256>, s_g2: &str) -> Result<()> {
+    if params.g2() != G2Affine::generator() || format!("{:?}", params.s_g2()) != s_g2 {
+        bail!("The params are of another setup");
+    }
+    let g = params.get_g();
+    if g[0] != G1Affine::generator() {
+        bail!("The params don't start at the generator");
+    }
+
+    let r: Vec<_> = (1..g.len()).map(|_| Fr::random(OsRng)).collect();
+    let lhs = best_multiexp(&r, &g[1..]).to_affine();
+    let rhs = best_multiexp(&r, &g[..g.len() - 1]).to_affine();
+    if Bn256::pairing(&lhs, &params.g2()) != Bn256::pairing(&rhs, &params.s_g2()) {
+        bail!("The params aren't the powers of the secret of the setup");
+    }
+
+    let domain = EvaluationDomain::<Fr>::new(1, params.k());
+    let c: Vec<_> = (0..g.len()).map(|_| Fr::random(OsRng)).collect();
+    let a = domain.lagrange_to_coeff(domain.lagrange_from_vec(c.clone()));
+    if best_multiexp(&c, &params.g_lagrange()) != best_multiexp(&a, g) {
+        bail!("The Lagrange points of the params aren't of their powers");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+        thread,
+    };
+
+    const DEGREE: u32 = 4;
+
+    fn test_dir(name: &str) -> String {
+        let dir =
+            std::env::temp_dir().join(format!("params-manager-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.to_str().unwrap().to_string()
+    }
+
+    /// Bytes of the params of a new setup of `DEGREE`, and the `s_g2` of the
+    /// setup.
+    fn setup() -> (Vec<u8>, String) {
+        let params = ParamsKZG::<Bn256>::setup(DEGREE, OsRng);
+        let mut bytes = Vec::new();
+        params
+            .write_custom(&mut bytes, DEFAULT_SERDE_FORMAT)
+            .unwrap();
+        (bytes, format!("{:?}", params.s_g2()))
+    }
+
+    fn test_manager(dir: &str, s_g2: &str) -> ParamsManager {
+        ParamsManager {
+            s_g2: s_g2.to_string(),
+            ..ParamsManager::new(dir)
+        }
+    }
+
+    /// Swap the g1 points `i` and `j` of the params, counting the `g_lagrange`
+    /// ones after the `g` ones.
+    fn swap_points(bytes: &mut [u8], i: usize, j: usize) {
+        let point = |i: usize| 4 + 64 * i..4 + 64 * (i + 1);
+        let p = bytes[point(i)].to_vec();
+        bytes.copy_within(point(j), point(i).start);
+        bytes[point(j)].copy_from_slice(&p);
+    }
+
+    /// Serve `body` to `n` HTTP requests, and return the URL of the server.
+    fn serve(body: Vec<u8>, n: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming().take(n) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut line = String::new();
+                // skip the request up to its empty line
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                )
+                .unwrap();
+                stream.write_all(&body).unwrap();
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn verify_setup_of_params() {
+        let (bytes, s_g2) = setup();
+        let read = |bytes: &[u8]| {
+            ParamsKZG::<Bn256>::read_custom(&mut &bytes[..], DEFAULT_SERDE_FORMAT).unwrap()
+        };
+        assert!(verify_setup(&read(&bytes), &s_g2).is_ok());
+        assert!(verify_setup(&read(&bytes), PARAMS_G2_SECRET_POWER).is_err());
+
+        let n = 1 << DEGREE;
+        for (i, j) in [(1, 2), (n + 1, n + 2)] {
+            let mut tampered = bytes.clone();
+            swap_points(&mut tampered, i, j);
+            assert!(verify_setup(&read(&tampered), &s_g2).is_err());
+        }
+    }
+
+    #[test]
+    fn load_pins_verified_params() {
+        let dir = test_dir("pin");
+        let (bytes, s_g2) = setup();
+        let manager = test_manager(&dir, &s_g2);
+        fs::write(manager.path(DEGREE), &bytes).unwrap();
+
+        assert_eq!(manager.pinned_sha256(DEGREE).unwrap(), None);
+        manager.verify(DEGREE).unwrap();
+        let sha256 = hex::encode(Sha256::digest(&bytes));
+        assert_eq!(manager.pinned_sha256(DEGREE).unwrap(), Some(sha256));
+
+        // a file of the setup not matching the pinned hash is rejected
+        let (other, _) = setup();
+        fs::write(manager.path(DEGREE), other).unwrap();
+        assert!(manager.verify(DEGREE).is_err());
+    }
+
+    #[test]
+    fn load_rejects_unverified_params() {
+        let dir = test_dir("reject");
+        let (mut bytes, s_g2) = setup();
+        swap_points(&mut bytes, 3, 5);
+        let mut manager = test_manager(&dir, &s_g2);
+        fs::write(manager.path(DEGREE), &bytes).unwrap();
+        assert!(manager.load(DEGREE).is_err());
+        assert_eq!(manager.pinned_sha256(DEGREE).unwrap(), None);
+
+        let manager = manager.with_sha256(DEGREE, &"00".repeat(32));
+        assert!(manager.verify(DEGREE).is_err());
+    }
+
+    #[test]
+    fn fetch_verifies_downloads() {
+        let (bytes, s_g2) = setup();
+
+        let dir = test_dir("download");
+        let mut manager = test_manager(&dir, &s_g2).with_url(&serve(bytes.clone(), 1));
+        assert!(manager.load(DEGREE).is_ok());
+        assert!(Path::new(&manager.path(DEGREE)).exists());
+        assert!(manager.pinned_sha256(DEGREE).unwrap().is_some());
+
+        let dir = test_dir("tampered-download");
+        let mut tampered = bytes;
+        swap_points(&mut tampered, 1, 2);
+        let manager = test_manager(&dir, &s_g2).with_url(&serve(tampered, 1));
+        assert!(manager.fetch(DEGREE).is_err());
+        assert!(!Path::new(&manager.path(DEGREE)).exists());
+        assert!(!Path::new(&format!("{}.download", manager.path(DEGREE))).exists());
+    }
+}
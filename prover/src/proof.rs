@@ -70,6 +70,15 @@ impl Proof {
         dump_as_json(dir, filename, &self)
     }
 
+    /// Returns the calldata given to the EVM verifier.
+    /// Format: instances as concatenated U256 || Proof
+    pub fn calldata(&self) -> Vec<u8> {
+        let mut calldata = self.instances.clone();
+        calldata.extend_from_slice(&self.proof);
+
+        calldata
+    }
+
     pub fn evm_verify(&self, deployment_code: Vec<u8>) -> bool {
         verify_evm_proof(deployment_code, self.instances(), self.proof().to_vec())
     }
@@ -60,13 +60,7 @@ impl BundleProof {
     /// Returns the calldata given to YUL verifier.
     /// Format: Accumulator(12x32bytes) || PI(13x32bytes) || Proof
     pub fn calldata(self) -> Vec<u8> {
-        let proof = self.proof_to_verify();
-
-        // calldata = instances + proof
-        let mut calldata = proof.instances;
-        calldata.extend(proof.proof);
-
-        calldata
+        self.proof_to_verify().calldata()
     }
 
     pub fn dump(&self, dir: &str, name: &str) -> Result<()> {
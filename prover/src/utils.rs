@@ -35,6 +35,23 @@ pub const GIT_VERSION: &str = git_version!(args = ["--abbrev=7", "--always"]);
 
 pub const PARAMS_G2_SECRET_POWER: &str = "(Fq2 { c0: 0x17944351223333f260ddc3b4af45191b856689eda9eab5cbcddbbe570ce860d2, c1: 0x186282957db913abd99f91db59fe69922e95040603ef44c0bd7aa3adeef8f5ac }, Fq2 { c0: 0x297772d34bc9aa8ae56162486363ffe417b02dc7e8c207fc2cc20203e67a02ad, c1: 0x298adc7396bd3865cbf6d6df91bae406694e6d2215baa893bdeadb63052895f4 })";
 
+/// Length of the params file of `degree`:
+///   len: 4 bytes
+///   g: 2**DEGREE g1 points, each 32 bytes(256bits) if processed, 64 otherwise
+///   g_lagrange: 2**DEGREE g1 points
+///   g2: g2 point, twice as long as a g1 point
+///   s_g2: g2 point
+pub fn params_file_len(degree: u32, serde_fmt: SerdeFormat) -> u64 {
+    let g1_num = 2 * (1 << degree);
+    let g2_num = 2;
+    let g1_bytes_len = match serde_fmt {
+        SerdeFormat::Processed => 32,
+        SerdeFormat::RawBytes | SerdeFormat::RawBytesUnchecked => 64,
+    };
+    let g2_bytes_len = 2 * g1_bytes_len;
+    4 + g1_num * g1_bytes_len + g2_num * g2_bytes_len
+}
+
 /// Load setup params from a file.
 pub fn load_params(
     params_dir: &str,
@@ -53,23 +70,10 @@ pub fn load_params(
     }
     let f = File::open(params_path)?;
 
-    // check params file length:
-    //   len: 4 bytes
-    //   g: 2**DEGREE g1 points, each 32 bytes(256bits)
-    //   g_lagrange: 2**DEGREE g1 points, each 32 bytes(256bits)
-    //   g2: g2 point, 64 bytes
-    //   s_g2: g2 point, 64 bytes
+    // check params file length
     let file_size = f.metadata()?.len();
-    let g1_num = 2 * (1 << degree);
-    let g2_num = 2;
-
     let serde_fmt = serde_fmt.unwrap_or(DEFAULT_SERDE_FORMAT);
-    let g1_bytes_len = match serde_fmt {
-        SerdeFormat::Processed => 32,
-        SerdeFormat::RawBytes | SerdeFormat::RawBytesUnchecked => 64,
-    };
-    let g2_bytes_len = 2 * g1_bytes_len;
-    let expected_len = 4 + g1_num * g1_bytes_len + g2_num * g2_bytes_len;
+    let expected_len = params_file_len(degree, serde_fmt);
     if file_size != expected_len {
         bail!("invalid params file len {} for degree {}. check DEGREE or remove the invalid params file", file_size, degree);
     }
@@ -41,4 +41,8 @@ pub struct SuperCircuit {}
 
 impl TargetCircuit for SuperCircuit {
     type Inner = ScrollSuperCircuit;
+
+    fn from_witness_block(witness_block: &witness::Block) -> anyhow::Result<Self::Inner> {
+        Ok(ScrollSuperCircuit::new_from_block(witness_block).with_last_phase_evm_only())
+    }
 }
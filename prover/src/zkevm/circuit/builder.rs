@@ -1,7 +1,7 @@
 use crate::{utils::read_env_var, zkevm::SubCircuitRowUsage};
 use anyhow::{bail, Result};
 use bus_mapping::circuit_input_builder::CircuitInputBuilder;
-use eth_types::{l2_types::BlockTrace, ToWord};
+use eth_types::{geth_types::TxTypeSupport, l2_types::BlockTrace, ToWord};
 use itertools::Itertools;
 use mpt_zktrie::state::ZkTrieHash;
 use std::sync::LazyLock;
@@ -95,6 +95,21 @@ pub fn validite_block_traces(block_traces: &[BlockTrace]) -> Result<()> {
             *CHAIN_ID
         );
     }
+    for block_trace in block_traces {
+        for (index, tx) in block_trace.transactions.iter().enumerate() {
+            let support = TxTypeSupport::of(tx.type_ as u64);
+            if !support.supported {
+                bail!(
+                    "unsupported tx type {} ({}) of {}th tx in block {:?}, required feature {:?}",
+                    support.type_id,
+                    support.name,
+                    index,
+                    block_trace.header.number,
+                    support.required_feature
+                );
+            }
+        }
+    }
     Ok(())
 }
 
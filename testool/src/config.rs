@@ -19,6 +19,9 @@ pub struct TestSuite {
     pub paths: Vec<String>,
     pub max_gas: u64,
     pub max_steps: u64,
+    /// Maximum seconds of the witness generation of a test, unlimited if unset.
+    #[serde(default)]
+    pub witness_timeout: Option<u64>,
 
     ignore_tests: Option<Filter>,
     allow_tests: Option<Filter>,
@@ -31,6 +34,7 @@ impl Default for TestSuite {
             paths: vec![],
             max_gas: u64::MAX,
             max_steps: u64::MAX,
+            witness_timeout: None,
             ignore_tests: Some(Filter::any()),
             allow_tests: None,
         }
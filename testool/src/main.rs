@@ -9,7 +9,7 @@ mod utils;
 
 use crate::{config::TestSuite, statetest::ResultLevel};
 use anyhow::{bail, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use compiler::Compiler;
 use config::Config;
 use log::info;
@@ -25,6 +25,14 @@ use std::{
     time::SystemTime,
 };
 use strum_macros::EnumString;
+use utils::{set_test_fork, MainnetFork};
+use zkevm_circuits::{
+    super_circuit::params::ScrollSuperCircuit,
+    witness::{
+        debug::{render, RenderFormat, WitnessFilter, WitnessTable},
+        Block,
+    },
+};
 
 const REPORT_FOLDER: &str = "report";
 const CODEHASH_FILE: &str = "./codehash.txt";
@@ -40,10 +48,41 @@ enum Circuits {
     sc,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Render a table of a witness block written with --dump-witness, e.g.
+    /// `inspect --witness dir/test.json --table rws --filter tag=AccountStorage,key=0x1`
+    Inspect {
+        /// Witness block JSON file
+        #[clap(long)]
+        witness: PathBuf,
+
+        /// Table to render: rws, copyevents, steps or txs
+        #[clap(long, default_value = "rws")]
+        table: WitnessTable,
+
+        /// Comma separated name=value filters, with the names tag, id,
+        /// address, key, rwc (start..end), offset and limit
+        #[clap(long, default_value = "")]
+        filter: WitnessFilter,
+
+        /// Output format: text or html
+        #[clap(long, default_value = "text")]
+        format: RenderFormat,
+
+        /// File to write the rendered table to, stdout by default
+        #[clap(long)]
+        out: Option<PathBuf>,
+    },
+}
+
 /// EVM test vectors utility
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// Suite (by default is "default")
     #[clap(long, default_value = "default")]
     suite: String,
@@ -88,6 +127,34 @@ struct Args {
     #[clap(long)]
     exclude_test_ids: Option<String>,
 
+    /// Build every witness twice, with and without memory in the geth trace,
+    /// and fail tests whose witnesses differ
+    #[clap(long)]
+    check_mem_strict: bool,
+
+    /// Write the witness block of each test to this directory, to be
+    /// rendered with the inspect subcommand
+    #[clap(long)]
+    dump_witness: Option<PathBuf>,
+
+    /// Re-execute each step of the traces with an interpreter, compare its
+    /// stack, memory and storage against the witness builder and the geth
+    /// trace, and fail at the first difference. The geth trace is only
+    /// compared against with the enable-stack, enable-memory and
+    /// enable-storage features
+    #[clap(long)]
+    self_check: bool,
+
+    /// Do not execute any test, just print the height, cells and max
+    /// constraint degree of each EVM circuit gadget
+    #[clap(long)]
+    circuit_stats: bool,
+
+    /// Fork to run the tests at, e.g. "Berlin": selects the expectations of
+    /// the fillers, the fork schedule of the tracer and the gas costs
+    #[clap(long, default_value = "Cancun")]
+    fork: MainnetFork,
+
     /// Verbose
     #[clap(short, long)]
     v: bool,
@@ -139,7 +206,7 @@ fn run_single_test(
     log::info!("run single test {}", &test);
     let circuits_config = CircuitsConfig {
         verbose: true,
-        super_circuit: circuits_config.super_circuit,
+        ..circuits_config
     };
     //let trace = geth_trace(test.clone())?;
     //crate::utils::print_trace(trace)?;
@@ -147,6 +214,30 @@ fn run_single_test(
     Ok(())
 }
 
+fn print_circuit_stats() {
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use prettytable::Table;
+    use zkevm_circuits::evm_circuit::EvmCircuit;
+
+    let mut table = Table::new();
+    table.add_row(row!["state", "height", "max degree", "cells"]);
+    for report in EvmCircuit::<Fr>::gadget_report() {
+        let cells = report
+            .cells
+            .iter()
+            .map(|(cell_type, cells)| format!("{cell_type}: {cells}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        table.add_row(row![
+            format!("{:?}", report.state),
+            report.height,
+            report.max_degree,
+            cells
+        ]);
+    }
+    table.printstd();
+}
+
 fn go() -> Result<()> {
     //  RAYON_NUM_THREADS=1 RUST_BACKTRACE=1 cargo run -- --path
     // "tests/src/GeneralStateTestsFiller/**/" --skip-state-circuit
@@ -155,10 +246,52 @@ fn go() -> Result<()> {
 
     let args = Args::parse();
 
+    if args.circuit_stats {
+        print_circuit_stats();
+        return Ok(());
+    }
+
+    if let Some(Command::Inspect {
+        witness,
+        table,
+        filter,
+        format,
+        out,
+    }) = &args.command
+    {
+        let block = Block::from_json_file(witness)?;
+        let rendered = render(&block, *table, filter, *format);
+        match out {
+            Some(out) => std::fs::write(out, rendered)?,
+            None => print!("{rendered}"),
+        }
+        return Ok(());
+    }
+
+    set_test_fork(args.fork)?;
+
     let mut circuits_config = CircuitsConfig::default();
     if args.circuits == Some(Circuits::sc) {
         circuits_config.super_circuit = true;
     }
+    circuits_config.check_mem_strict = args.check_mem_strict;
+    circuits_config.self_check = args.self_check;
+    if args.self_check
+        && !cfg!(all(
+            feature = "enable-stack",
+            feature = "enable-memory",
+            feature = "enable-storage"
+        ))
+    {
+        log::warn!(
+            "--self-check without the enable-stack, enable-memory and enable-storage features \
+             doesn't compare against the geth trace, only against the witness builder"
+        );
+    }
+    if let Some(dir) = &args.dump_witness {
+        std::fs::create_dir_all(dir)?;
+        circuits_config.dump_witness = Some(dir.clone());
+    }
 
     if let Some(oneliner) = &args.oneliner {
         let test = StateTest::parse_oneline_spec(oneliner)?;
@@ -242,6 +375,11 @@ fn go() -> Result<()> {
     if args.report {
         let git_hash = utils::current_git_commit()?;
         let git_submodule_tests_hash = utils::current_submodule_git_commit()?;
+        let fingerprint = format!(
+            "{:?}",
+            ScrollSuperCircuit::fingerprint(utils::ETH_CHAIN_ID)?
+        );
+        info!("super circuit fingerprint {fingerprint}");
         let timestamp = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .unwrap()
@@ -292,9 +430,12 @@ fn go() -> Result<()> {
                     .tests
                     .retain(|_, test| !whitelist_levels.contains(&test.level));
             } else {
-                // by default skip ignore and success tests
+                // by default skip ignore, capacity exceeded and success tests
                 previous_results.tests.retain(|_, test| {
-                    test.level == ResultLevel::Ignored || test.level == ResultLevel::Success
+                    matches!(
+                        test.level,
+                        ResultLevel::Ignored | ResultLevel::CapacityExceeded | ResultLevel::Success
+                    )
                 });
             }
 
@@ -329,7 +470,10 @@ fn go() -> Result<()> {
             None
         };
         let report = previous_results.report(previous);
-        std::fs::write(&html_filename, report.gen_html(git_submodule_tests_hash)?)?;
+        std::fs::write(
+            &html_filename,
+            report.gen_html(git_submodule_tests_hash, fingerprint)?,
+        )?;
 
         report.print_tty()?;
         info!("{}", html_filename);
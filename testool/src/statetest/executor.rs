@@ -1,21 +1,34 @@
-use super::{AccountMatch, StateTest, StateTestResult};
-use crate::{config::TestSuite, utils::ETH_CHAIN_ID};
-use bus_mapping::circuit_input_builder::{CircuitInputBuilder, CircuitsParams, PrecompileEcParams};
+use super::{witness_diff::diff_witness_blocks, AccountMatch, StateTest, StateTestResult};
+use crate::{
+    config::TestSuite,
+    utils::{test_fork, ETH_CHAIN_ID},
+};
+use bus_mapping::circuit_input_builder::{
+    BuildMonitor, CircuitInputBuilder, CircuitsParams, PrecompileEcParams,
+};
 use eth_types::{
-    geth_types, state_db::CodeDB, Address, Bytes, GethExecTrace, ToBigEndian, ToWord, H256, U256,
-    U64,
+    evm_types::block_utils::calc_blob_base_fee, geth_types, state_db::CodeDB, tx_envelope, Address,
+    Bytes, GethExecTrace, ToBigEndian, ToWord, H256, U256, U64,
 };
 use ethers_core::utils::keccak256;
 use ethers_signers::LocalWallet;
 use external_tracer::{LoggerConfig, TraceConfig};
 use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr, plonk::Circuit};
 use itertools::Itertools;
-use std::{collections::BTreeMap, env, str::FromStr, sync::LazyLock};
+use std::{
+    collections::BTreeMap,
+    env,
+    path::PathBuf,
+    str::FromStr,
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 use zkevm_circuits::{
     bytecode_circuit::circuit::BytecodeCircuit,
     ecc_circuit::EccCircuit,
     modexp_circuit::ModExpCircuit,
+    prover_input::{self, ProverInput},
     sig_circuit::SigCircuit,
     super_circuit::params::{
         get_sub_circuit_limit_and_confidence, get_super_circuit_params, ScrollSuperCircuit,
@@ -56,6 +69,8 @@ pub enum StateTestError {
     SkipTestMaxGasLimit(u64),
     #[error("SkipTestMaxSteps({0})")]
     SkipTestMaxSteps(usize),
+    #[error("SkipTestWitnessTimeout(steps:{steps}, rws:{rws})")]
+    SkipTestWitnessTimeout { steps: usize, rws: usize },
     #[error("SkipTestSelfDestruct")]
     SkipTestSelfDestruct,
     #[error("SkipTestDifficulty")]
@@ -67,6 +82,34 @@ pub enum StateTestError {
     Exception { expected: bool, found: String },
     #[error("CircuitOverflow(circuit:{circuit:?}, needed:{needed:?})")]
     CircuitOverflow { circuit: String, needed: usize },
+    #[error("CapacityExceeded(resource:{resource}, used:{used}, limit:{limit})")]
+    CapacityExceeded {
+        resource: String,
+        used: usize,
+        limit: usize,
+    },
+    #[error("WitnessMemoryDependent(mismatches:{mismatches:?}, first:{first})")]
+    WitnessMemoryDependent { mismatches: usize, first: String },
+    #[error("CircuitTest({0})")]
+    CircuitTest(String),
+    #[error("UnsupportedTxType(index:{index}, tx_type:{tx_type}, required_feature:{required_feature:?})")]
+    UnsupportedTxType {
+        index: usize,
+        tx_type: u64,
+        required_feature: Option<&'static str>,
+    },
+    #[error("InvalidTx(index:{index}, reason:{reason})")]
+    InvalidTx { index: usize, reason: String },
+    #[error("SelfCheckFailed({0})")]
+    SelfCheckFailed(String),
+    #[error("CallTreeMismatch(index:{index}, reason:{reason})")]
+    CallTreeMismatch { index: usize, reason: String },
+    #[error("CircuitUnsatisfied(circuit:{circuit}, failures:{failures:?}, first:{first})")]
+    CircuitUnsatisfied {
+        circuit: String,
+        failures: usize,
+        first: String,
+    },
 }
 
 impl StateTestError {
@@ -79,18 +122,105 @@ impl StateTestError {
         matches!(
             self,
             StateTestError::SkipTestMaxSteps(_)
+                | StateTestError::SkipTestWitnessTimeout { .. }
                 | StateTestError::SkipTestMaxGasLimit(_)
                 | StateTestError::SkipTestSelfDestruct
                 | StateTestError::SkipTestBalanceOverflow
                 | StateTestError::SkipTestDifficulty
+                | StateTestError::UnsupportedTxType { .. }
+        )
+    }
+
+    /// Whether the test doesn't fit in the circuits with the configured params.
+    pub fn is_capacity_exceeded(&self) -> bool {
+        matches!(
+            self,
+            StateTestError::CircuitOverflow { .. } | StateTestError::CapacityExceeded { .. }
         )
     }
+
+    /// Report the builder errors which are a property of the test rather than
+    /// a bug, e.g. a tx type this build doesn't support, and the self-check
+    /// failures, which are failures rather than panics.
+    fn from_unsupported(err: &bus_mapping::Error) -> Option<Self> {
+        match *err {
+            bus_mapping::Error::UnsupportedTxType {
+                index,
+                tx_type,
+                required_feature,
+            } => Some(StateTestError::UnsupportedTxType {
+                index,
+                tx_type,
+                required_feature,
+            }),
+            bus_mapping::Error::InvalidTx { index, ref reason } => {
+                Some(StateTestError::InvalidTx {
+                    index,
+                    reason: format!("{reason:?}"),
+                })
+            }
+            bus_mapping::Error::CapacityExceeded {
+                resource,
+                used,
+                limit,
+            } => Some(StateTestError::CapacityExceeded {
+                resource: format!("{resource:?}"),
+                used,
+                limit,
+            }),
+            bus_mapping::Error::UnsupportedModExpSize { input_lens, limit } => {
+                Some(StateTestError::CapacityExceeded {
+                    resource: String::from("ModExpBytes"),
+                    used: input_lens
+                        .iter()
+                        .max()
+                        .map_or(0, |len| (*len).min(U256::from(usize::MAX)).as_usize()),
+                    limit,
+                })
+            }
+            bus_mapping::Error::SelfCheckFailed { .. } => {
+                Some(StateTestError::SelfCheckFailed(err.to_string()))
+            }
+            bus_mapping::Error::Cancelled(progress) => {
+                Some(StateTestError::SkipTestWitnessTimeout {
+                    steps: progress.steps,
+                    rws: progress.rws,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Monitor stopping the witness generation after the timeout of the suite,
+/// and self-checking it if configured.
+fn witness_monitor(suite: &TestSuite, circuits_config: &CircuitsConfig) -> BuildMonitor {
+    let monitor = match suite.witness_timeout {
+        Some(secs) => {
+            BuildMonitor::default().with_deadline(Instant::now() + Duration::from_secs(secs))
+        }
+        None => BuildMonitor::default(),
+    };
+    if circuits_config.self_check {
+        monitor.with_self_check()
+    } else {
+        monitor
+    }
 }
 
 #[derive(Default, Debug, Clone)]
 pub struct CircuitsConfig {
     pub super_circuit: bool,
     pub verbose: bool,
+    /// Build the witness a second time from a trace collected with the opposite
+    /// `enable_memory` setting and require both witnesses to be identical.
+    pub check_mem_strict: bool,
+    /// Re-execute each step with an interpreter and compare its state against
+    /// the builder and the trace, see `BuildMonitor::with_self_check`.
+    pub self_check: bool,
+    /// Directory to write the witness block of each test to, as
+    /// `{test_id}.json`, to be inspected with `testool inspect`.
+    pub dump_witness: Option<PathBuf>,
 }
 
 fn check_post(
@@ -160,16 +290,15 @@ fn check_post(
 }
 
 fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
-    let tx_type = st.tx_type();
     let tx = st.build_tx();
 
     let wallet = LocalWallet::from_str(&hex::encode(st.secret_key.0.clone())).unwrap();
 
-    let rlp_unsigned = tx.rlp().to_vec();
     let sig = wallet.sign_transaction_sync(&tx).unwrap();
-    let v = st.normalize_sig_v(sig.v);
-    let rlp_signed = tx.rlp_signed(&sig).to_vec();
-    let tx_hash = keccak256(tx.rlp_signed(&sig));
+    // decode the signed tx back, so that its RLP and hash are computed as
+    // the circuits do
+    let eth_tx = tx_envelope::decode_signed(&tx.rlp_signed(&sig)).expect("decode signed tx");
+    assert_eq!(eth_tx.from, st.from, "sender recovered from the signed tx");
     let accounts = st.pre;
 
     (
@@ -184,26 +313,12 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 difficulty: st.env.current_difficulty,
                 gas_limit: U256::from(st.env.current_gas_limit),
                 base_fee: st.env.current_base_fee,
+                blob_base_fee: calc_blob_base_fee(0),
             },
 
             transactions: vec![geth_types::Transaction {
-                tx_type,
-                from: st.from,
-                to: st.to,
-                nonce: st.nonce,
-                value: st.value,
-                gas_limit: U256::from(st.gas_limit),
                 gas_price: Some(st.gas_price),
-                gas_fee_cap: st.max_fee_per_gas,
-                gas_tip_cap: st.max_priority_fee_per_gas,
-                call_data: st.data,
-                access_list: st.access_list,
-                v,
-                r: sig.r,
-                s: sig.s,
-                rlp_bytes: rlp_signed,
-                rlp_unsigned_bytes: rlp_unsigned,
-                hash: tx_hash.into(),
+                ..geth_types::Transaction::from(&eth_tx)
             }],
             accounts,
             logger_config: LoggerConfig {
@@ -215,6 +330,7 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                     || bus_mapping::util::GETH_TRACE_CHECK_LEVEL.should_check()),
                 ..Default::default()
             },
+            chain_config: Some(test_fork().chain_config()),
             ..Default::default()
         },
         st.result,
@@ -232,6 +348,19 @@ pub fn geth_trace(st: StateTest) -> Result<GethExecTrace, StateTestError> {
 }
 */
 
+/// Check the calls reconstructed by the builder for each tx against the call
+/// graph of geth's call tracer.
+fn check_call_trees(
+    builder: &CircuitInputBuilder,
+    geth_traces: &[GethExecTrace],
+) -> Result<(), StateTestError> {
+    for (index, (tx, geth_trace)) in builder.block.txs.iter().zip(geth_traces).enumerate() {
+        tx.check_call_tree(&geth_trace.call_trace)
+            .map_err(|reason| StateTestError::CallTreeMismatch { index, reason })?;
+    }
+    Ok(())
+}
+
 fn check_geth_traces(
     geth_traces: &[GethExecTrace],
     suite: &TestSuite,
@@ -271,7 +400,7 @@ fn trace_config_to_witness_block_l2(
     st: StateTest,
     suite: TestSuite,
     circuits_params: CircuitsParams,
-    verbose: bool,
+    circuits_config: &CircuitsConfig,
 ) -> Result<Option<(eth_types::l2_types::BlockTrace, Block, CircuitInputBuilder)>, StateTestError> {
     let block_trace = external_tracer::l2trace(&trace_config);
 
@@ -302,7 +431,7 @@ fn trace_config_to_witness_block_l2(
     // if the trace exceed max steps, we cannot fit it into circuit
     // but sometimes we still want to make it go through bus-mapping generation
     let always_run_bus_mapping = false;
-    let exceed_max_steps = match check_geth_traces(&geth_traces, &suite, verbose) {
+    let exceed_max_steps = match check_geth_traces(&geth_traces, &suite, circuits_config.verbose) {
         Err(StateTestError::SkipTestMaxSteps(steps)) => {
             if always_run_bus_mapping {
                 steps
@@ -315,15 +444,16 @@ fn trace_config_to_witness_block_l2(
     };
 
     eth_types::constants::set_scroll_block_constants_with_trace(&block_trace);
-    let mut builder =
-        CircuitInputBuilder::new_from_l2_trace(circuits_params, block_trace.clone(), false)
-            .expect("could not handle block tx");
-    builder
-        .finalize_building()
-        .expect("could not finalize building block");
-    let mut block =
-        zkevm_circuits::witness::block_convert(&builder.block, &builder.code_db).unwrap();
-    block.apply_mpt_updates(builder.mpt_init_state.as_ref().unwrap());
+    let ProverInput { block, builder } = prover_input::build_witness_from_l2_trace_with_monitor(
+        block_trace.clone(),
+        circuits_params,
+        witness_monitor(&suite, circuits_config),
+    )
+    .map_err(|err| match StateTestError::from_unsupported(&err) {
+        Some(err) => err,
+        None => panic!("could not build witness block: {err:?}"),
+    })?;
+    check_call_trees(&builder, &geth_traces)?;
     // as mentioned above, we cannot fit the trace into circuit
     // stop here
     if exceed_max_steps != 0 {
@@ -338,21 +468,16 @@ fn trace_config_to_witness_block_l1(
     st: StateTest,
     suite: TestSuite,
     circuits_params: CircuitsParams,
-    verbose: bool,
+    circuits_config: &CircuitsConfig,
 ) -> Result<Option<(Block, CircuitInputBuilder)>, StateTestError> {
     use eth_types::geth_types::TxType;
     use ethers_signers::Signer;
 
     let geth_traces = external_tracer::trace(&trace_config);
 
+    // an invalid tx which the tracer accepts must be rejected by the builder below
     let geth_traces = match (geth_traces, st.exception) {
-        (Ok(res), false) => res,
-        (Ok(_), true) => {
-            return Err(StateTestError::Exception {
-                expected: true,
-                found: "no error".into(),
-            })
-        }
+        (Ok(res), _) => res,
         (Err(_), true) => return Ok(None),
         (Err(err), false) => {
             return Err(StateTestError::Exception {
@@ -362,8 +487,9 @@ fn trace_config_to_witness_block_l1(
         }
     };
 
-    check_geth_traces(&geth_traces, &suite, verbose)?;
+    check_geth_traces(&geth_traces, &suite, circuits_config.verbose)?;
 
+    let accounts = trace_config.accounts_with_overrides();
     let transactions = trace_config
         .transactions
         .into_iter()
@@ -419,49 +545,91 @@ fn trace_config_to_witness_block_l1(
         chain_id: trace_config.chain_id,
         history_hashes: trace_config.history_hashes.clone(),
         geth_traces: geth_traces.clone(),
-        accounts: trace_config.accounts.values().cloned().collect(),
-        eth_block: eth_block.clone(),
+        accounts: accounts.into_values().collect(),
+        eth_block,
         ..Default::default()
     };
 
-    let block_data =
-        bus_mapping::mock::BlockData::new_from_geth_data_with_params(geth_data, circuits_params);
-
-    let mut builder = block_data.new_circuit_input_builder();
-    builder
-        .handle_block(&eth_block, &geth_traces)
-        .map_err(|err| StateTestError::CircuitInput(err.to_string()))?;
-
-    let block: Block =
-        zkevm_circuits::evm_circuit::witness::block_convert(&builder.block, &builder.code_db)
-            .unwrap();
+    let witness = prover_input::build_witness_from_state_test(
+        &geth_data,
+        circuits_params,
+        test_fork().gas_schedule(),
+        witness_monitor(&suite, circuits_config),
+    );
+    let ProverInput { block, builder } = match (witness, st.exception) {
+        (Ok(witness), false) => witness,
+        (Ok(_), true) => {
+            return Err(StateTestError::Exception {
+                expected: true,
+                found: "no error".into(),
+            })
+        }
+        (Err(bus_mapping::Error::InvalidTx { .. }), true) => return Ok(None),
+        (Err(err), _) => {
+            return Err(StateTestError::from_unsupported(&err)
+                .unwrap_or_else(|| StateTestError::CircuitInput(err.to_string())))
+        }
+    };
+    check_call_trees(&builder, &geth_traces)?;
     Ok(Some((block, builder)))
 }
 
-/*
-fn get_params_for_super_circuit_test() -> CircuitsParams {
-    CircuitsParams {
-        max_txs: MAX_TXS,
-        max_calldata: MAX_CALLDATA,
-        max_rws: 256,
-        max_copy_rows: 256,
-        max_mpt_rows: 2049,
-        max_exp_steps: 256,
-        max_bytecode: 512,
-        max_evm_rows: 0,
-        max_keccak_rows: 0,
-        max_poseidon_rows: 0,
-        max_vertical_circuit_rows: 0,
-        max_inner_blocks: 64,
-        max_rlp_rows: 512,
-        max_ec_ops: PrecompileEcParams {
-            ec_add: 50,
-            ec_mul: 50,
-            ec_pairing: 2,
-        },
+/// Rebuild the witness from a trace collected with the opposite memory tracing
+/// setting and compare it against `witness_block`, catching witness generation
+/// which silently depends on memory snapshots being present in the trace.
+fn check_mem_strict(
+    trace_config: &TraceConfig,
+    st: &StateTest,
+    suite: &TestSuite,
+    circuits_params: CircuitsParams,
+    witness_block: &Block,
+) -> Result<(), StateTestError> {
+    let mut trace_config = trace_config.clone();
+    trace_config.logger_config.enable_memory = !trace_config.logger_config.enable_memory;
+    log::debug!(
+        "{}: rebuilding witness with enable_memory = {}",
+        st.id,
+        trace_config.logger_config.enable_memory
+    );
+
+    #[cfg(feature = "scroll")]
+    let other_block = trace_config_to_witness_block_l2(
+        trace_config,
+        st.clone(),
+        suite.clone(),
+        circuits_params,
+        &CircuitsConfig::default(),
+    )?
+    .map(|(_, block, _)| block);
+    #[cfg(not(feature = "scroll"))]
+    let other_block = trace_config_to_witness_block_l1(
+        trace_config,
+        st.clone(),
+        suite.clone(),
+        circuits_params,
+        &CircuitsConfig::default(),
+    )?
+    .map(|(block, _)| block);
+
+    let Some(other_block) = other_block else {
+        return Ok(());
+    };
+    let diffs = diff_witness_blocks(witness_block, &other_block);
+    if diffs.is_empty() {
+        return Ok(());
+    }
+    for diff in &diffs {
+        log::error!("{}: witness depends on memory tracing: {diff}", st.id);
     }
+    Err(StateTestError::WitnessMemoryDependent {
+        mismatches: diffs.len(),
+        first: diffs[0].clone(),
+    })
 }
-*/
+
+/// Operand byte length limit of the modexp circuit in the sub-circuit tests, for the EIP-198 tests
+/// with operands longer than the scroll size limit to be proven rather than skipped.
+const MAX_TEST_MODEXP_BYTES: usize = 128;
 
 fn get_params_for_sub_circuit_test() -> CircuitsParams {
     CircuitsParams {
@@ -476,12 +644,15 @@ fn get_params_for_sub_circuit_test() -> CircuitsParams {
         max_keccak_rows: 0, // dynamic?
         max_poseidon_rows: 0,
         max_vertical_circuit_rows: MAX_VERTICAL_ROWS, // is it good?
+        max_blake2f_rounds: 0,
+        max_modexp_bytes: MAX_TEST_MODEXP_BYTES,
         max_inner_blocks: 64,
         max_rlp_rows: 6000,
         max_ec_ops: PrecompileEcParams {
             ec_add: 50,
             ec_mul: 50,
             ec_pairing: 2,
+            ..Default::default()
         },
     }
 }
@@ -508,7 +679,6 @@ pub fn run_test(
     log::info!("{test_id}: run-test BEGIN - {circuits_config:?}");
 
     // get the geth traces
-    #[cfg_attr(not(feature = "scroll"), allow(unused_mut))]
     let (_, mut trace_config, post) = into_traceconfig(st.clone());
 
     let balance_overflow = trace_config
@@ -522,6 +692,15 @@ pub fn run_test(
             //return Err(StateTestError::SkipTestBalanceOverflow);
         }
     }
+    if circuits_config.self_check {
+        // capture the state which is self-checked
+        trace_config.logger_config = LoggerConfig {
+            enable_memory: true,
+            disable_stack: false,
+            disable_storage: false,
+            ..trace_config.logger_config
+        };
+    }
     log::debug!("trace_config generated");
     let circuits_params = if !circuits_config.super_circuit {
         get_params_for_sub_circuit_test()
@@ -531,7 +710,6 @@ pub fn run_test(
             get_super_circuit_params()
         } else {
             unreachable!("why are we testing super circuit with L1 mode?");
-            //get_params_for_super_circuit_test()
         }
     };
 
@@ -542,7 +720,7 @@ pub fn run_test(
             st.clone(),
             suite.clone(),
             circuits_params,
-            circuits_config.verbose,
+            &circuits_config,
         )?;
         match result {
             Some((scroll_trace, witness_block, builder)) => (scroll_trace, witness_block, builder),
@@ -556,7 +734,7 @@ pub fn run_test(
             st.clone(),
             suite.clone(),
             circuits_params,
-            circuits_config.verbose,
+            &circuits_config,
         )?;
         match result {
             Some((witness_block, builder)) => (witness_block, builder),
@@ -565,6 +743,19 @@ pub fn run_test(
     };
 
     log::debug!("witness_block created");
+    if let Some(dir) = &circuits_config.dump_witness {
+        let path = dir.join(format!("{test_id}.json"));
+        match witness_block.to_json_file(&path) {
+            Ok(()) => log::info!("{test_id}: witness written to {}", path.display()),
+            Err(err) => log::error!(
+                "{test_id}: cannot write witness to {}: {err}",
+                path.display()
+            ),
+        }
+    }
+    if circuits_config.check_mem_strict {
+        check_mem_strict(&trace_config, &st, &suite, circuits_params, &witness_block)?;
+    }
     //builder.sdb.list_accounts();
 
     let row_usage = ScrollSuperCircuit::min_num_rows_block_subcircuits(&witness_block);
@@ -609,7 +800,26 @@ pub fn run_test(
 
     if !circuits_config.super_circuit {
         if (*CIRCUIT).is_empty() {
-            CircuitTestBuilder::<1, 1>::new_from_block(witness_block).run();
+            let report = CircuitTestBuilder::new_from_block(witness_block)
+                .try_run()
+                .map_err(|err| StateTestError::CircuitTest(err.to_string()))?;
+            for result in &report.results {
+                log::debug!(
+                    "circuit test: st.id {}, circuit {:?}, k {}, rows {}, elapsed {:?}",
+                    st.id,
+                    result.circuit,
+                    result.k,
+                    result.checked_rows,
+                    result.elapsed
+                );
+            }
+            if let Some(result) = report.failed().next() {
+                return Err(StateTestError::CircuitUnsatisfied {
+                    circuit: format!("{:?}", result.circuit),
+                    failures: result.failures.len(),
+                    first: result.failures[0].to_string(),
+                });
+            }
         } else {
             match (*CIRCUIT).as_str() {
                 "modexp" => test_with::<ModExpCircuit<Fr>>(&witness_block),
@@ -668,7 +878,7 @@ pub fn run_test(
             // It is better to fill these info after (instead of before) bus-mapping re-exec.
             // To prevent these data being used unexpectedly.
             // TODO: another method will be to skip empty account inside check_post?
-            for account in trace_config.accounts.values() {
+            for account in trace_config.accounts_with_overrides().values() {
                 builder.code_db.insert(account.code.to_vec());
                 let (exist, acc_in_local_sdb) = builder.sdb.get_account_mut(&account.address);
                 if !exist {
@@ -701,9 +911,7 @@ pub fn run_test(
 #[cfg(not(any(feature = "inner-prove", feature = "chunk-prove")))]
 fn mock_prove(test_id: &str, witness_block: &Block) {
     log::info!("{test_id}: mock-prove BEGIN");
-    // TODO: do we need to automatically adjust this k?
-    let k = 20;
-    // TODO: remove this MOCK_RANDOMNESS?
+    let k = ScrollSuperCircuit::min_k(witness_block);
     let circuit = ScrollSuperCircuit::new_from_block(witness_block);
     let instance = circuit.instance();
     let prover = MockProver::run(k, &circuit, instance).unwrap();
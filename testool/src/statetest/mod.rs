@@ -4,6 +4,7 @@ mod parse;
 mod results;
 pub mod spec;
 mod suite;
+mod witness_diff;
 mod yaml;
 
 pub use executor::{run_test, CircuitsConfig};
@@ -26,6 +26,8 @@ pub enum ResultLevel {
     Fail,
     #[strum(ascii_case_insensitive)]
     Panic,
+    #[strum(ascii_case_insensitive)]
+    CapacityExceeded,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
@@ -43,6 +45,7 @@ impl ResultLevel {
             Panic => "💀PANIC",
             Fail => "🔴FAILED",
             Ignored => "🟠IGNORE",
+            CapacityExceeded => "🟡CAPACITY",
             Success => "🟢SUCCESS",
         }
         .to_string()
@@ -155,7 +158,7 @@ impl Report {
         );
         Ok(())
     }
-    pub fn gen_html(&self, githash: String) -> Result<String> {
+    pub fn gen_html(&self, githash: String, fingerprint: String) -> Result<String> {
         let template = include_str!("report.handlebars");
         let reg = Handlebars::new();
         let mut by_folder = Vec::new();
@@ -207,6 +210,7 @@ impl Report {
                 "diffs" : String::from_utf8(diffs)?,
                 "all_results" : tests_for_render,
                 "githash": githash,
+                "fingerprint": fingerprint,
         });
 
         let html = reg.render_template(template, data)?;
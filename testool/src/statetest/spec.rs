@@ -332,21 +332,6 @@ impl StateTest {
         }
     }
 
-    /// Normalize the signature back to 0/1.
-    pub fn normalize_sig_v(&self, v: u64) -> u64 {
-        match self.tx_type() {
-            TxType::Eip1559 | TxType::Eip2930 => {
-                // <https://github.com/gakonst/ethers-rs/blob/8421cfdbb4f26be3989bd11e525f8768d4323bfe/ethers-core/src/types/transaction/mod.rs#L40>
-                if v > 1 {
-                    v - ETH_CHAIN_ID * 2 - 35
-                } else {
-                    v
-                }
-            }
-            _ => v,
-        }
-    }
-
     /// Build a transaction from this test case.
     pub fn build_tx(&self) -> TypedTransaction {
         match self.tx_type() {
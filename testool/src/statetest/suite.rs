@@ -168,6 +168,8 @@ pub fn run_statetests_suite(
                     test_id,
                     level: if err.is_skip() {
                         ResultLevel::Ignored
+                    } else if err.is_capacity_exceeded() {
+                        ResultLevel::CapacityExceeded
                     } else {
                         ResultLevel::Fail
                     },
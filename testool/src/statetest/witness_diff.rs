@@ -0,0 +1,171 @@
+//! Differential comparison of witness blocks built from traces collected with
+//! different tracer settings (e.g. with and without full memory snapshots).
+
+use strum::IntoEnumIterator;
+use zkevm_circuits::{table::RwTableTag, witness::Block};
+
+/// Maximum number of mismatches reported per witness component.
+const MAX_REPORTED_MISMATCHES: usize = 8;
+
+/// Compare two witness blocks and return a human readable description of
+/// every component that differs. An empty result means the witnesses are
+/// equivalent.
+///
+/// Memory snapshots only live in the geth trace and never in the witness, so
+/// everything stored in the [`Block`] must be identical regardless of
+/// whether the tracer dumped memory or not.
+pub fn diff_witness_blocks(lhs: &Block, rhs: &Block) -> Vec<String> {
+    let mut diffs = vec![];
+
+    for tag in RwTableTag::iter() {
+        let empty = vec![];
+        let lhs_rws = lhs.rws.0.get(&tag).unwrap_or(&empty);
+        let rhs_rws = rhs.rws.0.get(&tag).unwrap_or(&empty);
+        if lhs_rws.len() != rhs_rws.len() {
+            diffs.push(format!(
+                "rws {tag:?}: length {} vs {}",
+                lhs_rws.len(),
+                rhs_rws.len()
+            ));
+        }
+        diffs.extend(
+            lhs_rws
+                .iter()
+                .zip(rhs_rws.iter())
+                .enumerate()
+                .filter(|(_, (l, r))| l != r)
+                .take(MAX_REPORTED_MISMATCHES)
+                .map(|(idx, (l, r))| format!("rws {tag:?}[{idx}]: {l:?} vs {r:?}")),
+        );
+    }
+
+    if lhs.txs.len() != rhs.txs.len() {
+        diffs.push(format!(
+            "txs: length {} vs {}",
+            lhs.txs.len(),
+            rhs.txs.len()
+        ));
+    }
+    for (tx_idx, (l_tx, r_tx)) in lhs.txs.iter().zip(rhs.txs.iter()).enumerate() {
+        if l_tx.steps.len() != r_tx.steps.len() {
+            diffs.push(format!(
+                "tx {tx_idx} steps: length {} vs {}",
+                l_tx.steps.len(),
+                r_tx.steps.len()
+            ));
+        }
+        diffs.extend(
+            l_tx.steps
+                .iter()
+                .zip(r_tx.steps.iter())
+                .enumerate()
+                .filter(|(_, (l, r))| l != r)
+                .take(MAX_REPORTED_MISMATCHES)
+                .map(|(idx, (l, r))| format!("tx {tx_idx} step {idx}: {l:?} vs {r:?}")),
+        );
+        if l_tx.calls != r_tx.calls {
+            diffs.push(format!("tx {tx_idx} calls differ"));
+        }
+    }
+
+    if lhs.copy_events.len() != rhs.copy_events.len() {
+        diffs.push(format!(
+            "copy_events: length {} vs {}",
+            lhs.copy_events.len(),
+            rhs.copy_events.len()
+        ));
+    }
+    // CopyEvent has no PartialEq, compare the debug representation instead.
+    diffs.extend(
+        lhs.copy_events
+            .iter()
+            .zip(rhs.copy_events.iter())
+            .enumerate()
+            .filter(|(_, (l, r))| format!("{l:?}") != format!("{r:?}"))
+            .take(MAX_REPORTED_MISMATCHES)
+            .map(|(idx, (l, r))| {
+                format!(
+                    "copy_events[{idx}]: {:?}@{}..{} -> {:?}@{} vs {:?}@{}..{} -> {:?}@{}",
+                    l.src_type,
+                    l.src_addr,
+                    l.src_addr_end,
+                    l.dst_type,
+                    l.dst_addr,
+                    r.src_type,
+                    r.src_addr,
+                    r.src_addr_end,
+                    r.dst_type,
+                    r.dst_addr,
+                )
+            }),
+    );
+
+    if lhs.sha3_inputs != rhs.sha3_inputs {
+        diffs.push("sha3_inputs differ".to_string());
+    }
+    if format!("{:?}", lhs.exp_events) != format!("{:?}", rhs.exp_events) {
+        diffs.push("exp_events differ".to_string());
+    }
+    if format!("{:?}", lhs.precompile_events) != format!("{:?}", rhs.precompile_events) {
+        diffs.push("precompile_events differ".to_string());
+    }
+    if lhs.end_block_step != rhs.end_block_step {
+        diffs.push(format!(
+            "end_block_step: {:?} vs {:?}",
+            lhs.end_block_step, rhs.end_block_step
+        ));
+    }
+
+    diffs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use eth_types::Word;
+    use std::collections::HashMap;
+    use zkevm_circuits::witness::{ExecStep, Rw, RwMap, Transaction};
+
+    fn block(stack_value: u64, gas_left: u64) -> Block {
+        let stack = |rw_counter, value: u64| Rw::Stack {
+            rw_counter,
+            is_write: true,
+            call_id: 1,
+            stack_pointer: 1023,
+            value: Word::from(value),
+        };
+        Block {
+            rws: RwMap(HashMap::from([(
+                RwTableTag::Stack,
+                vec![stack(1, 1), stack(2, stack_value)],
+            )])),
+            txs: vec![Transaction {
+                steps: vec![ExecStep {
+                    gas_left,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn identical_blocks() {
+        assert!(diff_witness_blocks(&block(2, 100), &block(2, 100)).is_empty());
+    }
+
+    #[test]
+    fn rw_mismatch() {
+        let diffs = diff_witness_blocks(&block(2, 100), &block(3, 100));
+        assert_eq!(diffs.len(), 1);
+        assert!(diffs[0].starts_with("rws Stack[1]: "), "{diffs:?}");
+    }
+
+    #[test]
+    fn step_mismatch() {
+        let diffs = diff_witness_blocks(&block(2, 100), &block(2, 99));
+        assert_eq!(diffs.len(), 1);
+        assert!(diffs[0].starts_with("tx 0 step 0: "), "{diffs:?}");
+    }
+}
@@ -1,10 +1,18 @@
 use std::str::FromStr;
 
 use anyhow::{bail, Result};
-use eth_types::{bytecode::OpcodeWithData, Bytecode, GethExecTrace};
+use eth_types::{
+    bytecode::OpcodeWithData,
+    evm_types::{EthFork, GasSchedule},
+    Bytecode, GethExecTrace,
+};
+use external_tracer::ChainConfig;
 use log::{error, info};
 use prettytable::Table;
-use std::process::{Command, Stdio};
+use std::{
+    process::{Command, Stdio},
+    sync::OnceLock,
+};
 
 #[cfg(any(feature = "enable-stack", feature = "enable-storage"))]
 use eth_types::U256;
@@ -12,7 +20,7 @@ use eth_types::U256;
 /// Chain ID of ETH mainnet
 pub const ETH_CHAIN_ID: u64 = 1;
 
-#[derive(Debug, Eq, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd)]
 pub enum MainnetFork {
     Cancun = 16,
     Shanghai = 15,
@@ -32,7 +40,24 @@ pub enum MainnetFork {
     Frontier = 1,
 }
 
-pub const TEST_FORK: MainnetFork = MainnetFork::Cancun;
+/// Fork the tests are run at, unless set with [`set_test_fork`].
+pub const DEFAULT_TEST_FORK: MainnetFork = MainnetFork::Cancun;
+
+static TEST_FORK: OnceLock<MainnetFork> = OnceLock::new();
+
+/// Set the fork the tests are run at, before loading them. It can only be set
+/// once.
+pub fn set_test_fork(fork: MainnetFork) -> Result<()> {
+    if TEST_FORK.set(fork).is_err() {
+        bail!("test fork already set to {:?}", test_fork());
+    }
+    Ok(())
+}
+
+/// Return the fork the tests are run at.
+pub fn test_fork() -> MainnetFork {
+    *TEST_FORK.get_or_init(|| DEFAULT_TEST_FORK)
+}
 
 impl FromStr for MainnetFork {
     type Err = anyhow::Error;
@@ -71,14 +96,14 @@ impl MainnetFork {
             let mut in_network = false;
             for network in expect {
                 if let Some(network) = network.strip_prefix(">=") {
-                    if crate::utils::TEST_FORK >= crate::utils::MainnetFork::from_str(network)? {
+                    if test_fork() >= MainnetFork::from_str(network)? {
                         in_network = true;
                     }
                 } else if let Some(network) = network.strip_prefix('<') {
-                    if crate::utils::TEST_FORK < crate::utils::MainnetFork::from_str(network)? {
+                    if test_fork() < MainnetFork::from_str(network)? {
                         in_network = true;
                     }
-                } else if crate::utils::TEST_FORK == crate::utils::MainnetFork::from_str(network)? {
+                } else if test_fork() == MainnetFork::from_str(network)? {
                     in_network = true;
                 }
             }
@@ -87,6 +112,44 @@ impl MainnetFork {
 
         Ok(in_network)
     }
+
+    /// Fork schedule of the tracer activating the forks up to this one at
+    /// genesis.
+    pub fn chain_config(&self) -> ChainConfig {
+        let at_genesis = |fork: MainnetFork| (*self >= fork).then_some(0);
+        ChainConfig {
+            homestead_block: at_genesis(Self::Homestead),
+            dao_fork_block: at_genesis(Self::Homestead),
+            dao_fork_support: *self >= Self::Homestead,
+            eip150_block: at_genesis(Self::TangerineWhistle),
+            eip155_block: at_genesis(Self::SpuriousDragon),
+            eip158_block: at_genesis(Self::SpuriousDragon),
+            byzantium_block: at_genesis(Self::Byzantium),
+            constantinople_block: at_genesis(Self::Constantinople),
+            petersburg_block: at_genesis(Self::Constantinople),
+            istanbul_block: at_genesis(Self::Istanbul),
+            muir_glacier_block: at_genesis(Self::MuirGlacier),
+            berlin_block: at_genesis(Self::Berlin),
+            london_block: at_genesis(Self::London),
+            terminal_total_difficulty: at_genesis(Self::Paris),
+            terminal_total_difficulty_passed: *self >= Self::Paris,
+            shanghai_time: at_genesis(Self::Shanghai),
+            cancun_time: at_genesis(Self::Cancun),
+        }
+    }
+
+    /// Gas costs the txs of this fork are charged with.
+    pub fn gas_schedule(&self) -> GasSchedule {
+        GasSchedule::of(match self {
+            Self::Cancun | Self::Shanghai => EthFork::Shanghai,
+            Self::Paris | Self::GrayGlacier | Self::ArrowGlacier | Self::Altair | Self::London => {
+                EthFork::London
+            }
+            Self::Berlin => EthFork::Berlin,
+            Self::MuirGlacier | Self::Istanbul => EthFork::Istanbul,
+            _ => EthFork::Petersburg,
+        })
+    }
 }
 
 pub fn print_trace(trace: GethExecTrace) -> Result<()> {
@@ -59,6 +59,18 @@ pub fn unroll_with_codehash<F: Field>(code_hash: U256, bytes: Vec<u8>) -> Unroll
     UnrolledBytecode { bytes, rows }
 }
 
+/// Remove the bytecodes identical to a previous one, so that a bytecode
+/// referenced several times has a single entry in the bytecode table.
+pub fn dedup_unrolled<F: Field>(bytecodes: Vec<UnrolledBytecode<F>>) -> Vec<UnrolledBytecode<F>> {
+    let mut unique: Vec<UnrolledBytecode<F>> = Vec::with_capacity(bytecodes.len());
+    for bytecode in bytecodes {
+        if !unique.contains(&bytecode) {
+            unique.push(bytecode);
+        }
+    }
+    unique
+}
+
 use crate::table::PoseidonTable;
 /// re-export bytes wrapped in hash field
 /// specify byte in field for encoding bytecode
@@ -19,7 +19,7 @@ use halo2_proofs::{
 use std::vec;
 
 use super::{
-    bytecode_unroller::{unroll_with_codehash, BytecodeRow, UnrolledBytecode},
+    bytecode_unroller::{dedup_unrolled, unroll_with_codehash, BytecodeRow, UnrolledBytecode},
     param::PUSH_TABLE_WIDTH,
 };
 
@@ -996,10 +996,10 @@ pub struct BytecodeCircuit<F: Field> {
 }
 
 impl<F: Field> BytecodeCircuit<F> {
-    /// new BytecodeCircuitTester
+    /// new BytecodeCircuitTester. Identical bytecodes are assigned once.
     pub fn new(bytecodes: Vec<UnrolledBytecode<F>>, size: usize) -> Self {
         BytecodeCircuit {
-            bytecodes,
+            bytecodes: dedup_unrolled(bytecodes),
             size,
             overwrite: Default::default(),
         }
@@ -1007,6 +1007,11 @@ impl<F: Field> BytecodeCircuit<F> {
 
     /// Creates bytecode circuit from block and bytecode_size.
     pub fn new_from_block_sized(block: &witness::Block, bytecode_size: usize) -> Self {
+        log::debug!(
+            "bytecode circuit: {} bytecodes, {} rows saved by deduplication",
+            block.bytecodes.len(),
+            block.bytecode_rows_deduplicated()
+        );
         let bytecodes: Vec<UnrolledBytecode<F>> = block
             .bytecodes
             .iter()
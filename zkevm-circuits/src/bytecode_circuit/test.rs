@@ -142,6 +142,18 @@ fn bytecode_simple() {
     test_bytecode_circuit_unrolled::<Fr>(k, bytecodes, true);
 }
 
+/// Tests that identical bytecodes are assigned once
+#[test]
+fn bytecode_dedup() {
+    let k = 9;
+    // Each copy alone takes more than half of the usable rows.
+    let large = unroll(vec![7u8; 300]);
+    let bytecodes = vec![large.clone(), unroll(vec![6u8]), large];
+    let circuit = BytecodeCircuit::<Fr>::new(bytecodes.clone(), 2usize.pow(k));
+    assert_eq!(circuit.bytecodes.len(), 2);
+    test_bytecode_circuit_unrolled::<Fr>(k, bytecodes, true);
+}
+
 /// Tests a fully full circuit
 #[test]
 fn bytecode_full() {
@@ -179,6 +191,12 @@ fn bytecode_push() {
                 OpcodeId::PUSH32.as_u8(),
                 OpcodeId::ADD.as_u8(),
             ]),
+            // PUSH0 has no immediate, the following byte is code
+            unroll(vec![
+                OpcodeId::PUSH0.as_u8(),
+                OpcodeId::PUSH1.as_u8(),
+                OpcodeId::PUSH0.as_u8(),
+            ]),
         ],
         true,
     );
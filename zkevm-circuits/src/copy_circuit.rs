@@ -16,7 +16,7 @@ use array_init::array_init;
 use bus_mapping::circuit_input_builder::{CopyDataType, CopyEvent};
 use eth_types::Word;
 use gadgets::{
-    binary_number::BinaryNumberChip,
+    binary_number::{BinaryNumberChip, BinaryNumberTag},
     is_equal::{IsEqualChip, IsEqualConfig, IsEqualInstruction},
     util::{not, select, Expr},
 };
@@ -51,7 +51,7 @@ use self::copy_gadgets::{
     constrain_forward_parameters, constrain_is_memory_copy, constrain_is_pad, constrain_mask,
     constrain_masked_value, constrain_must_terminate, constrain_non_pad_non_mask,
     constrain_rw_counter, constrain_rw_word_complete, constrain_tag, constrain_value_rlc,
-    constrain_word_index, constrain_word_rlc,
+    constrain_word_index, constrain_word_rlc, constrain_word_step,
 };
 
 /// The current row.
@@ -93,6 +93,9 @@ pub struct CopyCircuitConfig<F> {
     pub value_acc: Column<Advice>,
     /// Whether the row is padding for out-of-bound reads when source address >= src_addr_end.
     pub is_pad: Column<Advice>,
+    /// Whether the row copies a whole inner word of a copy from memory to memory, at the index of
+    /// its last byte, instead of a single byte. See `CopyEvent::is_word_mode`.
+    pub is_word_step: Column<Advice>,
     /// Booleans to indicate what copy data type exists at the current row.
     pub is_tx_calldata: Column<Advice>,
     /// Booleans to indicate what copy data type exists at the current row.
@@ -173,7 +176,7 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
         let value_word_rlc_prev = meta.advice_column_in(SecondPhase);
         let value_acc = meta.advice_column_in(SecondPhase);
 
-        let [is_pad, is_tx_calldata, is_bytecode, is_memory, is_memory_copy, is_tx_log, is_access_list_address, is_access_list_storage_key] =
+        let [is_pad, is_word_step, is_tx_calldata, is_bytecode, is_memory, is_memory_copy, is_tx_log, is_access_list_address, is_access_list_storage_key] =
             array_init(|_| meta.advice_column());
         let is_first = copy_table.is_first;
         let id = copy_table.id;
@@ -294,6 +297,23 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
 
             constrain_masked_value(cb, meta, mask.expr(), value, value_prev);
 
+            let (is_word_step, is_word_step_next) = constrain_word_step(
+                cb,
+                meta,
+                is_reader.expr(),
+                is_first.expr(),
+                is_continue.expr(),
+                is_word_end.expr(),
+                mask.expr(),
+                is_pad.expr(),
+                is_word_step,
+                is_memory,
+                addr,
+                src_addr_end,
+                real_bytes_left,
+                value_word_rlc,
+            );
+
             constrain_value_rlc(
                 cb,
                 meta,
@@ -303,6 +323,7 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                 non_pad_non_mask,
                 is_pad_next.expr(),
                 mask_next.expr(),
+                is_word_step_next.expr(),
                 value_acc,
                 value,
                 challenges.keccak_input(),
@@ -322,6 +343,7 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     is_first.expr() * (1.expr() - is_access_list.expr()),
                     is_continue.expr() * (1.expr() - is_access_list.expr()),
                     is_word_end.expr(),
+                    is_word_step_next.expr(),
                     word_rlc,
                     value,
                     challenges.evm_word(),
@@ -334,6 +356,7 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                 is_first.expr(),
                 is_continue.expr(),
                 is_word_end.expr(),
+                is_word_step_next.expr(),
                 word_index,
             );
 
@@ -343,10 +366,18 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                 is_first.expr(),
                 is_continue.expr(),
                 mask.expr(),
+                is_word_step.expr(),
                 real_bytes_left,
             );
 
-            constrain_address(cb, meta, is_continue.expr(), front_mask.expr(), addr);
+            constrain_address(
+                cb,
+                meta,
+                is_continue.expr(),
+                front_mask.expr(),
+                is_word_step_next.expr(),
+                addr,
+            );
 
             {
                 let is_rw_word_type = meta.query_advice(is_memory, CURRENT) + is_tx_log.expr();
@@ -591,6 +622,7 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             front_mask,
             value_acc,
             is_pad,
+            is_word_step,
             is_tx_calldata,
             is_bytecode,
             is_memory,
@@ -670,6 +702,7 @@ impl<F: Field> CopyCircuitConfig<F> {
                 self.mask,
                 self.front_mask,
                 self.word_index,
+                self.is_word_step,
             ]
             .iter()
             .zip_eq(circuit_row)
@@ -713,7 +746,7 @@ impl<F: Field> CopyCircuitConfig<F> {
             lt_word_end_chip.assign(
                 region,
                 *offset,
-                Value::known(F::from((step_idx as u64 / 2) % 32)), // word index
+                circuit_row[9].0, // word index
                 Value::known(F::from(31u64)),
             )?;
 
@@ -788,18 +821,14 @@ impl<F: Field> CopyCircuitConfig<F> {
         max_copy_rows: usize,
         challenges: Challenges<Value<F>>,
     ) -> Result<(), Error> {
-        let copy_rows_needed = copy_events
-            .iter()
-            .map(|c| c.full_length() as usize * 2)
-            .sum::<usize>();
+        let copy_rows_needed = copy_events.iter().map(CopyEvent::copy_rows).sum::<usize>();
         let max_copy_rows = if max_copy_rows == 0 {
             // dynamic
             copy_rows_needed + DISABLED_ROWS + UNUSED_ROWS
         } else {
-            assert!(
-                copy_rows_needed + DISABLED_ROWS + UNUSED_ROWS <= max_copy_rows,
-                "copy rows not enough {copy_rows_needed} vs {max_copy_rows}"
-            );
+            if let Err(err) = check_copy_rows(copy_events, max_copy_rows) {
+                panic!("{err}");
+            }
             max_copy_rows
         };
         let filler_rows = max_copy_rows - copy_rows_needed - DISABLED_ROWS;
@@ -821,6 +850,7 @@ impl<F: Field> CopyCircuitConfig<F> {
                 region.name_column(|| "mask", self.mask);
                 region.name_column(|| "front_mask", self.front_mask);
                 region.name_column(|| "is_pad", self.is_pad);
+                region.name_column(|| "is_word_step", self.is_word_step);
                 region.name_column(|| "non_pad_non_mask", self.non_pad_non_mask);
 
                 let mut offset = 0;
@@ -1015,6 +1045,13 @@ impl<F: Field> CopyCircuitConfig<F> {
             *offset,
             || Value::known(F::zero()),
         )?;
+        // is_word_step
+        region.assign_advice(
+            || format!("assign is_word_step {}", *offset),
+            self.is_word_step,
+            *offset,
+            || Value::known(F::zero()),
+        )?;
         // rw_counter
         region.assign_advice(
             || format!("assign rw_counter {}", *offset),
@@ -1098,6 +1135,75 @@ pub struct ExternalData {
     pub bytecodes: BTreeMap<Word, Bytecode>,
 }
 
+/// Error returned when the copy events of a block do not fit in the copy
+/// circuit.
+#[derive(Clone, Debug)]
+pub enum CopyCircuitError {
+    /// The copy rows exceed `max_copy_rows`.
+    RowsExceeded {
+        /// Index of the first copy event which does not fit.
+        event_index: usize,
+        /// The copy event, without its bytes.
+        event: Box<CopyEvent>,
+        /// Rows needed by all the copy events, including the unusable rows.
+        rows_needed: usize,
+        /// Max number of rows of the copy circuit.
+        max_copy_rows: usize,
+    },
+}
+
+impl std::fmt::Display for CopyCircuitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RowsExceeded {
+                event_index,
+                event,
+                rows_needed,
+                max_copy_rows,
+            } => write!(
+                f,
+                "copy rows not enough {rows_needed} vs {max_copy_rows}, from copy event {event_index} \
+                 ({:?} -> {:?}, length {}, origin {:?})",
+                event.src_type,
+                event.dst_type,
+                event.full_length(),
+                event.origin,
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CopyCircuitError {}
+
+// Check that `copy_events` fit in `max_copy_rows`.
+fn check_copy_rows(
+    copy_events: &[CopyEvent],
+    max_copy_rows: usize,
+) -> Result<(), CopyCircuitError> {
+    let rows_needed =
+        copy_events.iter().map(CopyEvent::copy_rows).sum::<usize>() + DISABLED_ROWS + UNUSED_ROWS;
+    if rows_needed <= max_copy_rows {
+        return Ok(());
+    }
+    let mut rows = DISABLED_ROWS + UNUSED_ROWS;
+    let event_index = copy_events
+        .iter()
+        .position(|event| {
+            rows += event.copy_rows();
+            rows > max_copy_rows
+        })
+        .expect("some copy event exceeds max_copy_rows");
+    Err(CopyCircuitError::RowsExceeded {
+        event_index,
+        event: Box::new(CopyEvent {
+            copy_bytes: Default::default(),
+            ..copy_events[event_index].clone()
+        }),
+        rows_needed,
+        max_copy_rows,
+    })
+}
+
 /// Copy Circuit
 #[derive(Clone, Debug, Default)]
 pub struct CopyCircuit<F: Field> {
@@ -1135,6 +1241,15 @@ impl<F: Field> CopyCircuit<F> {
         }
     }
 
+    /// Return a new CopyCircuit from a block, or the error of the first copy
+    /// event which does not fit in `max_copy_rows`.
+    pub fn try_new_from_block(block: &witness::Block) -> Result<Self, CopyCircuitError> {
+        if block.circuits_params.max_copy_rows != 0 {
+            check_copy_rows(&block.copy_events, block.circuits_params.max_copy_rows)?;
+        }
+        Ok(<Self as SubCircuit<F>>::new_from_block(block))
+    }
+
     /// Return a new CopyCircuit from a block without the external data required
     /// to assign lookup tables.  This constructor is only suitable to be
     /// used by the SuperCircuit, which already assigns the external lookup
@@ -1176,7 +1291,7 @@ impl<F: Field> SubCircuit<F> for CopyCircuit<F> {
         let row_num = block
             .copy_events
             .iter()
-            .map(|c| c.full_length() as usize * 2)
+            .map(CopyEvent::copy_rows)
             .sum::<usize>()
             + UNUSED_ROWS
             + DISABLED_ROWS;
@@ -1235,7 +1350,7 @@ mod copy_circuit_stats {
                 block
                     .copy_events
                     .iter()
-                    .map(|c| c.copy_bytes.bytes.len() * 2)
+                    .map(bus_mapping::circuit_input_builder::CopyEvent::copy_rows)
                     .sum::<usize>()
             },
         );
@@ -2,7 +2,7 @@ use super::{CURRENT, NEXT_ROW, NEXT_STEP};
 use crate::util::Field;
 use bus_mapping::circuit_input_builder::CopyDataType;
 use gadgets::{
-    binary_number::BinaryNumberConfig,
+    binary_number::{BinaryNumberConfig, BinaryNumberTag},
     is_equal::IsEqualConfig,
     util::{and, not, select, sum, Expr},
 };
@@ -283,6 +283,7 @@ pub fn constrain_value_rlc<F: Field>(
     non_pad_non_mask: Column<Advice>,
     is_pad_next: Expression<F>,
     mask_next: Expression<F>,
+    is_word_step_next: Expression<F>,
     value_acc: Column<Advice>,
     value: Column<Advice>,
     challenge: Expression<F>,
@@ -305,8 +306,8 @@ pub fn constrain_value_rlc<F: Field>(
         // If source padding, replace the value with 0.
         let value_or_pad = meta.query_advice(value, NEXT_STEP) * not::expr(is_pad_next.expr());
         let accumulated = current.expr() * challenge + value_or_pad;
-        // If masked, copy the accumulator forward, otherwise update it.
-        let copy_or_acc = select::expr(mask_next, current, accumulated);
+        // If masked or a word step, copy the accumulator forward, otherwise update it.
+        let copy_or_acc = select::expr(mask_next + is_word_step_next, current, accumulated);
         cb.require_equal(
             "value_acc(2) == value_acc(0) * r + value(2), or copy value_acc(0)",
             copy_or_acc,
@@ -375,6 +376,7 @@ pub fn constrain_word_rlc<F: Field>(
     is_first: Expression<F>,
     is_continue: Expression<F>,
     is_word_end: Expression<F>,
+    is_word_step_next: Expression<F>,
     word_rlc: Column<Advice>,
     value: Column<Advice>,
     challenge: Expression<F>,
@@ -391,8 +393,9 @@ pub fn constrain_word_rlc<F: Field>(
         }
     });
 
-    // Accumulate the next value into the next word_rlc.
-    cb.condition(is_continue.expr(), |cb| {
+    // Accumulate the next value into the next word_rlc. A word step has the RLC of its whole word,
+    // verified by its RW lookup.
+    cb.condition(is_continue.expr() * not::expr(is_word_step_next), |cb| {
         let current_or_reset = select::expr(
             is_word_end.expr(),
             0.expr(),
@@ -415,6 +418,7 @@ pub fn constrain_word_index<F: Field>(
     is_first: Expression<F>,
     is_continue: Expression<F>,
     is_word_end: Expression<F>,
+    is_word_step_next: Expression<F>,
     word_index: Column<Advice>,
 ) {
     // Initial values derived from the event.
@@ -425,15 +429,16 @@ pub fn constrain_word_index<F: Field>(
         }
     });
 
-    // Update the index into the current or next word.
+    // Update the index into the current or next word. A word step is at the last byte of its word.
     cb.condition(is_continue.expr(), |cb| {
         let inc_or_reset = select::expr(
             is_word_end.expr(),
             0.expr(),
             meta.query_advice(word_index, CURRENT) + 1.expr(),
         );
+        let inc_or_reset = select::expr(is_word_step_next, 31.expr(), inc_or_reset);
         cb.require_equal(
-            "word_index increments or resets to 0",
+            "word_index increments, resets to 0, or is 31 for a word step",
             inc_or_reset,
             meta.query_advice(word_index, NEXT_STEP),
         );
@@ -447,6 +452,7 @@ pub fn constrain_bytes_left<F: Field>(
     is_first: Expression<F>,
     is_continue: Expression<F>,
     mask: Expression<F>,
+    is_word_step: Expression<F>,
     bytes_left: Column<Advice>,
 ) {
     let [current, next_row, next_step] =
@@ -457,12 +463,12 @@ pub fn constrain_bytes_left<F: Field>(
         cb.require_equal("writer initial length", current.expr(), next_row);
     });
 
-    // Decrement real_bytes_left for the next step, on non-masked rows.
-    let new_value = current - not::expr(mask);
+    // Decrement real_bytes_left for the next step, on non-masked rows, or by a word on word steps.
+    let new_value = current - not::expr(mask) - 31.expr() * is_word_step;
     // At the end, it must reach 0.
     let update_or_finish = select::expr(is_continue, next_step, 0.expr());
     cb.require_equal(
-        "bytes_left[2] == bytes_left[0] - !mask, or 0 at the end",
+        "bytes_left[2] == bytes_left[0] - !mask - 31 * is_word_step, or 0 at the end",
         new_value,
         update_or_finish,
     );
@@ -474,15 +480,16 @@ pub fn constrain_address<F: Field>(
     meta: &mut VirtualCells<'_, F>,
     is_continue: Expression<F>,
     front_mask: Expression<F>,
+    is_word_step_next: Expression<F>,
     addr: Column<Advice>,
 ) {
     cb.condition(is_continue, |cb| {
         // The address is incremented by 1, except in the front mask. There must be the
         // right amount of front mask until the row matches up with the
-        // initial address of the event.
-        let addr_diff = not::expr(front_mask);
+        // initial address of the event. A word step is at the last byte of the next word.
+        let addr_diff = not::expr(front_mask) + 31.expr() * is_word_step_next;
         cb.require_equal(
-            "rows[0].addr + !front_mask == rows[2].addr",
+            "rows[0].addr + !front_mask + 31 * is_word_step[2] == rows[2].addr",
             meta.query_advice(addr, CURRENT) + addr_diff,
             meta.query_advice(addr, NEXT_STEP),
         );
@@ -608,3 +615,91 @@ pub fn constrain_rw_word_complete<F: Field>(
         ]),
     );
 }
+
+/// Verify the word steps, which copy an inner word of a copy from memory to memory at once, in
+/// place of the steps of its 32 bytes. Return (is_word_step, is_word_step at NEXT_STEP).
+#[allow(clippy::too_many_arguments)]
+pub fn constrain_word_step<F: Field>(
+    cb: &mut BaseConstraintBuilder<F>,
+    meta: &mut VirtualCells<'_, F>,
+    is_reader: Expression<F>,
+    is_first: Expression<F>,
+    is_continue: Expression<F>,
+    is_word_end: Expression<F>,
+    mask: Expression<F>,
+    is_pad: Expression<F>,
+    is_word_step: Column<Advice>,
+    is_memory: Column<Advice>,
+    addr: Column<Advice>,
+    src_addr_end: Column<Advice>,
+    bytes_left: Column<Advice>,
+    word_rlc: Column<Advice>,
+) -> (Expression<F>, Expression<F>) {
+    let [is_word_step, is_word_step_writer, is_word_step_next] =
+        [CURRENT, NEXT_ROW, NEXT_STEP].map(|at| meta.query_advice(is_word_step, at));
+
+    cb.require_boolean("is_word_step is boolean", is_word_step.expr());
+
+    // The first and last words have the unaligned edges, so they are copied byte by byte.
+    cb.condition(is_word_step.expr(), |cb| {
+        cb.require_zero("a word step is not the first step", is_first.expr());
+        cb.require_equal(
+            "a word step is not the last step",
+            is_continue.expr(),
+            1.expr(),
+        );
+        cb.require_equal(
+            "a word step is in memory",
+            meta.query_advice(is_memory, CURRENT),
+            1.expr(),
+        );
+        cb.require_zero("a word step is not masked", mask);
+        cb.require_zero("a word step is not padded", is_pad);
+    });
+
+    cb.condition(is_continue * is_word_step_next.expr(), |cb| {
+        cb.require_equal(
+            "a word step follows the end of a word",
+            is_word_end,
+            1.expr(),
+        );
+    });
+
+    cb.condition(is_reader.expr(), |cb| {
+        cb.require_equal(
+            "reader and writer copy a word at the same step",
+            is_word_step.expr(),
+            is_word_step_writer,
+        );
+    });
+
+    cb.condition(is_reader * is_word_step.expr(), |cb| {
+        let [word_rlc, word_rlc_writer] =
+            [CURRENT, NEXT_ROW].map(|at| meta.query_advice(word_rlc, at));
+        cb.require_equal(
+            "the word written is the word read",
+            word_rlc,
+            word_rlc_writer,
+        );
+
+        // The same bytes are left on both sides, so the data has the same alignment in the words
+        // of the reader and of the writer, and the word is copied at the same offset.
+        let [bytes_left, bytes_left_writer] =
+            [CURRENT, NEXT_ROW].map(|at| meta.query_advice(bytes_left, at));
+        cb.require_equal(
+            "reader and writer have the same bytes left at a word step",
+            bytes_left.expr(),
+            bytes_left_writer,
+        );
+
+        // The word step skips the source padding checks of its bytes, so the source data must end
+        // exactly at src_addr_end, after all the bytes left from the start of the word.
+        cb.require_equal(
+            "src_addr_end == word address + bytes_left at a word step",
+            meta.query_advice(src_addr_end, CURRENT),
+            meta.query_advice(addr, CURRENT) - 31.expr() + bytes_left,
+        );
+    });
+
+    (is_word_step, is_word_step_next)
+}
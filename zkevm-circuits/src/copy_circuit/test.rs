@@ -141,6 +141,30 @@ fn gen_mcopy_data() -> CircuitInputBuilder {
     builder
 }
 
+// MCOPY of 7 memory words with the same alignment at the source and destination.
+fn gen_mcopy_word_data() -> CircuitInputBuilder {
+    let code = bytecode! {
+        PUSH32(Word::MAX) // value
+        PUSH1(0x60u64) // offset
+        MSTORE
+        PUSH3(0x803f1au64) // value
+        PUSH1(0xc0u64) // offset
+        MSTORE
+        PUSH32(Word::from(0xc0)) // copy_size
+        PUSH32(Word::from(0x45)) // src_offset
+        PUSH32(Word::from(0x125)) // dest_offset
+        MCOPY
+        STOP
+    };
+    let test_ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+    let block: GethData = test_ctx.into();
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    builder
+}
+
 fn gen_returndatacopy_data() -> CircuitInputBuilder {
     let (addr_a, addr_b) = (mock::MOCK_ACCOUNTS[0], mock::MOCK_ACCOUNTS[1]);
 
@@ -211,6 +235,62 @@ fn gen_returndatacopy_data() -> CircuitInputBuilder {
     builder
 }
 
+// RETURNDATACOPY of 7 memory words with the same alignment at the source and destination.
+fn gen_returndatacopy_word_data() -> CircuitInputBuilder {
+    let (addr_a, addr_b) = (mock::MOCK_ACCOUNTS[0], mock::MOCK_ACCOUNTS[1]);
+
+    let pushdata = (0..32).collect::<Vec<u8>>();
+    let code_b = bytecode! {
+        PUSH32(Word::from_big_endian(&pushdata))
+        PUSH32(0x40)
+        MSTORE
+
+        PUSH32(0x100)
+        PUSH32(0x0)
+        RETURN
+        STOP
+    };
+
+    let code_a = bytecode! {
+        PUSH32(0x0) // retLength
+        PUSH32(0x0) // retOffset
+        PUSH1(0x00) // argsLength
+        PUSH1(0x00) // argsOffset
+        PUSH1(0x00) // value
+        PUSH32(addr_b.to_word()) // addr
+        PUSH32(0x1_0000) // gas
+        CALL
+        PUSH32(0xc0) // size
+        PUSH32(0x10) // offset
+        PUSH32(0x30) // dest_offset
+        RETURNDATACOPY
+        STOP
+    };
+
+    let test_ctx = TestContext::<3, 1>::new(
+        None,
+        |accs| {
+            accs[0].address(addr_a).code(code_a);
+            accs[1].address(addr_b).code(code_b);
+            accs[2]
+                .address(mock::MOCK_ACCOUNTS[2])
+                .balance(Word::from(1u64 << 30));
+        },
+        |mut txs, accs| {
+            txs[0].to(accs[0].address).from(accs[2].address);
+        },
+        |block, _tx| block,
+    )
+    .unwrap();
+
+    let block: GethData = test_ctx.into();
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    builder
+}
+
 fn gen_extcodecopy_data() -> CircuitInputBuilder {
     let external_address = MOCK_ACCOUNTS[0];
     let code = bytecode! {
@@ -380,6 +460,27 @@ fn copy_circuit_valid_codecopy() {
     assert_eq!(test_copy_circuit_from_block(block), Ok(()));
 }
 
+#[test]
+fn copy_circuit_rows_exceeded() {
+    let builder = gen_codecopy_data();
+    let mut block = block_convert(&builder.block, &builder.code_db).unwrap();
+    block.circuits_params.max_copy_rows = DISABLED_ROWS + UNUSED_ROWS + 1;
+
+    let err = CopyCircuit::<Fr>::try_new_from_block(&block).unwrap_err();
+    let CopyCircuitError::RowsExceeded {
+        event_index, event, ..
+    } = err;
+    assert_eq!(
+        event_index,
+        block
+            .copy_events
+            .iter()
+            .position(|event| event.copy_rows() > 0)
+            .unwrap()
+    );
+    assert_eq!(event.origin.unwrap().opcode, Some(OpcodeId::CODECOPY));
+}
+
 // unit test for mcopy in copy circuit side.
 #[test]
 fn copy_circuit_valid_mcopy() {
@@ -395,6 +496,35 @@ fn copy_circuit_valid_returndatacopy() {
     assert_eq!(test_copy_circuit_from_block(block), Ok(()));
 }
 
+// Find the copy event of 7 words in word mode: the first and last words byte by byte, and the 5
+// inner words in a step each.
+fn word_mode_event(block: &Block) -> &CopyEvent {
+    let event = block
+        .copy_events
+        .iter()
+        .find(|event| event.is_word_mode())
+        .expect("a copy event in word mode");
+    assert_eq!(event.full_length(), 7 * 32);
+    assert_eq!(event.copy_rows(), (2 * 32 + 5) * 2);
+    event
+}
+
+#[test]
+fn copy_circuit_valid_mcopy_word_mode() {
+    let builder = gen_mcopy_word_data();
+    let block = block_convert(&builder.block, &builder.code_db).unwrap();
+    word_mode_event(&block);
+    assert_eq!(test_copy_circuit_from_block(block), Ok(()));
+}
+
+#[test]
+fn copy_circuit_valid_returndatacopy_word_mode() {
+    let builder = gen_returndatacopy_word_data();
+    let block = block_convert(&builder.block, &builder.code_db).unwrap();
+    word_mode_event(&block);
+    assert_eq!(test_copy_circuit_from_block(block), Ok(()));
+}
+
 #[test]
 fn copy_circuit_valid_extcodecopy() {
     let builder = gen_extcodecopy_data();
@@ -525,6 +655,29 @@ fn copy_circuit_invalid_tx_log() {
         .expect("there should be a lookup error");
 }
 
+#[test]
+fn copy_circuit_invalid_mcopy_word_mode() {
+    let mut builder = gen_mcopy_word_data();
+
+    // modify a byte of an inner word, copied by a word step
+    let event = builder
+        .block
+        .copy_events
+        .iter_mut()
+        .find(|event| event.is_word_mode())
+        .unwrap();
+    event.copy_bytes.bytes[3 * 32 + 7].0 = event.copy_bytes.bytes[3 * 32 + 7].0.wrapping_add(1);
+
+    let block = block_convert(&builder.block, &builder.code_db).unwrap();
+    let result = test_copy_circuit_from_block(block);
+
+    let errors = result.expect_err("result is not an error");
+    errors
+        .iter()
+        .find(|err| matches!(err, VerifyFailure::Lookup { .. }))
+        .expect("there should be a lookup error");
+}
+
 #[test]
 fn copy_circuit_precompile_call() {
     // TODO: as we add support for more precompiles, we should populate those here as well.
@@ -5,7 +5,10 @@ use std::{iter, marker::PhantomData};
 
 use crate::util::Field;
 use bus_mapping::{
-    circuit_input_builder::{EcAddOp, EcMulOp, EcPairingOp, N_BYTES_PER_PAIR, N_PAIRING_PER_OP},
+    circuit_input_builder::{
+        CircuitsParams, EcAddOp, EcMulOp, EcPairingOp, EcPairingPair, PrecompileEcParams,
+        N_BYTES_PER_PAIR,
+    },
     precompile::PrecompileCalls,
 };
 use eth_types::{ToLittleEndian, U256};
@@ -42,7 +45,7 @@ use crate::{
     evm_circuit::{param::N_BYTES_WORD, EvmCircuit},
     keccak_circuit::KeccakCircuit,
     table::{EccTable, LookupTable},
-    util::{Challenges, SubCircuit, SubCircuitConfig},
+    util::{Challenges, PrecompileRowFormula, SubCircuit, SubCircuitConfig},
     witness::Block,
 };
 
@@ -150,6 +153,9 @@ pub struct EccCircuit<F: Field, const XI_0: i64> {
     pub max_mul_ops: usize,
     /// Maximum number of pairing operations supported in one instance of the ECC Circuit.
     pub max_pairing_ops: usize,
+    /// Number of (G1, G2) pairs of each pairing operation, the pairs of the EVM input being padded
+    /// to it.
+    pub max_pairing_pairs: usize,
 
     /// EcAdd operations provided as witness data to the ECC circuit.
     pub add_ops: Vec<EcAddOp>,
@@ -176,6 +182,73 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         (1 << LOG_TOTAL_NUM_ROWS) - (max_blinding_factor + 3)
     }
 
+    /// Assign the ECC table from the witness only, leaving out the ECC circuit which would
+    /// constrain it.
+    pub(crate) fn load_table(
+        &self,
+        config: &EccCircuitConfig<F>,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.ecc_table.dev_load(
+            layouter,
+            PrecompileEcParams {
+                ec_add: self.max_add_ops,
+                ec_mul: self.max_mul_ops,
+                ec_pairing: self.max_pairing_ops,
+                ec_pairing_pairs: self.max_pairing_pairs,
+            },
+            &self.add_ops,
+            &self.mul_ops,
+            &self.pairing_ops,
+            challenges,
+        )
+    }
+
+    /// Rows the circuit is sized to: `max_vertical_circuit_rows`, or the
+    /// whole usable area when it is 0.
+    fn circuit_rows(params: &CircuitsParams) -> usize {
+        if params.max_vertical_circuit_rows == 0 {
+            Self::min_num_rows()
+        } else {
+            params.max_vertical_circuit_rows
+        }
+    }
+
+    /// Closed-form row formulas of the EcAdd, EcMul and EcPairing ops. Each
+    /// op kind gets an equal share of the circuit rows per supported op. The
+    /// share of the pairing ops is sized for ops of [`N_PAIRING_PER_OP`] pairs
+    /// and spread over their pairs, so that ops padded to more pairs take
+    /// more rows, and fewer of them fit.
+    pub fn row_formulas(params: &CircuitsParams) -> [PrecompileRowFormula; 3] {
+        let row_num = Self::circuit_rows(params);
+        let max_ec_ops = &params.max_ec_ops;
+        let [ec_add, ec_mul] = [
+            (PrecompileCalls::Bn128Add, max_ec_ops.ec_add),
+            (PrecompileCalls::Bn128Mul, max_ec_ops.ec_mul),
+        ]
+        .map(|(op, max_ops)| PrecompileRowFormula {
+            op,
+            rows_per_op: row_num / max_ops.max(1),
+            rows_per_pair: 0,
+            pairs_per_op: 0,
+            max_ops: Some(max_ops),
+        });
+
+        let pairs_per_op = max_ec_ops.ec_pairing_pairs;
+        let rows_per_pair = row_num / (max_ec_ops.ec_pairing * N_PAIRING_PER_OP).max(1);
+        let rows_per_op = rows_per_pair * pairs_per_op;
+        let ec_pairing = PrecompileRowFormula {
+            op: PrecompileCalls::Bn128Pairing,
+            rows_per_op,
+            rows_per_pair,
+            pairs_per_op,
+            max_ops: Some(max_ec_ops.ec_pairing.min(row_num / rows_per_op.max(1))),
+        };
+
+        [ec_add, ec_mul, ec_pairing]
+    }
+
     /// Assign witness from the ecXX ops to the circuit.
     pub(crate) fn assign(
         &self,
@@ -198,12 +271,28 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
             );
             return Err(Error::Synthesis);
         }
+        if self
+            .pairing_ops
+            .iter()
+            .any(|op| op.pairs.len() > self.max_pairing_pairs)
+        {
+            error!(
+                "pairing op with more than max pairing pairs = {}",
+                self.max_pairing_pairs
+            );
+            return Err(Error::Synthesis);
+        }
+        // the pairing check of the all zero inputs takes 2 pairs.
+        if self.max_pairing_pairs < 2 {
+            error!("max pairing pairs = {} < 2", self.max_pairing_pairs);
+            return Err(Error::Synthesis);
+        }
 
-        // keccak powers of randomness.
+        // keccak powers of randomness, up to the one of a (G1, G2) pair.
         let keccak_powers = std::iter::successors(Some(Value::known(F::one())), |coeff| {
             Some(challenges.keccak_input() * coeff)
         })
-        .take(N_PAIRING_PER_OP * N_BYTES_PER_PAIR)
+        .take(N_BYTES_PER_PAIR + 1)
         .map(|x| QuantumCell::Witness(x))
         .collect_vec();
 
@@ -440,6 +529,12 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
                         config.ecc_table.is_valid,
                         idx,
                     );
+                    // number of pairs
+                    ec_pairing_assigned.n_pairs.copy_advice(
+                        &mut region,
+                        config.ecc_table.arg1_rlc,
+                        idx,
+                    );
                     // RLC(input_bytes)
                     ec_pairing_assigned.input_rlc.copy_advice(
                         &mut region,
@@ -453,7 +548,6 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
                         idx,
                     );
                     for &col in [
-                        config.ecc_table.arg1_rlc,
                         config.ecc_table.arg2_rlc,
                         config.ecc_table.arg3_rlc,
                         config.ecc_table.arg4_rlc,
@@ -697,6 +791,8 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         let decomposed_pairs = op
             .pairs
             .iter()
+            .chain(iter::repeat(&EcPairingPair::padding_pair()))
+            .take(self.max_pairing_pairs)
             .map(|pair| {
                 // process x and y co-ordinates of G1.
                 let (g1x, g1x_cells, g1x_valid, g1x_is_zero) =
@@ -823,11 +919,11 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         log::trace!("[ECC] EcPairing g1s and g2s Assigned:");
         log_context_cursor!(ctx);
 
-        // EVM input for EcPairing in Big-Endian representation, padded by 0 bytes so that the
-        // total number of bytes are N_PAIRING_PER_OP * N_BYTES_PER_PAIR.
-        let input_cells = decomposed_pairs
+        // EVM input for EcPairing in Big-Endian representation, per pair. The padding pairs are
+        // all 0 bytes.
+        let pair_cells = decomposed_pairs
             .iter()
-            .flat_map(|(_, _, g1, g2)| {
+            .map(|(_, _, g1, g2)| {
                 std::iter::empty()
                     .chain(g1.x_cells.iter().rev())
                     .chain(g1.y_cells.iter().rev())
@@ -838,9 +934,39 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
                     .cloned()
                     .collect::<Vec<QuantumCell<F>>>()
             })
-            .collect::<Vec<QuantumCell<F>>>();
+            .collect_vec();
 
-        log::trace!("[ECC] EcPairing Inputs RLC Assigned:");
+        // The pairs of the EVM input come first: is_input_pair is 1 for the first n_pairs pairs,
+        // then 0 for the padding pairs.
+        let gate = ecc_chip.field_chip().range().gate();
+        let is_input_pair = (0..self.max_pairing_pairs)
+            .map(|idx| {
+                let is_input_pair =
+                    gate.load_witness(ctx, Value::known(F::from((idx < op.pairs.len()) as u64)));
+                gate.assert_bit(ctx, is_input_pair);
+                is_input_pair
+            })
+            .collect_vec();
+        for (prev, curr) in is_input_pair.iter().tuple_windows() {
+            let both = gate.mul(
+                ctx,
+                QuantumCell::Existing(*prev),
+                QuantumCell::Existing(*curr),
+            );
+            gate.assert_equal(
+                ctx,
+                QuantumCell::Existing(both),
+                QuantumCell::Existing(*curr),
+            );
+        }
+        let n_pairs = gate.sum(
+            ctx,
+            is_input_pair
+                .iter()
+                .map(|&cell| QuantumCell::Existing(cell)),
+        );
+
+        log::trace!("[ECC] EcPairing Inputs Assigned:");
         log_context_cursor!(ctx);
 
         // Whether all the pairs are (G1::identity, G2::valid) or (G1::valid, G2::identity) form.
@@ -879,6 +1005,11 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
             EcPairingOp::dummy_pairing_check_ok()
                 .pairs
                 .iter()
+                .chain(iter::repeat(&EcPairingPair::new(
+                    G1Affine::identity(),
+                    G2Affine::generator(),
+                )))
+                .take(self.max_pairing_pairs)
                 .map(|pair| {
                     let (g1_point, g2_point) =
                         pair.as_g1_g2().expect("dummy pairing check OK pair");
@@ -1008,7 +1139,9 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
 
         EcPairingDecomposed {
             is_valid,
-            input_cells,
+            pair_cells,
+            is_input_pair,
+            n_pairs,
             success,
         }
     }
@@ -1102,7 +1235,8 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         }
     }
 
-    /// Handles Phase2 for EcPairing operation and returns the RLC'd input bytes.
+    /// Handles Phase2 for EcPairing operation and returns the RLC'd input bytes, i.e. the bytes of
+    /// the pairs of the EVM input without the padding pairs.
     fn assign_ec_pairing(
         &self,
         ctx: &mut Context<F>,
@@ -1110,13 +1244,51 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         ecc_chip: &EccChip<F, FpConfig<F, Fq>>,
         keccak_powers: &[QuantumCell<F>],
     ) -> EcPairingAssigned<F> {
-        EcPairingAssigned {
-            is_valid: ec_pairing_decomposed.is_valid,
-            input_rlc: ecc_chip.field_chip().range().gate().inner_product(
+        let gate = ecc_chip.field_chip().range().gate();
+        let pair_rand_pow = keccak_powers[N_BYTES_PER_PAIR].clone();
+
+        let mut input_rlc = gate.load_zero(ctx);
+        for (cells, is_input_pair) in ec_pairing_decomposed
+            .pair_cells
+            .iter()
+            .zip_eq(ec_pairing_decomposed.is_input_pair.iter())
+        {
+            let pair_rlc = gate.inner_product(
                 ctx,
-                ec_pairing_decomposed.input_cells.clone().into_iter().rev(),
+                cells.clone().into_iter().rev(),
                 keccak_powers.iter().cloned(),
-            ),
+            );
+            // a padding pair is all 0 bytes.
+            let is_padding_pair = gate.not(ctx, QuantumCell::Existing(*is_input_pair));
+            let padding_rlc = gate.mul(
+                ctx,
+                QuantumCell::Existing(is_padding_pair),
+                QuantumCell::Existing(pair_rlc),
+            );
+            gate.assert_is_const(ctx, &padding_rlc, F::zero());
+            // input_rlc := input_rlc * r^192 + pair_rlc, for a pair of the EVM input only.
+            let rand_pow = gate.select(
+                ctx,
+                pair_rand_pow.clone(),
+                QuantumCell::Constant(F::one()),
+                QuantumCell::Existing(*is_input_pair),
+            );
+            let shifted_rlc = gate.mul(
+                ctx,
+                QuantumCell::Existing(input_rlc),
+                QuantumCell::Existing(rand_pow),
+            );
+            input_rlc = gate.add(
+                ctx,
+                QuantumCell::Existing(shifted_rlc),
+                QuantumCell::Existing(pair_rlc),
+            );
+        }
+
+        EcPairingAssigned {
+            is_valid: ec_pairing_decomposed.is_valid,
+            n_pairs: ec_pairing_decomposed.n_pairs,
+            input_rlc,
             success: ec_pairing_decomposed.success,
         }
     }
@@ -1322,6 +1494,7 @@ impl<F: Field, const XI_0: i64> SubCircuit<F> for EccCircuit<F, XI_0> {
             max_add_ops: block.circuits_params.max_ec_ops.ec_add,
             max_mul_ops: block.circuits_params.max_ec_ops.ec_mul,
             max_pairing_ops: block.circuits_params.max_ec_ops.ec_pairing,
+            max_pairing_pairs: block.circuits_params.max_ec_ops.ec_pairing_pairs,
             add_ops: block.get_ec_add_ops(),
             mul_ops: block.get_ec_mul_ops(),
             pairing_ops: block.get_ec_pairing_ops(),
@@ -1354,11 +1527,7 @@ impl<F: Field, const XI_0: i64> SubCircuit<F> for EccCircuit<F, XI_0> {
     }
 
     fn min_num_rows_block(block: &Block) -> (usize, usize) {
-        let row_num = if block.circuits_params.max_vertical_circuit_rows == 0 {
-            Self::min_num_rows()
-        } else {
-            block.circuits_params.max_vertical_circuit_rows
-        };
+        let row_num = Self::circuit_rows(&block.circuits_params);
 
         let ec_adds = block.get_ec_add_ops().len();
         let ec_muls = block.get_ec_mul_ops().len();
@@ -1370,10 +1539,11 @@ impl<F: Field, const XI_0: i64> SubCircuit<F> for EccCircuit<F, XI_0> {
         // Instead of showing actual minimum row usage,
         // halo2-lib based circuits use min_row_num to represent a percentage of total-used capacity
         // This functionality allows l2geth to decide if additional ops can be added.
+        let [ec_add, ec_mul, ec_pairing] = Self::row_formulas(&block.circuits_params);
         let min_row_num = [
-            (row_num / max_ec_ops.ec_add) * ec_adds,
-            (row_num / max_ec_ops.ec_mul) * ec_muls,
-            (row_num / max_ec_ops.ec_pairing) * ec_pairings,
+            ec_add.rows(ec_adds),
+            ec_mul.rows(ec_muls),
+            ec_pairing.rows(ec_pairings),
         ]
         .into_iter()
         .max()
@@ -1381,4 +1551,8 @@ impl<F: Field, const XI_0: i64> SubCircuit<F> for EccCircuit<F, XI_0> {
 
         (min_row_num, row_num)
     }
+
+    fn precompile_row_formulas(params: &CircuitsParams) -> Vec<PrecompileRowFormula> {
+        Self::row_formulas(params).to_vec()
+    }
 }
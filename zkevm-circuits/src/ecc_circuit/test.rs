@@ -28,6 +28,7 @@ fn run<F: Field, const MUST_FAIL: bool>(
         max_add_ops: max_ec_ops.ec_add,
         max_mul_ops: max_ec_ops.ec_mul,
         max_pairing_ops: max_ec_ops.ec_pairing,
+        max_pairing_pairs: max_ec_ops.ec_pairing_pairs,
         add_ops,
         mul_ops,
         pairing_ops,
@@ -116,7 +117,7 @@ impl GenRand for EcPairingOp {
         let point_c = G1Affine::from(G1Affine::generator() * alpha * beta);
         let point_d = G2Affine::generator();
 
-        let mut pairs = [
+        let mut pairs = vec![
             EcPairingPair::new(point_p_negated, point_q),
             EcPairingPair::new(point_s, point_t),
             EcPairingPair::new(point_a_negated, point_b),
@@ -251,7 +252,7 @@ mod valid_invalid_cases {
                 let point_q = G2Affine::from(G2Affine::generator() * beta);
                 let point_s = G1Affine::from(G1Affine::generator() * alpha * beta);
                 let point_t = G2Affine::generator();
-                let pairs = [
+                let pairs = vec![
                     EcPairingPair::new(point_p_negated, point_q),
                     EcPairingPair::new(point_s, point_t),
                     EcPairingPair::padding_pair(),
@@ -271,7 +272,7 @@ mod valid_invalid_cases {
                 let point_p_negated = point_p.neg();
                 let point_q = G2Affine::from(G2Affine::generator() * beta);
                 let point_t = G2Affine::from(G2Affine::generator() * alpha * beta);
-                let pairs = [
+                let pairs = vec![
                     EcPairingPair::new(point_p_negated, point_q),
                     EcPairingPair {
                         g1_point: (
@@ -308,7 +309,7 @@ mod valid_invalid_cases {
                 let point_q = G2Affine::from(G2Affine::generator() * beta);
                 let point_s = G1Affine::from(G1Affine::generator() * gamma);
                 let point_t = G2Affine::generator();
-                let pairs = [
+                let pairs = vec![
                     EcPairingPair::new(point_p_negated, point_q),
                     EcPairingPair::new(point_s, point_t),
                     EcPairingPair::padding_pair(),
@@ -322,7 +323,7 @@ mod valid_invalid_cases {
             },
             // 4. invalid: not on curve G1.
             EcPairingOp {
-                pairs: [
+                pairs: vec![
                     EcPairingPair {
                         g1_point: (U256::from(3), U256::from(4)),
                         g2_point: (U256::zero(), U256::zero(), U256::zero(), U256::zero()),
@@ -340,7 +341,7 @@ mod valid_invalid_cases {
         vec![
             // 5. invalid: not on curve G2.
             EcPairingOp {
-                pairs: [
+                pairs: vec![
                     EcPairingPair {
                         g1_point: (U256::zero(), U256::zero()),
                         g2_point: (U256::from(3), U256::from(4), U256::from(5), U256::from(6)),
@@ -354,7 +355,7 @@ mod valid_invalid_cases {
             },
             // 6. valid: all zero.
             EcPairingOp {
-                pairs: [
+                pairs: vec![
                     EcPairingPair::padding_pair(),
                     EcPairingPair::padding_pair(),
                     EcPairingPair::padding_pair(),
@@ -369,7 +370,7 @@ mod valid_invalid_cases {
         vec![
             // 7. valid: [(G1::gen, G2::gen), (-G1::gen, G2::gen)]
             EcPairingOp {
-                pairs: [
+                pairs: vec![
                     EcPairingPair::new(G1Affine::generator(), G2Affine::generator()),
                     EcPairingPair::new(G1Affine::generator().neg(), G2Affine::generator()),
                     EcPairingPair::padding_pair(),
@@ -380,7 +381,7 @@ mod valid_invalid_cases {
             },
             // 8. valid: [(G1::gen, G2::gen), (-G1::gen, G2::gen); 2]
             EcPairingOp {
-                pairs: [
+                pairs: vec![
                     EcPairingPair::new(G1Affine::generator(), G2Affine::generator()),
                     EcPairingPair::new(G1Affine::generator().neg(), G2Affine::generator()),
                     EcPairingPair::new(G1Affine::generator(), G2Affine::generator()),
@@ -391,6 +392,26 @@ mod valid_invalid_cases {
             },
         ]
     });
+    pub(crate) static EC_PAIRING_OPS5: LazyLock<Vec<EcPairingOp>> = LazyLock::new(|| {
+        let pairs = [
+            EcPairingPair::new(G1Affine::generator(), G2Affine::generator()),
+            EcPairingPair::new(G1Affine::generator().neg(), G2Affine::generator()),
+        ];
+        vec![
+            // 9. valid: [(G1::gen, G2::gen), (-G1::gen, G2::gen); 3]
+            EcPairingOp {
+                pairs: pairs.repeat(3),
+                output: 1.into(),
+                ..Default::default()
+            },
+            // 10. valid: [(G1::gen, G2::gen), (-G1::gen, G2::gen)], padded with 4 pairs.
+            EcPairingOp {
+                pairs: pairs.to_vec(),
+                output: 1.into(),
+                ..Default::default()
+            },
+        ]
+    });
 }
 
 #[test]
@@ -399,6 +420,7 @@ fn test_ecc_circuit_valid_invalid() {
     use halo2_proofs::halo2curves::bn256::Fr;
     use valid_invalid_cases::{
         EC_ADD_OPS, EC_MUL_OPS, EC_PAIRING_OPS1, EC_PAIRING_OPS2, EC_PAIRING_OPS3, EC_PAIRING_OPS4,
+        EC_PAIRING_OPS5,
     };
 
     run::<Fr, false>(
@@ -415,6 +437,7 @@ fn test_ecc_circuit_valid_invalid() {
             ec_add: 0,
             ec_mul: 0,
             ec_pairing: 2,
+            ..Default::default()
         },
         vec![],
         vec![],
@@ -427,6 +450,7 @@ fn test_ecc_circuit_valid_invalid() {
             ec_add: 0,
             ec_mul: 0,
             ec_pairing: 2,
+            ..Default::default()
         },
         vec![],
         vec![],
@@ -439,11 +463,25 @@ fn test_ecc_circuit_valid_invalid() {
             ec_add: 0,
             ec_mul: 0,
             ec_pairing: 2,
+            ..Default::default()
         },
         vec![],
         vec![],
         EC_PAIRING_OPS4.clone(),
     );
+
+    run::<Fr, false>(
+        LOG_TOTAL_NUM_ROWS,
+        PrecompileEcParams {
+            ec_add: 0,
+            ec_mul: 0,
+            ec_pairing: 2,
+            ec_pairing_pairs: 6,
+        },
+        vec![],
+        vec![],
+        EC_PAIRING_OPS5.clone(),
+    );
 }
 
 #[ignore = "generate a lot of random invalid inputs for bn254 add"]
@@ -494,6 +532,7 @@ fn test_invalid_ec_add() {
             ec_add: ec_adds.len(),
             ec_mul: 0,
             ec_pairing: 0,
+            ..Default::default()
         },
         ec_adds,
         vec![],
@@ -537,6 +576,7 @@ fn test_invalid_ec_add() {
             ec_add: ec_adds.len(),
             ec_mul: 0,
             ec_pairing: 0,
+            ..Default::default()
         },
         ec_adds,
         vec![],
@@ -590,6 +630,7 @@ fn variadic_size_check() {
         max_add_ops: default_params.ec_add,
         max_mul_ops: default_params.ec_mul,
         max_pairing_ops: default_params.ec_pairing,
+        max_pairing_pairs: default_params.ec_pairing_pairs,
         add_ops: gen(&mut rng, 25, false),
         mul_ops: gen(&mut rng, 20, false),
         pairing_ops: EC_PAIRING_OPS1.clone(),
@@ -601,6 +642,7 @@ fn variadic_size_check() {
         max_add_ops: default_params.ec_add,
         max_mul_ops: default_params.ec_mul,
         max_pairing_ops: default_params.ec_pairing,
+        max_pairing_pairs: default_params.ec_pairing_pairs,
         add_ops: {
             let mut ops = gen(&mut rng, 30, false);
             ops.extend_from_slice(&EC_ADD_OPS);
@@ -619,3 +661,60 @@ fn variadic_size_check() {
     assert_eq!(prover1.fixed(), prover2.fixed());
     assert_eq!(prover1.permutation(), prover2.permutation());
 }
+
+#[test]
+fn row_formulas_share_vertical_rows() {
+    use crate::{super_circuit::SuperCircuit, util::SubCircuit};
+    use bus_mapping::{
+        circuit_input_builder::{CircuitsParams, N_PAIRING_PER_OP},
+        precompile::PrecompileCalls,
+    };
+
+    let params = CircuitsParams {
+        max_vertical_circuit_rows: 1_000_000,
+        max_ec_ops: PrecompileEcParams {
+            ec_add: 50,
+            ec_mul: 50,
+            ec_pairing: 2,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let [ec_add, ec_mul, ec_pairing] = EccCircuit::<Fr, 9>::row_formulas(&params);
+
+    assert_eq!(ec_add.rows(1), 20_000);
+    assert_eq!(ec_mul.rows(50), 1_000_000);
+    // one pairing takes half of the circuit
+    assert_eq!(ec_pairing.rows(1), 500_000);
+    assert!(ec_pairing.fits(2));
+    assert!(!ec_pairing.fits(3));
+    assert!(ec_pairing.fits_pairs(N_PAIRING_PER_OP));
+    assert!(!ec_pairing.fits_pairs(N_PAIRING_PER_OP + 1));
+    assert!(ec_add.fits_pairs(N_PAIRING_PER_OP + 1));
+
+    // ops padded to twice the pairs take twice the rows
+    let params = CircuitsParams {
+        max_ec_ops: PrecompileEcParams {
+            ec_pairing_pairs: 2 * N_PAIRING_PER_OP,
+            ..params.max_ec_ops
+        },
+        ..params
+    };
+    let [_, _, ec_pairing] = EccCircuit::<Fr, 9>::row_formulas(&params);
+    assert_eq!(ec_pairing.rows_per_pair, 125_000);
+    assert_eq!(ec_pairing.rows(1), 1_000_000);
+    assert!(ec_pairing.fits(1));
+    assert!(!ec_pairing.fits(2));
+    assert_eq!(
+        SuperCircuit::<Fr>::precompile_row_formulas(&params)
+            .iter()
+            .map(|formula| formula.op)
+            .collect::<Vec<_>>(),
+        [
+            PrecompileCalls::Modexp,
+            PrecompileCalls::Bn128Add,
+            PrecompileCalls::Bn128Mul,
+            PrecompileCalls::Bn128Pairing,
+        ]
+    );
+}
@@ -86,14 +86,21 @@ pub(super) struct EcMulAssigned<F: Field> {
 /// State of EcPairing operation post first phase.
 pub(super) struct EcPairingDecomposed<F: Field> {
     pub is_valid: AssignedValue<F>,
-    pub input_cells: Vec<QuantumCell<F>>,
+    /// Big-endian bytes of each (G1, G2) pair, padding pairs included.
+    pub pair_cells: Vec<Vec<QuantumCell<F>>>,
+    /// Whether each pair is a pair of the EVM input, rather than a padding pair.
+    pub is_input_pair: Vec<AssignedValue<F>>,
+    /// Number of pairs of the EVM input.
+    pub n_pairs: AssignedValue<F>,
     pub success: AssignedValue<F>,
 }
 
 /// State of EcPairing operation post second phase.
 pub(super) struct EcPairingAssigned<F: Field> {
     pub is_valid: AssignedValue<F>,
-    /// RLC of (G1, G2) pairs.
+    /// Number of pairs of the EVM input.
+    pub n_pairs: AssignedValue<F>,
+    /// RLC of the (G1, G2) pairs of the EVM input.
     pub input_rlc: AssignedValue<F>,
     pub success: AssignedValue<F>,
 }
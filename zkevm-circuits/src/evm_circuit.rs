@@ -32,9 +32,30 @@ use execution::ExecutionConfig;
 use itertools::Itertools;
 use strum::IntoEnumIterator;
 use table::FixedTableTag;
-pub use util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon};
+pub use util::{
+    constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    instrumentation::GadgetReport,
+};
 use witness::Block;
 
+/// What a row of the [`EvmCircuit`] is assigned with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvmRowOwner {
+    /// Row `offset` of the step `step_index` of the tx `tx_index` of the
+    /// block, both indexes starting at 0.
+    Step {
+        tx_index: usize,
+        step_index: usize,
+        offset: usize,
+    },
+    /// A padding step.
+    Padding,
+    /// Row `offset` of the EndBlock step.
+    EndBlock { offset: usize },
+    /// A row after the EndBlock step.
+    Unused,
+}
+
 /// EvmCircuitConfig implements verification of execution trace of a block.
 #[derive(Clone, Debug)]
 pub struct EvmCircuitConfig<F> {
@@ -279,6 +300,49 @@ impl<F: Field> EvmCircuit<F> {
         num_rows
     }
 
+    /// Owner of `row` of the circuit assigned with `block`, following the
+    /// layout of `assign_block`: the steps of the txs, the padding steps, then
+    /// the EndBlock step.
+    pub fn row_owner(block: &Block, row: usize) -> EvmRowOwner {
+        let mut start = 0;
+        for (tx_index, tx) in block.txs.iter().enumerate() {
+            for (step_index, step) in tx.steps.iter().enumerate() {
+                let height = step.execution_state.get_step_height();
+                if row < start + height {
+                    return EvmRowOwner::Step {
+                        tx_index,
+                        step_index,
+                        offset: row - start,
+                    };
+                }
+                start += height;
+            }
+        }
+        let end_block_height = ExecutionState::EndBlock.get_step_height();
+        let padding_height = match block.circuits_params.max_evm_rows {
+            0 => 1,
+            evm_rows => evm_rows.saturating_sub(start + end_block_height + 1),
+        };
+        if row < start + padding_height {
+            return EvmRowOwner::Padding;
+        }
+        start += padding_height;
+        if row < start + end_block_height {
+            return EvmRowOwner::EndBlock {
+                offset: row - start,
+            };
+        }
+        EvmRowOwner::Unused
+    }
+
+    /// Height, cells by cell type and max constraint degree of the gadget of
+    /// each execution state.
+    pub fn gadget_report() -> Vec<GadgetReport> {
+        let mut meta = ConstraintSystem::<F>::default();
+        let (config, _) = Self::configure(&mut meta);
+        config.execution.instrument().gadget_reports()
+    }
+
     pub fn get_test_cicuit_from_block(block: Block) -> Self {
         let fixed_table_tags = detect_fixed_table_tags(&block);
         EvmCircuit::<F>::new_dev(block, fixed_table_tags)
@@ -513,9 +577,13 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
         config
             .bytecode_table
             .dev_load(&mut layouter, block.bytecodes.values(), &challenges)?;
-        config
-            .block_table
-            .dev_load(&mut layouter, &block.context, &block.txs, &challenges)?;
+        config.block_table.dev_load(
+            &mut layouter,
+            &block.context,
+            &block.txs,
+            &block.chunk.unwrap_or_default(),
+            &challenges,
+        )?;
         config
             .copy_table
             .dev_load(&mut layouter, block, &challenges)?;
@@ -536,7 +604,7 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
             .dev_load(&mut layouter, block, &challenges)?;
         config
             .modexp_table
-            .dev_load(&mut layouter, &block.get_big_modexp())?;
+            .dev_load(&mut layouter, &block.get_big_modexp(), &challenges)?;
         config.ecc_table.dev_load(
             &mut layouter,
             block.circuits_params.max_ec_ops,
@@ -627,6 +695,16 @@ mod evm_circuit_stats {
         )
     }
 
+    #[test]
+    fn gadget_report_matches_step_heights() {
+        let reports = EvmCircuit::<Fr>::gadget_report();
+        assert!(!reports.is_empty());
+        for report in reports {
+            assert_eq!(report.height, report.state.get_step_height());
+            assert!(report.max_degree > 0);
+        }
+    }
+
     #[test]
     pub fn empty_evm_circuit_no_padding() {
         CircuitTestBuilder::new_from_test_ctx(
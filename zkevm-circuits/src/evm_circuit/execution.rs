@@ -19,9 +19,9 @@ use crate::{
             },
             rlc, CellType,
         },
-        witness::{Block, Call, ExecStep, Transaction},
+        witness::{Block, Call, ExecStep, Rw, Transaction},
     },
-    table::{LookupTable, RwTableTag, TxReceiptFieldTag},
+    table::{LookupTable, TxReceiptFieldTag},
     util::{query_expression, Challenges, Expr, Field},
 };
 use bus_mapping::util::read_env_var;
@@ -51,7 +51,7 @@ use halo2_proofs::plonk::SecondPhase;
 #[cfg(not(feature = "onephase"))]
 use halo2_proofs::plonk::ThirdPhase;
 
-use strum::{EnumCount, IntoEnumIterator};
+use strum::IntoEnumIterator;
 pub(crate) static CHECK_RW_LOOKUP: LazyLock<bool> =
     LazyLock::new(|| read_env_var("CHECK_RW_LOOKUP", false));
 
@@ -59,8 +59,11 @@ mod add_sub;
 mod addmod;
 mod address;
 mod balance;
+mod begin_chunk;
 mod begin_tx;
 mod bitwise;
+#[cfg(not(feature = "scroll"))]
+mod blobhash;
 mod block_ctx;
 mod blockhash;
 mod byte;
@@ -79,6 +82,7 @@ mod create;
 mod dummy;
 mod dup;
 mod end_block;
+mod end_chunk;
 mod end_inner_block;
 mod end_tx;
 mod error_code_store;
@@ -139,15 +143,22 @@ mod sload;
 mod sstore;
 mod stop;
 mod swap;
+#[cfg(feature = "cancun")]
 mod tload;
+#[cfg(feature = "cancun")]
 mod tstore;
 
 use add_sub::AddSubGadget;
 use addmod::AddModGadget;
 use address::AddressGadget;
 use balance::BalanceGadget;
+use begin_chunk::BeginChunkGadget;
 use begin_tx::BeginTxGadget;
 use bitwise::BitwiseGadget;
+#[cfg(not(feature = "scroll"))]
+use blobhash::BlobHashGadget;
+#[cfg(not(feature = "scroll"))]
+use block_ctx::BlobBaseFeeGadget;
 #[cfg(feature = "scroll")]
 use block_ctx::DifficultyGadget;
 use block_ctx::{BlockCtxU160Gadget, BlockCtxU256Gadget, BlockCtxU64Gadget};
@@ -168,6 +179,7 @@ use create::CreateGadget;
 use dummy::DummyGadget;
 use dup::DupGadget;
 use end_block::EndBlockGadget;
+use end_chunk::EndChunkGadget;
 use end_inner_block::EndInnerBlockGadget;
 use end_tx::EndTxGadget;
 use error_code_store::ErrorCodeStoreGadget;
@@ -230,7 +242,9 @@ use sload::SloadGadget;
 use sstore::SstoreGadget;
 use stop::StopGadget;
 use swap::SwapGadget;
+#[cfg(feature = "cancun")]
 use tload::TloadGadget;
+#[cfg(feature = "cancun")]
 use tstore::TstoreGadget;
 
 pub(crate) trait ExecutionGadget<F: Field> {
@@ -275,6 +289,8 @@ pub(crate) struct ExecutionConfig<F> {
     instrument: Instrument,
     // internal state gadgets
     begin_tx_gadget: Box<BeginTxGadget<F>>,
+    begin_chunk_gadget: Box<BeginChunkGadget<F>>,
+    end_chunk_gadget: Box<EndChunkGadget<F>>,
     end_block_gadget: Box<EndBlockGadget<F>>,
     padding_gadget: Box<PaddingGadget<F>>,
     end_inner_block_gadget: Box<EndInnerBlockGadget<F>>,
@@ -334,7 +350,9 @@ pub(crate) struct ExecutionConfig<F> {
     signextend_gadget: Box<SignextendGadget<F>>,
     sload_gadget: Box<SloadGadget<F>>,
     sstore_gadget: Box<SstoreGadget<F>>,
+    #[cfg(feature = "cancun")]
     tload_gadget: Box<TloadGadget<F>>,
+    #[cfg(feature = "cancun")]
     tstore_gadget: Box<TstoreGadget<F>>,
     stop_gadget: Box<StopGadget<F>>,
     swap_gadget: Box<SwapGadget<F>>,
@@ -344,6 +362,10 @@ pub(crate) struct ExecutionConfig<F> {
     block_ctx_u256_gadget: Box<BlockCtxU256Gadget<F>>,
     #[cfg(feature = "scroll")]
     difficulty_gadget: Box<DifficultyGadget<F>>,
+    #[cfg(not(feature = "scroll"))]
+    blobhash_gadget: Box<BlobHashGadget<F>>,
+    #[cfg(not(feature = "scroll"))]
+    blobbasefee_gadget: Box<BlobBaseFeeGadget<F>>,
     // error gadgets
     error_oog_call: Box<ErrorOOGCallGadget<F>>,
     error_oog_precompile: Box<ErrorOOGPrecompileGadget<F>>,
@@ -440,11 +462,14 @@ impl<F: Field> ExecutionConfig<F> {
 
             // NEW: Enabled, this will break hand crafted tests, maybe we can remove them?
             let first_step_check = {
-                let begin_tx_or_padding_selector = step_curr
-                    .execution_state_selector([ExecutionState::BeginTx, ExecutionState::Padding]);
+                let first_step_selector = step_curr.execution_state_selector([
+                    ExecutionState::BeginTx,
+                    ExecutionState::BeginChunk,
+                    ExecutionState::Padding,
+                ]);
                 iter::once((
-                    "First step should be BeginTx or Padding",
-                    q_step_first * (1.expr() - begin_tx_or_padding_selector),
+                    "First step should be BeginTx, BeginChunk or Padding",
+                    q_step_first * (1.expr() - first_step_selector),
                 ))
             };
 
@@ -469,14 +494,18 @@ impl<F: Field> ExecutionConfig<F> {
             let q_step = meta.query_advice(q_step, Rotation::cur());
             let mut cb = BaseConstraintBuilder::default();
             // q_step needs to be enabled on the first row
-            // rw_counter starts at 1
+            // rw_counter starts at 1, unless a later chunk begins, whose BeginChunk
+            // constrains its initial rw_counter.
+            let is_begin_chunk = step_curr.execution_state_selector([ExecutionState::BeginChunk]);
             cb.condition(q_usable, |cb| {
                 cb.require_equal("q_step == 1", q_step.clone(), 1.expr());
-                cb.require_equal(
-                    "rw_counter is initialized to be 1",
-                    step_curr.state.rw_counter.expr(),
-                    1.expr(),
-                )
+                cb.condition(1.expr() - is_begin_chunk, |cb| {
+                    cb.require_equal(
+                        "rw_counter is initialized to be 1",
+                        step_curr.state.rw_counter.expr(),
+                        1.expr(),
+                    )
+                });
             });
             cb.gate(q_step_first)
         });
@@ -571,6 +600,8 @@ impl<F: Field> ExecutionConfig<F> {
             advices,
             // internal states
             begin_tx_gadget: configure_gadget!(),
+            begin_chunk_gadget: configure_gadget!(),
+            end_chunk_gadget: configure_gadget!(),
             end_block_gadget: configure_gadget!(),
             end_inner_block_gadget: configure_gadget!(),
             end_tx_gadget: configure_gadget!(),
@@ -631,7 +662,9 @@ impl<F: Field> ExecutionConfig<F> {
             signextend_gadget: configure_gadget!(),
             sload_gadget: configure_gadget!(),
             sstore_gadget: configure_gadget!(),
+            #[cfg(feature = "cancun")]
             tload_gadget: configure_gadget!(),
+            #[cfg(feature = "cancun")]
             tstore_gadget: configure_gadget!(),
             stop_gadget: configure_gadget!(),
             swap_gadget: configure_gadget!(),
@@ -640,6 +673,10 @@ impl<F: Field> ExecutionConfig<F> {
             block_ctx_u256_gadget: configure_gadget!(),
             #[cfg(feature = "scroll")]
             difficulty_gadget: configure_gadget!(),
+            #[cfg(not(feature = "scroll"))]
+            blobhash_gadget: configure_gadget!(),
+            #[cfg(not(feature = "scroll"))]
+            blobbasefee_gadget: configure_gadget!(),
             // error gadgets
             error_oog_constant: configure_gadget!(),
             error_oog_static_memory_gadget: configure_gadget!(),
@@ -796,7 +833,7 @@ impl<F: Field> ExecutionConfig<F> {
             (height - 1).expr(),
         );
 
-        instrument.on_gadget_built(execution_state, &cb);
+        instrument.on_gadget_built(execution_state, height, &cb);
 
         let (state_selector, constraints, stored_expressions, _) = cb.build();
         debug_assert!(
@@ -862,9 +899,19 @@ impl<F: Field> ExecutionConfig<F> {
                             vec![ExecutionState::BeginTx, ExecutionState::EndInnerBlock],
                         ),
                         (
-                            "EndInnerBlock can only transition to BeginTx, EndInnerBlock or Padding",
+                            "EndInnerBlock can only transition to BeginTx, EndInnerBlock, EndChunk or Padding",
                             ExecutionState::EndInnerBlock,
-                            vec![ExecutionState::BeginTx, ExecutionState::EndInnerBlock, ExecutionState::Padding],
+                            vec![ExecutionState::BeginTx, ExecutionState::EndInnerBlock, ExecutionState::EndChunk, ExecutionState::Padding],
+                        ),
+                        (
+                            "BeginChunk can only transit to BeginTx",
+                            ExecutionState::BeginChunk,
+                            vec![ExecutionState::BeginTx],
+                        ),
+                        (
+                            "EndChunk can only transit to Padding",
+                            ExecutionState::EndChunk,
+                            vec![ExecutionState::Padding],
                         ),
                         (
                             "Padding can only transit to Padding or EndBlock",
@@ -878,9 +925,20 @@ impl<F: Field> ExecutionConfig<F> {
                 .chain(
                     IntoIterator::into_iter([
                         (
-                            "Only EndTx or EndInnerBlock can transit to BeginTx",
+                            "Only EndTx, EndInnerBlock or BeginChunk can transit to BeginTx",
                             ExecutionState::BeginTx,
-                            vec![ExecutionState::EndTx, ExecutionState::EndInnerBlock],
+                            vec![ExecutionState::EndTx, ExecutionState::EndInnerBlock, ExecutionState::BeginChunk],
+                        ),
+                        (
+                            // BeginChunk can only be the first step.
+                            "No state can transit to BeginChunk",
+                            ExecutionState::BeginChunk,
+                            vec![],
+                        ),
+                        (
+                            "Only EndInnerBlock can transit to EndChunk",
+                            ExecutionState::EndChunk,
+                            vec![ExecutionState::EndInnerBlock],
                         ),
                         (
                             "Only ExecutionState which halts / precompile or BeginTx can transit to EndTx",
@@ -915,9 +973,9 @@ impl<F: Field> ExecutionConfig<F> {
                             step_next.state.block_number.expr() - step_curr.state.block_number.expr() - 1.expr(),
                         ),
                         (
-                            "EndInnerBlock -> Padding: block number does not change",
+                            "EndInnerBlock -> EndChunk/Padding: block number does not change",
                             ExecutionState::EndInnerBlock,
-                            vec![ExecutionState::Padding],
+                            vec![ExecutionState::EndChunk, ExecutionState::Padding],
                             step_next.state.block_number.expr() - step_curr.state.block_number.expr(),
                         ),
                     ])
@@ -1166,23 +1224,22 @@ impl<F: Field> ExecutionConfig<F> {
                 tx.steps.clear();
                 tx.rlp_signed.clear();
                 tx.rlp_unsigned.clear();
-                let total_gas = {
-                    let gas_used = tx.gas - step.gas_left;
-                    let current_cumulative_gas_used: u64 = if tx.id == 1 {
-                        0
-                    } else {
-                        // first transaction needs TxReceiptFieldTag::COUNT(3) lookups
-                        // to tx receipt,
-                        // while later transactions need 4 (with one extra cumulative
-                        // gas read) lookups
-                        let rw = &block.rws[(
-                            RwTableTag::TxReceipt,
-                            (tx.id - 2) * (TxReceiptFieldTag::COUNT + 1) + 2,
-                        )];
-                        rw.receipt_value()
-                    };
-                    current_cumulative_gas_used + gas_used
-                };
+                // the cumulative gas used written by the EndTx
+                let total_gas = step
+                    .rw_indices
+                    .iter()
+                    .map(|idx| &block.rws[*idx])
+                    .find(|rw| {
+                        matches!(
+                            rw,
+                            Rw::TxReceipt {
+                                is_write: true,
+                                field_tag: TxReceiptFieldTag::CumulativeGasUsed,
+                                ..
+                            }
+                        )
+                    })
+                    .map_or(0, |rw| rw.receipt_value());
                 log::info!(
                     "offset {} tx_num {} total_gas {} assign last step {:?} of tx {:?}",
                     offset,
@@ -1571,6 +1628,8 @@ impl<F: Field> ExecutionConfig<F> {
             // internal states
             ExecutionState::BeginTx => assign_exec_step!(self.begin_tx_gadget),
             ExecutionState::EndTx => assign_exec_step!(self.end_tx_gadget),
+            ExecutionState::BeginChunk => assign_exec_step!(self.begin_chunk_gadget),
+            ExecutionState::EndChunk => assign_exec_step!(self.end_chunk_gadget),
             ExecutionState::EndInnerBlock => assign_exec_step!(self.end_inner_block_gadget),
             ExecutionState::EndBlock => assign_exec_step!(self.end_block_gadget),
             ExecutionState::Padding => assign_exec_step!(self.padding_gadget),
@@ -1623,6 +1682,10 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::BLOCKCTXU256 => assign_exec_step!(self.block_ctx_u256_gadget),
             #[cfg(feature = "scroll")]
             ExecutionState::DIFFICULTY => assign_exec_step!(self.difficulty_gadget),
+            #[cfg(not(feature = "scroll"))]
+            ExecutionState::BLOBHASH => assign_exec_step!(self.blobhash_gadget),
+            #[cfg(not(feature = "scroll"))]
+            ExecutionState::BLOBBASEFEE => assign_exec_step!(self.blobbasefee_gadget),
             ExecutionState::BLOCKHASH => assign_exec_step!(self.blockhash_gadget),
             ExecutionState::SELFBALANCE => assign_exec_step!(self.selfbalance_gadget),
             ExecutionState::CREATE => assign_exec_step!(self.create_gadget),
@@ -1639,7 +1702,9 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::SIGNEXTEND => assign_exec_step!(self.signextend_gadget),
             ExecutionState::SLOAD => assign_exec_step!(self.sload_gadget),
             ExecutionState::SSTORE => assign_exec_step!(self.sstore_gadget),
+            #[cfg(feature = "cancun")]
             ExecutionState::TLOAD => assign_exec_step!(self.tload_gadget),
+            #[cfg(feature = "cancun")]
             ExecutionState::TSTORE => assign_exec_step!(self.tstore_gadget),
             ExecutionState::STOP => assign_exec_step!(self.stop_gadget),
             ExecutionState::SWAP => assign_exec_step!(self.swap_gadget),
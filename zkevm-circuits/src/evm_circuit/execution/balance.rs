@@ -4,21 +4,21 @@ use crate::{
         param::N_BYTES_ACCOUNT_ADDRESS,
         step::ExecutionState,
         util::{
-            common_gadget::SameContextGadget,
+            common_gadget::{AccountAccessGadget, SameContextGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::Delta,
             },
             from_bytes,
             math_gadget::IsZeroGadget,
-            not, select, CachedRegion, Cell, Word,
+            not, CachedRegion, Cell, Word,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
     table::{AccountFieldTag, CallContextFieldTag},
     util::{Expr, Field},
 };
-use eth_types::{evm_types::GasCost, ToLittleEndian};
+use eth_types::ToLittleEndian;
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
@@ -27,7 +27,7 @@ pub(crate) struct BalanceGadget<F> {
     address_word: Word<F>,
     reversion_info: ReversionInfo<F>,
     tx_id: Cell<F>,
-    is_warm: Cell<F>,
+    account_access: AccountAccessGadget<F>,
     code_hash: Cell<F>,
     not_exists: IsZeroGadget<F>,
     balance: Cell<F>,
@@ -45,12 +45,10 @@ impl<F: Field> ExecutionGadget<F> for BalanceGadget<F> {
 
         let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
         let mut reversion_info = cb.reversion_info_read(None);
-        let is_warm = cb.query_bool();
-        cb.account_access_list_write(
+        let account_access = AccountAccessGadget::construct(
+            cb,
             tx_id.expr(),
             address.expr(),
-            1.expr(),
-            is_warm.expr(),
             Some(&mut reversion_info),
         );
         let code_hash = cb.query_cell_phase2();
@@ -68,17 +66,11 @@ impl<F: Field> ExecutionGadget<F> for BalanceGadget<F> {
 
         cb.stack_push(balance.expr());
 
-        let gas_cost = select::expr(
-            is_warm.expr(),
-            GasCost::WARM_ACCESS.expr(),
-            GasCost::COLD_ACCOUNT_ACCESS.expr(),
-        );
-
         let step_state_transition = StepStateTransition {
             rw_counter: Delta(7.expr() + exists.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(0.expr()),
-            gas_left: Delta(-gas_cost),
+            gas_left: Delta(-account_access.gas_cost()),
             reversible_write_counter: Delta(1.expr()),
             ..Default::default()
         };
@@ -91,7 +83,7 @@ impl<F: Field> ExecutionGadget<F> for BalanceGadget<F> {
             address_word,
             reversion_info,
             tx_id,
-            is_warm,
+            account_access,
             code_hash,
             not_exists,
             balance,
@@ -124,9 +116,8 @@ impl<F: Field> ExecutionGadget<F> for BalanceGadget<F> {
             call.is_persistent,
         )?;
 
-        let (_, is_warm) = block.rws[step.rw_indices[4]].tx_access_list_value_pair();
-        self.is_warm
-            .assign(region, offset, Value::known(F::from(is_warm)))?;
+        self.account_access
+            .assign(region, offset, &block.rws[step.rw_indices[4]])?;
 
         let code_hash = block.rws[step.rw_indices[5]].account_value_pair().0;
         self.code_hash
@@ -202,6 +193,19 @@ mod test {
         test_internal_ok(0x1010, 0xff, &account, true);
     }
 
+    #[test]
+    fn balance_gadget_precompile_account() {
+        // Precompiles are in the access list from the beginning of the tx.
+        let account = Some(Account {
+            address: address!("0x0000000000000000000000000000000000000004"),
+            balance: U256::from(900),
+            ..Default::default()
+        });
+
+        test_root_ok(&account, false);
+        test_internal_ok(0x20, 0x00, &account, false);
+    }
+
     fn test_root_ok(account: &Option<Account>, is_warm: bool) {
         let address = account.as_ref().map(|a| a.address).unwrap_or(*TEST_ADDRESS);
 
@@ -0,0 +1,70 @@
+use std::marker::PhantomData;
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            constraint_builder::{EVMConstraintBuilder, StepStateTransition, Transition::Same},
+            CachedRegion,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{BlockContextFieldTag, CallContextFieldTag},
+    util::{Expr, Field},
+};
+use halo2_proofs::plonk::Error;
+
+/// First step of a chunk but the first of its block, which continues where the
+/// previous chunk ends.
+#[derive(Clone, Debug)]
+pub(crate) struct BeginChunkGadget<F> {
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BeginChunkGadget<F> {
+    const NAME: &'static str = "BeginChunk";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BeginChunk;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // The rw counter carries over from the end of the previous chunk.
+        cb.block_lookup(
+            BlockContextFieldTag::ChunkInitialRwc.expr(),
+            0.expr(),
+            cb.curr.state.rw_counter.expr(),
+        );
+
+        // The txs of the chunk are numbered from 1 as in a block of their own, so the first
+        // one gets tx_id 1. The lookup uses the next step initial rwc, thus the same record
+        // as the TxId write of the BeginTx.
+        let next_step_rwc = cb.next.state.rw_counter.expr();
+        cb.call_context_lookup_write_with_counter(
+            next_step_rwc.clone(),
+            Some(next_step_rwc),
+            CallContextFieldTag::TxId,
+            1.expr(),
+        );
+
+        cb.require_step_state_transition(StepStateTransition {
+            rw_counter: Same,
+            ..StepStateTransition::any()
+        });
+
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        _region: &mut CachedRegion<'_, '_, F>,
+        _offset: usize,
+        _block: &Block,
+        _: &Transaction,
+        _: &Call,
+        _step: &ExecStep,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+}
@@ -30,14 +30,19 @@ use crate::{
     util::Field,
 };
 use array_init::array_init;
-use bus_mapping::{circuit_input_builder::CopyDataType, precompile::PrecompileCalls};
-use eth_types::{utils::is_precompiled, Address, ToLittleEndian, U256};
+use bus_mapping::{
+    circuit_input_builder::CopyDataType,
+    precompile::{PrecompileCalls, LAST_PRECOMPILE_ADDRESS},
+};
+use eth_types::{
+    evm_types::MAX_INIT_CODE_SIZE, utils::is_precompiled, Address, ToLittleEndian, U256,
+};
 use ethers_core::utils::{get_contract_address, keccak256, rlp::RlpStream};
 use gadgets::util::{expr_from_bytes, not, select, Expr};
 use gadgets::ToScalar;
 use halo2_proofs::{circuit::Value, plonk::Error};
 
-const PRECOMPILE_COUNT: usize = 9;
+const PRECOMPILE_COUNT: usize = LAST_PRECOMPILE_ADDRESS as usize;
 
 #[derive(Clone, Debug)]
 pub(crate) struct BeginTxGadget<F> {
@@ -45,6 +50,8 @@ pub(crate) struct BeginTxGadget<F> {
     tx_type: Cell<F>,
     sender_nonce: Cell<F>,
     tx_nonce: Cell<F>,
+    // EIP-2681: the nonce of a tx is less than 2^64 - 1.
+    tx_nonce_in_range: LtGadget<F, N_BYTES_U64>,
     tx_gas: Cell<F>,
     tx_gas_price: Word<F>,
     mul_gas_fee_by_gas: MulWordByU64Gadget<F>,
@@ -59,6 +66,8 @@ pub(crate) struct BeginTxGadget<F> {
     tx_call_data_length: Cell<F>,
     is_call_data_empty: IsZeroGadget<F>,
     tx_call_data_word_length: ConstantDivisionGadget<F, N_BYTES_U64>,
+    // EIP-3860: the init code of a creation tx is at most 49152 bytes.
+    init_code_size_not_overflow: LtGadget<F, N_BYTES_U64>,
     tx_call_data_gas_cost: Cell<F>,
     // The gas cost for rlp-encoded bytes of unsigned tx
     tx_data_gas_cost: Cell<F>,
@@ -87,7 +96,7 @@ pub(crate) struct BeginTxGadget<F> {
     init_code_rlc: Cell<F>,
     /// RLP gadget for CREATE address.
     create: ContractCreateGadget<F, false>,
-    // Caller, callee, coinbase, precompile addresses (9) and optional
+    // Caller, callee, coinbase, precompile addresses and optional
     // access-list addresses are added to the access list.
     // <https://github.com/ethereum/go-ethereum/blob/604e215d1bb070dff98fb76aa965064c74e3633f/core/state/statedb.go#L1098>
     is_caller_warm: Cell<F>,
@@ -136,6 +145,8 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             ]
             .map(|field_tag| cb.tx_context(tx_id.expr(), field_tag, None));
 
+        let tx_nonce_in_range = LtGadget::construct(cb, tx_nonce.expr(), u64::MAX.expr());
+
         let tx_signed_length = cb.tx_context(tx_id.expr(), TxContextFieldTag::TxHashLength, None);
         let tx_access_list = TxAccessListGadget::construct(cb, tx_id.expr(), tx_type.expr());
         let is_call_data_empty = IsZeroGadget::construct(cb, tx_call_data_length.expr());
@@ -149,6 +160,8 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 tx_nonce.expr(),
                 sender_nonce.expr(),
             );
+            // so that the increased sender nonce is still a u64
+            cb.require_equal("tx.nonce < 2^64 - 1", tx_nonce_in_range.expr(), 1.expr());
             TxL1FeeGadget::construct(
                 cb,
                 not::expr(curie.is_before_curie.expr()),
@@ -166,11 +179,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 tx_l1_fee.tx_l1_fee_word().expr(),
             );
         });
-        // the rw delta caused by l1 related handling
+        // the rw delta caused by l1 related handling, and the caller code hash read of non l1
+        // msg txs
         let l1_rw_delta = select::expr(
             tx_l1_msg.is_l1_msg(),
             tx_l1_msg.rw_delta(),
-            tx_l1_fee.rw_delta(not::expr(curie.is_before_curie.expr())),
+            tx_l1_fee.rw_delta(not::expr(curie.is_before_curie.expr())) + 1.expr(),
         ) + 1.expr();
 
         // the cost caused by l1
@@ -247,8 +261,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             from_bytes::expr(&tx_fee.cells[..16]),
         );
 
-        // a valid precompile address is: 1 <= addr <= 9 (addr != 0 && addr < 0xA)
-        let is_precompile_lt = LtGadget::construct(cb, tx_callee_address.expr(), 0xA.expr());
+        // a valid precompile address is: 1 <= addr <= LAST_PRECOMPILE_ADDRESS
+        let is_precompile_lt = LtGadget::construct(
+            cb,
+            tx_callee_address.expr(),
+            (LAST_PRECOMPILE_ADDRESS as u64 + 1).expr(),
+        );
         let is_precompile = and::expr([
             not::expr(tx_callee_address_is_zero.expr()),
             is_precompile_lt.expr(),
@@ -258,6 +276,21 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         let tx_call_data_word_length =
             ConstantDivisionGadget::construct(cb, tx_call_data_length.expr() + 31.expr(), 32);
 
+        // Creation txs with an oversized init code are invalid, they cannot be
+        // included in a block.
+        let init_code_size_not_overflow = LtGadget::construct(
+            cb,
+            tx_call_data_length.expr(),
+            (MAX_INIT_CODE_SIZE + 1).expr(),
+        );
+        cb.condition(tx_is_create.expr(), |cb| {
+            cb.require_equal(
+                "Init code size of a creation tx must be not overflow",
+                init_code_size_not_overflow.expr(),
+                1.expr(),
+            );
+        });
+
         // Use intrinsic gas
         // TODO2: constrain calling precompile directly
 
@@ -798,6 +831,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             tx_id,
             tx_type,
             sender_nonce,
+            tx_nonce_in_range,
             tx_nonce,
             tx_gas,
             tx_gas_price,
@@ -813,6 +847,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             tx_call_data_length,
             is_call_data_empty,
             tx_call_data_word_length,
+            init_code_size_not_overflow,
             tx_call_data_gas_cost,
             tx_data_gas_cost,
             tx_signed_length,
@@ -861,8 +896,8 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         ////////////// RWS ////////////////
         // TxID
         // gen_tx_access_list_ops
+        // CodeHash
         // if L1:
-        //      CodeHash
         //      if empty:
         //          CodeHash
         //          if scroll:
@@ -884,16 +919,15 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         debug_assert_eq!(rw.field_tag(), Some(CallContextFieldTag::TxId as u64));
 
         let tx_type = tx.tx_type;
-        let caller_code_hash = if tx_type.is_l1_msg() {
-            let caller_code_hash_pair = rws.next().account_codehash_pair();
-            assert_eq!(
-                caller_code_hash_pair.0, caller_code_hash_pair.1,
-                "expected a read for code hash"
-            );
-            caller_code_hash_pair.0
-        } else {
-            U256::zero()
-        };
+        // Add access-list RW offset.
+        rws.offset_add(TxAccessListGadget::<F>::rw_delta_value(tx) as usize);
+
+        let caller_code_hash_pair = rws.next().account_codehash_pair();
+        assert_eq!(
+            caller_code_hash_pair.0, caller_code_hash_pair.1,
+            "expected a read for code hash"
+        );
+        let caller_code_hash = caller_code_hash_pair.0;
         self.tx_l1_msg
             .assign(region, offset, tx_type, caller_code_hash)?;
 
@@ -901,8 +935,6 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             block.chain_id,
             tx.block_number,
         );
-        // Add access-list RW offset.
-        rws.offset_add(TxAccessListGadget::<F>::rw_delta_value(tx) as usize);
 
         rws.offset_add(if tx_type.is_l1_msg() {
             if caller_code_hash.is_zero() {
@@ -999,6 +1031,8 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             .assign(region, offset, Value::known(F::from(tx_type as u64)))?;
         self.tx_nonce
             .assign(region, offset, Value::known(F::from(tx.nonce)))?;
+        self.tx_nonce_in_range
+            .assign(region, offset, F::from(tx.nonce), F::from(u64::MAX))?;
         self.sender_nonce
             .assign(region, offset, Value::known(F::from(nonce_rw.1.as_u64())))?;
         self.tx_gas
@@ -1031,8 +1065,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             .assign(region, offset, Value::known(callee_address))?;
         self.tx_callee_address_is_zero
             .assign(region, offset, callee_address)?;
-        self.is_precompile_lt
-            .assign(region, offset, callee_address, F::from(0xA))?;
+        self.is_precompile_lt.assign(
+            region,
+            offset,
+            callee_address,
+            F::from(LAST_PRECOMPILE_ADDRESS as u64 + 1),
+        )?;
         // precompile related assignment.
         let (precompile_input_len, precompile_input_bytes_rlc) = if tx
             .callee_address
@@ -1095,6 +1133,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             .assign(region, offset, F::from(tx.call_data_length as u64))?;
         self.tx_call_data_word_length
             .assign(region, offset, tx.call_data_length as u128 + 31)?;
+        self.init_code_size_not_overflow.assign(
+            region,
+            offset,
+            F::from(tx.call_data_length as u64),
+            F::from(MAX_INIT_CODE_SIZE + 1),
+        )?;
         self.tx_call_data_gas_cost.assign(
             region,
             offset,
@@ -1690,6 +1734,83 @@ mod test {
         CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
+    fn test_precompile_as_tx_target(precompile: u64, input: Vec<u8>) {
+        let ctx = TestContext::<1, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(Address::from_low_u64_be(precompile))
+                    .input(Bytes::from(input));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    /// Every precompile called directly as the tx target, each with an input
+    /// that makes the call succeed (or fail gracefully for the ones without
+    /// dedicated gadgets).
+    #[test]
+    fn begin_tx_precompile_each_address() {
+        let ecrecover_input = hex::decode(
+            "456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3\
+             000000000000000000000000000000000000000000000000000000000000001c\
+             9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608\
+             4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada",
+        )
+        .unwrap();
+        let modexp_input = hex::decode(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+             0000000000000000000000000000000000000000000000000000000000000001\
+             0000000000000000000000000000000000000000000000000000000000000001\
+             08090a",
+        )
+        .unwrap();
+        let ec_add_input = hex::decode(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+             0000000000000000000000000000000000000000000000000000000000000002\
+             0000000000000000000000000000000000000000000000000000000000000001\
+             0000000000000000000000000000000000000000000000000000000000000002",
+        )
+        .unwrap();
+        let ec_mul_input = hex::decode(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+             0000000000000000000000000000000000000000000000000000000000000002\
+             0000000000000000000000000000000000000000000000000000000000000002",
+        )
+        .unwrap();
+
+        for (precompile, input) in [
+            (0x01, ecrecover_input),
+            (0x02, vec![0x01, 0x02, 0x03]),
+            (0x03, vec![0x01, 0x02, 0x03]),
+            (0x04, vec![0x01, 0x02, 0x03]),
+            (0x05, modexp_input),
+            (0x06, ec_add_input),
+            (0x07, ec_mul_input),
+            // empty input is a valid pairing check (of zero pairs).
+            (0x08, vec![]),
+            // malformed input length, blake2f fails without consuming the tx.
+            (0x09, vec![0x01, 0x02, 0x03]),
+        ] {
+            test_precompile_as_tx_target(precompile, input);
+        }
+    }
+
+    /// Precompile called directly as the tx target with empty calldata.
+    #[test]
+    fn begin_tx_precompile_each_address_empty_input() {
+        for precompile in 0x01..=0x09 {
+            test_precompile_as_tx_target(precompile, vec![]);
+        }
+    }
+
     /// testool case EmptyTransaction3_d0_g0_v0
     #[test]
     fn begin_tx_create_empty_tx() {
@@ -0,0 +1,104 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::SameContextGadget,
+            constraint_builder::{
+                EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::{Expr, Field},
+};
+use bus_mapping::evm::OpcodeId;
+use halo2_proofs::plonk::Error;
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlobHashGadget<F> {
+    same_context: SameContextGadget<F>,
+    index: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlobHashGadget<F> {
+    const NAME: &'static str = "BLOBHASH";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BLOBHASH;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let index = cb.query_cell_phase2();
+        cb.stack_pop(index.expr());
+
+        // Blob txs are rejected by the builder, so the tx has no versioned
+        // hashes and any index is out of range.
+        cb.stack_push(0.expr());
+
+        // State transition
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(2.expr()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Same,
+            gas_left: Delta(-OpcodeId::BLOBHASH.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let opcode = cb.query_cell();
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            index,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context
+            .assign_exec_step(region, offset, block, call, step)?;
+
+        let index = block.rws[step.rw_indices[0]].stack_value();
+        self.index.assign(region, offset, region.word_rlc(index))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{evm_circuit::test::rand_word, test_util::CircuitTestBuilder};
+    use eth_types::{bytecode, Word};
+    use mock::TestContext;
+
+    fn test_ok(index: Word) {
+        let bytecode = bytecode! {
+            PUSH32(index)
+            BLOBHASH
+            STOP
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .run();
+    }
+
+    #[test]
+    fn blobhash_gadget_simple() {
+        test_ok(Word::zero());
+        test_ok(Word::one());
+    }
+
+    #[test]
+    fn blobhash_gadget_rand() {
+        test_ok(rand_word());
+    }
+}
@@ -197,6 +197,8 @@ mod test {
     use crate::test_util::CircuitTestBuilder;
     use eth_types::{bytecode, U256};
     use mock::test_ctx::{helpers::*, TestContext};
+    #[cfg(not(feature = "scroll"))]
+    use mock::{eth, MOCK_ACCOUNTS};
 
     fn test_ok(block_number: U256, current_block_number: u64) {
         let code = bytecode! {
@@ -250,4 +252,40 @@ mod test {
     fn blockhash_gadget_block_number_overflow() {
         test_ok(U256::MAX, 0xcafeu64);
     }
+
+    // Each tx stores the hash of the parent of its block, which is the
+    // previous block of the context from the second block on.
+    #[cfg(not(feature = "scroll"))]
+    #[test]
+    fn blockhash_gadget_multi_block() {
+        let code = bytecode! {
+            PUSH1(1)
+            NUMBER
+            SUB
+            BLOCKHASH
+            NUMBER
+            SSTORE
+            STOP
+        };
+        let history_hashes = (0..5).map(|i| U256::from(0xbeefcafeu64 + i)).collect();
+        let ctx = TestContext::<2, 3>::new(
+            Some(history_hashes),
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code);
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |txs, accs| {
+                for tx in txs {
+                    tx.from(accs[1].address).to(accs[0].address);
+                }
+            },
+            |block, _tx| block.number(5),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_multi_block_test_ctx(ctx.with_blocks(3)).run()
+    }
 }
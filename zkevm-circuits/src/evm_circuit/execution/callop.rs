@@ -5,7 +5,9 @@ use crate::{
         step::ExecutionState,
         util::{
             and,
-            common_gadget::{CommonCallGadget, TransferGadget, TransferGadgetInfo},
+            common_gadget::{
+                AccountAccessGadget, CommonCallGadget, TransferGadget, TransferGadgetInfo,
+            },
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::{Delta, To},
@@ -24,7 +26,9 @@ use crate::{
     util::{Expr, Field},
 };
 use bus_mapping::{
-    circuit_input_builder::CopyDataType, evm::OpcodeId, precompile::PrecompileCalls,
+    circuit_input_builder::CopyDataType,
+    evm::OpcodeId,
+    precompile::{PrecompileCalls, LAST_PRECOMPILE_ADDRESS},
 };
 use eth_types::{
     evm_types::{memory::MemoryWordRange, GAS_STIPEND_CALL_WITH_VALUE},
@@ -55,8 +59,7 @@ pub(crate) struct CallOpGadget<F> {
     depth: Cell<F>,
     call: CommonCallGadget<F, MemoryAddressGadget<F>, true>,
     current_value: Word<F>,
-    is_warm: Cell<F>,
-    is_warm_prev: Cell<F>,
+    callee_access: AccountAccessGadget<F>,
     callee_reversion_info: ReversionInfo<F>,
     transfer: TransferGadget<F>,
     code_hash_previous: Cell<F>,
@@ -76,6 +79,8 @@ pub(crate) struct CallOpGadget<F> {
     precompile_return_length: Cell<F>,
     precompile_return_length_zero: IsZeroGadget<F>,
     precompile_return_data_copy_size: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    // whether the caller gets only a prefix of the precompile output.
+    precompile_return_truncated: LtGadget<F, N_BYTES_MEMORY_ADDRESS>,
     precompile_input_len: Cell<F>, // the number of input bytes taken for the precompile call.
     precompile_input_bytes_rlc: Cell<F>, // input bytes to precompile call.
     precompile_output_bytes_rlc: Cell<F>, // output bytes from precompile call.
@@ -151,14 +156,10 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         );
 
         // Add callee to access list
-        let is_warm = cb.query_bool();
-        let is_warm_prev = cb.query_bool();
-        cb.require_true("callee add should be updated to warm", is_warm.expr());
-        cb.account_access_list_write(
+        let callee_access = AccountAccessGadget::construct(
+            cb,
             tx_id.expr(),
             call_gadget.callee_address_expr(),
-            is_warm.expr(),
-            is_warm_prev.expr(),
             Some(&mut reversion_info),
         );
         // rwc_delta = 7 + is_delegatecall * 2 + call_gadget.rw_delta()
@@ -214,10 +215,13 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         });
 
         // whether the call is to a precompiled contract.
-        // precompile contracts are stored from address 0x01 to 0x09.
+        // precompile contracts are stored from address 0x01 to LAST_PRECOMPILE_ADDRESS.
         let is_code_address_zero = IsZeroGadget::construct(cb, call_gadget.callee_address_expr());
-        let is_precompile_lt =
-            LtGadget::construct(cb, call_gadget.callee_address_expr(), 0x0A.expr());
+        let is_precompile_lt = LtGadget::construct(
+            cb,
+            call_gadget.callee_address_expr(),
+            (LAST_PRECOMPILE_ADDRESS as u64 + 1).expr(),
+        );
         let is_precompile = and::expr([
             not::expr(is_code_address_zero.expr()),
             is_precompile_lt.expr(),
@@ -230,6 +234,11 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             precompile_return_length.expr(),
             call_gadget.rd_address.length(),
         );
+        let precompile_return_truncated = LtGadget::construct(
+            cb,
+            call_gadget.rd_address.length(),
+            precompile_return_length.expr(),
+        );
 
         let precompile_input_rws = cb.query_cell();
         let precompile_output_rws = cb.query_cell();
@@ -278,7 +287,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
 
         // Sum up and verify gas cost.
         // Only CALL opcode could invoke transfer to make empty account into non-empty.
-        let gas_cost = call_gadget.gas_cost_expr(is_warm_prev.expr(), is_call.expr());
+        let gas_cost = call_gadget.gas_cost_expr(callee_access.is_warm(), is_call.expr());
         // Apply EIP 150
         let gas_available = cb.curr.state.gas_left.expr() - gas_cost.clone();
         let one_64th_gas = cb.annotation("one_64th_gas", |cb| {
@@ -322,7 +331,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                 );
                 cb.require_true(
                     "Precompile addresses are always warm",
-                    and::expr([is_warm.expr(), is_warm_prev.expr()]),
+                    callee_access.is_warm(),
                 );
 
                 // Write to callee's context.
@@ -450,6 +459,10 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                 // - from precompile (min(rd_length, precompile_return_length) bytes)
                 // - to caller's memory (min(rd_length, precompile_return_length) bytes starting at
                 //   `return_data_offset`).
+                // Unless the output is truncated, the source is the RLC of the output rather than
+                // the callee's memory: those words were written by the output copy above in this
+                // step, so reading them back could only return the same bytes, and the memory
+                // reads are elided. A truncated output is read back from the callee's memory.
                 let precompile_return_bytes_rlc = cb.condition(
                     and::expr([
                         call_gadget.is_success.expr(),
@@ -458,17 +471,34 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                     ]),
                     |cb| {
                         let precompile_return_bytes_rlc = cb.query_cell_phase2();
+                        let is_truncated = precompile_return_truncated.expr();
+                        cb.condition(not::expr(is_truncated.expr()), |cb| {
+                            cb.require_equal(
+                                "return bytes == output bytes if not truncated",
+                                precompile_return_bytes_rlc.expr(),
+                                precompile_output_bytes_rlc.expr(),
+                            );
+                        });
                         cb.copy_table_lookup(
                             callee_call_id.expr(),
-                            CopyDataType::Memory.expr(), // refer u64::from(CopyDataType)
+                            select::expr(
+                                is_truncated.expr(),
+                                CopyDataType::Memory.expr(),
+                                CopyDataType::RlcAcc.expr(),
+                            ),
                             cb.curr.state.call_id.expr(),
                             CopyDataType::Memory.expr(),
                             0.expr(),
                             precompile_return_data_copy_size.min(),
                             call_gadget.rd_address.offset(),
                             precompile_return_data_copy_size.min(),
-                            0.expr(), // Memory to Memory copy has no RLC accumulation.
-                            precompile_return_rws.expr(), // writes
+                            // Memory to Memory copy has no RLC accumulation.
+                            select::expr(
+                                is_truncated,
+                                0.expr(),
+                                precompile_return_bytes_rlc.expr(),
+                            ),
+                            precompile_return_rws.expr(), // reads if truncated, and writes
                         ); // rwc_delta += `return_data_copy_size.min()` for precompile
                         precompile_return_bytes_rlc
                     },
@@ -721,8 +751,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             is_static,
             depth,
             call: call_gadget,
-            is_warm,
-            is_warm_prev,
+            callee_access,
             callee_reversion_info,
             transfer,
             code_hash_previous,
@@ -740,6 +769,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             precompile_return_length,
             precompile_return_length_zero,
             precompile_return_data_copy_size,
+            precompile_return_truncated,
             precompile_input_len,
             precompile_input_bytes_rlc,
             precompile_output_bytes_rlc,
@@ -796,7 +826,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         let callee_code_hash = rws.next().account_codehash_pair().0;
         let callee_exists = !callee_code_hash.is_zero();
 
-        let (is_warm, is_warm_prev) = rws.next().tx_access_list_value_pair();
+        let callee_access_rw = rws.next();
 
         let [callee_rw_counter_end_of_reversion, callee_is_persistent] =
             [(); 2].map(|_| rws.next().call_context_value());
@@ -910,10 +940,9 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             step.memory_word_size(),
             region.code_hash(callee_code_hash),
         )?;
-        self.is_warm
-            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
-        self.is_warm_prev
-            .assign(region, offset, Value::known(F::from(is_warm_prev as u64)))?;
+        let is_warm_prev = self
+            .callee_access
+            .assign(region, offset, &callee_access_rw)?;
         self.callee_reversion_info.assign(
             region,
             offset,
@@ -948,8 +977,12 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         let code_address: F = callee_address.to_address().to_scalar().unwrap();
         self.is_code_address_zero
             .assign(region, offset, code_address)?;
-        self.is_precompile_lt
-            .assign(region, offset, code_address, 0x0Au64.into())?;
+        self.is_precompile_lt.assign(
+            region,
+            offset,
+            code_address,
+            (LAST_PRECOMPILE_ADDRESS as u64 + 1).into(),
+        )?;
         log::trace!("callop is precompile call {}", is_precompile_call);
         let precompile_return_length = if is_precompile_call && is_precheck_ok {
             rws.offset_add(15); // skip
@@ -979,6 +1012,13 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             precompile_return_length.to_scalar().unwrap(),
             rd_length.to_scalar().unwrap(),
         )?;
+        self.precompile_return_truncated.assign(
+            region,
+            offset,
+            rd_length.to_scalar().unwrap(),
+            precompile_return_length.to_scalar().unwrap(),
+        )?;
+        let is_return_truncated = rd_length < precompile_return_length;
 
         let (
             precompile_input_len,
@@ -1059,7 +1099,10 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                 .collect::<Vec<_>>();
             let return_bytes = (0..return_bytes_word_count)
                 .map(|_| {
-                    let _read_word = rws.next();
+                    // read word, unless elided.
+                    if is_return_truncated {
+                        rws.next();
+                    }
 
                     // write word.
                     rws.next().memory_word_pair().0
@@ -1095,7 +1138,9 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             trace!("return_bytes_rlc: {return_bytes_rlc:?}");
             let input_rws = Value::known(F::from(input_bytes_word_count as u64));
             let output_rws = Value::known(F::from(output_bytes_word_count as u64));
-            let return_rws = Value::known(F::from((return_bytes_word_count * 2) as u64));
+            let return_rws = Value::known(F::from(
+                (return_bytes_word_count * (1 + is_return_truncated as usize)) as u64,
+            ));
             trace!("input_rws: {input_rws:?}");
             trace!("output_rws: {output_rws:?}");
             trace!("return_rws: {return_rws:?}");
@@ -1747,6 +1792,8 @@ mod test_precompiles {
             .params(CircuitsParams {
                 max_rws: arg.max_rws,
                 max_copy_rows: 1100,
+                // the call of blake2f is checked, not its compressions.
+                max_blake2f_rounds: 12,
                 ..Default::default()
             })
             .run();
@@ -1857,6 +1904,24 @@ mod test_precompiles {
             stack_value: vec![(Word::from(0x20), word!("0123456789ABCDEF0123456789ABCDEF"))],
             ..Default::default()
         },
+        identity_truncated: PrecompileCallArgs {
+            name: "identity (truncated return data)",
+            setup_code: bytecode! {
+                PUSH16(word!("0123456789ABCDEF0123456789ABCDEF"))
+                PUSH1(0x00)
+                MSTORE
+            },
+            ret_size: Word::from(0x10),
+            ret_offset: Word::from(0x20),
+            call_data_offset: Word::from(0x10),
+            call_data_length: Word::from(0x20),
+            address: Word::from(0x4),
+            stack_value: vec![(
+                Word::from(0x20),
+                word!("0x0123456789ABCDEF0123456789ABCDEF00000000000000000000000000000000"),
+            )],
+            ..Default::default()
+        },
         modexp: PrecompileCallArgs {
             name: "modexp",
             setup_code: bytecode! {
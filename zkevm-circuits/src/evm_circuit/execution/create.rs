@@ -838,8 +838,10 @@ mod test {
     use eth_types::{
         address, bytecode, evm_types::OpcodeId, geth_types::Account, word, Address, Bytecode, Word,
     };
+    use ethers_core::utils::get_contract_address;
+    use ethers_signers::Signer;
     use itertools::Itertools;
-    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+    use mock::{eth, generate_init_code, TestContext, MOCK_ACCOUNTS, MOCK_WALLETS};
     use std::sync::LazyLock;
 
     const CALLEE_ADDRESS: Address = Address::repeat_byte(0xff);
@@ -977,6 +979,35 @@ mod test {
         .unwrap()
     }
 
+    #[test]
+    fn test_create_from_deployment_tx() {
+        // The constructor deploys a child with CREATE2, the deployed code
+        // deploys another one with CREATE when called.
+        let constructor = creator_bytecode(get_initcode(true), Word::zero(), true, true);
+        let runtime = creator_bytecode(get_initcode(true), Word::zero(), false, true);
+        let deployer = MOCK_WALLETS[0].address();
+        let deployed = get_contract_address(deployer, 0);
+
+        let ctx = TestContext::<1, 2>::new(
+            None,
+            |accs| {
+                accs[0].address(deployer).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .from(MOCK_WALLETS[0].clone())
+                    .deploy(generate_init_code(constructor, &runtime));
+                txs[1].from(MOCK_WALLETS[0].clone()).to(deployed);
+            },
+            |block, _| block,
+        )
+        .unwrap();
+        assert_eq!(ctx.created_address(0), Some(deployed));
+        assert_eq!(ctx.created_address(1), None);
+
+        run_test_circuits(ctx);
+    }
+
     #[test]
     fn test_create() {
         for ((is_success, is_create2), is_persistent) in [true, false]
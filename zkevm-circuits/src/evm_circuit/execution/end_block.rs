@@ -36,6 +36,8 @@ pub(crate) struct EndBlockGadget<F> {
     total_txs: Cell<F>,
     total_txs_is_max_txs: IsEqualGadget<F>,
     is_empty_block: IsZeroGadget<F>,
+    chunk_initial_rwc: Cell<F>,
+    chunk_end_rwc: Cell<F>,
     max_rws: Cell<F>,
     max_txs: Cell<F>,
     phase2_withdraw_root: Cell<F>,
@@ -54,6 +56,8 @@ impl<F: Clone> Clone for EndBlockGadget<F> {
             total_txs: self.total_txs.clone(),
             total_txs_is_max_txs: self.total_txs_is_max_txs.clone(),
             is_empty_block: self.is_empty_block.clone(),
+            chunk_initial_rwc: self.chunk_initial_rwc.clone(),
+            chunk_end_rwc: self.chunk_end_rwc.clone(),
             max_rws: self.max_rws.clone(),
             max_txs: self.max_txs.clone(),
             phase2_withdraw_root: self.phase2_withdraw_root.clone(),
@@ -170,14 +174,37 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
             }
         });
 
-        // Note that rw_counter starts at 1
-        let is_empty_block =
-            IsZeroGadget::construct(cb, cb.curr.state.rw_counter.clone().expr() - 1.expr());
+        // The rws of a chunk are counted from its initial rw counter, which is 1 if the block
+        // is not chunked. The end rw counter is 0 for the last chunk.
+        let chunk_initial_rwc = cb.query_cell();
+        let chunk_end_rwc = cb.query_cell();
+        cb.block_lookup(
+            BlockContextFieldTag::ChunkInitialRwc.expr(),
+            0.expr(),
+            chunk_initial_rwc.expr(),
+        );
+        cb.block_lookup(
+            BlockContextFieldTag::ChunkEndRwc.expr(),
+            0.expr(),
+            chunk_end_rwc.expr(),
+        );
+        cb.condition(chunk_end_rwc.expr(), |cb| {
+            cb.require_equal(
+                "rw_counter of the EndBlock is the end rw counter of the chunk",
+                cb.curr.state.rw_counter.expr(),
+                chunk_end_rwc.expr(),
+            );
+        });
+
+        let is_empty_block = IsZeroGadget::construct(
+            cb,
+            cb.curr.state.rw_counter.clone().expr() - chunk_initial_rwc.expr(),
+        );
         // If the block is empty, we do 0 rw_table lookups
         // If the block is not empty, we will do 1 call_context lookup
         // and add 1 withdraw_root lookup
         let total_rws = not::expr(is_empty_block.expr())
-            * (cb.curr.state.rw_counter.clone().expr() - 1.expr() + 1.expr())
+            * (cb.curr.state.rw_counter.clone().expr() - chunk_initial_rwc.expr() + 1.expr())
             + 1.expr()
             + is_curie_fork_block.expr() * 7.expr();
 
@@ -255,6 +282,8 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
             total_txs,
             total_txs_is_max_txs,
             is_empty_block,
+            chunk_initial_rwc,
+            chunk_end_rwc,
             is_curie_fork_block,
             withdraw_root_assigned: Default::default(),
         }
@@ -271,8 +300,19 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
     ) -> Result<(), Error> {
         self.chain_id
             .assign(region, offset, Value::known(F::from(block.chain_id)))?;
-        self.is_empty_block
-            .assign(region, offset, F::from(step.rw_counter as u64 - 1))?;
+        let chunk = block.chunk.unwrap_or_default();
+        self.chunk_initial_rwc.assign(
+            region,
+            offset,
+            Value::known(F::from(chunk.initial_rwc as u64)),
+        )?;
+        self.chunk_end_rwc
+            .assign(region, offset, Value::known(F::from(chunk.end_rwc as u64)))?;
+        self.is_empty_block.assign(
+            region,
+            offset,
+            F::from(step.rw_counter as u64) - F::from(chunk.initial_rwc as u64),
+        )?;
         let max_rws = F::from(block.circuits_params.max_rws as u64);
         let max_rws_assigned = self.max_rws.assign(region, offset, Value::known(max_rws))?;
 
@@ -290,10 +330,12 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
             .last_key_value()
             .map(|(_, b)| b.number)
             .unwrap_or_default();
-        let is_curie = bus_mapping::circuit_input_builder::curie::is_curie_fork_block(
-            block.chain_id,
-            last_block_number.as_u64(),
-        );
+        // The hardfork state transition is done at the end of the last chunk only.
+        let is_curie = chunk.is_last()
+            && bus_mapping::circuit_input_builder::curie::is_curie_fork_block(
+                block.chain_id,
+                last_block_number.as_u64(),
+            );
         self.is_curie_fork_block
             .assign(region, offset, Value::known(F::from(is_curie as u64)))?;
 
@@ -343,7 +385,7 @@ mod test {
         let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap();
 
         // finish required tests using this witness block
-        CircuitTestBuilder::<2, 1>::new_from_test_ctx(ctx)
+        CircuitTestBuilder::new_from_test_ctx(ctx)
             .block_modifier(Box::new(move |block| {
                 block.circuits_params.max_evm_rows = evm_circuit_pad_to
             }))
@@ -0,0 +1,166 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            constraint_builder::{EVMConstraintBuilder, StepStateTransition, Transition::Same},
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{BlockContextFieldTag, CallContextFieldTag},
+    util::{Expr, Field},
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Step after the last tx of a chunk but the last of its block, where the next
+/// chunk continues.
+#[derive(Clone, Debug)]
+pub(crate) struct EndChunkGadget<F> {
+    initial_tx_id: Cell<F>,
+    end_tx_id: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for EndChunkGadget<F> {
+    const NAME: &'static str = "EndChunk";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::EndChunk;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let initial_tx_id = cb.query_cell();
+        let end_tx_id = cb.query_cell();
+
+        // The next chunk starts at the rw counter after the last tx of this one.
+        cb.block_lookup(
+            BlockContextFieldTag::ChunkEndRwc.expr(),
+            0.expr(),
+            cb.curr.state.rw_counter.expr(),
+        );
+        cb.block_lookup(
+            BlockContextFieldTag::ChunkInitialTxId.expr(),
+            0.expr(),
+            initial_tx_id.expr(),
+        );
+        cb.block_lookup(
+            BlockContextFieldTag::ChunkEndTxId.expr(),
+            0.expr(),
+            end_tx_id.expr(),
+        );
+
+        // The last call is the one of the last tx of the chunk, whose id in the block is the
+        // public end tx id. The lookup uses the same record as the TxId read of the EndBlock.
+        cb.call_context_lookup_read_with_counter(
+            cb.curr.state.rw_counter.expr(),
+            None,
+            CallContextFieldTag::TxId,
+            end_tx_id.expr() - initial_tx_id.expr() + 1.expr(),
+        );
+
+        // Propagate rw_counter and call_id down to the EndBlock.
+        cb.require_step_state_transition(StepStateTransition {
+            rw_counter: Same,
+            call_id: Same,
+            ..StepStateTransition::any()
+        });
+
+        Self {
+            initial_tx_id,
+            end_tx_id,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block,
+        _: &Transaction,
+        _: &Call,
+        _step: &ExecStep,
+    ) -> Result<(), Error> {
+        let chunk = block.chunk.unwrap_or_default();
+        self.initial_tx_id.assign(
+            region,
+            offset,
+            Value::known(F::from(chunk.initial_tx_id as u64)),
+        )?;
+        self.end_tx_id.assign(
+            region,
+            offset,
+            Value::known(F::from(chunk.end_tx_id as u64)),
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        test_util::CircuitTestBuilder,
+        witness::{check_chunk_continuity, chunk_convert},
+    };
+    use bus_mapping::{circuit_input_builder::CircuitsParams, mock::BlockData};
+    use eth_types::{address, bytecode, geth_types::GethData, Word};
+    use mock::TestContext;
+
+    #[test]
+    fn end_chunk_continuity() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        };
+        let block: GethData = TestContext::<2, 3>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000cafe001"))
+                    .code(code);
+                accs[1]
+                    .address(address!("0x000000000000000000000000000000000cafe002"))
+                    .balance(Word::from(1u64 << 30));
+            },
+            |txs, accs| {
+                for (nonce, tx) in txs.into_iter().enumerate() {
+                    tx.to(accs[0].address)
+                        .from(accs[1].address)
+                        .nonce(Word::from(nonce));
+                }
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            CircuitsParams {
+                max_txs: 3,
+                max_rws: 0,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        // Room for the largest tx, the EndBlock of its chunk and the Start padding, but not for
+        // two txs.
+        let largest = builder
+            .block
+            .tx_rwc_ranges()
+            .iter()
+            .map(|rwc| rwc.len())
+            .max()
+            .unwrap();
+        let chunks = chunk_convert(&builder.block, &builder.code_db, largest + 8).unwrap();
+        assert!(chunks.len() > 1);
+        check_chunk_continuity(&chunks).unwrap();
+
+        for chunk in chunks {
+            CircuitTestBuilder::new_from_block(chunk).run();
+        }
+    }
+}
@@ -32,6 +32,8 @@ pub(crate) struct EndInnerBlockGadget<F> {
     cum_num_txs: Cell<F>,
     /// Gadget used to check if the inner block was empty.
     is_empty_block: IsZeroGadget<F>,
+    /// The end rw counter of the chunk, which is 0 for the last chunk.
+    chunk_end_rwc: Cell<F>,
     _marker: PhantomData<F>,
 }
 
@@ -76,16 +78,34 @@ impl<F: Field> ExecutionGadget<F> for EndInnerBlockGadget<F> {
             );
         });
 
-        // Depending on whether or not this is the final inner block, we must constrain
-        // the next step's block number.
-        let next_step_padding = cb.next.execution_state_selector([ExecutionState::Padding]);
+        // Depending on whether or not this is the final inner block of the chunk, we must
+        // constrain the next step's block number.
+        let next_step_padding = cb
+            .next
+            .execution_state_selector([ExecutionState::EndChunk, ExecutionState::Padding]);
         cb.condition(next_step_padding.clone(), |cb| {
             cb.require_equal(
-                "block number does not change if this is the last inner block",
+                "block number does not change if this is the last inner block of the chunk",
                 cb.next.state.block_number.expr(),
                 cb.curr.state.block_number.expr(),
             );
         });
+        // Only the last chunk may end without an EndChunk.
+        let chunk_end_rwc = cb.query_cell();
+        cb.block_lookup(
+            BlockContextFieldTag::ChunkEndRwc.expr(),
+            0.expr(),
+            chunk_end_rwc.expr(),
+        );
+        cb.condition(
+            cb.next.execution_state_selector([ExecutionState::Padding]),
+            |cb| {
+                cb.require_zero(
+                    "the chunk is the last one if no EndChunk follows",
+                    chunk_end_rwc.expr(),
+                );
+            },
+        );
         cb.condition(not::expr(next_step_padding), |cb| {
             cb.require_equal(
                 "block number increments if there are more inner blocks",
@@ -107,6 +127,7 @@ impl<F: Field> ExecutionGadget<F> for EndInnerBlockGadget<F> {
             num_txs,
             cum_num_txs,
             is_empty_block,
+            chunk_end_rwc,
             _marker: PhantomData,
         }
     }
@@ -139,6 +160,11 @@ impl<F: Field> ExecutionGadget<F> for EndInnerBlockGadget<F> {
             .assign(region, offset, Value::known(F::from(cum_num_txs as u64)))?;
         self.is_empty_block
             .assign(region, offset, F::from(num_txs as u64))?;
+        self.chunk_end_rwc.assign(
+            region,
+            offset,
+            Value::known(F::from(block.chunk.unwrap_or_default().end_rwc as u64)),
+        )?;
 
         Ok(())
     }
@@ -16,10 +16,10 @@ use crate::{
             },
             CachedRegion, Cell, StepRws, Word,
         },
-        witness::{Block, Call, ExecStep, Transaction},
+        witness::{Block, Call, ExecStep, Rw, Transaction},
     },
     table::{
-        AccountFieldTag, BlockContextFieldTag, CallContextFieldTag, RwTableTag, TxContextFieldTag,
+        AccountFieldTag, BlockContextFieldTag, CallContextFieldTag, TxContextFieldTag,
         TxReceiptFieldTag,
     },
     util::{Expr, Field},
@@ -28,7 +28,6 @@ use eth_types::{evm_types::MAX_REFUND_QUOTIENT_OF_GAS_USED, geth_types::TxType,
 use gadgets::util::{not, select};
 use gadgets::ToScalar;
 use halo2_proofs::{circuit::Value, plonk::Error};
-use strum::EnumCount;
 
 #[derive(Clone, Debug)]
 pub(crate) struct EndTxGadget<F> {
@@ -52,6 +51,7 @@ pub(crate) struct EndTxGadget<F> {
     coinbase_codehash_is_zero: IsZeroGadget<F>,
     coinbase_transfer: TransferToGadget<F>,
     current_cumulative_gas_used: Cell<F>,
+    chunk_initial_tx_id: Cell<F>,
     is_first_tx: IsEqualGadget<F>,
     is_persistent: Cell<F>,
     tx_is_l1msg: IsEqualGadget<F>,
@@ -205,7 +205,19 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
         );
         // rwc_delta = 7 + !tx_is_l1msg * (coinbase_transfer.rw_delta + 1)
 
-        let is_first_tx = IsEqualGadget::construct(cb, tx_id.expr(), 1.expr());
+        // The txs of a chunk are numbered from 1, so the first tx of the block is the one whose
+        // id in the block is 1.
+        let chunk_initial_tx_id = cb.query_cell();
+        cb.block_lookup(
+            BlockContextFieldTag::ChunkInitialTxId.expr(),
+            0.expr(),
+            chunk_initial_tx_id.expr(),
+        );
+        let is_first_tx = IsEqualGadget::construct(
+            cb,
+            tx_id.expr() + chunk_initial_tx_id.expr() - 1.expr(),
+            1.expr(),
+        );
 
         let current_cumulative_gas_used = cb.query_cell();
         cb.condition(is_first_tx.expr(), |cb| {
@@ -294,6 +306,7 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             coinbase_codehash_is_zero,
             coinbase_transfer,
             current_cumulative_gas_used,
+            chunk_initial_tx_id,
             is_first_tx,
             is_persistent,
             tx_is_l1msg,
@@ -453,25 +466,41 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
         self.effective_fee
             .assign(region, offset, Some(effective_fee.to_le_bytes()))?;
 
-        let current_cumulative_gas_used: u64 = if tx.id == 1 {
-            0
-        } else {
-            // first transaction needs TxReceiptFieldTag::COUNT(3) lookups to tx receipt,
-            // while later transactions need 4 (with one extra cumulative gas read) lookups
-            let rw = &block.rws[(
-                RwTableTag::TxReceipt,
-                (tx.id - 2) * (TxReceiptFieldTag::COUNT + 1) + 2,
-            )];
-            rw.receipt_value()
-        };
+        // The first tx of the block does no cumulative gas read, whereas the later ones read
+        // the cumulative gas of the previous tx.
+        let current_cumulative_gas_used = step
+            .rw_indices
+            .iter()
+            .map(|idx| &block.rws[*idx])
+            .find(|rw| {
+                matches!(
+                    rw,
+                    Rw::TxReceipt {
+                        is_write: false,
+                        field_tag: TxReceiptFieldTag::CumulativeGasUsed,
+                        ..
+                    }
+                )
+            })
+            .map_or(0, |rw| rw.receipt_value());
 
         self.current_cumulative_gas_used.assign(
             region,
             offset,
             Value::known(F::from(current_cumulative_gas_used)),
         )?;
-        self.is_first_tx
-            .assign(region, offset, F::from(tx.id as u64), F::one())?;
+        let chunk_initial_tx_id = block.chunk.unwrap_or_default().initial_tx_id;
+        self.chunk_initial_tx_id.assign(
+            region,
+            offset,
+            Value::known(F::from(chunk_initial_tx_id as u64)),
+        )?;
+        self.is_first_tx.assign(
+            region,
+            offset,
+            F::from((tx.id + chunk_initial_tx_id - 1) as u64),
+            F::one(),
+        )?;
         self.is_persistent.assign(
             region,
             offset,
@@ -486,9 +515,11 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
 mod test {
     use crate::test_util::CircuitTestBuilder;
     use bus_mapping::circuit_input_builder::CircuitsParams;
-    use eth_types::{self, bytecode};
+    use eth_types::{self, bytecode, Bytecode, Word};
 
-    use mock::{eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+    use mock::{
+        eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
+    };
 
     fn test_ok<const NACC: usize, const NTX: usize>(ctx: TestContext<NACC, NTX>) {
         CircuitTestBuilder::new_from_test_ctx(ctx)
@@ -499,22 +530,46 @@ mod test {
             .run();
     }
 
+    /// Tx clearing the first `n_slots` slots of the callee, each clear is
+    /// refunded SSTORE_CLEARS_SCHEDULE.
+    fn clear_slots_ctx(n_slots: u64) -> TestContext<2, 1> {
+        let mut code = Bytecode::default();
+        for slot in 0..n_slots {
+            code.op_sstore(slot, 0u64);
+        }
+        code.op_stop();
+        TestContext::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code)
+                    .storage((0..n_slots).map(|slot| (slot.into(), Word::one())));
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[1].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+    }
+
     #[test]
-    fn end_tx_gadget_simple() {
-        // TODO: Enable this with respective code when SSTORE is implemented.
-        // Tx with non-capped refund
-        // test_ok(vec![mock_tx(
-        //     address!("0x00000000000000000000000000000000000000fe"),
-        //     Some(27000),
-        //     None,
-        // )]);
-        // Tx with capped refund
-        // test_ok(vec![mock_tx(
-        //     address!("0x00000000000000000000000000000000000000fe"),
-        //     Some(65000),
-        //     None,
-        // )]);
+    fn end_tx_gadget_refund_not_capped() {
+        // refund 4800 < gas used 26006 / 5
+        test_ok(clear_slots_ctx(1));
+    }
+
+    #[test]
+    fn end_tx_gadget_refund_capped() {
+        // refund 9600 > gas used 31012 / 5
+        test_ok(clear_slots_ctx(2));
+    }
 
+    #[test]
+    fn end_tx_gadget_simple() {
         // Multiple txs
         test_ok(
             // Get the execution steps from the external tracer
@@ -4,21 +4,18 @@ use crate::{
         param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS},
         step::ExecutionState,
         util::{
-            common_gadget::CommonErrorGadget,
+            common_gadget::{AccountAccessGadget, CommonErrorGadget},
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             from_bytes,
             math_gadget::LtGadget,
-            select, CachedRegion, Cell, Word,
+            CachedRegion, Cell, Word,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
     table::CallContextFieldTag,
     util::{Expr, Field},
 };
-use eth_types::{
-    evm_types::{GasCost, OpcodeId},
-    ToLittleEndian,
-};
+use eth_types::{evm_types::OpcodeId, ToLittleEndian};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 /// Gadget to implement the corresponding out of gas errors for
@@ -28,7 +25,7 @@ pub(crate) struct ErrorOOGAccountAccessGadget<F> {
     opcode: Cell<F>,
     address_word: Word<F>,
     tx_id: Cell<F>,
-    is_warm: Cell<F>,
+    account_access: AccountAccessGadget<F>,
     insufficient_gas_cost: LtGadget<F, N_BYTES_GAS>,
     common_error_gadget: CommonErrorGadget<F>,
 }
@@ -55,18 +52,10 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGAccountAccessGadget<F> {
         cb.stack_pop(address_word.expr());
 
         let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
-        let is_warm = cb.query_bool();
-        // read is_warm
-        cb.account_access_list_read(tx_id.expr(), address.expr(), is_warm.expr());
-
-        let gas_cost = select::expr(
-            is_warm.expr(),
-            GasCost::WARM_ACCESS.expr(),
-            GasCost::COLD_ACCOUNT_ACCESS.expr(),
-        );
+        let account_access = AccountAccessGadget::construct_read(cb, tx_id.expr(), address.expr());
 
         let insufficient_gas_cost =
-            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost);
+            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), account_access.gas_cost());
 
         cb.require_equal(
             "Gas left is less than gas cost",
@@ -79,7 +68,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGAccountAccessGadget<F> {
             opcode,
             address_word,
             tx_id,
-            is_warm,
+            account_access,
             insufficient_gas_cost,
             common_error_gadget,
         }
@@ -105,22 +94,18 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGAccountAccessGadget<F> {
         self.tx_id
             .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
 
-        let (_, is_warm) = block.rws[step.rw_indices[2]].tx_access_list_value_pair();
-        self.is_warm
-            .assign(region, offset, Value::known(F::from(is_warm)))?;
+        let is_warm = self
+            .account_access
+            .assign(region, offset, &block.rws[step.rw_indices[2]])?;
 
         // BALANCE EXTCODESIZE EXTCODEHASH shares same gas cost model
-        let gas_cost = if is_warm {
-            GasCost::WARM_ACCESS
-        } else {
-            GasCost::COLD_ACCOUNT_ACCESS
-        };
+        let gas_cost = AccountAccessGadget::<F>::gas_cost_for_assignment(is_warm);
 
         self.insufficient_gas_cost.assign_value(
             region,
             offset,
             Value::known(F::from(step.gas_left)),
-            Value::known(F::from(gas_cost.as_u64())),
+            Value::known(F::from(gas_cost)),
         )?;
         self.common_error_gadget
             .assign(region, offset, block, call, step, 5)?;
@@ -4,7 +4,7 @@ use crate::{
         param::N_BYTES_GAS,
         step::ExecutionState,
         util::{
-            common_gadget::{CommonCallGadget, CommonErrorGadget},
+            common_gadget::{AccountAccessGadget, CommonCallGadget, CommonErrorGadget},
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{IsZeroGadget, LtGadget},
             memory_gadget::MemoryExpandedAddressGadget,
@@ -31,7 +31,7 @@ pub(crate) struct ErrorOOGCallGadget<F> {
     is_staticcall: IsZeroGadget<F>,
     tx_id: Cell<F>,
     is_static: Cell<F>,
-    is_warm: Cell<F>,
+    callee_access: AccountAccessGadget<F>,
     call: CommonCallGadget<F, MemoryExpandedAddressGadget<F>, false>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     common_error_gadget: CommonErrorGadget<F>,
@@ -65,11 +65,10 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCallGadget<F> {
             );
 
         // Add callee to access list
-        let is_warm = cb.query_bool();
-        cb.account_access_list_read(
+        let callee_access = AccountAccessGadget::construct_read(
+            cb,
             tx_id.expr(),
             call_gadget.callee_address_expr(),
-            is_warm.expr(),
         );
 
         cb.condition(is_call.expr() * call_gadget.has_value.expr(), |cb| {
@@ -80,7 +79,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCallGadget<F> {
         });
 
         // Verify gas cost
-        let gas_cost = call_gadget.gas_cost_expr(is_warm.expr(), is_call.expr());
+        let gas_cost = call_gadget.gas_cost_expr(callee_access.is_warm(), is_call.expr());
 
         // Check if the amount of gas available is less than the amount of gas required
         let insufficient_gas = LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost);
@@ -111,7 +110,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCallGadget<F> {
             is_staticcall,
             tx_id,
             is_static,
-            is_warm,
+            callee_access,
             call: call_gadget,
             insufficient_gas,
             common_error_gadget,
@@ -150,7 +149,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCallGadget<F> {
         rws.offset_add(1);
         let callee_code_hash = rws.next().account_value_pair().0;
         let callee_exists = !callee_code_hash.is_zero();
-        let (is_warm, is_warm_prev) = rws.next().tx_access_list_value_pair();
+        let callee_access_rw = rws.next();
 
         let memory_expansion_gas_cost = self.call.assign(
             region,
@@ -197,8 +196,9 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCallGadget<F> {
         self.is_static
             .assign(region, offset, Value::known(F::from(is_static.low_u64())))?;
 
-        self.is_warm
-            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+        let is_warm_prev = self
+            .callee_access
+            .assign(region, offset, &callee_access_rw)?;
 
         let has_value = !value.is_zero();
         let gas_cost = self.call.cal_gas_cost_for_assignment(
@@ -1,14 +1,14 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
+        param::N_BYTES_GAS,
         step::ExecutionState,
         util::{
             common_gadget::CommonErrorGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{LtGadget, PairSelectGadget},
             memory_gadget::{
-                CommonMemoryAddressGadget, MemoryExpandedAddressGadget, MemoryExpansionGadget,
+                CommonMemoryAddressGadget, MemoryExpandedAddressGadget, MemoryExpansionU64Gadget,
             },
             or, CachedRegion, Cell,
         },
@@ -25,7 +25,7 @@ pub(crate) struct ErrorOOGDynamicMemoryGadget<F> {
     opcode: Cell<F>,
     is_return: PairSelectGadget<F>,
     memory_address: MemoryExpandedAddressGadget<F>,
-    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_expansion: MemoryExpansionU64Gadget<F, 1>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     common_error_gadget: CommonErrorGadget<F>,
 }
@@ -48,7 +48,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGDynamicMemoryGadget<F> {
         cb.stack_pop(memory_address.offset_rlc());
         cb.stack_pop(memory_address.length_rlc());
 
-        let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.end_offset()]);
+        let memory_expansion =
+            MemoryExpansionU64Gadget::construct(cb, [memory_address.end_offset_u64()]);
 
         let insufficient_gas = LtGadget::construct(
             cb,
@@ -62,7 +63,11 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGDynamicMemoryGadget<F> {
 
         cb.require_equal(
             "Memory address is overflow or gas left is less than cost",
-            or::expr([memory_address.overflow(), insufficient_gas.expr()]),
+            or::expr([
+                memory_address.overflow_u64(),
+                memory_expansion.overflow(),
+                insufficient_gas.expr(),
+            ]),
             1.expr(),
         );
 
@@ -100,13 +105,19 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGDynamicMemoryGadget<F> {
 
         let [memory_offset, memory_length] =
             [0, 1].map(|idx| block.rws[step.rw_indices[idx]].stack_value());
-        let memory_address =
-            self.memory_address
-                .assign(region, offset, memory_offset, memory_length)?;
+        self.memory_address
+            .assign(region, offset, memory_offset, memory_length)?;
+        let memory_address_u64 =
+            MemoryExpandedAddressGadget::<F>::end_offset_u64_value(memory_offset, memory_length);
 
         let memory_expansion_gas = self
             .memory_expansion
-            .assign(region, offset, step.memory_word_size(), [memory_address])?
+            .assign(
+                region,
+                offset,
+                step.memory_word_size(),
+                [memory_address_u64],
+            )?
             .1;
         let constant_gas_cost = opcode.constant_gas_cost().0;
         self.insufficient_gas.assign(
@@ -139,6 +150,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_oog_dynamic_memory_max_in_range_address() {
+        // 0x1fffffffc0 + 0x20 = MAX_EXPANDED_MEMORY_ADDRESS (0x1fffffffe0), the
+        // address is not overflow but the quadratic expansion cost is out of gas
+        for code in testing_bytecodes(0x1fffffffc0_u64.into(), 0x20.into()).iter() {
+            test_root(code);
+            test_internal(code);
+        }
+    }
+
     #[test]
     fn test_oog_dynamic_memory_max_expanded_address() {
         // 0xffffffff1 + 0xffffffff0 = 0x1fffffffe1
@@ -149,6 +170,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_oog_dynamic_memory_u64_address() {
+        // Within u64 but above MAX_EXPANDED_MEMORY_ADDRESS, the memory gas cost
+        // overflows
+        for code in testing_bytecodes(0xffffffff00000000_u64.into(), 0xffffffff_u64.into()).iter() {
+            test_root(code);
+            test_internal(code);
+        }
+    }
+
     #[test]
     fn test_oog_dynamic_memory_max_u64_address() {
         for code in testing_bytecodes(u64::MAX.into(), u64::MAX.into()).iter() {
@@ -1,14 +1,14 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
+        param::N_BYTES_GAS,
         step::ExecutionState,
         util::{
             common_gadget::CommonErrorGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::LtGadget,
             memory_gadget::{
-                CommonMemoryAddressGadget, MemoryExpandedAddressGadget, MemoryExpansionGadget,
+                CommonMemoryAddressGadget, MemoryExpandedAddressGadget, MemoryExpansionU64Gadget,
             },
             or, CachedRegion, Cell,
         },
@@ -28,7 +28,7 @@ pub(crate) struct ErrorOOGLogGadget<F> {
     is_static_call: Cell<F>,
     is_opcode_logn: LtGadget<F, 1>,
     // constrain gas left is less than gas cost
-    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_expansion: MemoryExpansionU64Gadget<F, 1>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     common_error_gadget: CommonErrorGadget<F>,
 }
@@ -61,7 +61,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGLogGadget<F> {
 
         // Calculate the next memory size and the gas cost for this memory
         // access
-        let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.end_offset()]);
+        let memory_expansion =
+            MemoryExpansionU64Gadget::construct(cb, [memory_address.end_offset_u64()]);
 
         let gas_cost = GasCost::LOG.as_u64().expr()
             + GasCost::LOG.as_u64().expr() * topic_count
@@ -74,7 +75,11 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGLogGadget<F> {
 
         cb.require_equal(
             "Memory address is overflow or gas left is less than cost",
-            or::expr([memory_address.overflow(), insufficient_gas.expr()]),
+            or::expr([
+                memory_address.overflow_u64(),
+                memory_expansion.overflow(),
+                insufficient_gas.expr(),
+            ]),
             1.expr(),
         );
 
@@ -107,14 +112,20 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGLogGadget<F> {
         let [memory_start, msize] =
             [step.rw_indices[0], step.rw_indices[1]].map(|idx| block.rws[idx].stack_value());
 
-        let memory_address = self
-            .memory_address
+        self.memory_address
             .assign(region, offset, memory_start, msize)?;
+        let memory_address_u64 =
+            MemoryExpandedAddressGadget::<F>::end_offset_u64_value(memory_start, msize);
 
         // Memory expansion
         let memory_expansion_cost = self
             .memory_expansion
-            .assign(region, offset, step.memory_word_size(), [memory_address])?
+            .assign(
+                region,
+                offset,
+                step.memory_word_size(),
+                [memory_address_u64],
+            )?
             .1;
 
         let topic_count = opcode.postfix().expect("opcode with postfix") as u64;
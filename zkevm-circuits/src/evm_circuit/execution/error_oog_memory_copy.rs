@@ -1,7 +1,7 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
+        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS},
         step::ExecutionState,
         util::{
             common_gadget::CommonErrorGadget,
@@ -10,7 +10,7 @@ use crate::{
             math_gadget::{IsZeroGadget, LtGadget},
             memory_gadget::{
                 CommonMemoryAddressGadget, MemoryCopierGasGadget, MemoryExpandedAddressGadget,
-                MemoryExpansionGadget,
+                MemoryExpansionU64Gadget,
             },
             not, or, select, CachedRegion, Cell, Word,
         },
@@ -42,9 +42,9 @@ pub(crate) struct ErrorOOGMemoryCopyGadget<F> {
 
     addr_expansion_gadget: MemoryAddrExpandGadget<F>,
     // mcopy expansion
-    memory_expansion_mcopy: MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_expansion_mcopy: MemoryExpansionU64Gadget<F, 2>,
     // other kind(CALLDATACOPY, CODECOPY, EXTCODECOPY, RETURNDATACOPY) expansion
-    memory_expansion_normal: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_expansion_normal: MemoryExpansionU64Gadget<F, 1>,
     memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     is_extcodecopy: IsZeroGadget<F>,
@@ -105,9 +105,9 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
 
         // for others (CALLDATACOPY, CODECOPY, EXTCODECOPY, RETURNDATACOPY)
         let memory_expansion_normal = cb.condition(not::expr(is_mcopy.expr()), |cb| {
-            MemoryExpansionGadget::construct(
+            MemoryExpansionU64Gadget::construct(
                 cb,
-                [addr_expansion_gadget.dst_memory_addr.end_offset()],
+                [addr_expansion_gadget.dst_memory_addr.end_offset_u64()],
             )
         });
 
@@ -145,8 +145,13 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             // for mcopy, both dst_memory_addr and dst_memory_addr likely overflow.
             "Memory address is overflow or gas left is less than cost",
             or::expr([
-                addr_expansion_gadget.dst_memory_addr.overflow(),
-                addr_expansion_gadget.src_memory_addr.overflow(),
+                addr_expansion_gadget.dst_memory_addr.overflow_u64(),
+                addr_expansion_gadget.src_memory_addr.overflow_u64(),
+                select::expr(
+                    is_mcopy.expr(),
+                    memory_expansion_mcopy.overflow(),
+                    memory_expansion_normal.overflow(),
+                ),
                 insufficient_gas.expr(),
             ]),
             1.expr(),
@@ -218,14 +223,15 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
         self.external_address
             .assign(region, offset, Some(external_address.to_le_bytes()))?;
 
-        let src_memory_addr = self
-            .addr_expansion_gadget
+        self.addr_expansion_gadget
             .src_memory_addr
             .assign(region, offset, src_offset, copy_size)?;
-        let dst_memory_addr = self
-            .addr_expansion_gadget
+        self.addr_expansion_gadget
             .dst_memory_addr
             .assign(region, offset, dst_offset, copy_size)?;
+        let [src_memory_addr, dst_memory_addr] = [src_offset, dst_offset].map(|memory_offset| {
+            MemoryExpandedAddressGadget::<F>::end_offset_u64_value(memory_offset, copy_size)
+        });
         let (_, memory_expansion_cost) = self.memory_expansion_normal.assign(
             region,
             offset,
@@ -314,18 +320,18 @@ impl<F: Field> MemoryAddrExpandGadget<F> {
         &self,
         cb: &mut EVMConstraintBuilder<F>,
         is_mcopy: Expression<F>,
-    ) -> MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE> {
+    ) -> MemoryExpansionU64Gadget<F, 2> {
         cb.condition(is_mcopy.expr(), |cb| {
             cb.require_equal(
                 "mcopy src_address length == dst_address length",
                 self.src_memory_addr.length_rlc(),
                 self.dst_memory_addr.length_rlc(),
             );
-            MemoryExpansionGadget::construct(
+            MemoryExpansionU64Gadget::construct(
                 cb,
                 [
-                    self.src_memory_addr.end_offset(),
-                    self.dst_memory_addr.end_offset(),
+                    self.src_memory_addr.end_offset_u64(),
+                    self.dst_memory_addr.end_offset_u64(),
                 ],
             )
         })
@@ -718,7 +724,7 @@ mod tests {
     #[derive(Clone)]
     struct ErrOOGMemoryCopyGadgetTestContainer<F> {
         gadget: MemoryAddrExpandGadget<F>,
-        memory_expansion_mcopy: MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE>,
+        memory_expansion_mcopy: MemoryExpansionU64Gadget<F, 2>,
         is_mcopy: Cell<F>,
     }
 
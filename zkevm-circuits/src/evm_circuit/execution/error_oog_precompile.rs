@@ -20,6 +20,10 @@ use gadgets::util::{sum, Expr};
 use gadgets::ToScalar;
 use halo2_proofs::{circuit::Value, plonk::Error};
 
+/// Out of gas inside a precompile call whose gas cost only depends on the call
+/// data length: ecrecover, sha256, identity and the bn128 operations. The
+/// caller context is restored with empty return data. Modexp runs out of gas
+/// in `ModExpGadget`, ripemd160 and blake2f in `ErrorPrecompileFailedGadget`.
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorOOGPrecompileGadget<F> {
     precompile_addr: Cell<F>,
@@ -60,13 +64,17 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
                 )
             },
         );
-        let n_words = cb.condition(addr_bits.value_equals(PrecompileCalls::Identity), |cb| {
-            ConstantDivisionGadget::construct(
-                cb,
-                call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
-                N_BYTES_WORD as u64,
-            )
-        });
+        let n_words = cb.condition(
+            addr_bits.value_equals(PrecompileCalls::Identity)
+                + addr_bits.value_equals(PrecompileCalls::Sha256),
+            |cb| {
+                ConstantDivisionGadget::construct(
+                    cb,
+                    call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
+                    N_BYTES_WORD as u64,
+                )
+            },
+        );
 
         // calculate required gas for precompile
         let precompiles_required_gas = vec![
@@ -74,14 +82,12 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
                 addr_bits.value_equals(PrecompileCalls::Ecrecover),
                 GasCost::PRECOMPILE_ECRECOVER_BASE.expr(),
             ),
-            // These are handled in PrecompileFailedGadget
             (
                 addr_bits.value_equals(PrecompileCalls::Sha256),
                 GasCost::PRECOMPILE_SHA256_BASE.expr()
                     + n_words.quotient() * GasCost::PRECOMPILE_SHA256_PER_WORD.expr(),
             ),
-            // addr_bits.value_equals(PrecompileCalls::Ripemd160),
-            // addr_bits.value_equals(PrecompileCalls::Blake2F),
+            // ripemd160 and blake2f are handled in ErrorPrecompileFailedGadget
             (
                 addr_bits.value_equals(PrecompileCalls::Identity),
                 GasCost::PRECOMPILE_IDENTITY_BASE.expr()
@@ -212,7 +218,10 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
             PrecompileCalls::Bn128Add | PrecompileCalls::Bn128Mul | PrecompileCalls::Ecrecover => {
                 precompile_call.base_gas_cost().as_u64()
             }
-            _ => unreachable!(),
+            _ => {
+                log::error!("unexpected out of gas precompile {precompile_call:?}");
+                return Err(Error::Synthesis);
+            }
         };
 
         self.required_gas
@@ -270,6 +279,70 @@ mod test {
                 .to_word(),
                 ..Default::default()
             },
+            PrecompileCallArgs {
+                name: "sha256 one word short of gas",
+                setup_code: bytecode! {
+                    PUSH30(word!("0x0123456789abcdef0f1e2d3c4b5a6978"))
+                    PUSH1(0x00)
+                    MSTORE
+                },
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x21.into(),
+                ret_offset: 0x40.into(),
+                ret_size: 0x20.into(),
+                address: PrecompileCalls::Sha256.address().to_word(),
+                gas: (PrecompileCalls::Sha256.base_gas_cost().as_u64()
+                    + GasCost::PRECOMPILE_SHA256_PER_WORD.as_u64())
+                .to_word(),
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                name: "ecrecover with empty input",
+                setup_code: bytecode! {},
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x00.into(),
+                ret_offset: 0x00.into(),
+                ret_size: 0x20.into(),
+                address: PrecompileCalls::Ecrecover.address().to_word(),
+                gas: (PrecompileCalls::Ecrecover.base_gas_cost().as_u64() - 1).to_word(),
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                name: "bn128 add with empty input",
+                setup_code: bytecode! {},
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x00.into(),
+                ret_offset: 0x00.into(),
+                ret_size: 0x40.into(),
+                address: PrecompileCalls::Bn128Add.address().to_word(),
+                gas: (PrecompileCalls::Bn128Add.base_gas_cost().as_u64() - 1).to_word(),
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                name: "bn128 mul with empty input",
+                setup_code: bytecode! {},
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x00.into(),
+                ret_offset: 0x00.into(),
+                ret_size: 0x40.into(),
+                address: PrecompileCalls::Bn128Mul.address().to_word(),
+                gas: (PrecompileCalls::Bn128Mul.base_gas_cost().as_u64() - 1).to_word(),
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                name: "bn128 pairing of one pair",
+                setup_code: bytecode! {},
+                call_data_offset: 0x00.into(),
+                call_data_length: 0xc0.into(),
+                ret_offset: 0x00.into(),
+                ret_size: 0x20.into(),
+                address: PrecompileCalls::Bn128Pairing.address().to_word(),
+                gas: (PrecompileCalls::Bn128Pairing.base_gas_cost().as_u64()
+                    + GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.as_u64()
+                    - 1)
+                .to_word(),
+                ..Default::default()
+            },
             PrecompileCallArgs {
                 name: "modexp length in u256",
                 setup_code: bytecode! {
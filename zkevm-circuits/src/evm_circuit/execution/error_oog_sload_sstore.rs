@@ -7,7 +7,7 @@ use crate::{
             and,
             common_gadget::{
                 cal_sload_gas_cost_for_assignment, cal_sstore_gas_cost_for_assignment,
-                CommonErrorGadget, SloadGasGadget, SstoreGasGadget,
+                CommonErrorGadget, SloadGasGadget, SstoreGasGadget, StorageSlotAccessGadget,
             },
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{LtGadget, PairSelectGadget},
@@ -37,7 +37,7 @@ pub(crate) struct ErrorOOGSloadSstoreGadget<F> {
     phase2_value: Cell<F>,
     phase2_value_prev: Cell<F>,
     phase2_original_value: Cell<F>,
-    is_warm: Cell<F>,
+    slot_access: StorageSlotAccessGadget<F>,
     is_sstore: PairSelectGadget<F>,
     sstore_gas_cost: SstoreGasGadget<F>,
     insufficient_gas_cost: LtGadget<F, N_BYTES_GAS>,
@@ -72,17 +72,16 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGSloadSstoreGadget<F> {
         let phase2_value = cb.query_cell_phase2();
         let phase2_value_prev = cb.query_cell_phase2();
         let phase2_original_value = cb.query_cell_phase2();
-        let is_warm = cb.query_bool();
 
         cb.stack_pop(phase2_key.expr());
-        cb.account_storage_access_list_read(
+        let slot_access = StorageSlotAccessGadget::construct_read(
+            cb,
             tx_id.expr(),
             callee_address.expr(),
             phase2_key.expr(),
-            is_warm.expr(),
         );
 
-        let sload_gas_cost = SloadGasGadget::construct(cb, is_warm.expr());
+        let sload_gas_cost = SloadGasGadget::construct(cb, slot_access.is_warm().expr());
         let sstore_gas_cost = cb.condition(is_sstore.expr().0, |cb| {
             cb.stack_pop(phase2_value.expr());
 
@@ -99,7 +98,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGSloadSstoreGadget<F> {
                 phase2_value.clone(),
                 phase2_value_prev.clone(),
                 phase2_original_value.clone(),
-                is_warm.clone(),
+                slot_access.is_warm(),
             )
         });
 
@@ -142,7 +141,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGSloadSstoreGadget<F> {
             phase2_value,
             phase2_value_prev,
             phase2_original_value,
-            is_warm,
+            slot_access,
             is_sstore,
             sstore_gas_cost,
             insufficient_gas_cost,
@@ -163,7 +162,9 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGSloadSstoreGadget<F> {
         let opcode = step.opcode.unwrap();
         let is_sstore = opcode == OpcodeId::SSTORE;
         let key = block.rws[step.rw_indices[3]].stack_value();
-        let (is_warm, _) = block.rws[step.rw_indices[4]].tx_access_list_value_pair();
+        let is_warm = self
+            .slot_access
+            .assign(region, offset, &block.rws[step.rw_indices[4]])?;
 
         let (value, value_prev, original_value, gas_cost) = if is_sstore {
             let value = block.rws[step.rw_indices[5]].stack_value();
@@ -208,8 +209,6 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGSloadSstoreGadget<F> {
             .assign(region, offset, region.word_rlc(value_prev))?;
         self.phase2_original_value
             .assign(region, offset, region.word_rlc(original_value))?;
-        self.is_warm
-            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
 
         self.is_sstore.assign(
             region,
@@ -1,14 +1,14 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
+        param::N_BYTES_GAS,
         step::ExecutionState,
         util::{
             common_gadget::CommonErrorGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{IsEqualGadget, LtGadget},
             memory_gadget::{
-                CommonMemoryAddressGadget, MemoryExpandedAddressGadget, MemoryExpansionGadget,
+                CommonMemoryAddressGadget, MemoryExpandedAddressGadget, MemoryExpansionU64Gadget,
             },
             or, select, CachedRegion, Cell,
         },
@@ -23,7 +23,7 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 pub(crate) struct ErrorOOGStaticMemoryGadget<F> {
     opcode: Cell<F>,
     memory_address: MemoryExpandedAddressGadget<F>,
-    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_expansion: MemoryExpansionU64Gadget<F, 1>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     is_mload: IsEqualGadget<F>,
     is_mstore8: IsEqualGadget<F>,
@@ -67,7 +67,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
         );
 
         // Get the next memory size and the gas cost for this memory access
-        let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.end_offset()]);
+        let memory_expansion =
+            MemoryExpansionU64Gadget::construct(cb, [memory_address.end_offset_u64()]);
 
         // Check if the amount of gas available is less than the amount of gas
         // required
@@ -79,7 +80,11 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
 
         cb.require_equal(
             "Memory address is overflow or gas left is less than cost",
-            or::expr([memory_address.overflow(), insufficient_gas.expr()]),
+            or::expr([
+                memory_address.overflow_u64(),
+                memory_expansion.overflow(),
+                insufficient_gas.expr(),
+            ]),
             1.expr(),
         );
 
@@ -134,13 +139,21 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
             F::from(OpcodeId::MSTORE8.as_u64()),
         )?;
 
-        let memory_address =
-            self.memory_address
-                .assign(region, offset, memory_offset, memory_length.into())?;
+        self.memory_address
+            .assign(region, offset, memory_offset, memory_length.into())?;
+        let memory_address_u64 = MemoryExpandedAddressGadget::<F>::end_offset_u64_value(
+            memory_offset,
+            memory_length.into(),
+        );
 
         let memory_expansion_cost = self
             .memory_expansion
-            .assign(region, offset, step.memory_word_size(), [memory_address])?
+            .assign(
+                region,
+                offset,
+                step.memory_word_size(),
+                [memory_address_u64],
+            )?
             .1;
 
         // Gas insufficient check
@@ -175,6 +188,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_oog_static_memory_max_in_range_address() {
+        // 0x1fffffffc0 + 0x20 = MAX_EXPANDED_MEMORY_ADDRESS (0x1fffffffe0), the
+        // address is not overflow but the quadratic expansion cost is out of gas
+        for code in testing_bytecodes(0x1fffffffc0_u64.into()).iter() {
+            test_root(code);
+            test_internal(code);
+        }
+    }
+
     #[test]
     fn test_oog_static_memory_max_expanded_address() {
         // > MAX_EXPANDED_MEMORY_ADDRESS (0x1fffffffe0)
@@ -184,6 +207,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_oog_static_memory_u64_address() {
+        // Within u64 but above MAX_EXPANDED_MEMORY_ADDRESS, the memory gas cost
+        // overflows
+        for code in testing_bytecodes(0xffffffff00000000_u64.into()).iter() {
+            test_root(code);
+            test_internal(code);
+        }
+    }
+
     #[test]
     fn test_oog_static_memory_max_u64_address() {
         for code in testing_bytecodes(u64::MAX.into()).iter() {
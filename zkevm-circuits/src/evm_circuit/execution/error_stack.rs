@@ -121,6 +121,76 @@ mod test {
         );
     }
 
+    /// Execute `opcode` with one item less than it pops.
+    fn test_stack_underflow_of(opcode: OpcodeId) {
+        let (_, max_stack_ptr) = opcode.valid_stack_ptr_range();
+        let mut bytecode = Bytecode::default();
+        for _ in max_stack_ptr + 1..1024 {
+            bytecode.op_push0();
+        }
+        bytecode.write_op(opcode);
+        bytecode.op_stop();
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .run();
+    }
+
+    /// Execute `opcode`, which pushes without popping, on a full stack.
+    fn test_stack_overflow_of(opcode: OpcodeId) {
+        assert_eq!(opcode.valid_stack_ptr_range().0, 1);
+        let mut bytecode = Bytecode::default();
+        for _ in 0..1024 {
+            bytecode.op_push0();
+        }
+        bytecode.write_op(opcode);
+        bytecode.op_stop();
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .params(CircuitsParams {
+            max_rws: 2048,
+            ..Default::default()
+        })
+        .run();
+    }
+
+    #[test]
+    fn stack_underflow_per_arity() {
+        // one opcode for each number of popped items
+        for opcode in [
+            OpcodeId::POP,
+            OpcodeId::ADD,
+            OpcodeId::ADDMOD,
+            OpcodeId::LOG2,
+            OpcodeId::LOG3,
+            OpcodeId::STATICCALL,
+            OpcodeId::CALL,
+        ] {
+            test_stack_underflow_of(opcode);
+        }
+    }
+
+    #[test]
+    fn stack_underflow_dup_swap() {
+        test_stack_underflow_of(OpcodeId::DUP16);
+        test_stack_underflow_of(OpcodeId::SWAP16);
+    }
+
+    #[test]
+    fn stack_overflow_non_push() {
+        for opcode in [
+            OpcodeId::ADDRESS,
+            OpcodeId::PC,
+            OpcodeId::MSIZE,
+            OpcodeId::DUP1,
+        ] {
+            test_stack_overflow_of(opcode);
+        }
+    }
+
     fn test_stack_overflow(opcode: OpcodeId, bytes: &[u8]) {
         assert!(bytes.len() == opcode.data_len());
         let mut bytecode = bytecode! {
@@ -3,7 +3,7 @@ use crate::{
         param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64},
         step::ExecutionState,
         util::{
-            common_gadget::{SameContextGadget, WordByteCapGadget},
+            common_gadget::{AccountAccessGadget, SameContextGadget, WordByteCapGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition,
@@ -37,7 +37,7 @@ pub(crate) struct ExtcodecopyGadget<F> {
     code_offset: WordByteCapGadget<F, N_BYTES_U64>,
     tx_id: Cell<F>,
     reversion_info: ReversionInfo<F>,
-    is_warm: Cell<F>,
+    account_access: AccountAccessGadget<F>,
     code_hash: Cell<F>,
     not_exists: IsZeroGadget<F>,
     code_size: Cell<F>,
@@ -71,12 +71,10 @@ impl<F: Field> ExecutionGadget<F> for ExtcodecopyGadget<F> {
 
         let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
         let mut reversion_info = cb.reversion_info_read(None);
-        let is_warm = cb.query_bool();
-        cb.account_access_list_write(
+        let account_access = AccountAccessGadget::construct(
+            cb,
             tx_id.expr(),
             external_address.expr(),
-            1.expr(),
-            is_warm.expr(),
             Some(&mut reversion_info),
         );
 
@@ -102,12 +100,7 @@ impl<F: Field> ExecutionGadget<F> for ExtcodecopyGadget<F> {
             memory_address.length(),
             memory_expansion.gas_cost(),
         );
-        let gas_cost = memory_copier_gas.gas_cost()
-            + select::expr(
-                is_warm.expr(),
-                GasCost::WARM_ACCESS.expr(),
-                GasCost::COLD_ACCOUNT_ACCESS.expr(),
-            );
+        let gas_cost = memory_copier_gas.gas_cost() + account_access.gas_cost();
 
         let copy_rwc_inc = cb.query_cell();
         cb.condition(memory_address.has_length(), |cb| {
@@ -156,7 +149,7 @@ impl<F: Field> ExecutionGadget<F> for ExtcodecopyGadget<F> {
             code_offset,
             tx_id,
             reversion_info,
-            is_warm,
+            account_access,
             code_hash,
             not_exists,
             code_size,
@@ -195,9 +188,8 @@ impl<F: Field> ExecutionGadget<F> for ExtcodecopyGadget<F> {
             call.is_persistent,
         )?;
 
-        let (_, is_warm) = block.rws[step.rw_indices[7]].tx_access_list_value_pair();
-        self.is_warm
-            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+        self.account_access
+            .assign(region, offset, &block.rws[step.rw_indices[7]])?;
 
         let code_hash = block.rws[step.rw_indices[8]].account_value_pair().0;
         self.code_hash
@@ -361,6 +353,26 @@ mod test {
         ); // cold account
     }
 
+    #[test]
+    fn extcodecopy_precompile_account() {
+        let identity = address!("0x0000000000000000000000000000000000000004");
+        // An empty precompile does not exist, while one that received ETH has
+        // the empty code. Both copy zeros.
+        for balance in [Word::zero(), Word::one()] {
+            test_ok(
+                Some(Account {
+                    address: identity,
+                    balance,
+                    ..Default::default()
+                }),
+                Word::zero(),
+                Word::zero(),
+                0x36,
+                false,
+            );
+        }
+    }
+
     #[test]
     fn extcodecopy_largerthan256() {
         test_ok(
@@ -4,18 +4,18 @@ use crate::{
         param::N_BYTES_ACCOUNT_ADDRESS,
         step::ExecutionState,
         util::{
-            common_gadget::SameContextGadget,
+            common_gadget::{AccountAccessGadget, SameContextGadget},
             constraint_builder::{
                 EVMConstraintBuilder, ReversionInfo, StepStateTransition, Transition::Delta,
             },
-            from_bytes, select, CachedRegion, Cell, Word,
+            from_bytes, CachedRegion, Cell, Word,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
     table::{AccountFieldTag, CallContextFieldTag},
     util::{Expr, Field},
 };
-use eth_types::{evm_types::GasCost, ToLittleEndian};
+use eth_types::ToLittleEndian;
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
@@ -24,7 +24,7 @@ pub(crate) struct ExtcodehashGadget<F> {
     address_word: Word<F>,
     tx_id: Cell<F>,
     reversion_info: ReversionInfo<F>,
-    is_warm: Cell<F>,
+    account_access: AccountAccessGadget<F>,
     code_hash: Cell<F>,
 }
 
@@ -41,12 +41,10 @@ impl<F: Field> ExecutionGadget<F> for ExtcodehashGadget<F> {
         let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
         let mut reversion_info = cb.reversion_info_read(None);
 
-        let is_warm = cb.query_bool();
-        cb.account_access_list_write(
+        let account_access = AccountAccessGadget::construct(
+            cb,
             tx_id.expr(),
             address.expr(),
-            1.expr(),
-            is_warm.expr(),
             Some(&mut reversion_info),
         );
 
@@ -63,16 +61,11 @@ impl<F: Field> ExecutionGadget<F> for ExtcodehashGadget<F> {
         );
         cb.stack_push(code_hash.expr());
 
-        let gas_cost = select::expr(
-            is_warm.expr(),
-            GasCost::WARM_ACCESS.expr(),
-            GasCost::COLD_ACCOUNT_ACCESS.expr(),
-        );
         let step_state_transition = StepStateTransition {
             rw_counter: Delta(cb.rw_counter_offset()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(0.expr()),
-            gas_left: Delta(-gas_cost),
+            gas_left: Delta(-account_access.gas_cost()),
             reversible_write_counter: Delta(1.expr()),
             ..Default::default()
         };
@@ -85,7 +78,7 @@ impl<F: Field> ExecutionGadget<F> for ExtcodehashGadget<F> {
             address_word,
             tx_id,
             reversion_info,
-            is_warm,
+            account_access,
             code_hash,
         }
     }
@@ -115,9 +108,8 @@ impl<F: Field> ExecutionGadget<F> for ExtcodehashGadget<F> {
             call.is_persistent,
         )?;
 
-        let (_, is_warm) = block.rws[step.rw_indices[4]].tx_access_list_value_pair();
-        self.is_warm
-            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+        self.account_access
+            .assign(region, offset, &block.rws[step.rw_indices[4]])?;
 
         let code_hash = block.rws[step.rw_indices[5]].account_value_pair().0;
         self.code_hash
@@ -263,6 +255,59 @@ mod test {
         }
     }
 
+    #[test]
+    fn extcodehash_precompile_account() {
+        let identity = address!("0x0000000000000000000000000000000000000004");
+        // A precompile without balance is empty, so its code hash is 0 ...
+        for is_warm in [false, true] {
+            test_ok(
+                Some(Account {
+                    address: identity,
+                    ..Default::default()
+                }),
+                is_warm,
+            );
+        }
+        // ... while one that received ETH exists and has the empty code hash.
+        test_ok(
+            Some(Account {
+                address: identity,
+                balance: U256::from(1),
+                ..Default::default()
+            }),
+            false,
+        );
+    }
+
+    #[test]
+    fn extcodehash_non_existing_account() {
+        // Unlike in the other tests, the external account is not in the state at all.
+        let code = bytecode! {
+            PUSH20(EXTERNAL_ADDRESS.to_word())
+            EXTCODEHASH
+            STOP
+        };
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .code(code);
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000000010"))
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[1].address);
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     #[test]
     // Regression test to ensure that the code hash for an account that is is being initialized is
     // the empty code hash.
@@ -4,21 +4,21 @@ use crate::{
         param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_U64},
         step::ExecutionState,
         util::{
-            common_gadget::SameContextGadget,
+            common_gadget::{AccountAccessGadget, SameContextGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::Delta,
             },
             from_bytes,
             math_gadget::IsZeroGadget,
-            not, select, CachedRegion, Cell, RandomLinearCombination, Word,
+            not, CachedRegion, Cell, RandomLinearCombination, Word,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
     table::{AccountFieldTag, CallContextFieldTag},
     util::{Expr, Field},
 };
-use eth_types::{evm_types::GasCost, ToLittleEndian};
+use eth_types::ToLittleEndian;
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
@@ -27,7 +27,7 @@ pub(crate) struct ExtcodesizeGadget<F> {
     address_word: Word<F>,
     reversion_info: ReversionInfo<F>,
     tx_id: Cell<F>,
-    is_warm: Cell<F>,
+    account_access: AccountAccessGadget<F>,
     code_hash: Cell<F>,
     not_exists: IsZeroGadget<F>,
     code_size: RandomLinearCombination<F, N_BYTES_U64>,
@@ -45,12 +45,10 @@ impl<F: Field> ExecutionGadget<F> for ExtcodesizeGadget<F> {
 
         let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
         let mut reversion_info = cb.reversion_info_read(None);
-        let is_warm = cb.query_bool();
-        cb.account_access_list_write(
+        let account_access = AccountAccessGadget::construct(
+            cb,
             tx_id.expr(),
             address.expr(),
-            1.expr(),
-            is_warm.expr(),
             Some(&mut reversion_info),
         );
 
@@ -78,12 +76,6 @@ impl<F: Field> ExecutionGadget<F> for ExtcodesizeGadget<F> {
 
         cb.stack_push(code_size.expr());
 
-        let gas_cost = select::expr(
-            is_warm.expr(),
-            GasCost::WARM_ACCESS.expr(),
-            GasCost::COLD_ACCOUNT_ACCESS.expr(),
-        );
-
         let rw_counter_delta = 7.expr();
         #[cfg(feature = "scroll")]
         let rw_counter_delta = rw_counter_delta + exists;
@@ -91,7 +83,7 @@ impl<F: Field> ExecutionGadget<F> for ExtcodesizeGadget<F> {
             rw_counter: Delta(rw_counter_delta),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(0.expr()),
-            gas_left: Delta(-gas_cost),
+            gas_left: Delta(-account_access.gas_cost()),
             reversible_write_counter: Delta(1.expr()),
             ..Default::default()
         };
@@ -104,7 +96,7 @@ impl<F: Field> ExecutionGadget<F> for ExtcodesizeGadget<F> {
             address_word,
             reversion_info,
             tx_id,
-            is_warm,
+            account_access,
             code_hash,
             not_exists,
             code_size,
@@ -137,9 +129,8 @@ impl<F: Field> ExecutionGadget<F> for ExtcodesizeGadget<F> {
             call.is_persistent,
         )?;
 
-        let (_, is_warm) = block.rws[step.rw_indices[4]].tx_access_list_value_pair();
-        self.is_warm
-            .assign(region, offset, Value::known(F::from(is_warm)))?;
+        self.account_access
+            .assign(region, offset, &block.rws[step.rw_indices[4]])?;
 
         let code_hash = block.rws[step.rw_indices[5]].account_value_pair().0;
         self.code_hash
@@ -140,7 +140,7 @@ mod test {
         )
         .unwrap();
 
-        CircuitTestBuilder::<2, 1>::new_from_test_ctx(ctx)
+        CircuitTestBuilder::new_from_test_ctx(ctx)
             .block_modifier(Box::new(|block| {
                 // The above block has 2 steps (GAS and STOP). We forcefully assign a
                 // wrong `gas_left` value for the second step, to assert that
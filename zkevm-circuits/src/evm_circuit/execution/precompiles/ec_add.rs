@@ -2,8 +2,7 @@ use crate::util::Field;
 use bus_mapping::precompile::{PrecompileAuxData, PrecompileCalls};
 use eth_types::{evm_types::GasCost, ToLittleEndian};
 use gadgets::util::{not, select, Expr};
-use gadgets::ToScalar;
-use halo2_proofs::{circuit::Value, plonk::Error};
+use halo2_proofs::plonk::Error;
 
 use crate::{
     evm_circuit::{
@@ -11,17 +10,17 @@ use crate::{
         param::N_BYTES_MEMORY_ADDRESS,
         step::ExecutionState,
         util::{
-            common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::LtGadget,
             padding_gadget::PaddingGadget,
             rlc, CachedRegion, Cell,
         },
     },
-    table::CallContextFieldTag,
     witness::{Block, Call, ExecStep, Transaction},
 };
 
+use super::{PrecompileCallContext, PrecompileReturnGadget};
+
 #[derive(Clone, Debug)]
 pub struct EcAddGadget<F> {
     // input bytes RLC.
@@ -43,14 +42,7 @@ pub struct EcAddGadget<F> {
     point_r_x_rlc: Cell<F>,
     point_r_y_rlc: Cell<F>,
 
-    is_success: Cell<F>,
-    callee_address: Cell<F>,
-    is_root: Cell<F>,
-    call_data_offset: Cell<F>,
-    call_data_length: Cell<F>,
-    return_data_offset: Cell<F>,
-    return_data_length: Cell<F>,
-    restore_context: RestoreContextGadget<F>,
+    precompile_return: PrecompileReturnGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for EcAddGadget<F> {
@@ -81,34 +73,18 @@ impl<F: Field> ExecutionGadget<F> for EcAddGadget<F> {
             cb.query_cell_phase2(),
         );
 
-        let [is_success, callee_address, is_root, call_data_offset, call_data_length, return_data_offset, return_data_length] =
-            [
-                CallContextFieldTag::IsSuccess,
-                CallContextFieldTag::CalleeAddress,
-                CallContextFieldTag::IsRoot,
-                CallContextFieldTag::CallDataOffset,
-                CallContextFieldTag::CallDataLength,
-                CallContextFieldTag::ReturnDataOffset,
-                CallContextFieldTag::ReturnDataLength,
-            ]
-            .map(|tag| cb.call_context(None, tag));
-
-        // all gas sent to this call will be consumed if `is_success == false`.
+        let call_context = PrecompileCallContext::construct(cb);
+
+        // all gas sent to this call will be consumed if `call_context.is_success == false`.
         let gas_cost = select::expr(
-            is_success.expr(),
+            call_context.is_success.expr(),
             GasCost::PRECOMPILE_BN256ADD.expr(),
             cb.curr.state.gas_left.expr(),
         );
 
-        cb.precompile_info_lookup(
-            cb.execution_state().as_u64().expr(),
-            callee_address.expr(),
-            cb.execution_state().precompile_base_gas_cost().expr(),
-        );
-
         cb.ecc_table_lookup(
             u64::from(PrecompileCalls::Bn128Add).expr(),
-            is_success.expr(),
+            call_context.is_success.expr(),
             point_p_x_rlc.expr(),
             point_p_y_rlc.expr(),
             point_q_x_rlc.expr(),
@@ -117,18 +93,22 @@ impl<F: Field> ExecutionGadget<F> for EcAddGadget<F> {
             point_r_x_rlc.expr(),
             point_r_y_rlc.expr(),
         );
-        cb.condition(not::expr(is_success.expr()), |cb| {
+        cb.condition(not::expr(call_context.is_success.expr()), |cb| {
             cb.require_zero("R_x == 0", point_r_x_rlc.expr());
             cb.require_zero("R_y == 0", point_r_y_rlc.expr());
         });
 
         let required_input_len = 128.expr();
-        let pad_right = LtGadget::construct(cb, call_data_length.expr(), required_input_len.expr());
+        let pad_right = LtGadget::construct(
+            cb,
+            call_context.call_data_length.expr(),
+            required_input_len.expr(),
+        );
         let padding = cb.condition(pad_right.expr(), |cb| {
             PaddingGadget::construct(
                 cb,
                 input_bytes_rlc.expr(),
-                call_data_length.expr(),
+                call_context.call_data_length.expr(),
                 required_input_len,
             )
         });
@@ -162,12 +142,11 @@ impl<F: Field> ExecutionGadget<F> for EcAddGadget<F> {
             point_r_x_rlc.expr() * r_pow_32 + point_r_y_rlc.expr(),
         );
 
-        let restore_context = super::gen_restore_context(
+        let precompile_return = PrecompileReturnGadget::construct(
             cb,
-            is_root.expr(),
-            is_success.expr(),
+            &call_context,
             gas_cost.expr(),
-            select::expr(is_success.expr(), 0x40.expr(), 0x00.expr()), // ReturnDataLength
+            select::expr(call_context.is_success.expr(), 0x40.expr(), 0x00.expr()), // ReturnDataLength
         );
 
         Self {
@@ -185,14 +164,7 @@ impl<F: Field> ExecutionGadget<F> for EcAddGadget<F> {
             point_r_x_rlc,
             point_r_y_rlc,
 
-            is_success,
-            callee_address,
-            is_root,
-            call_data_offset,
-            call_data_length,
-            return_data_offset,
-            return_data_length,
-            restore_context,
+            precompile_return,
         }
     }
 
@@ -250,41 +222,8 @@ impl<F: Field> ExecutionGadget<F> for EcAddGadget<F> {
             return Err(Error::Synthesis);
         }
 
-        self.is_success.assign(
-            region,
-            offset,
-            Value::known(F::from(u64::from(call.is_success))),
-        )?;
-        self.callee_address.assign(
-            region,
-            offset,
-            Value::known(call.code_address.unwrap().to_scalar().unwrap()),
-        )?;
-        self.is_root
-            .assign(region, offset, Value::known(F::from(call.is_root as u64)))?;
-        self.call_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_offset)),
-        )?;
-        self.call_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_length)),
-        )?;
-        self.return_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_offset)),
-        )?;
-        self.return_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_length)),
-        )?;
-
-        self.restore_context
-            .assign(region, offset, block, call, step, 7)
+        self.precompile_return
+            .assign(region, offset, block, call, step)
     }
 }
 
@@ -655,4 +594,27 @@ mod test {
                 .run();
             })
     }
+
+    #[test]
+    fn precompile_ec_add_usage_test() {
+        use bus_mapping::{circuit_input_builder::PrecompileUsage, mock::BlockData};
+        use eth_types::geth_types::GethData;
+
+        let bytecode = TEST_VECTOR[0].with_call_op(OpcodeId::STATICCALL);
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode)
+            .unwrap()
+            .into();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        assert_eq!(
+            builder.precompile_usage(),
+            PrecompileUsage {
+                ec_add: 1,
+                input_bytes: 128,
+                ..Default::default()
+            }
+        );
+    }
 }
@@ -1,28 +1,23 @@
+use super::{PrecompileCallContext, PrecompileReturnGadget};
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
         param::N_BYTES_MEMORY_ADDRESS,
         step::ExecutionState,
         util::{
-            common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{AddWordsGadget, IsEqualGadget, IsZeroGadget, LtGadget, ModGadget},
             padding_gadget::PaddingGadget,
             rlc, CachedRegion, Cell, Word,
         },
     },
-    table::CallContextFieldTag,
     util::Field,
     witness::{Block, Call, ExecStep, Transaction},
 };
 use bus_mapping::precompile::{PrecompileAuxData, PrecompileCalls};
 use eth_types::{evm_types::GasCost, ToLittleEndian, U256};
 use gadgets::util::{and, not, or, select, split_u256, sum, Expr};
-use gadgets::ToScalar;
-use halo2_proofs::{
-    circuit::Value,
-    plonk::{Error, Expression},
-};
+use halo2_proofs::plonk::{Error, Expression};
 use std::{
     ops::{Add, Sub},
     sync::LazyLock,
@@ -75,14 +70,7 @@ pub struct EcMulGadget<F> {
     fr_modulus: Word<F>,   // Fr::MODULUS
     modword: ModGadget<F, false>,
 
-    is_success: Cell<F>,
-    callee_address: Cell<F>,
-    is_root: Cell<F>,
-    call_data_offset: Cell<F>,
-    call_data_length: Cell<F>,
-    return_data_offset: Cell<F>,
-    return_data_length: Cell<F>,
-    restore_context: RestoreContextGadget<F>,
+    precompile_return: PrecompileReturnGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for EcMulGadget<F> {
@@ -182,21 +170,11 @@ impl<F: Field> ExecutionGadget<F> for EcMulGadget<F> {
             sum::expr(fq_modulus_hi.to_le_bytes()),
         );
 
-        let [is_success, callee_address, is_root, call_data_offset, call_data_length, return_data_offset, return_data_length] =
-            [
-                CallContextFieldTag::IsSuccess,
-                CallContextFieldTag::CalleeAddress,
-                CallContextFieldTag::IsRoot,
-                CallContextFieldTag::CallDataOffset,
-                CallContextFieldTag::CallDataLength,
-                CallContextFieldTag::ReturnDataOffset,
-                CallContextFieldTag::ReturnDataLength,
-            ]
-            .map(|tag| cb.call_context(None, tag));
-
-        // all gas sent to this call will be consumed if `is_success == false`.
+        let call_context = PrecompileCallContext::construct(cb);
+
+        // all gas sent to this call will be consumed if `call_context.is_success == false`.
         let gas_cost = select::expr(
-            is_success.expr(),
+            call_context.is_success.expr(),
             GasCost::PRECOMPILE_BN256MUL.expr(),
             cb.curr.state.gas_left.expr(),
         );
@@ -214,7 +192,7 @@ impl<F: Field> ExecutionGadget<F> for EcMulGadget<F> {
             |cb| {
                 cb.ecc_table_lookup(
                     u64::from(PrecompileCalls::Bn128Mul).expr(),
-                    is_success.expr(),
+                    call_context.is_success.expr(),
                     point_p_x_rlc.expr(),
                     point_p_y_rlc.expr(),
                     scalar_s_native.expr(),
@@ -225,7 +203,7 @@ impl<F: Field> ExecutionGadget<F> for EcMulGadget<F> {
                 );
             },
         );
-        cb.condition(not::expr(is_success.expr()), |cb| {
+        cb.condition(not::expr(call_context.is_success.expr()), |cb| {
             cb.require_zero("R_x == 0", point_r_x_rlc.expr());
             cb.require_zero("R_y == 0", point_r_y_rlc.expr());
         });
@@ -245,7 +223,7 @@ impl<F: Field> ExecutionGadget<F> for EcMulGadget<F> {
         // - P_y + R_y == Fq::MODULUS
         let p_y_plus_r_y = cb.condition(
             and::expr([
-                is_success.expr(),
+                call_context.is_success.expr(),
                 s_is_fr_mod_minus_1.expr(),
                 not::expr(p_is_zero.expr()),
             ]),
@@ -263,19 +241,17 @@ impl<F: Field> ExecutionGadget<F> for EcMulGadget<F> {
             },
         );
 
-        cb.precompile_info_lookup(
-            cb.execution_state().as_u64().expr(),
-            callee_address.expr(),
-            cb.execution_state().precompile_base_gas_cost().expr(),
-        );
-
         let required_input_len = 96.expr();
-        let pad_right = LtGadget::construct(cb, call_data_length.expr(), required_input_len.expr());
+        let pad_right = LtGadget::construct(
+            cb,
+            call_context.call_data_length.expr(),
+            required_input_len.expr(),
+        );
         let padding = cb.condition(pad_right.expr(), |cb| {
             PaddingGadget::construct(
                 cb,
                 input_bytes_rlc.expr(),
-                call_data_length.expr(),
+                call_context.call_data_length.expr(),
                 required_input_len,
             )
         });
@@ -307,12 +283,11 @@ impl<F: Field> ExecutionGadget<F> for EcMulGadget<F> {
             point_r_x_rlc.expr() * r_pow_32 + point_r_y_rlc.expr(),
         );
 
-        let restore_context = super::gen_restore_context(
+        let precompile_return = PrecompileReturnGadget::construct(
             cb,
-            is_root.expr(),
-            is_success.expr(),
+            &call_context,
             gas_cost.expr(),
-            select::expr(is_success.expr(), 0x40.expr(), 0x00.expr()), // ReturnDataLength
+            select::expr(call_context.is_success.expr(), 0x40.expr(), 0x00.expr()), // ReturnDataLength
         );
 
         Self {
@@ -343,14 +318,7 @@ impl<F: Field> ExecutionGadget<F> for EcMulGadget<F> {
             fr_modulus,
             modword,
 
-            is_success,
-            callee_address,
-            is_root,
-            call_data_offset,
-            call_data_length,
-            return_data_offset,
-            return_data_length,
-            restore_context,
+            precompile_return,
         }
     }
 
@@ -455,41 +423,8 @@ impl<F: Field> ExecutionGadget<F> for EcMulGadget<F> {
             return Err(Error::Synthesis);
         }
 
-        self.is_success.assign(
-            region,
-            offset,
-            Value::known(F::from(u64::from(call.is_success))),
-        )?;
-        self.callee_address.assign(
-            region,
-            offset,
-            Value::known(call.code_address.unwrap().to_scalar().unwrap()),
-        )?;
-        self.is_root
-            .assign(region, offset, Value::known(F::from(call.is_root as u64)))?;
-        self.call_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_offset)),
-        )?;
-        self.call_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_length)),
-        )?;
-        self.return_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_offset)),
-        )?;
-        self.return_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_length)),
-        )?;
-
-        self.restore_context
-            .assign(region, offset, block, call, step, 7)
+        self.precompile_return
+            .assign(region, offset, block, call, step)
     }
 }
 
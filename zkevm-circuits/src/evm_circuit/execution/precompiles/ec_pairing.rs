@@ -1,11 +1,10 @@
 use crate::util::Field;
 use bus_mapping::{
-    circuit_input_builder::{N_BYTES_PER_PAIR, N_PAIRING_PER_OP},
+    circuit_input_builder::N_BYTES_PER_PAIR,
     precompile::{EcPairingError, PrecompileAuxData, PrecompileCalls},
 };
 use eth_types::evm_types::GasCost;
-use gadgets::util::{and, not, or, select, Expr};
-use gadgets::ToScalar;
+use gadgets::util::{and, not, select, Expr};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 use crate::{
@@ -13,20 +12,29 @@ use crate::{
         execution::ExecutionGadget,
         step::ExecutionState,
         util::{
-            common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-            math_gadget::{BinaryNumberGadget, IsZeroGadget, LtGadget},
+            math_gadget::{ConstantDivisionGadget, IsZeroGadget, LtGadget},
             rlc, CachedRegion, Cell,
         },
     },
-    table::CallContextFieldTag,
     witness::{Block, Call, ExecStep, Transaction},
 };
 
-/// Note: input_len ∈ { 0, 192, 384, 576, 768 } if valid.
+use super::{PrecompileCallContext, PrecompileReturnGadget};
+
+/// Maximum number of pairs of a successful call. scroll fails the calls with more than
+/// `N_PAIRING_PER_OP` pairs, while the number of pairs is only bounded by the ECC circuit
+/// otherwise.
+#[cfg(feature = "scroll")]
+const MAX_N_PAIRS: u64 = bus_mapping::circuit_input_builder::N_PAIRING_PER_OP as u64;
+#[cfg(not(feature = "scroll"))]
+const MAX_N_PAIRS: u64 = u32::MAX as u64 / N_BYTES_PER_PAIR as u64;
+
+/// Note: input_len == 192 * n_pairs if valid, with n_pairs <= `MAX_N_PAIRS`.
 ///
-/// Note: input bytes are padded to 768 bytes within our zkEVM implementation to standardise a
-/// pairing operation, such that each pairing op has 4 pairs: [(G1, G2); 4].
+/// Note: the ECC circuit pads the pairs to `ec_pairing_pairs` pairs within our zkEVM
+/// implementation to standardise a pairing operation, and looks up the input bytes without the
+/// padding pairs with the number of pairs.
 #[derive(Clone, Debug)]
 pub struct EcPairingGadget<F> {
     // Random linear combination of input bytes to the precompile ecPairing call.
@@ -41,31 +49,16 @@ pub struct EcPairingGadget<F> {
     output: Cell<F>,
 
     // Verify invalidity of input bytes. We basically check `or(1, 2)` where:
-    // 1. input_len > 4 * 192
-    // 2. input_len % 192 != 0
-    input_is_zero: IsZeroGadget<F>,
-
-    // call_data_len must less than 2^32.
-    input_lt_769: LtGadget<F, 4>,
-    input_mod_192: Cell<F>,
-    input_div_192: Cell<F>,
-    input_mod_192_lt: LtGadget<F, 1>,
+    // 1. input_len % 192 != 0
+    // 2. input_len // 192 > MAX_N_PAIRS
+    //
+    // The quotient is the number of pairs provided through EVM input. call_data_len must less
+    // than 2^32.
+    input_div_192: ConstantDivisionGadget<F, 4>,
     input_mod_192_is_zero: IsZeroGadget<F>,
+    n_pairs_lt: LtGadget<F, 4>,
 
-    /// Number of pairs provided through EVM input. Since a maximum of 4 pairs can be supplied from
-    /// EVM, we need 3 binary bits for a max value of [1, 0, 0].
-    n_pairs: Cell<F>,
-    n_pairs_cmp: BinaryNumberGadget<F, 3>,
-    rand_pow_64: Cell<F>,
-
-    is_success: Cell<F>,
-    callee_address: Cell<F>,
-    is_root: Cell<F>,
-    call_data_offset: Cell<F>,
-    call_data_length: Cell<F>,
-    return_data_offset: Cell<F>,
-    return_data_length: Cell<F>,
-    restore_context: RestoreContextGadget<F>,
+    precompile_return: PrecompileReturnGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
@@ -81,94 +74,42 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             cb.query_bool(),
         );
 
-        let n_pairs = cb.query_cell();
-        let n_pairs_cmp = BinaryNumberGadget::construct(cb, n_pairs.expr());
+        let call_context = PrecompileCallContext::construct(cb);
 
-        let [is_success, callee_address, is_root, call_data_offset, call_data_length, return_data_offset, return_data_length] =
-            [
-                CallContextFieldTag::IsSuccess,
-                CallContextFieldTag::CalleeAddress,
-                CallContextFieldTag::IsRoot,
-                CallContextFieldTag::CallDataOffset,
-                CallContextFieldTag::CallDataLength,
-                CallContextFieldTag::ReturnDataOffset,
-                CallContextFieldTag::ReturnDataLength,
-            ]
-            .map(|tag| cb.call_context(None, tag));
-
-        cb.precompile_info_lookup(
-            cb.execution_state().as_u64().expr(),
-            callee_address.expr(),
-            cb.execution_state().precompile_base_gas_cost().expr(),
+        // q == len(input) // 192 and r == len(input) % 192
+        let input_div_192 = ConstantDivisionGadget::construct(
+            cb,
+            call_context.call_data_length.expr(),
+            N_BYTES_PER_PAIR as u64,
         );
+        let input_mod_192_is_zero = IsZeroGadget::construct(cb, input_div_192.remainder());
+        let n_pairs = input_div_192.quotient();
+        let n_pairs_lt = LtGadget::construct(cb, n_pairs.expr(), (MAX_N_PAIRS + 1).expr());
+        let is_valid_len = and::expr([input_mod_192_is_zero.expr(), n_pairs_lt.expr()]);
 
-        // all gas sent to this call will be consumed if `is_success == false`.
+        // all gas sent to this call will be consumed if `call_context.is_success == false`.
         let gas_cost = select::expr(
-            is_success.expr(),
+            call_context.is_success.expr(),
             GasCost::PRECOMPILE_BN256PAIRING.expr()
                 + n_pairs.expr() * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.expr(),
             cb.curr.state.gas_left.expr(),
         );
 
         // if the precompile call was unsuccessful, the output (pairing check) MUST BE 0.
-        // `is_success` and `output` both are booleans, so:
+        // `call_context.is_success` and `output` both are booleans, so:
         cb.require_boolean(
             "if the precompile call was unsuccessful, pairing check == 0",
-            is_success.expr() - output.expr(),
+            call_context.is_success.expr() - output.expr(),
         );
 
         //////////////////////////////// INVALID BEGIN ////////////////////////////////
-        let input_is_zero = IsZeroGadget::construct(cb, call_data_length.expr());
-        let input_lt_769 = LtGadget::construct(cb, call_data_length.expr(), 769.expr());
-        let (input_mod_192, input_div_192, input_mod_192_lt, input_mod_192_is_zero) = cb.condition(
-            and::expr([not::expr(input_is_zero.expr()), input_lt_769.expr()]),
-            |cb| {
-                // r == len(input) % 192
-                let input_mod_192 = cb.query_byte();
-                // r < 192
-                let input_mod_192_lt = LtGadget::construct(cb, input_mod_192.expr(), 192.expr());
-                cb.require_equal("len(input) % 192 < 192", input_mod_192_lt.expr(), 1.expr());
-                // q == len(input) // 192
-                let input_div_192 = cb.query_cell();
-                cb.require_in_set(
-                    "len(input) // 192 ∈ { 0, 1, 2, 3, 4 }",
-                    input_div_192.expr(),
-                    vec![0.expr(), 1.expr(), 2.expr(), 3.expr(), 4.expr()],
-                );
-                // q * 192 + r == call_data_length
-                cb.require_equal(
-                    "q * 192 + r == len(input)",
-                    input_div_192.expr() * 192.expr() + input_mod_192.expr(),
-                    call_data_length.expr(),
-                );
-                let input_mod_192_is_zero = IsZeroGadget::construct(cb, input_mod_192.expr());
-                (
-                    input_mod_192,
-                    input_div_192,
-                    input_mod_192_lt,
-                    input_mod_192_is_zero,
-                )
-            },
-        );
-
-        // when input is zero then input mod_192 is zero
-        cb.condition(input_is_zero.expr(), |cb| {
-            cb.require_true(
-                "when input is zero, then input mod192 is zero",
-                input_mod_192_is_zero.expr(),
-            );
-        });
-
         cb.condition(
-            // (len(input) > 768) || (len(input) % 192 != 0)
-            or::expr([
-                not::expr(input_lt_769.expr()),
-                not::expr(input_mod_192_is_zero.expr()),
-            ]),
+            // (len(input) % 192 != 0) || (len(input) // 192 > MAX_N_PAIRS)
+            not::expr(is_valid_len.expr()),
             |cb| {
                 cb.require_equal(
-                    "len(input) is invalid => is_success == false",
-                    is_success.expr(),
+                    "len(input) is invalid => call_context.is_success == false",
+                    call_context.is_success.expr(),
                     false.expr(),
                 );
                 cb.require_zero("pairing check == 0", output.expr());
@@ -177,100 +118,37 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
         //////////////////////////////// INVALID END //////////////////////////////////
 
         ///////////////////////////////// VALID BEGIN /////////////////////////////////
-        let rand_pow_64 = cb.condition(
-            // (len(input) == 0) || ((len(input) <= 768) && (len(input) % 192 == 0))
-            or::expr([
-                input_is_zero.expr(),
-                and::expr([input_lt_769.expr(), input_mod_192_is_zero.expr()]),
-            ]),
-            |cb| {
-                let rand_pow_64 = cb.query_cell_phase2();
-                let (rand_pow_192, rand_pow_384, rand_pow_576) = {
-                    let rand_pow_128 = rand_pow_64.expr() * rand_pow_64.expr();
-                    let rand_pow_192 = rand_pow_128.expr() * rand_pow_64.expr();
-                    let rand_pow_384 = rand_pow_192.expr() * rand_pow_192.expr();
-                    let rand_pow_576 = rand_pow_384.expr() * rand_pow_192.expr();
-                    (rand_pow_192, rand_pow_384, rand_pow_576)
-                };
-                cb.pow_of_rand_lookup(64.expr(), rand_pow_64.expr());
-
-                // RLC(inputs) that was processed in the ECC Circuit.
-                let ecc_circuit_input_rlc = select::expr(
-                    n_pairs_cmp.value_equals(0usize),
-                    0.expr(),
-                    select::expr(
-                        n_pairs_cmp.value_equals(1usize),
-                        input_bytes_rlc.expr() * rand_pow_576.expr(), /* 576 bytes padded */
-                        select::expr(
-                            n_pairs_cmp.value_equals(2usize),
-                            input_bytes_rlc.expr() * rand_pow_384.expr(), /* 384 bytes padded */
-                            select::expr(
-                                n_pairs_cmp.value_equals(3usize),
-                                input_bytes_rlc.expr() * rand_pow_192.expr(), /* 192 bytes padded */
-                                input_bytes_rlc.expr(),                       /* 0 bytes padded */
-                            ),
-                        ),
-                    ),
-                );
-                cb.condition(n_pairs_cmp.value_equals(0usize), |cb| {
-                    cb.require_zero(
-                        "ecPairing: n_pairs == 0 => evm input == 0",
-                        input_bytes_rlc.expr(),
-                    );
-                });
-
-                // Covers the following cases:
-                // 1. pairing == 1 (where input_rlc == 0, i.e. len(input) == 0).
-                // 2. pairing == 1 (where input_rlc != 0, i.e. len(input) != 0).
-                // 3. pairing == 0 (both valid and invalid inputs)
-                //     - G1 point not on curve
-                //     - G2 point not on curve
-                //     - G1 co-ord is not in canonical form
-                //     - G2 co-ord is not in canonical form
-                //     - G1, G2 both valid
-                cb.ecc_table_lookup(
-                    u64::from(PrecompileCalls::Bn128Pairing).expr(),
-                    is_success.expr(),
-                    0.expr(),
-                    0.expr(),
-                    0.expr(),
-                    0.expr(),
-                    ecc_circuit_input_rlc.expr(),
-                    output.expr(),
-                    0.expr(),
-                );
-
-                // since len(input) was valid, we know that we are left with 3 scenarios:
-                // 1. pairing check == true
-                // 2. pairing check == false
-                // 3. invalid inputs:
-                //      - invalid field element
-                //      - point not on G1
-                //      - point not on G2
-                //
-                // In all the above, we know that len(input) % 192 == 0 and len(input) <= 768
-                cb.require_equal(
-                    "ecPairing: n_pairs * N_BYTES_PER_PAIR == call_data_length",
-                    n_pairs.expr() * N_BYTES_PER_PAIR.expr(),
-                    call_data_length.expr(),
-                );
-                cb.require_in_set(
-                    "ecPairing: input_len ∈ { 0, 192, 384, 576, 768 }",
-                    call_data_length.expr(),
-                    vec![0.expr(), 192.expr(), 384.expr(), 576.expr(), 768.expr()],
-                );
-
-                rand_pow_64
-            },
-        );
+        cb.condition(is_valid_len.expr(), |cb| {
+            // Covers the following cases:
+            // 1. pairing == 1 (where input_rlc == 0, i.e. len(input) == 0).
+            // 2. pairing == 1 (where input_rlc != 0, i.e. len(input) != 0).
+            // 3. pairing == 0 (both valid and invalid inputs)
+            //     - G1 point not on curve
+            //     - G2 point not on curve
+            //     - G1 co-ord is not in canonical form
+            //     - G2 co-ord is not in canonical form
+            //     - G1, G2 both valid
+            //
+            // The ECC circuit proves the pairs of an op with as many pairs as the call.
+            cb.ecc_table_lookup(
+                u64::from(PrecompileCalls::Bn128Pairing).expr(),
+                call_context.is_success.expr(),
+                n_pairs.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                input_bytes_rlc.expr(),
+                output.expr(),
+                0.expr(),
+            );
+        });
         ///////////////////////////////// VALID END ///////////////////////////////////
 
-        let restore_context = super::gen_restore_context(
+        let precompile_return = PrecompileReturnGadget::construct(
             cb,
-            is_root.expr(),
-            is_success.expr(),
+            &call_context,
             gas_cost.expr(),
-            select::expr(is_success.expr(), 0x20.expr(), 0x00.expr()), // ReturnDataLength
+            select::expr(call_context.is_success.expr(), 0x20.expr(), 0x00.expr()), // ReturnDataLength
         );
 
         Self {
@@ -280,25 +158,11 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
 
             output,
 
-            input_is_zero,
-            input_lt_769,
-            input_mod_192,
             input_div_192,
-            input_mod_192_lt,
             input_mod_192_is_zero,
+            n_pairs_lt,
 
-            n_pairs,
-            n_pairs_cmp,
-            rand_pow_64,
-
-            is_success,
-            callee_address,
-            is_root,
-            call_data_offset,
-            call_data_length,
-            return_data_offset,
-            return_data_length,
-            restore_context,
+            precompile_return,
         }
     }
 
@@ -315,56 +179,33 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             let keccak_rand = region.challenges().keccak_input();
 
             // len(input) related assignment.
-            self.input_is_zero
-                .assign(region, offset, F::from(call.call_data_length))?;
             log::trace!(
                 "assign ec pairing exec step: calldata_len = {}",
                 call.call_data_length
             );
-            self.input_lt_769.assign(
+            let (n_pairs, input_mod_192) =
+                self.input_div_192
+                    .assign(region, offset, call.call_data_length as u128)?;
+            self.input_mod_192_is_zero
+                .assign(region, offset, F::from(input_mod_192 as u64))?;
+            self.n_pairs_lt.assign(
                 region,
                 offset,
-                F::from(call.call_data_length),
-                F::from(769),
+                F::from(n_pairs as u64),
+                F::from(MAX_N_PAIRS + 1),
             )?;
-            let (input_div_192, input_mod_192) = (
-                call.call_data_length / (N_BYTES_PER_PAIR as u64),
-                call.call_data_length % (N_BYTES_PER_PAIR as u64),
-            );
-            self.input_div_192
-                .assign(region, offset, Value::known(F::from(input_div_192)))?;
-            self.input_mod_192
-                .assign(region, offset, Value::known(F::from(input_mod_192)))?;
-            self.input_mod_192_lt
-                .assign(region, offset, F::from(input_mod_192), F::from(192))?;
-            self.input_mod_192_is_zero
-                .assign(region, offset, F::from(input_mod_192))?;
 
             match *res_aux_data {
                 Ok(aux_data) => {
                     debug_assert!(
-                        call.call_data_length <= (N_PAIRING_PER_OP * N_BYTES_PER_PAIR) as u64,
-                        "len(input) > 768"
-                    );
-                    debug_assert!(
-                        call.call_data_length % (N_BYTES_PER_PAIR as u64) == 0,
-                        "len(input) % 192 != 0"
+                        n_pairs as u64 <= MAX_N_PAIRS,
+                        "len(input) > 192 * MAX_N_PAIRS"
                     );
-                    // Consider only call_data_length bytes for EVM input.
+                    debug_assert!(input_mod_192 == 0, "len(input) % 192 != 0");
                     self.input_bytes_rlc.assign(
                         region,
                         offset,
-                        keccak_rand.map(|r| {
-                            rlc::value(
-                                aux_data
-                                    .0
-                                    .to_bytes_be()
-                                    .iter()
-                                    .take(call.call_data_length as usize)
-                                    .rev(),
-                                r,
-                            )
-                        }),
+                        keccak_rand.map(|r| rlc::value(aux_data.0.to_bytes_be().iter().rev(), r)),
                     )?;
                     self.output_bytes_rlc.assign(
                         region,
@@ -388,16 +229,6 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                                 .expect("ecPairing: output in {0, 1}"),
                         ),
                     )?;
-                    // Number of pairs provided in the EVM call.
-                    let n_pairs = (call.call_data_length as usize) / N_BYTES_PER_PAIR;
-                    self.n_pairs
-                        .assign(region, offset, Value::known(F::from(n_pairs as u64)))?;
-                    self.n_pairs_cmp.assign(region, offset, n_pairs)?;
-                    self.rand_pow_64.assign(
-                        region,
-                        offset,
-                        keccak_rand.map(|r| r.pow([64, 0, 0, 0])),
-                    )?;
                 }
                 Err(EcPairingError::InvalidInputLen(input_bytes)) => {
                     debug_assert_eq!(
@@ -406,8 +237,7 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                         "len(input) != call_data_length"
                     );
                     debug_assert!(
-                        (call.call_data_length > (N_PAIRING_PER_OP * N_BYTES_PER_PAIR) as u64)
-                            || (call.call_data_length % (N_BYTES_PER_PAIR as u64) != 0),
+                        (n_pairs as u64 > MAX_N_PAIRS) || (input_mod_192 != 0),
                         "len(input) is expected to be invalid",
                     );
                     // Consider only call_data_length bytes for EVM input.
@@ -428,40 +258,8 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             return Err(Error::Synthesis);
         }
 
-        self.is_success.assign(
-            region,
-            offset,
-            Value::known(F::from(u64::from(call.is_success))),
-        )?;
-        self.callee_address.assign(
-            region,
-            offset,
-            Value::known(call.code_address.unwrap().to_scalar().unwrap()),
-        )?;
-        self.is_root
-            .assign(region, offset, Value::known(F::from(call.is_root as u64)))?;
-        self.call_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_offset)),
-        )?;
-        self.call_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_length)),
-        )?;
-        self.return_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_offset)),
-        )?;
-        self.return_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_length)),
-        )?;
-        self.restore_context
-            .assign(region, offset, block, call, step, 7)
+        self.precompile_return
+            .assign(region, offset, block, call, step)
     }
 }
 
@@ -1190,4 +988,52 @@ mod test {
                     .run()
             })
     }
+
+    // without the scroll pair limit, the call succeeds and is proven by pairing ops of as many
+    // pairs, up to ec_pairing_pairs.
+    #[cfg(not(feature = "scroll"))]
+    #[test]
+    fn precompile_ec_pairing_more_pairs_test() {
+        use bus_mapping::{circuit_input_builder::PrecompileEcParams, mock::BlockData};
+        use eth_types::geth_types::GethData;
+
+        let bytecode = PrecompileCallArgs {
+            name: "ecPairing: all zero bytes, len(input) == 5 * 192",
+            setup_code: bytecode! {},
+            call_data_offset: 0x00.into(),
+            call_data_length: 0x3C0.into(),
+            ret_offset: 0x3C0.into(),
+            ret_size: 0x20.into(),
+            address: PrecompileCalls::Bn128Pairing.address().to_word(),
+            ..Default::default()
+        }
+        .with_call_op(OpcodeId::STATICCALL);
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode.clone())
+            .unwrap()
+            .into();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let err = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                bus_mapping::Error::UnsupportedEcPairingSize { n_pairs: 5, limit }
+                    if limit == PrecompileEcParams::default().ec_pairing_pairs
+            ),
+            "{err:?}"
+        );
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .params(CircuitsParams {
+            max_ec_ops: PrecompileEcParams {
+                ec_pairing_pairs: 5,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .run();
+    }
 }
@@ -2,7 +2,6 @@ use crate::util::Field;
 use bus_mapping::precompile::{PrecompileAuxData, PrecompileCalls};
 use eth_types::{evm_types::GasCost, word, ToLittleEndian, U256};
 use gadgets::util::{and, not, or, select, sum, Expr};
-use gadgets::ToScalar;
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
@@ -15,7 +14,6 @@ use crate::{
         param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_MEMORY_ADDRESS, N_BYTES_WORD},
         step::ExecutionState,
         util::{
-            common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             from_bytes,
             math_gadget::{IsEqualGadget, IsZeroGadget, LtGadget, LtWordGadget, ModGadget},
@@ -23,10 +21,11 @@ use crate::{
             rlc, CachedRegion, Cell, RandomLinearCombination, Word,
         },
     },
-    table::CallContextFieldTag,
     witness::{Block, Call, ExecStep, Transaction},
 };
 
+use super::{PrecompileCallContext, PrecompileReturnGadget};
+
 static FQ_MODULUS: LazyLock<U256> =
     LazyLock::new(|| word!("0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"));
 
@@ -61,14 +60,7 @@ pub struct EcrecoverGadget<F> {
     sig_v_eq27: IsEqualGadget<F>,
     sig_v_eq28: IsEqualGadget<F>,
 
-    is_success: Cell<F>,
-    callee_address: Cell<F>,
-    is_root: Cell<F>,
-    call_data_offset: Cell<F>,
-    call_data_length: Cell<F>,
-    return_data_offset: Cell<F>,
-    return_data_length: Cell<F>,
-    restore_context: RestoreContextGadget<F>,
+    precompile_return: PrecompileReturnGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for EcrecoverGadget<F> {
@@ -178,20 +170,10 @@ impl<F: Field> ExecutionGadget<F> for EcrecoverGadget<F> {
             cb.word_rlc::<N_BYTES_WORD>(FQ_MODULUS.to_le_bytes().map(|b| b.expr())),
         );
 
-        let [is_success, callee_address, is_root, call_data_offset, call_data_length, return_data_offset, return_data_length] =
-            [
-                CallContextFieldTag::IsSuccess,
-                CallContextFieldTag::CalleeAddress,
-                CallContextFieldTag::IsRoot,
-                CallContextFieldTag::CallDataOffset,
-                CallContextFieldTag::CallDataLength,
-                CallContextFieldTag::ReturnDataOffset,
-                CallContextFieldTag::ReturnDataLength,
-            ]
-            .map(|tag| cb.call_context(None, tag));
+        let call_context = PrecompileCallContext::construct(cb);
 
         let gas_cost = select::expr(
-            is_success.expr(),
+            call_context.is_success.expr(),
             GasCost::PRECOMPILE_ECRECOVER_BASE.expr(),
             cb.curr.state.gas_left.expr(),
         );
@@ -232,19 +214,17 @@ impl<F: Field> ExecutionGadget<F> for EcrecoverGadget<F> {
             );
         });
 
-        cb.precompile_info_lookup(
-            cb.execution_state().as_u64().expr(),
-            callee_address.expr(),
-            cb.execution_state().precompile_base_gas_cost().expr(),
-        );
-
         let required_input_len = 128.expr();
-        let pad_right = LtGadget::construct(cb, call_data_length.expr(), required_input_len.expr());
+        let pad_right = LtGadget::construct(
+            cb,
+            call_context.call_data_length.expr(),
+            required_input_len.expr(),
+        );
         let padding = cb.condition(pad_right.expr(), |cb| {
             PaddingGadget::construct(
                 cb,
                 input_bytes_rlc.expr(),
-                call_data_length.expr(),
+                call_context.call_data_length.expr(),
                 required_input_len,
             )
         });
@@ -282,10 +262,9 @@ impl<F: Field> ExecutionGadget<F> for EcrecoverGadget<F> {
             cb.require_zero("output bytes == 0", output_bytes_rlc.expr());
         });
 
-        let restore_context = super::gen_restore_context(
+        let precompile_return = PrecompileReturnGadget::construct(
             cb,
-            is_root.expr(),
-            is_success.expr(),
+            &call_context,
             gas_cost.expr(),
             select::expr(recovered.expr(), 0x20.expr(), 0x00.expr()), // ReturnDataLength
         );
@@ -320,14 +299,7 @@ impl<F: Field> ExecutionGadget<F> for EcrecoverGadget<F> {
             sig_v_eq27,
             sig_v_eq28,
 
-            is_success,
-            callee_address,
-            is_root,
-            call_data_offset,
-            call_data_length,
-            return_data_offset,
-            return_data_length,
-            restore_context,
+            precompile_return,
         }
     }
 
@@ -477,40 +449,8 @@ impl<F: Field> ExecutionGadget<F> for EcrecoverGadget<F> {
             return Err(Error::Synthesis);
         }
 
-        self.is_success.assign(
-            region,
-            offset,
-            Value::known(F::from(u64::from(call.is_success))),
-        )?;
-        self.callee_address.assign(
-            region,
-            offset,
-            Value::known(call.code_address.unwrap().to_scalar().unwrap()),
-        )?;
-        self.is_root
-            .assign(region, offset, Value::known(F::from(call.is_root as u64)))?;
-        self.call_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_offset)),
-        )?;
-        self.call_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_length)),
-        )?;
-        self.return_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_offset)),
-        )?;
-        self.return_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_length)),
-        )?;
-        self.restore_context
-            .assign(region, offset, block, call, step, 7)
+        self.precompile_return
+            .assign(region, offset, block, call, step)
     }
 }
 
@@ -81,20 +81,150 @@ pub fn gen_restore_context<F: Field>(
     })
 }
 
+/// Number of call context reads done by [`PrecompileCallContext`].
+const N_CALL_CONTEXT_READS: usize = 7;
+
+/// Call context of a precompile call, read at the start of its step.
+#[derive(Clone, Debug)]
+pub struct PrecompileCallContext<F> {
+    pub(crate) is_success: Cell<F>,
+    pub(crate) callee_address: Cell<F>,
+    pub(crate) is_root: Cell<F>,
+    pub(crate) call_data_offset: Cell<F>,
+    pub(crate) call_data_length: Cell<F>,
+    pub(crate) return_data_offset: Cell<F>,
+    pub(crate) return_data_length: Cell<F>,
+}
+
+impl<F: Field> PrecompileCallContext<F> {
+    /// Read the call context and check that the callee address is the one of
+    /// the precompile of the current execution state.
+    /// MUST be called before any other rw of the step.
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let [is_success, callee_address, is_root, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::IsRoot,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|tag| cb.call_context(None, tag));
+
+        cb.precompile_info_lookup(
+            cb.execution_state().as_u64().expr(),
+            callee_address.expr(),
+            cb.execution_state().precompile_base_gas_cost().expr(),
+        );
+
+        Self {
+            is_success,
+            callee_address,
+            is_root,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+        }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        call: &Call,
+    ) -> Result<(), Error> {
+        self.is_success.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(call.code_address.unwrap().to_scalar().unwrap()),
+        )?;
+        self.is_root
+            .assign(region, offset, Value::known(F::from(call.is_root as u64)))?;
+        self.call_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_offset)),
+        )?;
+        self.call_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_length)),
+        )?;
+        self.return_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_offset)),
+        )?;
+        self.return_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_length)),
+        )?;
+        Ok(())
+    }
+}
+
+/// Call context of a precompile call and the return to its caller, shared by
+/// all the precompile gadgets.
+#[derive(Clone, Debug)]
+pub struct PrecompileReturnGadget<F> {
+    pub(crate) call_context: PrecompileCallContext<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> PrecompileReturnGadget<F> {
+    /// Charge `gas_cost` and return `return_data_length` bytes to the caller,
+    /// or end the tx for a root call.
+    /// MUST be called after all rw has completed, see [`gen_restore_context`].
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        call_context: &PrecompileCallContext<F>,
+        gas_cost: Expression<F>,
+        return_data_length: Expression<F>,
+    ) -> Self {
+        let restore_context = gen_restore_context(
+            cb,
+            call_context.is_root.expr(),
+            call_context.is_success.expr(),
+            gas_cost,
+            return_data_length,
+        );
+
+        Self {
+            call_context: call_context.clone(),
+            restore_context,
+        }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.call_context.assign(region, offset, call)?;
+        self.restore_context
+            .assign(region, offset, block, call, step, N_CALL_CONTEXT_READS)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct BasePrecompileGadget<F, const S: ExecutionState> {
     input_bytes_rlc: Cell<F>,
     output_bytes_rlc: Cell<F>,
     return_bytes_rlc: Cell<F>,
 
-    is_success: Cell<F>,
-    callee_address: Cell<F>,
-    is_root: Cell<F>,
-    call_data_offset: Cell<F>,
-    call_data_length: Cell<F>,
-    return_data_offset: Cell<F>,
-    return_data_length: Cell<F>,
-    restore_context: RestoreContextGadget<F>,
+    precompile_return: PrecompileReturnGadget<F>,
     gas_cost: Cell<F>,
 }
 
@@ -110,23 +240,7 @@ impl<F: Field, const S: ExecutionState> ExecutionGadget<F> for BasePrecompileGad
             cb.query_cell_phase2(),
         );
         let gas_cost = cb.query_cell();
-        let [is_success, callee_address, is_root, call_data_offset, call_data_length, return_data_offset, return_data_length] =
-            [
-                CallContextFieldTag::IsSuccess,
-                CallContextFieldTag::CalleeAddress,
-                CallContextFieldTag::IsRoot,
-                CallContextFieldTag::CallDataOffset,
-                CallContextFieldTag::CallDataLength,
-                CallContextFieldTag::ReturnDataOffset,
-                CallContextFieldTag::ReturnDataLength,
-            ]
-            .map(|tag| cb.call_context(None, tag));
-
-        cb.precompile_info_lookup(
-            cb.execution_state().as_u64().expr(),
-            callee_address.expr(),
-            cb.execution_state().precompile_base_gas_cost().expr(),
-        );
+        let call_context = PrecompileCallContext::construct(cb);
 
         let last_callee_return_data_length = match Self::EXECUTION_STATE {
             ExecutionState::PrecompileRipemd160 => 0x20,
@@ -134,16 +248,16 @@ impl<F: Field, const S: ExecutionState> ExecutionGadget<F> for BasePrecompileGad
             _ => unreachable!("{} should not use the base gadget", Self::EXECUTION_STATE),
         };
 
-        let restore_context = gen_restore_context(
+        let return_data_length = select::expr(
+            call_context.is_success.expr(),
+            last_callee_return_data_length.expr(),
+            0x00.expr(),
+        );
+        let precompile_return = PrecompileReturnGadget::construct(
             cb,
-            is_root.expr(),
-            is_success.expr(),
+            &call_context,
             gas_cost.expr(),
-            select::expr(
-                is_success.expr(),
-                last_callee_return_data_length.expr(),
-                0x00.expr(),
-            ), // ReturnDataLength
+            return_data_length,
         );
 
         Self {
@@ -151,14 +265,7 @@ impl<F: Field, const S: ExecutionState> ExecutionGadget<F> for BasePrecompileGad
             output_bytes_rlc,
             return_bytes_rlc,
 
-            is_success,
-            callee_address,
-            is_root,
-            call_data_offset,
-            call_data_length,
-            return_data_offset,
-            return_data_length,
-            restore_context,
+            precompile_return,
             gas_cost,
         }
     }
@@ -199,40 +306,50 @@ impl<F: Field, const S: ExecutionState> ExecutionGadget<F> for BasePrecompileGad
 
         self.gas_cost
             .assign(region, offset, Value::known(F::from(step.gas_cost)))?;
-        self.is_success.assign(
-            region,
-            offset,
-            Value::known(F::from(u64::from(call.is_success))),
-        )?;
-        self.callee_address.assign(
-            region,
-            offset,
-            Value::known(call.code_address.unwrap().to_scalar().unwrap()),
-        )?;
-        self.is_root
-            .assign(region, offset, Value::known(F::from(call.is_root as u64)))?;
-        self.call_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_offset)),
-        )?;
-        self.call_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_length)),
-        )?;
-        self.return_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_offset)),
-        )?;
-        self.return_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_length)),
-        )?;
+        self.precompile_return
+            .assign(region, offset, block, call, step)
+    }
+}
 
-        self.restore_context
-            .assign(region, offset, block, call, step, 7)
+#[cfg(test)]
+mod precompile_return_test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::precompile::PrecompileCalls;
+    use eth_types::Bytecode;
+    use mock::TestContext;
+
+    /// Several precompiles called in a row from the same caller, so that each
+    /// of them has to restore the context left by the previous one.
+    #[test]
+    fn precompile_calls_restore_caller_context() {
+        let mut code = Bytecode::default();
+        // P = (1, 1) is not on the curve, so the first ecAdd fails.
+        code.op_mstore(0x00, 1u64).op_mstore(0x20, 1u64);
+        for (precompile, args_offset, args_size, ret_size) in [
+            (PrecompileCalls::Identity, 0x00u64, 0x20u64, 0x20u64),
+            (PrecompileCalls::Sha256, 0x00, 0x40, 0x20),
+            (PrecompileCalls::Bn128Add, 0x00, 0x80, 0x40),
+            (PrecompileCalls::Identity, 0x00, 0x00, 0x00),
+            // both points at infinity
+            (PrecompileCalls::Bn128Add, 0x100, 0x80, 0x40),
+        ] {
+            code.op_staticcall(
+                0x1_0000u64,
+                precompile.address(),
+                args_offset,
+                args_size,
+                0x200u64,
+                ret_size,
+            )
+            .op_pop()
+            .op_returndatasize()
+            .op_pop();
+        }
+        code.op_stop();
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+        )
+        .run();
     }
 }
@@ -1,566 +1,43 @@
 use crate::util::Field;
-use bus_mapping::precompile::{
-    PrecompileAuxData, PrecompileCalls, MODEXP_INPUT_LIMIT, MODEXP_SIZE_LIMIT,
-};
-use eth_types::{evm_types::GasCost, ToBigEndian, U256};
-use gadgets::util::{self, not, select, Expr};
-use gadgets::ToScalar;
-use halo2_proofs::{
-    circuit::Value,
-    plonk::{Error, Expression},
+use bus_mapping::{
+    circuit_input_builder::BigModExp,
+    precompile::{modexp_size_limit, PrecompileAuxData, MODEXP_INPUT_LIMIT},
 };
+use eth_types::evm_types::GasCost;
+use gadgets::util::{and, not, select, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
 
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BITS_U8, N_BYTES_MEMORY_ADDRESS, N_BYTES_U64, N_BYTES_WORD},
+        param::{N_BYTES_MEMORY_ADDRESS, N_BYTES_U64},
         step::ExecutionState,
         util::{
-            common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-            math_gadget::{
-                BinaryNumberGadget, BitLengthGadget, ByteOrWord, ByteSizeGadget,
-                ConstantDivisionGadget, IsZeroGadget, LtGadget, MinMaxGadget,
-            },
-            padding_gadget::PaddingGadget,
+            math_gadget::{ConstantDivisionGadget, LtGadget, MinMaxGadget},
             rlc, CachedRegion, Cell,
         },
     },
-    table::CallContextFieldTag,
     witness::{Block, Call, ExecStep, Transaction},
 };
 
-#[derive(Clone, Debug)]
-struct RandPowRepresent<F, const BIT_LIMIT: usize> {
-    bits: BinaryNumberGadget<F, BIT_LIMIT>,
-    pow_lookup: Cell<F>,
-    cache_for_degree: Option<Cell<F>>,
-    pow: Expression<F>,
-}
-
-impl<F: Field, const BIT_LIMIT: usize> RandPowRepresent<F, BIT_LIMIT> {
-    const BIT_EXP_MAX_DEGREE: usize = 4;
-
-    /// build randomness r, r**2, ... r**(2**BIT_LIMIT)
-    pub fn base_pows_expr(randomness: Expression<F>) -> [Expression<F>; BIT_LIMIT] {
-        std::iter::successors(Some(randomness), |r| Some(r.clone() * r.clone()))
-            .take(BIT_LIMIT)
-            .collect::<Vec<_>>()
-            .try_into()
-            .expect("same length")
-    }
-
-    /// refer to a binary represent of exponent (like BinaryNumberGadget), can
-    /// link another expression so the expr is linked_val * r ** exponent
-    pub fn configure(
-        cb: &mut EVMConstraintBuilder<F>,
-        _randomness: Expression<F>,
-        exponent: Expression<F>,
-        linked_val: Option<Expression<F>>,
-    ) -> Self {
-        let bits = BinaryNumberGadget::construct(cb, exponent);
-        let pow_lookup = cb.query_cell_phase2();
-        cb.pow_of_rand_lookup(bits.value(), pow_lookup.expr());
-
-        let pow = linked_val.unwrap_or_else(|| 1.expr()) * pow_lookup.expr();
-        // we would cache the pow expression in case degree is too largest
-        let cache_for_degree = if pow.degree() > Self::BIT_EXP_MAX_DEGREE {
-            let cached_cell = cb.query_cell_phase2();
-            cb.require_equal(
-                "pow_assemble cached current expression",
-                cached_cell.expr(),
-                pow.clone(),
-            );
-            Some(cached_cell)
-        } else {
-            None
-        };
-
-        Self {
-            bits,
-            pow_lookup,
-            cache_for_degree,
-            pow,
-        }
-    }
-
-    pub fn expr(&self) -> Expression<F> {
-        self.pow.clone()
-    }
-
-    pub fn assign(
-        &self,
-        region: &mut CachedRegion<'_, '_, F>,
-        offset: usize,
-        exponent: usize,
-        linked_value: Option<Value<F>>,
-    ) -> Result<Value<F>, Error> {
-        assert!(
-            2usize.pow(BIT_LIMIT as u32) > exponent,
-            "exponent ({exponent}) can not exceed bit limit (2**{BIT_LIMIT}-1)"
-        );
-        self.bits.assign(region, offset, exponent)?;
-
-        let pow_of_rand = region
-            .challenges()
-            .keccak_input()
-            .map(|v| v.pow([exponent as u64, 0, 0, 0]));
-        let value_should_assigned =
-            linked_value.unwrap_or_else(|| Value::known(F::one())) * pow_of_rand;
-
-        self.pow_lookup.assign(region, offset, pow_of_rand)?;
-
-        if let Some(cell) = &self.cache_for_degree {
-            cell.assign(region, offset, value_should_assigned)?;
-        }
-
-        Ok(value_should_assigned)
-    }
-}
-
-const SIZE_LIMIT: usize = MODEXP_SIZE_LIMIT;
-const SIZE_REPRESENT_BITS: usize = 6;
-const SIZE_REPRESENT_BYTES: usize = SIZE_LIMIT / 256 + 1;
-const INPUT_LIMIT: usize = 32 * 6;
-const INPUT_REPRESENT_BYTES: usize = MODEXP_INPUT_LIMIT / 256 + 1;
-const INPUT_REPRESENT_BITS: usize = 8;
-
-type Word<F> = [Cell<F>; 32];
-
-fn assign_word<F: Field, const N: usize>(
-    region: &mut CachedRegion<'_, '_, F>,
-    offset: usize,
-    cells: &[Cell<F>; N],
-    bytes: [u8; N],
-) -> Result<(), Error> {
-    for (cell, byte) in cells.iter().zip(bytes) {
-        cell.assign(region, offset, Value::known(F::from(byte as u64)))?;
-    }
-
-    Ok(())
-}
-
-// rlc cells array, in the reversed byte order
-fn rlc_rev<F: Field, const N: usize>(
-    cells: &[Cell<F>; N],
-    randomness: Expression<F>,
-) -> Expression<F> {
-    cells
-        .iter()
-        .map(|cell| cell.expr())
-        .reduce(|acc, value| acc * randomness.clone() + value)
-        .expect("values should not be empty")
-}
-
-// rlc word, in the reversed byte order
-fn rlc_word_rev<F: Field>(cells: &[Cell<F>; 32], randomness: Expression<F>) -> Expression<F> {
-    rlc_rev(cells, randomness)
-}
-
-// calc for big-endian (notice util::expr_from_bytes calc for little-endian)
-fn expr_from_bytes<F: Field, E: Expr<F>>(bytes: &[E]) -> Expression<F> {
-    bytes
-        .iter()
-        .fold(0.expr(), |acc, byte| acc * F::from(256) + byte.expr())
-}
-
-#[derive(Clone, Debug)]
-struct SizeRepresent<F> {
-    len_bytes: Word<F>,
-    expression: Expression<F>,
-    is_rest_field_zero: IsZeroGadget<F>,
-    is_not_exceed_limit: LtGadget<F, SIZE_REPRESENT_BYTES>,
-}
-
-impl<F: Field> SizeRepresent<F> {
-    pub fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
-        let len_bytes = cb.query_bytes();
-        let expression = rlc_word_rev(&len_bytes, cb.challenges().keccak_input());
-        // we calculate at most 31 bytes so it can be fit into a field
-        let len_blank_bytes = len_bytes[..(32 - SIZE_REPRESENT_BYTES)]
-            .iter()
-            .map(Cell::expr)
-            .collect::<Vec<_>>();
-        let is_rest_field_zero = IsZeroGadget::construct(cb, expr_from_bytes(&len_blank_bytes));
-        let len_effect_bytes = len_bytes[(32 - SIZE_REPRESENT_BYTES)..]
-            .iter()
-            .map(Cell::expr)
-            .collect::<Vec<_>>();
-        let is_not_exceed_limit = LtGadget::construct(
-            cb,
-            expr_from_bytes(&len_effect_bytes),
-            (SIZE_LIMIT + 1).expr(),
-        );
-        Self {
-            len_bytes,
-            expression,
-            is_rest_field_zero,
-            is_not_exceed_limit,
-        }
-    }
-
-    /// the rlc of size memory, in reversed byte order
-    pub fn memory_rlc(&self) -> Expression<F> {
-        self.expression.clone()
-    }
-
-    /// the value of size
-    pub fn value(&self) -> Expression<F> {
-        let len_effect_bytes = self.len_bytes[(32 - SIZE_REPRESENT_BYTES)..]
-            .iter()
-            .map(Cell::expr)
-            .collect::<Vec<_>>();
-        expr_from_bytes(&len_effect_bytes)
-    }
-
-    pub fn is_valid(&self) -> Expression<F> {
-        util::and::expr([
-            self.is_rest_field_zero.expr(),
-            self.is_not_exceed_limit.expr(),
-        ])
-    }
-
-    pub fn assign(
-        &self,
-        region: &mut CachedRegion<'_, '_, F>,
-        offset: usize,
-        size: &U256,
-    ) -> Result<(), Error> {
-        let mut bytes = [0u8; 32];
-        size.to_big_endian(&mut bytes);
-
-        assign_word(region, offset, &self.len_bytes, bytes)?;
-
-        let rest_field = U256::from_big_endian(&bytes[..(32 - SIZE_REPRESENT_BYTES)]);
-        let effect_field = U256::from_big_endian(&bytes[(32 - SIZE_REPRESENT_BYTES)..]);
-
-        self.is_rest_field_zero
-            .assign(region, offset, rest_field.to_scalar().unwrap())?;
-        self.is_not_exceed_limit.assign(
-            region,
-            offset,
-            effect_field.to_scalar().unwrap(),
-            F::from((SIZE_LIMIT + 1) as u64),
-        )?;
-        Ok(())
-    }
-}
-
-type RandPow<F> = RandPowRepresent<F, SIZE_REPRESENT_BITS>;
-
-// parse as (valid, len, value: [base, exp, modulus])
-type InputParsedResult = (bool, [U256; 3], [[u8; SIZE_LIMIT]; 3]);
-type OutputParsedResult = (usize, [u8; SIZE_LIMIT]);
-
-#[derive(Clone, Debug)]
-struct ModExpInputs<F> {
-    base_len: SizeRepresent<F>,
-    modulus_len: SizeRepresent<F>,
-    exp_len: SizeRepresent<F>,
-    base_pow: RandPow<F>,
-    base: Word<F>,
-    modulus_pow: RandPow<F>,
-    modulus: Word<F>,
-    exp_pow: RandPow<F>,
-    exp: Word<F>,
-    input_valid: Cell<F>,
-    input_bytes_rlc: Expression<F>,
-    input_len_expected: Expression<F>,
-    pub base_limbs: Limbs<F>,
-    pub exp_limbs: Limbs<F>,
-    pub modulus_limbs: Limbs<F>,
-}
-
-impl<F: Field> ModExpInputs<F> {
-    pub fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
-        let base_len = SizeRepresent::configure(cb);
-        let modulus_len = SizeRepresent::configure(cb);
-        let exp_len = SizeRepresent::configure(cb);
-
-        let r_pow_32 =
-            RandPowRepresent::<_, 6>::base_pows_expr(cb.challenges().keccak_input())[5].clone(); //r**32
-        let r_pow_64 = r_pow_32.clone().square();
-
-        let base = cb.query_bytes();
-        let modulus = cb.query_bytes();
-        let exp = cb.query_bytes();
-
-        let base_limbs = Limbs::configure(cb, &base);
-        let exp_limbs = Limbs::configure(cb, &exp);
-        let modulus_limbs = Limbs::configure(cb, &modulus);
-
-        let input_valid = cb.query_bool();
-        cb.require_equal(
-            "mark input valid by checking 3 lens is valid",
-            input_valid.expr(),
-            util::and::expr([
-                base_len.is_valid(),
-                exp_len.is_valid(),
-                modulus_len.is_valid(),
-            ]),
-        );
-
-        let base_len_expected = util::select::expr(input_valid.expr(), base_len.value(), 0.expr());
-
-        let exp_len_expected = util::select::expr(input_valid.expr(), exp_len.value(), 0.expr());
-
-        let modulus_len_expected =
-            util::select::expr(input_valid.expr(), modulus_len.value(), 0.expr());
-
-        let input_len_expected = 96.expr()
-            + base_len_expected.clone()
-            + exp_len_expected.clone()
-            + modulus_len_expected.clone();
-
-        // we put correct size in each input word if input is valid
-        // else we just put as most as possible bytes (32) into it
-        // so we finally handle the memory in limited sized (32*3)
-        let modulus_pow = RandPow::configure(
-            cb,
-            cb.challenges().keccak_input(),
-            modulus_len_expected,
-            None,
-        );
-
-        // exp_pow = r**(modulus_len + exp_len)
-        let exp_pow = RandPow::configure(
-            cb,
-            cb.challenges().keccak_input(),
-            exp_len_expected,
-            Some(modulus_pow.expr()),
-        );
-
-        // base_pow = r**(modulus_len + exp_len + base_len)
-        let base_pow = RandPow::configure(
-            cb,
-            cb.challenges().keccak_input(),
-            base_len_expected,
-            Some(exp_pow.expr()),
-        );
-
-        let input_bytes_rlc = rlc_word_rev(&modulus, cb.challenges().keccak_input()) //rlc of base
-            + modulus_pow.expr() * rlc_word_rev(&exp, cb.challenges().keccak_input()) //rlc of exp plus r**base_len
-            + exp_pow.expr() * rlc_word_rev(&base, cb.challenges().keccak_input()) //rlc of exp plus r**(base_len + exp_len)
-            + base_pow.expr() * modulus_len.memory_rlc()
-            + base_pow.expr() * r_pow_32 * exp_len.memory_rlc()
-            + base_pow.expr() * r_pow_64 * base_len.memory_rlc();
-
-        // println!("phase 2 cell used {}",
-        //     padding_pow.phase2_cell_cost() + [&modulus_pow, &exp_pow,
-        // &base_pow].iter().map(|pw|pw.phase2_cell_cost()).sum::<usize>() );
-
-        Self {
-            base_len,
-            modulus_len,
-            exp_len,
-            base_pow,
-            base,
-            modulus_pow,
-            modulus,
-            exp_pow,
-            exp,
-            input_valid,
-            input_bytes_rlc,
-            input_len_expected,
-            base_limbs,
-            exp_limbs,
-            modulus_limbs,
-        }
-    }
-
-    pub fn modulus_len(&self) -> Expression<F> {
-        self.modulus_len.value()
-    }
-    pub fn is_valid(&self) -> Expression<F> {
-        self.input_valid.expr()
-    }
-    pub fn len_expected(&self) -> Expression<F> {
-        self.input_len_expected.clone()
-    }
-    pub fn bytes_rlc(&self) -> Expression<F> {
-        self.input_bytes_rlc.clone()
-    }
-
-    pub fn assign(
-        &self,
-        region: &mut CachedRegion<'_, '_, F>,
-        offset: usize,
-        (input_valid, lens, values): InputParsedResult,
-    ) -> Result<(), Error> {
-        self.input_valid.assign(
-            region,
-            offset,
-            Value::known(if input_valid { F::one() } else { F::zero() }),
-        )?;
-
-        for (len, len_represent) in
-            lens.iter()
-                .zip([&self.base_len, &self.exp_len, &self.modulus_len])
-        {
-            len_represent.assign(region, offset, len)?;
-        }
-
-        let mut linked_v = None;
-        for (len, pow) in lens
-            .iter()
-            .zip([&self.base_pow, &self.exp_pow, &self.modulus_pow])
-            .rev()
-        {
-            let assigned = pow.assign(
-                region,
-                offset,
-                if input_valid { len.as_usize() } else { 0 },
-                linked_v,
-            )?;
-
-            linked_v = Some(assigned);
-        }
-
-        for (val_r, input_limbs) in
-            values
-                .iter()
-                .zip([&self.base_limbs, &self.exp_limbs, &self.modulus_limbs])
-        {
-            input_limbs.assign(region, offset, val_r)?;
-        }
-
-        for (&val, input_bytes) in values.iter().zip([&self.base, &self.exp, &self.modulus]) {
-            assign_word(region, offset, input_bytes, val)?;
-        }
-
-        Ok(())
-    }
-}
-
-#[derive(Clone, Debug)]
-struct ModExpOutputs<F> {
-    result: Word<F>,
-    is_result_zero: IsZeroGadget<F>,
-    output_bytes_rlc: Expression<F>,
-    pub result_limbs: Limbs<F>,
-}
-
-impl<F: Field> ModExpOutputs<F> {
-    fn configure(
-        cb: &mut EVMConstraintBuilder<F>,
-        inner_success: Expression<F>,
-        modulus_len: Expression<F>,
-    ) -> Self {
-        let output_len = inner_success * modulus_len;
-        let is_result_zero = cb.annotation("if output len is nil", |cb| {
-            IsZeroGadget::construct(cb, output_len)
-        });
-
-        let result = cb.query_bytes();
-        let result_limbs = Limbs::configure(cb, &result);
-
-        let output_bytes_rlc = util::select::expr(
-            is_result_zero.expr(),
-            0.expr(),
-            rlc_word_rev(&result, cb.challenges().keccak_input()),
-        );
-
-        Self {
-            result,
-            is_result_zero,
-            output_bytes_rlc,
-            result_limbs,
-        }
-    }
-
-    pub fn is_nil(&self) -> Expression<F> {
-        self.is_result_zero.expr()
-    }
-
-    pub fn bytes_rlc(&self) -> Expression<F> {
-        self.output_bytes_rlc.clone()
-    }
-
-    pub fn assign(
-        &self,
-        region: &mut CachedRegion<'_, '_, F>,
-        offset: usize,
-        (output_len, data): OutputParsedResult,
-    ) -> Result<(), Error> {
-        self.is_result_zero
-            .assign(region, offset, F::from(output_len as u64))?;
-        self.result_limbs.assign(region, offset, &data)?;
-        assign_word(region, offset, &self.result, data)?;
-        Ok(())
-    }
-}
-
-#[derive(Clone, Debug)]
-pub(crate) struct Limbs<F> {
-    byte14_split_lo: Cell<F>,
-    byte14_split_hi: Cell<F>,
-    limbs: [Expression<F>; 3],
-}
-
-impl<F: Field> Limbs<F> {
-    pub fn configure(cb: &mut EVMConstraintBuilder<F>, word: &Word<F>) -> Self {
-        let byte14_split_lo = cb.query_byte();
-        let byte14_split_hi = cb.query_byte();
-
-        cb.require_equal(
-            "split 14th byte in word into half",
-            word[MODEXP_SIZE_LIMIT - 14].expr(),
-            byte14_split_lo.expr() + byte14_split_hi.expr(),
-        );
-
-        let inv_16 = Expression::Constant(F::from(16u64).invert().unwrap());
-
-        let limbs = [
-            expr_from_bytes(
-                &std::iter::once(&byte14_split_lo)
-                    .chain(&word[MODEXP_SIZE_LIMIT - 13..])
-                    .collect::<Vec<_>>(),
-            ),
-            expr_from_bytes(
-                &word[MODEXP_SIZE_LIMIT - 27..MODEXP_SIZE_LIMIT - 14]
-                    .iter()
-                    .chain(std::iter::once(&byte14_split_hi))
-                    .collect::<Vec<_>>(),
-            ) * inv_16,
-            expr_from_bytes(&word[..MODEXP_SIZE_LIMIT - 27]),
-        ];
-
-        Self {
-            byte14_split_lo,
-            byte14_split_hi,
-            limbs,
-        }
-    }
-
-    pub fn limbs(&self) -> [Expression<F>; 3] {
-        self.limbs.clone()
-    }
-
-    pub fn assign(
-        &self,
-        region: &mut CachedRegion<'_, '_, F>,
-        offset: usize,
-        big_int: &[u8; MODEXP_SIZE_LIMIT],
-    ) -> Result<(), Error> {
-        let byte14_lo = big_int[MODEXP_SIZE_LIMIT - 14] & 0xf;
-        let byte14_hi = big_int[MODEXP_SIZE_LIMIT - 14] & 0xf0;
+use super::{PrecompileCallContext, PrecompileReturnGadget};
 
-        self.byte14_split_lo
-            .assign(region, offset, Value::known(F::from(byte14_lo as u64)))?;
-        self.byte14_split_hi
-            .assign(region, offset, Value::known(F::from(byte14_hi as u64)))?;
-        Ok(())
-    }
-}
+/// Number of bytes of the operand lengths, and of the values derived from them, in the gas
+/// calculation. The lengths are at most [`MODEXP_BYTES_BOUND`](bus_mapping::precompile).
+const N_BYTES_LEN: usize = 2;
 
+/// Gas cost of a modexp call, see EIP-2565:
+/// `max(200, words(max(Bsize, Msize))^2 * max(adjusted_exp_len, 1) / 3)`.
 #[derive(Clone, Debug)]
-pub(crate) struct ModExpGasCost<F> {
-    max_length: MinMaxGadget<F, 1>,
-    words: ConstantDivisionGadget<F, 1>,
-    exp_is_zero: IsZeroGadget<F>,
-    exp_byte_size: ByteSizeGadget<F>,
-    exp_msb_bit_length: BitLengthGadget<F>,
-    exp_msb: BinaryNumberGadget<F, N_BITS_U8>,
+struct ModExpGasCost<F> {
+    max_length: MinMaxGadget<F, N_BYTES_LEN>,
+    words: ConstantDivisionGadget<F, N_BYTES_LEN>,
+    // max(Esize, 32), for the bytes of the exponent beyond the first 32.
+    exp_len: MinMaxGadget<F, N_BYTES_LEN>,
+    // adjusted_exp_len + 1 = max(bit_len(E), 8 * (Esize - 32) + 1).
+    adjusted_exp_len: MinMaxGadget<F, N_BYTES_LEN>,
+    iteration_count: MinMaxGadget<F, N_BYTES_LEN>,
     calc_gas: ConstantDivisionGadget<F, N_BYTES_U64>,
     dynamic_gas: MinMaxGadget<F, N_BYTES_U64>,
 }
@@ -568,56 +45,30 @@ pub(crate) struct ModExpGasCost<F> {
 impl<F: Field> ModExpGasCost<F> {
     fn construct(
         cb: &mut EVMConstraintBuilder<F>,
-        b_size: &SizeRepresent<F>,
-        exp: &[Cell<F>; MODEXP_SIZE_LIMIT],
-        m_size: &SizeRepresent<F>,
+        input_lens: &[Cell<F>; 3],
+        exp_bit_len: &Cell<F>,
     ) -> Self {
-        let max_length = MinMaxGadget::construct(cb, b_size.value(), m_size.value());
+        let [base_len, exp_len, modulus_len] = input_lens;
+        let max_length = MinMaxGadget::construct(cb, base_len.expr(), modulus_len.expr());
         let words = ConstantDivisionGadget::construct(cb, max_length.max() + 7.expr(), 8);
         let multiplication_complexity = words.quotient() * words.quotient();
-        let exp_is_zero = IsZeroGadget::construct(
+
+        // With Esize <= 32, the adjusted exponent length is max(bit_len(E) - 1, 0). With
+        // Esize > 32, it is 8 * (Esize - 32) + max(bit_len(head of E) - 1, 0), where the bit length
+        // of the whole exponent is bit_len(head of E) + 8 * (Esize - 32) unless its head is 0.
+        let exp_len = MinMaxGadget::construct(cb, exp_len.expr(), 32.expr());
+        let adjusted_exp_len = MinMaxGadget::construct(
             cb,
-            rlc::expr(&exp.clone().map(|c| c.expr()), cb.challenges().evm_word()),
+            exp_bit_len.expr(),
+            (exp_len.max() - 32.expr()) * 8.expr() + 1.expr(),
         );
-
-        let (exp_byte_size, exp_msb, exp_msb_bit_length) =
-            cb.condition(not::expr(exp_is_zero.expr()), |cb| {
-                let exp_byte_size = ByteSizeGadget::construct(
-                    cb,
-                    exp.iter()
-                        .rev()
-                        .map(Expr::expr)
-                        .collect::<Vec<Expression<F>>>()
-                        .try_into()
-                        .unwrap(),
-                );
-                let exp_msb =
-                    BinaryNumberGadget::construct(cb, exp_byte_size.most_significant_value.expr());
-                let exp_msb_bit_length = BitLengthGadget::construct(
-                    cb,
-                    exp_msb
-                        .bits
-                        .iter()
-                        .rev()
-                        .map(Expr::expr)
-                        .collect::<Vec<Expression<F>>>()
-                        .try_into()
-                        .unwrap(),
-                );
-                (exp_byte_size, exp_msb, exp_msb_bit_length)
-            });
-        let exp_bit_length =
-            (exp_byte_size.size() - 1.expr()) * N_BITS_U8.expr() + exp_msb_bit_length.size();
-
-        // We already restrict Esize <= 32. So we can completely ignore the branch concerning
-        // Esize > 32. We only care about whether or not exponent is zero.
-        let iteration_count = select::expr(
-            exp_is_zero.expr(),
-            0.expr(),
-            exp_bit_length.expr() - 1.expr(),
+        let iteration_count =
+            MinMaxGadget::construct(cb, adjusted_exp_len.max() - 1.expr(), 1.expr());
+        let calc_gas = ConstantDivisionGadget::construct(
+            cb,
+            multiplication_complexity * iteration_count.max(),
+            3,
         );
-        let calc_gas =
-            ConstantDivisionGadget::construct(cb, multiplication_complexity * iteration_count, 3);
         let dynamic_gas = MinMaxGadget::construct(
             cb,
             GasCost::PRECOMPILE_MODEXP_MIN.expr(),
@@ -627,10 +78,9 @@ impl<F: Field> ModExpGasCost<F> {
         Self {
             max_length,
             words,
-            exp_is_zero,
-            exp_byte_size,
-            exp_msb_bit_length,
-            exp_msb,
+            exp_len,
+            adjusted_exp_len,
+            iteration_count,
             calc_gas,
             dynamic_gas,
         }
@@ -640,96 +90,65 @@ impl<F: Field> ModExpGasCost<F> {
         &self,
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
-        b_size: &U256,
-        m_size: &U256,
-        exponent: &[u8; MODEXP_SIZE_LIMIT],
+        input_lens: [u64; 3],
+        exp_bit_len: u64,
     ) -> Result<u64, Error> {
-        let mut base_len = [0u8; 32];
-        base_len[(32 - SIZE_REPRESENT_BYTES)..]
-            .copy_from_slice(&b_size.to_be_bytes()[(32 - SIZE_REPRESENT_BYTES)..]);
-        let mut mod_len = [0u8; 32];
-        mod_len[(32 - SIZE_REPRESENT_BYTES)..]
-            .copy_from_slice(&m_size.to_be_bytes()[(32 - SIZE_REPRESENT_BYTES)..]);
-        let b_size = U256::from_big_endian(&base_len);
-        let m_size = U256::from_big_endian(&mod_len);
+        let [base_len, exp_len, modulus_len] = input_lens;
+        let max_length = base_len.max(modulus_len);
+        self.max_length
+            .assign(region, offset, F::from(base_len), F::from(modulus_len))?;
+        self.words.assign(region, offset, (max_length + 7).into())?;
+        let words = (max_length + 7) / 8;
 
-        self.max_length.assign(
-            region,
-            offset,
-            b_size.to_scalar().expect("Bsize is within scalar field"),
-            m_size.to_scalar().expect("Msize is within scalar field"),
-        )?;
-        self.words
-            .assign(region, offset, b_size.max(m_size).as_u128() + 7u128)?;
-        let exp_word = U256::from_big_endian(exponent);
-        self.exp_is_zero.assign_value(
+        self.exp_len
+            .assign(region, offset, F::from(exp_len), F::from(32))?;
+        let exp_len_over = exp_len.max(32) - 32;
+        self.adjusted_exp_len.assign(
             region,
             offset,
-            region
-                .challenges()
-                .evm_word()
-                .map(|r| rlc::value(exponent, r)),
+            F::from(exp_bit_len),
+            F::from(exp_len_over * 8 + 1),
         )?;
-        self.exp_byte_size
-            .assign(region, offset, ByteOrWord::Word(exp_word))?;
-        let exp_byte_size = (exp_word.bits() + 7) / 8;
-        let exp_msb = if exp_byte_size > 0 {
-            exponent[N_BYTES_WORD - exp_byte_size]
-        } else {
-            0
-        };
-        self.exp_msb.assign(region, offset, exp_msb)?;
-        self.exp_msb_bit_length
-            .assign(region, offset, ByteOrWord::Byte(exp_msb))?;
-        let exp_bit_length = exp_word.bits();
-        let max_length = b_size.max(m_size);
-        let words = (max_length + 7) / 8;
-        let multiplication_complexity = words * words;
-        let iteration_count = if exp_word.is_zero() {
-            0
-        } else {
-            exp_bit_length - 1
-        };
-        let numerator = multiplication_complexity * iteration_count;
-        self.calc_gas.assign(region, offset, numerator.as_u128())?;
+        let adjusted_exp_len = exp_bit_len.max(exp_len_over * 8 + 1) - 1;
+        self.iteration_count
+            .assign(region, offset, F::from(adjusted_exp_len), F::one())?;
+        let iteration_count = adjusted_exp_len.max(1);
+
+        let numerator = words * words * iteration_count;
+        self.calc_gas.assign(region, offset, numerator.into())?;
         self.dynamic_gas.assign(
             region,
             offset,
             F::from(GasCost::PRECOMPILE_MODEXP_MIN.0),
-            F::from((numerator / 3).as_u64()),
+            F::from(numerator / 3),
         )?;
-        let gas_cost = std::cmp::max(GasCost::PRECOMPILE_MODEXP_MIN.0, (numerator / 3).as_u64());
 
-        Ok(gas_cost)
+        Ok(GasCost::PRECOMPILE_MODEXP_MIN.0.max(numerator / 3))
     }
 }
 
+/// Gadget for the MODEXP precompile. The parsing of the input and the exponentiation are proven by
+/// the modexp circuit: the step looks up its row in the modexp table by the RLC of the input bytes,
+/// which gives the operand lengths, whether they are within the operand size limit of the circuit,
+/// the bit length of the exponent for the gas cost, and the RLC of the result.
 #[derive(Clone, Debug)]
 pub struct ModExpGadget<F> {
     input_bytes_rlc: Cell<F>,
     output_bytes_rlc: Cell<F>,
     return_bytes_rlc: Cell<F>,
 
-    pad_right: LtGadget<F, N_BYTES_MEMORY_ADDRESS>,
-    padding: PaddingGadget<F>,
+    // the call op copies min(call_data_length, MODEXP_INPUT_LIMIT) bytes of input.
+    input_len: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    is_valid: Cell<F>,
+    // [base, exp, modulus] lengths, 0 if not valid.
+    input_lens: [Cell<F>; 3],
+    exp_bit_len: Cell<F>,
+    modexp_output_rlc: Cell<F>,
 
-    is_success: Cell<F>,
-    callee_address: Cell<F>,
-    is_root: Cell<F>,
-    call_data_offset: Cell<F>,
-    call_data_length: Cell<F>,
-    return_data_offset: Cell<F>,
-    return_data_length: Cell<F>,
-    restore_context_gadget: RestoreContextGadget<F>,
-
-    input: ModExpInputs<F>,
-    padding_zero: RandPowRepresent<F, INPUT_REPRESENT_BITS>,
-    output: ModExpOutputs<F>,
-
-    input_bytes_acc: Cell<F>,
-    is_gas_insufficient: LtGadget<F, N_BYTES_U64>,
     gas_cost_gadget: ModExpGasCost<F>,
-    garbage_bytes_holder: [Cell<F>; INPUT_LIMIT - 96],
+    is_gas_insufficient: LtGadget<F, N_BYTES_U64>,
+
+    precompile_return: PrecompileReturnGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
@@ -743,119 +162,56 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
             cb.query_cell_phase2(),
             cb.query_cell_phase2(),
         );
-        // we 'copy' the acc_bytes cell inside call_op step, so it must be the first query cells
-        let input_bytes_acc = cb.query_cell_phase2();
+        let modexp_output_rlc = cb.query_cell_phase2();
+        let is_valid = cb.query_bool();
+        let input_lens = [cb.query_cell(), cb.query_cell(), cb.query_cell()];
+        let exp_bit_len = cb.query_cell();
 
-        let [is_success, callee_address, is_root, call_data_offset, call_data_length, return_data_offset, return_data_length] =
-            [
-                CallContextFieldTag::IsSuccess,
-                CallContextFieldTag::CalleeAddress,
-                CallContextFieldTag::IsRoot,
-                CallContextFieldTag::CallDataOffset,
-                CallContextFieldTag::CallDataLength,
-                CallContextFieldTag::ReturnDataOffset,
-                CallContextFieldTag::ReturnDataLength,
-            ]
-            .map(|tag| cb.call_context(None, tag));
+        let call_context = PrecompileCallContext::construct(cb);
 
-        cb.precompile_info_lookup(
-            cb.execution_state().as_u64().expr(),
-            callee_address.expr(),
-            cb.execution_state().precompile_base_gas_cost().expr(),
-        );
-
-        let input = ModExpInputs::configure(cb);
-        let padding_zero = RandPowRepresent::configure(
+        let input_len = MinMaxGadget::construct(
             cb,
-            cb.challenges().keccak_input(),
-            MODEXP_INPUT_LIMIT.expr() - input.len_expected(),
-            None,
+            call_context.call_data_length.expr(),
+            MODEXP_INPUT_LIMIT.expr(),
+        );
+        cb.modexp_table_lookup(
+            input_bytes_rlc.expr(),
+            input_len.min(),
+            is_valid.expr(),
+            input_lens.clone().map(|len| len.expr()),
+            exp_bit_len.expr(),
+            modexp_output_rlc.expr(),
         );
 
-        let gas_cost_gadget =
-            ModExpGasCost::construct(cb, &input.base_len, &input.exp, &input.modulus_len);
+        let gas_cost_gadget = ModExpGasCost::construct(cb, &input_lens, &exp_bit_len);
         let is_gas_insufficient = LtGadget::construct(
             cb,
             cb.curr.state.gas_left.expr(),
             gas_cost_gadget.dynamic_gas.max(),
         );
-
-        let call_success =
-            util::and::expr([input.is_valid(), not::expr(is_gas_insufficient.expr())]);
-
         cb.require_equal(
             "call success if valid input and enough gas",
-            is_success.expr(),
-            call_success.clone(),
-        );
-
-        let output = ModExpOutputs::configure(
-            cb,
-            //FIXME: there may be still some edge cases lead to nil output (even modulus_len is
-            // not 0)
-            call_success,
-            input.modulus_len(),
-        );
-
-        cb.condition(util::not::expr(output.is_nil()), |cb| {
-            cb.modexp_table_lookup(
-                input.base_limbs.limbs(),
-                input.exp_limbs.limbs(),
-                input.modulus_limbs.limbs(),
-                output.result_limbs.limbs(),
-            );
-        });
-
-        let garbage_bytes_holder = cb.query_bytes();
-
-        cb.require_equal(
-            "input acc bytes with padding must equal",
-            input_bytes_acc.expr(),
-            padding_zero.expr() * input.bytes_rlc()
-                + rlc_rev(&garbage_bytes_holder, cb.challenges().keccak_input()),
+            call_context.is_success.expr(),
+            and::expr([is_valid.expr(), not::expr(is_gas_insufficient.expr())]),
         );
 
         cb.require_equal(
-            "output acc bytes must equal",
+            "output bytes (RLC) = modexp result if call success",
             output_bytes_rlc.expr(),
-            output.bytes_rlc(),
+            call_context.is_success.expr() * modexp_output_rlc.expr(),
         );
 
+        // all gas sent to this call will be consumed if `call_context.is_success == false`.
         let gas_cost = select::expr(
-            is_success.expr(),
+            call_context.is_success.expr(),
             gas_cost_gadget.dynamic_gas.max(),
             cb.curr.state.gas_left.expr(),
         );
-
-        let required_input_len = MODEXP_INPUT_LIMIT.expr();
-        let pad_right = LtGadget::construct(cb, call_data_length.expr(), required_input_len.expr());
-        let padding = cb.condition(pad_right.expr(), |cb| {
-            PaddingGadget::construct(
-                cb,
-                input_bytes_rlc.expr(),
-                call_data_length.expr(),
-                required_input_len,
-            )
-        });
-        cb.condition(not::expr(pad_right.expr()), |cb| {
-            cb.require_equal(
-                "no padding implies padded bytes == input bytes",
-                padding.padded_rlc(),
-                input_bytes_rlc.expr(),
-            );
-        });
-        cb.require_equal(
-            "copy padded input bytes",
-            padding.padded_rlc(),
-            input_bytes_acc.expr(),
-        );
-
-        let restore_context_gadget = super::gen_restore_context(
+        let precompile_return = PrecompileReturnGadget::construct(
             cb,
-            is_root.expr(),
-            is_success.expr(),
-            gas_cost.expr(),
-            select::expr(is_success.expr(), input.modulus_len(), 0.expr()),
+            &call_context,
+            gas_cost,
+            call_context.is_success.expr() * input_lens[2].expr(),
         );
 
         Self {
@@ -863,24 +219,16 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
             output_bytes_rlc,
             return_bytes_rlc,
 
-            pad_right,
-            padding,
+            input_len,
+            is_valid,
+            input_lens,
+            exp_bit_len,
+            modexp_output_rlc,
 
-            is_success,
-            callee_address,
-            is_root,
-            call_data_offset,
-            call_data_length,
-            return_data_offset,
-            return_data_length,
-            restore_context_gadget,
-            input,
-            padding_zero,
-            output,
-            input_bytes_acc,
-            is_gas_insufficient,
             gas_cost_gadget,
-            garbage_bytes_holder,
+            is_gas_insufficient,
+
+            precompile_return,
         }
     }
 
@@ -894,137 +242,58 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
         step: &ExecStep,
     ) -> Result<(), Error> {
         if let Some(PrecompileAuxData::Modexp(data)) = &step.aux_data {
-            self.input
-                .assign(region, offset, (data.valid, data.input_lens, data.inputs))?;
-
-            let input_expected_len = 96
-                + if data.valid {
-                    data.input_lens.iter().map(U256::as_usize).sum::<usize>()
-                } else {
-                    0
-                };
-
-            let garbage_bytes = if call.call_data_length as usize > input_expected_len {
-                let mut bts = vec![0; input_expected_len - 96]; //front prefix zero
-                bts.append(&mut Vec::from(&data.input_bytes[input_expected_len..]));
-                bts.resize(96, 0); //padding zero
-                bts
-            } else {
-                Vec::from([0u8; 96])
-            };
-
-            self.padding_zero
-                .assign(region, offset, INPUT_LIMIT - input_expected_len, None)?;
+            // the row of the call in the modexp table.
+            let op = BigModExp::new(
+                data.input_bytes.clone(),
+                modexp_size_limit(block.circuits_params.max_modexp_bytes),
+            );
 
-            for (cell, bt) in self.garbage_bytes_holder.iter().zip(garbage_bytes) {
-                cell.assign(region, offset, Value::known(F::from(bt as u64)))?;
+            let keccak_rand = region.challenges().keccak_input();
+            for (col, bytes) in [
+                (&self.input_bytes_rlc, &data.input_bytes),
+                (&self.output_bytes_rlc, &data.output_bytes),
+                (&self.return_bytes_rlc, &data.return_bytes),
+                (&self.modexp_output_rlc, &op.result),
+            ] {
+                col.assign(
+                    region,
+                    offset,
+                    keccak_rand.map(|r| rlc::value(bytes.iter().rev(), r)),
+                )?;
             }
 
-            self.output
-                .assign(region, offset, (data.output_len, data.output))?;
-
-            let input_rlc = region
-                .challenges()
-                .keccak_input()
-                .map(|randomness| rlc::value(data.input_bytes.iter().rev(), randomness));
-
-            self.input_bytes_rlc.assign(region, offset, input_rlc)?;
-            self.return_bytes_rlc.assign(
+            self.input_len.assign(
                 region,
                 offset,
-                region
-                    .challenges()
-                    .keccak_input()
-                    .map(|r| rlc::value(data.return_bytes.iter().rev(), r)),
+                F::from(call.call_data_length),
+                F::from(MODEXP_INPUT_LIMIT as u64),
             )?;
+            self.is_valid
+                .assign(region, offset, Value::known(F::from(op.valid as u64)))?;
+            let input_lens = op.lens().map(|len| len as u64);
+            for (cell, len) in self.input_lens.iter().zip(input_lens) {
+                cell.assign(region, offset, Value::known(F::from(len)))?;
+            }
+            let exp_bit_len = op.exp_bit_len() as u64;
+            self.exp_bit_len
+                .assign(region, offset, Value::known(F::from(exp_bit_len)))?;
 
-            // if the input to modexp has more than 192 bytes, then we only keep the first 192 bytes
-            // and discard the remaining bytes
-            let input_len_limit = INPUT_LIMIT as u64;
-            let n_padded_zeros = if call.call_data_length > input_len_limit {
-                0
-            } else {
-                input_len_limit - call.call_data_length
-            };
-            let n_padded_zeroes_pow = region
-                .challenges()
-                .keccak_input()
-                .map(|r| r.pow([n_padded_zeros, 0, 0, 0]));
-
-            let output_rlc = region
-                .challenges()
-                .keccak_input()
-                .map(|randomness| rlc::value(data.output_bytes.iter().rev(), randomness));
-
-            self.input_bytes_acc
-                .assign(region, offset, n_padded_zeroes_pow * input_rlc)?;
-            self.output_bytes_rlc.assign(region, offset, output_rlc)?;
-
-            let required_gas_cost = self.gas_cost_gadget.assign(
-                region,
-                offset,
-                &data.input_lens[0],
-                &data.input_lens[2],
-                &data.inputs[1],
-            )?;
+            let gas_cost = self
+                .gas_cost_gadget
+                .assign(region, offset, input_lens, exp_bit_len)?;
             self.is_gas_insufficient.assign(
                 region,
                 offset,
                 F::from(step.gas_left),
-                F::from(required_gas_cost),
-            )?;
-            self.pad_right
-                .assign(region, offset, call.call_data_length.into(), 192.into())?;
-            self.padding.assign(
-                region,
-                offset,
-                PrecompileCalls::Modexp,
-                region
-                    .challenges()
-                    .keccak_input()
-                    .map(|r| rlc::value(data.input_bytes.iter().rev(), r)),
-                call.call_data_length,
-                region.challenges().keccak_input(),
+                F::from(gas_cost),
             )?;
         } else {
             log::error!("unexpected aux_data {:?} for modexp", step.aux_data);
             return Err(Error::Synthesis);
         }
 
-        self.is_success.assign(
-            region,
-            offset,
-            Value::known(F::from(u64::from(call.is_success))),
-        )?;
-        self.callee_address.assign(
-            region,
-            offset,
-            Value::known(call.code_address.unwrap().to_scalar().unwrap()),
-        )?;
-        self.is_root
-            .assign(region, offset, Value::known(F::from(call.is_root as u64)))?;
-        self.call_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_offset)),
-        )?;
-        self.call_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_length)),
-        )?;
-        self.return_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_offset)),
-        )?;
-        self.return_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_length)),
-        )?;
-        self.restore_context_gadget
-            .assign(region, offset, block, call, step, 7)?;
+        self.precompile_return
+            .assign(region, offset, block, call, step)?;
 
         Ok(())
     }
@@ -1038,71 +307,12 @@ mod test {
         precompile::PrecompileCalls,
     };
     use eth_types::{bytecode, word, ToWord};
-    use ethers_core::k256::elliptic_curve::PrimeField;
     use itertools::Itertools;
     use mock::TestContext;
     use std::sync::LazyLock;
 
     use crate::test_util::CircuitTestBuilder;
 
-    #[test]
-    fn test_limbs() {
-        use crate::table::ModExpTable;
-        use halo2_proofs::halo2curves::bn256::Fr;
-        use misc_precompiled_circuit::circuits::modexp::Number;
-        use num_bigint::BigUint;
-
-        // simply take an hash for test
-        let bi = BigUint::parse_bytes(
-            b"fcb51a0695d8f838b1ee009b3fbf66bda078cd64590202a864a8f3e8c4315c47",
-            16,
-        )
-        .unwrap();
-        let n = Number::<Fr>::from_bn(&bi);
-        let w = word!("0xfcb51a0695d8f838b1ee009b3fbf66bda078cd64590202a864a8f3e8c4315c47");
-        let mut bytes = [0u8; 32];
-        w.to_big_endian(&mut bytes);
-        assert_eq!(BigUint::from_bytes_be(&bytes), bi);
-
-        let byte14_lo = bytes[MODEXP_SIZE_LIMIT - 14] & 0xf;
-        let byte14_hi = bytes[MODEXP_SIZE_LIMIT - 14] & 0xf0;
-
-        let limb0: Fr = U256::from_big_endian(
-            &(std::iter::once(byte14_lo))
-                .chain(bytes[MODEXP_SIZE_LIMIT - 13..].iter().copied())
-                .collect::<Vec<_>>(),
-        )
-        .to_scalar()
-        .unwrap();
-
-        let limb1: Fr = U256::from_big_endian(
-            &bytes[MODEXP_SIZE_LIMIT - 27..MODEXP_SIZE_LIMIT - 14]
-                .iter()
-                .copied()
-                .chain(std::iter::once(byte14_hi))
-                .collect::<Vec<_>>(),
-        )
-        .to_scalar()
-        .unwrap();
-
-        let limb2: Fr = U256::from_big_endian(&bytes[..MODEXP_SIZE_LIMIT - 27])
-            .to_scalar()
-            .unwrap();
-
-        assert_eq!(limb0, n.limbs[0].value);
-        assert_eq!(limb1, n.limbs[1].value * Fr::from(16_u64));
-        assert_eq!(limb2, n.limbs[2].value);
-
-        let nt: Fr = ModExpTable::native_u256(&w);
-
-        let table_split = ModExpTable::split_u256_108bit_limbs(&w);
-        assert_eq!(Fr::from_u128(table_split[0]), n.limbs[0].value);
-        assert_eq!(Fr::from_u128(table_split[1]), n.limbs[1].value);
-        assert_eq!(Fr::from_u128(table_split[2]), n.limbs[2].value);
-        assert_eq!(nt, n.limbs[3].value);
-        //Limb::new(None, value)
-    }
-
     static TEST_VECTOR: LazyLock<Vec<PrecompileCallArgs>> = LazyLock::new(|| {
         vec![
             PrecompileCallArgs {
@@ -1626,4 +836,43 @@ mod test {
             .run();
         }
     }
+
+    // without the scroll size limit, the call succeeds and cannot be proven
+    #[cfg(not(feature = "scroll"))]
+    #[test]
+    fn precompile_modexp_test_oversized() {
+        use bus_mapping::mock::BlockData;
+        use eth_types::geth_types::GethData;
+
+        let bytecode = TEST_INVALID_VECTOR[0].with_call_op(OpcodeId::STATICCALL);
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode)
+            .unwrap()
+            .into();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let err = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap_err();
+        assert!(
+            matches!(err, bus_mapping::Error::UnsupportedModExpSize { .. }),
+            "{err:?}"
+        );
+    }
+
+    // the same call is proven by a modexp circuit configured for longer operands
+    #[cfg(not(feature = "scroll"))]
+    #[test]
+    fn precompile_modexp_test_max_modexp_bytes() {
+        use bus_mapping::circuit_input_builder::CircuitsParams;
+
+        let bytecode = TEST_INVALID_VECTOR[0].with_call_op(OpcodeId::STATICCALL);
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .params(CircuitsParams {
+            max_modexp_bytes: 64,
+            ..Default::default()
+        })
+        .run();
+    }
 }
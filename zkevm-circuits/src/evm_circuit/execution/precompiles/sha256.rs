@@ -2,8 +2,7 @@ use crate::util::Field;
 use bus_mapping::precompile::PrecompileAuxData;
 use eth_types::evm_types::GasCost;
 use gadgets::util::{select, Expr};
-use gadgets::ToScalar;
-use halo2_proofs::{circuit::Value, plonk::Error};
+use halo2_proofs::plonk::Error;
 
 use crate::{
     evm_circuit::{
@@ -11,14 +10,15 @@ use crate::{
         param::{N_BYTES_MEMORY_WORD_SIZE, N_BYTES_WORD},
         step::ExecutionState,
         util::{
-            common_gadget::RestoreContextGadget, constraint_builder::EVMConstraintBuilder,
-            math_gadget::ConstantDivisionGadget, rlc, CachedRegion, Cell,
+            constraint_builder::EVMConstraintBuilder, math_gadget::ConstantDivisionGadget, rlc,
+            CachedRegion, Cell,
         },
     },
-    table::CallContextFieldTag,
     witness::{Block, Call, ExecStep, Transaction},
 };
 
+use super::{PrecompileCallContext, PrecompileReturnGadget};
+
 #[derive(Clone, Debug)]
 pub struct SHA256Gadget<F> {
     input_bytes_rlc: Cell<F>,
@@ -26,14 +26,7 @@ pub struct SHA256Gadget<F> {
     return_bytes_rlc: Cell<F>,
 
     input_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
-    is_success: Cell<F>,
-    callee_address: Cell<F>,
-    is_root: Cell<F>,
-    call_data_offset: Cell<F>,
-    call_data_length: Cell<F>,
-    return_data_offset: Cell<F>,
-    return_data_length: Cell<F>,
-    restore_context: RestoreContextGadget<F>,
+    precompile_return: PrecompileReturnGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for SHA256Gadget<F> {
@@ -47,52 +40,35 @@ impl<F: Field> ExecutionGadget<F> for SHA256Gadget<F> {
             cb.query_cell_phase2(),
             cb.query_cell_phase2(),
         );
-        let [is_success, callee_address, is_root, call_data_offset, call_data_length, return_data_offset, return_data_length] =
-            [
-                CallContextFieldTag::IsSuccess,
-                CallContextFieldTag::CalleeAddress,
-                CallContextFieldTag::IsRoot,
-                CallContextFieldTag::CallDataOffset,
-                CallContextFieldTag::CallDataLength,
-                CallContextFieldTag::ReturnDataOffset,
-                CallContextFieldTag::ReturnDataLength,
-            ]
-            .map(|tag| cb.call_context(None, tag));
+        let call_context = PrecompileCallContext::construct(cb);
 
         let input_word_size = ConstantDivisionGadget::construct(
             cb,
-            call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
+            call_context.call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
             N_BYTES_WORD as u64,
         );
 
         let gas_cost = select::expr(
-            is_success.expr(),
+            call_context.is_success.expr(),
             GasCost::PRECOMPILE_SHA256_BASE.expr()
                 + input_word_size.quotient() * GasCost::PRECOMPILE_SHA256_PER_WORD.expr(),
             cb.curr.state.gas_left.expr(),
         );
 
-        cb.precompile_info_lookup(
-            cb.execution_state().as_u64().expr(),
-            callee_address.expr(),
-            cb.execution_state().precompile_base_gas_cost().expr(),
-        );
-
         // sha256 verify lookup
-        cb.condition(is_success.expr(), |cb| {
+        cb.condition(call_context.is_success.expr(), |cb| {
             cb.sha256_table_lookup(
                 input_bytes_rlc.expr(),
-                call_data_length.expr(),
+                call_context.call_data_length.expr(),
                 output_bytes_rlc.expr(),
             );
         });
 
-        let restore_context = super::gen_restore_context(
+        let precompile_return = PrecompileReturnGadget::construct(
             cb,
-            is_root.expr(),
-            is_success.expr(),
+            &call_context,
             gas_cost.expr(),
-            select::expr(is_success.expr(), 0x20.expr(), 0x00.expr()), // ReturnDataLength
+            select::expr(call_context.is_success.expr(), 0x20.expr(), 0x00.expr()), // ReturnDataLength
         );
 
         Self {
@@ -101,14 +77,7 @@ impl<F: Field> ExecutionGadget<F> for SHA256Gadget<F> {
             return_bytes_rlc,
 
             input_word_size,
-            is_success,
-            callee_address,
-            is_root,
-            call_data_offset,
-            call_data_length,
-            return_data_offset,
-            return_data_length,
-            restore_context,
+            precompile_return,
         }
     }
 
@@ -170,40 +139,8 @@ impl<F: Field> ExecutionGadget<F> for SHA256Gadget<F> {
             offset,
             (call.call_data_length + (N_BYTES_WORD as u64) - 1).into(),
         )?;
-        self.is_success.assign(
-            region,
-            offset,
-            Value::known(F::from(u64::from(call.is_success))),
-        )?;
-        self.callee_address.assign(
-            region,
-            offset,
-            Value::known(call.code_address.unwrap().to_scalar().unwrap()),
-        )?;
-        self.is_root
-            .assign(region, offset, Value::known(F::from(call.is_root as u64)))?;
-        self.call_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_offset)),
-        )?;
-        self.call_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_length)),
-        )?;
-        self.return_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_offset)),
-        )?;
-        self.return_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_length)),
-        )?;
-        self.restore_context
-            .assign(region, offset, block, call, step, 7)
+        self.precompile_return
+            .assign(region, offset, block, call, step)
     }
 }
 
@@ -291,7 +291,8 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
                     cb.next.state.call_id.expr(),
                     CopyDataType::Memory.expr(),
                     range.offset(),
-                    range.end_offset(),
+                    // The source ends with the copy, as it is never padded.
+                    range.offset() + copy_length.min(),
                     return_data_offset.expr(),
                     copy_length.min(),
                     0.expr(),
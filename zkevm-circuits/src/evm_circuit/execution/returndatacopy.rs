@@ -116,14 +116,16 @@ impl<F: Field> ExecutionGadget<F> for ReturnDataCopyGadget<F> {
 
         let copy_rwc_inc = cb.query_cell();
         cb.condition(dst_memory_addr.has_length(), |cb| {
+            let src_addr = return_data_offset.expr()
+                + from_bytes::expr(&check_overflow_gadget.data_offset().cells[..N_BYTES_U64]);
             cb.copy_table_lookup(
                 last_callee_id.expr(),
                 CopyDataType::Memory.expr(),
                 cb.curr.state.call_id.expr(),
                 CopyDataType::Memory.expr(),
-                return_data_offset.expr()
-                    + from_bytes::expr(&check_overflow_gadget.data_offset().cells[..N_BYTES_U64]),
-                return_data_offset.expr() + return_data_size.expr(),
+                src_addr.expr(),
+                // The copy is within the return data, so the source ends with it.
+                src_addr + dst_memory_addr.length(),
                 dst_memory_addr.offset(),
                 dst_memory_addr.length(),
                 0.expr(), // for RETURNDATACOPY rlc_acc is 0
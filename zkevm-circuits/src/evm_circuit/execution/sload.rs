@@ -3,7 +3,7 @@ use crate::{
         execution::ExecutionGadget,
         step::ExecutionState,
         util::{
-            common_gadget::{SameContextGadget, SloadGasGadget},
+            common_gadget::{SameContextGadget, SloadGasGadget, StorageSlotAccessGadget},
             constraint_builder::{
                 EVMConstraintBuilder, ReversionInfo, StepStateTransition, Transition::Delta,
             },
@@ -26,7 +26,7 @@ pub(crate) struct SloadGadget<F> {
     phase2_key: Cell<F>,
     phase2_value: Cell<F>,
     phase2_committed_value: Cell<F>,
-    is_warm: Cell<F>,
+    slot_access: StorageSlotAccessGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for SloadGadget<F> {
@@ -57,23 +57,15 @@ impl<F: Field> ExecutionGadget<F> for SloadGadget<F> {
 
         cb.stack_push(phase2_value.expr());
 
-        let is_warm = cb.query_bool();
-        cb.account_storage_access_list_read(
+        let slot_access = StorageSlotAccessGadget::construct(
+            cb,
             tx_id.expr(),
             callee_address.expr(),
             phase2_key.expr(),
-            is_warm.expr(),
-        );
-        cb.account_storage_access_list_write(
-            tx_id.expr(),
-            callee_address.expr(),
-            phase2_key.expr(),
-            true.expr(),
-            is_warm.expr(),
             Some(&mut reversion_info),
         );
 
-        let gas_cost = SloadGasGadget::construct(cb, is_warm.expr()).expr();
+        let gas_cost = SloadGasGadget::construct(cb, slot_access.is_warm().expr()).expr();
         let step_state_transition = StepStateTransition {
             rw_counter: Delta(9.expr()),
             program_counter: Delta(1.expr()),
@@ -91,7 +83,7 @@ impl<F: Field> ExecutionGadget<F> for SloadGadget<F> {
             phase2_key,
             phase2_value,
             phase2_committed_value,
-            is_warm,
+            slot_access,
         }
     }
 
@@ -136,9 +128,8 @@ impl<F: Field> ExecutionGadget<F> for SloadGadget<F> {
         self.phase2_committed_value
             .assign(region, offset, region.word_rlc(committed_value))?;
 
-        let (_, is_warm) = block.rws[step.rw_indices[7]].tx_access_list_value_pair();
-        self.is_warm
-            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+        self.slot_access
+            .assign(region, offset, &block.rws[step.rw_indices[7]])?;
 
         Ok(())
     }
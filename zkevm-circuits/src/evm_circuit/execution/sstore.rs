@@ -6,6 +6,7 @@ use crate::{
         util::{
             common_gadget::{
                 cal_sstore_gas_cost_for_assignment, SameContextGadget, SstoreGasGadget,
+                StorageSlotAccessGadget,
             },
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
@@ -39,7 +40,7 @@ pub(crate) struct SstoreGadget<F> {
     phase2_value: Cell<F>,
     phase2_value_prev: Cell<F>,
     phase2_original_value: Cell<F>,
-    is_warm: Cell<F>,
+    slot_access: StorageSlotAccessGadget<F>,
     tx_refund_prev: Cell<F>,
     // Constrain for SSTORE reentrancy sentry.
     sufficient_gas_sentry: LtGadget<F, N_BYTES_GAS>,
@@ -84,19 +85,11 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
             Some(&mut reversion_info),
         );
 
-        let is_warm = cb.query_bool();
-        cb.account_storage_access_list_read(
-            tx_id.expr(),
-            callee_address.expr(),
-            phase2_key.expr(),
-            is_warm.expr(),
-        );
-        cb.account_storage_access_list_write(
+        let slot_access = StorageSlotAccessGadget::construct(
+            cb,
             tx_id.expr(),
             callee_address.expr(),
             phase2_key.expr(),
-            true.expr(),
-            is_warm.expr(),
             Some(&mut reversion_info),
         );
 
@@ -117,7 +110,7 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
             phase2_value.clone(),
             phase2_value_prev.clone(),
             phase2_original_value.clone(),
-            is_warm.clone(),
+            slot_access.is_warm(),
         );
 
         let tx_refund_prev = cb.query_cell();
@@ -155,7 +148,7 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
             phase2_value,
             phase2_value_prev,
             phase2_original_value,
-            is_warm,
+            slot_access,
             tx_refund_prev,
             sufficient_gas_sentry,
             gas_cost,
@@ -208,9 +201,9 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
         self.phase2_original_value
             .assign(region, offset, region.word_rlc(original_value))?;
 
-        let (_, is_warm) = block.rws[step.rw_indices[8]].tx_access_list_value_pair();
-        self.is_warm
-            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+        let is_warm = self
+            .slot_access
+            .assign(region, offset, &block.rws[step.rw_indices[8]])?;
 
         let (tx_refund, tx_refund_prev) = block.rws[step.rw_indices[10]].tx_refund_value_pair();
         self.tx_refund_prev
@@ -14,6 +14,7 @@ use halo2_proofs::{
     circuit::Value,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression},
 };
+use serde::{Deserialize, Serialize};
 use std::{fmt::Display, iter, marker::ConstParamTy};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
@@ -35,11 +36,15 @@ impl From<PrecompileCalls> for ExecutionState {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(ConstParamTy, Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
+#[derive(
+    ConstParamTy, Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize,
+)]
 pub enum ExecutionState {
     // Internal state
     BeginTx,
     EndTx,
+    BeginChunk,
+    EndChunk,
     EndInnerBlock,
     EndBlock,
     Padding,
@@ -83,6 +88,10 @@ pub enum ExecutionState {
     BLOCKCTXU256, // BASEFEE, DIFFICULTY (for non-scroll)
     #[cfg(feature = "scroll")]
     DIFFICULTY, // DIFFICULTY
+    #[cfg(not(feature = "scroll"))]
+    BLOBHASH,
+    #[cfg(not(feature = "scroll"))]
+    BLOBBASEFEE,
     CHAINID,
     SELFBALANCE,
     POP,
@@ -96,7 +105,9 @@ pub enum ExecutionState {
     MSIZE,
     GAS,
     JUMPDEST,
+    #[cfg(feature = "cancun")]
     TLOAD,
+    #[cfg(feature = "cancun")]
     TSTORE,
     PUSH, // PUSH0, PUSH1, PUSH2, ..., PUSH32
     DUP,  // DUP1, DUP2, ..., DUP16
@@ -284,6 +295,10 @@ impl ExecutionState {
             }
             #[cfg(feature = "scroll")]
             Self::DIFFICULTY => vec![OpcodeId::DIFFICULTY],
+            #[cfg(not(feature = "scroll"))]
+            Self::BLOBHASH => vec![OpcodeId::BLOBHASH],
+            #[cfg(not(feature = "scroll"))]
+            Self::BLOBBASEFEE => vec![OpcodeId::BLOBBASEFEE],
             Self::CHAINID => vec![OpcodeId::CHAINID],
             Self::SELFBALANCE => vec![OpcodeId::SELFBALANCE],
             Self::POP => vec![OpcodeId::POP],
@@ -299,7 +314,9 @@ impl ExecutionState {
             Self::MSIZE => vec![OpcodeId::MSIZE],
             Self::GAS => vec![OpcodeId::GAS],
             Self::JUMPDEST => vec![OpcodeId::JUMPDEST],
+            #[cfg(feature = "cancun")]
             Self::TLOAD => vec![OpcodeId::TLOAD],
+            #[cfg(feature = "cancun")]
             Self::TSTORE => vec![OpcodeId::TSTORE],
             Self::PUSH => vec![
                 OpcodeId::PUSH0,
@@ -347,10 +347,12 @@ pub(crate) enum Lookup<F> {
         is_valid: Expression<F>,
     },
     ModExpTable {
-        base_limbs: [Expression<F>; 3],
-        exp_limbs: [Expression<F>; 3],
-        modulus_limbs: [Expression<F>; 3],
-        result_limbs: [Expression<F>; 3],
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        is_valid: Expression<F>,
+        input_lens: [Expression<F>; 3],
+        exp_bit_len: Expression<F>,
+        output_rlc: Expression<F>,
     },
     EccTable {
         op_type: Expression<F>,
@@ -539,24 +541,22 @@ impl<F: Field> Lookup<F> {
                 is_valid.clone(),
             ],
             Self::ModExpTable {
-                base_limbs,
-                exp_limbs,
-                modulus_limbs,
-                result_limbs,
+                input_rlc,
+                input_len,
+                is_valid,
+                input_lens,
+                exp_bit_len,
+                output_rlc,
             } => vec![
-                1.expr(), // q_head
-                base_limbs[0].clone(),
-                exp_limbs[0].clone(),
-                modulus_limbs[0].clone(),
-                result_limbs[0].clone(),
-                base_limbs[1].clone(),
-                exp_limbs[1].clone(),
-                modulus_limbs[1].clone(),
-                result_limbs[1].clone(),
-                base_limbs[2].clone(),
-                exp_limbs[2].clone(),
-                modulus_limbs[2].clone(),
-                result_limbs[2].clone(),
+                1.expr(), // q_enable
+                input_rlc.clone(),
+                input_len.clone(),
+                is_valid.clone(),
+                input_lens[0].clone(),
+                input_lens[1].clone(),
+                input_lens[2].clone(),
+                exp_bit_len.clone(),
+                output_rlc.clone(),
             ],
             Self::EccTable {
                 op_type,
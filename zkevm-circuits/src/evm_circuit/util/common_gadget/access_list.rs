@@ -0,0 +1,151 @@
+use super::{CachedRegion, Cell};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{EVMConstraintBuilder, ReversionInfo},
+        select,
+    },
+    util::{Expr, Field},
+    witness::Rw,
+};
+use eth_types::evm_types::GasCost;
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Access to an account of the access list of the tx (EIP-2929), through the
+/// `TxAccessListAccount` rows of the rw table. Gadgets accessing an account
+/// warm it up and pay the warm or cold access cost of [`Self::gas_cost`].
+#[derive(Clone, Debug)]
+pub(crate) struct AccountAccessGadget<F> {
+    is_warm: Cell<F>,
+}
+
+impl<F: Field> AccountAccessGadget<F> {
+    /// Warm up `address`, which is cold again if the call reverts.
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        tx_id: Expression<F>,
+        address: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) -> Self {
+        let is_warm = cb.query_bool();
+        cb.account_access_list_write(tx_id, address, 1.expr(), is_warm.expr(), reversion_info);
+
+        Self { is_warm }
+    }
+
+    /// Read the warmth of `address` without warming it up, for the steps
+    /// failing before the access.
+    pub(crate) fn construct_read(
+        cb: &mut EVMConstraintBuilder<F>,
+        tx_id: Expression<F>,
+        address: Expression<F>,
+    ) -> Self {
+        let is_warm = cb.query_bool();
+        cb.account_access_list_read(tx_id, address, is_warm.expr());
+
+        Self { is_warm }
+    }
+
+    /// Whether the account was warm before the access.
+    pub(crate) fn is_warm(&self) -> Expression<F> {
+        self.is_warm.expr()
+    }
+
+    /// Cost of the access to the account.
+    pub(crate) fn gas_cost(&self) -> Expression<F> {
+        select::expr(
+            self.is_warm.expr(),
+            GasCost::WARM_ACCESS.expr(),
+            GasCost::COLD_ACCOUNT_ACCESS.expr(),
+        )
+    }
+
+    /// Assign the warmth of the account before the access `rw`, and return it.
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        rw: &Rw,
+    ) -> Result<bool, Error> {
+        let (_, is_warm) = rw.tx_access_list_value_pair();
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm)))?;
+        Ok(is_warm)
+    }
+
+    /// Cost of the access to an account as assigned.
+    pub(crate) fn gas_cost_for_assignment(is_warm: bool) -> u64 {
+        if is_warm {
+            GasCost::WARM_ACCESS.0
+        } else {
+            GasCost::COLD_ACCOUNT_ACCESS.0
+        }
+    }
+}
+
+/// Access to a storage slot of the access list of the tx (EIP-2929), through
+/// the `TxAccessListAccountStorage` rows of the rw table. The warmth is read
+/// before the slot is warmed up, SLOAD and SSTORE costing it differently.
+#[derive(Clone, Debug)]
+pub(crate) struct StorageSlotAccessGadget<F> {
+    is_warm: Cell<F>,
+}
+
+impl<F: Field> StorageSlotAccessGadget<F> {
+    /// Warm up the slot `key` of `address`, which is cold again if the call
+    /// reverts.
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        tx_id: Expression<F>,
+        address: Expression<F>,
+        key: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) -> Self {
+        let gadget = Self::construct_read(cb, tx_id.expr(), address.expr(), key.expr());
+        cb.account_storage_access_list_write(
+            tx_id,
+            address,
+            key,
+            true.expr(),
+            gadget.is_warm.expr(),
+            reversion_info,
+        );
+
+        gadget
+    }
+
+    /// Read the warmth of the slot `key` of `address` without warming it up,
+    /// for the steps failing before the access.
+    pub(crate) fn construct_read(
+        cb: &mut EVMConstraintBuilder<F>,
+        tx_id: Expression<F>,
+        address: Expression<F>,
+        key: Expression<F>,
+    ) -> Self {
+        let is_warm = cb.query_bool();
+        cb.account_storage_access_list_read(tx_id, address, key, is_warm.expr());
+
+        Self { is_warm }
+    }
+
+    /// Whether the slot was warm before the access, as a cell for the SSTORE
+    /// gas cost.
+    pub(crate) fn is_warm(&self) -> Cell<F> {
+        self.is_warm.clone()
+    }
+
+    /// Assign the warmth of the slot before the access `rw`, and return it.
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        rw: &Rw,
+    ) -> Result<bool, Error> {
+        let (_, is_warm) = rw.tx_access_list_value_pair();
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm)))?;
+        Ok(is_warm)
+    }
+}
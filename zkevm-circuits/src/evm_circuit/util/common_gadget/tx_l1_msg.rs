@@ -4,7 +4,7 @@ use crate::{
         and,
         constraint_builder::EVMConstraintBuilder,
         math_gadget::{IsEqualGadget, IsZeroGadget},
-        select,
+        not, select,
     },
     table::AccountFieldTag,
     util::{Expr, Field},
@@ -12,7 +12,8 @@ use crate::{
 use eth_types::{geth_types::TxType, U256};
 use halo2_proofs::plonk::{Error, Expression};
 
-/// L1 Msg Transaction gadget for some extra handling
+/// L1 Msg Transaction gadget for some extra handling. It also reads the caller's code hash of
+/// other txs, whose sender must not be a contract (EIP-3607).
 #[derive(Clone, Debug)]
 pub(crate) struct TxL1MsgGadget<F> {
     /// tx is l1 msg tx
@@ -35,11 +36,16 @@ impl<F: Field> TxL1MsgGadget<F> {
             IsZeroGadget::construct(cb, caller_codehash.expr())
         });
 
-        cb.condition(tx_is_l1msg.expr(), |cb| {
-            cb.account_read(
-                caller_address.expr(),
-                AccountFieldTag::CodeHash,
-                caller_codehash.expr(),
+        cb.account_read(
+            caller_address.expr(),
+            AccountFieldTag::CodeHash,
+            caller_codehash.expr(),
+        );
+        // L1 msgs may be sent by L1 contracts, other txs must be sent by an EOA
+        cb.condition(not::expr(tx_is_l1msg.expr()), |cb| {
+            cb.require_zero(
+                "caller code hash is empty or zero (EIP-3607)",
+                (caller_codehash.expr() - cb.empty_code_hash_rlc()) * caller_codehash.expr(),
             );
         });
 
@@ -92,7 +98,7 @@ impl<F: Field> TxL1MsgGadget<F> {
         Ok(())
     }
 
-    // return rw_delta WHEN tx is l1msg
+    // return rw_delta WHEN tx is l1msg, other txs only read the caller code hash
     pub(crate) fn rw_delta(&self) -> Expression<F> {
         select::expr(
             self.is_caller_empty.expr(),
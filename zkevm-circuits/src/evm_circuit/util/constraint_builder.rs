@@ -383,6 +383,24 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         )
     }
 
+    /// Max degree of the constraints added so far, before they get multiplied
+    /// by the step and execution state selectors.
+    pub(crate) fn max_constraint_degree(&self) -> usize {
+        let Constraints {
+            step,
+            step_first,
+            step_last,
+            not_step_last,
+        } = &self.constraints;
+        step.iter()
+            .chain(step_first)
+            .chain(step_last)
+            .chain(not_step_last)
+            .map(|(_, expr)| expr.degree())
+            .max()
+            .unwrap_or_default()
+    }
+
     fn condition_expr_opt(&self) -> Option<Expression<F>> {
         let mut iter = self.conditions.iter();
         let first = match iter.next() {
@@ -1235,6 +1253,33 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // same as call_context_lookup_write_with_counter for a read
+    // Note: will not bumping internal rwc
+    pub(crate) fn call_context_lookup_read_with_counter(
+        &mut self,
+        rw_counter: Expression<F>,
+        call_id: Option<Expression<F>>,
+        field_tag: CallContextFieldTag,
+        value: Expression<F>,
+    ) {
+        self.rw_lookup_with_counter(
+            "CallContext lookup",
+            rw_counter,
+            0.expr(),
+            RwTableTag::CallContext,
+            RwValues::new(
+                call_id.unwrap_or_else(|| self.curr.state.call_id.expr()),
+                0.expr(),
+                field_tag.expr(),
+                0.expr(),
+                value,
+                0.expr(),
+                0.expr(),
+                0.expr(),
+            ),
+        );
+    }
+
     pub(crate) fn call_context_lookup(
         &mut self,
         is_write: Expression<F>,
@@ -1598,18 +1643,22 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     // ModExp table
     pub(crate) fn modexp_table_lookup(
         &mut self,
-        base_limbs: [Expression<F>; 3],
-        exp_limbs: [Expression<F>; 3],
-        modulus_limbs: [Expression<F>; 3],
-        result_limbs: [Expression<F>; 3],
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        is_valid: Expression<F>,
+        input_lens: [Expression<F>; 3],
+        exp_bit_len: Expression<F>,
+        output_rlc: Expression<F>,
     ) {
         self.add_lookup(
-            "u256 exponentiation modulus lookup",
+            "modexp lookup",
             Lookup::ModExpTable {
-                base_limbs,
-                exp_limbs,
-                modulus_limbs,
-                result_limbs,
+                input_rlc,
+                input_len,
+                is_valid,
+                input_lens,
+                exp_bit_len,
+                output_rlc,
             },
         );
     }
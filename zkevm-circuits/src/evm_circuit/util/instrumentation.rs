@@ -7,6 +7,7 @@ use crate::{
     util::Field,
 };
 use itertools::Itertools;
+use std::collections::BTreeMap;
 
 type StepSize = Vec<(CellType, ColumnSize)>;
 /// Contains (width, height, num_cells)
@@ -17,6 +18,8 @@ type ColumnSize = (usize, usize, usize);
 pub(crate) struct Instrument {
     // States -> Cell Types -> (width, height, num_cells)
     states: Vec<(ExecutionState, StepSize)>,
+    // States -> (step height, max constraint degree)
+    shapes: Vec<(usize, usize)>,
 }
 
 impl Instrument {
@@ -25,6 +28,7 @@ impl Instrument {
     pub(crate) fn on_gadget_built<F: Field>(
         &mut self,
         execution_state: ExecutionState,
+        height: usize,
         cb: &EVMConstraintBuilder<F>,
     ) {
         let sizes = cb
@@ -36,6 +40,24 @@ impl Instrument {
             .collect::<Vec<_>>();
 
         self.states.push((execution_state, sizes));
+        self.shapes.push((height, cb.max_constraint_degree()));
+    }
+
+    /// Returns a [`GadgetReport`] for each EVM `ExecutionState`.
+    pub(crate) fn gadget_reports(&self) -> Vec<GadgetReport> {
+        self.states
+            .iter()
+            .zip(&self.shapes)
+            .map(|((state, sizes), (height, max_degree))| GadgetReport {
+                state: *state,
+                height: *height,
+                cells: sizes
+                    .iter()
+                    .map(|(cell_type, (_, _, cells))| (format!("{cell_type:?}"), *cells))
+                    .collect(),
+                max_degree: *max_degree,
+            })
+            .collect()
     }
 
     /// Disassembles the instrumentation data and returns a collection of
@@ -128,6 +150,20 @@ impl Instrument {
     }
 }
 
+/// Layout of the gadget of an EVM `ExecutionState`.
+#[derive(Clone, Debug)]
+pub struct GadgetReport {
+    /// Execution state of the gadget
+    pub state: ExecutionState,
+    /// Number of rows of a step in this state
+    pub height: usize,
+    /// Number of cells used by the gadget, by cell type
+    pub cells: BTreeMap<String, usize>,
+    /// Max degree of the constraints of the gadget, not counting the step
+    /// and execution state selectors
+    pub max_degree: usize,
+}
+
 /// Struct which contains a Cost/ColumnType report for a particular EVM
 /// `ExecutionStep`.
 #[derive(Clone, Debug, Default)]
@@ -1,11 +1,11 @@
 use crate::{
     evm_circuit::util::{
-        self, constraint_builder::EVMConstraintBuilder, from_bytes, math_gadget::*, select,
-        CachedRegion,
+        self, constraint_builder::EVMConstraintBuilder, from_bytes, math_gadget::*, CachedRegion,
     },
-    util::{Expr, Field},
+    util::Field,
 };
 use eth_types::{ToLittleEndian, Word};
+use gadgets::comparison::{eq_of_limbs, lt_of_limbs};
 use halo2_proofs::plonk::{Error, Expression};
 
 #[derive(Clone, Debug)]
@@ -30,23 +30,20 @@ impl<F: Field> CmpWordsGadget<F> {
             from_bytes::expr(&b.cells[0..16]),
         );
 
-        let (lt_lo, eq_lo) = comparison_lo.expr();
-
         // `a[16..32] <= b[16..32]`
         let comparison_hi = ComparisonGadget::construct(
             cb,
             from_bytes::expr(&a.cells[16..32]),
             from_bytes::expr(&b.cells[16..32]),
         );
-        let (lt_hi, eq_hi) = comparison_hi.expr();
 
         // `a < b` when:
         // - `a[16..32] < b[16..32]` OR
         // - `a[16..32] == b[16..32]` AND `a[0..16] < b[0..16]`
-        let lt = select::expr(lt_hi, 1.expr(), eq_hi.clone() * lt_lo);
-
-        // `a == b` when both parts are equal
-        let eq = eq_hi * eq_lo;
+        // and `a == b` when both parts are equal
+        let limbs = [comparison_lo.expr(), comparison_hi.expr()];
+        let lt = lt_of_limbs(&limbs);
+        let eq = eq_of_limbs(&limbs);
 
         Self {
             comparison_lo,
@@ -86,7 +83,7 @@ impl<F: Field> CmpWordsGadget<F> {
 #[cfg(test)]
 mod tests {
     use super::{test_util::*, *};
-    use crate::evm_circuit::util::constraint_builder::ConstrainBuilderCommon;
+    use crate::{evm_circuit::util::constraint_builder::ConstrainBuilderCommon, util::Expr};
     use eth_types::Word;
     use halo2_proofs::{halo2curves::bn256::Fr, plonk::Error};
 
@@ -3,11 +3,12 @@ use crate::{
         param::MAX_N_BYTES_INTEGER,
         util::{
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-            from_bytes, pow_of_two, transpose_val_ret, CachedRegion, Cell,
+            transpose_val_ret, CachedRegion, Cell,
         },
     },
     util::{Expr, Field},
 };
+use gadgets::comparison::lt;
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
@@ -18,16 +19,14 @@ use halo2_proofs::{
 /// `N_BYTES` is required to be `<= MAX_N_BYTES_INTEGER` to prevent overflow:
 /// values are stored in a single field element and two of these are added
 /// together.
-/// The equation that is enforced is `lhs - rhs == diff - (lt * range)`.
-/// Because all values are `<= 256**N_BYTES` and `lt` is boolean, `lt` can only
-/// be `1` when `lhs < rhs`.
+/// The equation that is enforced is `lhs - rhs == diff - (lt * range)`, see
+/// [`gadgets::comparison::lt`].
 #[derive(Clone, Debug)]
 pub struct LtGadget<F, const N_BYTES: usize> {
     lt: Cell<F>, // `1` when `lhs < rhs`, `0` otherwise.
     diff: [Cell<F>; N_BYTES], /* The byte values of `diff`.
                   * `diff` equals `lhs - rhs` if `lhs >= rhs`,
                   * `lhs - rhs + range` otherwise. */
-    range: F, // The range of the inputs, `256**N_BYTES`
 }
 
 impl<F: Field, const N_BYTES: usize> LtGadget<F, N_BYTES> {
@@ -39,16 +38,15 @@ impl<F: Field, const N_BYTES: usize> LtGadget<F, N_BYTES> {
         assert!(N_BYTES <= MAX_N_BYTES_INTEGER);
         let lt = cb.query_bool();
         let diff = cb.query_bytes();
-        let range = pow_of_two(N_BYTES * 8);
 
         // The equation we require to hold: `lhs - rhs == diff - (lt * range)`.
         cb.require_equal(
             "lhs - rhs == diff - (lt ⋅ range)",
             lhs - rhs,
-            from_bytes::expr(&diff) - (lt.expr() * range),
+            lt::expr(lt.expr(), &diff),
         );
 
-        Self { lt, diff, range }
+        Self { lt, diff }
     }
 
     pub(crate) fn expr(&self) -> Expression<F> {
@@ -62,8 +60,9 @@ impl<F: Field, const N_BYTES: usize> LtGadget<F, N_BYTES> {
         lhs: F,
         rhs: F,
     ) -> Result<(F, Vec<u8>), Error> {
+        let (lt, diff_bytes) = lt::value(lhs, rhs, N_BYTES);
+
         // Set `lt`
-        let lt = lhs < rhs;
         self.lt.assign(
             region,
             offset,
@@ -71,8 +70,6 @@ impl<F: Field, const N_BYTES: usize> LtGadget<F, N_BYTES> {
         )?;
 
         // Set the bytes of diff
-        let diff = (lhs - rhs) + (if lt { self.range } else { F::zero() });
-        let diff_bytes = diff.to_repr();
         for (idx, diff) in self.diff.iter().enumerate() {
             diff.assign(
                 region,
@@ -81,7 +78,7 @@ impl<F: Field, const N_BYTES: usize> LtGadget<F, N_BYTES> {
             )?;
         }
 
-        Ok((if lt { F::one() } else { F::zero() }, diff_bytes.to_vec()))
+        Ok((if lt { F::one() } else { F::zero() }, diff_bytes))
     }
 
     pub(crate) fn diff_bytes(&self) -> Vec<Cell<F>> {
@@ -3,9 +3,10 @@ use crate::{
         self, constraint_builder::EVMConstraintBuilder, from_bytes, math_gadget::*, split_u256,
         CachedRegion,
     },
-    util::Field,
+    util::{Expr, Field},
 };
 use eth_types::Word;
+use gadgets::comparison::lt_of_limbs;
 use halo2_proofs::plonk::{Error, Expression};
 
 /// Returns `1` when `lhs < rhs`, and returns `0` otherwise.
@@ -40,7 +41,8 @@ impl<F: Field> LtWordGadget<F> {
 
     pub(crate) fn expr(&self) -> Expression<F> {
         let (hi_lt, hi_eq) = self.comparison_hi.expr();
-        hi_lt + hi_eq * self.lt_lo.expr()
+        // the eq of the low limb is not used
+        lt_of_limbs(&[(self.lt_lo.expr(), 0.expr()), (hi_lt, hi_eq)])
     }
 
     pub(crate) fn assign(
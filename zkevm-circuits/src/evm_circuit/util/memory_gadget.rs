@@ -322,6 +322,45 @@ impl<F: Field> MemoryExpandedAddressGadget<F> {
             })
     }
 
+    /// Return the u64 end offset of a non-empty access whose sum doesn't overflow u64,
+    /// otherwise 0. Unlike `end_offset`, it is not capped at `MAX_EXPANDED_MEMORY_ADDRESS`, for
+    /// `MemoryExpansionU64Gadget`.
+    pub(crate) fn end_offset_u64(&self) -> Expression<F> {
+        select::expr(
+            self.length_is_zero.expr(),
+            0.expr(),
+            select::expr(
+                self.within_u64(),
+                from_bytes::expr(&self.offset_length_sum.sum().cells[..N_BYTES_U64]),
+                0.expr(),
+            ),
+        )
+    }
+
+    /// Return the value of the expression returned by `end_offset_u64`.
+    pub(crate) fn end_offset_u64_value(memory_offset: U256, memory_length: U256) -> u64 {
+        if memory_length.is_zero() {
+            return 0;
+        }
+
+        memory_offset
+            .checked_add(memory_length)
+            .filter(|address| address.bits() <= 64)
+            .map_or(0, |address| address.as_u64())
+    }
+
+    /// Check if the end offset of a non-empty access overflows u64.
+    pub(crate) fn overflow_u64(&self) -> Expression<F> {
+        not::expr(or::expr([self.length_is_zero.expr(), self.within_u64()]))
+    }
+
+    fn within_u64(&self) -> Expression<F> {
+        and::expr([
+            self.sum_within_u64.expr(),
+            not::expr(self.offset_length_sum.carry().as_ref().unwrap()),
+        ])
+    }
+
     /// Check if overflow.
     pub(crate) fn overflow(&self) -> Expression<F> {
         not::expr(self.within_range())
@@ -331,11 +370,7 @@ impl<F: Field> MemoryExpandedAddressGadget<F> {
     pub(crate) fn within_range(&self) -> Expression<F> {
         or::expr([
             self.length_is_zero.expr(),
-            and::expr([
-                self.sum_lt_cap.expr(),
-                self.sum_within_u64.expr(),
-                not::expr(self.offset_length_sum.carry().as_ref().unwrap()),
-            ]),
+            and::expr([self.sum_lt_cap.expr(), self.within_u64()]),
         ])
     }
 }
@@ -823,6 +858,107 @@ impl<F: Field, const N: usize, const N_BYTES_MEMORY_WORD_SIZE: usize>
     }
 }
 
+/// Returns (new memory size, memory gas cost) for memory accesses ending at any u64 address, with
+/// whether any of them overflows the memory gas cost.
+/// The gas cost of an address above `MAX_EXPANDED_MEMORY_ADDRESS` overflows u64, which
+/// go-ethereum reports as out of gas (`ErrGasUintOverflow` of
+/// [memoryGasCost](https://github.com/ethereum/go-ethereum/blob/db18293c32f6dc5d6886e5e68ab8bfd12e33cad6/core/vm/gas_table.go#L38)),
+/// so such addresses are flagged by `overflow` and left out of the expansion. The other addresses
+/// are expanded with the quadratic cost of `MemoryExpansionGadget`.
+///
+/// Only the memory OOG gadgets use it. The successful MLOAD/MSTORE, *COPY, LOG and
+/// RETURN/REVERT gadgets keep `MemoryExpansionGadget`, since an access that doesn't run out of
+/// gas ends within `N_BYTES_MEMORY_ADDRESS` bytes, which those gadgets already constrain.
+#[derive(Clone, Debug)]
+pub(crate) struct MemoryExpansionU64Gadget<F, const N: usize> {
+    addresses_in_range: [LtGadget<F, N_BYTES_U64>; N],
+    memory_expansion: MemoryExpansionGadget<F, N, N_BYTES_MEMORY_WORD_SIZE>,
+}
+
+impl<F: Field, const N: usize> MemoryExpansionU64Gadget<F, N> {
+    /// Input requirements:
+    /// - `curr_memory_word_size < 256**N_BYTES_MEMORY_WORD_SIZE`
+    /// - `address < 256**N_BYTES_U64`
+    /// Output ranges:
+    /// - `next_memory_word_size < 256**N_BYTES_MEMORY_WORD_SIZE`
+    /// - `gas_cost <= GAS_MEM*256**N_BYTES_MEMORY_WORD_SIZE + 256**N_BYTES_GAS`
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        addresses: [Expression<F>; N],
+    ) -> Self {
+        let addresses_in_range = array_init(|idx| {
+            LtGadget::construct(
+                cb,
+                addresses[idx].clone(),
+                (MAX_EXPANDED_MEMORY_ADDRESS + 1).expr(),
+            )
+        });
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            array_init(|idx| {
+                select::expr(
+                    addresses_in_range[idx].expr(),
+                    addresses[idx].clone(),
+                    0.expr(),
+                )
+            }),
+        );
+
+        Self {
+            addresses_in_range,
+            memory_expansion,
+        }
+    }
+
+    /// Check if the gas cost of any of the addresses overflows.
+    pub(crate) fn overflow(&self) -> Expression<F> {
+        not::expr(and::expr(
+            self.addresses_in_range
+                .iter()
+                .map(|in_range| in_range.expr()),
+        ))
+    }
+
+    pub(crate) fn next_memory_word_size(&self) -> Expression<F> {
+        self.memory_expansion.next_memory_word_size()
+    }
+
+    /// Gas cost of the expansion to the addresses in range.
+    pub(crate) fn gas_cost(&self) -> Expression<F> {
+        self.memory_expansion.gas_cost()
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        curr_memory_word_size: u64,
+        addresses: [u64; N],
+    ) -> Result<(u64, u64), Error> {
+        for (in_range, address) in self.addresses_in_range.iter().zip(addresses.iter()) {
+            in_range.assign(
+                region,
+                offset,
+                F::from(*address),
+                F::from(MAX_EXPANDED_MEMORY_ADDRESS + 1),
+            )?;
+        }
+
+        self.memory_expansion.assign(
+            region,
+            offset,
+            curr_memory_word_size,
+            addresses.map(|address| {
+                if address > MAX_EXPANDED_MEMORY_ADDRESS {
+                    0
+                } else {
+                    address
+                }
+            }),
+        )
+    }
+}
+
 /// Returns (new memory size, memory gas cost) for a memory access.
 /// If the memory needs to be expanded this will result in an extra gas cost.
 /// This gas cost is the difference between the next and current memory costs:
@@ -1006,3 +1142,111 @@ impl<F: Field, const MAX_BYTES: usize, const ADDR_SIZE_IN_BYTES: usize>
         self.selectors[idx].expr()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm_circuit::util::math_gadget::test_util::*;
+    use eth_types::Word;
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    #[derive(Clone)]
+    /// MemoryExpansionU64TestContainer: require the overflow and the gas cost
+    /// of the expansion of an empty memory to an address
+    struct MemoryExpansionU64TestContainer<F> {
+        memory_expansion: MemoryExpansionU64Gadget<F, 1>,
+        address: Cell<F>,
+        overflow: Cell<F>,
+        gas_cost: Cell<F>,
+    }
+
+    impl<F: Field> MathGadgetContainer<F> for MemoryExpansionU64TestContainer<F> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let address = cb.query_cell();
+            let overflow = cb.query_cell();
+            let gas_cost = cb.query_cell();
+            let memory_expansion = MemoryExpansionU64Gadget::construct(cb, [address.expr()]);
+
+            cb.require_equal("overflow", memory_expansion.overflow(), overflow.expr());
+            cb.require_equal("gas cost", memory_expansion.gas_cost(), gas_cost.expr());
+
+            MemoryExpansionU64TestContainer {
+                memory_expansion,
+                address,
+                overflow,
+                gas_cost,
+            }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let [address, overflow, gas_cost] = [0, 1, 2].map(|idx| witnesses[idx].as_u64());
+            let offset = 0;
+
+            self.address
+                .assign(region, offset, Value::known(F::from(address)))?;
+            self.overflow
+                .assign(region, offset, Value::known(F::from(overflow)))?;
+            self.gas_cost
+                .assign(region, offset, Value::known(F::from(gas_cost)))?;
+            self.memory_expansion.assign(region, offset, 0, [address])?;
+
+            Ok(())
+        }
+    }
+
+    fn memory_cost(address: u64) -> u64 {
+        let words = (address + 31) / 32;
+        GasCost::MEMORY_EXPANSION_LINEAR_COEFF.as_u64() * words
+            + words * words / GasCost::MEMORY_EXPANSION_QUAD_DENOMINATOR.as_u64()
+    }
+
+    #[test]
+    fn test_memory_expansion_u64_in_range() {
+        for address in [0, 1, 0x20, 0xffffffff, MAX_EXPANDED_MEMORY_ADDRESS] {
+            try_test!(
+                MemoryExpansionU64TestContainer<Fr>,
+                [address, 0, memory_cost(address)].map(Word::from),
+                true,
+            );
+        }
+    }
+
+    #[test]
+    fn test_memory_expansion_u64_overflow() {
+        for address in [
+            MAX_EXPANDED_MEMORY_ADDRESS + 1,
+            0xffffffff00000000,
+            u64::MAX,
+        ] {
+            try_test!(
+                MemoryExpansionU64TestContainer<Fr>,
+                [address, 1, 0].map(Word::from),
+                true,
+            );
+        }
+    }
+
+    #[test]
+    fn test_memory_expansion_u64_unexpected() {
+        let address = MAX_EXPANDED_MEMORY_ADDRESS;
+        try_test!(
+            MemoryExpansionU64TestContainer<Fr>,
+            [address, 1, memory_cost(address)].map(Word::from),
+            false,
+        );
+        try_test!(
+            MemoryExpansionU64TestContainer<Fr>,
+            [address, 0, memory_cost(address) - 1].map(Word::from),
+            false,
+        );
+        try_test!(
+            MemoryExpansionU64TestContainer<Fr>,
+            [address + 1, 0, 0].map(Word::from),
+            false,
+        );
+    }
+}
@@ -2,7 +2,7 @@
 use crate::{
     evm_circuit::witness::{block_convert, Block},
     exp_circuit::ExpCircuit,
-    util::{unusable_rows, Field, SubCircuit},
+    util::{circuit_fingerprint, unusable_rows, Field, SubCircuit},
 };
 use bus_mapping::{
     circuit_input_builder::{CircuitInputBuilder, CircuitsParams},
@@ -23,6 +23,33 @@ fn exp_circuit_unusable_rows() {
     )
 }
 
+#[test]
+fn exp_circuit_fingerprint() {
+    // the u16 table takes 2^16 rows
+    let k = 17;
+    let params = CircuitsParams {
+        max_exp_steps: 10,
+        ..Default::default()
+    };
+    let circuit = ExpCircuit::<Fr>::new(Vec::new(), params.max_exp_steps);
+    let fingerprint = circuit_fingerprint(&circuit, k, &params).unwrap();
+
+    // the fixed columns enable the rows of the max exp steps
+    let larger_circuit = ExpCircuit::<Fr>::new(Vec::new(), 2 * params.max_exp_steps);
+    assert_ne!(
+        fingerprint,
+        circuit_fingerprint(&larger_circuit, k, &params).unwrap()
+    );
+    let other_params = CircuitsParams {
+        max_txs: params.max_txs + 1,
+        ..params
+    };
+    assert_ne!(
+        fingerprint,
+        circuit_fingerprint(&circuit, k, &other_params).unwrap()
+    );
+}
+
 /// Test exponentiation circuit with the provided block witness
 pub fn test_exp_circuit(k: u32, block: Block) {
     let circuit = ExpCircuit::<Fr>::new(
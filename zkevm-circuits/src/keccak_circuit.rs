@@ -21,7 +21,7 @@ pub use KeccakCircuitConfig as KeccakConfig;
 
 use self::{
     cell_manager::*,
-    keccak_packed_multi::{keccak_unusable_rows, multi_keccak, KeccakRow},
+    keccak_packed_multi::{keccak_unusable_rows, multi_keccak_chunks, KeccakRow},
     param::*,
     table::*,
     util::*,
@@ -1000,6 +1000,9 @@ pub struct KeccakCircuit<F: Field> {
     // Each input row is a pre-image of the hash
     // The output row of the hash, i.e., the digest is NOT part of the circuit input
     inputs: Vec<Vec<u8>>,
+    // Pre-images given as a stream of chunks, absorbed block by block without
+    // concatenating them
+    chunked_inputs: Vec<Vec<Vec<u8>>>,
     // The maximum number of rows, for example, 2^20
     // This needs to be large enough for the circuit.
     num_rows: usize,
@@ -1036,7 +1039,7 @@ impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
             keccak_inputs(block)
                 .unwrap()
                 .iter()
-                .map(|bytes| (bytes.len() as f64 / 136.0).ceil() as usize * rows_per_chunk)
+                .map(|bytes| Self::num_absorb_blocks(bytes.len()) * rows_per_chunk)
                 .sum::<usize>()
                 + get_num_rows_per_round(), // reserved for first 12 dummy rows
             max(
@@ -1064,11 +1067,47 @@ impl<F: Field> KeccakCircuit<F> {
     pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
         KeccakCircuit {
             inputs,
+            chunked_inputs: vec![],
             num_rows,
             _marker: PhantomData,
         }
     }
 
+    /// Add pre-images given as a stream of chunks of any size, e.g. the parts
+    /// of a large bytecode or calldata. They are absorbed block by block,
+    /// without concatenating the chunks.
+    pub fn with_chunked_inputs(mut self, inputs: Vec<Vec<Vec<u8>>>) -> Self {
+        self.chunked_inputs.extend(inputs);
+        self
+    }
+
+    /// The number of absorb blocks, i.e. keccak_f's, needed to hash an input of
+    /// `input_len` bytes, including the padding.
+    pub fn num_absorb_blocks(input_len: usize) -> usize {
+        input_len / RATE + 1
+    }
+
+    /// The number of keccak_f's used by the inputs of this circuit.
+    pub fn num_absorb_blocks_used(&self) -> usize {
+        self.inputs
+            .iter()
+            .map(Vec::len)
+            .chain(
+                self.chunked_inputs
+                    .iter()
+                    .map(|chunks| chunks.iter().map(Vec::len).sum()),
+            )
+            .map(Self::num_absorb_blocks)
+            .sum()
+    }
+
+    /// The number of keccak_f's left in this circuit, None if the circuit is
+    /// sized dynamically.
+    pub fn remaining_capacity(&self) -> Option<usize> {
+        self.capacity()
+            .map(|capacity| capacity.saturating_sub(self.num_absorb_blocks_used()))
+    }
+
     /// The number of keccak_f's that can be done in this circuit
     pub fn capacity(&self) -> Option<usize> {
         Self::capacity_for_row(self.num_rows)
@@ -1087,7 +1126,17 @@ impl<F: Field> KeccakCircuit<F> {
 
     /// Sets the witness using the data to be hashed
     pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<KeccakRow<F>> {
-        multi_keccak(self.inputs.as_slice(), challenges, self.capacity())
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|bytes| vec![bytes.as_slice()])
+            .chain(
+                self.chunked_inputs
+                    .iter()
+                    .map(|chunks| chunks.iter().map(Vec::as_slice).collect()),
+            )
+            .collect::<Vec<Vec<_>>>();
+        multi_keccak_chunks(&inputs, challenges, self.capacity())
             .expect("Too many inputs for given capacity")
     }
 }
@@ -531,39 +531,81 @@ pub(crate) mod transform_to {
     }
 }
 
-pub(crate) fn keccak_rows<F: Field>(
-    bytes: &[u8],
+/// Iterator over the absorb blocks of an input given as a stream of chunks of
+/// any size. Every block but the final one has `RATE` bytes, the final one has
+/// less (possibly none), and is the one to pad.
+struct AbsorbBlocks<'a, I> {
+    chunks: I,
+    current: &'a [u8],
+    done: bool,
+}
+
+impl<'a, I: Iterator<Item = &'a [u8]>> Iterator for AbsorbBlocks<'a, I> {
+    // (bytes of the block, is_final_block)
+    type Item = (Vec<u8>, bool);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut block = Vec::with_capacity(RATE);
+        while block.len() < RATE {
+            if self.current.is_empty() {
+                match self.chunks.next() {
+                    Some(chunk) => self.current = chunk,
+                    None => {
+                        self.done = true;
+                        return Some((block, true));
+                    }
+                }
+                continue;
+            }
+            let len = (RATE - block.len()).min(self.current.len());
+            block.extend_from_slice(&self.current[..len]);
+            self.current = &self.current[len..];
+        }
+        Some((block, false))
+    }
+}
+
+pub(crate) fn keccak_rows<'a, F: Field>(
+    chunks: impl IntoIterator<Item = &'a [u8]>,
     challenges: Challenges<Value<F>>,
 ) -> Vec<KeccakRow<F>> {
     let mut rows = Vec::new();
-    keccak(&mut rows, bytes, challenges);
+    keccak(&mut rows, chunks, challenges);
     rows
 }
 
-pub(crate) fn keccak<F: Field>(
+/// Generate the rows of the keccak of an input given as a stream of chunks,
+/// absorbed block by block.
+pub(crate) fn keccak<'a, F: Field>(
     rows: &mut Vec<KeccakRow<F>>,
-    bytes: &[u8],
+    chunks: impl IntoIterator<Item = &'a [u8]>,
     challenges: Challenges<Value<F>>,
 ) {
-    let mut bits = into_bits(bytes);
     let mut s = [[F::zero(); 5]; 5];
     let absorb_positions = get_absorb_positions();
-    let num_bytes_in_last_block = bytes.len() % RATE;
     let two = F::from(2u64);
 
-    // Padding
-    bits.push(1);
-    while (bits.len() + 1) % RATE_IN_BITS != 0 {
-        bits.push(0);
-    }
-    bits.push(1);
-
     let mut length = 0usize;
     let mut data_rlc = Value::known(F::zero());
-    let chunks = bits.chunks(RATE_IN_BITS);
-    let num_chunks = chunks.len();
-    for (idx, chunk) in chunks.enumerate() {
-        let is_final_block = idx == num_chunks - 1;
+    let blocks = AbsorbBlocks {
+        chunks: chunks.into_iter(),
+        current: &[],
+        done: false,
+    };
+    for (block, is_final_block) in blocks {
+        let num_bytes_in_last_block = block.len();
+        let mut chunk = into_bits(&block);
+        if is_final_block {
+            // Padding
+            chunk.push(1);
+            while (chunk.len() + 1) % RATE_IN_BITS != 0 {
+                chunk.push(0);
+            }
+            chunk.push(1);
+        }
 
         let mut absorb_rows = Vec::new();
         // Absorb
@@ -893,6 +935,21 @@ pub fn multi_keccak<F: Field>(
     bytes: &[Vec<u8>],
     challenges: Challenges<Value<F>>,
     capacity: Option<usize>,
+) -> Result<Vec<KeccakRow<F>>, Error> {
+    let inputs = bytes
+        .iter()
+        .map(|bytes| [bytes.as_slice()])
+        .collect::<Vec<_>>();
+    multi_keccak_chunks(&inputs, challenges, capacity)
+}
+
+/// Genenrate keccak circuit witness from preimages, each given as a stream of
+/// chunks of any size, which are absorbed block by block without concatenating
+/// them.
+pub fn multi_keccak_chunks<F: Field, S: AsRef<[C]> + Sync, C: AsRef<[u8]>>(
+    inputs: &[S],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
 ) -> Result<Vec<KeccakRow<F>>, Error> {
     log::info!("multi_keccak assign with capacity: {:?}", capacity);
 
@@ -919,19 +976,28 @@ pub fn multi_keccak<F: Field>(
         });
     }
 
-    let total_len: usize = bytes.iter().map(|b| b.len()).sum();
+    let total_len: usize = inputs
+        .iter()
+        .flat_map(|chunks| chunks.as_ref())
+        .map(|chunk| chunk.as_ref().len())
+        .sum();
     log::info!("multi keccak total len {total_len}");
     // TODO: optimize the `extend` using Iter?
-    let real_rows: Vec<_> = bytes
+    let real_rows: Vec<_> = inputs
         .par_iter()
-        .flat_map_iter(|bytes| keccak_rows(bytes, challenges))
+        .flat_map_iter(|chunks| {
+            keccak_rows(
+                chunks.as_ref().iter().map(|chunk| chunk.as_ref()),
+                challenges,
+            )
+        })
         .collect();
     rows.extend(real_rows);
     debug!("keccak rows len without padding: {}", rows.len());
     if let Some(capacity) = capacity {
         let padding_rows = {
             let mut rows = Vec::new();
-            keccak(&mut rows, &[], challenges);
+            keccak(&mut rows, std::iter::empty(), challenges);
             rows
         };
         // Pad with no data hashes to the expected capacity
@@ -55,6 +55,37 @@ fn packed_multi_keccak_simple() {
     verify::<Fr>(k, inputs, true);
 }
 
+#[test]
+fn packed_multi_keccak_chunked_inputs() {
+    let k = get_degree() as u32;
+    let input = (0u8..=255).cycle().take(300).collect::<Vec<_>>();
+    let chunks = vec![
+        input[..1].to_vec(),
+        vec![],
+        input[1..137].to_vec(),
+        input[137..].to_vec(),
+    ];
+    let circuit = KeccakCircuit::<Fr>::new(2usize.pow(k), vec![]).with_chunked_inputs(vec![chunks]);
+    assert_eq!(circuit.num_absorb_blocks_used(), 3);
+
+    let challenges = Challenges::mock(
+        Value::known(Fr::from(1)),
+        Value::known(Fr::from(2)),
+        Value::known(Fr::from(3)),
+    );
+    let chunked_rows = circuit.generate_witness(challenges);
+    let rows = KeccakCircuit::<Fr>::new(2usize.pow(k), vec![input]).generate_witness(challenges);
+    assert_eq!(chunked_rows.len(), rows.len());
+    for (chunked_row, row) in chunked_rows.iter().zip(rows.iter()) {
+        assert_eq!(chunked_row.cell_values, row.cell_values);
+        assert_eq!(chunked_row.length, row.length);
+        assert_eq!(chunked_row.is_final, row.is_final);
+    }
+
+    let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
 #[test]
 fn variadic_size_check() {
     let k = get_degree() as u32;
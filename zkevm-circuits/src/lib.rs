@@ -33,15 +33,15 @@ pub mod ecc_circuit;
 pub mod evm_circuit;
 pub mod exp_circuit;
 pub mod keccak_circuit;
+pub mod modexp_circuit;
 pub mod mpt_circuit;
 pub mod pi_circuit;
 pub mod poseidon_circuit;
+pub mod prover_input;
 pub mod rlp_circuit_fsm;
-pub mod sig_circuit;
-// we don't use this for aggregation
-//pub mod root_circuit;
-pub mod modexp_circuit;
+pub mod root_circuit;
 pub mod sha256_circuit;
+pub mod sig_circuit;
 pub mod state_circuit;
 pub mod super_circuit;
 pub mod table;
@@ -1,212 +1,895 @@
-//! The Modexp circuit is responsible for modexp operations on big integer from precompiled contract
-//! calls ModExp, current the size of supported integer is up to 32 bytes (U256)
+//! The Modexp circuit is responsible for modexp operations from precompiled contract calls ModExp,
+//! on operands of up to `CircuitsParams::max_modexp_bytes` bytes. It parses the input bytes of each
+//! call, checks the byte lengths of the operands against the size limit, and exponentiates with big
+//! integers of 64-bit limbs.
 
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 mod dev;
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 mod test;
+mod util;
 
+use std::{iter, marker::PhantomData};
+
+use halo2_base::{
+    gates::{
+        range::{RangeConfig, RangeStrategy},
+        GateInstructions, RangeInstructions,
+    },
+    AssignedValue, Context, ContextParams, QuantumCell, SKIP_FIRST_PASS,
+};
 use halo2_proofs::{
-    circuit::{Layouter, Region, Value},
-    plonk::{Advice, Column, ConstraintSystem, Error},
+    circuit::{Layouter, Value},
+    plonk::{ConstraintSystem, Error},
 };
+use itertools::Itertools;
+use num::{BigInt, BigUint, One, Zero};
 
 use crate::{
+    evm_circuit::EvmCircuit,
+    keccak_circuit::KeccakCircuit,
     table::ModExpTable,
-    util::{Challenges, Field, SubCircuit, SubCircuitConfig},
+    util::{Challenges, Field, PrecompileRowFormula, SubCircuit, SubCircuitConfig},
     witness,
 };
-use bus_mapping::circuit_input_builder::BigModExp;
-use eth_types::Word;
-
-//use misc_precompiled_circuit::value_for_assign;
-use misc_precompiled_circuit::circuits::{
-    modexp::{ModExpChip, Number},
-    range::{RangeCheckChip, RangeCheckConfig},
-    CommonGateConfig as ModExpConfig, Limb,
+use bus_mapping::{
+    circuit_input_builder::{BigModExp, CircuitsParams},
+    precompile::{modexp_size_limit, PrecompileCalls, MODEXP_INPUT_LIMIT},
+};
+
+use util::{
+    bigint_to_field, biguint_to_limbs, carry_bits, cells_per_op, n_limbs, BigUintAssigned,
+    ModExpAssigned, ModExpDecomposed, OperandDecomposed, LOG_TOTAL_NUM_ROWS, LOOKUP_BITS,
+    NUM_ADVICE, NUM_ADVICE_PHASE2, NUM_LOOKUP_ADVICE, N_BITS_LEN, N_BITS_LIMB, N_BYTES_LIMB,
 };
 
 /// ModExp circuit config
 #[derive(Clone, Debug)]
-pub struct ModExpCircuitConfig {
-    modexp_config: ModExpConfig,
-    rangecheck_config: RangeCheckConfig,
+pub struct ModExpCircuitConfig<F: Field> {
+    /// Range config of the big integer arithmetic.
+    range: RangeConfig<F>,
+    /// Lookup table for the I/Os of the ModExp operations.
     modexp_table: ModExpTable,
 }
 
-impl<F: Field> SubCircuitConfig<F> for ModExpCircuitConfig {
+impl<F: Field> SubCircuitConfig<F> for ModExpCircuitConfig<F> {
     type ConfigArgs = ModExpTable;
 
     /// Return a new ModExpCircuitConfig
     fn new(meta: &mut ConstraintSystem<F>, modexp_table: Self::ConfigArgs) -> Self {
-        let rangecheck_config = RangeCheckChip::configure(meta);
-        let modexp_config = ModExpChip::configure(meta, &rangecheck_config);
+        #[cfg(feature = "onephase")]
+        let num_advice = [NUM_ADVICE];
+        #[cfg(not(feature = "onephase"))]
+        let num_advice = [NUM_ADVICE, NUM_ADVICE_PHASE2];
+
+        let range = RangeConfig::configure(
+            meta,
+            RangeStrategy::Vertical,
+            &num_advice,
+            &[NUM_LOOKUP_ADVICE],
+            1, // num fixed
+            LOOKUP_BITS,
+            0,
+            LOG_TOTAL_NUM_ROWS as usize, // k
+        );
+
         Self {
-            modexp_config,
-            rangecheck_config,
+            range,
             modexp_table,
         }
     }
 }
 
-impl ModExpCircuitConfig {
-    pub(crate) fn assign_group<F: Field>(
+/// ModExp circuit for precompile modexp. Each op is padded to operands of `size_limit` bytes, so
+/// the rows it takes only depend on the size limit: about quadratic in the limbs of the operands
+/// for each of the bits of the exponent, i.e. cubic in `size_limit`.
+#[derive(Clone, Debug, Default)]
+pub struct ModExpCircuit<F: Field> {
+    /// Maximum number of ModExp operations supported in one instance of the circuit, `None` when
+    /// the circuit is sized to the operations.
+    pub max_ops: Option<usize>,
+    /// Byte length limit of the operands.
+    pub size_limit: usize,
+    /// ModExp operations provided as witness data to the circuit.
+    pub ops: Vec<BigModExp>,
+
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> ModExpCircuit<F> {
+    /// Return a new ModExpCircuit of operands of at most `size_limit` bytes.
+    pub fn new(ops: Vec<BigModExp>, size_limit: usize, max_ops: Option<usize>) -> Self {
+        Self {
+            max_ops,
+            size_limit,
+            ops,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove an input of a
+    /// particular size.
+    pub fn min_num_rows() -> usize {
+        // ModExpCircuit can't determine usable rows independently, see
+        // `EccCircuit::min_num_rows`.
+        let max_blinding_factor = Self::unusable_rows() - 1;
+
+        // same formula as halo2-lib's FlexGate
+        (1 << LOG_TOTAL_NUM_ROWS) - (max_blinding_factor + 3)
+    }
+
+    /// Rows the circuit is sized to: `max_vertical_circuit_rows`, or the
+    /// whole usable area when it is 0.
+    fn circuit_rows(params: &CircuitsParams) -> usize {
+        if params.max_vertical_circuit_rows == 0 {
+            Self::min_num_rows()
+        } else {
+            params.max_vertical_circuit_rows
+        }
+    }
+
+    /// Rows taken by each op with operands of at most `size_limit` bytes, from an upper bound of
+    /// its cells spread over the advice columns of each phase and the lookup advice columns.
+    pub fn rows_per_op(size_limit: usize) -> usize {
+        let [phase1, phase2, lookup] = cells_per_op(size_limit);
+        #[cfg(feature = "onephase")]
+        let columns = [(phase1 + phase2, NUM_ADVICE), (lookup, NUM_LOOKUP_ADVICE)];
+        #[cfg(not(feature = "onephase"))]
+        let columns = [
+            (phase1, NUM_ADVICE),
+            (phase2, NUM_ADVICE_PHASE2),
+            (lookup, NUM_LOOKUP_ADVICE),
+        ];
+        columns
+            .into_iter()
+            .map(|(cells, num_columns)| (cells + num_columns - 1) / num_columns)
+            .max()
+            .unwrap()
+    }
+
+    /// Closed-form row formula of the ModExp op with operands of at most
+    /// `max_modexp_bytes` bytes. The circuit holds as many ops as fit in
+    /// `max_vertical_circuit_rows`, or is sized to the ops when it is 0.
+    pub fn row_formula(params: &CircuitsParams) -> PrecompileRowFormula {
+        let rows_per_op = Self::rows_per_op(modexp_size_limit(params.max_modexp_bytes));
+        PrecompileRowFormula {
+            op: PrecompileCalls::Modexp,
+            rows_per_op,
+            rows_per_pair: 0,
+            pairs_per_op: 0,
+            max_ops: (params.max_vertical_circuit_rows != 0)
+                .then(|| params.max_vertical_circuit_rows / rows_per_op),
+        }
+    }
+
+    /// Assign the ModExp table from the witness only, leaving out the ModExp circuit which would
+    /// constrain it.
+    pub(crate) fn load_table(
         &self,
-        region: &mut Region<F>,
-        table_offset: usize,
-        mut calc_offset: usize,
-        event: &BigModExp,
-        modexp_chip: &ModExpChip<F>,
-        range_check_chip: &mut RangeCheckChip<F>,
-    ) -> Result<usize, Error> {
-        let base = self.assign_value(region, table_offset, self.modexp_table.base, &event.base)?;
-        let exp =
-            self.assign_value(region, table_offset, self.modexp_table.exp, &event.exponent)?;
-        let modulus = self.assign_value(
-            region,
-            table_offset,
-            self.modexp_table.modulus,
-            &event.modulus,
-        )?;
-        let ret = modexp_chip.mod_exp(
-            region,
-            range_check_chip,
-            &mut calc_offset,
-            &base,
-            &exp,
-            &modulus,
-        )?;
-        for i in 0..4 {
-            region.assign_fixed(
-                || format!("modexp table head {}", table_offset + i),
-                self.modexp_table.q_head,
-                table_offset + i,
-                || Value::known(if i == 0 { F::one() } else { F::zero() }),
-            )?;
-
-            ret.limbs[i]
-                .cell
-                .clone()
-                .expect("should has assigned after modexp")
-                .copy_advice(
-                    || "copy to result limbs",
+        config: &ModExpCircuitConfig<F>,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config
+            .modexp_table
+            .dev_load(layouter, &self.ops, challenges)
+    }
+
+    /// Assign witness from the ModExp ops to the circuit.
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        config: &ModExpCircuitConfig<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let n_ops = self.max_ops.unwrap_or(self.ops.len());
+        if self.ops.len() > n_ops {
+            log::error!("modexp ops = {} > max ops = {}", self.ops.len(), n_ops);
+            return Err(Error::Synthesis);
+        }
+        if let Some(op) = self
+            .ops
+            .iter()
+            .find(|op| op.lens().iter().any(|&len| len > self.size_limit))
+        {
+            log::error!(
+                "modexp op with operand lens {:?} > size limit = {}",
+                op.lens(),
+                self.size_limit
+            );
+            return Err(Error::Synthesis);
+        }
+
+        let keccak_rand = challenges.keccak_input();
+        let padding_op = BigModExp::default();
+        let range = &config.range;
+
+        let mut first_pass = SKIP_FIRST_PASS;
+
+        let assigned_ops = layouter.assign_region(
+            || "modexp circuit",
+            |region| {
+                if first_pass {
+                    first_pass = false;
+                    return Ok(vec![]);
+                }
+
+                let mut ctx = Context::new(
                     region,
-                    self.modexp_table.result,
-                    table_offset + i,
-                )?;
+                    ContextParams {
+                        max_rows: range.gate.max_rows,
+                        num_context_ids: 1,
+                        fixed_columns: range.gate.constants.clone(),
+                    },
+                );
+
+                let decomposed_ops = self
+                    .ops
+                    .iter()
+                    .chain(iter::repeat(&padding_op))
+                    .take(n_ops)
+                    .map(|op| self.decompose_op(&mut ctx, range, op))
+                    .collect_vec();
+
+                #[cfg(not(feature = "onephase"))]
+                {
+                    // finalize after first phase.
+                    range.finalize(&mut ctx);
+                    ctx.next_phase();
+                }
+
+                let assigned_ops = decomposed_ops
+                    .iter()
+                    .map(|decomposed_op| {
+                        self.assign_op(&mut ctx, range, decomposed_op, keccak_rand)
+                    })
+                    .collect_vec();
+
+                // Finalize the range config always at the end of assignment.
+                let lookup_cells = range.finalize(&mut ctx);
+                log::info!("total number of lookup cells: {}", lookup_cells);
+                ctx.print_stats(&["ModExpCircuit: range context"]);
+
+                Ok(assigned_ops)
+            },
+        )?;
+
+        layouter.assign_region(
+            || "expose modexp table",
+            |mut region| {
+                let table = &config.modexp_table;
+                for (idx, assigned_op) in assigned_ops.iter().enumerate() {
+                    region.assign_fixed(
+                        || format!("modexp table q_enable at offset = {idx}"),
+                        table.q_enable,
+                        idx,
+                        || Value::known(F::one()),
+                    )?;
+                    for (cell, column) in [
+                        (assigned_op.input_rlc, table.input_rlc),
+                        (assigned_op.input_len, table.input_len),
+                        (assigned_op.is_valid, table.is_valid),
+                        (assigned_op.input_lens[0], table.base_len),
+                        (assigned_op.input_lens[1], table.exp_len),
+                        (assigned_op.input_lens[2], table.modulus_len),
+                        (assigned_op.exp_bit_len, table.exp_bit_len),
+                        (assigned_op.output_rlc, table.output_rlc),
+                    ] {
+                        cell.copy_advice(&mut region, column, idx);
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Indicator of `idx` over `0..=max`, constraining `idx <= max`.
+    fn indicator(
+        &self,
+        ctx: &mut Context<F>,
+        range: &RangeConfig<F>,
+        idx: AssignedValue<F>,
+        max: usize,
+    ) -> Vec<AssignedValue<F>> {
+        let gate = range.gate();
+        let indicator = gate.idx_to_indicator(ctx, QuantumCell::Existing(idx), max + 1);
+        let sum = gate.sum(ctx, indicator.iter().map(|&x| QuantumCell::Existing(x)));
+        gate.assert_is_const(ctx, &sum, F::one());
+        indicator
+    }
+
+    /// Assign the bytes of a value, range checked.
+    fn assign_bytes(
+        &self,
+        ctx: &mut Context<F>,
+        range: &RangeConfig<F>,
+        bytes: impl IntoIterator<Item = u8>,
+    ) -> Vec<AssignedValue<F>> {
+        let cells = range.gate().assign_witnesses(
+            ctx,
+            bytes
+                .into_iter()
+                .map(|byte| Value::known(F::from(byte as u64)))
+                .collect(),
+        );
+        for cell in &cells {
+            range.range_check(ctx, cell, 8);
         }
-        Ok(calc_offset)
+        cells
     }
 
-    fn assign_value<F: Field>(
+    /// Assign a big integer of `n_limbs` range checked limbs.
+    fn assign_biguint(
         &self,
-        region: &mut Region<F>,
-        offset: usize,
-        col: Column<Advice>,
-        value: &Word,
-    ) -> Result<Number<F>, Error> {
-        let limbs_v = ModExpTable::split_u256_108bit_limbs(value);
-        let native_v = ModExpTable::native_u256(value);
-        let mut limbs = Vec::new();
-
-        for (i, limb) in limbs_v.into_iter().enumerate() {
-            let fv = F::from_u128(limb);
-            let c = region.assign_advice(
-                || "assign modexp limb",
-                col,
-                offset + i,
-                || Value::known(fv),
-            )?;
-            limbs.push(Limb::new(Some(c), fv));
-        }
-        let c = region.assign_advice(
-            || "assign modexp native",
-            col,
-            offset + 3,
-            || Value::known(native_v),
-        )?;
-        limbs.push(Limb::new(Some(c), native_v));
-        Ok(Number {
-            limbs: limbs.try_into().expect("just 4 pushes"),
-        })
+        ctx: &mut Context<F>,
+        range: &RangeConfig<F>,
+        value: BigUint,
+        n_limbs: usize,
+    ) -> BigUintAssigned<F> {
+        let limbs = range.gate().assign_witnesses(
+            ctx,
+            biguint_to_limbs(&value, n_limbs)
+                .into_iter()
+                .map(|limb| Value::known(F::from(limb)))
+                .collect(),
+        );
+        for limb in &limbs {
+            range.range_check(ctx, limb, N_BITS_LIMB);
+        }
+        BigUintAssigned { limbs, value }
     }
-}
 
-const MODEXPCONFIG_EACH_CHIP_ROWS: usize = 39962;
+    /// Big integer of the big-endian `bytes`, right-aligned in whole limbs.
+    fn bytes_to_biguint(
+        &self,
+        ctx: &mut Context<F>,
+        range: &RangeConfig<F>,
+        bytes: &[AssignedValue<F>],
+    ) -> Vec<AssignedValue<F>> {
+        let powers_of_256 = iter::successors(Some(F::one()), |coeff| Some(F::from(256) * coeff))
+            .take(N_BYTES_LIMB)
+            .map(QuantumCell::Constant)
+            .collect_vec();
+        bytes
+            .rchunks(N_BYTES_LIMB)
+            .map(|limb_bytes| {
+                range.gate().inner_product(
+                    ctx,
+                    limb_bytes
+                        .iter()
+                        .rev()
+                        .map(|&byte| QuantumCell::Existing(byte))
+                        .collect_vec(),
+                    powers_of_256[..limb_bytes.len()].to_vec(),
+                )
+            })
+            .collect()
+    }
 
-/// ModExp circuit for precompile modexp
-#[derive(Clone, Debug, Default)]
-pub struct ModExpCircuit<F: Field>(Vec<BigModExp>, std::marker::PhantomData<F>);
+    /// Constrain the big integer of the signed `columns`, i.e. `sum(columns[i] * 2^(64 * i))`, to
+    /// be 0, carrying over the columns.
+    fn assert_big_zero(
+        &self,
+        ctx: &mut Context<F>,
+        range: &RangeConfig<F>,
+        columns: Vec<(AssignedValue<F>, BigInt)>,
+        carry_bits: usize,
+    ) {
+        let gate = range.gate();
+        let n_columns = columns.len();
+        let mut carry = gate.load_zero(ctx);
+        let mut carry_value = BigInt::zero();
+        for (idx, (column, column_value)) in columns.into_iter().enumerate() {
+            let sum = gate.add(
+                ctx,
+                QuantumCell::Existing(column),
+                QuantumCell::Existing(carry),
+            );
+            let sum_value = column_value + &carry_value;
+            if idx + 1 == n_columns {
+                gate.assert_is_const(ctx, &sum, F::zero());
+                break;
+            }
+            debug_assert!((&sum_value % (BigInt::one() << N_BITS_LIMB)).is_zero());
+            carry_value = sum_value >> N_BITS_LIMB;
+            carry = gate.load_witness(ctx, Value::known(bigint_to_field(&carry_value)));
+            let shifted_carry = gate.add(
+                ctx,
+                QuantumCell::Existing(carry),
+                QuantumCell::Constant(F::from_u128(1 << carry_bits)),
+            );
+            range.range_check(ctx, &shifted_carry, carry_bits + 1);
+            let carried = gate.mul(
+                ctx,
+                QuantumCell::Existing(carry),
+                QuantumCell::Constant(F::from_u128(1 << N_BITS_LIMB)),
+            );
+            gate.assert_equal(
+                ctx,
+                QuantumCell::Existing(sum),
+                QuantumCell::Existing(carried),
+            );
+        }
+    }
 
-impl<F: Field> SubCircuit<F> for ModExpCircuit<F> {
-    type Config = ModExpCircuitConfig;
+    /// `a * b mod m`, for `a < m` and `m > 0`: `a * b == q * m + r` with `r < m`, i.e.
+    /// `r + t + 1 == m` for some `t >= 0`.
+    fn mod_mul(
+        &self,
+        ctx: &mut Context<F>,
+        range: &RangeConfig<F>,
+        a: &BigUintAssigned<F>,
+        b: &BigUintAssigned<F>,
+        m: &BigUintAssigned<F>,
+    ) -> BigUintAssigned<F> {
+        let gate = range.gate();
+        let n_limbs = m.limbs.len();
+        let product = &a.value * &b.value;
+        let q = self.assign_biguint(ctx, range, &product / &m.value, n_limbs);
+        let r = self.assign_biguint(ctx, range, &product % &m.value, n_limbs);
+        let t = self.assign_biguint(ctx, range, &m.value - &r.value - 1u64, n_limbs);
 
-    fn unusable_rows() -> usize {
-        // No column queried at more than 4 distinct rotations, so returns 8 as
-        // minimum unusable rows.
-        8
+        let limbs = |x: &BigUintAssigned<F>| {
+            biguint_to_limbs(&x.value, n_limbs)
+                .into_iter()
+                .map(BigInt::from)
+                .collect_vec()
+        };
+        let [a_limbs, b_limbs, m_limbs, q_limbs, r_limbs, t_limbs] =
+            [a, b, m, &q, &r, &t].map(limbs);
+
+        // a * b - q * m - r
+        let columns = (0..2 * n_limbs - 1)
+            .map(|k| {
+                let idx = k.saturating_sub(n_limbs - 1)..=k.min(n_limbs - 1);
+                let mut product_column = |x: &BigUintAssigned<F>, y: &BigUintAssigned<F>| {
+                    gate.inner_product(
+                        ctx,
+                        idx.clone()
+                            .map(|i| QuantumCell::Existing(x.limbs[i]))
+                            .collect_vec(),
+                        idx.clone()
+                            .map(|i| QuantumCell::Existing(y.limbs[k - i]))
+                            .collect_vec(),
+                    )
+                };
+                let ab = product_column(a, b);
+                let qm = product_column(&q, m);
+                let mut column =
+                    gate.sub(ctx, QuantumCell::Existing(ab), QuantumCell::Existing(qm));
+                let mut value: BigInt = idx
+                    .clone()
+                    .map(|i| &a_limbs[i] * &b_limbs[k - i] - &q_limbs[i] * &m_limbs[k - i])
+                    .sum();
+                if k < n_limbs {
+                    column = gate.sub(
+                        ctx,
+                        QuantumCell::Existing(column),
+                        QuantumCell::Existing(r.limbs[k]),
+                    );
+                    value -= &r_limbs[k];
+                }
+                (column, value)
+            })
+            .collect_vec();
+        self.assert_big_zero(ctx, range, columns, carry_bits(n_limbs));
+
+        // r + t + 1 - m
+        let columns = (0..n_limbs)
+            .map(|k| {
+                let sum = gate.sum(
+                    ctx,
+                    [
+                        QuantumCell::Existing(r.limbs[k]),
+                        QuantumCell::Existing(t.limbs[k]),
+                        QuantumCell::Constant(F::from((k == 0) as u64)),
+                    ],
+                );
+                let column = gate.sub(
+                    ctx,
+                    QuantumCell::Existing(sum),
+                    QuantumCell::Existing(m.limbs[k]),
+                );
+                let value = &r_limbs[k] + &t_limbs[k] + BigInt::from((k == 0) as u64) - &m_limbs[k];
+                (column, value)
+            })
+            .collect_vec();
+        self.assert_big_zero(ctx, range, columns, 1);
+
+        r
     }
 
-    fn new_from_block(block: &witness::Block) -> Self {
-        let event_limit = block.circuits_params.max_keccak_rows / MODEXPCONFIG_EACH_CHIP_ROWS;
+    /// Decompose an operand of `len` bytes, given by its big-endian `value`, into bytes
+    /// right-aligned in `n_bytes` bytes, the leading bytes past its length being 0.
+    fn decompose_operand(
+        &self,
+        ctx: &mut Context<F>,
+        range: &RangeConfig<F>,
+        value: &[u8],
+        len: AssignedValue<F>,
+        n_bytes: usize,
+    ) -> OperandDecomposed<F> {
+        let gate = range.gate();
+        let bytes = self.assign_bytes(
+            ctx,
+            range,
+            iter::repeat(0)
+                .take(n_bytes - value.len())
+                .chain(value.iter().copied()),
+        );
+        let len_indicator = self.indicator(ctx, range, len, n_bytes);
 
-        let mut exp_events = block.get_big_modexp();
-        if event_limit != 0 {
-            assert!(
-                exp_events.len() <= event_limit,
-                "no enough rows for modexp circuit, expected {}, limit {}",
-                exp_events.len(),
-                event_limit,
+        // is_value[j] = sum(len_indicator[n_bytes - j..]), i.e. j >= n_bytes - len.
+        let mut is_value = gate.load_zero(ctx);
+        for (j, &byte) in bytes.iter().enumerate() {
+            is_value = gate.add(
+                ctx,
+                QuantumCell::Existing(is_value),
+                QuantumCell::Existing(len_indicator[n_bytes - j]),
+            );
+            let masked = gate.mul(
+                ctx,
+                QuantumCell::Existing(byte),
+                QuantumCell::Existing(is_value),
+            );
+            gate.assert_equal(
+                ctx,
+                QuantumCell::Existing(masked),
+                QuantumCell::Existing(byte),
             );
-            exp_events.resize(event_limit, Default::default());
-            log::info!("modexp circuit work with maximum {} entries", event_limit);
         }
 
-        Self(exp_events, Default::default())
+        OperandDecomposed {
+            bytes,
+            len_indicator,
+        }
     }
 
-    fn min_num_rows_block(block: &witness::Block) -> (usize, usize) {
-        let exp_events = block.get_big_modexp();
-        let real_len = exp_events.len() * MODEXPCONFIG_EACH_CHIP_ROWS;
-        (
-            real_len,
-            real_len
-                .max(block.circuits_params.max_keccak_rows)
-                .max(4096),
+    /// Decompose a ModExp op: parse its input and exponentiate, in the first phase.
+    fn decompose_op(
+        &self,
+        ctx: &mut Context<F>,
+        range: &RangeConfig<F>,
+        op: &BigModExp,
+    ) -> ModExpDecomposed<F> {
+        let gate = range.gate();
+        let n_limbs = n_limbs(self.size_limit);
+        let n_bytes = n_limbs * N_BYTES_LIMB;
+
+        // input bytes, right padded with zeros.
+        let input_bytes = self.assign_bytes(
+            ctx,
+            range,
+            op.input
+                .iter()
+                .copied()
+                .chain(iter::repeat(0))
+                .take(MODEXP_INPUT_LIMIT),
+        );
+        let input_len = gate.load_witness(ctx, Value::known(F::from(op.input.len() as u64)));
+        let input_len_indicator = self.indicator(ctx, range, input_len, MODEXP_INPUT_LIMIT);
+        // is_input[i] = sum(input_len_indicator[i + 1..]), i.e. i < input_len.
+        let mut is_input = gate.load_zero(ctx);
+        for (i, &byte) in input_bytes.iter().enumerate().rev() {
+            is_input = gate.add(
+                ctx,
+                QuantumCell::Existing(is_input),
+                QuantumCell::Existing(input_len_indicator[i + 1]),
+            );
+            let masked = gate.mul(
+                ctx,
+                QuantumCell::Existing(byte),
+                QuantumCell::Existing(is_input),
+            );
+            gate.assert_equal(
+                ctx,
+                QuantumCell::Existing(masked),
+                QuantumCell::Existing(byte),
+            );
+        }
+
+        // the header of the lengths of [base, exponent, modulus], in 32-byte words.
+        let header = [0, 1, 2].map(|i| {
+            let word = &input_bytes[i * 32..(i + 1) * 32];
+            let high_bytes = gate.sum(ctx, word[..30].iter().map(|&b| QuantumCell::Existing(b)));
+            let high_bytes_zero = gate.is_zero(ctx, &high_bytes);
+            let len = gate.mul_add(
+                ctx,
+                QuantumCell::Existing(word[30]),
+                QuantumCell::Constant(F::from(256)),
+                QuantumCell::Existing(word[31]),
+            );
+            let len_within_limit = range.is_less_than(
+                ctx,
+                QuantumCell::Existing(len),
+                QuantumCell::Constant(F::from(self.size_limit as u64 + 1)),
+                N_BITS_LEN,
+            );
+            let len_valid = gate.and(
+                ctx,
+                QuantumCell::Existing(high_bytes_zero),
+                QuantumCell::Existing(len_within_limit),
+            );
+            (len_valid, len)
+        });
+        let is_valid = gate.and_many(
+            ctx,
+            header
+                .iter()
+                .map(|&(len_valid, _)| QuantumCell::Existing(len_valid))
+                .collect(),
+        );
+        let input_lens = header.map(|(_, len)| {
+            gate.mul(
+                ctx,
+                QuantumCell::Existing(is_valid),
+                QuantumCell::Existing(len),
+            )
+        });
+        let parsed_len = gate.sum(
+            ctx,
+            iter::once(QuantumCell::Constant(F::from(96)))
+                .chain(input_lens.iter().map(|&len| QuantumCell::Existing(len))),
+        );
+        let parsed_len_indicator = self.indicator(ctx, range, parsed_len, MODEXP_INPUT_LIMIT);
+
+        let values = [&op.base, &op.exponent, &op.modulus];
+        let operands = [0, 1, 2]
+            .map(|i| self.decompose_operand(ctx, range, values[i], input_lens[i], n_bytes));
+        let [base, exponent, mut modulus] = [0, 1, 2].map(|i| BigUintAssigned {
+            limbs: self.bytes_to_biguint(ctx, range, &operands[i].bytes),
+            value: BigUint::from_bytes_be(values[i]),
+        });
+
+        // exponentiate modulo max(modulus, 1), the result being 0 for the modulus 0.
+        let modulus_zero = gate.sum(
+            ctx,
+            modulus
+                .limbs
+                .iter()
+                .map(|&limb| QuantumCell::Existing(limb)),
+        );
+        let modulus_zero = gate.is_zero(ctx, &modulus_zero);
+        modulus.limbs[0] = gate.add(
+            ctx,
+            QuantumCell::Existing(modulus.limbs[0]),
+            QuantumCell::Existing(modulus_zero),
+        );
+        if modulus.value.is_zero() {
+            modulus.value = BigUint::one();
+        }
+
+        let one = BigUintAssigned {
+            limbs: iter::once(gate.load_constant(ctx, F::one()))
+                .chain((1..n_limbs).map(|_| gate.load_zero(ctx)))
+                .collect(),
+            value: BigUint::one(),
+        };
+        let mut acc = self.mod_mul(ctx, range, &one, &one, &modulus);
+        let mut seen_bit = gate.load_zero(ctx);
+        let mut exp_bit_len = gate.load_zero(ctx);
+        // square and multiply from the most significant bit.
+        for (limb_idx, limb) in exponent.limbs.iter().enumerate().rev() {
+            let bits = gate.num_to_bits(ctx, limb, N_BITS_LIMB);
+            for (bit_idx, &bit) in bits.iter().enumerate().rev() {
+                let squared = self.mod_mul(ctx, range, &acc, &acc, &modulus);
+                let multiplied = self.mod_mul(ctx, range, &squared, &base, &modulus);
+                let bit_value = exponent
+                    .value
+                    .bit((limb_idx * N_BITS_LIMB + bit_idx) as u64);
+                acc = BigUintAssigned {
+                    limbs: squared
+                        .limbs
+                        .iter()
+                        .zip_eq(&multiplied.limbs)
+                        .map(|(&squared, &multiplied)| {
+                            gate.select(
+                                ctx,
+                                QuantumCell::Existing(multiplied),
+                                QuantumCell::Existing(squared),
+                                QuantumCell::Existing(bit),
+                            )
+                        })
+                        .collect(),
+                    value: if bit_value {
+                        multiplied.value
+                    } else {
+                        squared.value
+                    },
+                };
+
+                seen_bit = gate.or(
+                    ctx,
+                    QuantumCell::Existing(seen_bit),
+                    QuantumCell::Existing(bit),
+                );
+                exp_bit_len = gate.add(
+                    ctx,
+                    QuantumCell::Existing(exp_bit_len),
+                    QuantumCell::Existing(seen_bit),
+                );
+            }
+        }
+
+        // the result is less than the modulus, so it has the byte length of the modulus.
+        let result = acc.value.to_bytes_be();
+        let result_bytes = self.assign_bytes(
+            ctx,
+            range,
+            iter::repeat(0).take(n_bytes - result.len()).chain(result),
+        );
+        for (limb, &acc_limb) in self
+            .bytes_to_biguint(ctx, range, &result_bytes)
+            .into_iter()
+            .zip_eq(&acc.limbs)
+        {
+            gate.assert_equal(
+                ctx,
+                QuantumCell::Existing(limb),
+                QuantumCell::Existing(acc_limb),
+            );
+        }
+
+        ModExpDecomposed {
+            input_bytes,
+            input_len,
+            input_len_indicator,
+            parsed_len_indicator,
+            is_valid,
+            input_lens,
+            operands,
+            exp_bit_len,
+            result_bytes,
+        }
+    }
+
+    /// Assign the RLCs of a decomposed ModExp op, in the second phase: the operands are the input
+    /// bytes after the header, i.e. the RLC of the input up to the end of the modulus is
+    /// `((rlc(header) * r^Bsize + rlc(B)) * r^Esize + rlc(E)) * r^Msize + rlc(M)`.
+    fn assign_op(
+        &self,
+        ctx: &mut Context<F>,
+        range: &RangeConfig<F>,
+        decomposed: &ModExpDecomposed<F>,
+        keccak_rand: Value<F>,
+    ) -> ModExpAssigned<F> {
+        let gate = range.gate();
+        let n_bytes = decomposed.result_bytes.len();
+        let rand = gate.load_witness(ctx, keccak_rand);
+
+        // prefix_rlcs[i] = RLC of the input bytes [..i]
+        let mut prefix_rlcs = vec![gate.load_zero(ctx)];
+        for &byte in &decomposed.input_bytes {
+            let prefix_rlc = gate.mul_add(
+                ctx,
+                QuantumCell::Existing(*prefix_rlcs.last().unwrap()),
+                QuantumCell::Existing(rand),
+                QuantumCell::Existing(byte),
+            );
+            prefix_rlcs.push(prefix_rlc);
+        }
+        let rlc_at = |ctx: &mut Context<F>, indicator: &[AssignedValue<F>]| {
+            gate.inner_product(
+                ctx,
+                prefix_rlcs
+                    .iter()
+                    .map(|&x| QuantumCell::Existing(x))
+                    .collect_vec(),
+                indicator
+                    .iter()
+                    .map(|&x| QuantumCell::Existing(x))
+                    .collect_vec(),
+            )
+        };
+        let input_rlc = rlc_at(ctx, &decomposed.input_len_indicator);
+        let parsed_rlc = rlc_at(ctx, &decomposed.parsed_len_indicator);
+
+        // rand^0, ..., rand^n_bytes
+        let mut powers = vec![QuantumCell::Constant(F::one())];
+        let mut power = rand;
+        for _ in 0..n_bytes {
+            powers.push(QuantumCell::Existing(power));
+            power = gate.mul(
+                ctx,
+                QuantumCell::Existing(power),
+                QuantumCell::Existing(rand),
+            );
+        }
+        let bytes_rlc = |ctx: &mut Context<F>, bytes: &[AssignedValue<F>]| {
+            gate.inner_product(
+                ctx,
+                bytes
+                    .iter()
+                    .map(|&byte| QuantumCell::Existing(byte))
+                    .collect_vec(),
+                powers[..n_bytes].iter().rev().cloned().collect_vec(),
+            )
+        };
+
+        let mut operands_rlc = prefix_rlcs[96];
+        for operand in &decomposed.operands {
+            let rand_pow_len = gate.inner_product(
+                ctx,
+                operand
+                    .len_indicator
+                    .iter()
+                    .map(|&x| QuantumCell::Existing(x))
+                    .collect_vec(),
+                powers.clone(),
+            );
+            let operand_rlc = bytes_rlc(ctx, &operand.bytes);
+            operands_rlc = gate.mul_add(
+                ctx,
+                QuantumCell::Existing(operands_rlc),
+                QuantumCell::Existing(rand_pow_len),
+                QuantumCell::Existing(operand_rlc),
+            );
+        }
+        gate.assert_equal(
+            ctx,
+            QuantumCell::Existing(operands_rlc),
+            QuantumCell::Existing(parsed_rlc),
+        );
+
+        ModExpAssigned {
+            input_rlc,
+            input_len: decomposed.input_len,
+            is_valid: decomposed.is_valid,
+            input_lens: decomposed.input_lens,
+            exp_bit_len: decomposed.exp_bit_len,
+            output_rlc: bytes_rlc(ctx, &decomposed.result_bytes),
+        }
+    }
+}
+
+impl<F: Field> SubCircuit<F> for ModExpCircuit<F> {
+    type Config = ModExpCircuitConfig<F>;
+
+    /// Returns number of unusable rows of the SubCircuit, which should be
+    /// `meta.blinding_factors() + 1`.
+    fn unusable_rows() -> usize {
+        [
+            KeccakCircuit::<F>::unusable_rows(),
+            EvmCircuit::<F>::unusable_rows(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap()
+    }
+
+    fn new_from_block(block: &witness::Block) -> Self {
+        let params = &block.circuits_params;
+        let max_ops = Self::row_formula(params).max_ops;
+        if let Some(max_ops) = max_ops {
+            log::info!("modexp circuit work with maximum {} entries", max_ops);
+        }
+        Self::new(
+            block.get_big_modexp(),
+            modexp_size_limit(params.max_modexp_bytes),
+            max_ops,
         )
     }
 
+    fn min_num_rows_block(block: &witness::Block) -> (usize, usize) {
+        let row_num = Self::circuit_rows(&block.circuits_params);
+        // As the ECC circuit, the minimum rows represent a percentage of the used capacity.
+        let min_row_num =
+            Self::row_formula(&block.circuits_params).rows(block.get_big_modexp().len());
+
+        (min_row_num, row_num)
+    }
+
     fn synthesize_sub(
         &self,
         config: &Self::Config,
-        _challenges: &Challenges<Value<F>>,
+        challenges: &Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
-        let modexp_chip = ModExpChip::new(config.modexp_config.clone());
-        let mut range_chip = RangeCheckChip::new(config.rangecheck_config.clone());
-
-        layouter.assign_region(
-            || "modexp circuit",
-            |mut region| {
-                range_chip.initialize(&mut region)?;
-                let _modexp_count = self.0.len();
-                let mut calc_offset = 0;
-                for (n, event) in self.0.iter().enumerate() {
-                    calc_offset = config.assign_group(
-                        &mut region,
-                        n * 4,
-                        calc_offset,
-                        event,
-                        &modexp_chip,
-                        &mut range_chip,
-                    )?;
-                }
-                //assert_eq!(max(calc_offset, range_chip.offset), MODEXPCONFIG_EACH_CHIP_ROWS *
-                // modexp_count);
-                Ok(())
-            },
-        )?;
+        config.range.load_lookup_table(layouter)?;
+        self.assign(layouter, config, challenges)
+    }
 
-        config.modexp_table.fill_blank(layouter)
+    fn precompile_row_formulas(params: &CircuitsParams) -> Vec<PrecompileRowFormula> {
+        vec![Self::row_formula(params)]
     }
 }
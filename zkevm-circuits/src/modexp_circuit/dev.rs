@@ -1,5 +1,5 @@
 use super::*;
-use crate::util::MockChallenges;
+use crate::util::Challenges;
 use halo2_proofs::{
     circuit::SimpleFloorPlanner,
     halo2curves::bn256::Fr,
@@ -7,7 +7,7 @@ use halo2_proofs::{
 };
 
 impl Circuit<Fr> for ModExpCircuit<Fr> {
-    type Config = (ModExpCircuitConfig, MockChallenges);
+    type Config = (ModExpCircuitConfig<Fr>, Challenges);
     type FloorPlanner = SimpleFloorPlanner;
     #[cfg(feature = "circuit-params")]
     type Params = ();
@@ -18,9 +18,9 @@ impl Circuit<Fr> for ModExpCircuit<Fr> {
 
     fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
         let modexp_table = ModExpTable::construct(meta);
-        let challenge = MockChallenges::construct(meta);
+        let challenge = Challenges::construct(meta);
         (
-            <ModExpCircuitConfig as SubCircuitConfig<Fr>>::new(meta, modexp_table),
+            <ModExpCircuitConfig<Fr> as SubCircuitConfig<Fr>>::new(meta, modexp_table),
             challenge,
         )
     }
@@ -1,34 +1,35 @@
 #![allow(unused_imports)]
 use super::*;
 
-use eth_types::U256;
-use halo2_proofs::dev::MockProver;
+use bus_mapping::precompile::MODEXP_SIZE_LIMIT;
+use eth_types::{Word, U256};
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+
+fn run(ops: Vec<BigModExp>, size_limit: usize) {
+    let test_circuit = ModExpCircuit::<Fr>::new(ops, size_limit, None);
+    let prover = MockProver::run(LOG_TOTAL_NUM_ROWS, &test_circuit, vec![]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
 
 #[test]
 fn test_modexp_circuit_00() {
     let event1 = construct_modexp(Word::from(1u128), Word::from(3u128), Word::from(7u128));
 
-    let test_circuit = ModExpCircuit(vec![event1], Default::default());
-    let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
-    assert_eq!(prover.verify(), Ok(()));
+    run(vec![event1], MODEXP_SIZE_LIMIT);
 }
 
 #[test]
 fn test_modexp_circuit_01() {
     let event1 = construct_modexp(Word::from(1u128), Word::from(2u128), Word::from(7u128));
 
-    let test_circuit = ModExpCircuit(vec![event1], Default::default());
-    let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
-    assert_eq!(prover.verify(), Ok(()));
+    run(vec![event1], MODEXP_SIZE_LIMIT);
 }
 #[test]
 fn test_modexp_circuit_02() {
     let event1 = construct_modexp(Word::from(2u128), Word::from(2u128), Word::from(7u128));
     let event2 = construct_modexp(Word::from(3u128), Word::from(21u128), Word::from(78u128));
 
-    let test_circuit = ModExpCircuit(vec![event1, event2], Default::default());
-    let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
-    assert_eq!(prover.verify(), Ok(()));
+    run(vec![event1, event2], MODEXP_SIZE_LIMIT);
 }
 
 // test all zeros case (exp == mod == base == 0)
@@ -36,22 +37,63 @@ fn test_modexp_circuit_02() {
 fn test_modexp_circuit_03() {
     let event1 = construct_modexp(Word::from(0u128), Word::from(0u128), Word::from(0u128));
 
-    let test_circuit = ModExpCircuit(vec![event1], Default::default());
-    let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
-    assert_eq!(prover.verify(), Ok(()));
+    run(vec![event1], MODEXP_SIZE_LIMIT);
+}
+
+// operands of 64 bytes, beyond the scroll size limit
+#[test]
+fn test_modexp_circuit_64_bytes() {
+    let base = [0xab; 64];
+    let exponent = [0x03; 2];
+    let modulus = [0xfe; 64];
+    let event1 = BigModExp::new(modexp_input(&base, &exponent, &modulus), 64);
+    assert!(event1.valid);
+
+    run(vec![event1], 64);
+}
+
+// the operand lengths are over the size limit, and the input is truncated
+#[test]
+fn test_modexp_circuit_invalid() {
+    let event1 = BigModExp::new(modexp_input(&[1; 33], &[1], &[7]), MODEXP_SIZE_LIMIT);
+    assert!(!event1.valid);
+    let mut truncated = modexp_input(&[2], &[2], &[7]);
+    truncated.truncate(98);
+    let event2 = BigModExp::new(truncated, MODEXP_SIZE_LIMIT);
+    assert!(event2.valid);
+
+    run(vec![event1, event2], MODEXP_SIZE_LIMIT);
+}
+
+// an op parsed against a larger size limit than the circuit's cannot be assigned
+#[test]
+fn test_modexp_circuit_over_size_limit() {
+    let event1 = BigModExp::new(modexp_input(&[1; 64], &[1], &[7]), 64);
+
+    let test_circuit = ModExpCircuit::<Fr>::new(vec![event1], MODEXP_SIZE_LIMIT, None);
+    assert!(MockProver::run(LOG_TOTAL_NUM_ROWS, &test_circuit, vec![]).is_err());
+}
+
+fn modexp_input(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Vec<u8> {
+    [base.len(), exponent.len(), modulus.len()]
+        .into_iter()
+        .flat_map(|len| {
+            let mut word = [0u8; 32];
+            Word::from(len).to_big_endian(&mut word);
+            word
+        })
+        .chain(base.iter().copied())
+        .chain(exponent.iter().copied())
+        .chain(modulus.iter().copied())
+        .collect()
 }
 
 fn construct_modexp(base: U256, exp: U256, modulus: U256) -> BigModExp {
-    let result = if modulus == Word::zero() {
-        Word::zero()
-    } else {
-        base.pow(exp).div_mod(modulus).1
-    };
-
-    BigModExp {
-        base,
-        exponent: exp,
-        modulus,
-        result,
-    }
+    let [base, exp, modulus] = [base, exp, modulus].map(|word| {
+        let mut bytes = [0u8; 32];
+        word.to_big_endian(&mut bytes);
+        bytes
+    });
+
+    BigModExp::new(modexp_input(&base, &exp, &modulus), MODEXP_SIZE_LIMIT)
 }
@@ -0,0 +1,151 @@
+use crate::util::Field;
+use bus_mapping::precompile::MODEXP_INPUT_LIMIT;
+use halo2_base::AssignedValue;
+use num::{BigInt, BigUint, Signed};
+
+// Total number of rows allowable for ModExp circuit
+pub const LOG_TOTAL_NUM_ROWS: u32 = 20;
+
+pub(super) const NUM_ADVICE: usize = 30;
+pub(super) const NUM_ADVICE_PHASE2: usize = 1;
+pub(super) const NUM_LOOKUP_ADVICE: usize = 4;
+pub(super) const LOOKUP_BITS: usize = 16;
+
+/// Big integers are of 64-bit limbs.
+pub(super) const N_BITS_LIMB: usize = 64;
+pub(super) const N_BYTES_LIMB: usize = 8;
+/// Bits of the length in the header of the input once its high bytes are 0.
+pub(super) const N_BITS_LEN: usize = 17;
+
+/// Number of limbs of the operands of at most `size_limit` bytes.
+pub(super) fn n_limbs(size_limit: usize) -> usize {
+    ((size_limit + N_BYTES_LIMB - 1) / N_BYTES_LIMB).max(1)
+}
+
+/// Bits of the signed carries of the columns of a product of `n_limbs` limbs.
+pub(super) fn carry_bits(n_limbs: usize) -> usize {
+    N_BITS_LIMB + (usize::BITS - n_limbs.leading_zeros()) as usize + 2
+}
+
+// Cell usage accounting of the gates of halo2-base, rounded up.
+const RANGE_CHECK_CELLS_PER_LOOKUP: usize = 4;
+const INDICATOR_CELLS: usize = 8;
+const GATE_CELLS: usize = 4;
+
+/// Upper bounds of the cells of a range check of `bits` bits, in advice and lookup advice columns.
+fn range_check_cells(bits: usize) -> [usize; 2] {
+    let n_lookups = (bits + LOOKUP_BITS - 1) / LOOKUP_BITS + 1;
+    [RANGE_CHECK_CELLS_PER_LOOKUP * n_lookups, n_lookups]
+}
+
+/// Upper bounds of the cells of each op, in the phase 1 advice, phase 2 advice and lookup advice
+/// columns. Each op is padded to operands of `size_limit` bytes, and exponentiates over all the
+/// bits of its exponent, so the cells only depend on `size_limit`.
+pub(super) fn cells_per_op(size_limit: usize) -> [usize; 3] {
+    let n = n_limbs(size_limit);
+    let n_bytes = n * N_BYTES_LIMB;
+    let [rc_byte, rc_byte_lookup] = range_check_cells(8);
+    let [rc_limb, rc_limb_lookup] = range_check_cells(N_BITS_LIMB);
+    let [rc_carry, rc_carry_lookup] = range_check_cells(carry_bits(n) + 1);
+    let [rc_len, rc_len_lookup] = range_check_cells(N_BITS_LEN);
+
+    // a * b == q * m + r and r < m: the inner products of the columns, the witnesses q, r, t of
+    // range checked limbs, and the carries of the columns.
+    let mod_mul = 6 * n * n
+        + (2 * n - 1) * (5 * GATE_CELLS + rc_carry)
+        + 3 * n * (1 + rc_limb)
+        + n * (5 * GATE_CELLS + range_check_cells(2)[0]);
+    let mod_mul_lookup =
+        (2 * n - 1) * rc_carry_lookup + 3 * n * rc_limb_lookup + n * range_check_cells(2)[1];
+    // square, multiply and select for each bit of the exponent, with its bit decomposition.
+    let exp_bits = N_BITS_LIMB * n;
+    let per_bit = 2 * mod_mul + (n + 3) * GATE_CELLS + 4 * GATE_CELLS;
+
+    let input = MODEXP_INPUT_LIMIT * (1 + rc_byte + 2 * GATE_CELLS)
+        + 2 * INDICATOR_CELLS * (MODEXP_INPUT_LIMIT + 1)
+        + 3 * (3 * 32 + 6 * GATE_CELLS + rc_len);
+    let operands = 4
+        * (n_bytes * (1 + rc_byte + 2 * GATE_CELLS)
+            + INDICATOR_CELLS * (n_bytes + 1)
+            + n * (3 * N_BYTES_LIMB + 1 + GATE_CELLS));
+
+    let phase1 = input + operands + (exp_bits + 2) * per_bit;
+    let phase2 = MODEXP_INPUT_LIMIT * GATE_CELLS
+        + 2 * (3 * (MODEXP_INPUT_LIMIT + 1) + 1)
+        + n_bytes * GATE_CELLS
+        + 4 * (2 * (3 * (n_bytes + 1) + 1) + GATE_CELLS);
+    let lookup = MODEXP_INPUT_LIMIT * rc_byte_lookup
+        + 3 * rc_len_lookup
+        + 4 * n_bytes * rc_byte_lookup
+        + (exp_bits + 2) * 2 * mod_mul_lookup;
+
+    [phase1, phase2, lookup]
+}
+
+/// Big integer of 64-bit limbs, little-endian, with its value.
+#[derive(Clone)]
+pub(super) struct BigUintAssigned<F: Field> {
+    pub limbs: Vec<AssignedValue<F>>,
+    pub value: BigUint,
+}
+
+/// An operand of the input, right-aligned in its bytes.
+pub(super) struct OperandDecomposed<F: Field> {
+    /// Big-endian bytes, of the operand size limit rounded up to limbs.
+    pub bytes: Vec<AssignedValue<F>>,
+    /// Indicator of the byte length of the operand.
+    pub len_indicator: Vec<AssignedValue<F>>,
+}
+
+/// Decomposed state of a ModExp op, i.e. everything but the RLCs.
+pub(super) struct ModExpDecomposed<F: Field> {
+    /// Input bytes, right padded with zeros to `MODEXP_INPUT_LIMIT` bytes.
+    pub input_bytes: Vec<AssignedValue<F>>,
+    pub input_len: AssignedValue<F>,
+    /// Indicator of the input length.
+    pub input_len_indicator: Vec<AssignedValue<F>>,
+    /// Indicator of the byte length of the header and the operands.
+    pub parsed_len_indicator: Vec<AssignedValue<F>>,
+    pub is_valid: AssignedValue<F>,
+    pub input_lens: [AssignedValue<F>; 3],
+    /// [base, exponent, modulus]
+    pub operands: [OperandDecomposed<F>; 3],
+    pub exp_bit_len: AssignedValue<F>,
+    /// Big-endian bytes of the result, right-aligned as the operands.
+    pub result_bytes: Vec<AssignedValue<F>>,
+}
+
+/// Assigned state of a ModExp op, to be copied to the ModExp table.
+pub(super) struct ModExpAssigned<F: Field> {
+    pub input_rlc: AssignedValue<F>,
+    pub input_len: AssignedValue<F>,
+    pub is_valid: AssignedValue<F>,
+    pub input_lens: [AssignedValue<F>; 3],
+    pub exp_bit_len: AssignedValue<F>,
+    pub output_rlc: AssignedValue<F>,
+}
+
+/// Little-endian 64-bit limbs of `value`.
+pub(super) fn biguint_to_limbs(value: &BigUint, n_limbs: usize) -> Vec<u64> {
+    let mut limbs = value.to_u64_digits();
+    debug_assert!(limbs.len() <= n_limbs);
+    limbs.resize(n_limbs, 0);
+    limbs
+}
+
+/// Field element of a signed integer smaller than the field modulus.
+pub(super) fn bigint_to_field<F: Field>(value: &BigInt) -> F {
+    let magnitude = value
+        .magnitude()
+        .to_u64_digits()
+        .iter()
+        .rev()
+        .fold(F::zero(), |acc, &digit| {
+            acc * F::from_u128(1 << N_BITS_LIMB) + F::from(digit)
+        });
+    if value.is_negative() {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
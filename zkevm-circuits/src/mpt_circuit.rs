@@ -2,6 +2,8 @@
 //! wrapping of mpt-circuit
 // #[cfg(test)]
 // use crate::mpt_circuit::mpt;
+pub mod keccak;
+
 use crate::{
     table::{LookupTable, MptTable, PoseidonTable},
     util::{Challenges, Field, SubCircuit, SubCircuitConfig},
@@ -86,6 +88,24 @@ impl SubCircuitConfig<Fr> for MptCircuitConfig<Fr> {
     }
 }
 
+impl MptCircuit<Fr> {
+    /// Assign the MPT table from the witness only, leaving out the MPT circuit which would
+    /// constrain it.
+    pub(crate) fn load_table(
+        &self,
+        config: &MptCircuitConfig<Fr>,
+        challenges: &Challenges<Value<Fr>>,
+        layouter: &mut impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.1.load(
+            layouter,
+            &self.mpt_updates,
+            self.row_limit,
+            challenges.evm_word(),
+        )
+    }
+}
+
 impl SubCircuit<Fr> for MptCircuit<Fr> {
     type Config = MptCircuitConfig<Fr>;
 
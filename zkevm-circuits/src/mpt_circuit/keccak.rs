@@ -0,0 +1,1778 @@
+//! The keccak MPT circuit verifies proofs of keys of the standard Ethereum
+//! hexary Merkle Patricia Tries, as returned by `eth_getProof`, against the
+//! keccak roots of the state and storage tries. It is the MPT circuit of the
+//! chains whose state is not a zktrie, i.e. without the `zktrie` feature.
+//!
+//! A proof takes a row per nibble of the hash of its key, and then a row per
+//! byte of its nodes, from the root. The bytes are decoded by a state machine
+//! of [`MptTag`]s, each tag accepting the bytes of its rows in a fixed table,
+//! and each node is looked up in the keccak table with the hash referenced by
+//! its parent. A proof ends at the leaf of its key, or at the node showing
+//! that the trie has no such key, where its root, key and value are exposed
+//! by [`KeccakMptCircuitConfig::lookup_exprs`].
+//!
+//! The nodes must reference their children by hash, and the branches must not
+//! have values, as in the secure tries of the state and the storage. Proofs
+//! through a child inlined in its parent, i.e. a node shorter than 32 bytes,
+//! as the leaves of short values deep in large storage tries, aren't
+//! supported yet.
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{KeccakTable, LookupTable},
+    util::{rlc_be_bytes, Challenges, Field, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::{
+    mpt_proof::{MptProof, MptProofError, EMPTY_TRIE_NODE, EMPTY_TRIE_ROOT},
+    Bytes, H256,
+};
+use ethers_core::utils::{keccak256, rlp::Rlp};
+use gadgets::{
+    is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
+    util::{select, sum, Expr},
+};
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+#[cfg(feature = "onephase")]
+use halo2_proofs::plonk::FirstPhase as SecondPhase;
+#[cfg(not(feature = "onephase"))]
+use halo2_proofs::plonk::SecondPhase;
+
+const MAX_DEGREE: usize = 9;
+
+/// Rows of the nibbles of the hash of the key of a proof.
+const KEY_ROWS: usize = 64;
+
+/// Tag of a row of the keccak MPT circuit: the part of a proof its byte is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumIter)]
+pub enum MptTag {
+    /// Row after the last proof
+    #[default]
+    Padding = 0,
+    /// A nibble of the hash of the key, from the most significant one
+    Key,
+    /// First byte of a node, the header of its list
+    ListHeader,
+    /// A length byte of the header of a long list
+    ListLength,
+    /// Header of a child of a branch: 0x80 if empty, 0xa0 for its hash
+    BranchChild,
+    /// A byte of the hash of a child of a branch or an extension
+    ChildHash,
+    /// Value of a branch, always empty
+    BranchValue,
+    /// Header of the path of an extension or a leaf, unless the path is a
+    /// single byte
+    PathHeader,
+    /// First byte of a hex-prefix encoded path, with its flags
+    PathFlag,
+    /// A byte of two nibbles of a path
+    PathNibbles,
+    /// Header of the child of an extension, 0xa0
+    ExtChild,
+    /// Header of the value of a leaf, or the value itself if a single byte
+    ValueHeader,
+    /// Length byte of the header of a long value
+    ValueLength,
+    /// A byte of the value of a leaf
+    ValueByte,
+}
+
+impl From<MptTag> for usize {
+    fn from(tag: MptTag) -> usize {
+        tag as usize
+    }
+}
+
+const N_TAGS: usize = MptTag::ValueByte as usize + 1;
+
+/// Tags of the bytes of a node.
+const NODE_TAGS: [MptTag; 12] = [
+    MptTag::ListHeader,
+    MptTag::ListLength,
+    MptTag::BranchChild,
+    MptTag::ChildHash,
+    MptTag::BranchValue,
+    MptTag::PathHeader,
+    MptTag::PathFlag,
+    MptTag::PathNibbles,
+    MptTag::ExtChild,
+    MptTag::ValueHeader,
+    MptTag::ValueLength,
+    MptTag::ValueByte,
+];
+
+/// Rows `[tag, byte, hi, lo]` of the fixed table of the bytes accepted by each
+/// tag. `hi` and `lo` are the nibbles of the byte, except for the headers:
+/// - list header: `hi` is 1 for a long list, and `lo` the length of its
+///   payload, or of its length for a long list;
+/// - path header: `lo` is the length of the path;
+/// - value header: `hi` is 0 for a single byte value, 1 for a short value and
+///   2 for a long one, and `lo` the length of the short value, or 1, the
+///   length of the length of the long one.
+fn fixed_table_rows() -> Vec<[u64; 4]> {
+    let mut rows = vec![[MptTag::Padding as u64, 0, 0, 0]];
+    let mut push = |tag: MptTag, byte: u64, hi: u64, lo: u64| rows.push([tag as u64, byte, hi, lo]);
+    for nibble in 0..16 {
+        push(MptTag::Key, nibble, 0, nibble);
+    }
+    for byte in 0xc0..=0xf7 {
+        push(MptTag::ListHeader, byte, 0, byte - 0xc0);
+    }
+    for byte in 0xf8..=0xf9 {
+        push(MptTag::ListHeader, byte, 1, byte - 0xf7);
+    }
+    for byte in 0..=0xff {
+        for tag in [
+            MptTag::ListLength,
+            MptTag::ChildHash,
+            MptTag::PathNibbles,
+            MptTag::ValueLength,
+            MptTag::ValueByte,
+        ] {
+            push(tag, byte, byte >> 4, byte & 0x0f);
+        }
+    }
+    push(MptTag::BranchChild, 0x80, 0, 0);
+    push(MptTag::BranchChild, 0xa0, 1, 0);
+    push(MptTag::BranchValue, 0x80, 0, 0);
+    for byte in 0x81..=0xa1 {
+        push(MptTag::PathHeader, byte, 0, byte - 0x80);
+    }
+    // the odd paths have a nibble in their first byte, the even ones a 0
+    for byte in 0..0x40 {
+        if (byte >> 4) & 1 == 1 || byte & 0x0f == 0 {
+            push(MptTag::PathFlag, byte, byte >> 4, byte & 0x0f);
+        }
+    }
+    push(MptTag::ExtChild, 0xa0, 0, 0);
+    for byte in 0..0x80 {
+        push(MptTag::ValueHeader, byte, 0, 0);
+    }
+    for byte in 0x80..=0xb7 {
+        push(MptTag::ValueHeader, byte, 1, byte - 0x80);
+    }
+    push(MptTag::ValueHeader, 0xb8, 2, 1);
+    rows
+}
+
+/// Config of the keccak MPT circuit.
+#[derive(Clone, Debug)]
+pub struct KeccakMptCircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    /// Enabled on all the rows but the last one, for the constraints to the
+    /// next row.
+    q_step: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_last: Column<Fixed>,
+    /// One-hot [`MptTag`] of the row
+    tags: [Column<Advice>; N_TAGS],
+    /// Byte of the node, or nibble of the key
+    byte: Column<Advice>,
+    /// Decomposition of the byte, see [`fixed_table_rows`]
+    hi: Column<Advice>,
+    lo: Column<Advice>,
+    /// Rows left in the key, in the length of a header, or in a string
+    rem: Column<Advice>,
+    /// Bytes left in the payload of the list of the node
+    list_rem: Column<Advice>,
+    /// Nibbles of the key before the row
+    depth: Column<Advice>,
+    /// Index of the child of a branch
+    child_index: Column<Advice>,
+    /// Nibbles of the key looked up by the row: at `depth` for `key_nibble`,
+    /// and at the depth of the low nibble of the byte for `kn_lo`
+    key_nibble: Column<Advice>,
+    kn_lo: Column<Advice>,
+    is_branch: Column<Advice>,
+    is_leaf: Column<Advice>,
+    /// Whether a path has an odd number of nibbles, or a key nibble is odd
+    is_odd: Column<Advice>,
+    /// Whether the path matches the key so far
+    all_match: Column<Advice>,
+    /// Whether the child of the row is on the path of the key
+    is_selected: Column<Advice>,
+    /// Whether the proof continues to a child of the node
+    cont: Column<Advice>,
+    /// Last row of a node
+    is_end: Column<Advice>,
+    /// Last row of a proof
+    is_proof_end: Column<Advice>,
+    proof_id: Column<Advice>,
+    key_len: Column<Advice>,
+    node_len: Column<Advice>,
+    value_len: Column<Advice>,
+    /// RLC of the root of the trie, over the evm word challenge
+    root: Column<Advice>,
+    /// RLC of the key before hashing, over the keccak input challenge
+    key_rlc: Column<Advice>,
+    /// RLC of the node so far, over the keccak input challenge
+    node_rlc: Column<Advice>,
+    /// RLC of the hash of the node, over the evm word challenge
+    node_hash: Column<Advice>,
+    /// RLC of the hash of the key, or of the child on the path of the key
+    hash_acc: Column<Advice>,
+    /// RLC of the value of the leaf so far, over the keccak input challenge
+    value_rlc: Column<Advice>,
+    rem_is_zero: IsZeroConfig<F>,
+    /// Whether the child of a branch is its last one
+    last_child: IsZeroConfig<F>,
+    /// Whether the child of a branch is the one of the key nibble
+    child_match: IsZeroConfig<F>,
+    /// Whether the nibbles of a path match the ones of the key
+    hi_match: IsZeroConfig<F>,
+    lo_match: IsZeroConfig<F>,
+    /// Fixed table of the bytes accepted by each tag
+    fixed_table: [Column<Fixed>; 4],
+}
+
+/// Circuit configuration arguments
+pub struct KeccakMptCircuitConfigArgs<F: Field> {
+    /// KeccakTable
+    pub keccak_table: KeccakTable,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+/// Expressions of the columns of a row.
+struct RowExprs<F> {
+    tags: [Expression<F>; N_TAGS],
+    byte: Expression<F>,
+    hi: Expression<F>,
+    lo: Expression<F>,
+    rem: Expression<F>,
+    list_rem: Expression<F>,
+    depth: Expression<F>,
+    child_index: Expression<F>,
+    key_nibble: Expression<F>,
+    kn_lo: Expression<F>,
+    is_branch: Expression<F>,
+    is_leaf: Expression<F>,
+    is_odd: Expression<F>,
+    all_match: Expression<F>,
+    is_selected: Expression<F>,
+    cont: Expression<F>,
+    is_end: Expression<F>,
+    is_proof_end: Expression<F>,
+    proof_id: Expression<F>,
+    key_len: Expression<F>,
+    node_len: Expression<F>,
+    value_len: Expression<F>,
+    root: Expression<F>,
+    key_rlc: Expression<F>,
+    node_rlc: Expression<F>,
+    node_hash: Expression<F>,
+    hash_acc: Expression<F>,
+    value_rlc: Expression<F>,
+    rem_is_zero: Expression<F>,
+    last_child: Expression<F>,
+    child_match: Expression<F>,
+    hi_match: Expression<F>,
+    lo_match: Expression<F>,
+}
+
+impl<F: Field> RowExprs<F> {
+    fn tag(&self, tag: MptTag) -> Expression<F> {
+        self.tags[tag as usize].clone()
+    }
+
+    fn tags(&self, tags: &[MptTag]) -> Expression<F> {
+        sum::expr(tags.iter().map(|tag| self.tag(*tag)))
+    }
+
+    /// Whether the row is a byte of a node.
+    fn is_node(&self) -> Expression<F> {
+        self.tags(&NODE_TAGS)
+    }
+}
+
+impl<F: Field> KeccakMptCircuitConfig<F> {
+    fn query_row(&self, meta: &mut VirtualCells<'_, F>, at: Rotation) -> RowExprs<F> {
+        let mut query = |column| meta.query_advice(column, at);
+        let tags = self.tags.map(&mut query);
+        let [byte, hi, lo, rem, list_rem, depth, child_index, key_nibble, kn_lo] = [
+            self.byte,
+            self.hi,
+            self.lo,
+            self.rem,
+            self.list_rem,
+            self.depth,
+            self.child_index,
+            self.key_nibble,
+            self.kn_lo,
+        ]
+        .map(&mut query);
+        let [is_branch, is_leaf, is_odd, all_match, is_selected, cont, is_end, is_proof_end] = [
+            self.is_branch,
+            self.is_leaf,
+            self.is_odd,
+            self.all_match,
+            self.is_selected,
+            self.cont,
+            self.is_end,
+            self.is_proof_end,
+        ]
+        .map(&mut query);
+        let [proof_id, key_len, node_len, value_len] =
+            [self.proof_id, self.key_len, self.node_len, self.value_len].map(&mut query);
+        let [root, key_rlc, node_rlc, node_hash, hash_acc, value_rlc] = [
+            self.root,
+            self.key_rlc,
+            self.node_rlc,
+            self.node_hash,
+            self.hash_acc,
+            self.value_rlc,
+        ]
+        .map(&mut query);
+
+        let rem_is_zero = self.rem_is_zero.expr_at(meta, at, rem.clone());
+        let last_child = self
+            .last_child
+            .expr_at(meta, at, child_index.clone() - 15.expr());
+        let child_match =
+            self.child_match
+                .expr_at(meta, at, child_index.clone() - key_nibble.clone());
+        let hi_match = self
+            .hi_match
+            .expr_at(meta, at, hi.clone() - key_nibble.clone());
+        let lo_match = self.lo_match.expr_at(meta, at, lo.clone() - kn_lo.clone());
+
+        RowExprs {
+            tags,
+            byte,
+            hi,
+            lo,
+            rem,
+            list_rem,
+            depth,
+            child_index,
+            key_nibble,
+            kn_lo,
+            is_branch,
+            is_leaf,
+            is_odd,
+            all_match,
+            is_selected,
+            cont,
+            is_end,
+            is_proof_end,
+            proof_id,
+            key_len,
+            node_len,
+            value_len,
+            root,
+            key_rlc,
+            node_rlc,
+            node_hash,
+            hash_acc,
+            value_rlc,
+            rem_is_zero,
+            last_child,
+            child_match,
+            hi_match,
+            lo_match,
+        }
+    }
+
+    /// The nibbles of the hashes of the keys, `[proof_id, depth, nibble]`.
+    fn key_nibble_table(&self, meta: &mut VirtualCells<'_, F>) -> [Expression<F>; 3] {
+        let q_enable = meta.query_fixed(self.q_enable, Rotation::cur());
+        let cur = self.query_row(meta, Rotation::cur());
+        let is_key = q_enable * cur.tag(MptTag::Key);
+        [cur.proof_id, cur.depth, cur.byte].map(|value| is_key.clone() * value)
+    }
+
+    /// Expressions of the proofs, to be looked up by the circuits using the
+    /// values proven: `[root, key_rlc, key_len, exists, value_rlc, value_len]`
+    /// on the last row of each proof, where `root` is the RLC of the root of
+    /// the trie and `key_rlc` the RLC of the key before hashing, over the evm
+    /// word and the keccak input challenges, and `value_rlc` the RLC of the
+    /// RLP encoded value of the key if it `exists`, over the keccak input
+    /// challenge, i.e. of the RLP encoded account or storage value.
+    /// They're all 0 on the other rows.
+    pub fn lookup_exprs(&self, meta: &mut VirtualCells<'_, F>) -> Vec<Expression<F>> {
+        let q_enable = meta.query_fixed(self.q_enable, Rotation::cur());
+        let cur = self.query_row(meta, Rotation::cur());
+        let is_proof_end = q_enable * cur.is_proof_end.clone();
+        let exists = is_proof_end.clone() * cur.is_end.clone() * cur.is_leaf * cur.all_match;
+        vec![
+            is_proof_end.clone() * cur.root,
+            is_proof_end.clone() * cur.key_rlc,
+            is_proof_end * cur.key_len,
+            exists.clone(),
+            exists.clone() * cur.value_rlc,
+            exists * cur.value_len,
+        ]
+    }
+}
+
+fn is_zero_config<F: Field>(
+    meta: &mut ConstraintSystem<F>,
+    q_enable: Column<Fixed>,
+    value: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+) -> IsZeroConfig<F> {
+    let value_inv = meta.advice_column();
+    IsZeroChip::configure(
+        meta,
+        |meta| meta.query_fixed(q_enable, Rotation::cur()),
+        value,
+        value_inv,
+    )
+}
+
+impl<F: Field> SubCircuitConfig<F> for KeccakMptCircuitConfig<F> {
+    type ConfigArgs = KeccakMptCircuitConfigArgs<F>;
+
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            keccak_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let [q_enable, q_step, q_first, q_last] = [(); 4].map(|_| meta.fixed_column());
+        let fixed_table = [(); 4].map(|_| meta.fixed_column());
+        let tags = [(); N_TAGS].map(|_| meta.advice_column());
+        let [byte, hi, lo, rem, list_rem, depth, child_index, key_nibble, kn_lo] =
+            [(); 9].map(|_| meta.advice_column());
+        let [is_branch, is_leaf, is_odd, all_match, is_selected, cont, is_end, is_proof_end] =
+            [(); 8].map(|_| meta.advice_column());
+        let [proof_id, key_len, node_len, value_len] = [(); 4].map(|_| meta.advice_column());
+        let [root, key_rlc, node_rlc, node_hash, hash_acc, value_rlc] =
+            [(); 6].map(|_| meta.advice_column_in(SecondPhase));
+
+        let rem_is_zero = is_zero_config(meta, q_enable, |meta| {
+            meta.query_advice(rem, Rotation::cur())
+        });
+        let last_child = is_zero_config(meta, q_enable, |meta| {
+            meta.query_advice(child_index, Rotation::cur()) - 15.expr()
+        });
+        let child_match = is_zero_config(meta, q_enable, |meta| {
+            meta.query_advice(child_index, Rotation::cur())
+                - meta.query_advice(key_nibble, Rotation::cur())
+        });
+        let hi_match = is_zero_config(meta, q_enable, |meta| {
+            meta.query_advice(hi, Rotation::cur()) - meta.query_advice(key_nibble, Rotation::cur())
+        });
+        let lo_match = is_zero_config(meta, q_enable, |meta| {
+            meta.query_advice(lo, Rotation::cur()) - meta.query_advice(kn_lo, Rotation::cur())
+        });
+
+        let config = Self {
+            q_enable,
+            q_step,
+            q_first,
+            q_last,
+            tags,
+            byte,
+            hi,
+            lo,
+            rem,
+            list_rem,
+            depth,
+            child_index,
+            key_nibble,
+            kn_lo,
+            is_branch,
+            is_leaf,
+            is_odd,
+            all_match,
+            is_selected,
+            cont,
+            is_end,
+            is_proof_end,
+            proof_id,
+            key_len,
+            node_len,
+            value_len,
+            root,
+            key_rlc,
+            node_rlc,
+            node_hash,
+            hash_acc,
+            value_rlc,
+            rem_is_zero,
+            last_child,
+            child_match,
+            hi_match,
+            lo_match,
+            fixed_table,
+        };
+        let r_key = challenges.keccak_input();
+        let r_word = challenges.evm_word();
+
+        meta.create_gate("keccak mpt row", |meta| {
+            use MptTag::*;
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let cur = config.query_row(meta, Rotation::cur());
+
+            for tag in cur.tags.iter() {
+                cb.require_boolean("tag is boolean", tag.clone());
+            }
+            cb.require_equal("one tag per row", sum::expr(cur.tags.iter()), 1.expr());
+            for (name, value) in [
+                ("is_branch is boolean", &cur.is_branch),
+                ("is_leaf is boolean", &cur.is_leaf),
+                ("is_odd is boolean", &cur.is_odd),
+                ("all_match is boolean", &cur.all_match),
+                ("is_selected is boolean", &cur.is_selected),
+                ("cont is boolean", &cur.cont),
+            ] {
+                cb.require_boolean(name, value.clone());
+            }
+
+            cb.condition(cur.tag(ListHeader), |cb| {
+                cb.require_equal(
+                    "node_rlc starts with the header",
+                    cur.node_rlc.clone(),
+                    cur.byte.clone(),
+                );
+                cb.require_equal("node_len starts at 1", cur.node_len.clone(), 1.expr());
+                cb.require_zero("hash_acc starts at 0", cur.hash_acc.clone());
+                cb.require_equal(
+                    "list_rem is the payload length of a short list",
+                    cur.list_rem.clone(),
+                    (1.expr() - cur.hi.clone()) * cur.lo.clone(),
+                );
+                cb.require_equal(
+                    "rem is the length of the length of a long list",
+                    cur.rem.clone(),
+                    cur.hi.clone() * cur.lo.clone(),
+                );
+            });
+            cb.condition(cur.tag(BranchChild), |cb| {
+                cb.require_equal(
+                    "rem is 32 before a child hash",
+                    cur.rem.clone(),
+                    32.expr() * cur.hi.clone(),
+                );
+                cb.require_equal(
+                    "the child of the key nibble is selected",
+                    cur.is_selected.clone(),
+                    cur.child_match.clone(),
+                );
+                cb.require_zero("a branch is not a leaf", cur.is_leaf.clone());
+            });
+            cb.condition(cur.tag(BranchChild) * cur.is_selected.clone(), |cb| {
+                cb.require_equal(
+                    "the proof continues iff the selected child is not empty",
+                    cur.cont.clone(),
+                    cur.hi.clone(),
+                );
+            });
+            cb.condition(cur.tag(BranchValue), |cb| {
+                cb.require_zero("the value of a branch is empty", cur.rem.clone());
+            });
+            cb.condition(cur.tag(PathHeader), |cb| {
+                cb.require_equal(
+                    "rem is the length of the path",
+                    cur.rem.clone(),
+                    cur.lo.clone(),
+                );
+            });
+            cb.condition(cur.tag(PathFlag), |cb| {
+                cb.require_equal(
+                    "the flag of a path is its parity and whether it is a leaf",
+                    cur.hi.clone(),
+                    2.expr() * cur.is_leaf.clone() + cur.is_odd.clone(),
+                );
+                cb.require_equal(
+                    "the nibble of an odd path matches the key",
+                    cur.all_match.clone(),
+                    select::expr(cur.is_odd.clone(), cur.lo_match.clone(), 1.expr()),
+                );
+            });
+            cb.condition(cur.tag(ExtChild), |cb| {
+                cb.require_equal("rem is 32 before a child hash", cur.rem.clone(), 32.expr());
+                cb.require_equal(
+                    "the child of an extension is selected",
+                    cur.is_selected.clone(),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "the proof continues iff the path of the extension matches the key",
+                    cur.cont.clone(),
+                    cur.all_match.clone(),
+                );
+            });
+            cb.condition(cur.tag(ValueHeader), |cb| {
+                cb.require_equal(
+                    "rem is the length of the value",
+                    cur.rem.clone(),
+                    cur.lo.clone(),
+                );
+                cb.require_zero("the proof ends at a leaf", cur.cont.clone());
+                // (hi - 1) * (hi - 2) / 2 is 1 for a single byte value, hi * (2 - hi) for a
+                // short value
+                let is_single_2 = (cur.hi.clone() - 1.expr()) * (cur.hi.clone() - 2.expr());
+                let is_short = cur.hi.clone() * (2.expr() - cur.hi.clone());
+                cb.require_equal(
+                    "value_len of a single byte or short value",
+                    2.expr() * cur.value_len.clone(),
+                    is_single_2.clone() + 2.expr() * is_short * cur.lo.clone(),
+                );
+                cb.require_equal(
+                    "value_rlc of a single byte value",
+                    2.expr() * cur.value_rlc.clone(),
+                    is_single_2 * cur.byte.clone(),
+                );
+            });
+            cb.condition(cur.tag(ValueLength), |cb| {
+                cb.require_equal(
+                    "rem is the length of the value",
+                    cur.rem.clone(),
+                    cur.byte.clone(),
+                );
+                cb.require_equal(
+                    "value_len is the length of the value",
+                    cur.value_len.clone(),
+                    cur.byte.clone(),
+                );
+                cb.require_zero("value_rlc starts at 0", cur.value_rlc.clone());
+            });
+
+            cb.require_equal(
+                "a node ends after its branch value, its extension child or its leaf value",
+                cur.is_end.clone(),
+                cur.tag(BranchValue)
+                    + cur.tag(ChildHash)
+                        * cur.rem_is_zero.clone()
+                        * (1.expr() - cur.is_branch.clone())
+                    + cur.tags(&[ValueHeader, ValueLength, ValueByte]) * cur.rem_is_zero.clone(),
+            );
+            cb.condition(cur.is_end.clone(), |cb| {
+                cb.require_zero("a node ends with its list", cur.list_rem.clone());
+            });
+            cb.condition(
+                cur.is_end.clone() * cur.is_leaf.clone() * cur.all_match.clone(),
+                |cb| {
+                    cb.require_equal(
+                        "the leaf of a key is at the end of its path",
+                        cur.depth.clone(),
+                        KEY_ROWS.expr(),
+                    );
+                },
+            );
+            cb.require_zero(
+                "padding rows are not proof ends",
+                cur.tag(Padding) * cur.is_proof_end.clone(),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("keccak mpt transition", |meta| {
+            use MptTag::*;
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let cur = config.query_row(meta, Rotation::cur());
+            let next = config.query_row(meta, Rotation::next());
+            let rem_zero = cur.rem_is_zero.clone();
+            let rem_not_zero = 1.expr() - rem_zero.clone();
+
+            cb.require_equal(
+                "a proof ends at a node not continuing, or the key of the empty trie",
+                cur.is_proof_end.clone(),
+                cur.tag(Key) * rem_zero.clone() * (1.expr() - next.tag(ListHeader))
+                    + cur.is_node() * cur.is_end.clone() * (1.expr() - cur.cont.clone()),
+            );
+
+            // state machine
+            cb.condition(cur.tag(Key) * rem_not_zero.clone(), |cb| {
+                cb.require_equal("Key -> Key", next.tag(Key), 1.expr());
+                cb.require_equal(
+                    "depth increases",
+                    next.depth.clone(),
+                    cur.depth.clone() + 1.expr(),
+                );
+                cb.require_equal(
+                    "parity alternates",
+                    next.is_odd.clone(),
+                    1.expr() - cur.is_odd.clone(),
+                );
+                cb.require_equal(
+                    "hash_acc accumulates the bytes of the hash of the key",
+                    next.hash_acc.clone(),
+                    select::expr(
+                        cur.is_odd.clone(),
+                        cur.hash_acc.clone(),
+                        cur.hash_acc.clone() * r_word.clone()
+                            + cur.byte.clone() * 16.expr()
+                            + next.byte.clone(),
+                    ),
+                );
+            });
+            cb.condition(cur.tag(Key) * rem_zero.clone(), |cb| {
+                cb.require_equal(
+                    "Key -> ListHeader, Key or Padding",
+                    next.tags(&[ListHeader, Key, Padding]),
+                    1.expr(),
+                );
+            });
+            cb.condition(
+                cur.tag(Key) * rem_zero.clone() * next.tag(ListHeader),
+                |cb| {
+                    cb.require_equal(
+                        "the first node is the root",
+                        next.node_hash.clone(),
+                        cur.root.clone(),
+                    );
+                    cb.require_zero("the first node is at depth 0", next.depth.clone());
+                },
+            );
+            cb.condition(
+                cur.tags(&[ListHeader, ListLength]) * rem_not_zero.clone(),
+                |cb| {
+                    cb.require_equal("-> ListLength", next.tag(ListLength), 1.expr());
+                    cb.require_equal(
+                        "list_rem accumulates the length of a long list",
+                        next.list_rem.clone(),
+                        cur.list_rem.clone() * 256.expr() + next.byte.clone(),
+                    );
+                },
+            );
+            cb.condition(
+                cur.tags(&[ListHeader, ListLength]) * rem_zero.clone(),
+                |cb| {
+                    cb.require_equal(
+                        "-> BranchChild, PathHeader or PathFlag",
+                        next.tags(&[BranchChild, PathHeader, PathFlag]),
+                        1.expr(),
+                    );
+                    cb.require_equal(
+                        "a node of children is a branch",
+                        cur.is_branch.clone(),
+                        next.tag(BranchChild),
+                    );
+                    cb.require_zero(
+                        "the children of a branch start at 0",
+                        next.tag(BranchChild) * next.child_index.clone(),
+                    );
+                    cb.require_zero(
+                        "a single byte path is the flag",
+                        next.tag(PathFlag) * next.rem.clone(),
+                    );
+                },
+            );
+            cb.condition(
+                cur.tags(&[BranchChild, ExtChild, ChildHash]) * rem_not_zero.clone(),
+                |cb| {
+                    cb.require_equal("-> ChildHash", next.tag(ChildHash), 1.expr());
+                    cb.require_equal(
+                        "is_selected is the same in a child",
+                        next.is_selected.clone(),
+                        cur.is_selected.clone(),
+                    );
+                    cb.require_equal(
+                        "child_index is the same in a child",
+                        next.child_index.clone(),
+                        cur.child_index.clone(),
+                    );
+                },
+            );
+            let child_end = (cur.tag(BranchChild) + cur.tag(ChildHash) * cur.is_branch.clone())
+                * rem_zero.clone();
+            cb.condition(
+                child_end.clone() * (1.expr() - cur.last_child.clone()),
+                |cb| {
+                    cb.require_equal("-> BranchChild", next.tag(BranchChild), 1.expr());
+                    cb.require_equal(
+                        "child_index increases",
+                        next.child_index.clone(),
+                        cur.child_index.clone() + 1.expr(),
+                    );
+                },
+            );
+            cb.condition(child_end * cur.last_child.clone(), |cb| {
+                cb.require_equal("-> BranchValue", next.tag(BranchValue), 1.expr());
+            });
+            cb.condition(cur.tag(PathHeader), |cb| {
+                cb.require_equal("PathHeader -> PathFlag", next.tag(PathFlag), 1.expr());
+            });
+            cb.condition(
+                cur.tags(&[PathFlag, PathNibbles]) * rem_not_zero.clone(),
+                |cb| {
+                    cb.require_equal("-> PathNibbles", next.tag(PathNibbles), 1.expr());
+                },
+            );
+            cb.condition(
+                cur.tags(&[PathFlag, PathNibbles]) * rem_zero.clone(),
+                |cb| {
+                    cb.require_equal(
+                        "a leaf path is followed by the value",
+                        next.tag(ValueHeader),
+                        cur.is_leaf.clone(),
+                    );
+                    cb.require_equal(
+                        "an extension path is followed by the child",
+                        next.tag(ExtChild),
+                        1.expr() - cur.is_leaf.clone(),
+                    );
+                },
+            );
+            cb.condition(cur.tag(ValueHeader) * rem_not_zero.clone(), |cb| {
+                cb.require_equal(
+                    "a long value has a length",
+                    next.tag(ValueLength),
+                    cur.hi.clone() - 1.expr(),
+                );
+                cb.require_equal(
+                    "a short value has bytes",
+                    next.tag(ValueByte),
+                    2.expr() - cur.hi.clone(),
+                );
+            });
+            cb.condition(
+                cur.tags(&[ValueLength, ValueByte]) * rem_not_zero.clone(),
+                |cb| {
+                    cb.require_equal("-> ValueByte", next.tag(ValueByte), 1.expr());
+                },
+            );
+            cb.condition(cur.tag(Padding), |cb| {
+                cb.require_equal("Padding -> Padding", next.tag(Padding), 1.expr());
+            });
+            cb.condition(
+                (cur.tags(&[
+                    Key,
+                    ListHeader,
+                    ListLength,
+                    BranchChild,
+                    ExtChild,
+                    ChildHash,
+                    PathHeader,
+                    PathFlag,
+                    PathNibbles,
+                    ValueLength,
+                    ValueByte,
+                ]) + cur.tag(ValueHeader) * (2.expr() - cur.hi.clone()))
+                    * rem_not_zero,
+                |cb| {
+                    cb.require_equal(
+                        "rem decreases",
+                        next.rem.clone(),
+                        cur.rem.clone() - 1.expr(),
+                    );
+                },
+            );
+
+            // within a node
+            cb.condition(cur.is_node() * (1.expr() - cur.is_end.clone()), |cb| {
+                for (name, cur, next) in [
+                    (
+                        "node_hash is the same in a node",
+                        &cur.node_hash,
+                        &next.node_hash,
+                    ),
+                    (
+                        "is_branch is the same in a node",
+                        &cur.is_branch,
+                        &next.is_branch,
+                    ),
+                    ("is_leaf is the same in a node", &cur.is_leaf, &next.is_leaf),
+                    ("cont is the same in a node", &cur.cont, &next.cont),
+                ] {
+                    cb.require_equal(name, next.clone(), cur.clone());
+                }
+                cb.require_equal(
+                    "node_rlc accumulates the bytes of the node",
+                    next.node_rlc.clone(),
+                    cur.node_rlc.clone() * r_key.clone() + next.byte.clone(),
+                );
+                cb.require_equal(
+                    "node_len counts the bytes of the node",
+                    next.node_len.clone(),
+                    cur.node_len.clone() + 1.expr(),
+                );
+                cb.require_equal(
+                    "depth increases by the nibbles of the path",
+                    next.depth.clone(),
+                    cur.depth.clone()
+                        + cur.tag(PathFlag) * cur.is_odd.clone()
+                        + 2.expr() * cur.tag(PathNibbles),
+                );
+                cb.require_equal(
+                    "hash_acc accumulates the hash of the selected child",
+                    next.hash_acc.clone(),
+                    cur.hash_acc.clone()
+                        + next.tag(ChildHash)
+                            * next.is_selected.clone()
+                            * (cur.hash_acc.clone() * (r_word.clone() - 1.expr())
+                                + next.byte.clone()),
+                );
+                cb.require_zero(
+                    "list_rem counts down the payload of the list",
+                    (1.expr() - next.tag(ListLength))
+                        * (next.list_rem.clone() - cur.list_rem.clone() + 1.expr()),
+                );
+                cb.require_zero(
+                    "all_match is the same after the path",
+                    (1.expr() - next.tags(&[PathFlag, PathNibbles]))
+                        * (next.all_match.clone() - cur.all_match.clone()),
+                );
+                cb.require_zero(
+                    "value_rlc accumulates the bytes of the value",
+                    next.tag(ValueByte)
+                        * (next.value_rlc.clone()
+                            - cur.value_rlc.clone() * r_key.clone()
+                            - next.byte.clone()),
+                );
+                cb.require_zero(
+                    "value_len is the same in the value",
+                    next.tag(ValueByte) * (next.value_len.clone() - cur.value_len.clone()),
+                );
+            });
+            cb.condition(next.tag(PathNibbles), |cb| {
+                cb.require_equal(
+                    "the nibbles of the path match the key",
+                    next.all_match.clone(),
+                    cur.all_match.clone() * next.hi_match.clone() * next.lo_match.clone(),
+                );
+            });
+
+            // between nodes and proofs
+            cb.condition(cur.is_end.clone() * cur.cont.clone(), |cb| {
+                cb.require_equal("-> ListHeader", next.tag(ListHeader), 1.expr());
+                cb.require_equal(
+                    "the next node is the selected child",
+                    next.node_hash.clone(),
+                    cur.hash_acc.clone(),
+                );
+                cb.require_equal(
+                    "a branch consumes a nibble",
+                    next.depth.clone(),
+                    cur.depth.clone() + cur.is_branch.clone(),
+                );
+            });
+            cb.condition(cur.is_proof_end.clone(), |cb| {
+                cb.require_equal(
+                    "a proof is followed by a proof or padding",
+                    next.tags(&[Key, Padding]),
+                    1.expr(),
+                );
+            });
+            cb.condition(cur.is_proof_end.clone() * next.tag(Key), |cb| {
+                cb.require_equal(
+                    "the next proof starts",
+                    next.rem.clone(),
+                    (KEY_ROWS - 1).expr(),
+                );
+                cb.require_zero("the next proof starts", next.depth.clone());
+                cb.require_zero("the next proof starts", next.is_odd.clone());
+                cb.require_zero("the next proof starts", next.hash_acc.clone());
+                cb.require_equal(
+                    "proof_id increases",
+                    next.proof_id.clone(),
+                    cur.proof_id.clone() + 1.expr(),
+                );
+            });
+            cb.condition(
+                (cur.tag(Key) + cur.is_node()) * (1.expr() - cur.is_proof_end.clone()),
+                |cb| {
+                    for (name, cur, next) in [
+                        (
+                            "proof_id is the same in a proof",
+                            &cur.proof_id,
+                            &next.proof_id,
+                        ),
+                        ("root is the same in a proof", &cur.root, &next.root),
+                        (
+                            "key_rlc is the same in a proof",
+                            &cur.key_rlc,
+                            &next.key_rlc,
+                        ),
+                        (
+                            "key_len is the same in a proof",
+                            &cur.key_len,
+                            &next.key_len,
+                        ),
+                    ] {
+                        cb.require_equal(name, next.clone(), cur.clone());
+                    }
+                },
+            );
+
+            cb.gate(meta.query_fixed(q_step, Rotation::cur()))
+        });
+
+        meta.create_gate("keccak mpt first row", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let cur = config.query_row(meta, Rotation::cur());
+            let is_key = cur.tag(MptTag::Key);
+            cb.require_equal(
+                "the first row is a proof or padding",
+                cur.tags(&[MptTag::Key, MptTag::Padding]),
+                1.expr(),
+            );
+            cb.condition(is_key, |cb| {
+                cb.require_equal(
+                    "the first proof starts",
+                    cur.rem.clone(),
+                    (KEY_ROWS - 1).expr(),
+                );
+                cb.require_zero("the first proof starts", cur.depth.clone());
+                cb.require_zero("the first proof starts", cur.is_odd.clone());
+                cb.require_zero("the first proof starts", cur.hash_acc.clone());
+                cb.require_equal("the first proof has id 1", cur.proof_id.clone(), 1.expr());
+            });
+            cb.gate(meta.query_fixed(q_first, Rotation::cur()))
+        });
+
+        meta.create_gate("keccak mpt last row", |meta| {
+            let is_padding = meta.query_advice(tags[MptTag::Padding as usize], Rotation::cur());
+            [meta.query_fixed(q_last, Rotation::cur()) * (1.expr() - is_padding)]
+        });
+
+        meta.lookup_any("keccak mpt bytes of the tags", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let cur = config.query_row(meta, Rotation::cur());
+            let tag = sum::expr(MptTag::iter().map(|tag| cur.tag(tag) * (tag as u64).expr()));
+            [tag, cur.byte, cur.hi, cur.lo]
+                .into_iter()
+                .zip(fixed_table)
+                .map(|(input, column)| {
+                    (
+                        q_enable.clone() * input,
+                        meta.query_fixed(column, Rotation::cur()),
+                    )
+                })
+                .collect()
+        });
+
+        meta.lookup_any("keccak mpt key nibble", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let cur = config.query_row(meta, Rotation::cur());
+            let condition = q_enable * cur.tags(&[MptTag::BranchChild, MptTag::PathNibbles]);
+            [cur.proof_id, cur.depth, cur.key_nibble]
+                .into_iter()
+                .zip(config.key_nibble_table(meta))
+                .map(|(input, table)| (condition.clone() * input, table))
+                .collect()
+        });
+        meta.lookup_any("keccak mpt key nibble of the low nibble", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let cur = config.query_row(meta, Rotation::cur());
+            let is_path_nibbles = cur.tag(MptTag::PathNibbles);
+            let condition = q_enable
+                * (cur.tag(MptTag::PathFlag) * cur.is_odd.clone() + is_path_nibbles.clone());
+            [cur.proof_id, cur.depth + is_path_nibbles, cur.kn_lo]
+                .into_iter()
+                .zip(config.key_nibble_table(meta))
+                .map(|(input, table)| (condition.clone() * input, table))
+                .collect()
+        });
+
+        let keccak_lookup =
+            |meta: &mut ConstraintSystem<F>,
+             name: &'static str,
+             input: fn(&RowExprs<F>) -> (Expression<F>, [Expression<F>; 3])| {
+                meta.lookup_any(name, |meta| {
+                    let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+                    let cur = config.query_row(meta, Rotation::cur());
+                    let (condition, [input_rlc, input_len, output_rlc]) = input(&cur);
+                    [1.expr(), 1.expr(), input_rlc, input_len, output_rlc]
+                        .into_iter()
+                        .zip(keccak_table.table_exprs(meta))
+                        .map(|(input, table)| (q_enable.clone() * condition.clone() * input, table))
+                        .collect()
+                });
+            };
+        keccak_lookup(meta, "keccak mpt node hash", |cur| {
+            (
+                cur.is_end.clone(),
+                [
+                    cur.node_rlc.clone(),
+                    cur.node_len.clone(),
+                    cur.node_hash.clone(),
+                ],
+            )
+        });
+        keccak_lookup(meta, "keccak mpt key hash", |cur| {
+            (
+                cur.tag(MptTag::Key) * cur.rem_is_zero.clone(),
+                [
+                    cur.key_rlc.clone(),
+                    cur.key_len.clone(),
+                    cur.hash_acc.clone(),
+                ],
+            )
+        });
+        keccak_lookup(meta, "keccak mpt empty trie root", |cur| {
+            (
+                cur.tag(MptTag::Key) * cur.is_proof_end.clone(),
+                [
+                    EMPTY_TRIE_NODE[0].expr(),
+                    EMPTY_TRIE_NODE.len().expr(),
+                    cur.root.clone(),
+                ],
+            )
+        });
+
+        config
+    }
+}
+
+/// Witness of a row of the keccak MPT circuit.
+#[derive(Clone, Debug)]
+struct MptRow<F> {
+    tag: MptTag,
+    byte: u8,
+    hi: u64,
+    lo: u64,
+    rem: u64,
+    list_rem: u64,
+    depth: u64,
+    child_index: u64,
+    key_nibble: u64,
+    kn_lo: u64,
+    is_branch: bool,
+    is_leaf: bool,
+    is_odd: bool,
+    all_match: bool,
+    is_selected: bool,
+    cont: bool,
+    is_end: bool,
+    is_proof_end: bool,
+    proof_id: u64,
+    key_len: u64,
+    node_len: u64,
+    value_len: u64,
+    root: Value<F>,
+    key_rlc: Value<F>,
+    node_rlc: Value<F>,
+    node_hash: Value<F>,
+    hash_acc: Value<F>,
+    value_rlc: Value<F>,
+}
+
+impl<F: Field> MptRow<F> {
+    fn padding() -> Self {
+        Self {
+            tag: MptTag::Padding,
+            byte: 0,
+            hi: 0,
+            lo: 0,
+            rem: 0,
+            list_rem: 0,
+            depth: 0,
+            child_index: 0,
+            key_nibble: 0,
+            kn_lo: 0,
+            is_branch: false,
+            is_leaf: false,
+            is_odd: false,
+            all_match: false,
+            is_selected: false,
+            cont: false,
+            is_end: false,
+            is_proof_end: false,
+            proof_id: 0,
+            key_len: 0,
+            node_len: 0,
+            value_len: 0,
+            root: Value::known(F::zero()),
+            key_rlc: Value::known(F::zero()),
+            node_rlc: Value::known(F::zero()),
+            node_hash: Value::known(F::zero()),
+            hash_acc: Value::known(F::zero()),
+            value_rlc: Value::known(F::zero()),
+        }
+    }
+
+    /// Row of a byte of a node, with its nibbles as `hi` and `lo`.
+    fn node_byte(tag: MptTag, byte: u8, rem: usize) -> Self {
+        Self::node_header(tag, byte, (byte >> 4).into(), (byte & 0x0f).into(), rem)
+    }
+
+    fn node_header(tag: MptTag, byte: u8, hi: u64, lo: u64, rem: usize) -> Self {
+        Self {
+            tag,
+            byte,
+            hi,
+            lo,
+            rem: rem as u64,
+            ..Self::padding()
+        }
+    }
+}
+
+/// Rows of the string `raw` of a hash, after its header of `tag`.
+fn hash_rows<F: Field>(
+    tag: MptTag,
+    raw: &[u8],
+    node_index: usize,
+) -> Result<Vec<MptRow<F>>, MptProofError> {
+    match raw {
+        [0xa0, hash @ ..] if hash.len() == 32 => Ok(std::iter::once(MptRow::node_header(
+            tag,
+            0xa0,
+            (tag == MptTag::BranchChild).into(),
+            0,
+            32,
+        ))
+        .chain(
+            hash.iter()
+                .enumerate()
+                .map(|(i, &byte)| MptRow::node_byte(MptTag::ChildHash, byte, 31 - i)),
+        )
+        .collect()),
+        [0xc0..=0xff, ..] => Err(MptProofError::UnsupportedNode(node_index)),
+        _ => Err(MptProofError::InvalidNode),
+    }
+}
+
+/// Rows of the node at `index` of a proof, before the fields of the node
+/// and the proof are set, with the hash of its child on the path of the key
+/// if any, and the depth after the node.
+fn node_rows<F: Field>(
+    index: usize,
+    node: &[u8],
+    nibbles: &[u8],
+    node_depth: usize,
+) -> Result<(Vec<MptRow<F>>, Option<H256>, usize), MptProofError> {
+    let mut depth = node_depth;
+    let nibble_at = |depth: usize| {
+        nibbles
+            .get(depth)
+            .map(|&nibble| u64::from(nibble))
+            .ok_or(MptProofError::InvalidNode)
+    };
+    let mut rows = Vec::with_capacity(node.len());
+
+    // the header of the list
+    let payload_offset = match node.first() {
+        Some(&byte @ 0xc0..=0xf7) => {
+            rows.push(MptRow {
+                list_rem: (byte - 0xc0).into(),
+                ..MptRow::node_header(MptTag::ListHeader, byte, 0, (byte - 0xc0).into(), 0)
+            });
+            1
+        }
+        Some(&byte @ 0xf8..=0xf9) => {
+            let len_len = usize::from(byte - 0xf7);
+            rows.push(MptRow::node_header(
+                MptTag::ListHeader,
+                byte,
+                1,
+                len_len as u64,
+                len_len,
+            ));
+            let len_bytes = node.get(1..=len_len).ok_or(MptProofError::InvalidNode)?;
+            let mut list_rem = 0;
+            for (i, &byte) in len_bytes.iter().enumerate() {
+                list_rem = list_rem * 256 + u64::from(byte);
+                rows.push(MptRow {
+                    list_rem,
+                    ..MptRow::node_byte(MptTag::ListLength, byte, len_len - 1 - i)
+                });
+            }
+            1 + len_len
+        }
+        _ => return Err(MptProofError::InvalidNode),
+    };
+
+    let rlp = Rlp::new(node);
+    let mut child_hash = None;
+    match rlp.item_count()? {
+        17 => {
+            let nibble = nibble_at(depth)?;
+            for child_index in 0..16u64 {
+                let raw = rlp.at(child_index as usize)?.as_raw();
+                let is_selected = child_index == nibble;
+                let mut child_rows = if raw == EMPTY_TRIE_NODE {
+                    vec![MptRow::node_header(MptTag::BranchChild, raw[0], 0, 0, 0)]
+                } else {
+                    hash_rows(MptTag::BranchChild, raw, index)?
+                };
+                if is_selected && child_rows.len() > 1 {
+                    child_hash = Some(H256::from_slice(&raw[1..]));
+                }
+                for row in child_rows.iter_mut() {
+                    row.child_index = child_index;
+                    row.is_selected = is_selected;
+                }
+                child_rows[0].key_nibble = nibble;
+                rows.extend(child_rows);
+            }
+            if rlp.at(16)?.as_raw() != EMPTY_TRIE_NODE {
+                return Err(MptProofError::UnsupportedNode(index));
+            }
+            rows.push(MptRow::node_header(
+                MptTag::BranchValue,
+                EMPTY_TRIE_NODE[0],
+                0,
+                0,
+                0,
+            ));
+            depth += 1;
+        }
+        2 => {
+            // the path, hex-prefix encoded
+            let raw = rlp.at(0)?.as_raw();
+            let path = match raw {
+                [0x00..=0x7f] => raw,
+                [header @ 0x81..=0xa1, path @ ..] if path.len() == usize::from(header - 0x80) => {
+                    rows.push(MptRow::node_header(
+                        MptTag::PathHeader,
+                        *header,
+                        0,
+                        path.len() as u64,
+                        path.len(),
+                    ));
+                    path
+                }
+                _ => return Err(MptProofError::InvalidNode),
+            };
+            let flag = path[0];
+            let (is_leaf, is_odd) = match flag >> 4 {
+                flags @ 0..=3 if flags & 1 == 1 || flag & 0x0f == 0 => {
+                    (flags >> 1 == 1, flags & 1 == 1)
+                }
+                _ => return Err(MptProofError::InvalidNode),
+            };
+            let mut all_match = true;
+            let mut kn_lo = 0;
+            if is_odd {
+                kn_lo = nibble_at(depth)?;
+                all_match = u64::from(flag & 0x0f) == kn_lo;
+            }
+            rows.push(MptRow {
+                depth: depth as u64,
+                kn_lo,
+                is_odd,
+                all_match,
+                ..MptRow::node_byte(MptTag::PathFlag, flag, path.len() - 1)
+            });
+            depth += usize::from(is_odd);
+            for (i, &byte) in path[1..].iter().enumerate() {
+                let key_nibble = nibble_at(depth)?;
+                let kn_lo = nibble_at(depth + 1)?;
+                all_match &= u64::from(byte >> 4) == key_nibble && u64::from(byte & 0x0f) == kn_lo;
+                rows.push(MptRow {
+                    depth: depth as u64,
+                    key_nibble,
+                    kn_lo,
+                    all_match,
+                    ..MptRow::node_byte(MptTag::PathNibbles, byte, path.len() - 2 - i)
+                });
+                depth += 2;
+            }
+
+            let raw = rlp.at(1)?.as_raw();
+            if is_leaf {
+                if all_match && depth != KEY_ROWS {
+                    return Err(MptProofError::InvalidNode);
+                }
+                match raw {
+                    [byte @ 0x00..=0x7f] => {
+                        rows.push(MptRow::node_header(MptTag::ValueHeader, *byte, 0, 0, 0))
+                    }
+                    [header @ 0x80..=0xb7, value @ ..]
+                        if value.len() == usize::from(header - 0x80) =>
+                    {
+                        rows.push(MptRow::node_header(
+                            MptTag::ValueHeader,
+                            *header,
+                            1,
+                            value.len() as u64,
+                            value.len(),
+                        ));
+                        rows.extend(value.iter().enumerate().map(|(i, &byte)| {
+                            MptRow::node_byte(MptTag::ValueByte, byte, value.len() - 1 - i)
+                        }));
+                    }
+                    [0xb8, len, value @ ..] if value.len() == usize::from(*len) => {
+                        rows.push(MptRow::node_header(MptTag::ValueHeader, 0xb8, 2, 1, 1));
+                        rows.push(MptRow::node_byte(MptTag::ValueLength, *len, value.len()));
+                        rows.extend(value.iter().enumerate().map(|(i, &byte)| {
+                            MptRow::node_byte(MptTag::ValueByte, byte, value.len() - 1 - i)
+                        }));
+                    }
+                    [0xb9..=0xbf, ..] => return Err(MptProofError::UnsupportedNode(index)),
+                    _ => return Err(MptProofError::InvalidNode),
+                }
+            } else {
+                let child_rows = hash_rows(MptTag::ExtChild, raw, index)?;
+                if all_match {
+                    child_hash = Some(H256::from_slice(&raw[1..]));
+                }
+                rows.extend(child_rows.into_iter().map(|row| MptRow {
+                    is_selected: true,
+                    ..row
+                }));
+            }
+            // the value or child after the path
+            for row in rows.iter_mut().skip_while(|row| {
+                matches!(
+                    row.tag,
+                    MptTag::ListHeader
+                        | MptTag::ListLength
+                        | MptTag::PathHeader
+                        | MptTag::PathFlag
+                        | MptTag::PathNibbles
+                )
+            }) {
+                row.all_match = all_match;
+                row.depth = depth as u64;
+            }
+        }
+        _ => return Err(MptProofError::InvalidNode),
+    }
+    for row in rows
+        .iter_mut()
+        .take_while(|row| row.tag != MptTag::PathFlag)
+    {
+        row.depth = node_depth as u64;
+    }
+    if rows.len() != node.len() || rlp.payload_info()?.header_len != payload_offset {
+        return Err(MptProofError::InvalidNode);
+    }
+    Ok((rows, child_hash, depth))
+}
+
+/// Rows of a proof: the nibbles of the hash of its key, and then the bytes
+/// of its nodes from the root.
+/// The hashes of the nodes are only checked by the circuit, for the proofs
+/// not matching their hashes to fail it rather than its witness generation.
+fn proof_rows<F: Field>(
+    proof_id: u64,
+    proof: &MptProof,
+    challenges: &Challenges<Value<F>>,
+) -> Result<Vec<MptRow<F>>, MptProofError> {
+    let r_word = challenges.evm_word();
+    let r_key = challenges.keccak_input();
+    let nibbles: Vec<u8> = keccak256(&proof.key)
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .collect();
+    let base = MptRow {
+        proof_id,
+        key_len: proof.key.len() as u64,
+        root: rlc_be_bytes(proof.root.as_bytes(), r_word),
+        key_rlc: rlc_be_bytes(&proof.key, r_key),
+        ..MptRow::padding()
+    };
+
+    let mut rows = Vec::new();
+    let mut hash_acc = Value::known(F::zero());
+    for (depth, &nibble) in nibbles.iter().enumerate() {
+        rows.push(MptRow {
+            tag: MptTag::Key,
+            byte: nibble,
+            lo: nibble.into(),
+            rem: (KEY_ROWS - 1 - depth) as u64,
+            depth: depth as u64,
+            is_odd: depth % 2 == 1,
+            hash_acc,
+            ..base.clone()
+        });
+        if depth % 2 == 0 {
+            let byte = 16 * nibble + nibbles[depth + 1];
+            hash_acc = hash_acc
+                .zip(r_word)
+                .map(|(acc, r)| acc * r + F::from(byte as u64));
+        }
+    }
+
+    if proof.root == EMPTY_TRIE_ROOT {
+        if !proof.nodes.is_empty() && proof.nodes != [Bytes::from(EMPTY_TRIE_NODE.to_vec())] {
+            return Err(MptProofError::UnusedNodes);
+        }
+        rows.last_mut().expect("key rows").is_proof_end = true;
+        return Ok(rows);
+    }
+
+    let mut next_hash = Some(proof.root);
+    let mut depth = 0;
+    for (index, node) in proof.nodes.iter().enumerate() {
+        let node_hash = next_hash.take().ok_or(MptProofError::UnusedNodes)?;
+        let (mut node_rows, child_hash, next_depth) = node_rows::<F>(index, node, &nibbles, depth)?;
+        let is_branch = node_rows.iter().any(|row| row.tag == MptTag::BranchChild);
+        let is_leaf = node_rows.iter().any(|row| row.tag == MptTag::ValueHeader);
+        let node_hash = rlc_be_bytes(node_hash.as_bytes(), r_word);
+
+        let mut node_rlc = Value::known(F::zero());
+        let mut hash_acc = Value::known(F::zero());
+        let mut value_rlc = Value::known(F::zero());
+        let mut value_len = 0;
+        let mut list_rem = 0;
+        for (i, row) in node_rows.iter_mut().enumerate() {
+            let byte = Value::known(F::from(row.byte as u64));
+            node_rlc = node_rlc.zip(r_key).map(|(acc, r)| acc * r) + byte;
+            if row.tag == MptTag::ChildHash && row.is_selected {
+                hash_acc = hash_acc.zip(r_word).map(|(acc, r)| acc * r) + byte;
+            }
+            match row.tag {
+                MptTag::ValueHeader => {
+                    (value_rlc, value_len) = match row.hi {
+                        0 => (byte, 1),
+                        1 => (Value::known(F::zero()), row.lo),
+                        _ => (Value::known(F::zero()), 0),
+                    }
+                }
+                MptTag::ValueLength => {
+                    (value_rlc, value_len) = (Value::known(F::zero()), row.byte.into())
+                }
+                MptTag::ValueByte => {
+                    value_rlc = value_rlc.zip(r_key).map(|(acc, r)| acc * r) + byte
+                }
+                _ => (),
+            }
+            match row.tag {
+                MptTag::ListHeader | MptTag::ListLength => list_rem = row.list_rem,
+                _ => list_rem -= 1,
+            }
+            *row = MptRow {
+                list_rem,
+                proof_id,
+                key_len: base.key_len,
+                node_len: i as u64 + 1,
+                value_len,
+                root: base.root,
+                key_rlc: base.key_rlc,
+                node_rlc,
+                node_hash,
+                hash_acc,
+                value_rlc,
+                is_branch,
+                is_leaf,
+                cont: child_hash.is_some(),
+                ..row.clone()
+            };
+            row.is_end = match row.tag {
+                MptTag::BranchValue => true,
+                MptTag::ChildHash => row.rem == 0 && !is_branch,
+                MptTag::ValueHeader | MptTag::ValueLength | MptTag::ValueByte => row.rem == 0,
+                _ => false,
+            };
+        }
+        let last = node_rows.last_mut().expect("node rows");
+        last.is_proof_end = child_hash.is_none();
+        rows.extend(node_rows);
+        next_hash = child_hash;
+        depth = next_depth;
+    }
+    if let Some(hash) = next_hash {
+        return Err(MptProofError::MissingNode(hash));
+    }
+    Ok(rows)
+}
+
+impl<F: Field> KeccakMptCircuitConfig<F> {
+    /// Load the fixed table of the bytes accepted by each tag.
+    pub(crate) fn load_fixed_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "keccak mpt fixed table",
+            |mut region| {
+                for (offset, row) in fixed_table_rows().into_iter().enumerate() {
+                    for (column, value) in self.fixed_table.into_iter().zip(row) {
+                        region.assign_fixed(
+                            || "keccak mpt fixed table",
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Assign the proofs to the circuit, padded to `max_rows` rows, or to
+    /// the rows of the proofs and a padding row if `max_rows` is 0.
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        proofs: &[MptProof],
+        max_rows: usize,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let mut rows = Vec::new();
+        for (proof_id, proof) in proofs.iter().enumerate() {
+            rows.extend(
+                proof_rows(proof_id as u64 + 1, proof, challenges).map_err(|err| {
+                    log::error!("invalid mpt proof {proof_id}: {err}");
+                    Error::Synthesis
+                })?,
+            );
+        }
+        let n_rows = if max_rows == 0 {
+            rows.len() + 1
+        } else {
+            max_rows
+        };
+        if rows.len() >= n_rows {
+            log::error!(
+                "keccak mpt circuit rows = {} + 1 padding row > max rows = {}",
+                rows.len(),
+                n_rows
+            );
+            return Err(Error::Synthesis);
+        }
+        rows.resize(n_rows, MptRow::padding());
+
+        let rem_is_zero = IsZeroChip::construct(self.rem_is_zero.clone());
+        let last_child = IsZeroChip::construct(self.last_child.clone());
+        let child_match = IsZeroChip::construct(self.child_match.clone());
+        let hi_match = IsZeroChip::construct(self.hi_match.clone());
+        let lo_match = IsZeroChip::construct(self.lo_match.clone());
+        let diff = |a: u64, b: u64| Value::known(F::from(a) - F::from(b));
+
+        layouter.assign_region(
+            || "keccak mpt",
+            |mut region| {
+                for (offset, row) in rows.iter().enumerate() {
+                    for (column, enabled) in [
+                        (self.q_enable, true),
+                        (self.q_step, offset + 1 < n_rows),
+                        (self.q_first, offset == 0),
+                        (self.q_last, offset + 1 == n_rows),
+                    ] {
+                        region.assign_fixed(
+                            || "keccak mpt selector",
+                            column,
+                            offset,
+                            || Value::known(F::from(enabled as u64)),
+                        )?;
+                    }
+                    for (tag, column) in MptTag::iter().zip(self.tags) {
+                        region.assign_advice(
+                            || format!("keccak mpt tag {tag:?}"),
+                            column,
+                            offset,
+                            || Value::known(F::from((tag == row.tag) as u64)),
+                        )?;
+                    }
+                    for (column, value) in [
+                        (self.byte, row.byte as u64),
+                        (self.hi, row.hi),
+                        (self.lo, row.lo),
+                        (self.rem, row.rem),
+                        (self.list_rem, row.list_rem),
+                        (self.depth, row.depth),
+                        (self.child_index, row.child_index),
+                        (self.key_nibble, row.key_nibble),
+                        (self.kn_lo, row.kn_lo),
+                        (self.is_branch, row.is_branch as u64),
+                        (self.is_leaf, row.is_leaf as u64),
+                        (self.is_odd, row.is_odd as u64),
+                        (self.all_match, row.all_match as u64),
+                        (self.is_selected, row.is_selected as u64),
+                        (self.cont, row.cont as u64),
+                        (self.is_end, row.is_end as u64),
+                        (self.is_proof_end, row.is_proof_end as u64),
+                        (self.proof_id, row.proof_id),
+                        (self.key_len, row.key_len),
+                        (self.node_len, row.node_len),
+                        (self.value_len, row.value_len),
+                    ] {
+                        region.assign_advice(
+                            || "keccak mpt",
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                    for (column, value) in [
+                        (self.root, row.root),
+                        (self.key_rlc, row.key_rlc),
+                        (self.node_rlc, row.node_rlc),
+                        (self.node_hash, row.node_hash),
+                        (self.hash_acc, row.hash_acc),
+                        (self.value_rlc, row.value_rlc),
+                    ] {
+                        region.assign_advice(|| "keccak mpt rlc", column, offset, || value)?;
+                    }
+
+                    rem_is_zero.assign(&mut region, offset, diff(row.rem, 0))?;
+                    last_child.assign(&mut region, offset, diff(row.child_index, 15))?;
+                    child_match.assign(
+                        &mut region,
+                        offset,
+                        diff(row.child_index, row.key_nibble),
+                    )?;
+                    hi_match.assign(&mut region, offset, diff(row.hi, row.key_nibble))?;
+                    lo_match.assign(&mut region, offset, diff(row.lo, row.kn_lo))?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// The keccak MPT circuit, proving the proofs of the keys of the block in
+/// its initial state.
+#[derive(Clone, Debug, Default)]
+pub struct KeccakMptCircuit<F: Field> {
+    /// The proofs
+    pub proofs: Vec<MptProof>,
+    /// Rows of the circuit, or 0 for the rows of the proofs
+    pub max_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> KeccakMptCircuit<F> {
+    /// Return a new KeccakMptCircuit
+    pub fn new(proofs: Vec<MptProof>, max_rows: usize) -> Self {
+        Self {
+            proofs,
+            max_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Rows of the proofs, without padding.
+    pub fn num_rows(proofs: &[MptProof]) -> usize {
+        proofs
+            .iter()
+            .map(|proof| {
+                KEY_ROWS
+                    + if proof.root == EMPTY_TRIE_ROOT {
+                        0
+                    } else {
+                        proof.nodes.iter().map(|node| node.len()).sum()
+                    }
+            })
+            .sum()
+    }
+}
+
+impl<F: Field> SubCircuit<F> for KeccakMptCircuit<F> {
+    type Config = KeccakMptCircuitConfig<F>;
+
+    fn new_from_block(block: &witness::Block) -> Self {
+        Self::new(block.mpt_proofs.clone(), block.circuits_params.max_mpt_rows)
+    }
+
+    fn min_num_rows_block(block: &witness::Block) -> (usize, usize) {
+        let rows = Self::num_rows(&block.mpt_proofs);
+        (
+            rows,
+            (rows + 1)
+                .max(block.circuits_params.max_mpt_rows)
+                .max(fixed_table_rows().len()),
+        )
+    }
+
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.load_fixed_table(layouter)?;
+        config.assign(layouter, &self.proofs, self.max_rows, challenges)
+    }
+}
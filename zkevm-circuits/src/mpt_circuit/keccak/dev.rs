@@ -0,0 +1,59 @@
+pub use super::KeccakMptCircuit;
+
+use super::{KeccakMptCircuitConfig, KeccakMptCircuitConfigArgs};
+use crate::{
+    table::KeccakTable,
+    util::{Challenges, Field, SubCircuit, SubCircuitConfig},
+    witness::keccak::keccak_inputs_mpt_circuit,
+};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Challenge, Circuit, ConstraintSystem, Error},
+};
+
+impl<F: Field> Circuit<F> for KeccakMptCircuit<F> {
+    type Config = (
+        KeccakMptCircuitConfig<F>,
+        KeccakTable,
+        Challenges<Challenge>,
+    );
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(vec![], self.max_rows)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let keccak_table = KeccakTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+        let challenge_exprs = challenges.exprs(meta);
+
+        (
+            KeccakMptCircuitConfig::new(
+                meta,
+                KeccakMptCircuitConfigArgs {
+                    keccak_table: keccak_table.clone(),
+                    challenges: challenge_exprs,
+                },
+            ),
+            keccak_table,
+            challenges,
+        )
+    }
+
+    fn synthesize(
+        &self,
+        (config, keccak_table, challenges): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&layouter);
+        keccak_table.dev_load(
+            &mut layouter,
+            &keccak_inputs_mpt_circuit(&self.proofs),
+            &challenges,
+        )?;
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
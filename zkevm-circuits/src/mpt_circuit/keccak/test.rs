@@ -0,0 +1,145 @@
+use super::*;
+use eth_types::{mpt_proof::secure_trie_proof, Word};
+use ethers_core::utils::rlp;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+
+const K: u32 = 14;
+
+fn slot_key(slot: u64) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    Word::from(slot).to_big_endian(&mut key);
+    key
+}
+
+/// Storage proofs of `keys` in the trie of `slots`.
+fn storage_proofs(slots: &[u64], keys: &[[u8; 32]]) -> Vec<MptProof> {
+    let entries: Vec<_> = slots
+        .iter()
+        .map(|&slot| {
+            (
+                slot_key(slot),
+                rlp::encode(&Word::from(slot * 1000)).to_vec(),
+            )
+        })
+        .collect();
+    keys.iter()
+        .map(|key| {
+            let (root, nodes) = secure_trie_proof(&entries, key);
+            MptProof {
+                root,
+                key: key.to_vec(),
+                nodes,
+            }
+        })
+        .collect()
+}
+
+fn run(proofs: Vec<MptProof>) -> Result<(), Vec<halo2_proofs::dev::VerifyFailure>> {
+    for proof in &proofs {
+        assert!(proof.verify().is_ok());
+    }
+    let circuit = KeccakMptCircuit::<Fr>::new(proofs, 0);
+    let prover = MockProver::run(K, &circuit, vec![]).unwrap();
+    prover.verify_par()
+}
+
+#[test]
+fn keccak_mpt_storage_proofs() {
+    let slots: Vec<u64> = (1..40).collect();
+    let keys: Vec<_> = [1, 7, 39, 40, 1000].map(slot_key).into();
+    assert_eq!(run(storage_proofs(&slots, &keys)), Ok(()));
+}
+
+#[test]
+fn keccak_mpt_extension_proofs() {
+    let first_byte = |slot: u64| keccak256(slot_key(slot))[0];
+    // slots of hashes sharing their first nibble, or their first byte, under
+    // an extension of odd or even path
+    for shared in [0xf0, 0xff] {
+        let (a, b) = (1..)
+            .flat_map(|a| (1..a).map(move |b| (a, b)))
+            .find(|&(a, b)| {
+                let diff = first_byte(a) ^ first_byte(b);
+                diff & shared == 0 && (shared == 0xff || diff != 0)
+            })
+            .unwrap();
+        // a key off the path of the extension
+        let absent = (1..)
+            .find(|&slot| (first_byte(slot) ^ first_byte(a)) & 0xf0 != 0)
+            .unwrap();
+        let keys = [a, b, absent].map(slot_key);
+        assert_eq!(run(storage_proofs(&[a, b], &keys)), Ok(()));
+    }
+}
+
+#[test]
+fn keccak_mpt_account_proofs() {
+    // accounts of long values, as the RLP encoded accounts of the state trie
+    let entries: Vec<_> = (1..20u8)
+        .map(|i| ([i; 20], vec![i; 70 + i as usize]))
+        .collect();
+    let proofs = [[1; 20], [19; 20], [0xaa; 20]]
+        .iter()
+        .map(|key| {
+            let (root, nodes) = secure_trie_proof(&entries, key);
+            MptProof {
+                root,
+                key: key.to_vec(),
+                nodes,
+            }
+        })
+        .collect();
+    assert_eq!(run(proofs), Ok(()));
+}
+
+#[test]
+fn keccak_mpt_empty_trie_proofs() {
+    let keys = [slot_key(1), slot_key(2)];
+    let proofs = storage_proofs(&[], &keys);
+    assert!(proofs.iter().all(|proof| proof.nodes.is_empty()));
+    assert_eq!(run(proofs), Ok(()));
+}
+
+#[test]
+fn keccak_mpt_invalid_value() {
+    let slots: Vec<u64> = (1..40).collect();
+    let mut proofs = storage_proofs(&slots, &[slot_key(7)]);
+    let mut leaf = proofs[0].nodes.last().unwrap().to_vec();
+    *leaf.last_mut().unwrap() ^= 1;
+    *proofs[0].nodes.last_mut().unwrap() = leaf.into();
+    assert!(proofs[0].verify().is_err());
+
+    let circuit = KeccakMptCircuit::<Fr>::new(proofs, 0);
+    let prover = MockProver::run(K, &circuit, vec![]).unwrap();
+    assert!(prover.verify_par().is_err());
+}
+
+#[test]
+fn keccak_mpt_inline_node_unsupported() {
+    // a leaf of a short value is inlined in its branch
+    let leaf = {
+        let mut stream = rlp::RlpStream::new_list(2);
+        stream.append(&vec![0x20u8]).append(&vec![1u8]);
+        stream.out().to_vec()
+    };
+    let mut branch = rlp::RlpStream::new_list(17);
+    branch.append_raw(&leaf, 1);
+    for _ in 1..17 {
+        branch.append_empty_data();
+    }
+    let branch = branch.out().to_vec();
+    let proof = MptProof {
+        root: H256(keccak256(&branch)),
+        key: vec![],
+        nodes: vec![branch.into()],
+    };
+    let challenges = Challenges::mock(
+        Value::known(Fr::from(3)),
+        Value::known(Fr::from(5)),
+        Value::known(Fr::from(7)),
+    );
+    assert_eq!(
+        proof_rows(1, &proof, &challenges).err(),
+        Some(MptProofError::UnsupportedNode(0))
+    );
+}
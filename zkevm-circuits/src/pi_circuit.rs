@@ -12,7 +12,7 @@ use std::{cell::RefCell, collections::BTreeMap, iter, marker::PhantomData};
 use crate::{
     evm_circuit::util::constraint_builder::ConstrainBuilderCommon, table::KeccakTable, util::Field,
 };
-use bus_mapping::circuit_input_builder::get_dummy_tx_hash;
+use bus_mapping::circuit_input_builder::{get_dummy_tx_hash, ChunkContext};
 use eth_types::{
     constants::{get_coinbase_constant, get_difficulty_constant},
     geth_types::TxType,
@@ -34,12 +34,12 @@ use crate::{
     evm_circuit::{util::constraint_builder::BaseConstraintBuilder, EvmCircuitExports},
     pi_circuit::param::{
         BASE_FEE_OFFSET, BLOCK_HEADER_BYTES_NUM, BLOCK_LEN, BLOCK_NUM_OFFSET, BYTE_POW_BASE,
-        CHAIN_ID_OFFSET, GAS_LIMIT_OFFSET, KECCAK_DIGEST_SIZE, RPI_CELL_IDX,
+        CHAIN_ID_OFFSET, GAS_LIMIT_OFFSET, KECCAK_DIGEST_SIZE, N_CHUNK_FIELDS, RPI_CELL_IDX,
         RPI_LENGTH_ACC_CELL_IDX, RPI_RLC_ACC_CELL_IDX, TIMESTAMP_OFFSET,
     },
     state_circuit::StateCircuitExports,
     tx_circuit::{CHAIN_ID_OFFSET as CHAIN_ID_OFFSET_IN_TX, TX_LEN},
-    witness::{self, Block, BlockContext, BlockContexts, Transaction},
+    witness::{self, chunk_table_values, Block, BlockContext, BlockContexts, Transaction},
 };
 use gadgets::util::{and, not, select, Expr};
 use halo2_proofs::{
@@ -63,6 +63,26 @@ use crate::{
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 use halo2_proofs::{circuit::SimpleFloorPlanner, plonk::Circuit};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+/// Layout of the public inputs exposed by the PI circuit. Verifier contracts are generated for
+/// one layout, so a layout is never changed in place but superseded by a new version. Each
+/// version exposes the public inputs of the previous one, followed by its own.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum PiLayout {
+    /// The pi hash bytes, followed by the anchor bytes if any, and the bytes of the chunk context
+    /// if the block is proven in chunks.
+    #[default]
+    V1,
+    /// The public inputs of [`PiLayout::V1`], followed by the withdraw trie root bytes and the
+    /// bytes of the EIP-4844 versioned hashes of the blobs of the txs, in tx order.
+    V2,
+    /// The public inputs of [`PiLayout::V2`], followed by the bytes of the keccak hash of the
+    /// concatenated RLP-signed bytes of the L2 txs, the data availability commitment of the
+    /// chunk. The verifier contract can check it against the published tx data without
+    /// re-encoding the txs.
+    V3,
+}
 
 /// PublicData contains all the values that the PiCircuit receives as input
 #[derive(Debug, Clone)]
@@ -87,6 +107,19 @@ pub struct PublicData {
     pub max_calldata: usize,
     /// Max number of supported inner blocks in a chunk
     pub max_inner_blocks: usize,
+    /// Externally supplied anchoring value (e.g. an L1 block hash or an EIP-4788 beacon root).
+    /// When set, the circuit reserves an extra slot for it which is exposed in the instance
+    /// column right after the pi hash and copied into the block table with the `Anchor` tag.
+    /// Like the `max_*` fields, its presence determines the circuit layout.
+    pub anchor: Option<H256>,
+    /// Context of the chunk of the block, if it is proven in chunks. When set, its fields are
+    /// exposed in the instance column after the anchor, as 8 big-endian bytes each, and copied
+    /// into the block table with the chunk tags. Otherwise the block table holds the constant
+    /// context of a block proven in a single chunk.
+    pub chunk: Option<ChunkContext>,
+    /// Layout of the public inputs. Since [`PiLayout::V2`], the number of blob versioned hashes
+    /// of the txs also determines the circuit layout.
+    pub layout: PiLayout,
 }
 
 impl PublicData {
@@ -323,6 +356,53 @@ impl PublicData {
     fn constants_end_offset(&self) -> usize {
         self.constants_start_offset() + N_BYTES_ACCOUNT_ADDRESS + N_BYTES_WORD
     }
+
+    fn anchor_start_offset(&self) -> usize {
+        self.constants_end_offset() + 1 // new row.
+    }
+
+    fn anchor_end_offset(&self) -> usize {
+        self.anchor_start_offset() + self.anchor.map_or(0, |_| N_BYTES_WORD)
+    }
+
+    fn chunk_start_offset(&self) -> usize {
+        self.anchor
+            .map_or(self.anchor_start_offset(), |_| self.anchor_end_offset() + 1)
+    }
+
+    fn chunk_end_offset(&self) -> usize {
+        self.chunk_start_offset() + self.chunk.map_or(0, |_| N_CHUNK_FIELDS * N_BYTES_U64)
+    }
+
+    fn blob_hashes_start_offset(&self) -> usize {
+        self.chunk
+            .map_or(self.chunk_start_offset(), |_| self.chunk_end_offset() + 1)
+    }
+
+    /// Big-endian bytes of the fields of the chunk context, if any.
+    fn chunk_bytes(&self) -> Vec<u8> {
+        self.chunk
+            .iter()
+            .flat_map(chunk_table_values)
+            .flat_map(|(_, value)| value.to_be_bytes())
+            .collect()
+    }
+
+    fn blob_hashes_end_offset(&self) -> usize {
+        self.blob_hashes_start_offset() + self.blob_versioned_hashes().len() * N_BYTES_WORD
+    }
+
+    /// Versioned hashes of the blobs of the txs, exposed as public inputs since
+    /// [`PiLayout::V2`].
+    fn blob_versioned_hashes(&self) -> Vec<H256> {
+        if self.layout < PiLayout::V2 {
+            return vec![];
+        }
+        self.transactions
+            .iter()
+            .flat_map(|tx| tx.blob.blob_versioned_hashes.iter().copied())
+            .collect()
+    }
 }
 
 impl BlockContext {
@@ -335,9 +415,11 @@ impl BlockContext {
             number: Default::default(),
             timestamp: Default::default(),
             base_fee: Default::default(),
+            blob_base_fee: Default::default(),
             history_hashes: vec![],
             parent_hash: Default::default(),
             state_root: Default::default(),
+            receipts_root: Default::default(),
         }
     }
 }
@@ -357,6 +439,8 @@ enum RpiFieldType {
     KeccakHiLo,
     /// Whether the assigned field represents the block's coinbase/difficulty constants.
     Constant,
+    /// Whether the assigned field represents the externally supplied anchoring value.
+    Anchor,
 }
 
 /// Config for PiCircuit
@@ -772,9 +856,19 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
     }
 }
 
-// (hi cell, lo cell)
+// pi hash byte cells, followed by the anchor byte cells if any, the chunk context byte cells if
+// any, and since the V2 layout the
+// withdraw trie root byte cells and the blob versioned hashes byte cells, and since the V3
+// layout the chunk txbytes hash byte cells.
 type PiHashExport<F> = Vec<AssignedCell<F, F>>;
 
+// Byte cells of the pi bytes fields which are exposed as public inputs by the later layouts.
+#[derive(Debug, Clone)]
+struct PiBytesExport<F: Field> {
+    withdraw_root: Vec<AssignedCell<F, F>>,
+    chunk_txbytes_hash: Vec<AssignedCell<F, F>>,
+}
+
 #[derive(Debug, Clone)]
 struct Connections<F: Field> {
     start_state_root: AssignedCell<F, F>,
@@ -835,10 +929,23 @@ impl<F: Field> PiCircuitConfig<F> {
     /// | ASSIGN   | coinbase               |                          |
     /// | CONSTS   | difficulty             |                          |
     /// |----------|------------------------|--------------------------|
+    /// | *PART 6* | rpi initialise         |                          |
+    /// | ANCHOR   | anchor                 | (only if anchor is set)  |
+    /// |----------|------------------------|--------------------------|
+    /// |          | rpi initialise         |                          |
+    /// | *PART 7* | chunk.initial_rwc      | (only if the block is    |
+    /// | CHUNK    | chunk.end_rwc          |  proven in chunks)       |
+    /// |          | chunk.initial_tx_id    |                          |
+    /// |          | chunk.end_tx_id        |                          |
+    /// |----------|------------------------|--------------------------|
+    /// | *PART 8* | rpi initialise         | (only since the V2       |
+    /// | BLOB     | blob_hash\[0\]         |  layout, with blob txs)  |
+    /// | HASHES   | ...                    |                          |
+    /// |----------|------------------------|--------------------------|
     ///
     /// Where each one of the rows above, i.e. block\[0\].number, block\[0\].timestamp,
-    /// ..., pi_hash_lo, coinbase, difficulty are assigned using the
-    /// assign_field method.
+    /// ..., pi_hash_lo, coinbase, difficulty, anchor, chunk.initial_rwc, blob_hash\[i\] are
+    /// assigned using the assign_field method.
     ///
     /// Each `field` takes multiple rows in the actual circuit layout depending on how many bytes
     /// it takes to represent the said field. For instance, pi_hash_lo represent the lower 16 bytes
@@ -872,7 +979,7 @@ impl<F: Field> PiCircuitConfig<F> {
         debug_assert_eq!(offset, public_data.pi_bytes_start_offset());
 
         // 3. Assign public input bytes.
-        let (offset, pi_hash_rlc_cell, connections) = self.assign_pi_bytes(
+        let (offset, pi_hash_rlc_cell, pi_bytes_export, connections) = self.assign_pi_bytes(
             region,
             offset,
             public_data,
@@ -894,7 +1001,67 @@ impl<F: Field> PiCircuitConfig<F> {
             self.assign_constants(region, offset, public_data, block_value_cells, challenges)?;
         debug_assert_eq!(offset, public_data.constants_end_offset() + 1);
 
-        Ok((pi_hash_cells, connections))
+        // 6. Assign the optional anchor.
+        let (offset, anchor_cells) = match public_data.anchor {
+            Some(anchor) => {
+                let (offset, cells) = self.assign_anchor(
+                    region,
+                    offset,
+                    public_data,
+                    anchor,
+                    block_value_cells,
+                    challenges,
+                )?;
+                debug_assert_eq!(offset, public_data.anchor_end_offset() + 1);
+                (offset, cells)
+            }
+            None => (offset, vec![]),
+        };
+        debug_assert_eq!(offset, public_data.chunk_start_offset());
+
+        // 7. Assign the chunk context of a block proven in chunks.
+        let (offset, chunk_cells) = match public_data.chunk {
+            Some(chunk) => {
+                let (offset, cells) = self.assign_chunk(
+                    region,
+                    offset,
+                    public_data,
+                    &chunk,
+                    block_value_cells,
+                    challenges,
+                )?;
+                debug_assert_eq!(offset, public_data.chunk_end_offset() + 1);
+                (offset, cells)
+            }
+            None => (offset, vec![]),
+        };
+        debug_assert_eq!(offset, public_data.blob_hashes_start_offset());
+
+        // 8. Assign the blob versioned hashes of the V2 layout.
+        let blob_hashes = public_data.blob_versioned_hashes();
+        let blob_hash_cells = if blob_hashes.is_empty() {
+            vec![]
+        } else {
+            let (offset, cells) = self.assign_blob_versioned_hashes(
+                region,
+                offset,
+                public_data,
+                &blob_hashes,
+                challenges,
+            )?;
+            debug_assert_eq!(offset, public_data.blob_hashes_end_offset() + 1);
+            cells
+        };
+
+        let mut pi_cells = [pi_hash_cells, anchor_cells, chunk_cells].concat();
+        if public_data.layout >= PiLayout::V2 {
+            pi_cells.extend(pi_bytes_export.withdraw_root);
+            pi_cells.extend(blob_hash_cells);
+        }
+        if public_data.layout >= PiLayout::V3 {
+            pi_cells.extend(pi_bytes_export.chunk_txbytes_hash);
+        }
+        Ok((pi_cells, connections))
     }
 
     /// Assign data bytes, that represent the pre-image to data_hash.
@@ -1149,6 +1316,8 @@ impl<F: Field> PiCircuitConfig<F> {
 
     /// Assign public input bytes, that represent the pre-image to pi_hash.
     /// i.e. keccak256(rlc(pi_bytes)) == pi_hash.
+    /// Also returns the byte cells of the withdraw trie root and of the chunk txbytes hash, exposed
+    /// as public inputs by the later layouts.
     #[allow(clippy::too_many_arguments)]
     #[allow(clippy::type_complexity)]
     fn assign_pi_bytes(
@@ -1161,7 +1330,7 @@ impl<F: Field> PiCircuitConfig<F> {
         data_hash_rlc_cell: &AssignedCell<F, F>,
         chunk_txbytes_hash_rlc_cell: &AssignedCell<F, F>,
         challenges: &Challenges<Value<F>>,
-    ) -> Result<(usize, AssignedCell<F, F>, Connections<F>), Error> {
+    ) -> Result<(usize, AssignedCell<F, F>, PiBytesExport<F>, Connections<F>), Error> {
         let (mut offset, mut rpi_rlc_acc, mut rpi_length) = self.assign_rlc_init(region, offset)?;
 
         // Enable RLC accumulator consistency check throughout the above rows.
@@ -1171,7 +1340,7 @@ impl<F: Field> PiCircuitConfig<F> {
 
         // Assign [chain_id, prev_state_root, state_root, withdraw_trie_root].
         let mut cells = vec![];
-        let rpi_cells = [
+        let (rpi_cells, rpi_byte_cells): (Vec<_>, Vec<_>) = [
             public_data.chain_id.to_be_bytes().to_vec(),
             public_data.prev_state_root.to_fixed_bytes().to_vec(),
             public_data.next_state_root.to_fixed_bytes().to_vec(),
@@ -1189,9 +1358,11 @@ impl<F: Field> PiCircuitConfig<F> {
                 rpi_length,
                 challenges,
             )?;
-            Ok(cells[RPI_CELL_IDX].clone())
+            Ok((cells[RPI_CELL_IDX].clone(), cells[3..].to_vec()))
         })
-        .collect::<Result<Vec<_>, Error>>()?;
+        .collect::<Result<Vec<_>, Error>>()?
+        .into_iter()
+        .unzip();
 
         // copy chain_id to block table
         for block_idx in 0..public_data.max_inner_blocks {
@@ -1242,6 +1413,10 @@ impl<F: Field> PiCircuitConfig<F> {
             challenges,
         )?;
         let chunk_txbytes_hash_cell = cells[RPI_CELL_IDX].clone();
+        let pi_bytes_export = PiBytesExport {
+            withdraw_root: rpi_byte_cells[3].clone(),
+            chunk_txbytes_hash: cells[3..].to_vec(),
+        };
         let pi_bytes_rlc = cells[RPI_RLC_ACC_CELL_IDX].clone();
         let pi_bytes_length = cells[RPI_LENGTH_ACC_CELL_IDX].clone();
 
@@ -1274,7 +1449,7 @@ impl<F: Field> PiCircuitConfig<F> {
         };
         self.q_keccak.enable(region, offset)?;
 
-        Ok((offset + 1, pi_hash_rlc_cell, connections))
+        Ok((offset + 1, pi_hash_rlc_cell, pi_bytes_export, connections))
     }
 
     /// Assign the (hi, lo) decomposition of pi_hash.
@@ -1380,6 +1555,123 @@ impl<F: Field> PiCircuitConfig<F> {
         Ok(offset)
     }
 
+    /// Assign the externally supplied anchor and copy it to the block table. Returns the offset
+    /// after the anchor rows and the anchor's byte cells, which are exposed as public inputs.
+    fn assign_anchor(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        public_data: &PublicData,
+        anchor: H256,
+        block_value_cells: &[AssignedCell<F, F>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(usize, Vec<AssignedCell<F, F>>), Error> {
+        let (offset, rpi_rlc_acc, rpi_length) = self.assign_rlc_init(region, offset)?;
+
+        // Enable RLC accumulator consistency check throughout the above rows.
+        for q_offset in public_data.anchor_start_offset()..public_data.anchor_end_offset() {
+            self.q_not_end.enable(region, q_offset)?;
+        }
+
+        let (offset, _, _, cells) = self.assign_field(
+            region,
+            offset,
+            &anchor.to_fixed_bytes(),
+            RpiFieldType::Anchor,
+            false, // no padding in this case
+            rpi_rlc_acc,
+            rpi_length,
+            challenges,
+        )?;
+
+        // Copy anchor to the block table, where it is the last assigned row.
+        let anchor_block_value_cell = block_value_cells
+            .last()
+            .expect("anchor must have been assigned to block table");
+        region.constrain_equal(cells[RPI_CELL_IDX].cell(), anchor_block_value_cell.cell())?;
+
+        Ok((offset, cells[3..].to_vec()))
+    }
+
+    /// Assign the context of the chunk and copy it to the block table. Returns the offset after
+    /// the chunk rows and the chunk's byte cells, which are exposed as public inputs.
+    fn assign_chunk(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        public_data: &PublicData,
+        chunk: &ChunkContext,
+        block_value_cells: &[AssignedCell<F, F>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(usize, Vec<AssignedCell<F, F>>), Error> {
+        let (mut offset, mut rpi_rlc_acc, mut rpi_length) = self.assign_rlc_init(region, offset)?;
+
+        // Enable RLC accumulator consistency check throughout the above rows.
+        for q_offset in public_data.chunk_start_offset()..public_data.chunk_end_offset() {
+            self.q_not_end.enable(region, q_offset)?;
+        }
+
+        // The chunk fields follow the blocks in the block table.
+        let chunk_value_cells = &block_value_cells[public_data.max_inner_blocks * BLOCK_LEN..];
+        let mut cells = vec![];
+        let mut byte_cells = vec![];
+        for ((_, value), block_value_cell) in
+            chunk_table_values(chunk).into_iter().zip(chunk_value_cells)
+        {
+            (offset, rpi_rlc_acc, rpi_length, cells) = self.assign_field(
+                region,
+                offset,
+                &value.to_be_bytes(),
+                RpiFieldType::DefaultType,
+                false, // no padding in this case
+                rpi_rlc_acc,
+                rpi_length,
+                challenges,
+            )?;
+            region.constrain_equal(cells[RPI_CELL_IDX].cell(), block_value_cell.cell())?;
+            byte_cells.extend_from_slice(&cells[3..]);
+        }
+
+        Ok((offset, byte_cells))
+    }
+
+    /// Assign the blob versioned hashes of the V2 layout. Returns the offset after the blob
+    /// hashes rows and their byte cells, which are exposed as public inputs.
+    fn assign_blob_versioned_hashes(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        public_data: &PublicData,
+        blob_hashes: &[H256],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(usize, Vec<AssignedCell<F, F>>), Error> {
+        let (mut offset, mut rpi_rlc_acc, mut rpi_length) = self.assign_rlc_init(region, offset)?;
+
+        // Enable RLC accumulator consistency check throughout the above rows.
+        for q_offset in public_data.blob_hashes_start_offset()..public_data.blob_hashes_end_offset()
+        {
+            self.q_not_end.enable(region, q_offset)?;
+        }
+
+        let mut cells = vec![];
+        let mut byte_cells = vec![];
+        for blob_hash in blob_hashes {
+            (offset, rpi_rlc_acc, rpi_length, cells) = self.assign_field(
+                region,
+                offset,
+                &blob_hash.to_fixed_bytes(),
+                RpiFieldType::DefaultType,
+                false, // no padding in this case
+                rpi_rlc_acc,
+                rpi_length,
+                challenges,
+            )?;
+            byte_cells.extend_from_slice(&cells[3..]);
+        }
+
+        Ok((offset, byte_cells))
+    }
+
     /// Initialise the RLC computation at the row with the given offset. Returns the offset at the
     /// next row.
     fn assign_rlc_init(
@@ -1437,7 +1729,9 @@ impl<F: Field> PiCircuitConfig<F> {
         //
         // However for other cases, we use the keccak randomness from the challenge API.
         let (is_rlc_keccak, rlc_rand) = match field_type {
-            RpiFieldType::KeccakHiLo | RpiFieldType::Constant => (F::zero(), challenges.evm_word()),
+            RpiFieldType::KeccakHiLo | RpiFieldType::Constant | RpiFieldType::Anchor => {
+                (F::zero(), challenges.evm_word())
+            }
             _ => (F::one(), challenges.keccak_input()),
         };
 
@@ -1752,6 +2046,76 @@ impl<F: Field> PiCircuitConfig<F> {
             }
         }
 
+        // The chunk context is not bound to any block either, so each field is assigned once with
+        // index 0. The context of a block proven in a single chunk is constant, whereas the one
+        // of a chunk is copied from the public inputs.
+        for (tag, value) in chunk_table_values(&public_data.chunk.unwrap_or_default()) {
+            region.assign_fixed(
+                || format!("block table row {offset}"),
+                self.block_table.tag,
+                offset,
+                || Value::known(F::from(tag as u64)),
+            )?;
+            region.assign_advice_from_constant(
+                || "chunk index",
+                self.block_table.index,
+                offset,
+                F::zero(),
+            )?;
+            let chunk_cell = if public_data.chunk.is_some() {
+                region.assign_advice(
+                    || "chunk value",
+                    self.block_table.value,
+                    offset,
+                    || Value::known(F::from(value)),
+                )?
+            } else {
+                region.assign_advice_from_constant(
+                    || "chunk value",
+                    self.block_table.value,
+                    offset,
+                    F::from(value),
+                )?
+            };
+            region.assign_advice(
+                || "cum_num_txs",
+                self.cum_num_txs,
+                offset,
+                || Value::known(F::zero()),
+            )?;
+            block_value_cells.push(chunk_cell);
+            offset += 1;
+        }
+
+        // The anchor is not bound to any block, so it is assigned once with index 0.
+        if let Some(anchor) = public_data.anchor {
+            region.assign_fixed(
+                || format!("block table row {offset}"),
+                self.block_table.tag,
+                offset,
+                || Value::known(F::from(BlockContextFieldTag::Anchor as u64)),
+            )?;
+            region.assign_advice_from_constant(
+                || "anchor index",
+                self.block_table.index,
+                offset,
+                F::zero(),
+            )?;
+            let anchor_cell = region.assign_advice(
+                || "anchor value",
+                self.block_table.value,
+                offset,
+                || rlc_be_bytes(&anchor.to_fixed_bytes(), challenges.evm_word()),
+            )?;
+            region.assign_advice(
+                || "cum_num_txs",
+                self.cum_num_txs,
+                offset,
+                || Value::known(F::zero()),
+            )?;
+            block_value_cells.push(anchor_cell);
+        }
+
         Ok(block_value_cells)
     }
 }
@@ -1791,6 +2155,9 @@ impl<F: Field> PiCircuit<F> {
             prev_state_root: prev_state_root_in_trie,
             next_state_root: block.post_state_root(),
             withdraw_trie_root: H256(block.withdraw_root.to_be_bytes()),
+            anchor: block.anchor,
+            chunk: block.chunk,
+            layout: block.pi_layout,
         };
 
         Self {
@@ -1883,6 +2250,15 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
         let tx_usage = block.txs.len() as f32 / block.circuits_params.max_txs as f32;
         let max_inner_blocks = block.circuits_params.max_inner_blocks;
         let max_txs = block.circuits_params.max_txs;
+        let num_blob_hashes: usize = if block.pi_layout >= PiLayout::V2 {
+            block
+                .txs
+                .iter()
+                .map(|tx| tx.blob.blob_versioned_hashes.len())
+                .sum()
+        } else {
+            0
+        };
 
         let num_rows = 1 + max_inner_blocks * BLOCK_HEADER_BYTES_NUM
             + max_txs * KECCAK_DIGEST_SIZE
@@ -1896,7 +2272,14 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
             + KECCAK_DIGEST_SIZE // pi hash bytes
             + 1 // for coinbase & difficulty start row
             + N_BYTES_ACCOUNT_ADDRESS
-            + N_BYTES_WORD;
+            + N_BYTES_WORD
+            + block.anchor.map_or(0, |_| 1 + N_BYTES_WORD) // anchor start row & bytes
+            + block.chunk.map_or(0, |_| 1 + N_CHUNK_FIELDS * N_BYTES_U64) // chunk start row & bytes
+            + if num_blob_hashes > 0 {
+                1 + num_blob_hashes * N_BYTES_WORD // blob hashes start row & bytes
+            } else {
+                0
+            };
 
         (
             (tx_usage * block.circuits_params.max_vertical_circuit_rows as f32).ceil() as usize,
@@ -1909,12 +2292,26 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
         let pi_hash = self.public_data.get_pi();
 
         let public_inputs = iter::empty()
+            .chain(pi_hash.to_fixed_bytes())
             .chain(
-                pi_hash
-                    .to_fixed_bytes()
+                self.public_data
+                    .anchor
+                    .iter()
+                    .flat_map(|anchor| anchor.to_fixed_bytes()),
+            )
+            .chain(self.public_data.chunk_bytes())
+            .chain(
+                (self.public_data.layout >= PiLayout::V2)
+                    .then_some(self.public_data.withdraw_trie_root)
                     .into_iter()
-                    .map(|byte| F::from(byte as u64)),
+                    .chain(self.public_data.blob_versioned_hashes())
+                    .chain(
+                        (self.public_data.layout >= PiLayout::V3)
+                            .then(|| self.public_data.get_chunk_txbytes_hash()),
+                    )
+                    .flat_map(|hash| hash.to_fixed_bytes()),
             )
+            .map(|byte| F::from(byte as u64))
             .collect::<Vec<F>>();
 
         vec![public_inputs]
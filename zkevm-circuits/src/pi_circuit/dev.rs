@@ -31,6 +31,9 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNER_
                 next_state_root: H256::zero(),
                 withdraw_trie_root: H256::zero(),
                 block_ctxs: Default::default(),
+                anchor: None,
+                chunk: None,
+                layout: Default::default(),
             },
             connections: Default::default(),
             tx_value_cells: Default::default(),
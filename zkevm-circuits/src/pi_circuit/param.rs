@@ -3,6 +3,8 @@ pub(super) const BLOCK_LEN: usize = 10;
 pub(super) const BYTE_POW_BASE: u64 = 256;
 pub(super) const BLOCK_HEADER_BYTES_NUM: usize = 58;
 pub(super) const KECCAK_DIGEST_SIZE: usize = 32;
+/// Number of fields of the chunk context, which follow the blocks in the block table
+pub(super) const N_CHUNK_FIELDS: usize = 4;
 
 pub(super) const RPI_CELL_IDX: usize = 0;
 pub(super) const RPI_RLC_ACC_CELL_IDX: usize = 1;
@@ -106,6 +106,110 @@ fn serial_test_simple_pi() {
     );
 }
 
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_test_simple_pi_with_anchor() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 20;
+    const MAX_INNER_BLOCKS: usize = 4;
+
+    let mut difficulty_be_bytes = [0u8; 32];
+    MOCK_DIFFICULTY.to_big_endian(&mut difficulty_be_bytes);
+    set_var("DIFFICULTY", hex::encode(difficulty_be_bytes));
+    set_var("COINBASE", "0x0000000000000000000000000000000000000000");
+
+    let mut block = block_1tx();
+    block.anchor = Some(H256::repeat_byte(0xab));
+
+    let circuit = PiCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, &block);
+    let instance = circuit.instance();
+    assert_eq!(instance[0].len(), KECCAK_DIGEST_SIZE + N_BYTES_WORD);
+    assert!(instance[0][KECCAK_DIGEST_SIZE..]
+        .iter()
+        .all(|byte| *byte == Fr::from(0xab)));
+
+    let k = 16;
+    assert_eq!(
+        run::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(k, block),
+        Ok(())
+    );
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_test_simple_pi_layout_v2() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 20;
+    const MAX_INNER_BLOCKS: usize = 4;
+
+    let mut difficulty_be_bytes = [0u8; 32];
+    MOCK_DIFFICULTY.to_big_endian(&mut difficulty_be_bytes);
+    set_var("DIFFICULTY", hex::encode(difficulty_be_bytes));
+    set_var("COINBASE", "0x0000000000000000000000000000000000000000");
+
+    let mut block = block_1tx();
+    block.pi_layout = PiLayout::V2;
+    block.withdraw_root = Word::from_big_endian(&[0xcd; 32]);
+    block.txs[0].blob.blob_versioned_hashes =
+        vec![H256::repeat_byte(0x01), H256::repeat_byte(0x02)];
+
+    let circuit = PiCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, &block);
+    let instance = circuit.instance();
+    assert_eq!(instance[0].len(), KECCAK_DIGEST_SIZE + 3 * N_BYTES_WORD);
+    for (i, byte) in [0xcd, 0x01, 0x02].into_iter().enumerate() {
+        let start = KECCAK_DIGEST_SIZE + i * N_BYTES_WORD;
+        assert!(instance[0][start..start + N_BYTES_WORD]
+            .iter()
+            .all(|value| *value == Fr::from(byte)));
+    }
+
+    let k = 16;
+    assert_eq!(
+        run::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(k, block),
+        Ok(())
+    );
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_test_simple_pi_layout_v3() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 20;
+    const MAX_INNER_BLOCKS: usize = 4;
+
+    let mut difficulty_be_bytes = [0u8; 32];
+    MOCK_DIFFICULTY.to_big_endian(&mut difficulty_be_bytes);
+    set_var("DIFFICULTY", hex::encode(difficulty_be_bytes));
+    set_var("COINBASE", "0x0000000000000000000000000000000000000000");
+
+    let mut block = block_1tx();
+    block.pi_layout = PiLayout::V3;
+
+    let circuit = PiCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, &block);
+    let instance = circuit.instance();
+    assert_eq!(instance[0].len(), KECCAK_DIGEST_SIZE + 2 * N_BYTES_WORD);
+    let txbytes = block
+        .txs
+        .iter()
+        .filter(|tx| tx.is_chunk_l2_tx())
+        .flat_map(|tx| tx.rlp_signed.clone())
+        .collect::<Vec<u8>>();
+    let txbytes_hash = keccak256(txbytes)
+        .into_iter()
+        .map(|byte| Fr::from(byte as u64))
+        .collect::<Vec<Fr>>();
+    assert_eq!(
+        instance[0][KECCAK_DIGEST_SIZE + N_BYTES_WORD..],
+        txbytes_hash
+    );
+
+    let k = 16;
+    assert_eq!(
+        run::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(k, block),
+        Ok(())
+    );
+}
+
 fn run_size_check<
     F: Field,
     const MAX_TXS: usize,
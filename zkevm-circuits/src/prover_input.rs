@@ -0,0 +1,87 @@
+//! Witness generation of a block from the traces a prover is given: the
+//! [`CircuitInputBuilder`] is built from the traces and its block converted
+//! to the witness [`Block`] of the circuits.
+
+use crate::witness::{block_convert, Block};
+use bus_mapping::{
+    circuit_input_builder::{BuildMonitor, CircuitInputBuilder, CircuitsParams},
+    mock::BlockData,
+    Error,
+};
+use eth_types::{evm_types::GasSchedule, geth_types::GethData, l2_types::BlockTrace};
+
+/// Witness block of a traced block, with the builder it was generated with
+/// for callers inspecting the bus-mapping side too.
+#[derive(Debug)]
+pub struct ProverInput {
+    /// Witness block of the circuits.
+    pub block: Block,
+    /// Builder which handled the traces of the block.
+    pub builder: CircuitInputBuilder,
+}
+
+/// Build the witness of the block and traces of `geth_data`, e.g. a block
+/// traced by an L1 node or a mock `TestContext`.
+pub fn build_witness_from_eth_block(
+    geth_data: &GethData,
+    circuits_params: CircuitsParams,
+) -> Result<ProverInput, Error> {
+    let builder = BlockData::new_from_geth_data_with_params(geth_data.clone(), circuits_params)
+        .new_circuit_input_builder();
+    handle_eth_block(builder, geth_data)
+}
+
+/// Build the witness of a block traced by an L2 node, with the MPT updates of
+/// its storage trace applied.
+pub fn build_witness_from_l2_trace(
+    l2_trace: BlockTrace,
+    circuits_params: CircuitsParams,
+) -> Result<ProverInput, Error> {
+    build_witness_from_l2_trace_with_monitor(l2_trace, circuits_params, BuildMonitor::default())
+}
+
+/// Same as [`build_witness_from_l2_trace`], with `monitor` reporting the
+/// progress of the witness generation.
+pub fn build_witness_from_l2_trace_with_monitor(
+    l2_trace: BlockTrace,
+    circuits_params: CircuitsParams,
+    monitor: BuildMonitor,
+) -> Result<ProverInput, Error> {
+    let mut builder = CircuitInputBuilder::new_from_l2_trace_with_monitor(
+        circuits_params,
+        l2_trace,
+        false,
+        monitor,
+    )?;
+    builder.finalize_building()?;
+    let mut block = block_convert(&builder.block, &builder.code_db)?;
+    // not in light mode, the MPT state is always built
+    block.apply_mpt_updates(builder.mpt_init_state.as_ref().unwrap());
+    Ok(ProverInput { block, builder })
+}
+
+/// Build the witness of the block of a state test, traced into `geth_data`.
+/// Unlike [`build_witness_from_eth_block`], the nonces of the txs are checked
+/// and the gas costs follow `gas_schedule`, the fork the test targets.
+pub fn build_witness_from_state_test(
+    geth_data: &GethData,
+    circuits_params: CircuitsParams,
+    gas_schedule: GasSchedule,
+    monitor: BuildMonitor,
+) -> Result<ProverInput, Error> {
+    let mut builder = BlockData::new_from_geth_data_with_params(geth_data.clone(), circuits_params)
+        .new_circuit_input_builder();
+    builder.block.check_tx_nonce = true;
+    builder.block.gas_schedule = gas_schedule;
+    builder.monitor = monitor;
+    handle_eth_block(builder, geth_data)
+}
+
+fn handle_eth_block(
+    mut builder: CircuitInputBuilder,
+    geth_data: &GethData,
+) -> Result<ProverInput, Error> {
+    builder.handle_block(&geth_data.eth_block, &geth_data.geth_traces)?;
+    let block = block_convert(&builder.block, &builder.code_db)?;
+    Ok(ProverInput { block, builder })
+}
@@ -22,7 +22,7 @@ use crate::{
     },
 };
 use gadgets::{
-    binary_number::{BinaryNumberChip, BinaryNumberConfig},
+    binary_number::{BinaryNumberChip, BinaryNumberConfig, BinaryNumberTag},
     comparator::{ComparatorChip, ComparatorConfig, ComparatorInstruction},
     is_equal::{IsEqualChip, IsEqualConfig, IsEqualInstruction},
     util::{and, not, select, sum, Expr},
@@ -244,7 +244,7 @@ pub struct RlpCircuitConfig<F> {
     /// The state of RLP verifier at the current row.
     state: Column<Advice>,
     /// A utility gadget to compare/query what state we are at.
-    state_bits: BinaryNumberConfig<State, 3>,
+    state_bits: BinaryNumberConfig<State, { State::N_BITS }>,
     /// The Rlp table which can be accessed by other circuits.
     rlp_table: RlpFsmRlpTable,
     /// The Rlp decoding table ensuring correct transition of
@@ -253,7 +253,7 @@ pub struct RlpCircuitConfig<F> {
     /// The tag, i.e. what field is being decoded at the current row.
     tag: Column<Advice>,
     /// A utility gadget to compare/query what tag we are at.
-    tag_bits: BinaryNumberConfig<Tag, 5>,
+    tag_bits: BinaryNumberConfig<Tag, { Tag::N_BITS }>,
     /// The tag that will be decoded next after the current tag is done decoding.
     tag_next: Column<Advice>,
     /// Boolean check whether or not the current tag represents a list or not.
@@ -1620,7 +1620,8 @@ impl<F: Field> RlpCircuitConfig<F> {
         // note: right now no other nested structures are defined at these depth levels
         // hence using depth alone is sufficient to determine clearing conditions.
         // however, this might change in the future if more nested structures are introduced at same
-        // depth level
+        // depth level. The blob_versioned_hashes list of EIP-4844 txs also ends at depth 2, after
+        // the access list, where al_idx is already cleared.
         meta.create_gate(
             "access list: clearing access_list_idx and storage_key_idx",
             |meta| {
@@ -1,6 +1,10 @@
 #![allow(unused_imports)]
 use crate::{rlp_circuit_fsm::RlpCircuit, witness::Transaction};
-use eth_types::{geth_types::TxType, word, Address};
+use eth_types::{
+    geth_types::{get_rlp_signed, get_rlp_unsigned, TxType},
+    tx_envelope::decode_signed,
+    word, AccessList, AccessListItem, Address, H256,
+};
 use ethers_core::{
     types::{
         transaction::eip2718::TypedTransaction, Eip1559TransactionRequest,
@@ -124,12 +128,9 @@ fn test_eip1559_tx() {
         .map(|(idx, bytes)| {
             let raw_tx_rlp_bytes = hex::decode(bytes).expect("decode tx's hex shall not fail");
 
-            let eth_tx = EthTransaction::decode(&Rlp::new(&raw_tx_rlp_bytes))
-                .expect("decode tx's rlp bytes shall not fail");
-
-            let eth_tx_req: Eip1559TransactionRequest = (&eth_tx).into();
-            let typed_tx: TypedTransaction = eth_tx_req.into();
-            let rlp_unsigned = typed_tx.rlp().to_vec();
+            let eth_tx =
+                decode_signed(&raw_tx_rlp_bytes).expect("decode tx's rlp bytes shall not fail");
+            let rlp_unsigned = get_rlp_unsigned(&eth_tx);
 
             Transaction::new_from_rlp_bytes(
                 idx + 1,
@@ -192,3 +193,95 @@ fn test_eip2930_tx() {
 
     mock_prover.assert_satisfied_par();
 }
+
+#[test]
+fn test_eip2930_tx_with_access_list() {
+    let rng = &mut OsRng;
+    let from = Wallet::new(rng);
+    let tx = TransactionRequest::new()
+        .to(Address::random())
+        .value(eth(1))
+        .data(vec![1, 2, 3])
+        .gas_price(word!("0x4321"))
+        .gas(word!("0x77320"))
+        .nonce(word!("0x7f"))
+        .chain_id(MOCK_CHAIN_ID);
+    // nested lists of an address and its storage keys, including an empty one
+    let access_list = AccessList(vec![
+        AccessListItem {
+            address: Address::random(),
+            storage_keys: vec![H256::zero(), H256::random()],
+        },
+        AccessListItem {
+            address: Address::random(),
+            storage_keys: vec![],
+        },
+    ]);
+    let typed_tx: TypedTransaction = Eip2930TransactionRequest::new(tx, access_list).into();
+    let sig = from.sign_transaction_sync(&typed_tx).unwrap();
+    let tx = Transaction::new_from_rlp_bytes(
+        1,
+        TxType::Eip2930,
+        typed_tx.rlp_signed(&sig).to_vec(),
+        typed_tx.rlp().to_vec(),
+    );
+    let rlp_circuit = RlpCircuit::<Fr, Transaction> {
+        txs: vec![tx],
+        max_txs: 10,
+        size: 1000,
+        _marker: Default::default(),
+    };
+
+    let mock_prover = MockProver::run(14, &rlp_circuit, vec![]).unwrap();
+    mock_prover.assert_satisfied_par();
+}
+
+#[test]
+fn test_eip4844_tx() {
+    let eth_tx = EthTransaction {
+        transaction_type: Some(3.into()),
+        chain_id: Some(MOCK_CHAIN_ID.into()),
+        nonce: word!("0x7f"),
+        max_priority_fee_per_gas: Some(word!("0x1234")),
+        max_fee_per_gas: Some(word!("0x4321")),
+        gas: word!("0x77320"),
+        to: Some(Address::random()),
+        value: eth(1),
+        input: vec![0xca, 0xfe].into(),
+        access_list: Some(Default::default()),
+        v: 1.into(),
+        r: word!("0x563304e8f2306c3fafed471bee76db83690ec113965c6775a8a94625dcb03774"),
+        s: word!("0x5bcc59f5737520f7d0dc8b4f967635473e0a58526ce9ddd69c4a2454c9955f12"),
+        other: serde_json::from_value(serde_json::json!({
+            "maxFeePerBlobGas": "0x3",
+            "blobVersionedHashes": [
+                "0x01a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8",
+                "0x0100000000000000000000000000000000000000000000000000000000000001",
+            ],
+        }))
+        .unwrap(),
+        ..Default::default()
+    };
+
+    let tx = Transaction::new_from_rlp_bytes(
+        1,
+        TxType::Eip4844,
+        get_rlp_signed(&eth_tx),
+        get_rlp_unsigned(&eth_tx),
+    );
+    let rlp_circuit = RlpCircuit::<Fr, Transaction> {
+        txs: vec![tx],
+        max_txs: 10,
+        size: 1000,
+        _marker: Default::default(),
+    };
+
+    let mock_prover = MockProver::run(14, &rlp_circuit, vec![]);
+    assert!(mock_prover.is_ok());
+    let mock_prover = mock_prover.unwrap();
+    if let Err(errors) = mock_prover.verify_par() {
+        log::debug!("errors.len() = {}", errors.len());
+    }
+
+    mock_prover.assert_satisfied_par();
+}
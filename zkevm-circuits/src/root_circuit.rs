@@ -1,144 +1,189 @@
 //! The Root circuit implementation.
 use halo2_proofs::{
     arithmetic::Field,
-    circuit::{Layouter, SimpleFloorPlanner, Value},
-    halo2curves::serde::SerdeObject,
-    plonk::{Circuit, ConstraintSystem, Error},
+    circuit::{Cell, Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::{Bn256, Fr},
+    plonk::{Circuit, ConstraintSystem, Error, Selector},
     poly::{commitment::ParamsProver, kzg::commitment::ParamsKZG},
 };
 use itertools::Itertools;
-use maingate::MainGateInstructions;
-use snark_verifier::{util::arithmetic::MultiMillerLoop, verifier::plonk::PlonkProtocol};
-use std::iter;
+use rand::Rng;
+use snark_verifier::{
+    loader::halo2::{
+        halo2_ecc::halo2_base::{self, Context, ContextParams},
+        Halo2Loader,
+    },
+    pcs::kzg::{Bdfg21, Kzg},
+};
+use snark_verifier_sdk::{aggregate, flatten_accumulator, types::Svk, CircuitExt, SnarkWitness};
 
 mod aggregation;
 
 #[cfg(any(feature = "test", test))]
 mod test;
-#[cfg(any(feature = "test", test, feature = "test-circuits"))]
-pub use self::RootCircuit as TestRootCircuit;
 
 pub use aggregation::{
-    aggregate, AggregationConfig, EccChip, Halo2Loader, KzgAs, KzgDk, KzgSvk,
-    PlonkSuccinctVerifier, PlonkVerifier, PoseidonTranscript, Snark, SnarkWitness, BITS, LIMBS,
+    accumulate, InstanceLink, RootCircuitConfig, RootCircuitParams, ACC_LEN, BITS, LIMBS,
 };
-pub use snark_verifier::system::halo2::{compile, Config};
-
-#[cfg(any(feature = "test", test))]
-pub use aggregation::TestAggregationCircuit;
-
-/// RootCircuit for aggregating SuperCircuit into a much smaller proof.
-#[derive(Clone)]
-pub struct RootCircuit<'a, M: MultiMillerLoop> {
-    svk: KzgSvk<M>,
-    snark: SnarkWitness<'a, M::G1Affine>,
-    instance: Vec<M::Scalar>,
+pub use snark_verifier_sdk::Snark;
+
+/// RootCircuit aggregating the proofs of the SuperCircuit, or of sub-circuits proven separately
+/// with their own `k`, into a much smaller proof. The values shared by the sub-circuits, e.g. the
+/// RLCs of their shared tables, are exposed as instances and constrained equal by
+/// [`InstanceLink`]s.
+///
+/// The instance is the accumulator, in its first [`ACC_LEN`] rows, followed by the instances of
+/// the snarks.
+#[derive(Clone, Debug)]
+pub struct RootCircuit {
+    svk: Svk,
+    snarks: Vec<SnarkWitness>,
+    links: Vec<InstanceLink>,
+    instance: Vec<Fr>,
+    as_proof: Value<Vec<u8>>,
 }
 
-impl<'a, M: MultiMillerLoop> RootCircuit<'a, M>
-where
-    M::G1Affine: SerdeObject,
-    M::G2Affine: SerdeObject,
-{
-    /// Create a `RootCircuit` with accumulator computed given a `SuperCircuit`
-    /// proof and its instance. Returns `None` if given proof is invalid.
+impl RootCircuit {
+    /// Create a `RootCircuit` aggregating `snarks`. Returns an error if a proof is invalid or the
+    /// instances of a link are not equal.
     pub fn new(
-        params: &ParamsKZG<M>,
-        super_circuit_protocol: &'a PlonkProtocol<M::G1Affine>,
-        super_circuit_instances: Value<&'a Vec<Vec<M::Scalar>>>,
-        super_circuit_proof: Value<&'a [u8]>,
+        params: &ParamsKZG<Bn256>,
+        snarks: Vec<Snark>,
+        links: Vec<InstanceLink>,
+        rng: impl Rng + Send,
     ) -> Result<Self, snark_verifier::Error> {
-        let num_instances = super_circuit_protocol.num_instance.iter().sum::<usize>() + 4 * LIMBS;
-        let instance = {
-            let mut instance = Ok(vec![M::Scalar::zero(); num_instances]);
-            super_circuit_instances
-                .as_ref()
-                .zip(super_circuit_proof.as_ref())
-                .map(|(super_circuit_instances, super_circuit_proof)| {
-                    let snark = Snark::new(
-                        super_circuit_protocol,
-                        super_circuit_instances,
-                        super_circuit_proof,
-                    );
-                    instance = aggregate::<M>(params, [snark]).map(|accumulator_limbs| {
-                        iter::empty()
-                            // Propagate `SuperCircuit`'s instance
-                            .chain(super_circuit_instances.iter().flatten().cloned())
-                            // Output aggregated accumulator limbs
-                            .chain(accumulator_limbs)
-                            .collect_vec()
-                    });
-                });
-            instance?
-        };
-        debug_assert_eq!(instance.len(), num_instances);
+        for link in &links {
+            link.check(&snarks)?;
+        }
+        let (accumulator_limbs, as_proof) = accumulate(params, &snarks, rng)?;
+        let instance = accumulator_limbs
+            .into_iter()
+            .chain(
+                snarks
+                    .iter()
+                    .flat_map(|snark| snark.instances.iter().flatten().copied()),
+            )
+            .collect_vec();
 
         Ok(Self {
-            svk: KzgSvk::<M>::new(params.get_g()[0]),
-            snark: SnarkWitness::new(
-                super_circuit_protocol,
-                super_circuit_instances,
-                super_circuit_proof,
-            ),
+            svk: params.get_g()[0].into(),
+            snarks: snarks.into_iter().map_into().collect(),
+            links,
             instance,
+            as_proof: Value::known(as_proof),
         })
     }
 
-    /// Returns accumulator indices in instance columns, which will be in
-    /// the last `4 * LIMBS` rows of instance column in `MainGate`.
-    pub fn accumulator_indices(&self) -> Vec<(usize, usize)> {
-        let offset = self.snark.protocol().num_instance.iter().sum::<usize>();
-        (offset..).map(|idx| (0, idx)).take(4 * LIMBS).collect()
-    }
-
-    /// Returns number of instance
-    pub fn num_instance(&self) -> Vec<usize> {
-        vec![self.snark.protocol().num_instance.iter().sum::<usize>() + 4 * LIMBS]
-    }
-
     /// Returns instance
-    pub fn instance(&self) -> Vec<Vec<M::Scalar>> {
+    pub fn instance(&self) -> Vec<Vec<Fr>> {
         vec![self.instance.clone()]
     }
 }
 
-impl<'a, M: MultiMillerLoop> Circuit<M::Scalar> for RootCircuit<'a, M> {
-    type Config = AggregationConfig;
+impl Circuit<Fr> for RootCircuit {
+    type Config = RootCircuitConfig;
     type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
 
     fn without_witnesses(&self) -> Self {
         Self {
             svk: self.svk,
-            snark: self.snark.without_witnesses(),
-            instance: vec![M::Scalar::zero(); self.instance.len()],
+            snarks: self
+                .snarks
+                .iter()
+                .map(SnarkWitness::without_witnesses)
+                .collect(),
+            links: self.links.clone(),
+            instance: vec![Fr::zero(); self.instance.len()],
+            as_proof: Value::unknown(),
         }
     }
 
-    fn configure(meta: &mut ConstraintSystem<M::Scalar>) -> Self::Config {
-        AggregationConfig::configure::<M::G1Affine>(meta)
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        RootCircuitConfig::configure(meta, RootCircuitParams::default())
     }
 
     fn synthesize(
         &self,
         config: Self::Config,
-        mut layouter: impl Layouter<M::Scalar>,
+        mut layouter: impl Layouter<Fr>,
     ) -> Result<(), Error> {
-        config.load_table(&mut layouter)?;
-        let (instance, accumulator_limbs) =
-            config.aggregate::<M>(&mut layouter, &self.svk, [self.snark])?;
+        config
+            .range()
+            .load_lookup_table(&mut layouter)
+            .expect("load range lookup table");
+
+        let mut first_pass = halo2_base::SKIP_FIRST_PASS;
+        let instance = layouter.assign_region(
+            || "root circuit",
+            |region| -> Result<Vec<Cell>, Error> {
+                if first_pass {
+                    first_pass = false;
+                    return Ok(vec![]);
+                }
+                let ctx = Context::new(
+                    region,
+                    ContextParams {
+                        max_rows: config.gate().max_rows,
+                        num_context_ids: 1,
+                        fixed_columns: config.gate().constants.clone(),
+                    },
+                );
+
+                let loader = Halo2Loader::new(config.ecc_chip(), ctx);
+                let (instances, accumulator) = aggregate::<Kzg<Bn256, Bdfg21>>(
+                    &self.svk,
+                    &loader,
+                    &self.snarks,
+                    self.as_proof.as_ref().map(Vec::as_slice),
+                );
+
+                let instance: Vec<Cell> = flatten_accumulator(accumulator)
+                    .iter()
+                    .chain(instances.iter().flatten())
+                    .map(|assigned| assigned.cell())
+                    .collect();
+
+                let mut ctx = loader.ctx_mut();
+                // Constrain the linked instances to be equal.
+                for link in &self.links {
+                    let [lhs, rhs] =
+                        [link.lhs, link.rhs].map(|(snark, index)| &instances[snark][index]);
+                    ctx.region.constrain_equal(lhs.cell(), rhs.cell())?;
+                }
+                config.range().finalize(&mut ctx);
+
+                Ok(instance)
+            },
+        )?;
 
         // Constrain equality to instance values
-        let main_gate = config.main_gate();
-        for (row, limb) in instance
-            .into_iter()
-            .flatten()
-            .flatten()
-            .chain(accumulator_limbs)
-            .enumerate()
-        {
-            main_gate.expose_public(layouter.namespace(|| ""), limb, row)?;
+        for (row, cell) in instance.into_iter().enumerate() {
+            layouter.constrain_instance(cell, config.instance, row)?;
         }
 
         Ok(())
     }
 }
+
+impl CircuitExt<Fr> for RootCircuit {
+    fn num_instance(&self) -> Vec<usize> {
+        vec![self.instance.len()]
+    }
+
+    fn instances(&self) -> Vec<Vec<Fr>> {
+        self.instance()
+    }
+
+    fn accumulator_indices() -> Option<Vec<(usize, usize)>> {
+        Some((0..ACC_LEN).map(|idx| (0, idx)).collect())
+    }
+
+    fn selectors(config: &Self::Config) -> Vec<Selector> {
+        config.gate().basic_gates[0]
+            .iter()
+            .map(|gate| gate.q_enable)
+            .collect()
+    }
+}
@@ -3,80 +3,153 @@ pub use super::*;
 use crate::super_circuit::{test::block_1tx, SuperCircuit};
 use bus_mapping::circuit_input_builder::CircuitsParams;
 use halo2_proofs::{
-    circuit::Value,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
     dev::MockProver,
-    halo2curves::bn256::Bn256,
-    plonk::{create_proof, keygen_pk, keygen_vk},
-    poly::kzg::{
-        commitment::{KZGCommitmentScheme, ParamsKZG},
-        multiopen::ProverGWC,
-    },
+    halo2curves::bn256::{Bn256, Fr},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
 };
-use itertools::Itertools;
-use rand::rngs::OsRng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use snark_verifier_sdk::{gen_pk, gen_snark_shplonk, CircuitExt};
+
+/// Circuit exposing the RLC of a table as its only instance, as the sub-circuits sharing the
+/// table do.
+#[derive(Clone, Default)]
+struct TableRlcCircuit(Fr);
+
+impl Circuit<Fr> for TableRlcCircuit {
+    type Config = (Column<Advice>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        (advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        (advice, instance): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let rlc = layouter.assign_region(
+            || "table rlc",
+            |mut region| region.assign_advice(|| "rlc", advice, 0, || Value::known(self.0)),
+        )?;
+        layouter.constrain_instance(rlc.cell(), instance, 0)
+    }
+}
+
+impl CircuitExt<Fr> for TableRlcCircuit {
+    fn num_instance(&self) -> Vec<usize> {
+        vec![1]
+    }
+
+    fn instances(&self) -> Vec<Vec<Fr>> {
+        vec![vec![self.0]]
+    }
+}
+
+/// Prove each circuit with its own `k`, from the same setup.
+fn table_rlc_snarks(params: &ParamsKZG<Bn256>, circuits: &[(u32, TableRlcCircuit)]) -> Vec<Snark> {
+    let mut rng = ChaCha20Rng::seed_from_u64(2);
+    circuits
+        .iter()
+        .map(|(k, circuit)| {
+            let mut params = params.clone();
+            params.downsize(*k);
+            let pk = gen_pk(&params, circuit, None);
+            gen_snark_shplonk(&params, &pk, circuit.clone(), &mut rng, None::<String>).unwrap()
+        })
+        .collect()
+}
 
 #[ignore = "Due to high memory requirement"]
 #[test]
-fn test_root_circuit() {
-    let (params, protocol, proof, instance) = {
-        // Preprocess
-        const MAX_TXS: usize = 1;
-        const MAX_CALLDATA: usize = 32;
-        const TEST_MOCK_RANDOMNESS: u64 = 0x100;
-        let circuits_params = CircuitsParams {
-            max_txs: MAX_TXS,
-            max_calldata: MAX_CALLDATA,
-            max_rws: 256,
-            max_copy_rows: 256,
-            max_exp_steps: 256,
-            max_bytecode: 512,
-            max_evm_rows: 0,
-            max_keccak_rows: 0,
-        };
-        let (k, circuit, instance, _) =
-            SuperCircuit::<_, MAX_TXS, MAX_CALLDATA, TEST_MOCK_RANDOMNESS>::build(
-                block_1tx(),
-                circuits_params,
-            )
-            .unwrap();
-        let params = ParamsKZG::<Bn256>::setup(k, OsRng);
-        let pk = keygen_pk(&params, keygen_vk(&params, &circuit).unwrap(), &circuit).unwrap();
-        let protocol = compile(
-            &params,
-            pk.get_vk(),
-            Config::kzg()
-                .with_num_instance(instance.iter().map(|instance| instance.len()).collect()),
-        );
-
-        // Create proof
-        let proof = {
-            let mut transcript = PoseidonTranscript::new(Vec::new());
-            create_proof::<KZGCommitmentScheme<_>, ProverGWC<_>, _, _, _, _>(
-                &params,
-                &pk,
-                &[circuit],
-                &[&instance.iter().map(Vec::as_slice).collect_vec()],
-                OsRng,
-                &mut transcript,
-            )
-            .unwrap();
-            transcript.finalize()
-        };
+fn test_root_circuit_linked_sub_circuits() {
+    let degree = RootCircuitParams::default().degree;
+    let params = ParamsKZG::<Bn256>::setup(degree, ChaCha20Rng::seed_from_u64(2));
+    let rlc = Fr::from(0xcafe);
+    let snarks = table_rlc_snarks(
+        &params,
+        &[(8, TableRlcCircuit(rlc)), (9, TableRlcCircuit(rlc))],
+    );
+    let links = vec![InstanceLink {
+        lhs: (0, 0),
+        rhs: (1, 0),
+    }];
 
-        (params, protocol, proof, instance)
-    };
+    let root_circuit =
+        RootCircuit::new(&params, snarks, links, ChaCha20Rng::seed_from_u64(2)).unwrap();
+    let instance = root_circuit.instance();
+    assert_eq!(instance[0][ACC_LEN..], [rlc, rlc]);
+    MockProver::run(degree, &root_circuit, instance)
+        .unwrap()
+        .assert_satisfied_par();
+}
 
-    let root_circuit = RootCircuit::new(
+#[test]
+fn test_root_circuit_unequal_link() {
+    let params = ParamsKZG::<Bn256>::setup(9, ChaCha20Rng::seed_from_u64(2));
+    let snarks = table_rlc_snarks(
         &params,
-        &protocol,
-        Value::known(&instance),
-        Value::known(&proof),
-    )
-    .unwrap();
-    assert_eq!(
-        MockProver::run(26, &root_circuit, root_circuit.instance())
-            .unwrap()
-            .verify_par(),
-        Ok(())
+        &[
+            (8, TableRlcCircuit(Fr::from(1))),
+            (9, TableRlcCircuit(Fr::from(2))),
+        ],
     );
+    let links = vec![InstanceLink {
+        lhs: (0, 0),
+        rhs: (1, 0),
+    }];
+
+    assert!(RootCircuit::new(&params, snarks, links, ChaCha20Rng::seed_from_u64(2)).is_err());
+}
+
+#[ignore = "Due to high memory requirement"]
+#[test]
+fn test_root_circuit() {
+    let circuits_params = CircuitsParams {
+        max_txs: 1,
+        max_calldata: 32,
+        max_rws: 256,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_evm_rows: 0,
+        max_keccak_rows: 0,
+        ..Default::default()
+    };
+    let (k, circuit, _) = SuperCircuit::<Fr>::build(block_1tx(), circuits_params).unwrap();
+
+    let degree = RootCircuitParams::default().degree;
+    let params = ParamsKZG::<Bn256>::setup(degree, ChaCha20Rng::seed_from_u64(2));
+    let snark = {
+        let mut params = params.clone();
+        params.downsize(k);
+        let pk = gen_pk(&params, &circuit, None);
+        gen_snark_shplonk(
+            &params,
+            &pk,
+            circuit,
+            &mut ChaCha20Rng::seed_from_u64(2),
+            None::<String>,
+        )
+        .unwrap()
+    };
+
+    let root_circuit =
+        RootCircuit::new(&params, vec![snark], vec![], ChaCha20Rng::seed_from_u64(2)).unwrap();
+    MockProver::run(degree, &root_circuit, root_circuit.instance())
+        .unwrap()
+        .assert_satisfied_par();
 }
@@ -1,5 +1,5 @@
-//! The SHA256 circuit is a wrapper for the circuit in sha256 crate and serve for precompile SHA-256
-//! calls
+//! The SHA256 circuit is a wrapper for the circuit in sha256 crate. It serves the precompile
+//! SHA-256 calls, and any other hashes looked up in the [`SHA256Table`].
 use halo2_proofs::{
     circuit::{Layouter, Value},
     halo2curves::bn256::Fr,
@@ -58,7 +58,7 @@ impl SubCircuitConfig<Fr> for CircuitConfig {
     }
 }
 
-/// SHA-256 circuit for precompile modexp
+/// SHA-256 circuit, proving the digests of the [`SHA256Table`]
 #[derive(Clone, Debug, Default)]
 pub struct SHA256Circuit<F: Field>(Vec<SHA256>, usize, std::marker::PhantomData<F>);
 
@@ -68,7 +68,15 @@ const MIN_PADDING_BYTES: usize = 9; // the additional bytes (a 0x80 byte)
                                     // and 8-byte length
 
 impl<F: Field> SHA256Circuit<F> {
-    fn expected_rows(&self) -> usize {
+    /// Creates a new circuit hashing the inputs of `events`, whose digests are
+    /// checked against the ones computed by the circuit. `row_limit` is the
+    /// maximum number of rows, 0 to size the circuit dynamically.
+    pub fn new(events: Vec<SHA256>, row_limit: usize) -> Self {
+        Self(events, 0, Default::default()).with_row_limit(row_limit)
+    }
+
+    /// The number of rows needed to hash the inputs of this circuit.
+    pub fn expected_rows(&self) -> usize {
         self.0
             .iter()
             .map(|evnt| {
@@ -96,8 +104,13 @@ impl<F: Field> SHA256Circuit<F> {
                 "no enough rows for sha256 circuit, expected {expected_rows}, limit {row_limit}",
             );
         }
+        let block_limit = if row_limit == 0 {
+            // dynamic
+            self.expected_rows() / TABLE16_BLOCK_ROWS
+        } else {
+            row_limit / TABLE16_BLOCK_ROWS
+        };
         let inp = self.0;
-        let block_limit = row_limit / TABLE16_BLOCK_ROWS;
 
         Self(inp, block_limit, Default::default())
     }
@@ -111,8 +124,7 @@ impl SubCircuit<Fr> for SHA256Circuit<Fr> {
     }
 
     fn new_from_block(block: &witness::Block) -> Self {
-        Self(block.get_sha256(), 0, Default::default())
-            .with_row_limit(block.circuits_params.max_keccak_rows)
+        Self::new(block.get_sha256(), block.circuits_params.max_keccak_rows)
     }
 
     fn min_num_rows_block(block: &witness::Block) -> (usize, usize) {
@@ -20,7 +20,10 @@ use crate::{
         EvmCircuit,
     },
     keccak_circuit::KeccakCircuit,
-    sig_circuit::{ecdsa::ecdsa_verify_no_pubkey_check, utils::*},
+    sig_circuit::{
+        ecdsa::{ecdsa_verify_no_pubkey_check, EcdsaConstants},
+        utils::*,
+    },
     table::{KeccakTable, SigTable},
     util::{Challenges, Expr, Field, SubCircuit, SubCircuitConfig},
 };
@@ -34,7 +37,7 @@ use halo2_base::{
     AssignedValue, Context, QuantumCell, SKIP_FIRST_PASS,
 };
 use halo2_ecc::{
-    bigint::CRTInteger,
+    bigint::{big_less_than, CRTInteger},
     ecc::EccChip,
     fields::{
         fp::{FpConfig, FpStrategy},
@@ -57,7 +60,11 @@ use halo2_proofs::{
 use ethers_core::utils::keccak256;
 use itertools::Itertools;
 use log::error;
-use std::{iter, marker::PhantomData};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    iter,
+    marker::PhantomData,
+};
 
 /// Circuit configuration arguments
 pub struct SigCircuitConfigArgs<F: Field> {
@@ -150,6 +157,7 @@ impl<F: Field> SubCircuitConfig<F> for SigCircuitConfig<F> {
         meta.enable_equality(sig_table.sig_s_rlc);
         meta.enable_equality(sig_table.sig_v);
         meta.enable_equality(sig_table.is_valid);
+        meta.enable_equality(sig_table.is_low_s);
         meta.enable_equality(sig_table.msg_hash_rlc);
 
         // Ref. spec SignVerifyChip 1. Verify that keccak(pub_key_bytes) = pub_key_hash
@@ -269,16 +277,11 @@ impl<F: Field> SubCircuit<F> for SigCircuit<F> {
             .filter(|tx| !tx.tx_type.is_l1_msg())
             .count()
             + block.precompile_events.get_ecrecover_events().len();
-        // Reserve one ecdsa verification for padding tx such that the bad case in which some tx
-        // calls MAX_NUM_SIG - 1 ecrecover precompile won't happen. If that case happens, the sig
-        // circuit won't have more space for the padding tx's ECDSA verification. Then the
-        // prover won't be able to produce any valid proof.
-        let max_num_verif = MAX_NUM_SIG - 1;
 
         // Instead of showing actual minimum row usage,
         // halo2-lib based circuits use min_row_num to represent a percentage of total-used capacity
         // This functionality allows l2geth to decide if additional ops can be added.
-        let min_row_num = (row_num / max_num_verif) * ecdsa_verif_count;
+        let min_row_num = Self::rows_per_sig(row_num) * ecdsa_verif_count;
 
         (min_row_num, row_num)
     }
@@ -294,6 +297,38 @@ impl<F: Field> SigCircuit<F> {
         }
     }
 
+    /// Max number of ECDSA verifications of a block, i.e. of its non L1 msg txs and ecrecover
+    /// calls together. Bound `CircuitsParams::max_txs` by it.
+    pub fn max_num_verif() -> usize {
+        // Reserve one ecdsa verification for padding tx such that the bad case in which some tx
+        // calls MAX_NUM_SIG - 1 ecrecover precompile won't happen. If that case happens, the sig
+        // circuit won't have more space for the padding tx's ECDSA verification. Then the
+        // prover won't be able to produce any valid proof.
+        MAX_NUM_SIG - 1
+    }
+
+    /// Rows that each ECDSA verification takes out of the `row_num` rows of the sig circuit,
+    /// which are `CircuitsParams::max_vertical_circuit_rows` or [`Self::min_num_rows`]. This is
+    /// the share of the advice and lookup advice cells of the circuit that the cells measured per
+    /// signature take, whichever is larger, scaled to `row_num` rows.
+    pub fn rows_per_sig(row_num: usize) -> usize {
+        let total_rows = 1 << LOG_TOTAL_NUM_ROWS;
+        [
+            (CELLS_PER_SIG, calc_required_advices(MAX_NUM_SIG)),
+            (
+                LOOKUP_CELLS_PER_SIG,
+                calc_required_lookup_advices(MAX_NUM_SIG),
+            ),
+        ]
+        .into_iter()
+        .map(|(cells, num_columns)| {
+            let capacity = num_columns * total_rows;
+            (cells * row_num + capacity - 1) / capacity
+        })
+        .max()
+        .unwrap()
+    }
+
     /// Return the minimum number of rows required to prove an input of a
     /// particular size.
     pub fn min_num_rows() -> usize {
@@ -329,6 +364,8 @@ impl<F: Field> SigCircuit<F> {
         &self,
         ctx: &mut Context<F>,
         ecdsa_chip: &FpChip<F>,
+        constants: &EcdsaConstants<F>,
+        assigned_pk: &AssignedPubkey<F>,
         sign_data: &SignData,
     ) -> Result<AssignedECDSA<F, FpChip<F>>, Error> {
         let gate = ecdsa_chip.gate();
@@ -336,7 +373,7 @@ impl<F: Field> SigCircuit<F> {
 
         let SignData {
             signature,
-            pk,
+            pk: _,
             msg: _,
             msg_hash,
         } = sign_data;
@@ -344,9 +381,7 @@ impl<F: Field> SigCircuit<F> {
 
         // build ecc chip from Fp chip
         let ecc_chip = EccChip::<F, FpChip<F>>::construct(ecdsa_chip.clone());
-        let pk_assigned = ecc_chip.load_private(ctx, (Value::known(pk.x), Value::known(pk.y)));
-        let pk_is_valid = ecc_chip.is_on_curve_or_infinity::<Secp256k1Affine>(ctx, &pk_assigned);
-        gate.assert_is_const(ctx, &pk_is_valid, F::one());
+        let pk_assigned = assigned_pk.pk.clone();
 
         // build Fq chip from Fp chip
         let fq_chip = FqChip::construct(ecdsa_chip.range.clone(), 88, 3, modulus::<Fq>());
@@ -365,14 +400,27 @@ impl<F: Field> SigCircuit<F> {
             ecdsa_verify_no_pubkey_check::<F, Fp, Fq, Secp256k1Affine>(
                 &ecc_chip.field_chip,
                 ctx,
+                constants,
                 &pk_assigned,
                 &integer_r,
                 &integer_s,
                 &msg_hash,
-                4,
-                4,
+                VAR_WINDOW_BITS,
             );
 
+        // =======================================
+        // s_is_low = s < n/2 + 1 (EIP-2)
+        // =======================================
+        // only the tx circuit requires a low s, ecrecover accepts either
+        let s_is_low = big_less_than::assign::<F>(
+            fq_chip.range(),
+            ctx,
+            &integer_s.truncation,
+            &constants.half_n_plus_one.truncation,
+            fq_chip.limb_bits,
+            fq_chip.limb_bases[1],
+        );
+
         // =======================================
         // constrains v == y.is_oddness()
         // =======================================
@@ -443,6 +491,7 @@ impl<F: Field> SigCircuit<F> {
             integer_s,
             v: assigned_y_is_odd,
             sig_is_valid,
+            s_is_low,
         })
     }
 
@@ -498,49 +547,105 @@ impl<F: Field> SigCircuit<F> {
         Ok(())
     }
 
-    /// Input the signature data,
-    /// Output the cells for byte decomposition of the keys and messages
-    fn sign_data_decomposition(
+    /// Constant cells of `[1, 2^8, 2^16, ...]`, to compose integers of up to 32
+    /// bytes.
+    fn powers_of_256_cells() -> Vec<QuantumCell<F>> {
+        iter::successors(Some(F::one()), |coeff| Some(F::from(256) * coeff))
+            .take(32)
+            .map(QuantumCell::Constant)
+            .collect_vec()
+    }
+
+    /// Input a public key,
+    /// Output the public key checked to be on the curve or at infinity, with the
+    /// cells of its bytes and of the bytes of its hash. It is assigned once and
+    /// shared by all the signatures under it, which are then verified against the
+    /// same point and hashed by a single keccak lookup.
+    fn assign_pk(
         &self,
         ctx: &mut Context<F>,
         ecdsa_chip: &FpChip<F>,
-        sign_data: &SignData,
-        assigned_data: &AssignedECDSA<F, FpChip<F>>,
-    ) -> Result<SignDataDecomposed<F>, Error> {
+        pk: &Secp256k1Affine,
+    ) -> Result<AssignedPubkey<F>, Error> {
+        let gate = ecdsa_chip.gate();
+        let powers_of_256_cells = Self::powers_of_256_cells();
+
         // build ecc chip from Fp chip
         let ecc_chip = EccChip::<F, FpChip<F>>::construct(ecdsa_chip.clone());
-
-        let zero = ecdsa_chip.range.gate.load_zero(ctx);
+        let pk_assigned = ecc_chip.load_private(ctx, (Value::known(pk.x), Value::known(pk.y)));
+        let pk_is_valid = ecc_chip.is_on_curve_or_infinity::<Secp256k1Affine>(ctx, &pk_assigned);
+        gate.assert_is_const(ctx, &pk_is_valid, F::one());
 
         // ================================================
-        // step 0. powers of aux parameters
+        // pk cells
         // ================================================
-        let powers_of_256 =
-            iter::successors(Some(F::one()), |coeff| Some(F::from(256) * coeff)).take(32);
-        let powers_of_256_cells = powers_of_256
-            .map(|x| QuantumCell::Constant(x))
-            .collect_vec();
+        let load_bytes = |ctx: &mut Context<F>, bytes: &[u8]| {
+            bytes
+                .iter()
+                .map(|&byte| {
+                    QuantumCell::Existing(
+                        gate.load_witness(ctx, Value::known(F::from(byte as u64))),
+                    )
+                })
+                .collect_vec()
+        };
+        let pk_x_le = load_bytes(ctx, &pk.x.to_bytes());
+        let pk_y_le = load_bytes(ctx, &pk.y.to_bytes());
+        self.assert_crt_int_byte_repr(
+            ctx,
+            &ecdsa_chip.range,
+            &pk_assigned.x,
+            &pk_x_le,
+            &powers_of_256_cells,
+        )?;
+        self.assert_crt_int_byte_repr(
+            ctx,
+            &ecdsa_chip.range,
+            &pk_assigned.y,
+            &pk_y_le,
+            &powers_of_256_cells,
+        )?;
 
         // ================================================
         // pk hash cells
         // ================================================
-        let pk_le = pk_bytes_le(&sign_data.pk);
+        let pk_le = pk_bytes_le(pk);
         let pk_be = pk_bytes_swap_endianness(&pk_le);
-        let pk_hash = keccak256(pk_be).map(|byte| Value::known(F::from(byte as u64)));
-
+        let mut pk_hash = keccak256(pk_be);
         log::trace!("pk hash {:0x?}", pk_hash);
-        let pk_hash_cells = pk_hash
-            .iter()
-            .map(|&x| QuantumCell::Witness(x))
-            .rev()
-            .collect_vec();
+        pk_hash.reverse();
+        let pk_hash_cells = load_bytes(ctx, &pk_hash);
+
+        Ok(AssignedPubkey {
+            pk: pk_assigned,
+            pk_cells: [pk_y_le, pk_x_le].concat(),
+            pk_hash_cells,
+        })
+    }
+
+    /// Input the signature data,
+    /// Output the cells for byte decomposition of the messages and signatures
+    fn sign_data_decomposition(
+        &self,
+        ctx: &mut Context<F>,
+        ecdsa_chip: &FpChip<F>,
+        sign_data: &SignData,
+        assigned_pk: &AssignedPubkey<F>,
+        assigned_data: &AssignedECDSA<F, FpChip<F>>,
+    ) -> Result<SignDataDecomposed<F>, Error> {
+        let zero = ecdsa_chip.range.gate.load_zero(ctx);
+
+        // ================================================
+        // step 0. powers of aux parameters
+        // ================================================
+        let powers_of_256_cells = Self::powers_of_256_cells();
 
         // address is the random linear combination of the public key
         // it is fine to use a phase 1 gate here
         let address = ecdsa_chip.range.gate.inner_product(
             ctx,
             powers_of_256_cells[..20].to_vec(),
-            pk_hash_cells[..20].to_vec(),
+            assigned_pk.pk_hash_cells[..20].to_vec(),
         );
         let address = ecdsa_chip.range.gate.select(
             ctx,
@@ -581,45 +686,6 @@ impl<F: Field> SigCircuit<F> {
         // msg_hash is an overflowing integer with 3 limbs, of sizes 88, 88, and 80
         let assigned_msg_hash_le =
             assert_crt(ctx, sign_data.msg_hash.to_bytes(), &assigned_data.msg_hash)?;
-
-        // ================================================
-        // pk cells
-        // ================================================
-        let pk_x_le = sign_data
-            .pk
-            .x
-            .to_bytes()
-            .iter()
-            .map(|&x| QuantumCell::Witness(Value::known(F::from_u128(x as u128))))
-            .collect_vec();
-        let pk_y_le = sign_data
-            .pk
-            .y
-            .to_bytes()
-            .iter()
-            .map(|&y| QuantumCell::Witness(Value::known(F::from_u128(y as u128))))
-            .collect_vec();
-        let pk_assigned = ecc_chip.load_private(
-            ctx,
-            (Value::known(sign_data.pk.x), Value::known(sign_data.pk.y)),
-        );
-
-        self.assert_crt_int_byte_repr(
-            ctx,
-            &ecdsa_chip.range,
-            &pk_assigned.x,
-            &pk_x_le,
-            &powers_of_256_cells,
-        )?;
-        self.assert_crt_int_byte_repr(
-            ctx,
-            &ecdsa_chip.range,
-            &pk_assigned.y,
-            &pk_y_le,
-            &powers_of_256_cells,
-        )?;
-
-        let assigned_pk_le_selected = [pk_y_le, pk_x_le].concat();
         log::trace!("finished data decomposition");
 
         let r_cells = assert_crt(
@@ -634,9 +700,7 @@ impl<F: Field> SigCircuit<F> {
         )?;
 
         Ok(SignDataDecomposed {
-            pk_hash_cells,
             msg_hash_cells: assigned_msg_hash_le,
-            pk_cells: assigned_pk_le_selected,
             address,
             is_address_zero,
             r_cells,
@@ -644,6 +708,53 @@ impl<F: Field> SigCircuit<F> {
         })
     }
 
+    /// Powers of the evm word and keccak input challenges, shared by the RLCs of
+    /// all the public keys and signatures.
+    fn challenge_powers(challenges: &Challenges<Value<F>>) -> [Vec<QuantumCell<F>>; 2] {
+        log::trace!("evm challenge: {:?} ", challenges.evm_word());
+
+        [(challenges.evm_word(), 32), (challenges.keccak_input(), 64)].map(|(challenge, n)| {
+            iter::successors(Some(Value::known(F::one())), |coeff| {
+                Some(challenge * coeff)
+            })
+            .take(n)
+            .map(QuantumCell::Witness)
+            .collect_vec()
+        })
+    }
+
+    /// Random linear combinations of a public key and of its hash, to be checked
+    /// by a keccak lookup.
+    fn assign_pk_rlcs(
+        &self,
+        ctx: &mut Context<F>,
+        rlc_chip: &RangeConfig<F>,
+        assigned_pk: &AssignedPubkey<F>,
+        [evm_challenge_powers, keccak_challenge_powers]: &[Vec<QuantumCell<F>>; 2],
+    ) -> [AssignedValue<F>; 2] {
+        // ================================================
+        // random linear combination of pk
+        // ================================================
+        let pk_rlc = rlc_chip.gate.inner_product(
+            ctx,
+            assigned_pk.pk_cells.clone(),
+            keccak_challenge_powers.clone(),
+        );
+        log::trace!("pk rlc: {:?}", pk_rlc.value());
+
+        // ================================================
+        // random linear combination of pk_hash
+        // ================================================
+        let pk_hash_rlc = rlc_chip.gate.inner_product(
+            ctx,
+            assigned_pk.pk_hash_cells.clone(),
+            evm_challenge_powers.clone(),
+        );
+        log::trace!("pk hash rlc halo2ecc: {:?}", pk_hash_rlc.value());
+
+        [pk_rlc, pk_hash_rlc]
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn assign_sig_verify(
         &self,
@@ -652,26 +763,9 @@ impl<F: Field> SigCircuit<F> {
         sign_data: &SignData,
         sign_data_decomposed: &SignDataDecomposed<F>,
         challenges: &Challenges<Value<F>>,
+        [evm_challenge_powers, _]: &[Vec<QuantumCell<F>>; 2],
         assigned_ecdsa: &AssignedECDSA<F, FpChip<F>>,
-    ) -> Result<([AssignedValue<F>; 3], AssignedSignatureVerify<F>), Error> {
-        // ================================================
-        // step 0. powers of aux parameters
-        // ================================================
-        let evm_challenge_powers = iter::successors(Some(Value::known(F::one())), |coeff| {
-            Some(challenges.evm_word() * coeff)
-        })
-        .take(32)
-        .map(|x| QuantumCell::Witness(x))
-        .collect_vec();
-
-        log::trace!("evm challenge: {:?} ", challenges.evm_word());
-
-        let keccak_challenge_powers = iter::successors(Some(Value::known(F::one())), |coeff| {
-            Some(challenges.keccak_input() * coeff)
-        })
-        .take(64)
-        .map(|x| QuantumCell::Witness(x))
-        .collect_vec();
+    ) -> Result<AssignedSignatureVerify<F>, Error> {
         // ================================================
         // step 1 random linear combination of message hash
         // ================================================
@@ -690,26 +784,7 @@ impl<F: Field> SigCircuit<F> {
 
         log::trace!("assigned msg hash rlc: {:?}", msg_hash_rlc.value());
 
-        // ================================================
-        // step 2 random linear combination of pk
-        // ================================================
-        let pk_rlc = rlc_chip.gate.inner_product(
-            ctx,
-            sign_data_decomposed.pk_cells.clone(),
-            keccak_challenge_powers,
-        );
-        log::trace!("pk rlc: {:?}", pk_rlc.value());
-
-        // ================================================
-        // step 3 random linear combination of pk_hash
-        // ================================================
-        let pk_hash_rlc = rlc_chip.gate.inner_product(
-            ctx,
-            sign_data_decomposed.pk_hash_cells.clone(),
-            evm_challenge_powers.clone(),
-        );
-
-        // step 4: r,s rlc
+        // step 2: r,s rlc
         let r_rlc = rlc_chip.gate.inner_product(
             ctx,
             sign_data_decomposed.r_cells.clone(),
@@ -718,13 +793,11 @@ impl<F: Field> SigCircuit<F> {
         let s_rlc = rlc_chip.gate.inner_product(
             ctx,
             sign_data_decomposed.s_cells.clone(),
-            evm_challenge_powers,
+            evm_challenge_powers.clone(),
         );
 
-        log::trace!("pk hash rlc halo2ecc: {:?}", pk_hash_rlc.value());
         log::trace!("finished sign verify");
-        let to_be_keccak_checked = [sign_data_decomposed.is_address_zero, pk_rlc, pk_hash_rlc];
-        let assigned_sig_verif = AssignedSignatureVerify {
+        Ok(AssignedSignatureVerify {
             address: sign_data_decomposed.address,
             msg_len: sign_data.msg.len(),
             msg_rlc: challenges
@@ -732,11 +805,18 @@ impl<F: Field> SigCircuit<F> {
                 .map(|r| rlc::value(sign_data.msg.iter().rev(), r)),
             msg_hash_rlc,
             sig_is_valid: assigned_ecdsa.sig_is_valid,
+            s_is_low: assigned_ecdsa.s_is_low,
             r_rlc,
             s_rlc,
             v: assigned_ecdsa.v,
-        };
-        Ok((to_be_keccak_checked, assigned_sig_verif))
+        })
+    }
+
+    /// Advice cells used so far in the current phase of the context, which
+    /// allocates them column by column.
+    fn advice_cells(ctx: &Context<F>, ecdsa_chip: &FpChip<F>) -> usize {
+        let (column, row) = ctx.advice_alloc[0];
+        column * ecdsa_chip.range.gate.max_rows + row
     }
 
     /// Assign witness data to the sig circuit.
@@ -767,34 +847,69 @@ impl<F: Field> SigCircuit<F> {
                 }
 
                 let mut ctx = ecdsa_chip.new_context(region);
-
-                // ================================================
-                // step 1: assert the signature is valid in circuit
-                // ================================================
-                let assigned_ecdsas = signatures
+                let sign_datas = signatures
                     .iter()
                     .chain(std::iter::repeat(&SignData::default()))
                     .take(self.max_verif)
-                    .map(|sign_data| self.assign_ecdsa(&mut ctx, ecdsa_chip, sign_data))
-                    .collect::<Result<Vec<AssignedECDSA<F, FpChip<F>>>, Error>>()?;
+                    .collect_vec();
 
                 // ================================================
+                // step 1: assert the signature is valid in circuit, and
                 // step 2: decompose the keys and messages
                 // ================================================
-                let sign_data_decomposed = signatures
-                    .iter()
-                    .chain(std::iter::repeat(&SignData::default()))
-                    .take(self.max_verif)
-                    .zip_eq(assigned_ecdsas.iter())
-                    .map(|(sign_data, assigned_ecdsa)| {
-                        self.sign_data_decomposition(
-                            &mut ctx,
-                            ecdsa_chip,
-                            sign_data,
-                            assigned_ecdsa,
-                        )
-                    })
-                    .collect::<Result<Vec<SignDataDecomposed<F>>, Error>>()?;
+                let constants = EcdsaConstants::load::<Fp, Fq, Secp256k1Affine>(
+                    ecdsa_chip,
+                    &mut ctx,
+                    FIXED_WINDOW_BITS,
+                );
+                // each public key is assigned once, at its first signature
+                let mut assigned_pks = Vec::new();
+                let mut pk_indices = HashMap::new();
+                let mut max_cells_per_sig = 0;
+                let mut assigned_ecdsas = Vec::with_capacity(sign_datas.len());
+                for sign_data in sign_datas.iter() {
+                    let cells = Self::advice_cells(&ctx, ecdsa_chip);
+
+                    let pk_idx = match pk_indices.entry(pk_bytes_le(&sign_data.pk)) {
+                        Entry::Occupied(entry) => *entry.get(),
+                        Entry::Vacant(entry) => {
+                            assigned_pks.push(self.assign_pk(
+                                &mut ctx,
+                                ecdsa_chip,
+                                &sign_data.pk,
+                            )?);
+                            *entry.insert(assigned_pks.len() - 1)
+                        }
+                    };
+                    let assigned_pk = &assigned_pks[pk_idx];
+                    let assigned_ecdsa = self.assign_ecdsa(
+                        &mut ctx,
+                        ecdsa_chip,
+                        &constants,
+                        assigned_pk,
+                        sign_data,
+                    )?;
+                    let sign_data_decomposed = self.sign_data_decomposition(
+                        &mut ctx,
+                        ecdsa_chip,
+                        sign_data,
+                        assigned_pk,
+                        &assigned_ecdsa,
+                    )?;
+                    assigned_ecdsas.push((pk_idx, assigned_ecdsa, sign_data_decomposed));
+
+                    max_cells_per_sig =
+                        max_cells_per_sig.max(Self::advice_cells(&ctx, ecdsa_chip) - cells);
+                }
+                log::info!(
+                    "ecdsa chip uses at most {} advice cells per signature, {} public keys",
+                    max_cells_per_sig,
+                    assigned_pks.len(),
+                );
+                debug_assert!(
+                    max_cells_per_sig <= CELLS_PER_SIG,
+                    "{max_cells_per_sig} advice cells per signature > {CELLS_PER_SIG}",
+                );
 
                 // IMPORTANT: Move to Phase2 before RLC
                 log::info!("before proceeding to the next phase");
@@ -810,44 +925,52 @@ impl<F: Field> SigCircuit<F> {
                 // ================================================
                 // step 3: compute RLC of keys and messages
                 // ================================================
-                let (assigned_keccak_values, assigned_sig_values): (
-                    Vec<[AssignedValue<F>; 3]>,
-                    Vec<AssignedSignatureVerify<F>>,
-                ) = signatures
+                let challenge_powers = Self::challenge_powers(challenges);
+                let assigned_pk_rlcs = assigned_pks
+                    .iter()
+                    .map(|assigned_pk| {
+                        self.assign_pk_rlcs(
+                            &mut ctx,
+                            &ecdsa_chip.range,
+                            assigned_pk,
+                            &challenge_powers,
+                        )
+                    })
+                    .collect_vec();
+                let assigned_sig_values = sign_datas
                     .iter()
-                    .chain(std::iter::repeat(&SignData::default()))
-                    .take(self.max_verif)
                     .zip_eq(assigned_ecdsas.iter())
-                    .zip_eq(sign_data_decomposed.iter())
-                    .map(|((sign_data, assigned_ecdsa), sign_data_decomp)| {
+                    .map(|(sign_data, (_, assigned_ecdsa, sign_data_decomp))| {
                         self.assign_sig_verify(
                             &mut ctx,
                             &ecdsa_chip.range,
                             sign_data,
                             sign_data_decomp,
                             challenges,
+                            &challenge_powers,
                             assigned_ecdsa,
                         )
                     })
-                    .collect::<Result<
-                        Vec<([AssignedValue<F>; 3], AssignedSignatureVerify<F>)>,
-                        Error,
-                    >>()?
-                    .into_iter()
-                    .unzip();
+                    .collect::<Result<Vec<AssignedSignatureVerify<F>>, Error>>()?;
 
                 // ================================================
-                // step 4: deferred keccak checks
+                // step 4: deferred keccak checks, once per public key
                 // ================================================
-                for (i, [is_address_zero, pk_rlc, pk_hash_rlc]) in
-                    assigned_keccak_values.iter().enumerate()
+                let mut first_sigs = vec![None; assigned_pks.len()];
+                for (pk_idx, _, sign_data_decomp) in assigned_ecdsas.iter() {
+                    first_sigs[*pk_idx].get_or_insert(sign_data_decomp.is_address_zero);
+                }
+                for (i, (is_address_zero, [pk_rlc, pk_hash_rlc])) in first_sigs
+                    .iter()
+                    .zip_eq(assigned_pk_rlcs.iter())
+                    .enumerate()
                 {
                     let offset = i * 3;
                     self.enable_keccak_lookup(
                         config,
                         &mut ctx,
                         offset,
-                        is_address_zero,
+                        &is_address_zero.unwrap(),
                         pk_rlc,
                         pk_hash_rlc,
                     )?;
@@ -906,6 +1029,12 @@ impl<F: Field> SigCircuit<F> {
                         idx,
                     );
 
+                    assigned_sig_verif.s_is_low.copy_advice(
+                        &mut region,
+                        config.sig_table.is_low_s,
+                        idx,
+                    );
+
                     assigned_sig_verif.msg_hash_rlc.copy_advice(
                         &mut region,
                         config.sig_table.msg_hash_rlc,
@@ -9,9 +9,164 @@ use halo2_base::{
 };
 use halo2_ecc::{
     bigint::{big_less_than, CRTInteger},
-    ecc::{fixed_base, scalar_multiply, EcPoint, EccChip},
+    ecc::{scalar_multiply, EcPoint, EccChip},
     fields::{fp::FpConfig, FieldChip, PrimeField, Selectable},
 };
+use halo2_proofs::halo2curves::group::{prime::PrimeCurveAffine, Curve};
+
+/// Constants of the ECDSA verification. They are loaded once per region and
+/// shared by all the signatures verified in it, instead of once per signature.
+/// This includes the windows of the fixed base scalar multiplication by the
+/// generator, which are the bulk of them.
+#[derive(Clone, Debug)]
+pub(crate) struct EcdsaConstants<F: PrimeField> {
+    /// scalar field modulus n
+    pub(crate) n: CRTInteger<F>,
+    /// n/2 + 1, the exclusive upper bound of a low s (EIP-2)
+    pub(crate) half_n_plus_one: CRTInteger<F>,
+    /// 0 in the scalar field
+    pub(crate) zero: CRTInteger<F>,
+    /// 1 in the scalar field
+    pub(crate) one: CRTInteger<F>,
+    /// the point at infinity, as (0, 0)
+    pub(crate) point_at_infinity: EcPoint<F, CRTInteger<F>>,
+    /// a random point that replaces a zero pubkey in the scalar multiplication
+    pub(crate) random_point: EcPoint<F, CRTInteger<F>>,
+    /// bits of the windows of the scalar multiplication by the generator
+    pub(crate) fixed_window_bits: usize,
+    /// `[j * 2^(i * fixed_window_bits)] * G` at `i * 2^fixed_window_bits + j`,
+    /// with `G` itself as a dummy point at `j = 0`
+    pub(crate) generator_windows: Vec<EcPoint<F, CRTInteger<F>>>,
+}
+
+impl<F: PrimeField> EcdsaConstants<F> {
+    /// Load the constants of the ECDSA verification over the curve `GA`, with
+    /// windows of `fixed_window_bits` bits for the multiplication by the
+    /// generator
+    pub(crate) fn load<CF: PrimeField, SF: PrimeField, GA>(
+        base_chip: &FpConfig<F, CF>,
+        ctx: &mut Context<F>,
+        fixed_window_bits: usize,
+    ) -> Self
+    where
+        GA: CurveAffineExt<Base = CF, ScalarExt = SF>,
+    {
+        let ecc_chip = EccChip::<F, FpConfig<F, CF>>::construct(base_chip.clone());
+        let scalar_chip = FpConfig::<F, SF>::construct(
+            base_chip.range.clone(),
+            base_chip.limb_bits,
+            base_chip.num_limbs,
+            modulus::<SF>(),
+        );
+        let n = scalar_chip.p.to_biguint().unwrap();
+        let generator_windows =
+            generator_windows::<GA>(base_chip.limb_bits * base_chip.num_limbs, fixed_window_bits)
+                .into_iter()
+                .map(|point| {
+                    let (x, y) = point.into_coordinates();
+                    EcPoint::construct(
+                        ecc_chip.field_chip().load_constant(ctx, fe_to_biguint(&x)),
+                        ecc_chip.field_chip().load_constant(ctx, fe_to_biguint(&y)),
+                    )
+                })
+                .collect();
+
+        Self {
+            half_n_plus_one: scalar_chip.load_constant(ctx, (n.clone() >> 1) + 1u64),
+            n: scalar_chip.load_constant(ctx, n),
+            zero: scalar_chip.load_constant(ctx, FpConfig::<F, SF>::fe_to_constant(SF::ZERO)),
+            one: scalar_chip.load_constant(ctx, FpConfig::<F, SF>::fe_to_constant(SF::ONE)),
+            point_at_infinity: EcPoint::construct(
+                ecc_chip
+                    .field_chip()
+                    .load_constant(ctx, fe_to_biguint(&CF::ZERO)),
+                ecc_chip
+                    .field_chip()
+                    .load_constant(ctx, fe_to_biguint(&CF::ZERO)),
+            ),
+            random_point: ecc_chip.load_random_point::<GA>(ctx),
+            fixed_window_bits,
+            generator_windows,
+        }
+    }
+}
+
+/// Multiples of the generator of the windows of `window_bits` bits of a scalar
+/// of `total_bits` bits, see [`EcdsaConstants::generator_windows`]. The last
+/// window is cut to the remaining bits.
+fn generator_windows<GA: CurveAffineExt>(total_bits: usize, window_bits: usize) -> Vec<GA> {
+    let num_windows = (total_bits + window_bits - 1) / window_bits;
+    let mut increment = GA::generator().to_curve();
+    let mut points = Vec::with_capacity(num_windows << window_bits);
+    for i in 0..num_windows {
+        let window_size = 1 << window_bits.min(total_bits - i * window_bits);
+        // start with increment instead of the identity as a dummy point
+        points.push(increment);
+        let mut curr = increment;
+        for _ in 1..window_size {
+            points.push(curr);
+            curr += increment;
+        }
+        increment = curr;
+    }
+    points.iter().map(|point| point.to_affine()).collect()
+}
+
+/// Multiply the generator by a scalar of limbs of `max_bits` bits, selecting
+/// the multiple of each window out of the windows shared by all the
+/// signatures. The scalar must not be zero.
+/// Modified from
+/// <https://github.com/scroll-tech/halo2-lib/blob/530e744232860641f9533c9b9f8c1fee57f54cab/halo2-ecc/src/ecc/fixed_base.rs>
+fn fixed_base_scalar_multiply<F: PrimeField, CF: PrimeField>(
+    ecc_chip: &EccChip<F, FpConfig<F, CF>>,
+    ctx: &mut Context<F>,
+    constants: &EcdsaConstants<F>,
+    scalar: &[AssignedValue<F>],
+    max_bits: usize,
+) -> EcPoint<F, CRTInteger<F>> {
+    let gate = ecc_chip.field_chip().range().gate();
+    let window_bits = constants.fixed_window_bits;
+    let bits = scalar
+        .iter()
+        .flat_map(|limb| gate.num_to_bits(ctx, limb, max_bits))
+        .collect::<Vec<_>>();
+
+    let mut curr_point: Option<EcPoint<F, CRTInteger<F>>> = None;
+    // whether curr_point is not the identity yet
+    let mut is_started = gate.load_zero(ctx);
+    for (window, bit_window) in constants
+        .generator_windows
+        .chunks(1 << window_bits)
+        .rev()
+        .zip(bits.chunks(window_bits).rev())
+    {
+        let bit_sum = gate.sum(ctx, bit_window.iter().map(|&bit| Existing(bit)));
+        let is_zero_window = gate.is_zero(ctx, &bit_sum);
+        let add_point = if window.len() == 1 {
+            window[0].clone()
+        } else {
+            let indicator = gate.bits_to_indicator(ctx, bit_window);
+            ecc_chip.select_by_indicator(ctx, window, &indicator)
+        };
+        curr_point = Some(match curr_point {
+            Some(curr_point) => {
+                let sum = ecc_chip.add_unequal(ctx, &curr_point, &add_point, false);
+                let zero_sum = ecc_chip.select(ctx, &curr_point, &sum, &is_zero_window);
+                ecc_chip.select(ctx, &zero_sum, &add_point, &is_started)
+            }
+            None => add_point,
+        });
+        // is_started || !is_zero_window == is_started * is_zero_window + !is_zero_window
+        let is_nonzero_window = gate.not(ctx, Existing(is_zero_window));
+        is_started = gate.mul_add(
+            ctx,
+            Existing(is_started),
+            Existing(is_zero_window),
+            Existing(is_nonzero_window),
+        );
+    }
+    curr_point.unwrap()
+}
 
 // CF is the coordinate field of GA
 // SF is the scalar field of GA
@@ -25,12 +180,12 @@ use halo2_ecc::{
 pub(crate) fn ecdsa_verify_no_pubkey_check<F: PrimeField, CF: PrimeField, SF: PrimeField, GA>(
     base_chip: &FpConfig<F, CF>,
     ctx: &mut Context<F>,
+    constants: &EcdsaConstants<F>,
     pubkey: &EcPoint<F, <FpConfig<F, CF> as FieldChip<F>>::FieldPoint>,
     r: &CRTInteger<F>,
     s: &CRTInteger<F>,
     msghash: &CRTInteger<F>,
     var_window_bits: usize,
-    fixed_window_bits: usize,
 ) -> (AssignedValue<F>, AssignedValue<F>, CRTInteger<F>)
 where
     GA: CurveAffineExt<Base = CF, ScalarExt = SF>,
@@ -42,7 +197,14 @@ where
         base_chip.num_limbs,
         modulus::<SF>(),
     );
-    let n = scalar_chip.load_constant(ctx, scalar_chip.p.to_biguint().unwrap());
+    let EcdsaConstants {
+        n,
+        zero,
+        one,
+        point_at_infinity,
+        random_point,
+        ..
+    } = constants;
 
     // check whether the pubkey is (0, 0), i.e. in the case of ecrecover, no pubkey could be
     // recovered.
@@ -78,26 +240,14 @@ where
         .gate()
         .or(ctx, Existing(s_is_zero), Existing(s_in_range));
 
-    // load required constants
-    let zero = scalar_chip.load_constant(ctx, FpConfig::<F, SF>::fe_to_constant(SF::ZERO));
-    let one = scalar_chip.load_constant(ctx, FpConfig::<F, SF>::fe_to_constant(SF::ONE));
-    let point_at_infinity = EcPoint::construct(
-        ecc_chip
-            .field_chip()
-            .load_constant(ctx, fe_to_biguint(&CF::ZERO)),
-        ecc_chip
-            .field_chip()
-            .load_constant(ctx, fe_to_biguint(&CF::ZERO)),
-    );
-
     // compute u1 = m * s^{-1} mod n
-    let s_prime = scalar_chip.select(ctx, &one, s, &s_is_zero);
+    let s_prime = scalar_chip.select(ctx, one, s, &s_is_zero);
     let u1 = scalar_chip.divide(ctx, msghash, &s_prime);
-    let u1 = scalar_chip.select(ctx, &zero, &u1, &s_is_zero);
+    let u1 = scalar_chip.select(ctx, zero, &u1, &s_is_zero);
 
     // compute u2 = r * s^{-1} mod n
     let u2 = scalar_chip.divide(ctx, r, &s_prime);
-    let u2 = scalar_chip.select(ctx, &zero, &u2, &s_is_zero);
+    let u2 = scalar_chip.select(ctx, zero, &u2, &s_is_zero);
 
     // we want to compute u1*G + u2*PK, there are two edge cases
     // 1. either u1 or u2 is 0; we use binary selections to handle the this case
@@ -107,21 +257,19 @@ where
     // case 1:
     // =================================
     let u1_is_zero = scalar_chip.is_zero(ctx, &u1);
-    let u1_prime = scalar_chip.select(ctx, &one, &u1, &u1_is_zero);
-    let u1_mul = fixed_base::scalar_multiply::<F, _, _>(
-        base_chip,
+    let u1_prime = scalar_chip.select(ctx, one, &u1, &u1_is_zero);
+    let u1_mul = fixed_base_scalar_multiply(
+        &ecc_chip,
         ctx,
-        &GA::generator(),
+        constants,
         &u1_prime.truncation.limbs,
         base_chip.limb_bits,
-        fixed_window_bits,
     );
-    let u1_mul = ecc_chip.select(ctx, &point_at_infinity, &u1_mul, &u1_is_zero);
+    let u1_mul = ecc_chip.select(ctx, point_at_infinity, &u1_mul, &u1_is_zero);
 
     // compute u2 * pubkey
-    let u2_prime = scalar_chip.select(ctx, &one, &u2, &s_is_zero);
-    let pubkey_prime = ecc_chip.load_random_point::<GA>(ctx);
-    let pubkey_prime = ecc_chip.select(ctx, &pubkey_prime, pubkey, &is_pubkey_zero);
+    let u2_prime = scalar_chip.select(ctx, one, &u2, &s_is_zero);
+    let pubkey_prime = ecc_chip.select(ctx, random_point, pubkey, &is_pubkey_zero);
     let u2_mul = scalar_multiply::<F, _>(
         base_chip,
         ctx,
@@ -135,7 +283,7 @@ where
             .range()
             .gate()
             .or(ctx, Existing(s_is_zero), Existing(is_pubkey_zero));
-    let u2_mul = ecc_chip.select(ctx, &point_at_infinity, &u2_mul, &u2_is_zero);
+    let u2_mul = ecc_chip.select(ctx, point_at_infinity, &u2_mul, &u2_is_zero);
 
     // =================================
     // case 2:
@@ -200,10 +348,10 @@ where
         // edge cases
         let x_3 = base_chip.select(ctx, u2_mul.x(), &x_3, &u1_is_zero);
         let x_3 = base_chip.select(ctx, u1_mul.x(), &x_3, &u2_is_zero);
-        let x_3 = base_chip.select(ctx, &zero, &x_3, &sum_is_infinity);
+        let x_3 = base_chip.select(ctx, zero, &x_3, &sum_is_infinity);
         let y_3 = base_chip.select(ctx, u2_mul.y(), &y_3, &u1_is_zero);
         let y_3 = base_chip.select(ctx, u1_mul.y(), &y_3, &u2_is_zero);
-        let y_3 = base_chip.select(ctx, &zero, &y_3, &sum_is_infinity);
+        let y_3 = base_chip.select(ctx, zero, &y_3, &sum_is_infinity);
 
         (x_3, y_3)
     };
@@ -229,6 +229,43 @@ fn sign_verify() {
     }
 }
 
+#[test]
+fn sign_verify_shared_pk() {
+    use super::utils::LOG_TOTAL_NUM_ROWS;
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    // several txs of a sender, and another sender
+    let mut rng = XorShiftRng::seed_from_u64(3);
+    let key_pairs = [gen_key_pair(&mut rng), gen_key_pair(&mut rng)];
+    let signatures = [0, 0, 1, 0]
+        .into_iter()
+        .map(|idx| {
+            let (sk, pk) = key_pairs[idx];
+            let msg_hash = gen_msg_hash(&mut rng);
+            SignData {
+                signature: sign_with_rng(&mut rng, sk, msg_hash),
+                pk,
+                msg: gen_msg(&mut rng).into(),
+                msg_hash,
+            }
+        })
+        .collect();
+
+    run::<Fr>(LOG_TOTAL_NUM_ROWS as u32, 8, signatures);
+}
+
+#[test]
+fn rows_per_sig_within_capacity() {
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    let row_num = SigCircuit::<Fr>::min_num_rows();
+    let rows_per_sig = SigCircuit::<Fr>::rows_per_sig(row_num);
+    assert!(rows_per_sig > 0);
+    assert!(rows_per_sig * SigCircuit::<Fr>::max_num_verif() <= row_num);
+}
+
 // Generate a test key pair
 fn gen_key_pair(rng: impl RngCore) -> (secp256k1::Fq, Secp256k1Affine) {
     // generate a valid signature
@@ -13,10 +13,16 @@ use halo2_proofs::{
 // Hard coded parameters.
 // FIXME: allow for a configurable param.
 pub(super) const MAX_NUM_SIG: usize = 128;
-// Each ecdsa signature requires 461174 cells
+// Each ecdsa signature requires at most 461174 cells, checked against the cells
+// measured when assigning the circuit
 pub(super) const CELLS_PER_SIG: usize = 461174;
 // Each ecdsa signature requires 63276 lookup cells
 pub(super) const LOOKUP_CELLS_PER_SIG: usize = 63276;
+// Bits of the windows of the scalar multiplication by the generator, whose
+// multiples are shared by all the signatures
+pub(super) const FIXED_WINDOW_BITS: usize = 4;
+// Bits of the windows of the scalar multiplication by a public key
+pub(super) const VAR_WINDOW_BITS: usize = 4;
 // Total number of rows allocated for ecdsa chip
 pub(super) const LOG_TOTAL_NUM_ROWS: usize = 20;
 // Max number of columns allowed
@@ -73,6 +79,7 @@ pub(crate) struct AssignedECDSA<F: Field, FC: FieldChip<F>> {
     pub(super) integer_s: CRTInteger<F>,
     pub(super) v: AssignedValue<F>,
     pub(super) sig_is_valid: AssignedValue<F>,
+    pub(super) s_is_low: AssignedValue<F>,
 }
 
 #[derive(Debug, Clone)]
@@ -85,12 +92,20 @@ pub(crate) struct AssignedSignatureVerify<F: Field> {
     pub(crate) s_rlc: AssignedValue<F>,
     pub(crate) v: AssignedValue<F>,
     pub(crate) sig_is_valid: AssignedValue<F>,
+    pub(crate) s_is_low: AssignedValue<F>,
 }
 
-pub(super) struct SignDataDecomposed<F: Field> {
+/// A public key assigned once and shared by all the signatures under it.
+pub(super) struct AssignedPubkey<F: Field> {
+    pub(super) pk: EcPoint<F, CRTInteger<F>>,
+    /// little-endian bytes of y, then of x
+    pub(super) pk_cells: Vec<QuantumCell<F>>,
+    /// little-endian bytes of the keccak hash of the public key
     pub(super) pk_hash_cells: Vec<QuantumCell<F>>,
+}
+
+pub(super) struct SignDataDecomposed<F: Field> {
     pub(super) msg_hash_cells: Vec<QuantumCell<F>>,
-    pub(super) pk_cells: Vec<QuantumCell<F>>,
     pub(super) address: AssignedValue<F>,
     pub(super) is_address_zero: AssignedValue<F>,
     pub(super) r_cells: Vec<QuantumCell<F>>,
@@ -25,10 +25,10 @@ use crate::{
     witness::{self, MptUpdates, Rw, RwMap},
 };
 use constraint_builder::{ConstraintBuilder, Queries};
-use eth_types::{Address, ToLittleEndian, ToWord};
+use eth_types::{Address, ToLittleEndian, ToWord, H256};
 use gadgets::{
     batched_is_zero::{BatchedIsZeroChip, BatchedIsZeroConfig},
-    binary_number::{BinaryNumberChip, BinaryNumberConfig},
+    binary_number::{BinaryNumberChip, BinaryNumberConfig, BinaryNumberTag},
 };
 use halo2_proofs::{
     circuit::{AssignedCell, Cell, Layouter, Region, Value},
@@ -41,6 +41,8 @@ use lookups::{Chip as LookupsChip, Config as LookupsConfig, Queries as LookupsQu
 use multiple_precision_integer::{Chip as MpiChip, Config as MpiConfig, Queries as MpiQueries};
 use param::*;
 use random_linear_combination::{Chip as RlcChip, Config as RlcConfig, Queries as RlcQueries};
+#[cfg(feature = "parallel_syn")]
+use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use std::marker::PhantomData;
 
 #[cfg(feature = "onephase")]
@@ -74,16 +76,22 @@ pub struct StateCircuitConfig<F> {
     not_first_access: Column<Advice>,
     lookups: LookupsConfig,
     power_of_randomness: [Expression<F>; N_BYTES_WORD - 1],
-    // External tables
-    mpt_table: MptTable,
+    // External tables. None in light mode.
+    mpt_table: Option<MptTable>,
 }
 
 /// Circuit configuration arguments
 pub struct StateCircuitConfigArgs<F: Field> {
     /// RwTable
     pub rw_table: RwTable,
-    /// MptTable
-    pub mpt_table: MptTable,
+    /// MptTable. When it is None, the circuit is configured in light mode:
+    /// the MPT lookups of the last accesses to Account and AccountStorage
+    /// keys are skipped, so only the consistency of the RwTable is proven.
+    /// The start and end state roots are then the commitments supplied with
+    /// the [`MptUpdates`], and binding them to the writes (e.g. by another
+    /// state tree proof) is left to the circuit exporting them as public
+    /// inputs.
+    pub mpt_table: Option<MptTable>,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
 }
@@ -169,7 +177,11 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
 
         // annotate columns
         rw_table.annotate_columns(meta);
-        mpt_table.annotate_columns(meta);
+        if let Some(mpt_table) = &mpt_table {
+            mpt_table.annotate_columns(meta);
+        } else {
+            log::info!("state circuit configured in light mode, without MPT lookups");
+        }
 
         let config = Self {
             selector,
@@ -201,6 +213,11 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
 }
 
 impl<F: Field> StateCircuitConfig<F> {
+    /// Return true if the circuit is configured without the MPT lookups.
+    pub fn is_light_mode(&self) -> bool {
+        self.mpt_table.is_none()
+    }
+
     /// load fixed tables
     pub(crate) fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
         LookupsChip::construct(self.lookups).load(layouter)
@@ -558,94 +575,88 @@ impl<F: Field> StateCircuitConfig<F> {
         Ok(is_first_access_vec)
     }
 
-    #[allow(clippy::too_many_arguments)]
+    #[cfg(feature = "parallel_syn")]
+    // Compute the state root assigned at each row. The state root changes on the last access
+    // row of a key with an mpt update, so the new and old roots of the segments of every key are
+    // computed in parallel, and only carrying the latest root forward is done sequentially.
+    fn state_roots(
+        rows: &[Rw],
+        is_first_access_vec: &[bool],
+        updates: &MptUpdates,
+        randomness: F,
+    ) -> Vec<F> {
+        let rows_len = rows.len();
+        let root_updates: Vec<Option<(F, F)>> = rows
+            .par_iter()
+            .enumerate()
+            .map(|(offset, row)| {
+                // The last row is always a last access.
+                let is_last_access = offset + 1 == rows_len || is_first_access_vec[offset + 1];
+                if offset + 1 < rows_len && is_last_access {
+                    let next_row = &rows[offset + 1];
+                    if matches!(next_row.tag(), RwTableTag::CallContext)
+                        && !next_row.is_write()
+                        && next_row.value_assignment(randomness) != F::zero()
+                    {
+                        log::error!("invalid call context: {:?}", next_row);
+                    }
+                }
+                is_last_access
+                    .then(|| updates.get(row))
+                    .flatten()
+                    .map(|update| update.root_assignments(randomness))
+            })
+            .collect();
+
+        let mut state_root = rlc::value(&updates.old_root().to_word().to_le_bytes(), randomness);
+        root_updates
+            .into_iter()
+            .zip_eq(rows)
+            .enumerate()
+            .map(|(offset, (root_update, row))| {
+                if let Some((new_root, old_root)) = root_update {
+                    if state_root != old_root {
+                        log::error!(
+                            "invalid root randomness {:?}, state_root {:?}, row {:?} offset {}",
+                            randomness,
+                            state_root,
+                            row,
+                            offset
+                        );
+                        assert_eq!(state_root, old_root);
+                    }
+                    state_root = new_root;
+                }
+                state_root
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "parallel_syn")]
     fn assign_state_roots(
         &self,
         region: &mut Region<'_, F>,
         rows: &[Rw],
-        padding_length: usize,
         is_first_access_vec: &[bool],
         updates: &MptUpdates,
         randomness: Value<F>,
     ) -> Result<StateCircuitExports<Assigned<F>>, Error> {
-        let rows_len = rows.len();
-
-        let mut state_root = randomness
-            .map(|randomness| rlc::value(&updates.old_root().to_word().to_le_bytes(), randomness));
+        let state_roots = randomness
+            .map(|randomness| Self::state_roots(rows, is_first_access_vec, updates, randomness));
 
         let mut start_state_root: Option<AssignedCell<_, F>> = None;
         let mut end_state_root: Option<AssignedCell<_, F>> = None;
-
-        for (offset, (row, is_first_access)) in
-            rows.iter().zip_eq(is_first_access_vec.iter()).enumerate()
-        {
-            if offset == 0 || offset + 1 >= padding_length {
-                log::trace!("state circuit assign offset:{} row:{:?}", offset, row);
-            }
-
-            if offset > 0 {
-                let prev_row = &rows[offset - 1];
-
-                if *is_first_access {
-                    // If previous row was a last access, we need to update the state root.
-                    state_root = randomness
-                        .zip(state_root)
-                        .map(|(randomness, mut state_root)| {
-                            if let Some(update) = updates.get(prev_row) {
-                                let (new_root, old_root) = update.root_assignments(randomness);
-                                if state_root != old_root {
-                                    log::error!("invalid root randomness {:?}, state_root {:?}, prev_row {:?} update {:?}", 
-                                    randomness, state_root, prev_row, update);
-                                    assert_eq!(state_root, old_root);
-                                }
-                                state_root = new_root;
-                            }
-                            if matches!(row.tag(), RwTableTag::CallContext)
-                                && !row.is_write()
-                                && row.value_assignment(randomness) != F::zero()
-                            {
-                                log::error!("invalid call context: {:?}", row);
-                            }
-                            state_root
-                        });
-                }
-            }
-
-            // TODO: Switch from Rw::Start -> Rw::Padding to simplify this logic.
-            // State root assignment is at previous row (offset - 1) because the state root
-            // changes on the last access row.
-            if offset != 0 {
-                let assigned = region.assign_advice(
-                    || "state_root",
-                    self.state_root,
-                    offset - 1,
-                    || state_root,
-                )?;
-                if start_state_root.is_none() {
-                    start_state_root.replace(assigned);
-                }
-            }
-
-            if offset + 1 == rows_len {
-                // The last row is always a last access, so we need to handle the case where the
-                // state root changes because of an mpt lookup on the last row.
-                if let Some(update) = updates.get(row) {
-                    state_root = randomness.zip(state_root).map(|(randomness, state_root)| {
-                        let (new_root, old_root) = update.root_assignments(randomness);
-                        if !state_root.is_zero_vartime() {
-                            assert_eq!(state_root, old_root);
-                        }
-                        new_root
-                    });
-                }
-                let assigned = region.assign_advice(
-                    || "last row state_root",
-                    self.state_root,
-                    offset,
-                    || state_root,
-                )?;
-                end_state_root.replace(assigned);
+        for offset in 0..rows.len() {
+            let assigned = region.assign_advice(
+                || "state_root",
+                self.state_root,
+                offset,
+                || state_roots.as_ref().map(|state_roots| state_roots[offset]),
+            )?;
+            if offset == 0 {
+                start_state_root.replace(assigned.clone());
             }
+            end_state_root.replace(assigned);
         }
 
         let start_state_root = start_state_root.expect("should be assigned");
@@ -775,8 +786,7 @@ impl<F: Field> StateCircuitConfig<F> {
         assert_eq!(is_first_access_vec.len(), rows.len());
 
         let mut is_first_time = true;
-        // assign state roots sequentially
-        // TODO: parallelize it
+        // the state roots are computed in parallel, and assigned in one region
         layouter.assign_region(
             || "state circuit (synthesize_sub) part3",
             |mut region| {
@@ -788,7 +798,6 @@ impl<F: Field> StateCircuitConfig<F> {
                 let exports = self.assign_state_roots(
                     &mut region,
                     &rows,
-                    padding_length,
                     &is_first_access_vec,
                     updates,
                     randomness,
@@ -821,7 +830,9 @@ impl<F: Field> StateCircuitConfig<F> {
 
     fn annotate_circuit_in_region(&self, region: &mut Region<F>) {
         self.rw_table.annotate_columns_in_region(region);
-        self.mpt_table.annotate_columns_in_region(region);
+        if let Some(mpt_table) = &self.mpt_table {
+            mpt_table.annotate_columns_in_region(region);
+        }
         self.is_non_exist
             .annotate_columns_in_region(region, "STATE");
         self.lexicographic_ordering
@@ -838,7 +849,7 @@ impl<F: Field> StateCircuitConfig<F> {
 /// Keys for sorting the rows of the state circuit
 #[derive(Clone, Copy)]
 pub struct SortKeysConfig {
-    tag: BinaryNumberConfig<RwTableTag, 4>,
+    tag: BinaryNumberConfig<RwTableTag, { RwTableTag::N_BITS }>,
     id: MpiConfig<u32, N_LIMBS_ID>,
     address: MpiConfig<Address, N_LIMBS_ACCOUNT_ADDRESS>,
     field_tag: Column<Advice>,
@@ -889,6 +900,28 @@ impl<F: Field> StateCircuit<F> {
             _marker: PhantomData,
         }
     }
+
+    /// make a new state circuit from an RwMap, whose start and end state roots
+    /// are the commitments `old_root` and `new_root`. It is meant for a state
+    /// circuit configured in light mode, which does not prove the MPT updates.
+    pub fn new_with_state_roots(
+        rw_map: RwMap,
+        n_rows: usize,
+        old_root: H256,
+        new_root: H256,
+    ) -> Self {
+        let rows = rw_map.table_assignments();
+        let updates = MptUpdates::from_rws_with_state_roots(&rows, old_root, new_root);
+        Self {
+            rows,
+            updates,
+            exports: std::cell::RefCell::new(None),
+            n_rows,
+            #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+            overrides: HashMap::new(),
+            _marker: PhantomData,
+        }
+    }
 }
 
 impl<F: Field> SubCircuit<F> for StateCircuit<F> {
@@ -1052,16 +1085,16 @@ fn queries<F: Field>(meta: &mut VirtualCells<'_, F>, c: &StateCircuitConfig<F>)
             value_prev: meta.query_advice(c.rw_table.value, Rotation::prev()),
             value_prev_column: meta.query_advice(c.rw_table.value_prev, Rotation::cur()),
         },
-        mpt_update_table: MptUpdateTableQueries {
-            q_enable: meta.query_fixed(c.mpt_table.q_enable, Rotation::cur()),
-            address: meta.query_advice(c.mpt_table.address, Rotation::cur()),
-            storage_key: meta.query_advice(c.mpt_table.storage_key, Rotation::cur()),
-            proof_type: meta.query_advice(c.mpt_table.proof_type, Rotation::cur()),
-            new_root: meta.query_advice(c.mpt_table.new_root, Rotation::cur()),
-            old_root: meta.query_advice(c.mpt_table.old_root, Rotation::cur()),
-            new_value: meta.query_advice(c.mpt_table.new_value, Rotation::cur()),
-            old_value: meta.query_advice(c.mpt_table.old_value, Rotation::cur()),
-        },
+        mpt_update_table: c.mpt_table.map(|mpt_table| MptUpdateTableQueries {
+            q_enable: meta.query_fixed(mpt_table.q_enable, Rotation::cur()),
+            address: meta.query_advice(mpt_table.address, Rotation::cur()),
+            storage_key: meta.query_advice(mpt_table.storage_key, Rotation::cur()),
+            proof_type: meta.query_advice(mpt_table.proof_type, Rotation::cur()),
+            new_root: meta.query_advice(mpt_table.new_root, Rotation::cur()),
+            old_root: meta.query_advice(mpt_table.old_root, Rotation::cur()),
+            new_value: meta.query_advice(mpt_table.new_value, Rotation::cur()),
+            old_value: meta.query_advice(mpt_table.old_value, Rotation::cur()),
+        }),
         lexicographic_ordering_selector: meta
             .query_fixed(c.lexicographic_ordering.selector, Rotation::cur()),
         rw_counter: MpiQueries::new(meta, c.sort_keys.rw_counter),
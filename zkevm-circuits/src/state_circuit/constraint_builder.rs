@@ -45,7 +45,8 @@ pub struct MptUpdateTableQueries<F: Field> {
 pub struct Queries<F: Field> {
     pub selector: Expression<F>,
     pub rw_table: RwTableQueries<F>,
-    pub mpt_update_table: MptUpdateTableQueries<F>,
+    // None in light mode
+    pub mpt_update_table: Option<MptUpdateTableQueries<F>>,
     pub lexicographic_ordering_selector: Expression<F>,
     pub rw_counter: MpiQueries<F, N_LIMBS_RW_COUNTER>,
     pub tag_bits: [Expression<F>; 4],
@@ -297,24 +298,9 @@ impl<F: Field> ConstraintBuilder<F> {
 
         // ref. spec 4.1. MPT lookup for last access to (address, storage_key)
         self.condition(q.last_access(), |cb| {
-            cb.add_lookup(
+            cb.add_mpt_update_lookup(
                 "mpt_update exists in mpt circuit for AccountStorage last access",
-                vec![
-                    (1.expr(), q.mpt_update_table.q_enable.clone()),
-                    (
-                        q.rw_table.address.clone(),
-                        q.mpt_update_table.address.clone(),
-                    ),
-                    (
-                        q.rw_table.storage_key.clone(),
-                        q.mpt_update_table.storage_key.clone(),
-                    ),
-                    (q.mpt_proof_type(), q.mpt_update_table.proof_type.clone()),
-                    (q.state_root(), q.mpt_update_table.new_root.clone()),
-                    (q.state_root_prev(), q.mpt_update_table.old_root.clone()),
-                    (q.value(), q.mpt_update_table.new_value.clone()),
-                    (q.initial_value(), q.mpt_update_table.old_value.clone()),
-                ],
+                q,
             );
         });
 
@@ -430,24 +416,9 @@ impl<F: Field> ConstraintBuilder<F> {
 
         // last_access degree = 1
         self.condition(q.last_access(), |cb| {
-            cb.add_lookup(
+            cb.add_mpt_update_lookup(
                 "mpt_update exists in mpt circuit for Account last access",
-                vec![
-                    (1.expr(), q.mpt_update_table.q_enable.clone()),
-                    (
-                        q.rw_table.address.clone(),
-                        q.mpt_update_table.address.clone(),
-                    ),
-                    (
-                        q.rw_table.storage_key.clone(),
-                        q.mpt_update_table.storage_key.clone(),
-                    ),
-                    (q.mpt_proof_type(), q.mpt_update_table.proof_type.clone()),
-                    (q.state_root(), q.mpt_update_table.new_root.clone()),
-                    (q.state_root_prev(), q.mpt_update_table.old_root.clone()),
-                    (q.value(), q.mpt_update_table.new_value.clone()),
-                    (q.initial_value(), q.mpt_update_table.old_value.clone()),
-                ],
+                q,
             );
         });
 
@@ -571,6 +542,29 @@ impl<F: Field> ConstraintBuilder<F> {
         self.lookups.push((name, lookup));
     }
 
+    // Lookup of the update of the last access to a key into the mpt table,
+    // skipped in light mode.
+    fn add_mpt_update_lookup(&mut self, name: &'static str, q: &Queries<F>) {
+        if let Some(mpt_update_table) = &q.mpt_update_table {
+            self.add_lookup(
+                name,
+                vec![
+                    (1.expr(), mpt_update_table.q_enable.clone()),
+                    (q.rw_table.address.clone(), mpt_update_table.address.clone()),
+                    (
+                        q.rw_table.storage_key.clone(),
+                        mpt_update_table.storage_key.clone(),
+                    ),
+                    (q.mpt_proof_type(), mpt_update_table.proof_type.clone()),
+                    (q.state_root(), mpt_update_table.new_root.clone()),
+                    (q.state_root_prev(), mpt_update_table.old_root.clone()),
+                    (q.value(), mpt_update_table.new_value.clone()),
+                    (q.initial_value(), mpt_update_table.old_value.clone()),
+                ],
+            );
+        }
+    }
+
     fn condition(&mut self, condition: Expression<F>, build: impl FnOnce(&mut Self)) {
         let original_condition = self.condition.clone();
         self.condition = self.condition.clone() * condition;
@@ -34,7 +34,7 @@ where
                 meta,
                 StateCircuitConfigArgs {
                     rw_table,
-                    mpt_table,
+                    mpt_table: Some(mpt_table),
                     challenges,
                 },
             )
@@ -49,12 +49,14 @@ where
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
         let challenges = challenges.values(&layouter);
-        config.mpt_table.load(
-            &mut layouter,
-            &self.updates,
-            self.n_rows,
-            challenges.evm_word(),
-        )?;
+        if let Some(mpt_table) = &config.mpt_table {
+            mpt_table.load(
+                &mut layouter,
+                &self.updates,
+                self.n_rows,
+                challenges.evm_word(),
+            )?;
+        }
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
 }
@@ -1,11 +1,11 @@
 use crate::{table::CallContextFieldTag, util::Field};
+use gadgets::binary_number::BinaryNumberTag;
 use halo2_proofs::{
     circuit::{Layouter, Value},
     plonk::{Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
     poly::Rotation,
 };
 use std::marker::PhantomData;
-use strum::IntoEnumIterator;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Config {
@@ -125,15 +125,11 @@ impl<F: Field> Chip<F> {
         layouter.assign_region(
             || "assign call_context_field_tags fixed column",
             |mut region| {
-                for field_tag in CallContextFieldTag::iter() {
-                    region.assign_fixed(
-                        || format!("assign {field_tag:?} in call_context_field_tag fixed column"),
-                        self.config.call_context_field_tag,
-                        field_tag as usize,
-                        || Value::known(F::from(field_tag as u64)),
-                    )?;
-                }
-                Ok(())
+                CallContextFieldTag::assign_fixed_table(
+                    &mut region,
+                    self.config.call_context_field_tag,
+                    "call_context_field_tag",
+                )
             },
         )?;
         Ok(())
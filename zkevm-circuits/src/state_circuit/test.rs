@@ -1,8 +1,10 @@
 #![allow(unused_imports)]
 pub use super::{dev::*, *};
 use crate::{
-    table::{AccountFieldTag, CallContextFieldTag, RwTableTag, TxLogFieldTag, TxReceiptFieldTag},
-    util::{unusable_rows, Field, SubCircuit},
+    table::{
+        AccountFieldTag, CallContextFieldTag, RwTable, RwTableTag, TxLogFieldTag, TxReceiptFieldTag,
+    },
+    util::{unusable_rows, Challenges, Field, SubCircuit, SubCircuitConfig},
     witness::{MptUpdates, Rw, RwMap},
 };
 use bus_mapping::operation::{
@@ -11,15 +13,15 @@ use bus_mapping::operation::{
 use eth_types::{
     address,
     evm_types::{MemoryAddress, StackAddress},
-    Address, ToAddress, Word, U256,
+    Address, ToAddress, Word, H256, U256,
 };
 use gadgets::binary_number::AsBits;
 use halo2_proofs::{
     arithmetic::Field as Halo2Field,
-    circuit::SimpleFloorPlanner,
+    circuit::{Layouter, SimpleFloorPlanner},
     dev::{MockProver, VerifyFailure},
     halo2curves::bn256::{Bn256, Fr},
-    plonk::{keygen_vk, Advice, Circuit, Column, ConstraintSystem},
+    plonk::{keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error},
     poly::kzg::commitment::ParamsKZG,
 };
 use rand::SeedableRng;
@@ -198,6 +200,40 @@ fn state_circuit_simple_6() {
     test_state_circuit_ok(vec![memory_op_0, memory_op_1], vec![], vec![storage_op_2]);
 }
 
+#[test]
+fn state_circuit_light_mode() {
+    let storage_op = |rwc: usize, rw, value: u64, value_prev: u64| {
+        Operation::new(
+            RWCounter::from(rwc),
+            rw,
+            StorageOp::new(
+                U256::from(100).to_address(),
+                Word::from(0x40),
+                Word::from(value),
+                Word::from(value_prev),
+                1usize,
+                Word::from(32),
+            ),
+        )
+    };
+    let rw_map = RwMap::from(&OperationContainer {
+        storage: vec![
+            storage_op(18, RW::READ, 32, 32),
+            storage_op(19, RW::WRITE, 33, 32),
+        ],
+        ..Default::default()
+    });
+
+    let circuit = LightStateCircuit(StateCircuit::<Fr>::new_with_state_roots(
+        rw_map,
+        N_ROWS,
+        H256::from_low_u64_be(1),
+        H256::from_low_u64_be(2),
+    ));
+    let prover = MockProver::<Fr>::run(17, &circuit, vec![]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
 #[test]
 fn lexicographic_ordering_test_1() {
     let memory_op = Operation::new(
@@ -1190,6 +1226,48 @@ fn prover(rows: Vec<Rw>, overrides: HashMap<(AdviceColumn, isize), Fr>) -> MockP
     MockProver::<Fr>::run(17, &circuit, instance).unwrap()
 }
 
+// State circuit configured in light mode, without the mpt table.
+#[derive(Default)]
+struct LightStateCircuit(StateCircuit<Fr>);
+
+impl Circuit<Fr> for LightStateCircuit {
+    type Config = (StateCircuitConfig<Fr>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let rw_table = RwTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+        let config = {
+            let challenges = challenges.exprs(meta);
+            StateCircuitConfig::new(
+                meta,
+                StateCircuitConfigArgs {
+                    rw_table,
+                    mpt_table: None,
+                    challenges,
+                },
+            )
+        };
+        assert!(config.is_light_mode());
+        (config, challenges)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&layouter);
+        self.0.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
+
 fn verify(rows: Vec<Rw>) -> Result<(), Vec<VerifyFailure>> {
     let used_rows = rows.len();
     prover(rows, HashMap::new())
@@ -77,6 +77,7 @@ use crate::{
     modexp_circuit::{ModExpCircuit, ModExpCircuitConfig},
     pi_circuit::{PiCircuit, PiCircuitConfig, PiCircuitConfigArgs},
     poseidon_circuit::{PoseidonCircuit, PoseidonCircuitConfig, PoseidonCircuitConfigArgs},
+    prover_input::build_witness_from_eth_block,
     rlp_circuit_fsm::{RlpCircuit, RlpCircuitConfig, RlpCircuitConfigArgs},
     sha256_circuit::{
         CircuitConfig as SHA256CircuitConfig, CircuitConfigArgs as SHA256CircuitConfigArgs,
@@ -90,16 +91,23 @@ use crate::{
         SigTable, TxTable, U16Table, U8Table,
     },
     tx_circuit::{TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
-    util::{circuit_stats, log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
-    witness::{block_convert, Block, Transaction},
+    util::{
+        circuit_fingerprint, circuit_stats, log2_ceil, Challenges, PrecompileRowFormula,
+        SubCircuit, SubCircuitConfig,
+    },
+    witness::{block_convert, dummy_witness_block, Block, Transaction},
 };
 
+#[cfg(not(feature = "zktrie"))]
+use crate::mpt_circuit::keccak::{
+    KeccakMptCircuit, KeccakMptCircuitConfig, KeccakMptCircuitConfigArgs,
+};
 #[cfg(feature = "zktrie")]
 use crate::mpt_circuit::{MptCircuit, MptCircuitConfig, MptCircuitConfigArgs};
 
 use crate::util::Field;
 use bus_mapping::circuit_input_builder::{CircuitInputBuilder, CircuitsParams};
-use eth_types::geth_types::GethData;
+use eth_types::{evm_types::GasSchedule, geth_types::GethData, H256};
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner, Value},
     halo2curves::bn256::Fr,
@@ -122,7 +130,7 @@ pub struct SuperCircuitConfig<F: Field> {
     state_circuit: StateCircuitConfig<F>,
     tx_circuit: TxCircuitConfig<F>,
     sig_circuit: SigCircuitConfig<F>,
-    modexp_circuit: ModExpCircuitConfig,
+    modexp_circuit: ModExpCircuitConfig<F>,
     ecc_circuit: EccCircuitConfig<F>,
     sha256_circuit: SHA256CircuitConfig,
     #[cfg(not(feature = "poseidon-codehash"))]
@@ -138,18 +146,12 @@ pub struct SuperCircuitConfig<F: Field> {
     /// Mpt Circuit
     #[cfg(feature = "zktrie")]
     mpt_circuit: MptCircuitConfig<F>,
+    #[cfg(not(feature = "zktrie"))]
+    mpt_circuit: KeccakMptCircuitConfig<F>,
 }
 
 /// Circuit configuration arguments
 pub struct SuperCircuitConfigArgs {
-    /// Max txs
-    pub max_txs: usize,
-    /// Max calldata
-    pub max_calldata: usize,
-    /// Max inner blocks
-    pub max_inner_blocks: usize,
-    /// Mock randomness
-    pub mock_randomness: u64,
     /// Challenges
     pub challenges: crate::util::Challenges,
 }
@@ -160,13 +162,7 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
     /// Configure SuperCircuitConfig
     fn new(
         meta: &mut ConstraintSystem<Fr>,
-        Self::ConfigArgs {
-            max_txs: _,
-            max_calldata: _,
-            max_inner_blocks: _,
-            mock_randomness: _mock_randomness,
-            challenges,
-        }: Self::ConfigArgs,
+        Self::ConfigArgs { challenges }: Self::ConfigArgs,
     ) -> Self {
         let log_circuit_info = |meta: &ConstraintSystem<Fr>, tag: &str| {
             log::debug!("circuit info after {}: {:#?}", tag, circuit_stats(meta));
@@ -269,6 +265,7 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
                 u16_table,
                 pow_of_rand_table,
                 challenges: challenges_expr.clone(),
+                gas_schedule: GasSchedule::default(),
             },
         );
         log_circuit_info(meta, "tx circuit");
@@ -321,6 +318,16 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
         );
         #[cfg(feature = "zktrie")]
         log_circuit_info(meta, "zktrie circuit");
+        #[cfg(not(feature = "zktrie"))]
+        let mpt_circuit = KeccakMptCircuitConfig::new(
+            meta,
+            KeccakMptCircuitConfigArgs {
+                keccak_table: keccak_table.clone(),
+                challenges: challenges_expr.clone(),
+            },
+        );
+        #[cfg(not(feature = "zktrie"))]
+        log_circuit_info(meta, "keccak mpt circuit");
 
         let modexp_circuit = ModExpCircuitConfig::new(meta, modexp_table);
         log_circuit_info(meta, "modexp circuit");
@@ -328,7 +335,7 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
             meta,
             StateCircuitConfigArgs {
                 rw_table,
-                mpt_table,
+                mpt_table: Some(mpt_table),
                 challenges: challenges_expr.clone(),
             },
         );
@@ -412,12 +419,14 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
             pi_circuit,
             exp_circuit,
             rlp_circuit,
-            #[cfg(feature = "zktrie")]
             mpt_circuit,
         }
     }
 }
 
+/// Rows of the table of the powers of the keccak randomness.
+const POW_OF_RAND_ROWS: usize = 4094 * 31;
+
 /// Row usage for each sub circuit
 #[derive(Clone, Default, Debug)]
 pub struct SubcircuitRowUsage {
@@ -430,15 +439,54 @@ pub struct SubcircuitRowUsage {
     pub row_num_total: usize,
 }
 
-/// The Super Circuit contains all the zkEVM circuits
+/// Sub-circuits which can be left out of the [`SuperCircuit`] in tests, for faster iterations.
+/// The lookup table of a left out sub-circuit is still assigned from the witness, so that the
+/// lookups of the other sub-circuits into it hold, but nothing constrains it: a selection
+/// leaving out a sub-circuit can only be set with the `test` feature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubCircuitSelection {
+    /// Whether the ECC circuit is included.
+    pub ecc: bool,
+    /// Whether the ModExp circuit is included.
+    pub modexp: bool,
+    /// Whether the MPT circuit is included.
+    pub mpt: bool,
+}
+
+impl Default for SubCircuitSelection {
+    fn default() -> Self {
+        Self {
+            ecc: true,
+            modexp: true,
+            mpt: true,
+        }
+    }
+}
+
+impl SubCircuitSelection {
+    /// Leave out the ECC circuit.
+    pub fn without_ecc(self) -> Self {
+        Self { ecc: false, ..self }
+    }
+
+    /// Leave out the ModExp circuit.
+    pub fn without_modexp(self) -> Self {
+        Self {
+            modexp: false,
+            ..self
+        }
+    }
+
+    /// Leave out the MPT circuit.
+    pub fn without_mpt(self) -> Self {
+        Self { mpt: false, ..self }
+    }
+}
+
+/// The Super Circuit contains all the zkEVM circuits. The capacities of the sub-circuits are
+/// the runtime [`CircuitsParams`] of the witness block the circuit is built from.
 #[derive(Clone, Debug)]
-pub struct SuperCircuit<
-    F: Field,
-    const MAX_TXS: usize,
-    const MAX_CALLDATA: usize,
-    const MAX_INNER_BLOCKS: usize,
-    const MOCK_RANDOMNESS: u64,
-> {
+pub struct SuperCircuit<F: Field> {
     /// EVM Circuit
     pub evm_circuit: EvmCircuit<F>,
     /// State Circuit
@@ -470,22 +518,47 @@ pub struct SuperCircuit<
     /// Mpt Circuit
     #[cfg(feature = "zktrie")]
     pub mpt_circuit: MptCircuit<F>,
+    /// Keccak Mpt Circuit
+    #[cfg(not(feature = "zktrie"))]
+    pub mpt_circuit: KeccakMptCircuit<F>,
 
     circuit_params: CircuitsParams,
+    selection: SubCircuitSelection,
+    last_phase_evm_only: bool,
 }
 
-impl<
-        F: Field,
-        const MAX_TXS: usize,
-        const MAX_CALLDATA: usize,
-        const MAX_INNER_BLOCKS: usize,
-        const MOCK_RANDOMNESS: u64,
-    > SuperCircuit<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
-{
+impl<F: Field> SuperCircuit<F> {
+    /// Return the capacities of the sub-circuits.
+    pub fn circuits_params(&self) -> CircuitsParams {
+        self.circuit_params
+    }
+
+    /// Include only the sub-circuits of `selection`. Only for tests, since the tables of the
+    /// left out sub-circuits are unconstrained.
+    #[cfg(any(feature = "test", test))]
+    pub fn with_selection(self, selection: SubCircuitSelection) -> Self {
+        Self { selection, ..self }
+    }
+
+    /// Return the sub-circuits included in the circuit.
+    pub fn selection(&self) -> SubCircuitSelection {
+        self.selection
+    }
+
+    /// Only assign the EVM circuit when synthesizing the phase of the `lookup_input` challenge,
+    /// the only sub-circuit using it. This requires a prover which synthesizes the circuit once
+    /// per phase, as the real prover does, and not once with all the challenges known, as the
+    /// `MockProver` does.
+    pub fn with_last_phase_evm_only(self) -> Self {
+        Self {
+            last_phase_evm_only: true,
+            ..self
+        }
+    }
+
     /// Return the number of rows required to verify a given block
     pub fn get_num_rows_required(block: &Block) -> usize {
         let num_rows_evm_circuit = EvmCircuit::<Fr>::get_num_rows_required(block);
-        assert_eq!(block.circuits_params.max_txs, MAX_TXS);
         // FIXME: need to call the SigCircuit::get_num_rows_required instead
         // let num_rows_tx_circuit =
         //     TxCircuitConfig::<F>::get_num_rows_required(block.circuits_params.max_txs);
@@ -544,6 +617,11 @@ impl<
             let mpt = MptCircuit::<Fr>::min_num_rows_block(block);
             push("mpt", mpt);
         }
+        #[cfg(not(feature = "zktrie"))]
+        {
+            let mpt = KeccakMptCircuit::<Fr>::min_num_rows_block(block);
+            push("mpt", mpt);
+        }
 
         let row_usage_details = rows
             .into_iter()
@@ -568,14 +646,7 @@ impl<
 // Eventhough the SuperCircuit is not a subcircuit we implement the SubCircuit
 // trait for it in order to get the `new_from_block` and `instance` methods that
 // allow us to generalize integration tests.
-impl<
-        const MAX_TXS: usize,
-        const MAX_CALLDATA: usize,
-        const MAX_INNER_BLOCKS: usize,
-        const MOCK_RANDOMNESS: u64,
-    > SubCircuit<Fr>
-    for SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
-{
+impl SubCircuit<Fr> for SuperCircuit<Fr> {
     type Config = SuperCircuitConfig<Fr>;
 
     fn unusable_rows() -> usize {
@@ -612,7 +683,9 @@ impl<
         let ecc_circuit = EccCircuit::new_from_block(block);
         #[cfg(feature = "zktrie")]
         let mpt_circuit = MptCircuit::new_from_block(block);
-        SuperCircuit::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS> {
+        #[cfg(not(feature = "zktrie"))]
+        let mpt_circuit = KeccakMptCircuit::new_from_block(block);
+        SuperCircuit::<Fr> {
             evm_circuit,
             state_circuit,
             tx_circuit,
@@ -627,9 +700,10 @@ impl<
             sig_circuit,
             modexp_circuit,
             ecc_circuit,
-            #[cfg(feature = "zktrie")]
             mpt_circuit,
             circuit_params: block.circuits_params,
+            selection: SubCircuitSelection::default(),
+            last_phase_evm_only: false,
         }
     }
 
@@ -657,6 +731,13 @@ impl<
         )
     }
 
+    fn precompile_row_formulas(params: &CircuitsParams) -> Vec<PrecompileRowFormula> {
+        ModExpCircuit::<Fr>::precompile_row_formulas(params)
+            .into_iter()
+            .chain(EccCircuit::<Fr, 9>::precompile_row_formulas(params))
+            .collect()
+    }
+
     /// Make the assignments to the SuperCircuit
     fn synthesize_sub(
         &self,
@@ -668,18 +749,13 @@ impl<
         config
             .evm_circuit
             .pow_of_rand_table
-            .assign(layouter, challenges, 4094 * 31)?;
+            .assign(layouter, challenges, POW_OF_RAND_ROWS)?;
         self.evm_circuit
             .synthesize_sub(&config.evm_circuit, challenges, layouter)?;
 
-        if !challenges.lookup_input().is_none() {
-            let is_mock_prover = format!("{:?}", challenges.lookup_input()) == *"Value { inner: Some(0x207a52ba34e1ed068be1e33b0bc39c8ede030835f549fe5c0dbe91dce97d17d2) }";
-            if is_mock_prover {
-                log::info!("continue assignment only for 3rd phase");
-            } else {
-                log::info!("only evm circuit needs 3rd phase assignment");
-                return Ok(());
-            }
+        if self.last_phase_evm_only && !challenges.lookup_input().is_none() {
+            log::info!("only evm circuit needs 3rd phase assignment");
+            return Ok(());
         }
         log::debug!("assigning keccak_circuit");
         self.keccak_circuit
@@ -699,12 +775,24 @@ impl<
         log::debug!("assigning sig_circuit");
         self.sig_circuit
             .synthesize_sub(&config.sig_circuit, challenges, layouter)?;
-        log::debug!("assigning ecc_circuit");
-        self.ecc_circuit
-            .synthesize_sub(&config.ecc_circuit, challenges, layouter)?;
-        log::debug!("assigning modexp_circuit");
-        self.modexp_circuit
-            .synthesize_sub(&config.modexp_circuit, challenges, layouter)?;
+        if self.selection.ecc {
+            log::debug!("assigning ecc_circuit");
+            self.ecc_circuit
+                .synthesize_sub(&config.ecc_circuit, challenges, layouter)?;
+        } else {
+            log::debug!("assigning ecc table");
+            self.ecc_circuit
+                .load_table(&config.ecc_circuit, challenges, layouter)?;
+        }
+        if self.selection.modexp {
+            log::debug!("assigning modexp_circuit");
+            self.modexp_circuit
+                .synthesize_sub(&config.modexp_circuit, challenges, layouter)?;
+        } else {
+            log::debug!("assigning modexp table");
+            self.modexp_circuit
+                .load_table(&config.modexp_circuit, challenges, layouter)?;
+        }
         log::debug!("assigning state_circuit");
         self.state_circuit
             .synthesize_sub(&config.state_circuit, challenges, layouter)?;
@@ -732,10 +820,21 @@ impl<
 
         // load both poseidon table and zktrie table
         #[cfg(feature = "zktrie")]
-        {
+        if self.selection.mpt {
             log::debug!("assigning mpt_circuit");
             self.mpt_circuit
                 .synthesize_sub(&config.mpt_circuit, challenges, layouter)?;
+        } else {
+            log::debug!("assigning mpt table");
+            self.mpt_circuit
+                .load_table(&config.mpt_circuit, challenges, layouter)?;
+        }
+        // the keccak mpt circuit has no table of its own to load when left out
+        #[cfg(not(feature = "zktrie"))]
+        if self.selection.mpt {
+            log::debug!("assigning keccak mpt_circuit");
+            self.mpt_circuit
+                .synthesize_sub(&config.mpt_circuit, challenges, layouter)?;
         }
 
         log::debug!("super circuit synthesize_sub done");
@@ -743,13 +842,7 @@ impl<
     }
 }
 
-impl<
-        const MAX_TXS: usize,
-        const MAX_CALLDATA: usize,
-        const MAX_INNER_BLOCKS: usize,
-        const MOCK_RANDOMNESS: u64,
-    > Circuit<Fr> for SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
-{
+impl Circuit<Fr> for SuperCircuit<Fr> {
     type Config = (SuperCircuitConfig<Fr>, Challenges);
     type FloorPlanner = SimpleFloorPlanner;
     #[cfg(feature = "circuit-params")]
@@ -760,22 +853,17 @@ impl<
             circuits_params: self.circuit_params,
             ..Default::default()
         };
-        Self::new_from_block(&dummy_block)
+        Self {
+            selection: self.selection,
+            last_phase_evm_only: self.last_phase_evm_only,
+            ..Self::new_from_block(&dummy_block)
+        }
     }
 
     fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
         let challenges = Challenges::construct(meta);
         (
-            SuperCircuitConfig::new(
-                meta,
-                SuperCircuitConfigArgs {
-                    max_txs: MAX_TXS,
-                    max_calldata: MAX_CALLDATA,
-                    max_inner_blocks: MAX_INNER_BLOCKS,
-                    mock_randomness: MOCK_RANDOMNESS,
-                    challenges,
-                },
-            ),
+            SuperCircuitConfig::new(meta, SuperCircuitConfigArgs { challenges }),
             challenges,
         )
     }
@@ -794,14 +882,7 @@ impl<
     }
 }
 
-impl<
-        const MAX_TXS: usize,
-        const MAX_CALLDATA: usize,
-        const MAX_INNER_BLOCKS: usize,
-        const MOCK_RANDOMNESS: u64,
-    > CircuitExt<Fr>
-    for SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
-{
+impl CircuitExt<Fr> for SuperCircuit<Fr> {
     fn num_instance(&self) -> Vec<usize> {
         self.instances().iter().map(|l| l.len()).collect_vec()
     }
@@ -811,13 +892,7 @@ impl<
     }
 }
 
-impl<
-        const MAX_TXS: usize,
-        const MAX_CALLDATA: usize,
-        const MAX_INNER_BLOCKS: usize,
-        const MOCK_RANDOMNESS: u64,
-    > SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
-{
+impl SuperCircuit<Fr> {
     /// From the witness data, generate a SuperCircuit instance with all of the
     /// sub-circuits filled with their corresponding witnesses.
     ///
@@ -828,18 +903,8 @@ impl<
         geth_data: GethData,
         circuits_params: CircuitsParams,
     ) -> Result<(u32, Self, Vec<Vec<Fr>>), bus_mapping::Error> {
-        let block_data = bus_mapping::mock::BlockData::new_from_geth_data_with_params(
-            geth_data.clone(),
-            circuits_params,
-        );
-
-        let mut builder = block_data.new_circuit_input_builder();
-        builder
-            .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
-            .expect("could not handle block tx");
-
-        let ret = Self::build_from_circuit_input_builder(builder)?;
-        Ok((ret.0, ret.1, ret.2))
+        let block = build_witness_from_eth_block(&geth_data, circuits_params)?.block;
+        Self::build_from_witness_block(block)
     }
 
     /// From CircuitInputBuilder, generate a SuperCircuit instance with all of
@@ -851,10 +916,34 @@ impl<
         builder: CircuitInputBuilder,
     ) -> Result<(u32, Self, Vec<Vec<Fr>>), bus_mapping::Error> {
         let block = block_convert(&builder.block, &builder.code_db).unwrap();
-        assert_eq!(block.circuits_params.max_txs, MAX_TXS);
-        assert_eq!(block.circuits_params.max_calldata, MAX_CALLDATA);
         Self::build_from_witness_block(block)
     }
+    /// Return the minimal degree `k` of a SuperCircuit proving `block`: the smallest `k` whose
+    /// `2^k` rows fit the rows of every sub-circuit and of the fixed lookup tables, followed by
+    /// the rows reserved for the blinding factors.
+    ///
+    /// Provers should setup their params and keys for at least this degree. A circuit of a
+    /// larger degree also proves the block, only slower.
+    pub fn min_k(block: &Block) -> u32 {
+        let (_, rows_needed) = Self::min_num_rows_block(block);
+        // the u16 range table and the table of the powers of randomness are always assigned.
+        let rows_needed = rows_needed.max(1 << 16).max(POW_OF_RAND_ROWS);
+
+        let mut cs = ConstraintSystem::default();
+        Self::configure(&mut cs);
+        let unusable_rows = Self::unusable_rows().max(cs.minimum_rows());
+
+        log2_ceil(rows_needed + unusable_rows)
+    }
+
+    /// Fingerprint of the super circuit of the params of [`dummy_witness_block`], at its
+    /// minimal degree, see [`circuit_fingerprint`]. It takes a keygen of the whole circuit.
+    pub fn fingerprint(chain_id: u64) -> Result<H256, Error> {
+        let block = dummy_witness_block(chain_id);
+        let circuit = Self::new_from_block(&block);
+        circuit_fingerprint(&circuit, Self::min_k(&block), &block.circuits_params)
+    }
+
     /// Build super circuit from witness block
     pub fn build_from_witness_block(
         block: Block,
@@ -864,12 +953,10 @@ impl<
             block.circuits_params
         );
 
-        let (_, rows_needed) = Self::min_num_rows_block(&block);
-        let k = log2_ceil(Self::unusable_rows() + rows_needed);
+        let k = Self::min_k(&block);
         log::debug!("super circuit needs k = {}", k);
 
-        let circuit =
-            SuperCircuit::<Fr, MAX_TXS, MAX_CALLDATA,MAX_INNER_BLOCKS, MOCK_RANDOMNESS>::new_from_block(&block);
+        let circuit = SuperCircuit::<Fr>::new_from_block(&block);
 
         let instance = circuit.instance();
         Ok((k, circuit, instance))
@@ -1,6 +1,9 @@
 #![allow(missing_docs)]
 use crate::exp_circuit::param::OFFSET_INCREMENT;
-use bus_mapping::circuit_input_builder::{CircuitsParams, PrecompileEcParams};
+use bus_mapping::{
+    circuit_input_builder::{CircuitsParams, PrecompileEcParams, N_PAIRING_PER_OP},
+    precompile::MODEXP_SIZE_LIMIT,
+};
 use halo2_proofs::halo2curves::bn256::Fr;
 
 use super::SuperCircuit;
@@ -21,6 +24,12 @@ pub const MAX_RWS: usize = 1_000_000;
 pub const MAX_PRECOMPILE_EC_ADD: usize = 50;
 pub const MAX_PRECOMPILE_EC_MUL: usize = 50;
 pub const MAX_PRECOMPILE_EC_PAIRING: usize = 2;
+pub const MAX_PRECOMPILE_EC_PAIRING_PAIRS: usize = N_PAIRING_PER_OP;
+/// Operand byte length limit of modexp (0x05), the one of the scroll chain.
+pub const MAX_MODEXP_BYTES: usize = MODEXP_SIZE_LIMIT;
+/// Rounds of the blake2f (0x09) compressions, unchecked: no circuit proves
+/// them yet, see `CircuitsParams::max_blake2f_rounds`.
+pub const MAX_BLAKE2F_ROUNDS: usize = 0;
 
 /// default params for super circuit
 pub fn get_super_circuit_params() -> CircuitsParams {
@@ -35,6 +44,8 @@ pub fn get_super_circuit_params() -> CircuitsParams {
         max_keccak_rows: MAX_KECCAK_ROWS,
         max_poseidon_rows: MAX_POSEIDON_ROWS,
         max_vertical_circuit_rows: MAX_VERTICAL_ROWS,
+        max_blake2f_rounds: MAX_BLAKE2F_ROUNDS,
+        max_modexp_bytes: MAX_MODEXP_BYTES,
         max_exp_steps: MAX_EXP_STEPS,
         max_mpt_rows: MAX_MPT_ROWS,
         max_rlp_rows: MAX_RLP_ROWS,
@@ -42,12 +53,13 @@ pub fn get_super_circuit_params() -> CircuitsParams {
             ec_add: MAX_PRECOMPILE_EC_ADD,
             ec_mul: MAX_PRECOMPILE_EC_MUL,
             ec_pairing: MAX_PRECOMPILE_EC_PAIRING,
+            ec_pairing_pairs: MAX_PRECOMPILE_EC_PAIRING_PAIRS,
         },
     }
 }
 
 /// The super circuit used for mainnet
-pub type ScrollSuperCircuit = SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, 0x100>;
+pub type ScrollSuperCircuit = SuperCircuit<Fr>;
 
 /// Capacity for each subcircuit
 pub fn get_sub_circuit_limit_and_confidence() -> Vec<(usize, f64)> {
@@ -63,7 +75,7 @@ pub fn get_sub_circuit_limit_and_confidence() -> Vec<(usize, f64)> {
         (MAX_VERTICAL_ROWS, default_confidence),                // tx
         (MAX_CALLDATA, default_confidence),                     // rlp
         (OFFSET_INCREMENT * MAX_EXP_STEPS, default_confidence), // exp
-        (MAX_KECCAK_ROWS, default_confidence),                  // modexp
+        (MAX_VERTICAL_ROWS, default_confidence),                // modexp
         (MAX_RWS, default_confidence),                          // pi
         (MAX_POSEIDON_ROWS, default_confidence),                // poseidon
         (MAX_VERTICAL_ROWS, default_confidence),                // sig
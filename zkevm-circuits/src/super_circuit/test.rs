@@ -1,9 +1,8 @@
 #![allow(unused_imports)]
-use crate::witness::dummy_witness_block;
+use crate::{prover_input::build_witness_from_l2_trace, witness::dummy_witness_block};
 
 pub use super::*;
 use bus_mapping::{
-    circuit_input_builder::CircuitInputBuilder,
     evm::{OpcodeId, PrecompileCallArgs},
     l2_predeployed,
     precompile::PrecompileCalls,
@@ -49,13 +48,45 @@ fn super_circuit_created_from_dummy_block() {
         },
         ..Default::default()
     };
-    let _circuit = SuperCircuit::<Fr, 128, 2_000_000, 64, 0x1000>::new_from_block(&dummy_block);
+    let _circuit = SuperCircuit::<Fr>::new_from_block(&dummy_block);
+}
+
+#[test]
+fn super_circuit_sub_circuit_selection() {
+    let selection = SubCircuitSelection::default()
+        .without_ecc()
+        .without_modexp();
+    assert!(!selection.ecc && !selection.modexp);
+
+    let circuit = ScrollSuperCircuit::new_from_block(&dummy_witness_block(MOCK_CHAIN_ID))
+        .with_selection(selection);
+    assert_eq!(circuit.selection(), selection);
+    assert_eq!(circuit.without_witnesses().selection(), selection);
+}
+
+#[test]
+fn super_circuit_min_k() {
+    let block = dummy_witness_block(MOCK_CHAIN_ID);
+    let k = ScrollSuperCircuit::min_k(&block);
+    let (_, rows_needed) = ScrollSuperCircuit::min_num_rows_block(&block);
+    assert!(k >= 17);
+    assert!(1 << k > rows_needed + ScrollSuperCircuit::unusable_rows());
+}
+
+#[ignore = "takes two keygens of the whole super circuit"]
+#[test]
+fn super_circuit_fingerprint() {
+    // chain_id is not related to vk, as in `super_circuit_vk`.
+    assert_eq!(
+        ScrollSuperCircuit::fingerprint(534351).unwrap(),
+        ScrollSuperCircuit::fingerprint(MOCK_CHAIN_ID).unwrap()
+    );
 }
 
 #[test]
 fn super_circuit_degree() {
     let mut cs = ConstraintSystem::<Fr>::default();
-    SuperCircuit::<Fr, 1, 32, 64, 0x100>::configure(&mut cs);
+    SuperCircuit::<Fr>::configure(&mut cs);
     cs = cs.chunk_lookups();
 
     log::info!("super circuit degree: {}", cs.degree());
@@ -85,14 +116,15 @@ fn super_circuit_vk() {
 }
 
 #[cfg(feature = "scroll")]
-fn test_super_circuit<
-    const MAX_TXS: usize,
-    const MAX_CALLDATA: usize,
-    const MAX_INNER_BLOCKS: usize,
-    const MOCK_RANDOMNESS: u64,
->(
+fn test_super_circuit(l2_trace: BlockTrace, circuits_params: CircuitsParams) {
+    test_super_circuit_with_selection(l2_trace, circuits_params, SubCircuitSelection::default());
+}
+
+#[cfg(feature = "scroll")]
+fn test_super_circuit_with_selection(
     l2_trace: BlockTrace,
     circuits_params: CircuitsParams,
+    selection: SubCircuitSelection,
 ) {
     set_var("COINBASE", "0x0000000000000000000000000000000000000000");
     set_var("CHAIN_ID", MOCK_CHAIN_ID.to_string());
@@ -100,31 +132,13 @@ fn test_super_circuit<
     MOCK_DIFFICULTY.to_big_endian(&mut difficulty_be_bytes);
     set_var("DIFFICULTY", hex::encode(difficulty_be_bytes));
 
-    let mut builder = CircuitInputBuilder::new_from_l2_trace(circuits_params, l2_trace, false)
-        .expect("could not handle block tx");
-
-    builder
-        .finalize_building()
-        .expect("could not finalize building block");
-
-    let mut block = block_convert(&builder.block, &builder.code_db).unwrap();
-    block.apply_mpt_updates(&builder.mpt_init_state.expect("used non-light mode"));
-
-    let active_row_num =SuperCircuit::<
-        Fr,
-        MAX_TXS,
-        MAX_CALLDATA,
-        MAX_INNER_BLOCKS,
-        MOCK_RANDOMNESS,
-    >::min_num_rows_block(&block).0;
-    let (k, circuit, instance) = SuperCircuit::<
-        Fr,
-        MAX_TXS,
-        MAX_CALLDATA,
-        MAX_INNER_BLOCKS,
-        MOCK_RANDOMNESS,
-    >::build_from_witness_block(block)
-    .unwrap();
+    let block = build_witness_from_l2_trace(l2_trace, circuits_params)
+        .expect("could not build witness block")
+        .block;
+
+    let active_row_num = SuperCircuit::<Fr>::min_num_rows_block(&block).0;
+    let (k, circuit, instance) = SuperCircuit::<Fr>::build_from_witness_block(block).unwrap();
+    let circuit = circuit.with_selection(selection);
     let prover = MockProver::run(k, &circuit, instance).unwrap();
 
     let res = prover.verify_at_rows_par(0..active_row_num, 0..active_row_num);
@@ -299,8 +313,6 @@ pub(crate) fn block_2tx() -> GethData {
     block_2tx_ctx().into()
 }
 
-const TEST_MOCK_RANDOMNESS: u64 = 0x100;
-
 // High memory usage test.  Run in serial with:
 // `cargo test [...] serial_ -- --ignored --test-threads 1`
 
@@ -327,10 +339,7 @@ fn serial_test_super_circuit_0tx_1max_tx() {
         max_rlp_rows: 500,
         ..Default::default()
     };
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, TEST_MOCK_RANDOMNESS>(
-        block,
-        circuits_params,
-    );
+    test_super_circuit(block, circuits_params);
 }
 
 #[ignore]
@@ -356,10 +365,7 @@ fn serial_test_super_circuit_1tx_1max_tx() {
         max_rlp_rows: 500,
         ..Default::default()
     };
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, TEST_MOCK_RANDOMNESS>(
-        block,
-        circuits_params,
-    );
+    test_super_circuit(block, circuits_params);
 }
 
 #[ignore]
@@ -387,10 +393,7 @@ fn serial_test_super_circuit_1tx_deploy_2max_tx() {
         max_rlp_rows: 500,
         ..Default::default()
     };
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, TEST_MOCK_RANDOMNESS>(
-        block,
-        circuits_params,
-    );
+    test_super_circuit(block, circuits_params);
 }
 
 #[ignore]
@@ -416,10 +419,7 @@ fn serial_test_super_circuit_1tx_2max_tx() {
         max_rlp_rows: 500,
         ..Default::default()
     };
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, TEST_MOCK_RANDOMNESS>(
-        block,
-        circuits_params,
-    );
+    test_super_circuit(block, circuits_params);
 }
 
 #[ignore]
@@ -447,10 +447,7 @@ fn serial_test_super_circuit_2tx_4max_tx() {
         max_rlp_rows: 800,
         ..Default::default()
     };
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, TEST_MOCK_RANDOMNESS>(
-        block,
-        circuits_params,
-    );
+    test_super_circuit(block, circuits_params);
 }
 
 #[ignore]
@@ -476,10 +473,7 @@ fn serial_test_super_circuit_2tx_2max_tx() {
         max_rlp_rows: 500,
         ..Default::default()
     };
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, TEST_MOCK_RANDOMNESS>(
-        block,
-        circuits_params,
-    );
+    test_super_circuit(block, circuits_params);
 }
 
 #[cfg(feature = "scroll")]
@@ -495,8 +489,6 @@ fn precomiple_super_circuits_params(max_txs: usize, max_calldata: usize) -> Circ
         max_mpt_rows: 2049,
         max_poseidon_rows: 8192,
         max_evm_rows: 0,
-        // modexp ref this to decide its ability, we
-        // need at least one (~25000 rows)
         max_keccak_rows: 40000,
         max_inner_blocks: 1,
         max_exp_steps: 256,
@@ -515,7 +507,23 @@ fn serial_test_super_circuit_ec_ops_txs() {
     let block = precompile_block_trace::block_ec_ops();
     let circuits_params = precomiple_super_circuits_params(MAX_TXS, MAX_CALLDATA);
 
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, 1, TEST_MOCK_RANDOMNESS>(block, circuits_params);
+    test_super_circuit(block, circuits_params);
+}
+
+#[ignore]
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_test_super_circuit_ec_ops_txs_without_ecc_and_modexp() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 0x320;
+
+    let block = precompile_block_trace::block_ec_ops();
+    let circuits_params = precomiple_super_circuits_params(MAX_TXS, MAX_CALLDATA);
+    let selection = SubCircuitSelection::default()
+        .without_ecc()
+        .without_modexp();
+
+    test_super_circuit_with_selection(block, circuits_params, selection);
 }
 
 #[ignore]
@@ -528,7 +536,7 @@ fn serial_test_super_circuit_precompile_oog() {
     let block = precompile_block_trace::block_precompile_oog();
     let circuits_params = precomiple_super_circuits_params(MAX_TXS, MAX_CALLDATA);
 
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, 1, TEST_MOCK_RANDOMNESS>(block, circuits_params);
+    test_super_circuit(block, circuits_params);
 }
 
 #[ignore]
@@ -541,7 +549,7 @@ fn serial_test_super_circuit_invalid_precompile() {
     let block = precompile_block_trace::block_invalid_precompile();
     let circuits_params = precomiple_super_circuits_params(MAX_TXS, MAX_CALLDATA);
 
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, 1, TEST_MOCK_RANDOMNESS>(block, circuits_params);
+    test_super_circuit(block, circuits_params);
 }
 
 #[ignore]
@@ -554,7 +562,7 @@ fn serial_test_super_circuit_precompile_invalid_ec_pairing_fq_overflow() {
     let block = precompile_block_trace::block_precompile_invalid_ec_pairing_fq_overflow();
     let circuits_params = precomiple_super_circuits_params(MAX_TXS, MAX_CALLDATA);
 
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, 1, TEST_MOCK_RANDOMNESS>(block, circuits_params);
+    test_super_circuit(block, circuits_params);
 }
 
 #[ignore]
@@ -567,7 +575,7 @@ fn serial_test_super_circuit_precompile_sha256() {
     let block = precompile_block_trace::block_precompile_sha256();
     let circuits_params = precomiple_super_circuits_params(MAX_TXS, MAX_CALLDATA);
 
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, 1, TEST_MOCK_RANDOMNESS>(block, circuits_params);
+    test_super_circuit(block, circuits_params);
 }
 
 #[ignore]
@@ -580,10 +588,7 @@ fn serial_test_super_circuit_eip_1559_tx() {
     let block_trace = eip1559_2930::test_block_1559_trace();
     let circuits_params = eip1559_2930::test_circuits_params(MAX_TXS, MAX_CALLDATA);
 
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, 1, TEST_MOCK_RANDOMNESS>(
-        block_trace,
-        circuits_params,
-    );
+    test_super_circuit(block_trace, circuits_params);
 }
 
 #[ignore]
@@ -597,10 +602,7 @@ fn serial_test_super_circuit_eip_2930_tx_no_accesslist() {
     let block_trace = eip1559_2930::test_block_2930_trace(false);
     let circuits_params = eip1559_2930::test_circuits_params(MAX_TXS, MAX_CALLDATA);
 
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, 1, TEST_MOCK_RANDOMNESS>(
-        block_trace,
-        circuits_params,
-    );
+    test_super_circuit(block_trace, circuits_params);
 }
 
 //TODO: disable this test for rlp issue now, will enable it after rlp issue fixed.
@@ -616,8 +618,24 @@ fn serial_test_super_circuit_eip_2930_tx_accesslist() {
     let block_trace = eip1559_2930::test_block_2930_trace(true);
     let circuits_params = eip1559_2930::test_circuits_params(MAX_TXS, MAX_CALLDATA);
 
-    test_super_circuit::<MAX_TXS, MAX_CALLDATA, 1, TEST_MOCK_RANDOMNESS>(
-        block_trace,
-        circuits_params,
-    );
+    test_super_circuit(block_trace, circuits_params);
+}
+
+#[ignore]
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_test_super_circuit_from_circuit_test_builder() {
+    crate::test_util::CircuitTestBuilder::new_from_test_ctx(block_1tx_ctx())
+        .params(CircuitsParams {
+            max_rws: 256,
+            max_copy_rows: 256,
+            max_exp_steps: 256,
+            max_bytecode: 512,
+            max_mpt_rows: 2049,
+            max_poseidon_rows: 512,
+            max_rlp_rows: 500,
+            ..Default::default()
+        })
+        .super_circuit_checks()
+        .run();
 }
@@ -10,14 +10,14 @@ use crate::{
     impl_expr,
     util::{build_tx_log_address, rlc_be_bytes, Challenges, Field},
     witness::{
-        Block, BlockContexts, Bytecode, MptUpdateRow, MptUpdates, RlpFsmWitnessGen, Rw, RwMap,
-        RwRow, Transaction,
+        chunk_table_values, Block, BlockContexts, Bytecode, MptUpdateRow, MptUpdates,
+        RlpFsmWitnessGen, Rw, RwMap, RwRow, Transaction,
     },
 };
 use bus_mapping::{
     circuit_input_builder::{
-        BigModExp, CopyDataType, CopyEvent, CopyStep, EcAddOp, EcMulOp, EcPairingOp, ExpEvent,
-        PrecompileEcParams,
+        BigModExp, ChunkContext, CopyDataType, CopyEvent, CopyStep, EcAddOp, EcMulOp, EcPairingOp,
+        ExpEvent, PrecompileEcParams,
     },
     precompile::PrecompileCalls,
 };
@@ -26,7 +26,8 @@ use eth_types::{sign_types::SignData, ToLittleEndian, ToWord, Word, H256, U256};
 use ethers_core::utils::keccak256;
 use gadgets::ToScalar;
 use gadgets::{
-    binary_number::{BinaryNumberChip, BinaryNumberConfig},
+    binary_number::{BinaryNumberChip, BinaryNumberConfig, BinaryNumberTag},
+    impl_binary_number_tag,
     util::{and, not, pow_of_two, split_u256, split_u256_limb64, Expr},
 };
 use halo2_proofs::{
@@ -47,6 +48,7 @@ use halo2_proofs::plonk::SecondPhase;
 
 use halo2_proofs::plonk::TableColumn;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::array;
 use strum_macros::{EnumCount, EnumIter};
 
@@ -199,6 +201,8 @@ impl From<TxFieldTag> for usize {
     }
 }
 
+impl_binary_number_tag!(TxFieldTag, TxFieldTag::MaxFeePerGas);
+
 /// Alias for TxFieldTag used by EVM Circuit
 pub type TxContextFieldTag = TxFieldTag;
 
@@ -453,7 +457,7 @@ impl<F: Field> LookupTable<F> for TxTable {
 }
 
 /// Tag to identify the operation type in a RwTable row
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
 pub enum RwTableTag {
     /// Start (used for padding)
     Start = 1,
@@ -503,8 +507,12 @@ impl From<RwTableTag> for usize {
     }
 }
 
+impl_binary_number_tag!(RwTableTag, RwTableTag::TxReceipt);
+
 /// Tag for an AccountField in RwTable
-#[derive(Clone, Copy, Debug, EnumIter, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Clone, Copy, Debug, EnumIter, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
 pub enum AccountFieldTag {
     /// Nonce field
     Nonce,
@@ -522,7 +530,7 @@ pub enum AccountFieldTag {
 impl_expr!(AccountFieldTag);
 
 /// Tag for a TxLogField in RwTable
-#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
 pub enum TxLogFieldTag {
     /// Address field
     Address = 1,
@@ -534,7 +542,7 @@ pub enum TxLogFieldTag {
 impl_expr!(TxLogFieldTag);
 
 /// Tag for a TxReceiptField in RwTable
-#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, EnumCount)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, EnumCount, Serialize, Deserialize)]
 pub enum TxReceiptFieldTag {
     /// Tx result
     PostStateOrStatus = 1,
@@ -546,7 +554,7 @@ pub enum TxReceiptFieldTag {
 impl_expr!(TxReceiptFieldTag);
 
 /// Tag for a CallContextField in RwTable
-#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
 pub enum CallContextFieldTag {
     /// RwCounterEndOfReversion
     RwCounterEndOfReversion = 1,
@@ -606,6 +614,14 @@ pub enum CallContextFieldTag {
 }
 impl_expr!(CallContextFieldTag);
 
+impl From<CallContextFieldTag> for usize {
+    fn from(t: CallContextFieldTag) -> Self {
+        t as usize
+    }
+}
+
+impl_binary_number_tag!(CallContextFieldTag, CallContextFieldTag::L1Fee);
+
 /// The RwTable shared between EVM Circuit and State Circuit, which contains
 /// traces of the EVM state operations.
 #[derive(Clone, Copy, Debug)]
@@ -1330,6 +1346,22 @@ pub enum BlockContextFieldTag {
     /// included in this block which also taking skipped l1 msgs into account.
     /// This could possibly be larger than NumTxs.
     NumAllTxs,
+    /// Externally supplied anchoring value of the chunk (e.g. an L1 block hash
+    /// or an EIP-4788 beacon root). Only present when the PI circuit is built
+    /// with an anchor, in which case it is assigned once with index 0.
+    Anchor,
+    /// Blob base fee (EIP-4844), derived from the excess blob gas of the
+    /// block.
+    BlobBaseFee,
+    /// Rw counter of the first rw of the chunk. The chunk fields are assigned
+    /// once with index 0, see [`ChunkContext`].
+    ChunkInitialRwc,
+    /// Rw counter of the first rw of the next chunk, 0 for the last chunk.
+    ChunkEndRwc,
+    /// Id in the block of the first tx of the chunk.
+    ChunkInitialTxId,
+    /// Id in the block of the last tx of the chunk, 0 for the last chunk.
+    ChunkEndTxId,
 }
 impl_expr!(BlockContextFieldTag);
 
@@ -1360,12 +1392,14 @@ impl BlockTable {
         }
     }
 
-    /// Assign the `BlockTable` from a `BlockContext`.
+    /// Assign the `BlockTable` from a `BlockContext`, followed by the fields of
+    /// the chunk.
     pub fn dev_load<F: Field>(
         &self,
         layouter: &mut impl Layouter<F>,
         block_ctxs: &BlockContexts,
         txs: &[Transaction],
+        chunk: &ChunkContext,
         challenges: &Challenges<Value<F>>,
     ) -> Result<(), Error> {
         layouter.assign_region(
@@ -1409,6 +1443,28 @@ impl BlockTable {
                     }
                 }
 
+                for (tag, value) in chunk_table_values(chunk) {
+                    region.assign_fixed(
+                        || format!("block table row {offset}"),
+                        self.tag,
+                        offset,
+                        || Value::known(F::from(tag as u64)),
+                    )?;
+                    region.assign_advice(
+                        || format!("block table row {offset}"),
+                        self.index,
+                        offset,
+                        || Value::known(F::zero()),
+                    )?;
+                    region.assign_advice(
+                        || format!("block table row {offset}"),
+                        self.value,
+                        offset,
+                        || Value::known(F::from(value)),
+                    )?;
+                    offset += 1;
+                }
+
                 Ok(())
             },
         )
@@ -1596,7 +1652,9 @@ impl KeccakTable {
     }
 }
 
-/// SHA256 Table, used to verify SHA256 hashing from RLC'ed input in precompile.
+/// SHA256 Table, used to verify SHA256 hashing from RLC'ed input, e.g. in the
+/// SHA-256 precompile. Gadgets look up `(input_rlc, input_len, output_rlc)` on
+/// its final rows, as they do with the [`KeccakTable`].
 #[derive(Clone, Debug)]
 pub struct SHA256Table {
     /// True when the row is enabled
@@ -1634,7 +1692,7 @@ impl<F: Field> LookupTable<F> for SHA256Table {
 }
 
 impl SHA256Table {
-    /// Construct a new KeccakTable
+    /// Construct a new SHA256Table
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
         Self {
             q_enable: meta.fixed_column(),
@@ -1715,6 +1773,21 @@ impl SHA256Table {
             },
         )
     }
+
+    /// returns matchings between the circuit columns passed as parameters and
+    /// the table columns
+    pub fn match_columns(
+        &self,
+        input_rlc: Column<Advice>,
+        input_len: Column<Advice>,
+        output_rlc: Column<Advice>,
+    ) -> Vec<(Column<Advice>, Column<Advice>)> {
+        vec![
+            (input_rlc, self.input_rlc),
+            (input_len, self.input_len),
+            (output_rlc, self.output_rlc),
+        ]
+    }
 }
 
 /// Copy Table, used to verify copies of byte chunks between Memory, Bytecode,
@@ -1759,7 +1832,7 @@ pub struct CopyTable {
 }
 
 type CopyTableRow<F> = [(Value<F>, &'static str); 8];
-type CopyCircuitRow<F> = [(Value<F>, &'static str); 10];
+type CopyCircuitRow<F> = [(Value<F>, &'static str); 11];
 
 /// CopyThread is the state used while generating rows of the copy table.
 struct CopyThread<F: Field> {
@@ -1873,6 +1946,14 @@ impl CopyTable {
             word_rlc_prev: Value::known(F::zero()),
         };
 
+        // The inner words of an event in word mode are copied in one step, at their last byte,
+        // and their data is verified by the word RLCs instead of value_acc.
+        let word_steps = if copy_event.is_word_mode() {
+            1..copy_event.full_length() / 32 - 1
+        } else {
+            0..0
+        };
+
         let is_access_list = copy_event.src_type == CopyDataType::AccessListAddresses
             || copy_event.src_type == CopyDataType::AccessListStorageKeys;
         for (step_idx, (is_read_step, mut copy_step)) in copy_steps
@@ -1933,7 +2014,9 @@ impl CopyTable {
                 value
             };
 
-            if !copy_step.mask {
+            let is_word_step = word_steps.contains(&(step_idx as u64 / 64));
+
+            if !copy_step.mask && !is_word_step {
                 thread.front_mask = false;
                 thread.value_acc = thread.value_acc * challenges.keccak_input() + value_or_pad;
             }
@@ -1962,6 +2045,14 @@ impl CopyTable {
             }
 
             let word_index = (step_idx as u64 / 2) % 32;
+            // The word step is at the last byte of the word, and counts the bytes left from the
+            // start of the word.
+            let is_word_step_row = is_word_step && word_index == 31;
+            let (bytes_left, [value, value_prev]) = if is_word_step_row {
+                (thread.bytes_left + 31, [Value::known(F::zero()); 2])
+            } else {
+                (thread.bytes_left, [value, value_prev])
+            };
 
             // For LOG, format the address including the log_id.
             let addr = if thread.tag == CopyDataType::TxLog {
@@ -1980,34 +2071,37 @@ impl CopyTable {
                 (rw_counter, rwc_inc_left)
             };
 
-            assignments.push((
-                thread.tag,
-                [
-                    (Value::known(F::from(is_first)), "is_first"),
-                    (thread.id, "id"),
-                    (Value::known(addr), "addr"),
-                    (Value::known(F::from(thread.addr_end)), "src_addr_end"),
-                    (Value::known(F::from(thread.bytes_left)), "real_bytes_left"),
-                    (rlc_acc, "rlc_acc"),
-                    (Value::known(F::from(rw_counter_in_column)), "rw_counter"),
-                    (
-                        Value::known(F::from(rwc_inc_left_in_column)),
-                        "rwc_inc_left",
-                    ),
-                ],
-                [
-                    (Value::known(F::from(is_last)), "is_last"),
-                    (value, "value"),
-                    (value_prev, "value_prev"),
-                    (thread.word_rlc, "value_word_rlc"),
-                    (thread.word_rlc_prev, "value_word_rlc_prev"),
-                    (thread.value_acc, "value_acc"),
-                    (Value::known(F::from(is_pad)), "is_pad"),
-                    (Value::known(F::from(copy_step.mask)), "mask"),
-                    (Value::known(F::from(thread.front_mask)), "front_mask"),
-                    (Value::known(F::from(word_index)), "word_index"),
-                ],
-            ));
+            if !is_word_step || is_word_step_row {
+                assignments.push((
+                    thread.tag,
+                    [
+                        (Value::known(F::from(is_first)), "is_first"),
+                        (thread.id, "id"),
+                        (Value::known(addr), "addr"),
+                        (Value::known(F::from(thread.addr_end)), "src_addr_end"),
+                        (Value::known(F::from(bytes_left)), "real_bytes_left"),
+                        (rlc_acc, "rlc_acc"),
+                        (Value::known(F::from(rw_counter_in_column)), "rw_counter"),
+                        (
+                            Value::known(F::from(rwc_inc_left_in_column)),
+                            "rwc_inc_left",
+                        ),
+                    ],
+                    [
+                        (Value::known(F::from(is_last)), "is_last"),
+                        (value, "value"),
+                        (value_prev, "value_prev"),
+                        (thread.word_rlc, "value_word_rlc"),
+                        (thread.word_rlc_prev, "value_word_rlc_prev"),
+                        (thread.value_acc, "value_acc"),
+                        (Value::known(F::from(is_pad)), "is_pad"),
+                        (Value::known(F::from(copy_step.mask)), "mask"),
+                        (Value::known(F::from(thread.front_mask)), "front_mask"),
+                        (Value::known(F::from(word_index)), "word_index"),
+                        (Value::known(F::from(is_word_step_row)), "is_word_step"),
+                    ],
+                ));
+            }
 
             // Increment the address.
             if !thread.front_mask {
@@ -2525,6 +2619,10 @@ pub struct SigTable {
     pub recovered_addr: Column<Advice>,
     /// Indicates whether or not the signature is valid or not upon signature verification.
     pub is_valid: Column<Advice>,
+    /// Whether the signature's `s` is in the lower half of the curve order (EIP-2). Only the tx
+    /// circuit looks it up, as ecrecover accepts a high `s`, so it is not part of the lookup
+    /// columns.
+    pub is_low_s: Column<Advice>,
 }
 
 impl SigTable {
@@ -2538,6 +2636,7 @@ impl SigTable {
             sig_r_rlc: meta.advice_column_in(SecondPhase),
             recovered_addr: meta.advice_column(),
             is_valid: meta.advice_column(),
+            is_low_s: meta.advice_column(),
         }
     }
 
@@ -2592,6 +2691,11 @@ impl SigTable {
                             self.is_valid,
                             Value::known(F::from(!sign_data.get_addr().is_zero())),
                         ),
+                        (
+                            "is_low_s",
+                            self.is_low_s,
+                            Value::known(F::from(sign_data.is_low_s())),
+                        ),
                     ] {
                         region.assign_advice(
                             || format!("sig table {column_name} {offset}"),
@@ -2650,8 +2754,9 @@ impl<F: Field> LookupTable<F> for SigTable {
 ///           output1_rlc = rlc(R.x), output2_rlc = rlc(R.y),
 ///
 /// 3. EcPairing:
-///    - arg*_rlc <- 0
-///    - input_rlc <- RLC over all input bytes
+///    - arg1_rlc <- number of (G1, G2) pairs
+///    - arg2_rlc, arg3_rlc, arg4_rlc <- 0
+///    - input_rlc <- RLC over all input bytes, without the padding pairs
 ///    - output1_rlc <- success {0, 1}
 #[derive(Clone, Copy, Debug)]
 pub struct EccTable {
@@ -2807,7 +2912,7 @@ impl EccTable {
             assignments.push([
                 Value::known(F::from(u64::from(PrecompileCalls::Bn128Pairing))),
                 Value::known(F::from(pairing_op.is_valid() as u64)),
-                Value::known(F::zero()),
+                Value::known(F::from(pairing_op.pairs.len() as u64)),
                 Value::known(F::zero()),
                 Value::known(F::zero()),
                 Value::known(F::zero()),
@@ -2851,187 +2956,135 @@ impl EccTable {
     }
 }
 
-/// Lookup table embedded in the modexp circuit for precompile.
+/// Lookup table embedded in the modexp circuit for precompile, with a row per MODEXP call:
+/// - input_rlc <- RLC over the input bytes of the call
+/// - input_len <- number of input bytes
+/// - is_valid <- whether the byte lengths of the operands are all within the size limit
+/// - base_len, exp_len, modulus_len <- byte lengths of the operands, 0 if not valid
+/// - exp_bit_len <- bit length of the exponent
+/// - output_rlc <- RLC over the bytes of base^exp mod modulus, of the byte length of the modulus
 #[derive(Clone, Copy, Debug)]
 pub struct ModExpTable {
-    /// Use for indicate beginning of a limbs group
-    pub q_head: Column<Fixed>,
-    /// base represented by limbs
-    pub base: Column<Advice>,
-    /// exp represented by limbs
-    pub exp: Column<Advice>,
-    /// modulus represented by limbs
-    pub modulus: Column<Advice>,
-    /// result represented by limbs
-    pub result: Column<Advice>,
+    /// Whether the row is a MODEXP call.
+    pub q_enable: Column<Fixed>,
+    /// RLC over the input bytes.
+    pub input_rlc: Column<Advice>,
+    /// Number of input bytes.
+    pub input_len: Column<Advice>,
+    /// Whether the byte lengths of the operands are within the size limit.
+    pub is_valid: Column<Advice>,
+    /// Byte length of the base.
+    pub base_len: Column<Advice>,
+    /// Byte length of the exponent.
+    pub exp_len: Column<Advice>,
+    /// Byte length of the modulus.
+    pub modulus_len: Column<Advice>,
+    /// Bit length of the exponent.
+    pub exp_bit_len: Column<Advice>,
+    /// RLC over the output bytes.
+    pub output_rlc: Column<Advice>,
 }
 
 impl ModExpTable {
     /// Construct the modexp table.
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
         let ret = Self {
-            q_head: meta.fixed_column(),
-            base: meta.advice_column(),
-            exp: meta.advice_column(),
-            modulus: meta.advice_column(),
-            result: meta.advice_column(),
+            q_enable: meta.fixed_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            input_len: meta.advice_column(),
+            is_valid: meta.advice_column(),
+            base_len: meta.advice_column(),
+            exp_len: meta.advice_column(),
+            modulus_len: meta.advice_column(),
+            exp_bit_len: meta.advice_column(),
+            output_rlc: meta.advice_column_in(SecondPhase),
         };
-        meta.enable_equality(ret.base);
-        meta.enable_equality(ret.exp);
-        meta.enable_equality(ret.modulus);
-        meta.enable_equality(ret.result);
+        for column in <ModExpTable as LookupTable<F>>::advice_columns(&ret) {
+            meta.enable_equality(column);
+        }
         ret
     }
 
-    /// helper for divide a U256 into 3 108bit limbs
-    pub fn split_u256_108bit_limbs(word: &Word) -> [u128; 3] {
-        let bit108 = 1u128 << 108;
-        let (next, limb0) = word.div_mod(U256::from(bit108));
-        let (limb2, limb1) = next.div_mod(U256::from(bit108));
-        [limb0.as_u128(), limb1.as_u128(), limb2.as_u128()]
-    }
-
-    /// helper for obtain the modulus of a U256 in Fr
-    pub fn native_u256<F: Field>(word: &Word) -> F {
-        let minus1 = -F::one();
-        let div = Word::from_little_endian(minus1.to_repr().as_ref()) + Word::from(1u64);
-        let (_, remainder) = word.div_mod(div);
-
-        let mut bytes = [0u8; 64];
-        remainder.to_little_endian(&mut bytes[..32]);
-        F::from_uniform_bytes(&bytes)
-    }
-
-    /// fill a blank 4-row region start from offset for empty lookup
-    pub fn fill_blank<F: Field>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-        layouter.assign_region(
-            || "modexp table blank region",
-            |mut region| {
-                for i in 0..4 {
-                    // fill last totally 0 row
-                    region.assign_fixed(
-                        || "modexp table blank head row",
-                        self.q_head,
-                        i,
-                        || Value::known(F::zero()),
-                    )?;
-                    for &col in [&self.base, &self.exp, &self.modulus, &self.result] {
-                        region.assign_advice(
-                            || "modexp table blank limb row",
-                            col,
-                            i,
-                            || Value::known(F::zero()),
-                        )?;
-                    }
-                }
-                Ok(())
-            },
-        )
-    }
-
     /// Get assignments to the modexp table. Meant to be used for dev purposes.
     pub fn dev_load<F: Field>(
         &self,
         layouter: &mut impl Layouter<F>,
         events: &[BigModExp],
+        challenges: &Challenges<Value<F>>,
     ) -> Result<(), Error> {
+        let keccak_rand = challenges.keccak_input();
+        let assignments = events
+            .iter()
+            .map(|event| {
+                let [base_len, exp_len, modulus_len] = event.lens();
+                [
+                    keccak_rand.map(|r| rlc::value(event.input.iter().rev(), r)),
+                    Value::known(F::from(event.input.len() as u64)),
+                    Value::known(F::from(event.valid as u64)),
+                    Value::known(F::from(base_len as u64)),
+                    Value::known(F::from(exp_len as u64)),
+                    Value::known(F::from(modulus_len as u64)),
+                    Value::known(F::from(event.exp_bit_len() as u64)),
+                    keccak_rand.map(|r| rlc::value(event.result.iter().rev(), r)),
+                ]
+            })
+            .collect_vec();
+
         layouter.assign_region(
-            || "modexp table",
+            || "modexp table dev load",
             |mut region| {
-                let mut offset = 0usize;
-
-                for event in events {
-                    for i in 0..4 {
-                        region.assign_fixed(
-                            || format!("modexp table head {}", offset + i),
-                            self.q_head,
-                            offset + i,
-                            || Value::known(if i == 0 { F::one() } else { F::zero() }),
-                        )?;
-                    }
-
-                    let base_limbs = Self::split_u256_108bit_limbs(&event.base);
-                    let exp_limbs = Self::split_u256_108bit_limbs(&event.exponent);
-                    let modulus_limbs = Self::split_u256_108bit_limbs(&event.modulus);
-                    let result_limbs = Self::split_u256_108bit_limbs(&event.result);
-
-                    for i in 0..3 {
-                        for (limbs, &col) in [base_limbs, exp_limbs, modulus_limbs, result_limbs]
-                            .iter()
-                            .zip([&self.base, &self.exp, &self.modulus, &self.result])
-                        {
-                            region.assign_advice(
-                                || format!("modexp table limb row {}", offset + i),
-                                col,
-                                offset + i,
-                                || Value::known(F::from_u128(limbs[i])),
-                            )?;
-                        }
-                    }
-
-                    // native is not used by lookup (and in fact it can be omitted in dev)
-                    for (word, &col) in
-                        [&event.base, &event.exponent, &event.modulus, &event.result]
-                            .iter()
-                            .zip([&self.base, &self.exp, &self.modulus, &self.result])
+                for (i, row) in assignments.iter().enumerate() {
+                    region.assign_fixed(
+                        || format!("modexp table row = {i}, q_enable"),
+                        self.q_enable,
+                        i,
+                        || Value::known(F::one()),
+                    )?;
+                    for (&column, &value) in <ModExpTable as LookupTable<F>>::advice_columns(self)
+                        .iter()
+                        .zip_eq(row.iter())
                     {
                         region.assign_advice(
-                            || format!("modexp table native row {}", offset + 3),
-                            col,
-                            offset + 3,
-                            || Value::<F>::known(Self::native_u256(word)),
+                            || format!("modexp table row = {i}, column = {column:?}"),
+                            column,
+                            i,
+                            || value,
                         )?;
                     }
-
-                    offset += 4;
                 }
 
                 Ok(())
             },
-        )?;
-        self.fill_blank(layouter)
+        )
     }
 }
 
 impl<F: Field> LookupTable<F> for ModExpTable {
     fn columns(&self) -> Vec<Column<Any>> {
         vec![
-            self.q_head.into(),
-            self.base.into(),
-            self.exp.into(),
-            self.modulus.into(),
-            self.result.into(),
+            self.q_enable.into(),
+            self.input_rlc.into(),
+            self.input_len.into(),
+            self.is_valid.into(),
+            self.base_len.into(),
+            self.exp_len.into(),
+            self.modulus_len.into(),
+            self.exp_bit_len.into(),
+            self.output_rlc.into(),
         ]
     }
 
     fn annotations(&self) -> Vec<String> {
         vec![
-            String::from("is_head"),
-            String::from("base"),
-            String::from("exp"),
-            String::from("modulus"),
-            String::from("result"),
-        ]
-    }
-
-    fn table_exprs(&self, meta: &mut VirtualCells<F>) -> Vec<Expression<F>> {
-        vec![
-            // ignore the is_valid field as the EVM circuit's use-case (Ecrecover precompile) does
-            // not care whether the signature is valid or not. It only cares about the recovered
-            // address.
-            meta.query_fixed(self.q_head, Rotation::cur()),
-            meta.query_advice(self.base, Rotation::cur()),
-            meta.query_advice(self.exp, Rotation::cur()),
-            meta.query_advice(self.modulus, Rotation::cur()),
-            meta.query_advice(self.result, Rotation::cur()),
-            meta.query_advice(self.base, Rotation::next()),
-            meta.query_advice(self.exp, Rotation::next()),
-            meta.query_advice(self.modulus, Rotation::next()),
-            meta.query_advice(self.result, Rotation::next()),
-            meta.query_advice(self.base, Rotation(2)),
-            meta.query_advice(self.exp, Rotation(2)),
-            meta.query_advice(self.modulus, Rotation(2)),
-            meta.query_advice(self.result, Rotation(2)),
+            String::from("q_enable"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+            String::from("is_valid"),
+            String::from("base_len"),
+            String::from("exp_len"),
+            String::from("modulus_len"),
+            String::from("exp_bit_len"),
+            String::from("output_rlc"),
         ]
     }
 }
@@ -3,22 +3,34 @@
 use crate::{
     copy_circuit::CopyCircuit,
     evm_circuit::{cached::EvmCircuitCached, EvmCircuit},
+    prover_input::build_witness_from_eth_block,
     state_circuit::StateCircuit,
+    super_circuit::SuperCircuit,
     util::{log2_ceil, SubCircuit},
     witness::{Block, Rw},
 };
-use bus_mapping::{circuit_input_builder::CircuitsParams, mock::BlockData};
+use bus_mapping::{
+    circuit_input_builder::CircuitsParams,
+    mock::{add_next_block, BlockData},
+};
 use eth_types::geth_types::GethData;
 
 use halo2_proofs::{
     circuit::Value,
-    dev::{unwrap_value, MockProver},
+    dev::{unwrap_value, MockProver, VerifyFailure},
     halo2curves::bn256::Fr,
 };
-use mock::TestContext;
+use mock::{MultiBlockTestContext, TestContext};
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
 
 #[cfg(feature = "scroll")]
-use bus_mapping::circuit_input_builder::CircuitInputBuilder;
+use crate::prover_input::build_witness_from_l2_trace;
+
+mod explain;
+pub use explain::{explain_failures, FailedStep, FailureExplanation, MAX_EXPLAINED_FAILURES};
 
 #[cfg(test)]
 #[ctor::ctor]
@@ -29,6 +41,60 @@ fn init_env_logger() {
 
 pub(crate) type FnBlockChecker = Option<Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>>;
 
+/// Maximum number of txs of the SuperCircuit run by
+/// [`CircuitTestBuilder::super_circuit_checks`].
+pub const SUPER_CIRCUIT_TEST_MAX_TXS: usize = 4;
+/// Maximum calldata bytes of the SuperCircuit run by
+/// [`CircuitTestBuilder::super_circuit_checks`].
+pub const SUPER_CIRCUIT_TEST_MAX_CALLDATA: usize = 4096;
+/// Maximum inner blocks of the SuperCircuit run by
+/// [`CircuitTestBuilder::super_circuit_checks`].
+pub const SUPER_CIRCUIT_TEST_MAX_INNER_BLOCKS: usize = 1;
+
+/// SuperCircuit run by [`CircuitTestBuilder::super_circuit_checks`].
+pub type TestSuperCircuit = SuperCircuit<Fr>;
+
+fn default_checks() -> FnBlockChecker {
+    Some(Box::new(|prover, gate_rows, lookup_rows| {
+        assert_eq!(
+            prover.verify_at_rows_par(gate_rows.iter().cloned(), lookup_rows.iter().cloned()),
+            Ok(())
+        );
+    }))
+}
+
+/// Source of the traced block a [`CircuitTestBuilder`] builds its witness from.
+///
+/// Implemented for [`TestContext`] of any size and for [`GethData`], so that
+/// callers which only know the number of accounts and transactions at runtime
+/// (like testool) can drive the builder too.
+pub trait BlockSource {
+    /// Number of transactions in the traced block.
+    fn num_txs(&self) -> usize;
+    /// Consume the source, returning the traced block.
+    fn into_geth_data(self: Box<Self>) -> GethData;
+}
+
+impl<const NACC: usize, const NTX: usize> BlockSource for TestContext<NACC, NTX> {
+    fn num_txs(&self) -> usize {
+        NTX
+    }
+
+    fn into_geth_data(self: Box<Self>) -> GethData {
+        (*self).into()
+    }
+}
+
+impl BlockSource for GethData {
+    fn num_txs(&self) -> usize {
+        self.eth_block.transactions.len()
+    }
+
+    fn into_geth_data(self: Box<Self>) -> GethData {
+        *self
+    }
+}
+
 #[allow(clippy::type_complexity)]
 /// Struct used to easily generate tests for EVM &| State circuits being able to
 /// customize all of the steps involved in the testing itself.
@@ -80,61 +146,94 @@ pub(crate) type FnBlockChecker = Option<Box<dyn Fn(MockProver<Fr>, &Vec<usize>,
 ///     .state_checks(Some(Box::new(|prover, evm_rows, lookup_rows| assert!(prover.verify_at_rows_par(evm_rows.iter().cloned(), lookup_rows.iter().cloned()).is_err()))))
 ///     .run();
 /// ```
-pub struct CircuitTestBuilder<const NACC: usize, const NTX: usize> {
-    test_ctx: Option<TestContext<NACC, NTX>>,
+pub struct CircuitTestBuilder {
+    block_source: Option<Box<dyn BlockSource>>,
+    multi_block_ctx: Option<MultiBlockTestContext>,
     circuits_params: Option<CircuitsParams>,
     block: Option<Block>,
     evm_checks: FnBlockChecker,
     state_checks: FnBlockChecker,
     copy_checks: FnBlockChecker,
+    super_checks: FnBlockChecker,
     block_modifiers: Vec<Box<dyn Fn(&mut Block)>>,
 }
 
-impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
+impl CircuitTestBuilder {
     /// Generates an empty/set to default `CircuitTestBuilder`.
     fn empty() -> Self {
         CircuitTestBuilder {
-            test_ctx: None,
+            block_source: None,
+            multi_block_ctx: None,
             circuits_params: None,
             block: None,
-            evm_checks: Some(Box::new(|prover, gate_rows, lookup_rows| {
-                assert_eq!(prover.verify_at_rows_par(
-                    gate_rows.iter().cloned(),
-                    lookup_rows.iter().cloned(),
-                ), Ok(()));
-            })),
-            state_checks: Some(Box::new(|prover, gate_rows, lookup_rows| {
-                assert_eq!(prover.verify_at_rows_par(
-                    gate_rows.iter().cloned(),
-                    lookup_rows.iter().cloned(),
-                ), Ok(()));
-            })),
-            copy_checks: Some(Box::new(|prover, gate_rows, lookup_rows| {
-                assert_eq!(prover.verify_at_rows_par(
-                    gate_rows.iter().cloned(),
-                    lookup_rows.iter().cloned(),
-                ), Ok(()));
-            })),
+            evm_checks: default_checks(),
+            state_checks: default_checks(),
+            copy_checks: default_checks(),
+            super_checks: None,
             block_modifiers: vec![],
         }
     }
 
     /// Generates a CTBC from a [`TestContext`] passed with all the other fields
     /// set to [`Default`].
-    pub fn new_from_test_ctx(ctx: TestContext<NACC, NTX>) -> Self {
+    pub fn new_from_test_ctx<const NACC: usize, const NTX: usize>(
+        ctx: TestContext<NACC, NTX>,
+    ) -> Self {
         Self::empty().test_ctx(ctx)
     }
 
+    /// Generates a CTBC from any [`BlockSource`] passed with all the other
+    /// fields set to [`Default`].
+    pub fn new_from_block_source(source: Box<dyn BlockSource>) -> Self {
+        Self::empty().block_source(source)
+    }
+
+    /// Generates a CTBC from a [`MultiBlockTestContext`] passed with all the
+    /// other fields set to [`Default`].
+    pub fn new_from_multi_block_test_ctx(ctx: MultiBlockTestContext) -> Self {
+        Self::empty().multi_block_test_ctx(ctx)
+    }
+
     /// Generates a CTBC from a [`Block`] passed with all the other fields
     /// set to [`Default`].
     pub fn new_from_block(block: Block) -> Self {
         Self::empty().block(block)
     }
 
+    /// Generates a CTBC from a [`Block`] written with [`Block::to_json_file`],
+    /// e.g. by the sequencer, with all the other fields set to [`Default`].
+    pub fn new_from_block_file<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        let block = Block::from_json_file(path).unwrap_or_else(|err| {
+            panic!(
+                "could not load witness block from {}: {err}",
+                path.display()
+            )
+        });
+        Self::new_from_block(block)
+    }
+
     /// Allows to produce a [`TestContext`] which will serve as the generator of
     /// the Block.
-    pub fn test_ctx(mut self, ctx: TestContext<NACC, NTX>) -> Self {
-        self.test_ctx = Some(ctx);
+    pub fn test_ctx<const NACC: usize, const NTX: usize>(
+        self,
+        ctx: TestContext<NACC, NTX>,
+    ) -> Self {
+        self.block_source(Box::new(ctx))
+    }
+
+    /// Allows to produce the Block from a runtime-sized [`BlockSource`], e.g.
+    /// a [`GethData`] with an arbitrary number of transactions.
+    pub fn block_source(mut self, source: Box<dyn BlockSource>) -> Self {
+        self.block_source = Some(source);
+        self
+    }
+
+    /// Allows to produce the Block from consecutive blocks, which are traced
+    /// one after the other on top of the state left by the previous ones.
+    /// `max_txs` and `max_inner_blocks` are set to fit all of them.
+    pub fn multi_block_test_ctx(mut self, ctx: MultiBlockTestContext) -> Self {
+        self.multi_block_ctx = Some(ctx);
         self
     }
 
@@ -188,6 +287,29 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         self
     }
 
+    /// Additionally runs the [`TestSuperCircuit`] on the block, with the degree
+    /// derived from the rows required by all of its sub-circuits, so that the
+    /// lookups between sub-circuits get exercised too.
+    ///
+    /// The block is built with [`SUPER_CIRCUIT_TEST_MAX_TXS`],
+    /// [`SUPER_CIRCUIT_TEST_MAX_CALLDATA`] and
+    /// [`SUPER_CIRCUIT_TEST_MAX_INNER_BLOCKS`] as its capacities.
+    pub fn super_circuit_checks(mut self) -> Self {
+        self.super_checks = default_checks();
+        self
+    }
+
+    #[allow(clippy::type_complexity)]
+    /// Allows to provide checks different than the default ones for the
+    /// SuperCircuit verification. Passing `None` disables the SuperCircuit run.
+    pub fn super_checks(
+        mut self,
+        super_checks: Option<Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>>,
+    ) -> Self {
+        self.super_checks = super_checks;
+        self
+    }
+
     #[allow(clippy::type_complexity)]
     /// Allows to provide modifier functions for the [`Block`] that will be
     /// generated within this builder.
@@ -200,113 +322,422 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
     }
 }
 
-impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
+/// Sub-circuit run by a [`CircuitTestBuilder`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestedCircuit {
+    /// The EVM circuit.
+    Evm,
+    /// The State circuit.
+    State,
+    /// The Copy circuit.
+    Copy,
+    /// The [`TestSuperCircuit`].
+    Super,
+}
+
+/// Error returned by [`CircuitTestBuilder::try_run`] when a circuit could not
+/// even be mock-proved. Unsatisfied constraints are not errors, they are
+/// reported in the [`CircuitTestReport`].
+#[derive(Debug)]
+pub enum CircuitTestError {
+    /// Neither a [`Block`] nor a [`BlockSource`] was passed to the builder.
+    NoBlockSource,
+    /// The witness block could not be generated from the traced block.
+    WitnessGeneration(bus_mapping::Error),
+    /// The block does not fit the capacities the circuit is run with.
+    InvalidParams(String),
+    /// The circuit needs a degree above the one supported in tests.
+    DegreeTooLarge {
+        /// Circuit needing the degree.
+        circuit: TestedCircuit,
+        /// Required degree.
+        k: u32,
+    },
+    /// The mock prover failed to synthesize the circuit.
+    Synthesis {
+        /// Circuit that failed to synthesize.
+        circuit: TestedCircuit,
+        /// Error returned by the mock prover.
+        error: halo2_proofs::plonk::Error,
+    },
+}
+
+impl std::fmt::Display for CircuitTestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoBlockSource => write!(
+                f,
+                "No attribute to build a block was passed to the CircuitTestBuilder"
+            ),
+            Self::WitnessGeneration(err) => write!(f, "could not build witness block: {err:?}"),
+            Self::InvalidParams(msg) => write!(f, "invalid circuit params: {msg}"),
+            Self::DegreeTooLarge { circuit, k } => {
+                write!(
+                    f,
+                    "{circuit:?} circuit needs degree {k} > {MAX_TEST_DEGREE}"
+                )
+            }
+            Self::Synthesis { circuit, error } => {
+                write!(f, "{circuit:?} circuit failed to synthesize: {error:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CircuitTestError {}
+
+/// Verification outcome of a single sub-circuit.
+#[derive(Debug)]
+pub struct CircuitTestResult {
+    /// Circuit that was verified.
+    pub circuit: TestedCircuit,
+    /// Degree the circuit was mock-proved with.
+    pub k: u32,
+    /// Number of rows whose gates and lookups were verified.
+    pub checked_rows: usize,
+    /// Unsatisfied constraints, each including the region/row it failed at.
+    pub failures: Vec<VerifyFailure>,
+    /// Explanations of the first failures, with the step they failed at.
+    pub explanations: Vec<FailureExplanation>,
+    /// Time spent synthesizing and verifying the circuit.
+    pub elapsed: Duration,
+}
+
+impl CircuitTestResult {
+    /// Whether all the checked rows are satisfied.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Per-circuit outcomes of [`CircuitTestBuilder::try_run`], in the order the
+/// circuits were run.
+#[derive(Debug, Default)]
+pub struct CircuitTestReport {
+    /// Outcome of each circuit that was run.
+    pub results: Vec<CircuitTestResult>,
+}
+
+impl CircuitTestReport {
+    /// Whether every circuit that was run is satisfied.
+    pub fn is_ok(&self) -> bool {
+        self.results.iter().all(CircuitTestResult::is_ok)
+    }
+
+    /// Results of the circuits that are not satisfied.
+    pub fn failed(&self) -> impl Iterator<Item = &CircuitTestResult> {
+        self.results.iter().filter(|result| !result.is_ok())
+    }
+}
+
+/// Highest degree allowed for the circuits run by [`CircuitTestBuilder`].
+const MAX_TEST_DEGREE: u32 = 20;
+const NUM_BLINDING_ROWS: usize = 64;
+
+/// A sub-circuit run through the [`MockProver`], with the rows to verify.
+struct MockedCircuit {
+    k: u32,
+    prover: MockProver<Fr>,
+    gate_rows: Vec<usize>,
+    lookup_rows: Vec<usize>,
+}
+
+impl MockedCircuit {
+    fn new(circuit: TestedCircuit, block: &Block) -> Result<MockedCircuit, CircuitTestError> {
+        let check_degree = |k: u32| {
+            if k > MAX_TEST_DEGREE {
+                Err(CircuitTestError::DegreeTooLarge { circuit, k })
+            } else {
+                Ok(k)
+            }
+        };
+        let synthesis_err = |error| CircuitTestError::Synthesis { circuit, error };
+
+        match circuit {
+            TestedCircuit::Evm => {
+                let k = check_degree(block.get_evm_test_circuit_degree())?;
+                let (gate_rows, lookup_rows) = EvmCircuit::<Fr>::get_active_rows(block);
+
+                let circuit = EvmCircuitCached::get_test_cicuit_from_block(block.clone());
+                let prover = MockProver::<Fr>::run(k, &circuit, vec![]).map_err(synthesis_err)?;
+
+                Ok(MockedCircuit {
+                    k,
+                    prover,
+                    gate_rows,
+                    lookup_rows,
+                })
+            }
+            TestedCircuit::State => {
+                let (_, rows_needed) = StateCircuit::<Fr>::min_num_rows_block(block);
+                let k = check_degree(log2_ceil(rows_needed + NUM_BLINDING_ROWS))?;
+                let state_circuit = StateCircuit::<Fr>::new(block.rws.clone(), rows_needed);
+                let instance = state_circuit.instance();
+                let prover =
+                    MockProver::<Fr>::run(k, &state_circuit, instance).map_err(synthesis_err)?;
+                // Skip verification of Start rows to accelerate testing
+                let non_start_rows_len = state_circuit
+                    .rows
+                    .iter()
+                    .filter(|rw| !matches!(rw, Rw::Start { .. }))
+                    .count();
+                let rows: Vec<usize> = (rows_needed - non_start_rows_len..rows_needed).collect();
+
+                Ok(MockedCircuit {
+                    k,
+                    prover,
+                    gate_rows: rows.clone(),
+                    lookup_rows: rows,
+                })
+            }
+            TestedCircuit::Copy => {
+                let (active_rows, max_rows) = CopyCircuit::<Fr>::min_num_rows_block(block);
+                let k1 = block.get_evm_test_circuit_degree();
+                let k2 = log2_ceil(max_rows + NUM_BLINDING_ROWS);
+                let k = k1.max(k2);
+                let copy_circuit = CopyCircuit::<Fr>::new_from_block(block);
+                let instance = copy_circuit.instance();
+                let prover =
+                    MockProver::<Fr>::run(k, &copy_circuit, instance).map_err(synthesis_err)?;
+                let rows: Vec<usize> = (0..active_rows).collect();
+
+                Ok(MockedCircuit {
+                    k,
+                    prover,
+                    gate_rows: rows.clone(),
+                    lookup_rows: rows,
+                })
+            }
+            TestedCircuit::Super => {
+                let active_rows = TestSuperCircuit::min_num_rows_block(block).0;
+                let (k, circuit, instance) =
+                    TestSuperCircuit::build_from_witness_block(block.clone())
+                        .map_err(CircuitTestError::WitnessGeneration)?;
+                let prover = MockProver::<Fr>::run(k, &circuit, instance).map_err(synthesis_err)?;
+                let rows: Vec<usize> = (0..active_rows).collect();
+
+                Ok(MockedCircuit {
+                    k,
+                    prover,
+                    gate_rows: rows.clone(),
+                    lookup_rows: rows,
+                })
+            }
+        }
+    }
+}
+
+/// Build the witness of the blocks of `ctx`, tracing each one on top of the
+/// state left by the previous ones in the circuit input builder.
+fn multi_block_witness(
+    ctx: &MultiBlockTestContext,
+    params: CircuitsParams,
+) -> Result<Block, CircuitTestError> {
+    if cfg!(feature = "scroll") {
+        return Err(CircuitTestError::InvalidParams(
+            "multi block contexts are not supported with scroll l2 traces".to_string(),
+        ));
+    }
+    let build = || -> Result<Block, bus_mapping::Error> {
+        let geth_data = ctx.trace_block(0, ctx.accounts.clone())?;
+        let mut builder = BlockData::new_from_geth_data_with_params(geth_data.clone(), params)
+            .new_circuit_input_builder();
+        builder.handle_block_inner(&geth_data.eth_block, &geth_data.geth_traces)?;
+        for idx in 1..ctx.eth_blocks.len() {
+            let geth_data = ctx.trace_block(idx, builder.warm_state().geth_accounts())?;
+            add_next_block(&mut builder, &geth_data)?;
+            builder.handle_block_inner(&geth_data.eth_block, &geth_data.geth_traces)?;
+        }
+        builder.finalize_building()?;
+        crate::witness::block_convert(&builder.block, &builder.code_db)
+    };
+    build().map_err(CircuitTestError::WitnessGeneration)
+}
+
+impl CircuitTestBuilder {
     /// Return the witness block
-    pub fn build_witness_block(self) -> (Block, FnBlockChecker, FnBlockChecker, FnBlockChecker) {
+    #[allow(clippy::type_complexity)]
+    pub fn build_witness_block(
+        self,
+    ) -> (
+        Block,
+        FnBlockChecker,
+        FnBlockChecker,
+        FnBlockChecker,
+        FnBlockChecker,
+    ) {
+        self.try_build_witness_block()
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Return the witness block, or the reason it could not be built.
+    #[allow(clippy::type_complexity)]
+    pub fn try_build_witness_block(
+        self,
+    ) -> Result<
+        (
+            Block,
+            FnBlockChecker,
+            FnBlockChecker,
+            FnBlockChecker,
+            FnBlockChecker,
+        ),
+        CircuitTestError,
+    > {
         let mut params = if let Some(block) = self.block.as_ref() {
             block.circuits_params
         } else {
             self.circuits_params.unwrap_or_default()
         };
-        params.max_txs = NTX;
+        if let Some(source) = self.block_source.as_ref() {
+            params.max_txs = source.num_txs();
+        }
+        if let Some(ctx) = self.multi_block_ctx.as_ref() {
+            params.max_txs = ctx.num_txs();
+            params.max_inner_blocks = ctx.eth_blocks.len();
+        }
+        if self.super_checks.is_some() && self.block.is_none() {
+            if params.max_txs > SUPER_CIRCUIT_TEST_MAX_TXS {
+                return Err(CircuitTestError::InvalidParams(format!(
+                    "super circuit checks support at most {SUPER_CIRCUIT_TEST_MAX_TXS} txs"
+                )));
+            }
+            if self.multi_block_ctx.is_some()
+                && params.max_inner_blocks > SUPER_CIRCUIT_TEST_MAX_INNER_BLOCKS
+            {
+                return Err(CircuitTestError::InvalidParams(format!(
+                    "super circuit checks support at most {SUPER_CIRCUIT_TEST_MAX_INNER_BLOCKS} blocks"
+                )));
+            }
+            params.max_txs = SUPER_CIRCUIT_TEST_MAX_TXS;
+            params.max_calldata = SUPER_CIRCUIT_TEST_MAX_CALLDATA;
+            params.max_inner_blocks = SUPER_CIRCUIT_TEST_MAX_INNER_BLOCKS;
+        }
         log::debug!("params in CircuitTestBuilder: {:?}", params);
 
         let block: Block = if self.block.is_some() {
             self.block.unwrap()
-        } else if self.test_ctx.is_some() {
+        } else if let Some(ctx) = self.multi_block_ctx {
+            let mut block = multi_block_witness(&ctx, params)?;
+            for modifier_fn in self.block_modifiers {
+                modifier_fn.as_ref()(&mut block);
+            }
+            block
+        } else if let Some(source) = self.block_source {
+            let geth_data = source.into_geth_data();
             // use scroll l2 trace
             let full_witness_block = cfg!(feature = "scroll");
-            let mut block = if full_witness_block {
+            let prover_input = if full_witness_block {
                 #[cfg(feature = "scroll")]
                 {
-                    let mut builder = CircuitInputBuilder::new_from_l2_trace(
-                        params,
-                        self.test_ctx.unwrap().l2_trace().clone(),
-                        false,
-                    )
-                    .expect("could not handle block tx");
-                    builder
-                        .finalize_building()
-                        .expect("could not finalize building block");
-                    let mut block =
-                        crate::witness::block_convert(&builder.block, &builder.code_db).unwrap();
-                    block.apply_mpt_updates(&builder.mpt_init_state.unwrap());
-                    block
+                    build_witness_from_l2_trace(geth_data.block_trace, params)
                 }
 
                 #[cfg(not(feature = "scroll"))]
                 panic!("full witness block only viable for scroll mode");
             } else {
-                let block: GethData = self.test_ctx.unwrap().into();
-                let mut builder = BlockData::new_from_geth_data_with_params(block.clone(), params)
-                    .new_circuit_input_builder();
-                builder
-                    .handle_block(&block.eth_block, &block.geth_traces)
-                    .unwrap();
-                // Build a witness block from trace result.
-                crate::witness::block_convert(&builder.block, &builder.code_db).unwrap()
+                build_witness_from_eth_block(&geth_data, params)
             };
+            let mut block = prover_input
+                .map_err(CircuitTestError::WitnessGeneration)?
+                .block;
 
             for modifier_fn in self.block_modifiers {
                 modifier_fn.as_ref()(&mut block);
             }
             block
         } else {
-            panic!("No attribute to build a block was passed to the CircuitTestBuilder")
+            return Err(CircuitTestError::NoBlockSource);
         };
-        (block, self.evm_checks, self.state_checks, self.copy_checks)
+        Ok((
+            block,
+            self.evm_checks,
+            self.state_checks,
+            self.copy_checks,
+            self.super_checks,
+        ))
     }
+
     /// Triggers the `CircuitTestBuilder` to convert the [`TestContext`] if any,
     /// into a [`Block`] and apply the default or provided block_modifiers or
     /// circuit checks to the provers generated for the State and EVM circuits.
     pub fn run(self) {
-        let (block, evm_checks, state_checks, copy_checks) = self.build_witness_block();
-
-        const NUM_BLINDING_ROWS: usize = 64;
-        // Run evm circuit test
-        if let Some(evm_checks) = &evm_checks {
-            let k = block.get_evm_test_circuit_degree();
-            assert!(k <= 20);
-            let (active_gate_rows, active_lookup_rows) = EvmCircuit::<Fr>::get_active_rows(&block);
-
-            let circuit = EvmCircuitCached::get_test_cicuit_from_block(block.clone());
-            let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+        let (block, evm_checks, state_checks, copy_checks, super_checks) =
+            self.build_witness_block();
 
-            evm_checks(prover, &active_gate_rows, &active_lookup_rows)
+        for (circuit, checks) in [
+            (TestedCircuit::Evm, &evm_checks),
+            (TestedCircuit::State, &state_checks),
+            (TestedCircuit::Copy, &copy_checks),
+            (TestedCircuit::Super, &super_checks),
+        ] {
+            if let Some(checks) = checks {
+                let mocked =
+                    MockedCircuit::new(circuit, &block).unwrap_or_else(|err| panic!("{err}"));
+                checks(mocked.prover, &mocked.gate_rows, &mocked.lookup_rows);
+            }
         }
+    }
 
-        // Run state circuit test
-        if let Some(state_checks) = &state_checks {
-            let (_, rows_needed) = StateCircuit::<Fr>::min_num_rows_block(&block);
-            let k: u32 = log2_ceil(rows_needed + NUM_BLINDING_ROWS);
-            assert!(k <= 20);
-            let state_circuit = StateCircuit::<Fr>::new(block.rws.clone(), rows_needed);
-            let instance = state_circuit.instance();
-            let prover = MockProver::<Fr>::run(k, &state_circuit, instance).unwrap();
-            // Skip verification of Start rows to accelerate testing
-            let non_start_rows_len = state_circuit
-                .rows
-                .iter()
-                .filter(|rw| !matches!(rw, Rw::Start { .. }))
-                .count();
-            let rows = (rows_needed - non_start_rows_len..rows_needed).collect();
+    /// Same as [`CircuitTestBuilder::run`], but instead of panicking returns
+    /// the verification outcome of each circuit, so that harnesses can report
+    /// failures without `catch_unwind`.
+    ///
+    /// The check functions passed to the builder only select which circuits
+    /// are run: each enabled circuit is verified at its active rows and its
+    /// unsatisfied constraints are collected in the report.
+    pub fn try_run(self) -> Result<CircuitTestReport, CircuitTestError> {
+        let (block, evm_checks, state_checks, copy_checks, super_checks) =
+            self.try_build_witness_block()?;
 
-            state_checks(prover, &rows, &rows);
-        }
+        let mut report = CircuitTestReport::default();
+        for (circuit, enabled) in [
+            (TestedCircuit::Evm, evm_checks.is_some()),
+            (TestedCircuit::State, state_checks.is_some()),
+            (TestedCircuit::Copy, copy_checks.is_some()),
+            (TestedCircuit::Super, super_checks.is_some()),
+        ] {
+            if !enabled {
+                continue;
+            }
+            let start = Instant::now();
+            let mocked = MockedCircuit::new(circuit, &block)?;
+            let failures = mocked
+                .prover
+                .verify_at_rows_par(
+                    mocked.gate_rows.iter().cloned(),
+                    mocked.lookup_rows.iter().cloned(),
+                )
+                .err()
+                .unwrap_or_default();
+            let elapsed = start.elapsed();
+            log::debug!(
+                "{circuit:?} circuit verified in {elapsed:?}, {} failures",
+                failures.len()
+            );
+            let explanations = explain_failures(
+                circuit,
+                &block,
+                &mocked.prover,
+                &mocked.gate_rows,
+                &mocked.lookup_rows,
+                &failures,
+            );
+            for explanation in &explanations {
+                log::error!("{circuit:?} circuit failure: {explanation}");
+            }
 
-        // Run copy circuit test
-        if let Some(copy_checks) = &copy_checks {
-            let (active_rows, max_rows) = CopyCircuit::<Fr>::min_num_rows_block(&block);
-            let k1 = block.get_evm_test_circuit_degree();
-            let k2 = log2_ceil(max_rows + NUM_BLINDING_ROWS);
-            let k = k1.max(k2);
-            let copy_circuit = CopyCircuit::<Fr>::new_from_block(&block);
-            let instance = copy_circuit.instance();
-            let prover = MockProver::<Fr>::run(k, &copy_circuit, instance).unwrap();
-            let rows = (0..active_rows).collect();
-
-            copy_checks(prover, &rows, &rows);
+            report.results.push(CircuitTestResult {
+                circuit,
+                k: mocked.k,
+                checked_rows: mocked.gate_rows.len(),
+                failures,
+                explanations,
+                elapsed,
+            });
         }
+
+        Ok(report)
     }
 }
 
@@ -318,3 +749,277 @@ pub fn escape_value<T>(v: Value<T>) -> Option<T> {
         Some(unwrap_value(v))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use eth_types::bytecode;
+    use mock::{random_block, RandomBlockProfile};
+
+    #[test]
+    fn try_run_reports_each_circuit() {
+        let code = bytecode! {
+            PUSH1(0x20)
+            PUSH1(0x00)
+            MSTORE
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+
+        let report = CircuitTestBuilder::new_from_test_ctx(ctx)
+            .try_run()
+            .unwrap();
+
+        assert!(report.is_ok());
+        assert_eq!(
+            report
+                .results
+                .iter()
+                .map(|result| result.circuit)
+                .collect::<Vec<_>>(),
+            vec![
+                TestedCircuit::Evm,
+                TestedCircuit::State,
+                TestedCircuit::Copy
+            ]
+        );
+    }
+
+    #[test]
+    fn try_run_explains_failures() {
+        let code = bytecode! {
+            PUSH1(0x20)
+            PUSH1(0x00)
+            MSTORE
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+
+        let report = CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| block.txs[0].steps[1].gas_left -= 1))
+            .state_checks(None)
+            .copy_checks(None)
+            .try_run()
+            .unwrap();
+
+        let result = report.failed().next().unwrap();
+        assert_eq!(result.circuit, TestedCircuit::Evm);
+        assert!(!result.explanations.is_empty());
+        let step = result.explanations[0]
+            .step
+            .as_ref()
+            .expect("the failure should be located at a step");
+        assert_eq!(step.tx_id, 1);
+        assert!(step.step_index <= 1, "{}", result.explanations[0]);
+    }
+
+    #[test]
+    fn random_blocks_satisfy_circuits() {
+        for profile in RandomBlockProfile::ALL {
+            for seed in 0..2 {
+                let ctx = random_block::<3>(seed, profile).unwrap();
+                let report = CircuitTestBuilder::new_from_test_ctx(ctx)
+                    .params(CircuitsParams {
+                        max_rws: 0,
+                        max_copy_rows: 0,
+                        max_bytecode: 10_000,
+                        ..Default::default()
+                    })
+                    .try_run()
+                    .unwrap();
+                assert!(
+                    report.is_ok(),
+                    "profile {profile:?} seed {seed}: {:?}",
+                    report.failed().collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn block_file_roundtrip() {
+        let code = bytecode! {
+            PUSH1(0x20)
+            PUSH1(0x00)
+            SSTORE
+            PUSH1(0x00)
+            SLOAD
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+        let (block, ..) = CircuitTestBuilder::new_from_test_ctx(ctx).build_witness_block();
+
+        let path = std::env::temp_dir().join("zkevm_circuits_block_file_roundtrip.json");
+        block.to_json_file(&path).unwrap();
+        let loaded = Block::from_json_file(&path).unwrap();
+        assert_eq!(loaded.txs, block.txs);
+        assert_eq!(loaded.rws.0, block.rws.0);
+        assert_eq!(loaded.end_block_step, block.end_block_step);
+        assert_eq!(loaded.sha3_inputs, block.sha3_inputs);
+
+        let report = CircuitTestBuilder::new_from_block_file(&path)
+            .try_run()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(report.is_ok());
+    }
+
+    /// Expectations of the `testdata/tiny_chain` fixture. The roots are the ones of the keccak
+    /// MPT, checked by the default build only.
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    #[cfg_attr(feature = "scroll", allow(dead_code))]
+    struct TinyChainExpected {
+        prev_state_root: eth_types::H256,
+        post_state_root: eth_types::H256,
+        contracts: std::collections::BTreeMap<eth_types::Address, TinyChainContract>,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct TinyChainContract {
+        code: eth_types::Bytes,
+        storage: std::collections::HashMap<eth_types::Word, eth_types::Word>,
+    }
+
+    /// Keccak MPT root of the non empty accounts of `sdb` among `addresses`.
+    #[cfg(not(feature = "scroll"))]
+    fn keccak_state_root(
+        sdb: &eth_types::state_db::StateDB,
+        addresses: impl IntoIterator<Item = eth_types::Address>,
+    ) -> eth_types::H256 {
+        use eth_types::mpt_proof::{state_root, storage_root, TrieAccount};
+
+        let accounts = addresses
+            .into_iter()
+            .filter_map(|address| {
+                let (_, account) = sdb.get_account(&address);
+                (!account.is_empty()).then(|| {
+                    // the builder commits the storage at the end of each tx
+                    let storage: Vec<_> = account
+                        .storage
+                        .iter()
+                        .map(|(key, value)| (*key, *value))
+                        .collect();
+                    let account = TrieAccount {
+                        nonce: account.nonce,
+                        balance: account.balance,
+                        storage_root: storage_root(&storage),
+                        code_hash: account.keccak_code_hash,
+                    };
+                    (address, account)
+                })
+            })
+            .collect::<Vec<_>>();
+        state_root(&accounts)
+    }
+
+    #[test]
+    fn golden_tiny_chain() {
+        use crate::table::RwTableTag;
+        use mock::genesis::{Genesis, GenesisChildBlock};
+
+        let genesis: Genesis =
+            serde_json::from_str(include_str!("testdata/tiny_chain/genesis.json")).unwrap();
+        let block_1: GenesisChildBlock =
+            serde_json::from_str(include_str!("testdata/tiny_chain/block_1.json")).unwrap();
+        let expected: TinyChainExpected =
+            serde_json::from_str(include_str!("testdata/tiny_chain/expected.json")).unwrap();
+
+        let geth_data = genesis.trace_child_block(&block_1).unwrap();
+        for (idx, trace) in geth_data.geth_traces.iter().enumerate() {
+            assert!(!trace.failed, "tx {idx} failed");
+        }
+
+        let params = CircuitsParams {
+            max_txs: block_1.transactions.len(),
+            max_rws: 0,
+            max_copy_rows: 0,
+            ..Default::default()
+        };
+
+        // The default build takes the roots from the headers, so they are checked against the
+        // roots of the keccak MPT recomputed from the state before and after the block.
+        #[cfg(not(feature = "scroll"))]
+        let block = {
+            use crate::witness::block_convert;
+
+            let mut builder = BlockData::new_from_geth_data_with_params(geth_data.clone(), params)
+                .new_circuit_input_builder();
+            let prev_state_root = keccak_state_root(&builder.sdb, genesis.alloc.keys().copied());
+            assert_eq!(prev_state_root, expected.prev_state_root);
+            assert_eq!(genesis.state_root, expected.prev_state_root);
+            builder.block.prev_state_root = genesis.state_root;
+            builder
+                .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+                .unwrap();
+            let block = block_convert(&builder.block, &builder.code_db).unwrap();
+
+            let touched = block.rws.0[&RwTableTag::Account].iter().map(|rw| match rw {
+                Rw::Account {
+                    account_address, ..
+                } => *account_address,
+                _ => unreachable!("account rw"),
+            });
+            let post_state_root =
+                keccak_state_root(&builder.sdb, genesis.alloc.keys().copied().chain(touched));
+            assert_eq!(post_state_root, expected.post_state_root);
+            assert_eq!(block.prev_state_root, expected.prev_state_root);
+            assert_eq!(block.post_state_root(), expected.post_state_root);
+            block
+        };
+        // The scroll build replays the mpt updates on the zktrie, whose roots are checked
+        // against the ones computed by the tracer.
+        #[cfg(feature = "scroll")]
+        let block = {
+            let storage_trace = geth_data.block_trace.storage_trace.clone();
+            let block = build_witness_from_l2_trace(geth_data.block_trace.clone(), params)
+                .unwrap()
+                .block;
+            assert_eq!(block.prev_state_root, storage_trace.root_before);
+            assert_eq!(block.post_state_root(), storage_trace.root_after);
+            assert_ne!(storage_trace.root_before, storage_trace.root_after);
+            block
+        };
+
+        let storage_rws = &block.rws.0[&RwTableTag::AccountStorage];
+        for (address, contract) in &expected.contracts {
+            assert!(
+                block
+                    .bytecodes
+                    .values()
+                    .any(|bytecode| bytecode.bytes == contract.code.to_vec()),
+                "missing code of {address:?}"
+            );
+            for (key, value) in &contract.storage {
+                let last_write = storage_rws
+                    .iter()
+                    .filter_map(|rw| match rw {
+                        Rw::AccountStorage {
+                            rw_counter,
+                            account_address,
+                            storage_key,
+                            value: rw_value,
+                            ..
+                        } if account_address == address && storage_key == key => {
+                            Some((*rw_counter, *rw_value))
+                        }
+                        _ => None,
+                    })
+                    .max_by_key(|(rw_counter, _)| *rw_counter)
+                    .map(|(_, value)| value);
+                assert_eq!(last_write, Some(*value), "storage {key} of {address:?}");
+            }
+        }
+
+        let report = CircuitTestBuilder::new_from_block(block).try_run().unwrap();
+        assert!(report.is_ok(), "{:?}", report.failed().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn try_run_without_block_source() {
+        assert!(matches!(
+            CircuitTestBuilder::empty().try_run(),
+            Err(CircuitTestError::NoBlockSource)
+        ));
+    }
+}
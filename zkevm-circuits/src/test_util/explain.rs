@@ -0,0 +1,194 @@
+//! Explanation of the unsatisfied constraints reported by the [`MockProver`],
+//! mapping the row a failure happened at back to the step of the witness
+//! block assigned there.
+
+use super::TestedCircuit;
+use crate::{
+    evm_circuit::{EvmCircuit, EvmRowOwner, ExecutionState},
+    witness::{Block, Rw},
+};
+use bus_mapping::evm::OpcodeId;
+use halo2_proofs::{
+    dev::{FailureLocation, MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+use std::fmt;
+
+/// Number of failures of a circuit explained by [`explain_failures`], as each
+/// one takes a few verifications of the circuit to be located.
+pub const MAX_EXPLAINED_FAILURES: usize = 8;
+
+/// Step of the witness block assigned at the row of a failure.
+#[derive(Clone, Debug)]
+pub struct FailedStep {
+    /// Id of the tx of the step, starting at 1.
+    pub tx_id: usize,
+    /// Index of the step in the tx.
+    pub step_index: usize,
+    /// Row of the failure in the step.
+    pub offset: usize,
+    /// Execution state of the step, naming the gadget it is assigned with.
+    pub execution_state: ExecutionState,
+    /// Opcode of the step.
+    pub opcode: Option<OpcodeId>,
+    /// Index of the call of the step in the tx.
+    pub call_index: usize,
+    /// Read/Write counter before the step.
+    pub rw_counter: usize,
+    /// Program counter of the step.
+    pub program_counter: u64,
+    /// Gas left before the step.
+    pub gas_left: u64,
+    /// Gas cost of the step.
+    pub gas_cost: u64,
+    /// Read/Write operations of the step.
+    pub rws: Vec<Rw>,
+}
+
+/// An unsatisfied constraint, with the row and the step it failed at when
+/// they could be found.
+#[derive(Clone, Debug)]
+pub struct FailureExplanation {
+    /// The failure as reported by the [`MockProver`], including the values of
+    /// the cells of the constraint.
+    pub failure: String,
+    /// Row of the circuit the failure happened at.
+    pub row: Option<usize>,
+    /// What the row is assigned with, for the EVM circuit.
+    pub owner: Option<EvmRowOwner>,
+    /// Step assigned at the row, for the EVM circuit.
+    pub step: Option<FailedStep>,
+}
+
+impl fmt::Display for FailureExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.failure.trim_end())?;
+        match self.row {
+            Some(row) => writeln!(f, "  at row {row}")?,
+            None => writeln!(f, "  at an unknown row")?,
+        }
+        match (&self.step, self.owner) {
+            (Some(step), _) => {
+                writeln!(
+                    f,
+                    "  in step {} of tx {}, row {} of the {:?} gadget",
+                    step.step_index, step.tx_id, step.offset, step.execution_state
+                )?;
+                writeln!(
+                    f,
+                    "  opcode {}, call {}, rwc {}, pc {}, gas left {}, gas cost {}",
+                    step.opcode
+                        .map_or_else(|| "-".to_string(), |opcode| format!("{opcode:?}")),
+                    step.call_index,
+                    step.rw_counter,
+                    step.program_counter,
+                    step.gas_left,
+                    step.gas_cost
+                )?;
+                for rw in &step.rws {
+                    writeln!(f, "    {rw:?}")?;
+                }
+            }
+            (None, Some(owner)) => writeln!(f, "  in {owner:?}")?,
+            (None, None) => {}
+        }
+        Ok(())
+    }
+}
+
+/// Explain the first [`MAX_EXPLAINED_FAILURES`] `failures` of `circuit`,
+/// verified by `prover` at `gate_rows` and `lookup_rows`.
+///
+/// The row of a failure located in a region is found by verifying the circuit
+/// at halves of the rows until the failure is isolated, as the regions of a
+/// circuit are assigned in chunks whose offsets don't map to rows.
+pub fn explain_failures(
+    circuit: TestedCircuit,
+    block: &Block,
+    prover: &MockProver<Fr>,
+    gate_rows: &[usize],
+    lookup_rows: &[usize],
+    failures: &[VerifyFailure],
+) -> Vec<FailureExplanation> {
+    failures
+        .iter()
+        .take(MAX_EXPLAINED_FAILURES)
+        .map(|failure| {
+            let row = locate_row(prover, gate_rows, lookup_rows, failure);
+            let owner = match (circuit, row) {
+                (TestedCircuit::Evm, Some(row)) => Some(EvmCircuit::<Fr>::row_owner(block, row)),
+                _ => None,
+            };
+            let step = match owner {
+                Some(EvmRowOwner::Step {
+                    tx_index,
+                    step_index,
+                    offset,
+                }) => {
+                    let tx = &block.txs[tx_index];
+                    let step = &tx.steps[step_index];
+                    Some(FailedStep {
+                        tx_id: tx.id,
+                        step_index,
+                        offset,
+                        execution_state: step.execution_state,
+                        opcode: step.opcode,
+                        call_index: step.call_index,
+                        rw_counter: step.rw_counter,
+                        program_counter: step.program_counter,
+                        gas_left: step.gas_left,
+                        gas_cost: step.gas_cost,
+                        rws: step.rw_indices.iter().map(|&rw| block.rws[rw]).collect(),
+                    })
+                }
+                _ => None,
+            };
+            FailureExplanation {
+                failure: failure.to_string(),
+                row,
+                owner,
+                step,
+            }
+        })
+        .collect()
+}
+
+/// Find the row `failure` happened at, bisecting the rows it is verified at.
+fn locate_row(
+    prover: &MockProver<Fr>,
+    gate_rows: &[usize],
+    lookup_rows: &[usize],
+    failure: &VerifyFailure,
+) -> Option<usize> {
+    let is_lookup = match failure {
+        VerifyFailure::ConstraintNotSatisfied {
+            location: FailureLocation::OutsideRegion { row },
+            ..
+        }
+        | VerifyFailure::Lookup {
+            location: FailureLocation::OutsideRegion { row },
+            ..
+        } => return Some(*row),
+        VerifyFailure::ConstraintNotSatisfied { .. } => false,
+        VerifyFailure::Lookup { .. } => true,
+        _ => return None,
+    };
+    let fails_at = |rows: &[usize]| {
+        let (gate_rows, lookup_rows) = if is_lookup {
+            (&[][..], rows)
+        } else {
+            (rows, &[][..])
+        };
+        prover
+            .verify_at_rows_par(gate_rows.iter().cloned(), lookup_rows.iter().cloned())
+            .err()
+            .map_or(false, |failures| failures.contains(failure))
+    };
+
+    let mut rows = if is_lookup { lookup_rows } else { gate_rows };
+    while rows.len() > 1 {
+        let (first, second) = rows.split_at(rows.len() / 2);
+        rows = if fails_at(first) { first } else { second };
+    }
+    rows.first().copied().filter(|&row| fails_at(&[row]))
+}
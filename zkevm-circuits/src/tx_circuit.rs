@@ -35,8 +35,8 @@ use crate::{
     witness::{
         rlp_fsm::{Tag, ValueTagLength},
         Format::{
-            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashPreEip155, TxSignEip155,
-            TxSignEip1559, TxSignEip2930, TxSignPreEip155,
+            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashEip4844, TxHashPreEip155,
+            TxSignEip155, TxSignEip1559, TxSignEip2930, TxSignEip4844, TxSignPreEip155,
         },
         RlpTag,
         RlpTag::{GasCost, Len, Null, RLC},
@@ -46,9 +46,10 @@ use crate::{
 };
 use crate::{util::Field, witness::keccak::keccak_inputs_sign_verify};
 use eth_types::{
+    evm_types::GasSchedule,
     geth_types::{
         access_list_size, TxType,
-        TxType::{Eip155, Eip1559, Eip2930, L1Msg, PreEip155},
+        TxType::{Eip155, Eip1559, Eip2930, Eip4844, L1Msg, PreEip155},
     },
     sign_types::SignData,
     AccessList, Address, ToAddress, ToBigEndian,
@@ -56,7 +57,7 @@ use eth_types::{
 use ethers_core::utils::keccak256;
 use gadgets::ToScalar;
 use gadgets::{
-    binary_number::{BinaryNumberChip, BinaryNumberConfig},
+    binary_number::{BinaryNumberChip, BinaryNumberConfig, BinaryNumberTag},
     comparator::{ComparatorChip, ComparatorConfig, ComparatorInstruction},
     is_equal::{IsEqualChip, IsEqualConfig, IsEqualInstruction},
     util::{and, not, select, sum, Expr},
@@ -338,10 +339,10 @@ pub struct TxCircuitConfig<F: Field> {
     // A selector which is enabled at 1st row
     q_first: Column<Fixed>,
     tx_table: TxTable,
-    tx_tag_bits: BinaryNumberConfig<TxFieldTag, 5>,
+    tx_tag_bits: BinaryNumberConfig<TxFieldTag, { TxFieldTag::N_BITS }>,
 
     tx_type: Column<Advice>,
-    tx_type_bits: BinaryNumberConfig<TxType, 3>,
+    tx_type_bits: BinaryNumberConfig<TxType, { TxType::N_BITS }>,
     // The associated rlp tag to lookup in the RLP table
     rlp_tag: Column<Advice>,
     // Whether tag's RLP-encoded value is 0x80 = rlp([])
@@ -369,6 +370,7 @@ pub struct TxCircuitConfig<F: Field> {
     is_l1_msg: Column<Advice>,
     is_eip2930: Column<Advice>,
     is_eip1559: Column<Advice>,
+    is_eip4844: Column<Advice>,
     is_chain_id: Column<Advice>,
     is_tx_id_zero: Column<Advice>,
     lookup_conditions: HashMap<LookupCondition, Column<Advice>>,
@@ -440,6 +442,8 @@ pub struct TxCircuitConfig<F: Field> {
     pow_of_rand: Column<Advice>,
     /// ROM table
     tx_rom_table: TxRomTable,
+    /// Gas costs of the calldata bytes
+    gas_schedule: GasSchedule,
 
     _marker: PhantomData<F>,
 }
@@ -464,6 +468,8 @@ pub struct TxCircuitConfigArgs<F: Field> {
     pub pow_of_rand_table: PowOfRandTable,
     /// Challenges
     pub challenges: crate::util::Challenges<Expression<F>>,
+    /// Gas costs of the fork the txs are charged with
+    pub gas_schedule: GasSchedule,
 }
 
 impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
@@ -482,9 +488,18 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             u16_table,
             pow_of_rand_table,
             challenges,
+            gas_schedule,
         }: Self::ConfigArgs,
     ) -> Self {
         let q_enable = tx_table.q_enable;
+        // cost of a calldata byte, zero or not, of the configured fork (EIP-2028)
+        let calldata_byte_gas = |is_zero: Expression<F>| {
+            select::expr(
+                is_zero,
+                gas_schedule.tx_data_zero.expr(),
+                gas_schedule.tx_data_non_zero.expr(),
+            )
+        };
 
         let q_first = meta.fixed_column();
         let q_dynamic_first = meta.fixed_column();
@@ -536,6 +551,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         let is_l1_msg = meta.advice_column();
         let is_eip2930 = meta.advice_column();
         let is_eip1559 = meta.advice_column();
+        let is_eip4844 = meta.advice_column();
         let is_calldata = meta.advice_column();
         let is_tx_id_zero = meta.advice_column();
         let is_caller_address = meta.advice_column();
@@ -835,6 +851,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                     usize::from(L1Msg).expr(),
                     usize::from(Eip2930).expr(),
                     usize::from(Eip1559).expr(),
+                    usize::from(Eip4844).expr(),
                 ],
             );
 
@@ -863,6 +880,16 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 );
             });
 
+            cb.condition(
+                and::expr([is_to(meta), meta.query_advice(is_eip4844, Rotation::cur())]),
+                |cb| {
+                    cb.require_zero(
+                        "blob tx cannot create contracts",
+                        meta.query_advice(is_none, Rotation::cur()),
+                    );
+                },
+            );
+
             let is_none_expr = meta.query_advice(is_none, Rotation::cur());
             // is_none == true
             cb.condition(is_none_expr.expr(), |cb| {
@@ -1067,7 +1094,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         });
 
         meta.create_gate(
-            "distinguish tx type: is_l1_msg, is_eip2930, is_eip1559",
+            "distinguish tx type: is_l1_msg, is_eip2930, is_eip1559, is_eip4844",
             |meta| {
                 let mut cb = BaseConstraintBuilder::default();
 
@@ -1089,6 +1116,12 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                     tx_type_bits.value_equals(Eip1559, Rotation::cur())(meta),
                 );
 
+                cb.require_equal(
+                    "is_eip4844 = (tx_type == Eip4844)",
+                    meta.query_advice(is_eip4844, Rotation::cur()),
+                    tx_type_bits.value_equals(Eip4844, Rotation::cur())(meta),
+                );
+
                 cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
             },
         );
@@ -1135,7 +1168,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             let is_tag_in_tx_sign = sum::expr([
                 is_nonce(meta),
                 and::expr([
-                    not::expr(meta.query_advice(is_eip1559, Rotation::cur())),
+                    not::expr(sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ])),
                     is_gas_price(meta),
                 ]),
                 is_gas(meta),
@@ -1148,14 +1184,21 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                         tx_type_bits.value_equals(Eip155, Rotation::cur())(meta),
                         meta.query_advice(is_eip2930, Rotation::cur()),
                         meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
                     ]),
                 ]),
                 and::expr([
-                    meta.query_advice(is_eip1559, Rotation::cur()),
+                    sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ]),
                     is_max_fee_per_gas(meta),
                 ]),
                 and::expr([
-                    meta.query_advice(is_eip1559, Rotation::cur()),
+                    sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ]),
                     is_max_priority_fee_per_gas(meta),
                 ]),
                 is_sign_length(meta),
@@ -1183,7 +1226,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             let is_tag_in_tx_hash = sum::expr([
                 is_nonce(meta),
                 and::expr([
-                    not::expr(meta.query_advice(is_eip1559, Rotation::cur())),
+                    not::expr(sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ])),
                     is_gas_price(meta),
                 ]),
                 is_gas(meta),
@@ -1197,11 +1243,17 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 is_hash_length(meta),
                 is_hash_rlc(meta),
                 and::expr([
-                    meta.query_advice(is_eip1559, Rotation::cur()),
+                    sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ]),
                     is_max_fee_per_gas(meta),
                 ]),
                 and::expr([
-                    meta.query_advice(is_eip1559, Rotation::cur()),
+                    sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ]),
                     is_max_priority_fee_per_gas(meta),
                 ]),
             ]);
@@ -1301,6 +1353,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             is_l1_msg,
             is_eip2930,
             is_eip1559,
+            is_eip4844,
             sv_address,
             calldata_gas_cost_acc,
             section_rlc,
@@ -1746,7 +1799,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 );
 
                 let value_next_is_zero = value_is_zero.expr(Rotation::next())(meta);
-                let gas_cost_next = select::expr(value_next_is_zero, 4.expr(), 16.expr());
+                let gas_cost_next = calldata_byte_gas(value_next_is_zero);
                 // call data gas cost accumulator check.
                 cb.require_equal(
                     "calldata_gas_cost_acc::next == calldata_gas_cost::cur + gas_cost_next",
@@ -1789,7 +1842,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             let mut cb = BaseConstraintBuilder::default();
 
             let value_is_zero = value_is_zero.expr(Rotation::cur())(meta);
-            let gas_cost = select::expr(value_is_zero, 4.expr(), 16.expr());
+            let gas_cost = calldata_byte_gas(value_is_zero);
 
             cb.require_equal(
                 "index == 0",
@@ -1848,7 +1901,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 and::expr([meta.query_advice(is_calldata, Rotation::next())]),
                 |cb| {
                     let value_next_is_zero = value_is_zero.expr(Rotation::next())(meta);
-                    let gas_cost_next = select::expr(value_next_is_zero, 4.expr(), 16.expr());
+                    let gas_cost_next = calldata_byte_gas(value_next_is_zero);
 
                     cb.require_equal(
                         "index' == 0",
@@ -2119,13 +2172,14 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 },
             );
 
-            // 4. EPI1559/2930: v Є {0, 1}
+            // 4. EPI1559/2930/4844: v Є {0, 1}
             cb.condition(
                 and::expr([
                     is_chain_id.expr(),
                     sum::expr([
                         tx_type_bits.value_equals(Eip1559, Rotation::cur())(meta),
                         tx_type_bits.value_equals(Eip2930, Rotation::cur())(meta),
+                        tx_type_bits.value_equals(Eip4844, Rotation::cur())(meta),
                     ]),
                 ]),
                 |cb| {
@@ -2351,6 +2405,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             is_l1_msg,
             is_eip2930,
             is_eip1559,
+            is_eip4844,
             is_row_hash_rlc,
             is_chain_id,
             is_final,
@@ -2377,6 +2432,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             chunk_txbytes_rlc,
             chunk_txbytes_len_acc,
             pow_of_rand,
+            gas_schedule,
             tx_rom_table,
             _marker: PhantomData,
             num_txs,
@@ -2394,7 +2450,7 @@ impl<F: Field> TxCircuitConfig<F> {
         rlp_tag: Column<Advice>,
         tx_value_rlc: Column<Advice>,
         tx_value_length: Column<Advice>,
-        tx_type_bits: BinaryNumberConfig<TxType, 3>,
+        tx_type_bits: BinaryNumberConfig<TxType, { TxType::N_BITS }>,
         tx_id_is_zero: IsZeroConfig<F>,
         is_none: Column<Advice>,
         lookup_conditions: &HashMap<LookupCondition, Column<Advice>>,
@@ -2404,6 +2460,7 @@ impl<F: Field> TxCircuitConfig<F> {
         is_l1_msg_col: Column<Advice>,
         is_eip2930: Column<Advice>,
         is_eip1559: Column<Advice>,
+        is_eip4844: Column<Advice>,
         sv_address: Column<Advice>,
         calldata_gas_cost_acc: Column<Advice>,
         section_rlc: Column<Advice>,
@@ -2679,7 +2736,8 @@ impl<F: Field> TxCircuitConfig<F> {
             let sign_format = is_pre_eip155(meta) * TxSignPreEip155.expr()
                 + is_eip155(meta) * TxSignEip155.expr()
                 + meta.query_advice(is_eip2930, Rotation::cur()) * TxSignEip2930.expr()
-                + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr();
+                + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr()
+                + meta.query_advice(is_eip4844, Rotation::cur()) * TxSignEip4844.expr();
 
             // q_enable, tx_id, format, rlp_tag, tag_value, is_output, is_none
             vec![
@@ -2722,7 +2780,8 @@ impl<F: Field> TxCircuitConfig<F> {
                 + is_eip155(meta) * TxHashEip155.expr()
                 + is_l1_msg(meta) * L1MsgHash.expr()
                 + meta.query_advice(is_eip2930, Rotation::cur()) * TxHashEip2930.expr()
-                + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr();
+                + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr()
+                + meta.query_advice(is_eip4844, Rotation::cur()) * TxHashEip4844.expr();
 
             vec![
                 1.expr(), // q_enable = true
@@ -2754,10 +2813,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     meta.query_advice(is_access_list_address, Rotation::cur()),
                 ]);
 
-                // only eip2930 and eip1559 contains an access list
+                // only eip2930, eip1559 and eip4844 contain an access list
                 let sign_format = meta.query_advice(is_eip2930, Rotation::cur())
                     * TxSignEip2930.expr()
-                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr();
+                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr()
+                    + meta.query_advice(is_eip4844, Rotation::cur()) * TxSignEip4844.expr();
 
                 vec![
                     1.expr(), // q_enable = true
@@ -2787,10 +2847,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     meta.query_advice(is_access_list_address, Rotation::cur()),
                 ]);
 
-                // only eip2930 and eip1559 contains an access list
+                // only eip2930, eip1559 and eip4844 contain an access list
                 let hash_format = meta.query_advice(is_eip2930, Rotation::cur())
                     * TxHashEip2930.expr()
-                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr();
+                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr()
+                    + meta.query_advice(is_eip4844, Rotation::cur()) * TxHashEip4844.expr();
 
                 vec![
                     1.expr(), // q_enable = true
@@ -2823,10 +2884,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     meta.query_advice(is_access_list_storage_key, Rotation::cur()),
                 ]);
 
-                // only eip2930 and eip1559 contains an access list
+                // only eip2930, eip1559 and eip4844 contain an access list
                 let sign_format = meta.query_advice(is_eip2930, Rotation::cur())
                     * TxSignEip2930.expr()
-                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr();
+                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr()
+                    + meta.query_advice(is_eip4844, Rotation::cur()) * TxSignEip4844.expr();
 
                 vec![
                     1.expr(), // q_enable = true
@@ -2856,10 +2918,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     meta.query_advice(is_access_list_storage_key, Rotation::cur()),
                 ]);
 
-                // only eip2930 and eip1559 contains an access list
+                // only eip2930, eip1559 and eip4844 contain an access list
                 let hash_format = meta.query_advice(is_eip2930, Rotation::cur())
                     * TxHashEip2930.expr()
-                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr();
+                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr()
+                    + meta.query_advice(is_eip4844, Rotation::cur()) * TxHashEip4844.expr();
 
                 vec![
                     1.expr(), // q_enable = true
@@ -2901,12 +2964,14 @@ impl<F: Field> TxCircuitConfig<F> {
             let sig_s = meta.query_advice(tx_table.value, Rotation(3));
             let sv_address = meta.query_advice(sv_address, Rotation::cur());
 
-            // include eip1559 and eip2930 type tx, sig_v is 0 or 1.
+            // include eip1559, eip2930 and eip4844 type tx, sig_v is 0 or 1.
+            // sig_v is a bit in the sig table, so only a canonical v passes the lookup.
 
             let v = is_eip155(meta) * (sig_v.expr() - 2.expr() * chain_id - 35.expr())
                 + is_pre_eip155(meta) * (sig_v.expr() - 27.expr())
                 + meta.query_advice(is_eip1559, Rotation::cur()) * sig_v.expr()
-                + meta.query_advice(is_eip2930, Rotation::cur()) * sig_v.expr();
+                + meta.query_advice(is_eip2930, Rotation::cur()) * sig_v.expr()
+                + meta.query_advice(is_eip4844, Rotation::cur()) * sig_v.expr();
 
             let input_exprs = vec![
                 1.expr(),     // q_enable = true
@@ -2916,6 +2981,7 @@ impl<F: Field> TxCircuitConfig<F> {
                 sig_s,        // sig_s
                 sv_address,
                 1.expr(), // is_valid
+                1.expr(), // is_low_s, malleable signatures are rejected (EIP-2)
             ];
 
             // LookupTable::table_exprs is not used here since `is_valid` not used by evm circuit.
@@ -2928,6 +2994,7 @@ impl<F: Field> TxCircuitConfig<F> {
                 meta.query_advice(sig_table.sig_s_rlc, Rotation::cur()),
                 meta.query_advice(sig_table.recovered_addr, Rotation::cur()),
                 meta.query_advice(sig_table.is_valid, Rotation::cur()),
+                meta.query_advice(sig_table.is_low_s, Rotation::cur()),
             ];
 
             input_exprs
@@ -3485,8 +3552,8 @@ impl<F: Field> TxCircuitConfig<F> {
                 let is_tag_in_set = sign_set.into_iter().filter(|tag| tx_tag == *tag).count() == 1;
                 let case1 = is_tag_in_set && !is_l1_msg;
                 let case2 = !tx.tx_type.is_pre_eip155() && !is_l1_msg && (tx_tag == ChainID);
-                let case3 = !tx.tx_type.is_eip1559() && !is_l1_msg && (tx_tag == GasPrice);
-                let case4 = tx.tx_type.is_eip1559()
+                let case3 = !tx.tx_type.is_dynamic_fee() && !is_l1_msg && (tx_tag == GasPrice);
+                let case4 = tx.tx_type.is_dynamic_fee()
                     && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
                 F::from((case1 || case2 || case3 || case4) as u64)
             });
@@ -3507,8 +3574,8 @@ impl<F: Field> TxCircuitConfig<F> {
                 ];
                 let is_tag_in_set = hash_set.into_iter().filter(|tag| tx_tag == *tag).count() == 1;
                 let case1 = is_tag_in_set && !is_l1_msg;
-                let case2 = !tx.tx_type.is_eip1559() && !is_l1_msg && (tx_tag == GasPrice);
-                let case3 = tx.tx_type.is_eip1559()
+                let case2 = !tx.tx_type.is_dynamic_fee() && !is_l1_msg && (tx_tag == GasPrice);
+                let case3 = tx.tx_type.is_dynamic_fee()
                     && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
                 F::from((case1 || case2 || case3) as u64)
             });
@@ -3584,7 +3651,7 @@ impl<F: Field> TxCircuitConfig<F> {
         let mut rlc = challenges.keccak_input().map(|_| F::zero());
         for (idx, byte) in tx.call_data.iter().enumerate() {
             let is_final = idx == (tx.call_data.len() - 1);
-            gas_cost_acc += if *byte == 0 { 4 } else { 16 };
+            gas_cost_acc += self.gas_schedule.tx_data_gas_cost(&[*byte]);
             rlc = rlc
                 .zip(challenges.keccak_input())
                 .map(|(rlc, keccak_input)| rlc * keccak_input + F::from(*byte as u64));
@@ -3858,6 +3925,11 @@ impl<F: Field> TxCircuitConfig<F> {
                 self.is_eip1559,
                 F::from(tx_type.is_eip1559() as u64),
             ),
+            (
+                "is_eip4844",
+                self.is_eip4844,
+                F::from(tx_type.is_eip4844() as u64),
+            ),
             (
                 "is_tx_id_zero",
                 self.is_tx_id_zero,
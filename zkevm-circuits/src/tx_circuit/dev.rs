@@ -14,6 +14,7 @@ use crate::{
     util::{Challenges, Field, SubCircuit, SubCircuitConfig},
     witness::Transaction,
 };
+use eth_types::evm_types::GasSchedule;
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner},
     plonk::{Circuit, ConstraintSystem, Error, Expression},
@@ -39,6 +40,8 @@ pub struct TxCircuitTesterConfigArgs<F: Field> {
     pub pow_of_rand_table: PowOfRandTable,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
+    /// Gas costs of the fork the txs are charged with
+    pub gas_schedule: GasSchedule,
 }
 
 /// TxCircuitTesterConfig
@@ -68,6 +71,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitTesterConfig<F> {
             u16_table,
             pow_of_rand_table,
             challenges,
+            gas_schedule,
         }: Self::ConfigArgs,
     ) -> Self {
         let sig_config = SigCircuitConfig::new(
@@ -90,6 +94,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitTesterConfig<F> {
                 u16_table,
                 pow_of_rand_table,
                 challenges,
+                gas_schedule,
             },
         );
         TxCircuitTesterConfig {
@@ -201,6 +206,7 @@ impl<F: Field> Circuit<F> for TxCircuitTester<F> {
                     u16_table,
                     pow_of_rand_table,
                     challenges,
+                    gas_schedule: GasSchedule::default(),
                 },
             );
             TxCircuitTesterConfig {
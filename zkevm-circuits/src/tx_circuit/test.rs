@@ -21,6 +21,9 @@ use crate::{
 use eth_types::{
     address,
     evm_types::gas_utils::{tx_access_list_gas_cost, tx_data_gas_cost},
+    geth_types::get_rlp_unsigned,
+    sign_types::SECP256K1_Q,
+    tx_envelope::decode_signed,
     word, H160, H256, U256, U64,
 };
 use halo2_proofs::{
@@ -39,6 +42,21 @@ fn tx_circuit_unusable_rows() {
 #[cfg(test)]
 fn build_pre_eip155_tx() -> Transaction {
     // pre-eip155 tx downloaded from [etherscan](https://etherscan.io/getRawTx?tx=0x9cd2288e69623b109e25edc46bc518156498b521e5c162d96e1ab392ff1d9dff)
+    let tx = build_pre_eip155_tx_with_sig(Signature {
+        r: word!("0x90b751c5870e9bc071c8d6b2bf1ee80f36ee7efd8e6fbabaa25bd3b8b68cfe9b"),
+        s: word!("0x79c25a01f12493a6d35f1330306d4e3c4e782fcbffc64c6809959577f41ff248"),
+        v: 0x1c,
+    });
+    assert_eq!(
+        hex::encode(tx.hash),
+        "9cd2288e69623b109e25edc46bc518156498b521e5c162d96e1ab392ff1d9dff"
+    );
+
+    tx
+}
+
+#[cfg(test)]
+fn build_pre_eip155_tx_with_sig(eth_sig: Signature) -> Transaction {
     let eth_tx = TransactionRequest::new()
         .from(address!("0xcf40d0d2b44f2b66e07cace1372ca42b73cf21a3"))
         .nonce(word!("0x2ea8"))
@@ -47,20 +65,10 @@ fn build_pre_eip155_tx() -> Transaction {
         .value(word!("0x00"))
         .data(hex::decode("606060405260008054600160a060020a0319163317905560f2806100236000396000f3606060405260e060020a6000350463f5537ede8114601c575b6002565b3460025760f06004356024356044356000805433600160a060020a039081169116141560ea5783905080600160a060020a031663a9059cbb84846000604051602001526040518360e060020a0281526004018083600160a060020a0316815260200182815260200192505050602060405180830381600087803b1560025760325a03f1156002575050604080518481529051600160a060020a0386811693508716917fd0ed88a3f042c6bbb1e3ea406079b5f2b4b198afccaa535d837f4c63abbc4de6919081900360200190a35b50505050565b00").expect("hex data can be decoded"));
 
-    let eth_sig = Signature {
-        r: word!("0x90b751c5870e9bc071c8d6b2bf1ee80f36ee7efd8e6fbabaa25bd3b8b68cfe9b"),
-        s: word!("0x79c25a01f12493a6d35f1330306d4e3c4e782fcbffc64c6809959577f41ff248"),
-        v: 0x1c,
-    };
-
     let signed_bytes = eth_tx.rlp_signed(&eth_sig).to_vec();
     let unsigned_bytes = eth_tx.rlp_unsigned().to_vec();
 
     let eth_tx_hash = keccak256(&signed_bytes);
-    assert_eq!(
-        hex::encode(eth_tx_hash),
-        "9cd2288e69623b109e25edc46bc518156498b521e5c162d96e1ab392ff1d9dff"
-    );
 
     let mut tx = Transaction::new_from_rlp_bytes(1, PreEip155, signed_bytes, unsigned_bytes);
 
@@ -124,12 +132,8 @@ fn build_eip1559_tx(id: usize) -> Transaction {
     let bytes = "02f90b7b01825cb38520955af4328521cf92558d830a1bff9400fc00900000002c00be4ef8f49c000211000c43830cc4d0b9015504673a0b85b3000bef3e26e01428d1b525a532ea7513b8f21661d0d1d76d3ecb8e1b9f1c923dbfffae4097020c532d1b995b7e3e37a1aa6369386e5939053779abd3597508b00129cd75b800073edec02aaa39b223fe8d0a0e5c4f27ead9083c756cc2f21661d0d1d76d3ecb8e1b9f1c923dbfffae40970bb86c3dc790b0d7291f864244b559b59b30f850a8cfb40dc7c53760375530e5af29fded5e139893252993820686c92b000094b61ba302f01b0f027d40c80d8f70f77d3884776531f80b21d20e5a6b806300024b2c713b4502988e070f96cf3bea50b4811cd5844e13a81b61a8078c761b0b85b3000bef3e26e01428d1b525a532ea7513b80002594ea302f03b9eb369241e4270796e665ea1afac355cb99f0c32078ab8ba00013c08711b06ed871e5a66bebf0af6fb768d343b1d14a04b5b34ab10cf761b0b85b3000bef3e26e01428d1b525a532ea7513b8000143542ef909b0f89b940b85b3000bef3e26e01428d1b525a532ea7513b8f884a00000000000000000000000000000000000000000000000000000000000000006a00000000000000000000000000000000000000000000000000000000000000007a0000000000000000000000000000000000000000000000000000000000000000ca00000000000000000000000000000000000000000000000000000000000000008f8dd94c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2f8c6a0e1dd9768c9de657aca2536cf1cdd1c4536b13ec81ff764307ea8312aa7a8790da070bc879403c8b875e45ea7afbb591f1fd4bde469db47d5f0e879e44c6798d33ea0f88aa3ad276c350a067c34b2bed705e1a2cd30c7c3154f62ece8ee00939bbd2ea0be11b0e2ba48478671bfcd8fd182e025c26fbfbcf4fdf6952051d6147955a36fa09a1a5a7ef77f3399dea2a1044425aaca7fec294fdfdcacd7a960c9c94d15f0a6a091828b9b711948523369ff1651b6332e98f75bcd940a551dc7247d5af88e71faf8bc945b7e3e37a1aa6369386e5939053779abd3597508f8a5a00000000000000000000000000000000000000000000000000000000000000004a00000000000000000000000000000000000000000000000000000000000000002a0697b2bd7bb2984c4e0dc14c79c987d37818484a62958b9c45a0e8b962f20650fa00000000000000000000000000000000000000000000000000000000000000009a00000000000000000000000000000000000000000000000000000000000000000f9018394c7c53760375530e5af29fded5e13989325299382f9016ba00000000000000000000000000000000000000000000000000000000000000010a0000000000000000000000000000000000000000000000000000000000000000ba00000000000000000000000000000000000000000000000000000000000000016a0000000000000000000000000000000000000000000000000000000000000000ea051d155e8243cd6886ab3b36f59778d90f3bbb4af820bc2d4536b23ca13814bfba00000000000000000000000000000000000000000000000000000000000000013a0a7609b0290b911c4b52861d3739b36793fd0e23d9ef78cf2fa96dd1b0cbc764da00000000000000000000000000000000000000000000000000000000000000000a0000000000000000000000000000000000000000000000000000000000000000ca0bda2b1a2a3e35ca431f3c4b50639098537d215591b9ca3db95c24c01795a9981a0000000000000000000000000000000000000000000000000000000000000000df89b94c790b0d7291f864244b559b59b30f850a8cfb40df884a0000000000000000000000000000000000000000000000000000000000000000ca00000000000000000000000000000000000000000000000000000000000000008a00000000000000000000000000000000000000000000000000000000000000006a00000000000000000000000000000000000000000000000000000000000000007f8dd9406ed871e5a66bebf0af6fb768d343b1d14a04b5bf8c6a0000000000000000000000000000000000000000000000000000000000000000ca00000000000000000000000000000000000000000000000000000000000000008a00000000000000000000000000000000000000000000000000000000000000006a00000000000000000000000000000000000000000000000000000000000000007a00000000000000000000000000000000000000000000000000000000000000009a0000000000000000000000000000000000000000000000000000000000000000af8bc94f21661d0d1d76d3ecb8e1b9f1c923dbfffae4097f8a5a04d3eb812b43a439547ce41ef251d01e8ad3d0dad3fde6f2bed3d0c0e29dcdd7aa026644b9dbbd32f8882f3abce5ac1575313789ab081b0fe9f3f39c946527bfa27a072fd74a6edf1b99d41f2c81c57f871e198cb7a24fd9861e998221c4aeb776014a0a7609b0290b911c4b52861d3739b36793fd0e23d9ef78cf2fa96dd1b0cbc764da01a3159eb932a0bb66f4d5b9c1cb119796d815774e3c4904b36748d7870d915c2f8dd940f027d40c80d8f70f77d3884776531f80b21d20ef8c6a00000000000000000000000000000000000000000000000000000000000000009a0000000000000000000000000000000000000000000000000000000000000000aa0000000000000000000000000000000000000000000000000000000000000000ca00000000000000000000000000000000000000000000000000000000000000008a00000000000000000000000000000000000000000000000000000000000000006a00000000000000000000000000000000000000000000000000000000000000007f8bc941a76bffd6d1fc1660e1d0e0552fde51ddbb120cff8a5a06d5257204ebe7d88fd91ae87941cb2dd9d8062b64ae5a2bd2d28ec40b9fbf6dfa030e699f4646032d62d40ca795ecffcb27a2d9d2859f21626b5a588210198e7a6a0c929f5ae32c0eabfbdd06198210bc49736d88e6501f814a66dd5b2fa59508b3ea0ea52bdd009b752a3e91262d66aae31638bc36b449d247d61d646b87a733d7d5da0877978b096db3b11862d0cdfe5f5b74f30fd7d5d29e8ce80626ed8a8bbef1beef8dd944502988e070f96cf3bea50b4811cd5844e13a81bf8c6a00000000000000000000000000000000000000000000000000000000000000009a0000000000000000000000000000000000000000000000000000000000000000aa0000000000000000000000000000000000000000000000000000000000000000ca00000000000000000000000000000000000000000000000000000000000000008a00000000000000000000000000000000000000000000000000000000000000006a00000000000000000000000000000000000000000000000000000000000000007f8dd949eb369241e4270796e665ea1afac355cb99f0c32f8c6a00000000000000000000000000000000000000000000000000000000000000008a00000000000000000000000000000000000000000000000000000000000000006a00000000000000000000000000000000000000000000000000000000000000007a00000000000000000000000000000000000000000000000000000000000000009a0000000000000000000000000000000000000000000000000000000000000000aa0000000000000000000000000000000000000000000000000000000000000000cf85994f9a2d7e60a3297e513317ad1d7ce101cc4c6c8f6f842a04b376a11d00750d42abab4d4e465d5dc4d9b1286d77cf0c819f028213ea08bdfa072fd74a6edf1b99d41f2c81c57f871e198cb7a24fd9861e998221c4aeb77601480a0d274986e36e16ec2d4846168d59422f68e4b8ec41690b80bdd2ee65819f238eea03d0394f6daae31ba5a276a3741cc2b3ba79b90024f80df865622a62078e72910";
     let raw_tx_rlp_bytes = hex::decode(bytes).expect("decode tx's hex shall not fail");
 
-    let eth_tx = EthTransaction::decode(&Rlp::new(&raw_tx_rlp_bytes))
-        .expect("decode tx's rlp bytes shall not fail");
-
-    let eth_tx_req: Eip1559TransactionRequest = (&eth_tx).into();
-    let typed_tx: TypedTransaction = eth_tx_req.into();
-    let rlp_unsigned = typed_tx.rlp().to_vec();
+    let eth_tx = decode_signed(&raw_tx_rlp_bytes).expect("decode tx's rlp bytes shall not fail");
+    let rlp_unsigned = get_rlp_unsigned(&eth_tx);
 
     let mut tx =
         Transaction::new_from_rlp_bytes(1, TxType::Eip1559, raw_tx_rlp_bytes, rlp_unsigned);
@@ -205,6 +209,19 @@ fn tx_circuit_1tx_2max_eip1559() {
     );
 }
 
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_1tx_2max_eip1559_tampered_access_list() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 3200;
+
+    // the access list charged for must be the signed one, decoded by the RLP circuit
+    let mut tx = build_eip1559_tx(1);
+    tx.access_list.as_mut().unwrap().0[0].storage_keys[0] = H256::repeat_byte(0xff);
+
+    assert!(run::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0).is_err());
+}
+
 #[test]
 #[cfg(feature = "scroll")]
 fn tx_circuit_2tx_2max_tx_eip1559() {
@@ -296,6 +313,25 @@ fn tx_circuit_1tx_2max_tx() {
     );
 }
 
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_high_s_tx() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 320;
+
+    // (r, n - s) with the flipped recovery id is signed by the same account, but it is malleable
+    let low_s_tx = build_pre_eip155_tx();
+    let high_s = U256::from_big_endian(&SECP256K1_Q.to_bytes_be()) - low_s_tx.s;
+    let tx = build_pre_eip155_tx_with_sig(Signature {
+        r: low_s_tx.r,
+        s: high_s,
+        v: 0x1b,
+    });
+    assert_eq!(tx.sign_data().unwrap().get_addr(), low_s_tx.caller_address);
+
+    assert!(run::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0).is_err());
+}
+
 #[test]
 #[cfg(feature = "scroll")]
 fn tx_circuit_l1_msg_tx() {
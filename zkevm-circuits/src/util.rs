@@ -1,10 +1,20 @@
 //! Common utility traits and functions.
 use std::collections::BTreeSet;
 
-use bus_mapping::evm::OpcodeId;
+use bus_mapping::{
+    circuit_input_builder::CircuitsParams, evm::OpcodeId, precompile::PrecompileCalls,
+};
 use halo2_proofs::{
     circuit::{Layouter, Value},
-    plonk::{Challenge, Circuit, ConstraintSystem, Error, Expression, FirstPhase, VirtualCells},
+    halo2curves::{
+        bn256::{Bn256, Fr},
+        group::ff::PrimeField,
+    },
+    plonk::{
+        keygen_vk, Challenge, Circuit, ConstraintSystem, Error, Expression, FirstPhase,
+        VirtualCells,
+    },
+    poly::kzg::commitment::ParamsKZG,
 };
 
 #[cfg(feature = "onephase")]
@@ -14,7 +24,7 @@ use halo2_proofs::plonk::SecondPhase;
 use sha3::Digest;
 
 use crate::{evm_circuit::util::rlc, table::TxLogFieldTag, witness};
-use eth_types::{ToAddress, Word};
+use eth_types::{utils::HashScheme, ToAddress, Word, H256};
 pub use ethers_core::types::{Address, U256};
 pub use gadgets::util::Expr;
 
@@ -58,7 +68,9 @@ pub struct Challenges<T = Challenge> {
     lookup_input: Option<T>,
 }
 
-/// ..
+/// Constant challenges, for the `onephase` debug feature where the circuits have a single phase
+/// in which no challenge can be drawn. The RLCs computed with them are not sound, so they must
+/// only be used to debug circuits and in tests; [`Challenges`] draws the real challenges.
 #[derive(Default, Clone, Copy, Debug)]
 pub struct MockChallenges {
     evm_word: u64,
@@ -67,22 +79,29 @@ pub struct MockChallenges {
 }
 
 impl MockChallenges {
-    /// ..
+    /// Constant value of the `evm_word` challenge.
+    pub const EVM_WORD: u64 = 0x100;
+    /// Constant value of the `keccak_input` challenge.
+    pub const KECCAK_INPUT: u64 = 0x101;
+    /// Constant value of the `lookup_input` challenge.
+    pub const LOOKUP_INPUT: u64 = 0x100;
+
+    /// Construct the constant challenges, as [`Challenges::construct`].
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
         Self {
-            lookup_input: Some(0x100),
+            lookup_input: Some(Self::LOOKUP_INPUT),
             ..Self::construct_p1(meta)
         }
     }
-    /// ..
+    /// Construct the constant challenges, as [`Challenges::construct_p1`].
     pub fn construct_p1<F: Field>(_meta: &mut ConstraintSystem<F>) -> Self {
         Self {
-            evm_word: 0x100,
-            keccak_input: 0x101,
+            evm_word: Self::EVM_WORD,
+            keccak_input: Self::KECCAK_INPUT,
             lookup_input: None,
         }
     }
-    /// ..
+    /// Returns the constant challenges as `Expression`s.
     pub fn exprs<F: Field>(&self, _meta: &mut ConstraintSystem<F>) -> Challenges<Expression<F>> {
         Challenges {
             evm_word: Expression::Constant(F::from(self.evm_word)),
@@ -90,7 +109,7 @@ impl MockChallenges {
             lookup_input: self.lookup_input.map(|c| Expression::Constant(F::from(c))),
         }
     }
-    /// ..
+    /// Returns the constant challenges as known `Value`s.
     pub fn values<F: Field>(&self, _layouter: &impl Layouter<F>) -> Challenges<Value<F>> {
         Challenges {
             evm_word: Value::known(F::from(self.evm_word)),
@@ -187,7 +206,8 @@ impl<T: Clone> Challenges<T> {
         ]
     }
 
-    /// ..
+    /// Challenges of the given values, to compute witnesses outside of a circuit, e.g. in tests
+    /// or to count rows. Circuits get their challenges from the `Layouter` instead.
     pub fn mock(evm_word: T, keccak_input: T, lookup_input: T) -> Self {
         Self {
             evm_word,
@@ -279,6 +299,12 @@ pub trait SubCircuit<F: Field> {
     /// Return the minimum number of rows required to prove the block.
     /// Row numbers without/with padding are both returned.
     fn min_num_rows_block(block: &witness::Block) -> (usize, usize);
+
+    /// Closed-form row formulas of the precompile ops proved by the circuit
+    /// under `params`, none by default.
+    fn precompile_row_formulas(_params: &CircuitsParams) -> Vec<PrecompileRowFormula> {
+        Vec::new()
+    }
 }
 
 /// SubCircuit configuration
@@ -296,6 +322,18 @@ pub fn log2_ceil(n: usize) -> u32 {
     (u32::BITS - (n as u32).leading_zeros()) - u32::from(n.is_power_of_two())
 }
 
+/// Code hash scheme constrained by the circuits: Poseidon with the
+/// `poseidon-codehash` feature, Keccak otherwise. Witnesses can be generated
+/// for either scheme at runtime, but the constraints are still selected at
+/// compile time.
+pub fn circuit_code_hash_scheme() -> HashScheme {
+    if cfg!(feature = "poseidon-codehash") {
+        HashScheme::Poseidon
+    } else {
+        HashScheme::Keccak
+    }
+}
+
 pub(crate) fn keccak(msg: &[u8]) -> Word {
     Word::from_big_endian(sha3::Keccak256::digest(msg).as_slice())
 }
@@ -334,6 +372,43 @@ pub struct CircuitStats {
     num_verification_ecmul: usize,
 }
 
+/// Closed-form row usage of a precompile op in the circuit proving it:
+/// `rows_per_op * num_ops`, with at most `max_ops` ops fitting. The ops of
+/// the EcPairing precompile are padded to `pairs_per_op` pairs, each taking
+/// `rows_per_pair` rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrecompileRowFormula {
+    /// Precompile the formula is for
+    pub op: PrecompileCalls,
+    /// Rows taken by each op
+    pub rows_per_op: usize,
+    /// Rows taken by each (G1, G2) pair of an EcPairing op, 0 for the other
+    /// precompiles
+    pub rows_per_pair: usize,
+    /// Pairs each EcPairing op is padded to, 0 for the other precompiles
+    pub pairs_per_op: usize,
+    /// Maximum number of ops, `None` when the circuit is sized dynamically
+    pub max_ops: Option<usize>,
+}
+
+impl PrecompileRowFormula {
+    /// Rows taken by `num_ops` ops.
+    pub fn rows(&self, num_ops: usize) -> usize {
+        self.rows_per_op * num_ops
+    }
+
+    /// Whether `num_ops` ops fit in the circuit.
+    pub fn fits(&self, num_ops: usize) -> bool {
+        self.max_ops.map_or(true, |max_ops| num_ops <= max_ops)
+    }
+
+    /// Whether an op of `num_pairs` (G1, G2) pairs fits in the circuit, which
+    /// only bounds the pairs of EcPairing ops.
+    pub fn fits_pairs(&self, num_pairs: usize) -> bool {
+        self.op != PrecompileCalls::Bn128Pairing || num_pairs <= self.pairs_per_op
+    }
+}
+
 /// Basic stats of circuit config
 pub fn circuit_stats<F: Field>(meta: &ConstraintSystem<F>) -> CircuitStats {
     let rotations = meta
@@ -392,3 +467,30 @@ pub(crate) fn unusable_rows<F: Field, C: Circuit<F>>() -> usize {
 
     cs.blinding_factors() + 1
 }
+
+/// Fingerprint of `circuit` at degree `k`, built for `circuits_params`: the
+/// keccak hash of the digest of its verifying key,
+/// `VerifyingKey::transcript_repr`, followed by `k` and the JSON of
+/// `circuits_params`. The digest commits to the pinned constraint system,
+/// i.e. the columns, gates, lookups and permutation, and to the values of the
+/// fixed columns, so the fingerprint changes with any change of the
+/// constraints or of the params. The verifying key is generated against a
+/// setup of a fixed, public secret, which is fine to tell circuits apart but
+/// must not be used to prove.
+pub fn circuit_fingerprint<C: Circuit<Fr>>(
+    circuit: &C,
+    k: u32,
+    circuits_params: &CircuitsParams,
+) -> Result<H256, Error> {
+    let params = ParamsKZG::<Bn256>::unsafe_setup_with_s(k, Fr::from(FINGERPRINT_SETUP_SECRET));
+    let vk = keygen_vk(&params, circuit)?;
+
+    let mut hasher = sha3::Keccak256::new();
+    hasher.update(vk.transcript_repr().to_repr());
+    hasher.update(k.to_be_bytes());
+    hasher.update(serde_json::to_vec(circuits_params).expect("params serialize to JSON"));
+    Ok(H256::from_slice(&hasher.finalize()))
+}
+
+/// Secret of the setup of [`circuit_fingerprint`].
+const FINGERPRINT_SETUP_SECRET: u64 = 1234;
@@ -3,7 +3,20 @@
 //! used to generate witnesses for circuits.
 
 mod block;
-pub use block::{block_convert, dummy_witness_block, Block, BlockContext, BlockContexts};
+pub use block::{
+    block_convert, dummy_witness_block, Block, BlockContext, BlockContexts, RowUsage,
+    RowUsageReport,
+};
+
+mod chunk;
+pub(crate) use chunk::chunk_table_values;
+pub use chunk::{apply_chunks_mpt_updates, check_chunk_continuity, chunk_convert};
+
+mod block_version;
+pub use block_version::{migrate_block_json, WITNESS_FORMAT_VERSION};
+
+/// Text and HTML rendering of the tables of a witness block
+pub mod debug;
 
 /// Keccak witness
 pub mod keccak;
@@ -17,8 +30,10 @@ pub use call::Call;
 mod mpt;
 pub use mpt::{MptUpdate, MptUpdateRow, MptUpdates, WithdrawProof};
 
-mod receipt;
-pub use receipt::Receipt;
+mod provenance;
+pub use provenance::{Provenance, ProvenanceIndex};
+
+pub use bus_mapping::circuit_input_builder::Receipt;
 
 pub(crate) mod rlp_fsm;
 pub use rlp_fsm::{
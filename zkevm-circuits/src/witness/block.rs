@@ -1,12 +1,28 @@
 use ethers_core::types::Signature;
 use gadgets::ToScalar;
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
 
 #[cfg(any(feature = "test", test))]
 use crate::evm_circuit::{detect_fixed_table_tags, EvmCircuit};
 
+#[cfg(not(feature = "zktrie"))]
+use crate::mpt_circuit::keccak::KeccakMptCircuit;
+#[cfg(feature = "zktrie")]
+use crate::mpt_circuit::MptCircuit;
 use crate::{
+    bytecode_circuit::circuit::BytecodeCircuit,
+    copy_circuit::CopyCircuit,
     evm_circuit::util::rlc,
+    keccak_circuit::KeccakCircuit,
+    modexp_circuit::ModExpCircuit,
+    pi_circuit::PiLayout,
+    rlp_circuit_fsm::RlpCircuit,
+    state_circuit::StateCircuit,
     super_circuit::params::get_super_circuit_params,
     table::{BlockContextFieldTag, RwTableTag},
     util::{Field, SubCircuit},
@@ -14,28 +30,32 @@ use crate::{
 };
 use bus_mapping::{
     circuit_input_builder::{
-        self, BigModExp, CircuitInputBuilder, CircuitsParams, CopyEvent, EcAddOp, EcMulOp,
-        EcPairingOp, ExpEvent, PrecompileEvents, SHA256,
+        self, BigModExp, Blake2fOp, ChunkContext, CircuitInputBuilder, CircuitsParams, CopyEvent,
+        EcAddOp, EcMulOp, EcPairingOp, ExpEvent, PrecompileEvents, SHA256,
     },
+    error::CapacityResource,
     Error,
 };
 use eth_types::{
+    mpt_proof::MptProof,
     sign_types::SignData,
     state_db::{CodeDB, StateDB},
     Address, ToLittleEndian, Word, H256, U256,
 };
 use halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
 use itertools::Itertools;
+use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
 
 use super::{
-    mpt::ZktrieState as MptState, step::step_convert, tx::tx_convert, Bytecode, ExecStep,
-    MptUpdates, RwMap, Transaction,
+    block_version, mpt::ZktrieState as MptState, step::step_convert, tx::tx_convert, Bytecode,
+    ExecStep, MptUpdates, Receipt, RwMap, Transaction,
 };
 use crate::util::Challenges;
 
 /// Block is the struct used by all circuits, which contains all the needed
 /// data for witness generation.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Block {
     /// Transactions in the block
     pub txs: Vec<Transaction>,
@@ -68,16 +88,36 @@ pub struct Block {
     pub prev_withdraw_root: Word,
     /// Mpt updates
     pub mpt_updates: MptUpdates,
+    /// Hexary trie proofs of the state the block is executed on, proven by
+    /// the [`KeccakMptCircuit`](crate::mpt_circuit::keccak::KeccakMptCircuit)
+    /// in place of the zktrie [`MptUpdates`] without the `zktrie` feature.
+    pub mpt_proofs: Vec<MptProof>,
     /// Chain ID
     pub chain_id: u64,
     /// StartL1QueueIndex
     pub start_l1_queue_index: u64,
     /// IO to/from precompile calls.
     pub precompile_events: PrecompileEvents,
+    /// Externally supplied anchoring value (e.g. L1 block hash or beacon root),
+    /// exposed as an extra public input by the PI circuit when set.
+    pub anchor: Option<H256>,
+    /// Layout of the public inputs exposed by the PI circuit.
+    pub pi_layout: PiLayout,
+    /// Receipts of the txs, in the order of the txs
+    pub receipts: Vec<Receipt>,
+    /// The receipts roots of the block headers were checked against the
+    /// receipts of the txs, see [`Blocks::check_receipts_root`].
+    ///
+    /// [`Blocks::check_receipts_root`]: circuit_input_builder::Blocks::check_receipts_root
+    pub check_receipts_root: bool,
+    /// Context of the chunk of the block proven by this witness, see
+    /// [`chunk_convert`](super::chunk_convert). None if the block is proven in
+    /// a single chunk.
+    pub chunk: Option<ChunkContext>,
 }
 
 /// ...
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct BlockContexts {
     /// Hashmap that maps block number to its block context.
     pub ctxs: BTreeMap<u64, BlockContext>,
@@ -101,6 +141,10 @@ impl Block {
     /// The state root after this chunk
     pub fn post_state_root(&self) -> H256 {
         let post_state_root_in_trie = self.mpt_updates.new_root();
+        // Only the last chunk of a block ends at the state root of its header.
+        if !self.chunk.unwrap_or_default().is_last() {
+            return post_state_root_in_trie;
+        }
         let post_state_root_in_header = self
             .context
             .ctxs
@@ -116,10 +160,55 @@ impl Block {
         }
         post_state_root_in_trie
     }
+    /// Number of calls executing each bytecode of the block, by code hash. A
+    /// bytecode has a single entry in the bytecode table however many calls
+    /// reference it.
+    pub fn bytecode_references(&self) -> BTreeMap<Word, usize> {
+        let mut references = BTreeMap::new();
+        for call in self.txs.iter().flat_map(|tx| tx.calls.iter()) {
+            *references.entry(call.code_hash).or_insert(0) += 1;
+        }
+        references
+    }
+    /// Bytecode table rows saved by sharing the entry of bytecodes executed by
+    /// several calls.
+    pub fn bytecode_rows_deduplicated(&self) -> usize {
+        self.bytecode_references()
+            .iter()
+            .filter_map(|(code_hash, references)| {
+                self.bytecodes
+                    .get(code_hash)
+                    .map(|bytecode| (references.saturating_sub(1)) * (bytecode.bytes.len() + 1))
+            })
+            .sum()
+    }
+    /// Root of the receipts of the txs of block `block_num`.
+    pub fn receipts_root(&self, block_num: u64) -> H256 {
+        Receipt::root(
+            self.txs
+                .iter()
+                .zip(&self.receipts)
+                .filter(|(tx, _)| tx.block_number == block_num)
+                .map(|(_, receipt)| receipt),
+        )
+    }
     /// Replay mpt updates to generate mpt witness
     pub fn apply_mpt_updates(&mut self, mpt_state: &MptState) {
         self.mpt_updates.fill_state_roots(mpt_state);
     }
+    /// Write the block as JSON, tagged with
+    /// [`WITNESS_FORMAT_VERSION`](super::WITNESS_FORMAT_VERSION), so
+    /// that it can be proven or tested on another machine with
+    /// [`Block::from_json_file`].
+    pub fn to_json_file<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        block_version::write_block(BufWriter::new(File::create(path)?), self)
+    }
+    /// Read a block written by [`Block::to_json_file`], migrating it if it was
+    /// written with a previous
+    /// [`WITNESS_FORMAT_VERSION`](super::WITNESS_FORMAT_VERSION).
+    pub fn from_json_file<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        block_version::read_block(BufReader::new(File::open(path)?))
+    }
     /// For each tx, for each step, print the rwc at the beginning of the step,
     /// and all the rw operations of the step.
     pub(crate) fn debug_print_txs_steps_rw_ops(&self) {
@@ -136,13 +225,13 @@ impl Block {
 
     /// Get signature (witness) from the block for tx signatures and ecRecover calls.
     pub(crate) fn get_sign_data(&self, padding: bool) -> Vec<SignData> {
+        // the public keys are recovered in parallel
         let mut signatures: Vec<SignData> = self
             .txs
-            .iter()
+            .par_iter()
             // Since L1Msg tx does not have signature, it do not need to do lookup into sig table
             .filter(|tx| !tx.tx_type.is_l1_msg())
-            .map(|tx| tx.sign_data())
-            .filter_map(|res| res.ok())
+            .filter_map(|tx| tx.sign_data().ok())
             .collect::<Vec<SignData>>();
         signatures.extend_from_slice(&self.precompile_events.get_ecrecover_events());
         if padding && self.txs.len() < self.circuits_params.max_txs {
@@ -177,6 +266,11 @@ impl Block {
         self.precompile_events.get_sha256_events()
     }
 
+    /// Get blake2f compressions from all precompiled contract calls in this block.
+    pub(crate) fn get_blake2f_ops(&self) -> Vec<Blake2fOp> {
+        self.precompile_events.get_blake2f_events()
+    }
+
     pub(crate) fn print_evm_circuit_row_usage(&self) {
         let mut num_rows = 0;
         let mut counter = HashMap::new();
@@ -271,6 +365,157 @@ impl Block {
         log::debug!("tx_log num: {}", self.rws.rw_num(RwTableTag::TxLog));
         log::debug!("start num: {}", self.rws.rw_num(RwTableTag::Start));
     }
+
+    /// Compare the rows (or events) each sub-circuit needs for this block
+    /// against the capacity allotted by the [`CircuitsParams`], so a capacity
+    /// overflow can be detected before any assignment happens.
+    pub fn row_usage_report(&self) -> RowUsageReport {
+        let params = &self.circuits_params;
+        let mut usages = vec![
+            RowUsage::new("txs", self.txs.len(), params.max_txs),
+            RowUsage::new(
+                "calldata",
+                self.txs.iter().map(|tx| tx.call_data.len()).sum(),
+                params.max_calldata,
+            ),
+            RowUsage::new(
+                "rws",
+                StateCircuit::<Fr>::min_num_rows_block(self).0,
+                params.max_rws,
+            ),
+            RowUsage::new(
+                "copy",
+                CopyCircuit::<Fr>::min_num_rows_block(self).0,
+                params.max_copy_rows,
+            ),
+            RowUsage::new(
+                "keccak",
+                KeccakCircuit::<Fr>::min_num_rows_block(self).0,
+                params.max_keccak_rows,
+            ),
+            RowUsage::new(
+                "bytecode",
+                BytecodeCircuit::<Fr>::min_num_rows_block(self).0,
+                params.max_bytecode,
+            ),
+            // max_exp_steps counts steps rather than rows.
+            RowUsage::new(
+                "exp",
+                self.exp_events.iter().map(|e| e.steps.len()).sum(),
+                params.max_exp_steps,
+            ),
+            RowUsage::new(
+                "rlp",
+                RlpCircuit::<Fr, Transaction>::min_num_rows_block(self).0,
+                params.max_rlp_rows,
+            ),
+            RowUsage::new(
+                "modexp",
+                self.get_big_modexp().len(),
+                ModExpCircuit::<Fr>::row_formula(params)
+                    .max_ops
+                    .unwrap_or_default(),
+            ),
+            RowUsage::new(
+                "ec_add",
+                self.get_ec_add_ops().len(),
+                params.max_ec_ops.ec_add,
+            ),
+            RowUsage::new(
+                "ec_mul",
+                self.get_ec_mul_ops().len(),
+                params.max_ec_ops.ec_mul,
+            ),
+            RowUsage::new(
+                "ec_pairing",
+                self.get_ec_pairing_ops().len(),
+                params.max_ec_ops.ec_pairing,
+            ),
+            // max_blake2f_rounds counts the rounds of the compressions
+            RowUsage::new(
+                "blake2f",
+                self.get_blake2f_ops()
+                    .iter()
+                    .map(|op| op.rounds as usize)
+                    .sum(),
+                params.max_blake2f_rounds,
+            ),
+        ];
+        #[cfg(feature = "zktrie")]
+        usages.push(RowUsage::new(
+            "mpt",
+            MptCircuit::<Fr>::min_num_rows_block(self).0,
+            params.max_mpt_rows,
+        ));
+        #[cfg(not(feature = "zktrie"))]
+        usages.push(RowUsage::new(
+            "mpt",
+            KeccakMptCircuit::<Fr>::min_num_rows_block(self).0,
+            params.max_mpt_rows,
+        ));
+        for usage in usages.iter().filter(|usage| usage.overflows()) {
+            log::warn!(
+                "{} capacity overflow: needed {}, allotted {:?}",
+                usage.name,
+                usage.needed,
+                usage.allotted
+            );
+        }
+        RowUsageReport { usages }
+    }
+}
+
+/// Capacity needed by a block for one sub-circuit, next to the capacity
+/// allotted to it by the [`CircuitsParams`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowUsage {
+    /// Name of the sub-circuit capacity
+    pub name: &'static str,
+    /// Rows (or events) needed to assign this block
+    pub needed: usize,
+    /// Rows (or events) allotted by the params, `None` when the param is 0
+    /// and the sub-circuit is sized dynamically.
+    pub allotted: Option<usize>,
+}
+
+impl RowUsage {
+    fn new(name: &'static str, needed: usize, allotted: usize) -> Self {
+        Self {
+            name,
+            needed,
+            allotted: (allotted != 0).then_some(allotted),
+        }
+    }
+
+    /// Whether the block needs more than the allotted capacity.
+    pub fn overflows(&self) -> bool {
+        self.allotted
+            .map_or(false, |allotted| self.needed > allotted)
+    }
+}
+
+/// Per sub-circuit capacity usage of a block, see [`Block::row_usage_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowUsageReport {
+    /// Usage of each sub-circuit capacity
+    pub usages: Vec<RowUsage>,
+}
+
+impl RowUsageReport {
+    /// Capacities the block does not fit in.
+    pub fn overflows(&self) -> impl Iterator<Item = &RowUsage> {
+        self.usages.iter().filter(|usage| usage.overflows())
+    }
+
+    /// Whether the block fits in every allotted capacity.
+    pub fn is_ok(&self) -> bool {
+        self.overflows().next().is_none()
+    }
+
+    /// Usage of the capacity with the given name.
+    pub fn get(&self, name: &str) -> Option<&RowUsage> {
+        self.usages.iter().find(|usage| usage.name == name)
+    }
 }
 
 #[cfg(feature = "test")]
@@ -297,11 +542,8 @@ impl Block {
             .values()
             .map(|bytecode| bytecode.bytes.len() + 1)
             .sum();
-        let num_rows_required_for_copy_table: usize = self
-            .copy_events
-            .iter()
-            .map(|c| c.copy_bytes.bytes.len() * 2)
-            .sum();
+        let num_rows_required_for_copy_table: usize =
+            self.copy_events.iter().map(CopyEvent::copy_rows).sum();
         let num_rows_required_for_keccak_table: usize = keccak_inputs(self).unwrap().len();
         // tx_table load only does tx padding, no calldata padding
         let num_rows_required_for_tx_table: usize = self.circuits_params.max_txs * TX_LEN
@@ -342,7 +584,7 @@ impl Block {
 }
 
 /// Block context for execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockContext {
     /// The address of the miner for the block
     pub coinbase: Address,
@@ -356,6 +598,8 @@ pub struct BlockContext {
     pub difficulty: Word,
     /// The base fee, the minimum amount of gas fee for a transaction
     pub base_fee: Word,
+    /// The blob base fee (EIP-4844)
+    pub blob_base_fee: Word,
     /// The hash of previous blocks
     pub history_hashes: Vec<Word>,
     /// The chain id
@@ -364,6 +608,8 @@ pub struct BlockContext {
     pub parent_hash: H256,
     /// State root of this block
     pub state_root: H256,
+    /// Receipts root of this block
+    pub receipts_root: H256,
 }
 
 impl BlockContext {
@@ -430,6 +676,15 @@ impl BlockContext {
                     Value::known(current_block_number),
                     Value::known(F::from(num_all_txs)),
                 ],
+                // Not part of the public input, so it comes after the rows
+                // assigned by the PI circuit.
+                [
+                    Value::known(F::from(BlockContextFieldTag::BlobBaseFee as u64)),
+                    Value::known(current_block_number),
+                    randomness.map(|randomness| {
+                        rlc::value(&self.blob_base_fee.to_le_bytes(), randomness)
+                    }),
+                ],
             ],
             self.block_hash_assignments(randomness),
         ]
@@ -437,7 +692,7 @@ impl BlockContext {
     }
 
     fn block_hash_assignments<F: Field>(&self, randomness: Value<F>) -> Vec<[Value<F>; 3]> {
-        use eth_types::ToWord;
+        use eth_types::{ToWord, WordExt};
 
         #[cfg(not(feature = "scroll"))]
         let history_hashes: &[U256] = &self.history_hashes;
@@ -452,10 +707,10 @@ impl BlockContext {
             .map(|(idx, hash)| {
                 let block_number = self
                     .number
-                    .low_u64()
+                    .fit_u64()
                     .checked_sub((len_history - idx) as u64)
                     .unwrap_or_default();
-                if block_number + 1 == self.number.low_u64() {
+                if block_number + 1 == self.number.fit_u64() {
                     debug_assert_eq!(self.parent_hash.to_word(), hash.into());
                 }
                 [
@@ -484,10 +739,12 @@ impl From<&circuit_input_builder::Blocks> for BlockContexts {
                             timestamp: block.timestamp,
                             difficulty: block.difficulty,
                             base_fee: block.base_fee,
+                            blob_base_fee: block.blob_base_fee,
                             history_hashes: block.history_hashes.clone(),
                             chain_id: block.chain_id,
                             parent_hash: block.parent_hash,
                             state_root: block.state_root,
+                            receipts_root: block.receipts_root,
                         },
                     )
                 })
@@ -501,6 +758,9 @@ pub fn block_convert(
     block: &circuit_input_builder::Blocks,
     code_db: &eth_types::state_db::CodeDB,
 ) -> Result<Block, Error> {
+    if block.check_receipts_root {
+        block.check_receipts_roots()?;
+    }
     let rws = RwMap::from(&block.container);
     rws.check_value()?;
     let num_txs = block.txs().len();
@@ -569,9 +829,10 @@ pub fn block_convert(
     let block = Block {
         context: BlockContexts::from(block),
         rws,
+        // the txs are converted independently of each other
         txs: block
             .txs()
-            .iter()
+            .par_iter()
             .enumerate()
             .map(|(idx, tx)| {
                 let next_block_num = if idx + 1 < num_txs {
@@ -579,7 +840,7 @@ pub fn block_convert(
                 } else {
                     last_block_num + 1
                 };
-                tx_convert(tx, idx + 1, chain_id, next_block_num)
+                tx_convert(tx, idx + 1, chain_id, next_block_num, &block.gas_schedule)
             })
             .collect(),
         sigs: block.txs().iter().map(|tx| tx.signature).collect(),
@@ -610,13 +871,48 @@ pub fn block_convert(
         withdraw_root: block.withdraw_root,
         prev_withdraw_root: block.prev_withdraw_root,
         mpt_updates,
+        mpt_proofs: block.mpt_proofs.clone(),
         chain_id,
         start_l1_queue_index: block.start_l1_queue_index,
         precompile_events: block.precompile_events.clone(),
+        anchor: None,
+        pi_layout: PiLayout::default(),
+        receipts: block.receipts(),
+        check_receipts_root: block.check_receipts_root,
+        chunk: None,
     };
+    check_capacity(&block)?;
     Ok(block)
 }
 
+/// Return [`Error::CapacityExceeded`] if the copy events or the keccak inputs
+/// of `block` don't fit in their circuits, which would panic when assigned.
+pub(super) fn check_capacity(block: &Block) -> Result<(), Error> {
+    let params = &block.circuits_params;
+    let copy_rows = CopyCircuit::<Fr>::min_num_rows_block(block).0;
+    if params.max_copy_rows != 0 && copy_rows > params.max_copy_rows {
+        return Err(Error::CapacityExceeded {
+            resource: CapacityResource::CopyRows,
+            used: copy_rows,
+            limit: params.max_copy_rows,
+        });
+    }
+    if let Some(capacity) = KeccakCircuit::<Fr>::capacity_for_row(params.max_keccak_rows) {
+        let keccak_fs = keccak_inputs(block)?
+            .iter()
+            .map(|bytes| KeccakCircuit::<Fr>::num_absorb_blocks(bytes.len()))
+            .sum::<usize>();
+        if keccak_fs > capacity {
+            return Err(Error::CapacityExceeded {
+                resource: CapacityResource::Keccak,
+                used: keccak_fs,
+                limit: capacity,
+            });
+        }
+    }
+    Ok(())
+}
+
 /// Generate a empty witness block, which can be used for key-gen.
 pub fn dummy_witness_block(chain_id: u64) -> Block {
     let builder_block = circuit_input_builder::Blocks::init(chain_id, get_super_circuit_params());
@@ -625,3 +921,123 @@ pub fn dummy_witness_block(chain_id: u64) -> Block {
     builder.finalize_building().expect("should not fail");
     block_convert(&builder.block, &builder.code_db).expect("should not fail")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bus_mapping::mock::BlockData;
+    use eth_types::{bytecode, geth_types::GethData};
+    use mock::{test_ctx::helpers::account_0_code_wallet_0_no_code, TestContext, MOCK_WALLETS};
+
+    const BLOCK_NUM: u64 = 0xcafe;
+
+    fn builder(params: CircuitsParams) -> CircuitInputBuilder {
+        builder_with_receipts_root(params, H256::zero())
+    }
+
+    fn builder_with_receipts_root(
+        params: CircuitsParams,
+        receipts_root: H256,
+    ) -> CircuitInputBuilder {
+        let code = bytecode! {
+            PUSH1(0x20)
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x20)
+            PUSH1(0x00)
+            SHA3
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_wallet_0_no_code(code),
+            |mut txs, accs| {
+                txs[0].from(MOCK_WALLETS[0].clone()).to(accs[0].address);
+            },
+            |block, _txs| block.number(BLOCK_NUM).receipts_root(receipts_root),
+        )
+        .unwrap()
+        .into();
+        let mut builder = BlockData::new_from_geth_data_with_params(block.clone(), params)
+            .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        builder
+    }
+
+    fn witness_block(params: CircuitsParams) -> Block {
+        let builder = builder(params);
+        block_convert(&builder.block, &builder.code_db).unwrap()
+    }
+
+    #[test]
+    fn row_usage_report_fits_default_params() {
+        let report = witness_block(CircuitsParams::default()).row_usage_report();
+        assert!(report.is_ok(), "{report:?}");
+        assert_eq!(report.get("txs").unwrap().needed, 1);
+        // max_keccak_rows defaults to 0, the keccak circuit is sized dynamically.
+        assert_eq!(report.get("keccak").unwrap().allotted, None);
+    }
+
+    #[test]
+    fn row_usage_report_detects_overflow() {
+        let mut block = witness_block(CircuitsParams::default());
+        // Shrinking the params after the witness is built, the builder itself
+        // cannot pad the rw table below the number of rws.
+        block.circuits_params.max_rws = 8;
+        let report = block.row_usage_report();
+        assert!(!report.is_ok());
+        assert_eq!(
+            report
+                .overflows()
+                .map(|usage| usage.name)
+                .collect::<Vec<_>>(),
+            vec!["rws"]
+        );
+    }
+
+    #[test]
+    fn block_convert_detects_copy_rows_overflow() {
+        let builder = builder(CircuitsParams {
+            max_copy_rows: 8,
+            ..Default::default()
+        });
+        assert!(matches!(
+            block_convert(&builder.block, &builder.code_db),
+            Err(Error::CapacityExceeded {
+                resource: CapacityResource::CopyRows,
+                limit: 8,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn block_convert_checks_receipts_root() {
+        let receipts_root = builder(CircuitsParams::default())
+            .block
+            .receipts_root(BLOCK_NUM);
+        assert_ne!(receipts_root, H256::zero());
+
+        let mut checked = builder_with_receipts_root(CircuitsParams::default(), receipts_root);
+        checked.block.check_receipts_root = true;
+        let block = block_convert(&checked.block, &checked.code_db).unwrap();
+        assert!(block.check_receipts_root);
+        assert_eq!(block.context.ctxs[&BLOCK_NUM].receipts_root, receipts_root);
+        assert_eq!(block.receipts_root(BLOCK_NUM), receipts_root);
+
+        // The mock header keeps a zero receipts root.
+        let mut unchecked = builder(CircuitsParams::default());
+        assert!(block_convert(&unchecked.block, &unchecked.code_db).is_ok());
+        unchecked.block.check_receipts_root = true;
+        assert!(matches!(
+            block_convert(&unchecked.block, &unchecked.code_db),
+            Err(Error::InvalidReceiptsRoot {
+                block_num: BLOCK_NUM,
+                header_root,
+                computed_root,
+            }) if header_root == H256::zero() && computed_root == receipts_root
+        ));
+    }
+}
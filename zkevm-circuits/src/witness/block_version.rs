@@ -0,0 +1,236 @@
+//! Versioning of the JSON witness [`Block`] written by
+//! [`Block::to_json_file`], so that a prover can still read the witnesses of
+//! the previous builder release while a fleet is being upgraded.
+//!
+//! Any change to the serialized form of [`Block`], or of the types it
+//! contains, must bump [`WITNESS_FORMAT_VERSION`] and register the migration
+//! from the previous version in [`MIGRATIONS`].
+
+use super::Block;
+use eth_types::{evm_types::block_utils::MIN_BLOB_BASE_FEE, geth_types::BlobTxFields, Word, H256};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io;
+
+/// Version of the witness format written by [`Block::to_json_file`].
+pub const WITNESS_FORMAT_VERSION: u32 = 6;
+
+/// Rewrite of the JSON of a block from the version preceding the one it is
+/// registered for.
+type Migration = fn(Value) -> Result<Value, String>;
+
+/// Migrations to each version from the previous one, starting with the
+/// migration from version 1 to version 2.
+const MIGRATIONS: [Migration; WITNESS_FORMAT_VERSION as usize - 1] = [
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+    migrate_v5_to_v6,
+];
+
+/// Version 1 blocks were written bare, without a version. The layout of the
+/// block itself is unchanged.
+fn migrate_v1_to_v2(block: Value) -> Result<Value, String> {
+    Ok(block)
+}
+
+/// Version 3 added the blob base fee to the block contexts. Blocks written
+/// before had no excess blob gas, so they get the minimum blob base fee.
+fn migrate_v2_to_v3(mut block: Value) -> Result<Value, String> {
+    let ctxs = block
+        .pointer_mut("/context/ctxs")
+        .and_then(Value::as_object_mut)
+        .ok_or("block without context")?;
+    let blob_base_fee =
+        serde_json::to_value(Word::from(MIN_BLOB_BASE_FEE)).map_err(|err| err.to_string())?;
+    for ctx in ctxs.values_mut() {
+        ctx.as_object_mut()
+            .ok_or("block context is not an object")?
+            .insert("blob_base_fee".to_string(), blob_base_fee.clone());
+    }
+    Ok(block)
+}
+
+/// Version 4 added the blob fields to the txs. Blob txs could not be proven
+/// before, so every tx gets empty blob fields.
+fn migrate_v3_to_v4(mut block: Value) -> Result<Value, String> {
+    let txs = block
+        .get_mut("txs")
+        .and_then(Value::as_array_mut)
+        .ok_or("block without txs")?;
+    let blob = serde_json::to_value(BlobTxFields::default()).map_err(|err| err.to_string())?;
+    for tx in txs {
+        tx.as_object_mut()
+            .ok_or("tx is not an object")?
+            .insert("blob".to_string(), blob.clone());
+    }
+    Ok(block)
+}
+
+/// Version 5 added the receipts to the block and the receipts roots to the
+/// block contexts. The receipts of older blocks are unknown, so they get no
+/// receipts and zero roots, and their receipts are not checked.
+fn migrate_v4_to_v5(mut block: Value) -> Result<Value, String> {
+    let ctxs = block
+        .pointer_mut("/context/ctxs")
+        .and_then(Value::as_object_mut)
+        .ok_or("block without context")?;
+    let receipts_root = serde_json::to_value(H256::zero()).map_err(|err| err.to_string())?;
+    for ctx in ctxs.values_mut() {
+        ctx.as_object_mut()
+            .ok_or("block context is not an object")?
+            .insert("receipts_root".to_string(), receipts_root.clone());
+    }
+    let block_object = block.as_object_mut().ok_or("block is not an object")?;
+    block_object.insert("receipts".to_string(), Value::Array(vec![]));
+    block_object.insert("check_receipts_root".to_string(), Value::Bool(false));
+    Ok(block)
+}
+
+/// Version 6 added the chunk context to the block. Older blocks were proven
+/// in a single chunk.
+fn migrate_v5_to_v6(mut block: Value) -> Result<Value, String> {
+    block
+        .as_object_mut()
+        .ok_or("block is not an object")?
+        .insert("chunk".to_string(), Value::Null);
+    Ok(block)
+}
+
+#[derive(Serialize)]
+struct VersionedBlockRef<'a> {
+    version: u32,
+    block: &'a Block,
+}
+
+#[derive(Deserialize)]
+struct VersionedBlockValue {
+    version: u32,
+    block: Value,
+}
+
+/// Migrate the JSON of a block written with the format `version` to
+/// [`WITNESS_FORMAT_VERSION`].
+pub fn migrate_block_json(version: u32, mut block: Value) -> Result<Value, String> {
+    if version == 0 || version > WITNESS_FORMAT_VERSION {
+        return Err(format!(
+            "unsupported witness format version {version}, expected 1 to {WITNESS_FORMAT_VERSION}"
+        ));
+    }
+    for migration in &MIGRATIONS[version as usize - 1..] {
+        block = migration(block)?;
+    }
+    Ok(block)
+}
+
+pub(super) fn write_block<W: io::Write>(writer: W, block: &Block) -> io::Result<()> {
+    let versioned = VersionedBlockRef {
+        version: WITNESS_FORMAT_VERSION,
+        block,
+    };
+    serde_json::to_writer(writer, &versioned)?;
+    Ok(())
+}
+
+pub(super) fn read_block<R: io::Read>(reader: R) -> io::Result<Block> {
+    let value: Value = serde_json::from_reader(reader)?;
+    let (version, block) = if value.get("version").is_some() {
+        let versioned: VersionedBlockValue = serde_json::from_value(value)?;
+        (versioned.version, versioned.block)
+    } else {
+        (1, value)
+    };
+    let block = migrate_block_json(version, block)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    Ok(serde_json::from_value(block)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_previous_and_current_versions() {
+        let block = Block::default();
+        let bare = serde_json::to_vec(&block).unwrap();
+        let mut current = vec![];
+        write_block(&mut current, &block).unwrap();
+
+        for bytes in [bare, current] {
+            let loaded = read_block(bytes.as_slice()).unwrap();
+            assert_eq!(loaded.txs, block.txs);
+            assert_eq!(loaded.end_block_step, block.end_block_step);
+        }
+    }
+
+    #[test]
+    fn migrate_block_context_to_v3() {
+        let mut block = Block::default();
+        block.context.ctxs.insert(1, Default::default());
+        let mut v2 = serde_json::to_value(&block).unwrap();
+        v2["context"]["ctxs"]["1"]
+            .as_object_mut()
+            .unwrap()
+            .remove("blob_base_fee");
+        let v2 = serde_json::json!({ "version": 2, "block": v2 });
+
+        let loaded = read_block(v2.to_string().as_bytes()).unwrap();
+        assert_eq!(
+            loaded.context.ctxs[&1].blob_base_fee,
+            MIN_BLOB_BASE_FEE.into()
+        );
+    }
+
+    #[test]
+    fn migrate_txs_to_v4() {
+        let mut block = Block::default();
+        block.txs.push(Default::default());
+        let mut v3 = serde_json::to_value(&block).unwrap();
+        v3["txs"][0].as_object_mut().unwrap().remove("blob");
+        let v3 = serde_json::json!({ "version": 3, "block": v3 });
+
+        let loaded = read_block(v3.to_string().as_bytes()).unwrap();
+        assert_eq!(loaded.txs[0].blob, BlobTxFields::default());
+    }
+
+    #[test]
+    fn migrate_receipts_to_v5() {
+        let mut block = Block::default();
+        block.context.ctxs.insert(1, Default::default());
+        let mut v4 = serde_json::to_value(&block).unwrap();
+        v4["context"]["ctxs"]["1"]
+            .as_object_mut()
+            .unwrap()
+            .remove("receipts_root");
+        let object = v4.as_object_mut().unwrap();
+        object.remove("receipts");
+        object.remove("check_receipts_root");
+        let v4 = serde_json::json!({ "version": 4, "block": v4 });
+
+        let loaded = read_block(v4.to_string().as_bytes()).unwrap();
+        assert_eq!(loaded.context.ctxs[&1].receipts_root, H256::zero());
+        assert!(loaded.receipts.is_empty());
+        assert!(!loaded.check_receipts_root);
+    }
+
+    #[test]
+    fn migrate_chunk_to_v6() {
+        let mut v5 = serde_json::to_value(Block::default()).unwrap();
+        v5.as_object_mut().unwrap().remove("chunk");
+        let v5 = serde_json::json!({ "version": 5, "block": v5 });
+
+        let loaded = read_block(v5.to_string().as_bytes()).unwrap();
+        assert!(loaded.chunk.is_none());
+    }
+
+    #[test]
+    fn reject_unknown_version() {
+        let newer = serde_json::json!({
+            "version": WITNESS_FORMAT_VERSION + 1,
+            "block": Block::default(),
+        });
+        let err = read_block(newer.to_string().as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
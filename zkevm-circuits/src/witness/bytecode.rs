@@ -1,12 +1,17 @@
 use crate::util::Field;
 use bus_mapping::evm::OpcodeId;
-use eth_types::{ToLittleEndian, Word};
+use eth_types::{utils::HashScheme, ToLittleEndian, ToWord, Word};
 use halo2_proofs::circuit::Value;
+use serde::{Deserialize, Serialize};
 
-use crate::{evm_circuit::util::rlc, table::BytecodeFieldTag, util::Challenges};
+use crate::{
+    evm_circuit::util::rlc,
+    table::BytecodeFieldTag,
+    util::{circuit_code_hash_scheme, Challenges},
+};
 
 /// Bytecode
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Bytecode {
     /// Hash of bytecode
     pub hash: Word,
@@ -15,22 +20,38 @@ pub struct Bytecode {
 }
 
 impl Bytecode {
+    /// Create a bytecode whose hash is computed with `scheme`.
+    pub fn new_with_scheme(bytes: Vec<u8>, scheme: HashScheme) -> Self {
+        Self {
+            hash: scheme.hash_code(&bytes).to_word(),
+            bytes,
+        }
+    }
+
     /// Assignments for bytecode table
     pub fn table_assignments<F: Field>(
         &self,
         challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 6]> {
+        self.table_assignments_with_scheme(challenges, circuit_code_hash_scheme())
+    }
+
+    /// Assignments for bytecode table, with the hash encoded as expected for
+    /// a code hash of `scheme`.
+    pub fn table_assignments_with_scheme<F: Field>(
+        &self,
+        challenges: &Challenges<Value<F>>,
+        scheme: HashScheme,
     ) -> Vec<[Value<F>; 6]> {
         let n = 1 + self.bytes.len();
         let mut rows = Vec::with_capacity(n);
-        let hash = if cfg!(feature = "poseidon-codehash") {
-            challenges
+        let hash = match scheme {
+            HashScheme::Poseidon => challenges
                 .evm_word()
-                .map(|_challenge| rlc::value(&self.hash.to_le_bytes(), F::from(256u64)))
-            //Value::known(rlc::value(&self.hash.to_le_bytes(), F::from(256u64)))
-        } else {
-            challenges
+                .map(|_challenge| rlc::value(&self.hash.to_le_bytes(), F::from(256u64))),
+            HashScheme::Keccak => challenges
                 .evm_word()
-                .map(|challenge| rlc::value(&self.hash.to_le_bytes(), challenge))
+                .map(|challenge| rlc::value(&self.hash.to_le_bytes(), challenge)),
         };
 
         rows.push([
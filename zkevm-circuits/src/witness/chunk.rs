@@ -0,0 +1,482 @@
+//! Witness blocks of the chunks of a block whose rws exceed the capacity of the
+//! state circuit, see [`Blocks::split_txs`].
+//!
+//! Each chunk is a block of its own to the circuits: its txs are numbered
+//! from 1, and it ends with an EndBlock step which reads the withdraw root
+//! after its last tx. The rw counters are not renumbered, so that the rws of
+//! consecutive chunks are contiguous, and the chunk exposes its
+//! [`ChunkContext`] as public inputs, through which the verifier checks that
+//! the chunks continue one another, see [`check_chunk_continuity`].
+//!
+//! [`Blocks::split_txs`]: circuit_input_builder::Blocks::split_txs
+
+use std::collections::{BTreeMap, HashMap};
+
+use bus_mapping::{
+    circuit_input_builder::{self, ChunkContext, CopyDataType, CopyEvent, NumberOrHash},
+    error::CapacityResource,
+    l2_predeployed::message_queue::{ADDRESS as MESSAGE_QUEUE, WITHDRAW_TRIE_ROOT_SLOT},
+    Error,
+};
+use eth_types::{state_db::CodeDB, Word, H256};
+use itertools::Itertools;
+
+use super::{
+    block::{check_capacity, BlockContexts},
+    block_convert,
+    mpt::ZktrieState as MptState,
+    step::step_convert,
+    tx::tx_convert,
+    Block, Bytecode, MptUpdates, Rw, RwMap,
+};
+use crate::{
+    pi_circuit::PiLayout,
+    table::{BlockContextFieldTag, CallContextFieldTag, RwTableTag},
+};
+
+/// Values of the chunk fields of the block table, all with index 0.
+pub(crate) fn chunk_table_values(chunk: &ChunkContext) -> [(BlockContextFieldTag, u64); 4] {
+    [
+        (
+            BlockContextFieldTag::ChunkInitialRwc,
+            chunk.initial_rwc as u64,
+        ),
+        (BlockContextFieldTag::ChunkEndRwc, chunk.end_rwc as u64),
+        (
+            BlockContextFieldTag::ChunkInitialTxId,
+            chunk.initial_tx_id as u64,
+        ),
+        (BlockContextFieldTag::ChunkEndTxId, chunk.end_tx_id as u64),
+    ]
+}
+
+/// Id of the tx of the rw, if any.
+fn rw_tx_id(rw: &mut Rw) -> Option<&mut usize> {
+    match rw {
+        Rw::TxAccessListAccount { tx_id, .. }
+        | Rw::TxAccessListAccountStorage { tx_id, .. }
+        | Rw::TxRefund { tx_id, .. }
+        | Rw::AccountStorage { tx_id, .. }
+        | Rw::AccountTransientStorage { tx_id, .. }
+        | Rw::TxLog { tx_id, .. }
+        | Rw::TxReceipt { tx_id, .. } => Some(tx_id),
+        _ => None,
+    }
+}
+
+/// Renumber the tx ids of the rw from the first tx of the chunk, the one after
+/// `tx_offset` txs. The cumulative gas read by the first tx of the chunk gets
+/// tx id 0.
+fn rebase_rw(rw: &Rw, tx_offset: usize) -> Rw {
+    let mut rw = *rw;
+    if let Some(tx_id) = rw_tx_id(&mut rw) {
+        *tx_id -= tx_offset;
+    }
+    if let Rw::CallContext {
+        field_tag: CallContextFieldTag::TxId,
+        value,
+        ..
+    } = &mut rw
+    {
+        *value = *value - tx_offset;
+    }
+    rw
+}
+
+/// Renumber the tx ids of the copy event from the first tx of the chunk.
+fn rebase_copy_event(event: &CopyEvent, tx_offset: usize) -> CopyEvent {
+    let mut event = event.clone();
+    let rebase_id = |id: &mut NumberOrHash| {
+        if let NumberOrHash::Number(tx_id) = id {
+            *tx_id -= tx_offset;
+        }
+    };
+    if matches!(
+        event.src_type,
+        CopyDataType::TxCalldata
+            | CopyDataType::AccessListAddresses
+            | CopyDataType::AccessListStorageKeys
+    ) {
+        rebase_id(&mut event.src_id);
+    }
+    if matches!(
+        event.dst_type,
+        CopyDataType::TxLog
+            | CopyDataType::AccessListAddresses
+            | CopyDataType::AccessListStorageKeys
+    ) {
+        rebase_id(&mut event.dst_id);
+    }
+    if let Some(origin) = &mut event.origin {
+        origin.tx_id -= tx_offset;
+    }
+    event
+}
+
+/// Build the witness blocks of the chunks of [`Blocks::chunk_contexts`], each
+/// of which fits a state circuit of `max_rws` rows. The block must have been
+/// built with a dynamic `max_rws`, i.e. 0, as its rws may exceed `max_rws`.
+///
+/// A block which fits in a single chunk is converted as by [`block_convert`].
+/// The state roots of the chunks are filled by [`apply_chunks_mpt_updates`].
+///
+/// Events which are not bound to rws, i.e. the exp events, the keccak inputs
+/// and the precompile events, are kept in every chunk, a superset of what its
+/// lookups need.
+///
+/// [`Blocks::chunk_contexts`]: circuit_input_builder::Blocks::chunk_contexts
+pub fn chunk_convert(
+    block: &circuit_input_builder::Blocks,
+    code_db: &CodeDB,
+    max_rws: usize,
+) -> Result<Vec<Block>, Error> {
+    let ctxs = block.chunk_contexts(max_rws)?;
+    if ctxs.len() <= 1 {
+        return Ok(vec![block_convert(block, code_db)?]);
+    }
+    if block.check_receipts_root {
+        block.check_receipts_roots()?;
+    }
+    let rws = RwMap::from(&block.container);
+    rws.check_value()?;
+    rws.check_rw_counter_sanity();
+
+    let chain_id = block.chain_id();
+    let num_txs = block.txs().len();
+    let last_block_num = block.last_block_num().unwrap_or_default();
+    let block_ctxs = BlockContexts::from(block);
+    let receipts = block.receipts();
+    let bytecodes = code_db
+        .0
+        .iter()
+        .map(|(code_hash, bytes)| {
+            let hash = Word::from_big_endian(code_hash.as_bytes());
+            (
+                hash,
+                Bytecode {
+                    hash,
+                    bytes: bytes.clone(),
+                },
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut chunks = Vec::with_capacity(ctxs.len());
+    let mut prev_withdraw_root = block.prev_withdraw_root;
+    for ctx in ctxs {
+        let tx_range = ctx.initial_tx_id - 1..if ctx.is_last() {
+            num_txs
+        } else {
+            ctx.end_tx_id
+        };
+        let tx_offset = tx_range.start;
+        let num_chunk_txs = tx_range.len();
+        let end_rwc = if ctx.is_last() {
+            block.block_steps.end_block_step.rwc.0
+        } else {
+            ctx.end_rwc
+        };
+        // The block number after the last tx of the chunk, where the next chunk continues.
+        let next_block_num = if ctx.is_last() {
+            last_block_num + 1
+        } else {
+            let last_tx_block_num = block.txs()[tx_range.end - 1].block_num;
+            (last_tx_block_num + 1).max(block.txs()[tx_range.end].block_num)
+        };
+
+        // The rws of the txs of the chunk, and the ones of the EndBlock for the last chunk.
+        let mut chunk_rws = RwMap::default();
+        let mut rw_idx_map = HashMap::new();
+        for (tag, tag_rws) in rws.0.iter() {
+            if *tag == RwTableTag::Start {
+                continue;
+            }
+            let chunk_tag_rws = chunk_rws.0.entry(*tag).or_default();
+            for (idx, rw) in tag_rws.iter().enumerate() {
+                let rwc = rw.rw_counter();
+                if rwc >= ctx.initial_rwc && (ctx.is_last() || rwc < ctx.end_rwc) {
+                    rw_idx_map.insert((*tag, idx), chunk_tag_rws.len());
+                    chunk_tag_rws.push(rebase_rw(rw, tx_offset));
+                }
+            }
+        }
+        let remap = |rw_indices: &[(RwTableTag, usize)]| {
+            rw_indices
+                .iter()
+                .filter(|(tag, _)| *tag != RwTableTag::Start)
+                .map(|(tag, idx)| (*tag, rw_idx_map[&(*tag, *idx)]))
+                .collect_vec()
+        };
+
+        let mut txs = block.txs()[tx_range.clone()]
+            .iter()
+            .enumerate()
+            .map(|(idx, tx)| {
+                let next_block_num = if idx + 1 < num_chunk_txs {
+                    block.txs()[tx_offset + idx + 1].block_num
+                } else {
+                    next_block_num
+                };
+                let mut tx = tx_convert(tx, idx + 1, chain_id, next_block_num, &block.gas_schedule);
+                for step in tx.steps.iter_mut() {
+                    step.rw_indices = remap(&step.rw_indices);
+                }
+                tx
+            })
+            .collect_vec();
+
+        // The withdraw root after the last tx of the chunk.
+        let withdraw_root_rws = chunk_rws
+            .0
+            .get(&RwTableTag::AccountStorage)
+            .into_iter()
+            .flatten()
+            .filter(|rw| {
+                matches!(rw, Rw::AccountStorage { account_address, storage_key, .. }
+                if *account_address == *MESSAGE_QUEUE && *storage_key == WITHDRAW_TRIE_ROOT_SLOT)
+            })
+            .filter(|rw| rw.rw_counter() < end_rwc)
+            .collect_vec();
+        let withdraw_root = if ctx.is_last() {
+            block.withdraw_root
+        } else {
+            withdraw_root_rws
+                .iter()
+                .max_by_key(|rw| rw.rw_counter())
+                .map_or(prev_withdraw_root, |rw| rw.storage_value_aux().0)
+        };
+
+        let mut end_block_step = step_convert(
+            &block.block_steps.end_block_step,
+            next_block_num.saturating_sub(1),
+        );
+        end_block_step.rw_counter = end_rwc;
+        if ctx.is_last() {
+            end_block_step.rw_indices = remap(&end_block_step.rw_indices);
+        } else {
+            // The EndBlock of a chunk but the last reads the tx id of the last call and the
+            // withdraw root, as the one of the block does.
+            let call_id = txs.last().map(|tx| tx.calls[0].id).unwrap_or_default();
+            let committed_value = withdraw_root_rws
+                .iter()
+                .filter_map(|rw| match rw {
+                    Rw::AccountStorage {
+                        tx_id,
+                        committed_value,
+                        ..
+                    } if *tx_id == num_chunk_txs => Some(*committed_value),
+                    _ => None,
+                })
+                .next()
+                .unwrap_or(withdraw_root);
+            let call_context_rws = chunk_rws.0.entry(RwTableTag::CallContext).or_default();
+            call_context_rws.push(Rw::CallContext {
+                rw_counter: end_rwc,
+                is_write: false,
+                call_id,
+                field_tag: CallContextFieldTag::TxId,
+                value: num_chunk_txs.into(),
+            });
+            let tx_id_idx = call_context_rws.len() - 1;
+            let storage_rws = chunk_rws.0.entry(RwTableTag::AccountStorage).or_default();
+            storage_rws.push(Rw::AccountStorage {
+                rw_counter: end_rwc + 1,
+                is_write: false,
+                account_address: *MESSAGE_QUEUE,
+                storage_key: WITHDRAW_TRIE_ROOT_SLOT,
+                value: withdraw_root,
+                value_prev: withdraw_root,
+                tx_id: num_chunk_txs,
+                committed_value,
+            });
+            end_block_step.rw_indices = vec![
+                (RwTableTag::CallContext, tx_id_idx),
+                (RwTableTag::AccountStorage, storage_rws.len() - 1),
+            ];
+
+            let end_chunk_step = block
+                .end_chunk_step(&ctx)
+                .expect("a chunk but the last has an EndChunk");
+            let mut end_chunk_step = step_convert(&end_chunk_step, next_block_num - 1);
+            end_chunk_step.rw_indices = vec![(RwTableTag::CallContext, tx_id_idx)];
+            txs.last_mut()
+                .expect("a chunk has txs")
+                .steps
+                .push(end_chunk_step);
+        }
+        if let Some(begin_chunk_step) = block.begin_chunk_step(&ctx) {
+            let mut begin_chunk_step = step_convert(&begin_chunk_step, txs[0].block_number);
+            begin_chunk_step.rw_indices = remap(&begin_chunk_step.rw_indices);
+            txs[0].steps.insert(0, begin_chunk_step);
+        }
+
+        // The Start rows before the rws of the chunk, which the EndBlock looks up.
+        let num_rws = chunk_rws.0.values().map(Vec::len).sum::<usize>();
+        if num_rws + 1 > max_rws {
+            return Err(Error::CapacityExceeded {
+                resource: CapacityResource::Rws,
+                used: num_rws + 1,
+                limit: max_rws,
+            });
+        }
+        chunk_rws.0.insert(
+            RwTableTag::Start,
+            vec![
+                Rw::Start { rw_counter: 1 },
+                Rw::Start {
+                    rw_counter: max_rws - num_rws,
+                },
+            ],
+        );
+        end_block_step
+            .rw_indices
+            .extend([(RwTableTag::Start, 0), (RwTableTag::Start, 1)]);
+        chunk_rws.check_rw_counter_sanity();
+
+        let mut padding_step =
+            step_convert(&block.block_steps.padding_step, end_block_step.block_num);
+        padding_step.rw_counter = end_rwc;
+
+        // The block contexts from the block of the first tx to the one of the last tx, and the
+        // empty blocks in between the chunks to the last chunk.
+        let first_block_num = if ctx.is_first() {
+            0
+        } else {
+            txs[0].block_number
+        };
+        let context = BlockContexts {
+            ctxs: block_ctxs
+                .ctxs
+                .range(first_block_num..next_block_num)
+                .map(|(num, ctx)| (*num, ctx.clone()))
+                .collect(),
+        };
+
+        // The queue index after the last l1 msg before the chunk.
+        let start_l1_queue_index = block.txs()[..tx_offset]
+            .iter()
+            .rev()
+            .find(|tx| tx.tx_type.is_l1_msg())
+            .map_or(block.start_l1_queue_index, |tx| tx.nonce + 1);
+
+        // The state roots are filled by `apply_chunks_mpt_updates`.
+        let new_root = if ctx.is_last() {
+            block.end_state_root()
+        } else {
+            block.prev_state_root
+        };
+        let mpt_updates = MptUpdates::from_unsorted_rws_with_state_roots(
+            &chunk_rws.table_assignments_unsorted(),
+            block.prev_state_root,
+            new_root,
+        );
+
+        let chunk = Block {
+            context,
+            rws: chunk_rws,
+            sigs: block.txs()[tx_range.clone()]
+                .iter()
+                .map(|tx| tx.signature)
+                .collect(),
+            txs,
+            padding_step,
+            end_block_step,
+            bytecodes: bytecodes.clone(),
+            copy_events: block
+                .copy_events
+                .iter()
+                .filter(|event| {
+                    let rwc = event.rw_counter_start.0;
+                    rwc >= ctx.initial_rwc && (ctx.is_last() || rwc < ctx.end_rwc)
+                })
+                .map(|event| rebase_copy_event(event, tx_offset))
+                .collect(),
+            exp_events: block.exp_events.clone(),
+            sha3_inputs: block.sha3_inputs.clone(),
+            circuits_params: circuit_input_builder::CircuitsParams {
+                max_rws,
+                ..block.circuits_params
+            },
+            prev_state_root: block.prev_state_root,
+            withdraw_root,
+            prev_withdraw_root,
+            mpt_updates,
+            // the proofs are of the state the block starts from
+            mpt_proofs: if ctx.is_first() {
+                block.mpt_proofs.clone()
+            } else {
+                Vec::new()
+            },
+            chain_id,
+            start_l1_queue_index,
+            precompile_events: block.precompile_events.clone(),
+            anchor: None,
+            pi_layout: PiLayout::default(),
+            receipts: receipts[tx_range]
+                .iter()
+                .cloned()
+                .map(|mut receipt| {
+                    receipt.id -= tx_offset;
+                    receipt
+                })
+                .collect(),
+            // the receipts roots were checked on the whole block
+            check_receipts_root: false,
+            chunk: Some(ctx),
+        };
+        check_capacity(&chunk)?;
+        prev_withdraw_root = withdraw_root;
+        chunks.push(chunk);
+    }
+    Ok(chunks)
+}
+
+/// Replay the mpt updates of the chunks of [`chunk_convert`], each of which
+/// starts from the state root after the previous one.
+pub fn apply_chunks_mpt_updates(chunks: &mut [Block], mpt_state: &MptState) {
+    MptUpdates::fill_chunks_state_roots(
+        chunks.iter_mut().map(|chunk| &mut chunk.mpt_updates),
+        mpt_state,
+    );
+    let mut prev_state_root = H256::from(mpt_state.root());
+    for chunk in chunks.iter_mut() {
+        chunk.prev_state_root = prev_state_root;
+        prev_state_root = chunk.mpt_updates.new_root();
+    }
+}
+
+/// Check that each chunk continues where the previous one ends, as the
+/// verifier does on the public inputs of their proofs.
+pub fn check_chunk_continuity(chunks: &[Block]) -> Result<(), Error> {
+    let contexts = chunks
+        .iter()
+        .map(|chunk| chunk.chunk.unwrap_or_default())
+        .collect_vec();
+    if contexts.first().map_or(false, |ctx| !ctx.is_first())
+        || contexts.last().map_or(false, |ctx| !ctx.is_last())
+    {
+        return Err(Error::InternalError("chunks don't cover the block"));
+    }
+    for (prev, chunk) in chunks.iter().tuple_windows() {
+        if !chunk
+            .chunk
+            .unwrap_or_default()
+            .follows(&prev.chunk.unwrap_or_default())
+        {
+            return Err(Error::InternalError(
+                "chunk doesn't continue the rws and txs of the previous one",
+            ));
+        }
+        if chunk.prev_state_root != prev.mpt_updates.new_root() {
+            return Err(Error::InternalError(
+                "chunk doesn't start at the state root of the previous one",
+            ));
+        }
+        if chunk.prev_withdraw_root != prev.withdraw_root {
+            return Err(Error::InternalError(
+                "chunk doesn't start at the withdraw root of the previous one",
+            ));
+        }
+    }
+    Ok(())
+}
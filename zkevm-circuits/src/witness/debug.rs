@@ -0,0 +1,420 @@
+//! Rendering of the tables of a witness [`Block`] as text or HTML, filtered
+//! and paginated, to find the rows a failed lookup was looking for, e.g. the
+//! rws of a storage slot:
+//!
+//! ```ignore
+//! let filter: WitnessFilter = "tag=AccountStorage,address=0xcafe...,key=0x1".parse()?;
+//! println!("{}", render(&block, WitnessTable::Rws, &filter, RenderFormat::Text));
+//! ```
+
+use super::{Block, Rw};
+use crate::table::RwTableTag;
+use bus_mapping::circuit_input_builder::NumberOrHash;
+use eth_types::{Address, Word};
+use std::{fmt::Write, ops::Range, str::FromStr};
+use strum::IntoEnumIterator;
+use strum_macros::EnumString;
+
+/// Table of a witness block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum WitnessTable {
+    /// Rws of the RW table, by rw counter
+    Rws,
+    /// Copy events
+    CopyEvents,
+    /// Execution steps of the txs
+    Steps,
+    /// Txs of the tx table
+    Txs,
+}
+
+/// Format of a rendered table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum RenderFormat {
+    /// Aligned plain text columns
+    Text,
+    /// HTML table
+    Html,
+}
+
+/// Filter and page of the rows of a table. A filter on a column the table
+/// doesn't have is ignored.
+///
+/// It parses from comma separated `name=value` pairs, with the names `tag`,
+/// `id`, `address`, `key`, `rwc` (a range `start..end`), `offset` and
+/// `limit`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WitnessFilter {
+    /// Tag of the rws
+    pub tag: Option<RwTableTag>,
+    /// Tx id of the steps and txs, id of the rws (tx or call id depending on
+    /// their tag), source or destination id of the copy events
+    pub id: Option<usize>,
+    /// Address of the rws, caller or callee of the txs
+    pub address: Option<Address>,
+    /// Storage key of the rws
+    pub storage_key: Option<Word>,
+    /// Range of the rw counter of the rws and steps, or of the first rw of
+    /// the copy events
+    pub rw_counter: Option<Range<usize>>,
+    /// Index of the first row of the page among the matching rows
+    pub offset: usize,
+    /// Number of rows of the page, all the rows if `None`
+    pub limit: Option<usize>,
+}
+
+impl FromStr for WitnessFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut filter = Self::default();
+        for pair in s.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected name=value, got {pair}"))?;
+            let invalid = |err: &dyn std::fmt::Debug| format!("invalid {name} {value}: {err:?}");
+            match name.trim() {
+                "tag" => {
+                    filter.tag = Some(
+                        RwTableTag::iter()
+                            .find(|tag| format!("{tag:?}").eq_ignore_ascii_case(value))
+                            .ok_or_else(|| invalid(&"unknown tag"))?,
+                    )
+                }
+                "id" => filter.id = Some(value.parse().map_err(|err| invalid(&err))?),
+                "address" => filter.address = Some(value.parse().map_err(|err| invalid(&err))?),
+                "key" => filter.storage_key = Some(parse_word(value).map_err(|err| invalid(&err))?),
+                "rwc" => {
+                    let (start, end) = value
+                        .split_once("..")
+                        .ok_or_else(|| invalid(&"expected start..end"))?;
+                    filter.rw_counter = Some(
+                        start.parse().map_err(|err| invalid(&err))?
+                            ..end.parse().map_err(|err| invalid(&err))?,
+                    );
+                }
+                "offset" => filter.offset = value.parse().map_err(|err| invalid(&err))?,
+                "limit" => filter.limit = Some(value.parse().map_err(|err| invalid(&err))?),
+                _ => return Err(format!("unknown filter {name}")),
+            }
+        }
+        Ok(filter)
+    }
+}
+
+/// Parse a hex word with a `0x` prefix, or a decimal one.
+fn parse_word(value: &str) -> Result<Word, String> {
+    match value.strip_prefix("0x") {
+        Some(hex) => Word::from_str_radix(hex, 16).map_err(|err| err.to_string()),
+        None => Word::from_dec_str(value).map_err(|err| err.to_string()),
+    }
+}
+
+impl WitnessFilter {
+    fn matches<T: PartialEq>(filter: &Option<T>, value: Option<T>) -> bool {
+        filter.is_none() || *filter == value
+    }
+
+    fn matches_rw_counter(&self, rw_counter: usize) -> bool {
+        self.rw_counter
+            .as_ref()
+            .map_or(true, |range| range.contains(&rw_counter))
+    }
+
+    fn matches_rw(&self, rw: &Rw) -> bool {
+        Self::matches(&self.tag, Some(rw.tag()))
+            && Self::matches(&self.id, rw.id())
+            && Self::matches(&self.address, rw.address())
+            && Self::matches(&self.storage_key, rw.storage_key())
+            && self.matches_rw_counter(rw.rw_counter())
+    }
+}
+
+/// Header and rows of a table.
+type Rows = (Vec<&'static str>, Vec<Vec<String>>);
+
+fn rws_rows(block: &Block, filter: &WitnessFilter) -> Rows {
+    let mut rws: Vec<_> = block
+        .rws
+        .0
+        .values()
+        .flatten()
+        .filter(|rw| filter.matches_rw(rw))
+        .collect();
+    rws.sort_by_key(|rw| rw.rw_counter());
+    let rows = rws
+        .into_iter()
+        .map(|rw| {
+            vec![
+                rw.rw_counter().to_string(),
+                if rw.is_write() { "W" } else { "R" }.to_string(),
+                format!("{:?}", rw.tag()),
+                rw.id().map_or_else(String::new, |id| id.to_string()),
+                rw.address()
+                    .map_or_else(String::new, |address| format!("{address:?}")),
+                rw.storage_key()
+                    .map_or_else(String::new, |key| format!("{key:#x}")),
+                format!("{rw:?}"),
+            ]
+        })
+        .collect();
+    (
+        vec!["rwc", "rw", "tag", "id", "address", "key", "detail"],
+        rows,
+    )
+}
+
+fn copy_events_rows(block: &Block, filter: &WitnessFilter) -> Rows {
+    let id = |id: &NumberOrHash| match id {
+        NumberOrHash::Number(number) => number.to_string(),
+        NumberOrHash::Hash(hash) => format!("{hash:?}"),
+    };
+    let rows = block
+        .copy_events
+        .iter()
+        .enumerate()
+        .filter(|(_, event)| {
+            filter.id.map_or(true, |filter_id| {
+                [&event.src_id, &event.dst_id]
+                    .into_iter()
+                    .any(|id| *id == NumberOrHash::Number(filter_id))
+            }) && filter.matches_rw_counter(event.rw_counter_start.0)
+        })
+        .map(|(index, event)| {
+            vec![
+                index.to_string(),
+                event.rw_counter_start.0.to_string(),
+                format!("{:?}", event.src_type),
+                id(&event.src_id),
+                format!("{}..{}", event.src_addr, event.src_addr_end),
+                format!("{:?}", event.dst_type),
+                id(&event.dst_id),
+                event.dst_addr.to_string(),
+                event.copy_length().to_string(),
+                event
+                    .origin
+                    .as_ref()
+                    .map_or_else(String::new, |origin| format!("{origin:?}")),
+            ]
+        })
+        .collect();
+    (
+        vec![
+            "index", "rwc", "src", "src id", "src addr", "dst", "dst id", "dst addr", "length",
+            "origin",
+        ],
+        rows,
+    )
+}
+
+fn steps_rows(block: &Block, filter: &WitnessFilter) -> Rows {
+    let rows = block
+        .txs
+        .iter()
+        .filter(|tx| WitnessFilter::matches(&filter.id, Some(tx.id)))
+        .flat_map(|tx| {
+            tx.steps
+                .iter()
+                .enumerate()
+                .map(move |(index, step)| (tx.id, index, step))
+        })
+        .filter(|(_, _, step)| filter.matches_rw_counter(step.rw_counter))
+        .map(|(tx_id, index, step)| {
+            vec![
+                tx_id.to_string(),
+                index.to_string(),
+                step.rw_counter.to_string(),
+                format!("{:?}", step.execution_state),
+                step.opcode
+                    .map_or_else(String::new, |opcode| format!("{opcode:?}")),
+                step.call_index.to_string(),
+                step.program_counter.to_string(),
+                step.gas_left.to_string(),
+                step.gas_cost.to_string(),
+                step.rw_indices.len().to_string(),
+            ]
+        })
+        .collect();
+    (
+        vec![
+            "tx", "step", "rwc", "state", "opcode", "call", "pc", "gas left", "gas cost", "rws",
+        ],
+        rows,
+    )
+}
+
+fn txs_rows(block: &Block, filter: &WitnessFilter) -> Rows {
+    let rows = block
+        .txs
+        .iter()
+        .filter(|tx| {
+            WitnessFilter::matches(&filter.id, Some(tx.id))
+                && filter.address.map_or(true, |address| {
+                    tx.caller_address == address || tx.callee_address == Some(address)
+                })
+        })
+        .map(|tx| {
+            vec![
+                tx.id.to_string(),
+                tx.block_number.to_string(),
+                format!("{:?}", tx.tx_type),
+                format!("{:?}", tx.hash),
+                format!("{:?}", tx.caller_address),
+                tx.callee_address
+                    .map_or_else(|| "create".to_string(), |to| format!("{to:?}")),
+                tx.nonce.to_string(),
+                tx.gas.to_string(),
+                tx.value.to_string(),
+                tx.call_data_length.to_string(),
+                tx.steps.len().to_string(),
+            ]
+        })
+        .collect();
+    (
+        vec![
+            "id", "block", "type", "hash", "from", "to", "nonce", "gas", "value", "calldata",
+            "steps",
+        ],
+        rows,
+    )
+}
+
+fn escape_html(cell: &str) -> String {
+    cell.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Render the page of the rows of `table` of `block` matching `filter`.
+pub fn render(
+    block: &Block,
+    table: WitnessTable,
+    filter: &WitnessFilter,
+    format: RenderFormat,
+) -> String {
+    let (header, rows) = match table {
+        WitnessTable::Rws => rws_rows(block, filter),
+        WitnessTable::CopyEvents => copy_events_rows(block, filter),
+        WitnessTable::Steps => steps_rows(block, filter),
+        WitnessTable::Txs => txs_rows(block, filter),
+    };
+    let total = rows.len();
+    let start = filter.offset.min(total);
+    let end = filter
+        .limit
+        .map_or(total, |limit| start.saturating_add(limit).min(total));
+    let page = &rows[start..end];
+    let summary = format!("{table:?}: rows {start}..{end} of {total}");
+
+    let mut out = String::new();
+    match format {
+        RenderFormat::Text => {
+            let mut widths: Vec<_> = header.iter().map(|name| name.len()).collect();
+            for row in page {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.len());
+                }
+            }
+            let mut write_row = |cells: &mut dyn Iterator<Item = &str>| {
+                let line = cells
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{cell:width$}"))
+                    .collect::<Vec<_>>()
+                    .join(" | ");
+                writeln!(out, "{}", line.trim_end()).unwrap();
+            };
+            write_row(&mut header.iter().copied());
+            for row in page {
+                write_row(&mut row.iter().map(String::as_str));
+            }
+            writeln!(out, "{summary}").unwrap();
+        }
+        RenderFormat::Html => {
+            writeln!(out, "<table>\n<caption>{}</caption>", escape_html(&summary)).unwrap();
+            let cells = |tag: &str, cells: &mut dyn Iterator<Item = &str>| {
+                cells
+                    .map(|cell| format!("<{tag}>{}</{tag}>", escape_html(cell)))
+                    .collect::<String>()
+            };
+            writeln!(out, "<tr>{}</tr>", cells("th", &mut header.iter().copied())).unwrap();
+            for row in page {
+                writeln!(
+                    out,
+                    "<tr>{}</tr>",
+                    cells("td", &mut row.iter().map(String::as_str))
+                )
+                .unwrap();
+            }
+            writeln!(out, "</table>").unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block() -> Block {
+        let address = Address::repeat_byte(0xca);
+        let storage = |rw_counter, storage_key: u64| Rw::AccountStorage {
+            rw_counter,
+            is_write: true,
+            account_address: address,
+            storage_key: storage_key.into(),
+            value: Word::from(rw_counter),
+            value_prev: Word::zero(),
+            tx_id: 1,
+            committed_value: Word::zero(),
+        };
+        let mut block = Block::default();
+        block.rws.0.insert(
+            RwTableTag::AccountStorage,
+            vec![storage(3, 1), storage(1, 1), storage(2, 2)],
+        );
+        block
+    }
+
+    #[test]
+    fn parse_filter() {
+        let filter: WitnessFilter = "tag=accountstorage, address=0xcacacacacacacacacacacacacacacacacacacaca, key=0x1, rwc=1..3, limit=10"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            filter,
+            WitnessFilter {
+                tag: Some(RwTableTag::AccountStorage),
+                address: Some(Address::repeat_byte(0xca)),
+                storage_key: Some(Word::one()),
+                rw_counter: Some(1..3),
+                limit: Some(10),
+                ..Default::default()
+            }
+        );
+        assert!("slot=1".parse::<WitnessFilter>().is_err());
+        assert!("tag=Storage".parse::<WitnessFilter>().is_err());
+    }
+
+    #[test]
+    fn render_storage_rws() {
+        let block = block();
+        let filter: WitnessFilter = "key=1".parse().unwrap();
+        let text = render(&block, WitnessTable::Rws, &filter, RenderFormat::Text);
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("1 "));
+        assert!(lines[2].starts_with("3 "));
+        assert_eq!(lines[3], "Rws: rows 0..2 of 2");
+
+        let filter = WitnessFilter {
+            offset: 1,
+            limit: Some(1),
+            ..Default::default()
+        };
+        let html = render(&block, WitnessTable::Rws, &filter, RenderFormat::Html);
+        assert!(html.contains("<caption>Rws: rows 1..2 of 3</caption>"));
+        assert_eq!(html.matches("<tr>").count(), 2);
+    }
+}
@@ -1,11 +1,13 @@
 use bus_mapping::Error;
 use eth_types::{
     geth_types::TxType,
+    mpt_proof::{MptProof, EMPTY_TRIE_NODE, EMPTY_TRIE_ROOT},
     sign_types::{get_dummy_tx, pk_bytes_le, pk_bytes_swap_endianness, SignData},
     ToBigEndian, ToWord, Word, H256,
 };
 use ethers_core::utils::keccak256;
 use itertools::Itertools;
+use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 use super::{Block, BlockContexts, Transaction};
 
@@ -53,6 +55,15 @@ pub fn keccak_inputs(block: &Block) -> Result<Vec<Vec<u8>>, Error> {
         "keccak total len after opcodes: {}",
         keccak_inputs.iter().map(|i| i.len()).sum::<usize>()
     );
+    // MPT Circuit
+    #[cfg(not(feature = "zktrie"))]
+    {
+        keccak_inputs.extend(keccak_inputs_mpt_circuit(&block.mpt_proofs));
+        log::debug!(
+            "keccak total len after mpt proofs: {}",
+            keccak_inputs.iter().map(|i| i.len()).sum::<usize>()
+        );
+    }
 
     let inputs_len: usize = keccak_inputs.iter().map(|k| k.len()).sum();
     let inputs_num = keccak_inputs.len();
@@ -61,11 +72,25 @@ pub fn keccak_inputs(block: &Block) -> Result<Vec<Vec<u8>>, Error> {
     let inputs_num2 = keccak_inputs.len();
     log::debug!("keccak inputs after dedup: input num {inputs_num}->{inputs_num2}, input total len {inputs_len}->{inputs_len2}");
 
-    // MPT Circuit
-    // TODO https://github.com/privacy-scaling-explorations/zkevm-circuits/issues/696
     Ok(keccak_inputs)
 }
 
+/// Generate the keccak inputs required by the keccak MPT circuit: the keys of
+/// the proofs, and their nodes, or the RLP of the empty trie for the proofs in
+/// the empty trie.
+pub fn keccak_inputs_mpt_circuit(proofs: &[MptProof]) -> Vec<Vec<u8>> {
+    let mut inputs = Vec::new();
+    for proof in proofs {
+        inputs.push(proof.key.clone());
+        if proof.root == EMPTY_TRIE_ROOT {
+            inputs.push(EMPTY_TRIE_NODE.to_vec());
+        } else {
+            inputs.extend(proof.nodes.iter().map(|node| node.to_vec()));
+        }
+    }
+    inputs
+}
+
 /// Generate the keccak inputs required by the SignVerify Chip from the
 /// signature datas.
 pub fn keccak_inputs_sign_verify(sigs: &[SignData]) -> Vec<Vec<u8>> {
@@ -186,8 +211,9 @@ pub fn keccak_inputs_tx_circuit(txs: &[Transaction]) -> Result<Vec<Vec<u8>>, Err
         .collect::<Vec<u8>>();
     inputs.push(chunk_txbytes);
 
+    // the public keys are recovered in parallel
     let sign_datas: Vec<SignData> = txs
-        .iter()
+        .par_iter()
         .enumerate()
         .filter(|(i, tx)| {
             if !tx.tx_type.is_l1_msg() && tx.v == 0 && tx.r.is_zero() && tx.s.is_zero() {
@@ -207,7 +233,7 @@ pub fn keccak_inputs_tx_circuit(txs: &[Transaction]) -> Result<Vec<Vec<u8>>, Err
                 tx.sign_data()
             }
         })
-        .try_collect()?;
+        .collect::<Result<_, _>>()?;
     // Keccak inputs from SignVerify Chip
     let sign_verify_inputs = keccak_inputs_sign_verify(&sign_datas);
     inputs.extend_from_slice(&sign_verify_inputs);
@@ -1,9 +1,9 @@
 use crate::{
     evm_circuit::{util::rlc, witness::Rw},
     table::AccountFieldTag,
-    util::Field,
+    util::{circuit_code_hash_scheme, Field},
 };
-use eth_types::{Address, ToLittleEndian, ToWord, Word, H256};
+use eth_types::{utils::HashScheme, Address, ToLittleEndian, ToWord, Word, H256};
 use gadgets::ToScalar;
 use halo2_proofs::circuit::Value;
 use itertools::Itertools;
@@ -30,7 +30,7 @@ pub struct WithdrawProof {
 }
 
 /// An MPT update whose validity is proved by the MptCircuit
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MptUpdate {
     key: Key,
     old_value: Word,
@@ -39,6 +39,7 @@ pub struct MptUpdate {
     new_root: Word,
     // for debugging
     #[cfg(debug_assertions)]
+    #[serde(skip)]
     original_rws: Vec<Rw>,
 }
 
@@ -93,7 +94,8 @@ impl Default for MptUpdate {
 }
 
 /// All the MPT updates in the MptCircuit, accessible by their key
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(into = "MptUpdatesRepr", from = "MptUpdatesRepr")]
 pub struct MptUpdates {
     old_root: H256,
     new_root: H256,
@@ -106,6 +108,50 @@ pub struct MptUpdates {
     pub(crate) proof_types: Vec<MPTProofType>,
 }
 
+/// Serialized form of [`MptUpdates`]. The updates are stored as a list since
+/// their keys can't be map keys in JSON, and the proof types are recomputed
+/// from the updates when loading.
+#[derive(Serialize, Deserialize)]
+struct MptUpdatesRepr {
+    old_root: H256,
+    new_root: H256,
+    updates: Vec<(Key, MptUpdate)>,
+    withdraw_proof: WithdrawProof,
+    smt_traces: Vec<SMTTrace>,
+}
+
+impl From<MptUpdates> for MptUpdatesRepr {
+    fn from(mpt_updates: MptUpdates) -> Self {
+        Self {
+            old_root: mpt_updates.old_root,
+            new_root: mpt_updates.new_root,
+            updates: mpt_updates.updates.into_iter().collect(),
+            withdraw_proof: mpt_updates.withdraw_proof,
+            smt_traces: mpt_updates.smt_traces,
+        }
+    }
+}
+
+impl From<MptUpdatesRepr> for MptUpdates {
+    fn from(repr: MptUpdatesRepr) -> Self {
+        let updates: BTreeMap<_, _> = repr.updates.into_iter().collect();
+        // proof types are only filled along with the smt traces
+        let proof_types = if repr.smt_traces.is_empty() {
+            Vec::new()
+        } else {
+            updates.values().map(MptUpdate::proof_type).collect()
+        };
+        Self {
+            old_root: repr.old_root,
+            new_root: repr.new_root,
+            updates,
+            withdraw_proof: repr.withdraw_proof,
+            smt_traces: repr.smt_traces,
+            proof_types,
+        }
+    }
+}
+
 /// The field element encoding of an MPT update, which is used by the MptTable
 #[derive(Debug, Clone, Copy)]
 pub struct MptUpdateRow<F>(pub(crate) [F; 7]);
@@ -225,6 +271,22 @@ impl MptUpdates {
         self.pretty_print();
     }
 
+    /// Fill the state roots of the updates of consecutive chunks of a block, each of which
+    /// starts from the trie after the previous one.
+    pub(crate) fn fill_chunks_state_roots<'a>(
+        chunks: impl IntoIterator<Item = &'a mut MptUpdates>,
+        init_trie: &ZktrieState,
+    ) {
+        let mut wit_gen = WitnessGenerator::from(init_trie);
+        for mpt_updates in chunks {
+            mpt_updates.old_root = wit_gen.root();
+            // a chunk without updates ends where it starts
+            mpt_updates.new_root = mpt_updates.old_root;
+            wit_gen = mpt_updates.fill_state_roots_from_generator(wit_gen);
+        }
+        log::debug!("fill_chunks_state_roots done");
+    }
+
     fn fill_state_roots_from_generator(
         &mut self,
         mut wit_gen: WitnessGenerator,
@@ -232,6 +294,15 @@ impl MptUpdates {
         self.smt_traces = Vec::new();
         self.proof_types = Vec::new();
 
+        wit_gen.prepare_key_hashes(self.updates.keys().map(|key| match key {
+            Key::Account { address, .. } => (address, None),
+            Key::AccountStorage {
+                address,
+                storage_key,
+                ..
+            } => (address, Some(storage_key)),
+        }));
+
         for (key, update) in &mut self.updates {
             log::trace!("apply update {:?} {:#?}", key, update);
             let key = key.set_non_exists(update.old_value, update.new_value);
@@ -372,13 +443,10 @@ impl MptUpdate {
             Key::Account {
                 field_tag: AccountFieldTag::CodeHash,
                 ..
-            } => {
-                if cfg!(feature = "poseidon-codehash") {
-                    x.to_scalar().unwrap()
-                } else {
-                    rlc::value(&x.to_le_bytes(), word_randomness)
-                }
-            }
+            } => match circuit_code_hash_scheme() {
+                HashScheme::Poseidon => x.to_scalar().unwrap(),
+                HashScheme::Keccak => rlc::value(&x.to_le_bytes(), word_randomness),
+            },
             Key::Account {
                 field_tag:
                     AccountFieldTag::Nonce | AccountFieldTag::NonExisting | AccountFieldTag::CodeSize,
@@ -440,7 +508,7 @@ impl MptUpdate {
     }
 }
 
-#[derive(Eq, PartialEq, Hash, Clone, Debug, Copy, PartialOrd, Ord)]
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Copy, PartialOrd, Ord, Serialize, Deserialize)]
 enum Key {
     Account {
         address: Address,
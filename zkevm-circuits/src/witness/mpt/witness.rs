@@ -14,6 +14,7 @@ use mpt_zktrie::{
 use std::collections::HashMap;
 
 use num_bigint::BigUint;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use std::{
     fmt,
     io::{Error as IoError, Read},
@@ -40,6 +41,9 @@ fn to_smt_account(acc: AccountData) -> SMTAccount {
 pub struct WitnessGenerator {
     trie: ZkTrie,
     storages_cache: HashMap<Address, ZkTrie>,
+    /// Poseidon hashes of the trie keys, computed once per key however many
+    /// updates touch it.
+    key_hashes: HashMap<[u8; 32], Word>,
 }
 
 impl From<&ZktrieState> for WitnessGenerator {
@@ -47,6 +51,7 @@ impl From<&ZktrieState> for WitnessGenerator {
         Self {
             trie: state.zk_db.borrow_mut().new_trie(&state.trie_root).unwrap(),
             storages_cache: HashMap::new(),
+            key_hashes: HashMap::new(),
         }
     }
 }
@@ -86,6 +91,39 @@ impl WitnessGenerator {
             .and_then(|trie| trie.prove(key.as_ref()).ok())
             .unwrap_or_default()
     }
+    /// Compute in parallel the hashes of the trie keys of the updates to
+    /// handle, so that handling the updates one by one doesn't compute them.
+    pub fn prepare_key_hashes<'a>(
+        &mut self,
+        keys: impl IntoIterator<Item = (&'a Address, Option<&'a Word>)>,
+    ) {
+        let mut key_bufs = Vec::new();
+        for (address, storage_key) in keys {
+            let address_buf = extend_address_to_h256(address);
+            key_bufs.push(address_buf);
+            if let Some(storage_key) = storage_key {
+                let mut word_buf = [0u8; 32];
+                storage_key.to_big_endian(word_buf.as_mut_slice());
+                key_bufs.push(word_buf);
+            }
+        }
+        key_bufs.sort_unstable();
+        key_bufs.dedup();
+        key_bufs.retain(|key_buf| !self.key_hashes.contains_key(key_buf));
+        log::debug!("hashing {} trie keys", key_bufs.len());
+
+        let hashes: Vec<_> = key_bufs
+            .into_par_iter()
+            .map(|key_buf| (key_buf, hash_zktrie_key(&key_buf)))
+            .collect();
+        self.key_hashes.extend(hashes);
+    }
+    fn key_hash(&mut self, key_buf: &[u8; 32]) -> Word {
+        *self
+            .key_hashes
+            .entry(*key_buf)
+            .or_insert_with(|| hash_zktrie_key(key_buf))
+    }
     fn fetch_storage_cache(&mut self, address: Address) -> Option<&mut ZkTrie> {
         let cache_entry = self.storages_cache.entry(address);
         match cache_entry {
@@ -114,7 +152,7 @@ impl WitnessGenerator {
         let (storage_key, key) = {
             let mut word_buf = [0u8; 32];
             key.to_big_endian(word_buf.as_mut_slice());
-            (hash_zktrie_key(&word_buf), HexBytes(word_buf))
+            (self.key_hash(&word_buf), HexBytes(word_buf))
         };
 
         let trie = if let Some(trie) = self.fetch_storage_cache(address) {
@@ -225,7 +263,7 @@ impl WitnessGenerator {
             }
         };
 
-        let address_key = hash_zktrie_key(&extend_address_to_h256(&address));
+        let address_key = self.key_hash(&extend_address_to_h256(&address));
 
         let (account_path_before, account_data_before) =
             decode_proof_for_mpt_path(address_key, proofs).expect("unless the db is totally empty");
@@ -619,6 +657,33 @@ fn witgen_update_one() {
     log::info!("ret {:?}", trace);
 }
 
+#[test]
+fn witgen_prepared_key_hashes() {
+    let (state, _, _) = build_state_from_string(EXAMPLE_TRACE);
+    let target_addr = Address::from_slice(
+        hex::decode("66613A02924ca4171675f3793b4eB3908A480D55")
+            .unwrap()
+            .as_slice(),
+    );
+    let storage_key = U256::zero();
+
+    let mut prepared = WitnessGenerator::from(&state);
+    prepared.prepare_key_hashes([(&target_addr, Some(&storage_key))]);
+    assert_eq!(prepared.key_hashes.len(), 2);
+    let mut unprepared = WitnessGenerator::from(&state);
+
+    let traces = [&mut prepared, &mut unprepared].map(|w| {
+        w.handle_new_state(
+            MPTProofType::StorageChanged,
+            target_addr,
+            U256::from(11u32),
+            U256::from(10u32),
+            Some(storage_key),
+        )
+    });
+    assert_eq!(format!("{:?}", traces[0]), format!("{:?}", traces[1]));
+}
+
 fn build_state_from_string(sample_str: &str) -> (ZktrieState, AccountDatas, StorageDatas) {
     let trace: StorageTrace = serde_json::from_str(sample_str).unwrap();
 
@@ -0,0 +1,166 @@
+use super::{Block, ExecStep, Rw};
+use crate::evm_circuit::ExecutionState;
+use bus_mapping::{circuit_input_builder::CopyEvent, evm::OpcodeId};
+use std::ops::Range;
+
+/// Step of the witness which performed a read/write operation or a copy
+/// event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Provenance {
+    /// Index of the tx in the txs of the block, None for the EndBlock step.
+    pub tx_index: Option<usize>,
+    /// Index of the step in the steps of the tx.
+    pub step_index: usize,
+    /// Id of the call of the step, the rw counter at its beginning.
+    pub call_id: usize,
+    /// Program counter of the step.
+    pub program_counter: u64,
+    /// Opcode of the step, None for steps which are not an opcode.
+    pub opcode: Option<OpcodeId>,
+    /// Execution state of the step.
+    pub execution_state: ExecutionState,
+}
+
+/// Index of the rw counters of a block by the step which performed them.
+///
+/// The rw counters are assigned to the steps in order, a step performing the
+/// operations from its rw counter to the one of the next step, so that the
+/// index only depends on the steps of the block and survives its JSON
+/// serialization.
+#[derive(Clone, Debug, Default)]
+pub struct ProvenanceIndex {
+    steps: Vec<(usize, Provenance)>,
+}
+
+impl ProvenanceIndex {
+    /// Index the steps of `block`.
+    pub fn new(block: &Block) -> Self {
+        let provenance = |tx_index, step_index, call_id, step: &ExecStep| Provenance {
+            tx_index,
+            step_index,
+            call_id,
+            program_counter: step.program_counter,
+            opcode: step.opcode,
+            execution_state: step.execution_state,
+        };
+        let mut steps: Vec<_> = block
+            .txs
+            .iter()
+            .enumerate()
+            .flat_map(|(tx_index, tx)| {
+                tx.steps.iter().enumerate().map(move |(step_index, step)| {
+                    let call_id = tx.calls.get(step.call_index).map_or(0, |call| call.id);
+                    (
+                        step.rw_counter,
+                        provenance(Some(tx_index), step_index, call_id, step),
+                    )
+                })
+            })
+            .collect();
+        if block.end_block_step.rw_counter != 0 {
+            steps.push((
+                block.end_block_step.rw_counter,
+                provenance(None, 0, 0, &block.end_block_step),
+            ));
+        }
+        Self { steps }
+    }
+
+    /// Step which performed the operation of `rw_counter`.
+    pub fn get(&self, rw_counter: usize) -> Option<&Provenance> {
+        // steps without operations share their rw counter with the next one
+        let index = self
+            .steps
+            .partition_point(|(start, _)| *start <= rw_counter);
+        index.checked_sub(1).map(|index| &self.steps[index].1)
+    }
+
+    /// Step which performed `rw`.
+    pub fn of_rw(&self, rw: &Rw) -> Option<&Provenance> {
+        self.get(rw.rw_counter())
+    }
+
+    /// Step which performed `event`.
+    pub fn of_copy_event(&self, event: &CopyEvent) -> Option<&Provenance> {
+        self.get(event.rw_counter_start.0)
+    }
+
+    /// Rw counters of the operations of the steps matching `filter`, e.g.
+    /// the steps at a program counter of a call.
+    pub fn rw_counters<'a>(
+        &'a self,
+        filter: impl Fn(&Provenance) -> bool + 'a,
+    ) -> impl Iterator<Item = (Range<usize>, &'a Provenance)> + 'a {
+        self.steps
+            .iter()
+            .enumerate()
+            .filter(move |(_, (_, provenance))| filter(provenance))
+            .map(|(index, (start, provenance))| {
+                let end = self
+                    .steps
+                    .get(index + 1)
+                    .map_or(usize::MAX, |(next, _)| *next);
+                (*start..end, provenance)
+            })
+            .filter(|(range, _)| !range.is_empty())
+    }
+}
+
+impl Block {
+    /// Index of the rw counters of the block by the step which performed
+    /// them, see [`ProvenanceIndex`].
+    pub fn provenance_index(&self) -> ProvenanceIndex {
+        ProvenanceIndex::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::bytecode;
+    use mock::TestContext;
+
+    #[test]
+    fn rws_and_copy_events_provenance() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            SLOAD
+            PUSH1(0x20)
+            PUSH1(0x00)
+            PUSH1(0x00)
+            CODECOPY
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+        let (block, ..) = CircuitTestBuilder::new_from_test_ctx(ctx).build_witness_block();
+        let index = block.provenance_index();
+
+        for rw in block.rws.0.values().flatten() {
+            assert!(index.of_rw(rw).is_some(), "{rw:?}");
+        }
+        let storage = block.rws.0[&crate::table::RwTableTag::AccountStorage][0];
+        let provenance = index.of_rw(&storage).unwrap();
+        assert_eq!(provenance.tx_index, Some(0));
+        assert_eq!(provenance.opcode, Some(OpcodeId::SLOAD));
+        assert_eq!(provenance.program_counter, 2);
+
+        let event = block
+            .copy_events
+            .iter()
+            .find(|event| event.origin.unwrap().opcode == Some(OpcodeId::CODECOPY))
+            .unwrap();
+        let provenance = index.of_copy_event(event).unwrap();
+        assert_eq!(provenance.opcode, Some(OpcodeId::CODECOPY));
+        let origin = event.origin.unwrap();
+        assert_eq!(provenance.step_index, origin.step_index);
+        assert_eq!(provenance.call_id, origin.call_id);
+        assert_eq!(provenance.program_counter, origin.pc.0 as u64);
+
+        let (rw_counters, _) = index
+            .rw_counters(|provenance| provenance.opcode == Some(OpcodeId::SLOAD))
+            .next()
+            .unwrap();
+        assert!(rw_counters.contains(&storage.rw_counter()));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::util::Field;
 use eth_types::{Address, H160, U256};
-use gadgets::{impl_expr, util::Expr};
+use gadgets::{impl_binary_number_tag, impl_expr, util::Expr};
 use halo2_proofs::{circuit::Value, halo2curves::ff::PrimeField, plonk::Expression};
 use strum_macros::EnumIter;
 
@@ -136,6 +136,12 @@ pub enum Tag {
     // L1MsgHash
     /// Sender
     Sender,
+
+    // EIP-4844
+    /// Max fee per blob gas
+    MaxFeePerBlobGas,
+    /// Versioned hash in blob_versioned_hashes
+    BlobVersionedHash,
 }
 
 impl From<Tag> for usize {
@@ -144,6 +150,8 @@ impl From<Tag> for usize {
     }
 }
 
+impl_binary_number_tag!(Tag, Tag::BlobVersionedHash);
+
 impl From<Tag> for RlpTag {
     fn from(value: Tag) -> Self {
         Self::Tag(value)
@@ -219,13 +227,14 @@ use crate::{
     witness::{
         l1_msg,
         Format::{
-            TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashPreEip155, TxSignEip155,
-            TxSignEip1559, TxSignEip2930, TxSignPreEip155,
+            TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashEip4844, TxHashPreEip155,
+            TxSignEip155, TxSignEip1559, TxSignEip2930, TxSignEip4844, TxSignPreEip155,
         },
         Tag::{
-            AccessListAddress, AccessListStorageKey, BeginObject, BeginVector, ChainId, Data,
-            EndObject, EndVector, Gas, GasPrice, MaxFeePerGas, MaxPriorityFeePerGas, Nonce, SigR,
-            SigS, SigV, To, TxType, Value as TxValue, Zero1, Zero2,
+            AccessListAddress, AccessListStorageKey, BeginObject, BeginVector, BlobVersionedHash,
+            ChainId, Data, EndObject, EndVector, Gas, GasPrice, MaxFeePerBlobGas, MaxFeePerGas,
+            MaxPriorityFeePerGas, Nonce, SigR, SigS, SigV, To, TxType, Value as TxValue, Zero1,
+            Zero2,
         },
     },
 };
@@ -545,6 +554,151 @@ pub fn eip1559_tx_sign_rom_table_rows() -> Vec<RomTableRow> {
         .collect()
 }
 
+// Blob txs cannot create contracts, which the tx circuit enforces, and carry
+// at least one versioned hash.
+pub fn eip4844_tx_sign_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (TxType, BeginObject, 1, vec![1]),
+        (BeginObject, ChainId, MAX_TAG_LENGTH_OF_LIST, vec![2]),
+        (ChainId, Nonce, N_BYTES_U64, vec![3]),
+        (Nonce, MaxPriorityFeePerGas, N_BYTES_U64, vec![4]),
+        (MaxPriorityFeePerGas, MaxFeePerGas, N_BYTES_WORD, vec![5]),
+        (MaxFeePerGas, Gas, N_BYTES_WORD, vec![6]),
+        (Gas, To, N_BYTES_U64, vec![7]),
+        (To, TxValue, N_BYTES_ACCOUNT_ADDRESS, vec![8]),
+        (TxValue, Data, N_BYTES_WORD, vec![9]),
+        (Data, BeginVector, N_BYTES_CALLDATA, vec![10, 11]),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![21]), // access_list is none
+        (BeginVector, BeginObject, MAX_TAG_LENGTH_OF_LIST, vec![12]),
+        (
+            BeginObject,
+            AccessListAddress,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![13],
+        ),
+        (
+            AccessListAddress,
+            BeginVector,
+            N_BYTES_ACCOUNT_ADDRESS,
+            vec![14, 15],
+        ),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![18]), /* access_list.storage_keys
+                                                                     * is none */
+        (
+            BeginVector,
+            AccessListStorageKey,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![16, 17],
+        ),
+        (AccessListStorageKey, EndVector, N_BYTES_WORD, vec![18]), // finished parsing storage keys
+        (
+            AccessListStorageKey,
+            AccessListStorageKey,
+            N_BYTES_WORD,
+            vec![16, 17],
+        ), // keep parsing storage_keys
+        (EndVector, EndObject, 0, vec![19, 20]),
+        (EndObject, EndVector, 0, vec![21]), // finished parsing access_list
+        (EndObject, BeginObject, 0, vec![12]), // parse another access_list entry
+        (EndVector, MaxFeePerBlobGas, 0, vec![22]),
+        (MaxFeePerBlobGas, BeginVector, N_BYTES_WORD, vec![23]),
+        (
+            BeginVector,
+            BlobVersionedHash,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![24, 25],
+        ),
+        (BlobVersionedHash, EndVector, N_BYTES_WORD, vec![26]), // finished parsing blob hashes
+        (
+            BlobVersionedHash,
+            BlobVersionedHash,
+            N_BYTES_WORD,
+            vec![24, 25],
+        ), // keep parsing
+        (EndVector, EndObject, 0, vec![27]),
+        (EndObject, EndObject, 0, vec![28]),
+        // used to emit TxGasCostInL1
+        (EndObject, BeginObject, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, TxSignEip4844, row.3).into())
+        .collect()
+}
+
+pub fn eip4844_tx_hash_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (TxType, BeginObject, 1, vec![1]),
+        (BeginObject, ChainId, MAX_TAG_LENGTH_OF_LIST, vec![2]),
+        (ChainId, Nonce, N_BYTES_U64, vec![3]),
+        (Nonce, MaxPriorityFeePerGas, N_BYTES_U64, vec![4]),
+        (MaxPriorityFeePerGas, MaxFeePerGas, N_BYTES_WORD, vec![5]),
+        (MaxFeePerGas, Gas, N_BYTES_WORD, vec![6]),
+        (Gas, To, N_BYTES_U64, vec![7]),
+        (To, TxValue, N_BYTES_ACCOUNT_ADDRESS, vec![8]),
+        (TxValue, Data, N_BYTES_WORD, vec![9]),
+        (Data, BeginVector, N_BYTES_CALLDATA, vec![10, 11]),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![21]), // access_list is none
+        (BeginVector, BeginObject, MAX_TAG_LENGTH_OF_LIST, vec![12]),
+        (
+            BeginObject,
+            AccessListAddress,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![13],
+        ),
+        (
+            AccessListAddress,
+            BeginVector,
+            N_BYTES_ACCOUNT_ADDRESS,
+            vec![14, 15],
+        ),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![18]), /* access_list.storage_keys
+                                                                     * is none */
+        (
+            BeginVector,
+            AccessListStorageKey,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![16, 17],
+        ),
+        (AccessListStorageKey, EndVector, N_BYTES_WORD, vec![18]), // finished parsing storage keys
+        (
+            AccessListStorageKey,
+            AccessListStorageKey,
+            N_BYTES_WORD,
+            vec![16, 17],
+        ), // keep parsing storage_keys
+        (EndVector, EndObject, 0, vec![19, 20]),
+        (EndObject, EndVector, 0, vec![21]), // finished parsing access_list
+        (EndObject, BeginObject, 0, vec![12]), // parse another access_list entry
+        (EndVector, MaxFeePerBlobGas, 0, vec![22]),
+        (MaxFeePerBlobGas, BeginVector, N_BYTES_WORD, vec![23]),
+        (
+            BeginVector,
+            BlobVersionedHash,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![24, 25],
+        ),
+        (BlobVersionedHash, EndVector, N_BYTES_WORD, vec![26]), // finished parsing blob hashes
+        (
+            BlobVersionedHash,
+            BlobVersionedHash,
+            N_BYTES_WORD,
+            vec![24, 25],
+        ), // keep parsing
+        (EndVector, SigV, 0, vec![27]),
+        (SigV, SigR, N_BYTES_U64, vec![28]),
+        (SigR, SigS, N_BYTES_WORD, vec![29]),
+        (SigS, EndObject, N_BYTES_WORD, vec![30]),
+        (EndObject, EndObject, 0, vec![31]),
+        // used to exit TxGasCostInL1
+        (EndObject, BeginObject, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, TxHashEip4844, row.3).into())
+        .collect()
+}
+
 /// Read-only Memory table row.
 #[derive(Debug, Clone)]
 pub struct RomTableRow {
@@ -603,6 +757,10 @@ pub enum Format {
     TxHashEip2930,
     /// L1 Msg
     L1MsgHash,
+    /// Sign for EIP4844 tx
+    TxSignEip4844,
+    /// Hash for EIP4844 tx
+    TxHashEip4844,
 }
 
 impl From<Format> for usize {
@@ -629,6 +787,8 @@ impl Format {
             TxSignEip2930 => eip2930_tx_sign_rom_table_rows(),
             TxHashEip2930 => eip2930_tx_hash_rom_table_rows(),
             Self::L1MsgHash => l1_msg::rom_table_rows(),
+            TxSignEip4844 => eip4844_tx_sign_rom_table_rows(),
+            TxHashEip4844 => eip4844_tx_hash_rom_table_rows(),
         }
     }
 }
@@ -654,6 +814,8 @@ impl From<State> for usize {
     }
 }
 
+impl_binary_number_tag!(State, State::LongList);
+
 impl_expr!(Tag);
 impl_expr!(Format);
 impl_expr!(State);
@@ -12,6 +12,7 @@ use gadgets::ToScalar;
 use halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
 use itertools::Itertools;
 use rayon::prelude::{ParallelBridge, ParallelIterator};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     evm_circuit::util::rlc,
@@ -25,7 +26,7 @@ const ERR_MSG_FIRST: &str = "first access reads don't change value";
 const ERR_MSG_NON_FIRST: &str = "non-first access reads don't change value";
 
 /// Rw container for a witness block
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct RwMap(pub HashMap<RwTableTag, Vec<Rw>>);
 
 impl std::ops::Index<(RwTableTag, usize)> for RwMap {
@@ -36,18 +37,21 @@ impl std::ops::Index<(RwTableTag, usize)> for RwMap {
     }
 }
 impl RwMap {
-    /// Check rw_counter is continuous and starting from 1
+    /// Check rw_counter is continuous and starting from the initial rw counter
+    /// of the chunk, 1 unless the block is split into chunks
     pub fn check_rw_counter_sanity(&self) {
-        for (idx, rw_counter) in self
+        let rw_counters = self
             .0
             .iter()
             .filter(|(tag, _rs)| !matches!(tag, RwTableTag::Start))
             .flat_map(|(_tag, rs)| rs)
             .map(|r| r.rw_counter())
             .sorted()
-            .enumerate()
-        {
-            debug_assert_eq!(idx, rw_counter - 1);
+            .collect_vec();
+        // The rws of a chunk start at its initial rw counter.
+        let initial_rwc = rw_counters.first().copied().unwrap_or(1);
+        for (idx, rw_counter) in rw_counters.into_iter().enumerate() {
+            debug_assert_eq!(idx, rw_counter - initial_rwc);
         }
     }
 
@@ -220,7 +224,7 @@ pub type RwKey = (u64, usize, Address, u64, Word);
 
 /// Read-write records in execution. Rws are used for connecting evm circuit and
 /// state circuits.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Rw {
     /// Start
     Start { rw_counter: usize },
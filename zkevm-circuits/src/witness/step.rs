@@ -16,9 +16,10 @@ use crate::{
     },
     table::RwTableTag,
 };
+use serde::{Deserialize, Serialize};
 
 /// Step executed in a transaction
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExecStep {
     /// The index in the Transaction calls
     pub call_index: usize,
@@ -47,6 +48,7 @@ pub struct ExecStep {
     /// The counter for log index within tx
     pub log_id: usize,
     /// The opcode corresponds to the step
+    #[serde(with = "opcode_serde")]
     pub opcode: Option<OpcodeId>,
     /// The block number in which this step exists.
     pub block_num: u64,
@@ -54,6 +56,26 @@ pub struct ExecStep {
     pub aux_data: Option<PrecompileAuxData>,
 }
 
+/// `OpcodeId`'s own serde impls don't roundtrip `INVALID(_)`, so the opcode of
+/// a step is stored as its byte.
+mod opcode_serde {
+    use bus_mapping::evm::OpcodeId;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        opcode: &Option<OpcodeId>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        opcode.map(|opcode| opcode.as_u8()).serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<OpcodeId>, D::Error> {
+        Ok(Option::<u8>::deserialize(deserializer)?.map(OpcodeId::from))
+    }
+}
+
 impl ExecStep {
     /// The memory size in word **before** this step
     pub fn memory_word_size(&self) -> u64 {
@@ -193,6 +215,10 @@ impl From<&circuit_input_builder::ExecStep> for ExecutionState {
                     OpcodeId::DIFFICULTY => ExecutionState::BLOCKCTXU256,
                     #[cfg(feature = "scroll")]
                     OpcodeId::DIFFICULTY => ExecutionState::DIFFICULTY,
+                    #[cfg(not(feature = "scroll"))]
+                    OpcodeId::BLOBHASH => ExecutionState::BLOBHASH,
+                    #[cfg(not(feature = "scroll"))]
+                    OpcodeId::BLOBBASEFEE => ExecutionState::BLOBBASEFEE,
                     OpcodeId::GAS => ExecutionState::GAS,
                     OpcodeId::SAR => ExecutionState::SAR,
                     OpcodeId::SELFBALANCE => ExecutionState::SELFBALANCE,
@@ -200,7 +226,9 @@ impl From<&circuit_input_builder::ExecStep> for ExecutionState {
                     OpcodeId::SHL | OpcodeId::SHR => ExecutionState::SHL_SHR,
                     OpcodeId::SLOAD => ExecutionState::SLOAD,
                     OpcodeId::SSTORE => ExecutionState::SSTORE,
+                    #[cfg(feature = "cancun")]
                     OpcodeId::TLOAD => ExecutionState::TLOAD,
+                    #[cfg(feature = "cancun")]
                     OpcodeId::TSTORE => ExecutionState::TSTORE,
                     OpcodeId::CALLDATASIZE => ExecutionState::CALLDATASIZE,
                     OpcodeId::CALLDATACOPY => ExecutionState::CALLDATACOPY,
@@ -238,6 +266,8 @@ impl From<&circuit_input_builder::ExecStep> for ExecutionState {
             },
             circuit_input_builder::ExecState::BeginTx => ExecutionState::BeginTx,
             circuit_input_builder::ExecState::EndTx => ExecutionState::EndTx,
+            circuit_input_builder::ExecState::BeginChunk => ExecutionState::BeginChunk,
+            circuit_input_builder::ExecState::EndChunk => ExecutionState::EndChunk,
             circuit_input_builder::ExecState::EndBlock => ExecutionState::EndBlock,
             circuit_input_builder::ExecState::Padding => ExecutionState::Padding,
         }
@@ -6,8 +6,8 @@ use crate::{
         rlp_fsm::{RlpStackOp, SmState},
         DataTable, Format,
         Format::{
-            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashPreEip155, TxSignEip155,
-            TxSignEip1559, TxSignEip2930, TxSignPreEip155,
+            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashEip4844, TxHashPreEip155,
+            TxSignEip155, TxSignEip1559, TxSignEip2930, TxSignEip4844, TxSignPreEip155,
         },
         RlpFsmWitnessGen, RlpFsmWitnessRow, RlpTable, RlpTag, State,
         State::DecodeTagStart,
@@ -17,8 +17,11 @@ use crate::{
 };
 use bus_mapping::circuit_input_builder::{self, get_dummy_tx_hash, TxL1Fee};
 use eth_types::{
-    evm_types::gas_utils::{tx_access_list_gas_cost, tx_data_gas_cost},
-    geth_types::{access_list_size, TxType, TxType::PreEip155},
+    evm_types::{
+        gas_utils::{tx_access_list_gas_cost, tx_data_gas_cost},
+        GasSchedule,
+    },
+    geth_types::{access_list_size, BlobTxFields, TxType, TxType::PreEip155},
     sign_types::{
         biguint_to_32bytes_le, ct_option_ok_or, get_dummy_tx, recover_pk2, SignData, SECP256K1_Q,
     },
@@ -35,12 +38,13 @@ use halo2_proofs::{
 };
 use num::Integer;
 use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, collections::BTreeMap};
 
 use super::{step::step_convert, Call, ExecStep};
 
 /// Transaction in a witness block
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Transaction {
     /// The block number in which this tx is included in
     pub block_number: u64,
@@ -96,6 +100,8 @@ pub struct Transaction {
     pub l1_fee_committed: TxL1Fee,
     /// Optional access list for EIP-2930
     pub access_list: Option<AccessList>,
+    /// Blob fields for EIP-4844
+    pub blob: BlobTxFields,
     /// The calls made in the transaction
     pub calls: Vec<Call>,
     /// The steps executioned in the transaction
@@ -464,6 +470,7 @@ impl Transaction {
                     TxType::Eip1559 => TxHashEip1559,
                     TxType::L1Msg => L1MsgHash,
                     TxType::Eip2930 => TxHashEip2930,
+                    TxType::Eip4844 => TxHashEip4844,
                 },
             )
         } else {
@@ -474,6 +481,7 @@ impl Transaction {
                     TxType::PreEip155 => TxSignPreEip155,
                     TxType::Eip1559 => TxSignEip1559,
                     TxType::Eip2930 => TxSignEip2930,
+                    TxType::Eip4844 => TxSignEip4844,
                     _ => unreachable!("tx type {:?} not supported", self.tx_type),
                 },
             )
@@ -1104,6 +1112,7 @@ impl<F: Field> RlpFsmWitnessGen<F> for Transaction {
             TxType::PreEip155 => (TxHashPreEip155, Some(TxSignPreEip155)),
             TxType::Eip1559 => (TxHashEip1559, Some(TxSignEip1559)),
             TxType::Eip2930 => (TxHashEip2930, Some(TxSignEip2930)),
+            TxType::Eip4844 => (TxHashEip4844, Some(TxSignEip4844)),
             TxType::L1Msg => (L1MsgHash, None),
         };
 
@@ -1200,6 +1209,7 @@ impl From<mock::MockTransaction> for Transaction {
             l1_fee: Default::default(),
             l1_fee_committed: Default::default(),
             access_list,
+            blob: Default::default(),
             calls: vec![],
             steps: vec![],
         }
@@ -1211,6 +1221,7 @@ pub(super) fn tx_convert(
     id: usize,
     chain_id: u64,
     next_block_num: u64,
+    gas_schedule: &GasSchedule,
 ) -> Transaction {
     if tx.chain_id != 0 {
         debug_assert_eq!(
@@ -1235,12 +1246,12 @@ pub(super) fn tx_convert(
         nonce: tx.nonce,
         gas: tx.gas,
         gas_price: tx.gas_price,
-        max_fee_per_gas: if tx.tx_type.is_eip1559() {
+        max_fee_per_gas: if tx.tx_type.is_dynamic_fee() {
             tx.gas_fee_cap
         } else {
             tx.gas_price
         },
-        max_priority_fee_per_gas: if tx.tx_type.is_eip1559() {
+        max_priority_fee_per_gas: if tx.tx_type.is_dynamic_fee() {
             tx.gas_tip_cap
         } else {
             tx.gas_price
@@ -1251,8 +1262,8 @@ pub(super) fn tx_convert(
         value: tx.value,
         call_data: tx.input.clone(),
         call_data_length: tx.input.len(),
-        call_data_gas_cost: tx_data_gas_cost(&tx.input),
-        access_list_gas_cost: tx_access_list_gas_cost(&tx.access_list),
+        call_data_gas_cost: gas_schedule.tx_data_gas_cost(&tx.input),
+        access_list_gas_cost: gas_schedule.tx_access_list_gas_cost(&tx.access_list),
         tx_data_gas_cost: tx_gas_cost,
         chain_id,
         rlp_unsigned: tx.rlp_unsigned_bytes.clone(),
@@ -1263,6 +1274,7 @@ pub(super) fn tx_convert(
         l1_fee: tx.l1_fee,
         l1_fee_committed: tx.l1_fee_committed,
         access_list: tx.access_list.clone(),
+        blob: tx.blob.clone(),
         calls: tx
             .calls()
             .iter()
@@ -1319,7 +1331,9 @@ pub(super) fn tx_convert(
 mod tests {
     use crate::witness::{tx::Challenges, RlpTag, Tag, Transaction};
     use eth_types::{
-        evm_types::gas_utils::tx_data_gas_cost, geth_types::TxType, Address, ToBigEndian,
+        evm_types::gas_utils::tx_data_gas_cost,
+        geth_types::{TxType, TxTypeConfig, TxTypeSupport},
+        Address, ToBigEndian,
     };
     use ethers_core::{
         types::{Transaction as EthTransaction, TransactionRequest},
@@ -1476,6 +1490,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rlp_system_tx() {
+        let raw_tx_rlp_bytes = hex::decode("7ef901b60b825dc0941a258d17bf244c4df02d40343a7626a9d321e10580b901848ef1332e000000000000000000000000ea08a65b1829af779261e768d609e59279b510f2000000000000000000000000f2ec6b6206f6208e8f9b394efc1a01c1cbde77750000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000000b00000000000000000000000000000000000000000000000000000000000000a000000000000000000000000000000000000000000000000000000000000000a4232e87480000000000000000000000002b5ad5c4795c026514f8317c7a215e218dccd6cf0000000000000000000000002b5ad5c4795c026514f8317c7a215e218dccd6cf0000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000008000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000094478cdd110520a8e733e2acf9e543d2c687ea5239")
+            .expect("decode tx's hex shall not fail");
+
+        let mut eth_tx = EthTransaction::decode(&Rlp::new(&raw_tx_rlp_bytes))
+            .expect("decode tx's rlp bytes shall not fail");
+        assert_eq!(
+            TxTypeConfig::default().rlp_signed(&eth_tx),
+            raw_tx_rlp_bytes
+        );
+
+        // the same tx as a deposit tx of a rollup whose system txs have type 0x7f
+        let config = TxTypeConfig {
+            system_tx_type_id: 0x7f,
+            zero_sig_is_system_tx: false,
+        };
+        eth_tx.transaction_type = Some(0x7f.into());
+        assert_eq!(config.tx_type(&eth_tx), TxType::L1Msg);
+        assert!(config.support_of_tx(&eth_tx).supported);
+        assert!(!TxTypeSupport::of_tx(&eth_tx).supported);
+
+        let rlp_signed = config.rlp_signed(&eth_tx);
+        assert_eq!(rlp_signed[0], 0x7f);
+        assert_eq!(rlp_signed[1..], raw_tx_rlp_bytes[1..]);
+
+        let tx = Transaction::new_from_rlp_signed_bytes(TxType::L1Msg, rlp_signed);
+        let mock_challenges = Challenges::mock(
+            Value::known(Fr::from(0x1ab)),
+            Value::known(Fr::from(0x10000)),
+            Value::known(Fr::from(0x100)),
+        );
+        let witness_table = tx.gen_rlp_witness(true, &mock_challenges);
+        let rlp_table = witness_table
+            .iter()
+            .filter(|row| row.rlp_table.is_output)
+            .map(|row| row.rlp_table)
+            .collect::<Vec<_>>();
+        assert_eq!(rlp_table[0].rlp_tag, Tag::TxType.into());
+        assert_eq!(unwrap_value(rlp_table[0].tag_value), Fr::from(0x7f));
+    }
+
     #[test]
     fn test_rlp_eip1559() {
         // the tx is downloaded from https://etherscan.io/getRawTx?tx=0x1c5bd618bdbc575f71bfe0a54f09bca2997bbf6d90d4f371a509b05e2b3124e3